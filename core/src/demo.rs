@@ -0,0 +1,68 @@
+use crate::game::eval::{best_placement, read_board, ActivePill, Placement};
+use crate::game::Game;
+use crate::input::GameInputKey;
+use std::time::Duration;
+
+/// Delay between each rotation or movement so the demo looks like someone is playing it
+const ACTION_INTERVAL: Duration = Duration::from_millis(120);
+
+/// Give up on steering a pill that cannot reach its target e.g. when blocked by the stack
+const MAX_ACTIONS: u32 = 16;
+
+/// CPU player for attract mode, steers each pill to the placement that best matches the stack
+pub struct DemoPlayer {
+    player: u32,
+    target: Option<Placement>,
+    actions: u32,
+    cooldown: Duration,
+}
+
+impl DemoPlayer {
+    pub fn new(player: u32) -> Self {
+        Self {
+            player,
+            target: None,
+            actions: 0,
+            cooldown: Duration::ZERO,
+        }
+    }
+
+    pub fn update(&mut self, delta: Duration, game: &Game) -> Vec<GameInputKey> {
+        let pill = match ActivePill::read(game) {
+            None => {
+                // pill has locked, plan again when the next one spawns
+                self.target = None;
+                return vec![];
+            }
+            Some(pill) => pill,
+        };
+
+        let target = *self.target.get_or_insert_with(|| {
+            self.actions = 0;
+            best_placement(&read_board(game), pill.colors)
+        });
+
+        self.cooldown = self.cooldown.saturating_sub(delta);
+        if self.actions >= MAX_ACTIONS {
+            return vec![GameInputKey::SoftDrop { player: self.player }];
+        }
+
+        let key = if pill.rotation != target.rotation {
+            GameInputKey::RotateClockwise { player: self.player }
+        } else if pill.x < target.x {
+            GameInputKey::MoveRight { player: self.player }
+        } else if pill.x > target.x {
+            GameInputKey::MoveLeft { player: self.player }
+        } else {
+            return vec![GameInputKey::SoftDrop { player: self.player }];
+        };
+
+        if self.cooldown.is_zero() {
+            self.cooldown = ACTION_INTERVAL;
+            self.actions += 1;
+            vec![key]
+        } else {
+            vec![]
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::game::GameSpeed;
+
+/// Pills locked since the last clear for the next clear to count as fast
+const FAST_CLEAR_PILLS: u32 = 3;
+/// Most speed levels added on top of those from the pill count
+const MAX_RAMP_UP: i32 = 10;
+/// Speed levels taken off when a pill locks near the top of the bottle
+const BACK_OFF: i32 = 3;
+/// Most speed levels taken off those from the pill count
+const MAX_BACK_OFF: i32 = 6;
+/// Rows at the top of the bottle that a pill locking into is a near top out
+pub const NEAR_TOP_OUT_ROWS: u32 = 4;
+
+/// Adaptive speed, the speed level ramps up faster while the player clears quickly & chains
+/// then backs off after a near top out
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveSpeed {
+    /// speed levels added to those from the pill count, taken off when negative
+    offset: i32,
+    /// pills locked since the last pill that cleared
+    pills_since_clear: u32,
+}
+
+impl AdaptiveSpeed {
+    /// Speed levels added by playing well, negative after a near top out
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Effective speed level of a speed level from the pill count & speed ups
+    pub fn speed_level(&self, speed_level: usize) -> usize {
+        (speed_level as i32 + self.offset).clamp(0, GameSpeed::MAX_LEVEL as i32) as usize
+    }
+
+    /// A locked pill has been resolved, ramping up for a fast clear & again for a chain
+    pub fn resolve_lock(&mut self, cleared: bool, chain: u32) {
+        if !cleared {
+            self.pills_since_clear += 1;
+            return;
+        }
+        let fast_clear = self.pills_since_clear < FAST_CLEAR_PILLS;
+        let ramp_up = fast_clear as i32 + (chain > 1) as i32;
+        self.offset = (self.offset + ramp_up).min(MAX_RAMP_UP);
+        self.pills_since_clear = 0;
+    }
+
+    /// A pill has locked into the top rows of the bottle
+    pub fn near_top_out(&mut self) {
+        self.offset = (self.offset - BACK_OFF).max(-MAX_BACK_OFF);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ramps_up_on_fast_clears_and_chains() {
+        let mut adaptive = AdaptiveSpeed::default();
+        adaptive.resolve_lock(true, 1);
+        assert_eq!(adaptive.offset(), 1);
+        adaptive.resolve_lock(true, 2);
+        assert_eq!(adaptive.offset(), 3);
+
+        for _ in 0..FAST_CLEAR_PILLS {
+            adaptive.resolve_lock(false, 0);
+        }
+        // a slow clear without a chain
+        adaptive.resolve_lock(true, 1);
+        assert_eq!(adaptive.offset(), 3);
+        assert_eq!(adaptive.speed_level(5), 8);
+    }
+
+    #[test]
+    fn backs_off_after_near_top_out() {
+        let mut adaptive = AdaptiveSpeed::default();
+        adaptive.resolve_lock(true, 1);
+        adaptive.near_top_out();
+        assert_eq!(adaptive.offset(), 1 - BACK_OFF);
+        adaptive.near_top_out();
+        adaptive.near_top_out();
+        assert_eq!(adaptive.offset(), -MAX_BACK_OFF);
+        assert_eq!(adaptive.speed_level(2), 0);
+    }
+
+    #[test]
+    fn speed_level_capped() {
+        let mut adaptive = AdaptiveSpeed::default();
+        for _ in 0..20 {
+            adaptive.resolve_lock(true, 2);
+        }
+        assert_eq!(adaptive.offset(), MAX_RAMP_UP);
+        assert_eq!(
+            adaptive.speed_level(GameSpeed::MAX_LEVEL),
+            GameSpeed::MAX_LEVEL
+        );
+    }
+}
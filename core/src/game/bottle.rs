@@ -1,44 +1,113 @@
 use crate::game::block::{block_partner_offset, Block};
-use crate::game::event::ColoredBlock;
+use crate::game::event::{ColoredBlock, VirusCrawl};
 use crate::game::geometry::BottlePoint;
-use crate::game::pill::{Garbage, Pill, PillShape, VirusColor, Vitamin, Vitamins};
+use crate::game::pill::{Garbage, Pill, PillEffect, PillShape, VirusColor, Vitamin, Vitamins};
 use crate::game::random::BottleSeed;
-use rand::rngs::ThreadRng;
+use crate::game::rules::GarbageStyle;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::collections::HashSet;
+use rand::Rng;
+use rand_chacha::ChaChaRng;
+use std::collections::{BTreeSet, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::ops::Range;
 
+/// Size of the classic bottle, every theme is drawn around a bottle of this size
 pub const BOTTLE_WIDTH: u32 = 8;
 pub const BOTTLE_HEIGHT: u32 = 16;
 pub const BOTTLE_FLOOR: u32 = BOTTLE_HEIGHT - 1;
 pub const TOTAL_BLOCKS: u32 = BOTTLE_WIDTH * BOTTLE_HEIGHT;
 
-fn index_at(x: u32, y: u32) -> usize {
-    (y * BOTTLE_WIDTH + x) as usize
+/// Blocks wide & high of a bottle
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BottleSize {
+    width: u32,
+    height: u32,
 }
 
-fn index_to_point(index: usize) -> BottlePoint {
-    BottlePoint::new(
-        (index % BOTTLE_WIDTH as usize) as i32,
-        (index / BOTTLE_WIDTH as usize) as i32,
-    )
-}
+impl BottleSize {
+    pub const CLASSIC: Self = Self::new(BOTTLE_WIDTH, BOTTLE_HEIGHT);
+    /// Room for a pill to spawn & rotate with viruses below it
+    pub const MIN_WIDTH: u32 = 4;
+    pub const MIN_HEIGHT: u32 = 8;
+
+    pub const fn new(width: u32, height: u32) -> Self {
+        assert!(width >= Self::MIN_WIDTH && height >= Self::MIN_HEIGHT);
+        Self { width, height }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn floor(&self) -> u32 {
+        self.height - 1
+    }
+
+    pub fn total_blocks(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    pub fn contains(&self, point: BottlePoint) -> bool {
+        point.x() >= 0
+            && point.x() < self.width as i32
+            && point.y() >= 0
+            && point.y() < self.height as i32
+    }
 
-fn index(point: BottlePoint) -> usize {
-    index_at(point.x() as u32, point.y() as u32)
+    /// Columns right of the classic spawn point that centers the pill of a hand in its own
+    /// lane of the bottle, a single hand has the whole bottle
+    pub fn spawn_offset(&self, hand: usize, hands: usize) -> i32 {
+        let lane = self.width as i32 / hands as i32;
+        hand as i32 * lane + (lane - BOTTLE_WIDTH as i32) / 2
+    }
+
+    fn index_at(&self, x: u32, y: u32) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    fn index_to_point(&self, index: usize) -> BottlePoint {
+        BottlePoint::new(
+            (index % self.width as usize) as i32,
+            (index / self.width as usize) as i32,
+        )
+    }
+
+    fn index(&self, point: BottlePoint) -> usize {
+        self.index_at(point.x() as u32, point.y() as u32)
+    }
+
+    fn row_range(&self, y: u32) -> Range<usize> {
+        self.index_at(0, y)..self.index_at(0, y + 1)
+    }
 }
 
-fn row_range(y: u32) -> Range<usize> {
-    index_at(0, y)..index_at(0, y + 1)
+impl Default for BottleSize {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
 }
 
 pub type SendGarbage = Vec<VirusColor>;
 
+/// Most pills that can be active in a bottle at once, one for each player of a co-op bottle
+pub const MAX_HANDS: usize = 2;
+
+/// Viruses remaining in the bottle indexed by `VirusColor`
+pub type VirusCounts = [u32; VirusColor::N];
+
+/// Chance of each virus crawling on every crawl of the bottle
+const VIRUS_CRAWL_CHANCE: f64 = 0.25;
+/// Viruses crawl sideways or down, never up into the spawn
+const VIRUS_CRAWL_OFFSETS: [(i32, i32); 3] = [(-1, 0), (1, 0), (0, 1)];
+
 struct PatternMatchContext {
     is_vertical: bool,
-    result: HashSet<BottlePoint>,
+    /// ordered so that destroyed blocks are reported the same way every time
+    result: BTreeSet<BottlePoint>,
     last_color: Option<VirusColor>,
     count: u32,
     patterns: Vec<VirusColor>,
@@ -50,7 +119,7 @@ impl PatternMatchContext {
             is_vertical,
             count: 0,
             last_color: None,
-            result: HashSet::new(),
+            result: BTreeSet::new(),
             patterns: vec![],
         }
     }
@@ -99,47 +168,107 @@ impl PatternMatchContext {
 
 #[derive(Clone)]
 pub struct Bottle {
-    blocks: [Block; TOTAL_BLOCKS as usize],
-    pill: Option<Pill>,
-    rng: ThreadRng,
+    size: BottleSize,
+    blocks: Vec<Block>,
+    /// active pill of each hand
+    pills: [Option<Pill>; MAX_HANDS],
+    /// hand that every pill operation applies to
+    hand: usize,
+    hands: usize,
+    /// render a ghost where the pill will land
+    ghost: bool,
 }
 
 impl Bottle {
     #[cfg(test)]
     pub fn new() -> Self {
+        Self::of_size(BottleSize::CLASSIC)
+    }
+
+    #[cfg(test)]
+    pub fn of_size(size: BottleSize) -> Self {
         Self {
-            blocks: [Block::Empty; TOTAL_BLOCKS as usize],
-            pill: None,
-            rng: thread_rng(),
+            size,
+            blocks: vec![Block::Empty; size.total_blocks()],
+            pills: [None; MAX_HANDS],
+            hand: 0,
+            hands: 1,
+            ghost: true,
         }
     }
 
     pub fn from_seed(seed: BottleSeed) -> Self {
         Self {
+            size: seed.size(),
             blocks: seed.into_blocks(),
-            pill: None,
-            rng: thread_rng(),
+            pills: [None; MAX_HANDS],
+            hand: 0,
+            hands: 1,
+            ghost: true,
         }
     }
 
+    pub fn set_ghost(&mut self, ghost: bool) {
+        self.ghost = ghost;
+        self.render_ghost();
+    }
+
+    pub fn size(&self) -> BottleSize {
+        self.size
+    }
+
+    /// Splits the bottle into lanes for some number of hands, each spawning their own pill
+    pub fn set_hands(&mut self, hands: usize) {
+        assert!(hands > 0 && hands <= MAX_HANDS);
+        self.hands = hands;
+    }
+
+    /// Selects the hand whose pill is moved, dropped & locked
+    pub fn select_hand(&mut self, hand: usize) {
+        assert!(hand < self.hands);
+        self.hand = hand;
+    }
+
+    fn pill(&self) -> Option<Pill> {
+        self.pills[self.hand]
+    }
+
+    fn set_pill(&mut self, pill: Option<Pill>) {
+        self.pills[self.hand] = pill;
+    }
+
+    /// True when a point is blocked for the pill of a hand, including by the pill of another hand
+    fn is_occupied(&self, hand: usize, point: BottlePoint) -> bool {
+        self.block(point).is_destructible()
+            || self
+                .pills
+                .iter()
+                .enumerate()
+                .filter(|(h, _)| *h != hand)
+                .filter_map(|(_, pill)| *pill)
+                .any(|pill| pill.vitamins().iter().any(|v| v.position() == point))
+    }
+
     pub fn row(&self, y: u32) -> &[Block] {
-        &self.blocks[row_range(y)]
+        &self.blocks[self.size.row_range(y)]
     }
 
     pub fn block(&self, point: BottlePoint) -> Block {
-        self.blocks[index(point)]
+        self.blocks[self.size.index(point)]
     }
 
     pub fn block_at(&self, x: u32, y: u32) -> Block {
-        self.blocks[index_at(x, y)]
+        self.blocks[self.size.index_at(x, y)]
     }
 
     fn set_block(&mut self, point: BottlePoint, state: Block) {
-        self.blocks[index(point)] = state;
+        let index = self.size.index(point);
+        self.blocks[index] = state;
     }
 
     fn set_block_at(&mut self, x: u32, y: u32, state: Block) {
-        self.blocks[index_at(x, y)] = state;
+        let index = self.size.index_at(x, y);
+        self.blocks[index] = state;
     }
 
     fn set_vitamin(&mut self, pill: &Pill, vitamin: &Vitamin) {
@@ -150,7 +279,7 @@ impl Bottle {
     }
 
     fn set_garbage(&mut self, point: BottlePoint) {
-        let index = index(point);
+        let index = self.size.index(point);
         self.blocks[index] = Block::Garbage(
             self.blocks[index]
                 .destructible_color()
@@ -162,24 +291,66 @@ impl Bottle {
         self.blocks.iter().filter(|b| b.is_virus()).count() as u32
     }
 
+    pub fn virus_counts(&self) -> VirusCounts {
+        let mut counts = [0; VirusColor::N];
+        for block in self.blocks.iter() {
+            if let Block::Virus(color) = block {
+                counts[*color as usize] += 1;
+            }
+        }
+        counts
+    }
+
     pub fn viruses(&self) -> Vec<ColoredBlock> {
         self.blocks
             .iter()
             .enumerate()
             .filter(|(_, b)| b.is_virus())
-            .map(|(index, b)| ColoredBlock::from_block(index_to_point(index), *b))
+            .map(|(index, b)| ColoredBlock::from_block(self.size.index_to_point(index), *b))
             .collect()
     }
 
+    /// Some viruses crawl into a random neighbouring empty block, the ghost is moved to match
+    pub fn crawl_viruses(&mut self, rng: &mut ChaChaRng) -> Vec<VirusCrawl> {
+        let mut crawls = vec![];
+        for index in 0..self.blocks.len() {
+            let color = match self.blocks[index] {
+                Block::Virus(color) => color,
+                _ => continue,
+            };
+            let from = self.size.index_to_point(index);
+            let crawled_here = crawls.iter().any(|c: &VirusCrawl| c.to == from);
+            if crawled_here || !rng.gen_bool(VIRUS_CRAWL_CHANCE) {
+                continue;
+            }
+            let targets = VIRUS_CRAWL_OFFSETS
+                .iter()
+                .map(|(dx, dy)| from.translate(*dx, *dy))
+                .filter(|p| self.size.contains(*p))
+                .filter(|p| matches!(self.block(*p), Block::Empty | Block::Ghost(..)))
+                .collect::<Vec<BottlePoint>>();
+            if let Some(&to) = targets.choose(rng) {
+                self.set_block(from, Block::Empty);
+                self.set_block(to, Block::Virus(color));
+                crawls.push(VirusCrawl { color, from, to });
+            }
+        }
+        if !crawls.is_empty() {
+            self.render_ghost();
+        }
+        crawls
+    }
+
     pub fn try_spawn(&mut self, shape: PillShape) -> Option<Vitamins> {
-        if self.pill.is_some() {
+        if self.pill().is_some() {
             panic!("pill already spawned")
         }
 
-        let pill = Pill::new(shape);
+        let mut pill = Pill::new(shape);
+        pill.translate(self.size.spawn_offset(self.hand, self.hands), 0);
         let mut success = true;
         for vitamin in pill.vitamins() {
-            if self.block(vitamin.position()).is_destructible() {
+            if self.is_occupied(self.hand, vitamin.position()) {
                 success = false;
             } else {
                 self.set_vitamin(&pill, &vitamin);
@@ -187,7 +358,7 @@ impl Bottle {
         }
 
         // regardless of success we have set blocks for this pill
-        self.pill = Some(pill);
+        self.set_pill(Some(pill));
 
         if success {
             self.render_ghost();
@@ -197,6 +368,19 @@ impl Bottle {
         }
     }
 
+    /// Removes a pill that could not spawn, leaving any blocks it overlapped
+    pub fn discard_pill(&mut self) {
+        if let Some(pill) = self.pill() {
+            self.set_pill(None);
+            for vitamin in pill.vitamins() {
+                if !self.is_occupied(self.hand, vitamin.position()) {
+                    self.set_block(vitamin.position(), Block::Empty);
+                }
+            }
+            self.render_ghost();
+        }
+    }
+
     pub fn left(&mut self) -> bool {
         self.move_pill(-1)
     }
@@ -207,13 +391,12 @@ impl Bottle {
 
     fn move_pill(&mut self, dx: i32) -> bool {
         debug_assert!(dx == -1 || dx == 1);
-        if self.pill.is_none() {
-            return false;
-        }
-
-        let vitamins = self.pill.unwrap().vitamins();
+        let vitamins = match self.pill() {
+            None => return false,
+            Some(pill) => pill.vitamins(),
+        };
         for vitamin in vitamins {
-            if dx > 0 && vitamin.position().x() == BOTTLE_WIDTH as i32 - 1 {
+            if dx > 0 && vitamin.position().x() == self.size.width() as i32 - 1 {
                 // collided with the right wall
                 return false;
             } else if dx < 0 && vitamin.position().x() == 0 {
@@ -221,8 +404,8 @@ impl Bottle {
                 return false;
             }
             let check_point = vitamin.position().translate(dx, 0);
-            if self.block(check_point).is_destructible() {
-                // collided with a virus
+            if self.is_occupied(self.hand, check_point) {
+                // collided with a virus or the pill of another hand
                 return false;
             }
         }
@@ -243,8 +426,8 @@ impl Bottle {
     /// Steps down the current pill
     /// Returns true if successful
     pub fn step_down_pill(&mut self) -> bool {
-        let vitamins = self.pill.expect("no pill").vitamins();
-        if self.vitamins_collide(vitamins) {
+        let vitamins = self.pill().expect("no pill").vitamins();
+        if self.vitamins_collide(self.hand, vitamins) {
             false
         } else {
             self.mutate_pill(|t| t.translate(0, 1));
@@ -253,16 +436,23 @@ impl Bottle {
     }
 
     pub fn hard_drop(&mut self) -> Option<(u32, Vitamins)> {
-        let (dropped, _) = self.dropped_vitamins()?;
-        let vitamins = self.pill?.vitamins();
+        let (dropped, _) = self.dropped_vitamins(self.hand)?;
+        let vitamins = self.pill()?.vitamins();
         if dropped > 0 {
             self.mutate_pill(|t| t.translate(0, dropped as i32));
         }
         Some((dropped, vitamins))
     }
 
-    pub fn lock(&mut self) -> Option<Vitamins> {
-        let pill = self.pill?;
+    /// Current vitamins and where they would land if hard dropped
+    pub fn drop_path(&self) -> Option<(Vitamins, Vitamins)> {
+        let (_, ghost) = self.dropped_vitamins(self.hand)?;
+        Some((self.pill()?.vitamins(), ghost))
+    }
+
+    /// Locks the pill into the stack, returning its vitamins & the effect of a power-up pill
+    pub fn lock(&mut self) -> Option<(Vitamins, PillEffect)> {
+        let pill = self.pill()?;
         let vitamins = pill.vitamins();
         for vitamin in vitamins {
             self.set_block(
@@ -270,29 +460,75 @@ impl Bottle {
                 Block::Stack(vitamin.color(), pill.rotation(), vitamin.ordinal()),
             );
         }
-        self.pill = None;
-        Some(vitamins)
+        self.set_pill(None);
+        Some((vitamins, pill.effect()))
+    }
+
+    /// Vitamins of the active power-up pills of every hand
+    pub fn power_ups(&self) -> Vec<(Vitamins, PillEffect)> {
+        self.pills
+            .iter()
+            .flatten()
+            .filter(|pill| pill.effect().is_power_up())
+            .map(|pill| (pill.vitamins(), pill.effect()))
+            .collect()
+    }
+
+    /// Blocks cleared by a power-up pill that just locked
+    pub fn power_up(&self, effect: PillEffect, vitamins: Vitamins) -> Vec<ColoredBlock> {
+        let points: BTreeSet<BottlePoint> = match effect {
+            PillEffect::None => BTreeSet::new(),
+            PillEffect::Bomb => {
+                let center = vitamins[0].position();
+                (-1..=1)
+                    .flat_map(|dy| (-1..=1).map(move |dx| center.translate(dx, dy)))
+                    .filter(|p| self.size.contains(*p))
+                    .collect()
+            }
+            PillEffect::Lightning => {
+                let color = Some(vitamins[0].color());
+                (0..self.blocks.len())
+                    .filter(|index| self.blocks[*index].destructible_color() == color)
+                    .map(|index| self.size.index_to_point(index))
+                    .collect()
+            }
+            PillEffect::Heavy => vitamins
+                .iter()
+                .flat_map(|v| {
+                    (1..)
+                        .map(move |dy| v.position().translate(0, dy))
+                        .take_while(|p| {
+                            self.size.contains(*p) && matches!(self.block(*p), Block::Garbage(_))
+                        })
+                })
+                .collect(),
+        };
+        points
+            .into_iter()
+            .filter(|p| self.block(*p).is_destructible())
+            .map(|p| ColoredBlock::from_block(p, self.block(p)))
+            .collect()
     }
 
     pub fn pattern(&self) -> (Vec<ColoredBlock>, Vec<VirusColor>) {
         let mut context = PatternMatchContext::new(false);
 
         // by rows
-        for y in 0..BOTTLE_HEIGHT {
+        for y in 0..self.size.height() {
             context.reset(false);
-            for x in 0..BOTTLE_WIDTH {
+            for x in 0..self.size.width() {
                 context.block(x, y, self.block_at(x, y));
             }
-            context.maybe_pattern(BOTTLE_WIDTH - 1, y, 0);
+            context.maybe_pattern(self.size.width() - 1, y, 0);
         }
 
         // by cols
-        for x in 0..BOTTLE_WIDTH {
+        for x in 0..self.size.width() {
             context.reset(true);
-            for y in 0..BOTTLE_HEIGHT {
+            for y in 0..self.size.height() {
                 context.block(x, y, self.block_at(x, y));
             }
-            context.maybe_pattern(x, BOTTLE_FLOOR, 0);
+            context.maybe_pattern(x, self.size.floor(), 0);
         }
 
         let blocks = context
@@ -304,6 +540,15 @@ impl Bottle {
         (blocks, context.patterns)
     }
 
+    /// Destructible blocks of the top rows, cleared when zen forgives a top out
+    pub fn top_rows(&self, rows: u32) -> Vec<ColoredBlock> {
+        (0..rows.min(self.size.height()))
+            .flat_map(|y| (0..self.size.width()).map(move |x| BottlePoint::new(x as i32, y as i32)))
+            .filter(|p| self.block(*p).is_destructible())
+            .map(|p| ColoredBlock::from_block(p, self.block(p)))
+            .collect()
+    }
+
     pub fn destroy(&mut self, blocks: Vec<ColoredBlock>) {
         for block in blocks {
             let point = block.position;
@@ -322,8 +567,8 @@ impl Bottle {
 
     pub fn step_down_garbage(&mut self) -> bool {
         let mut to_fall = HashSet::new();
-        for x in 0..BOTTLE_WIDTH {
-            for y in 0..BOTTLE_FLOOR {
+        for x in 0..self.size.width() {
+            for y in 0..self.size.floor() {
                 let point = BottlePoint::new(x as i32, y as i32);
                 if to_fall.contains(&point) {
                     continue;
@@ -340,8 +585,8 @@ impl Bottle {
             return false;
         }
 
-        for x in 0..BOTTLE_WIDTH {
-            for y in (0..BOTTLE_FLOOR).rev() {
+        for x in 0..self.size.width() {
+            for y in (0..self.size.floor()).rev() {
                 let point = BottlePoint::new(x as i32, y as i32);
                 if to_fall.contains(&point) {
                     let block = self.block(point);
@@ -361,7 +606,7 @@ impl Bottle {
             if result.contains(&point) {
                 continue;
             }
-            let is_floor = point.y() == BOTTLE_FLOOR as i32;
+            let is_floor = point.y() == self.size.floor() as i32;
             let block = self.block(point);
             match block {
                 Block::Empty => {
@@ -387,28 +632,45 @@ impl Bottle {
         }
     }
 
-    pub fn hold(&mut self) -> Option<PillShape> {
-        let pill = self.pill?;
+    /// Takes the pill out of the bottle, with where it was
+    pub fn hold(&mut self) -> Option<(PillShape, Vitamins)> {
+        let pill = self.pill()?;
 
         // remove from board
         for vitamin in pill.vitamins() {
             self.set_block(vitamin.position(), Block::Empty);
         }
-        self.pill = None;
+        self.set_pill(None);
         self.render_ghost();
 
-        Some(pill.shape())
+        Some((pill.shape(), pill.vitamins()))
     }
 
-    pub fn send_garbage(&mut self, garbage: SendGarbage) -> Vec<Garbage> {
+    pub fn send_garbage(
+        &mut self,
+        garbage: SendGarbage,
+        style: GarbageStyle,
+        rng: &mut ChaChaRng,
+    ) -> Vec<Garbage> {
         let mut available_x = self
             .row(0)
-            .into_iter()
+            .iter()
             .enumerate()
             .filter(|(_, b)| b.is_empty())
             .map(|(x, _)| x as u32)
             .collect::<Vec<u32>>();
-        available_x.shuffle(&mut self.rng);
+        available_x.shuffle(rng);
+
+        let garbage = match style {
+            GarbageStyle::Scattered => garbage,
+            // the colors sent repeat across the row, leaving at least one gap
+            GarbageStyle::Flush => garbage
+                .iter()
+                .copied()
+                .cycle()
+                .take(self.size.width() as usize - 1)
+                .collect(),
+        };
 
         let mut sent = vec![];
         for color in garbage.into_iter() {
@@ -423,39 +685,36 @@ impl Bottle {
     }
 
     pub fn register_lock_placement(&mut self) -> u32 {
-        self.pill
+        self.pills[self.hand]
             .as_mut()
             .expect("no pill to set lock placements")
             .register_lock_placement()
     }
 
     pub fn lock_placements(&self) -> u32 {
-        self.pill
+        self.pill()
             .expect("no pill to get lock placements")
             .lock_placements()
     }
 
     pub fn is_collision(&self) -> bool {
-        self.vitamins_collide(self.pill.expect("no pill to test for collision").vitamins())
+        let pill = self.pill().expect("no pill to test for collision");
+        self.vitamins_collide(self.hand, pill.vitamins())
     }
 
     fn try_rotate(&self, clockwise: bool) -> Option<(i32, i32)> {
-        let pill = &self.pill?;
+        let pill = &self.pill()?;
 
         let next_points = pill.next_rotation(clockwise);
         for (dx, dy) in pill.available_wall_kicks() {
             let mut success = true;
             for p in next_points.iter() {
                 let p = p.translate(*dx, *dy);
-                if p.x() < 0
-                    || p.x() >= BOTTLE_WIDTH as i32
-                    || p.y() < 0
-                    || p.y() >= BOTTLE_HEIGHT as i32
-                {
+                if !self.size.contains(p) {
                     success = false;
                     break;
                 }
-                if self.block(p).is_destructible() {
+                if self.is_occupied(self.hand, p) {
                     success = false;
                     break;
                 }
@@ -470,7 +729,7 @@ impl Bottle {
 
     fn mutate_pill<F: FnMut(&mut Pill)>(&mut self, mut f: F) {
         // remove from board
-        let mut pill = self.pill.unwrap();
+        let mut pill = self.pill().unwrap();
         for vitamin in pill.vitamins() {
             self.set_block(vitamin.position(), Block::Empty);
         }
@@ -483,15 +742,15 @@ impl Bottle {
             self.set_vitamin(&pill, &vitamin);
         }
 
-        self.pill = Some(pill);
+        self.set_pill(Some(pill));
         self.render_ghost();
     }
 
-    /// gets a copy of the current vitamins having dropped until a collision
-    fn dropped_vitamins(&self) -> Option<(u32, Vitamins)> {
-        let mut vitamins = self.pill?.vitamins();
+    /// gets a copy of the vitamins of a hand having dropped until a collision
+    fn dropped_vitamins(&self, hand: usize) -> Option<(u32, Vitamins)> {
+        let mut vitamins = self.pills[hand]?.vitamins();
         let mut dropped_rows = 0;
-        while !self.vitamins_collide(vitamins) {
+        while !self.vitamins_collide(hand, vitamins) {
             dropped_rows += 1;
             for vitamin in vitamins.iter_mut() {
                 vitamin.translate(0, 1);
@@ -502,38 +761,42 @@ impl Bottle {
 
     fn render_ghost(&mut self) {
         // remove all existing ghost blocks
-        for i in 0..(TOTAL_BLOCKS as usize) {
-            if matches!(self.blocks[i], Block::Ghost(_, _, _)) {
-                self.blocks[i] = Block::Empty;
+        for block in self.blocks.iter_mut() {
+            if matches!(block, Block::Ghost(_, _, _)) {
+                *block = Block::Empty;
             }
         }
 
-        if self.pill.is_none() {
-            // no pill, no ghost.
+        if !self.ghost {
+            // ghost disabled, no ghost.
             return;
         }
 
-        let rotation = self.pill.unwrap().rotation();
-        let (_, vitamins) = self.dropped_vitamins().unwrap();
-        for vitamin in vitamins {
-            if self.block(vitamin.position()) == Block::Empty {
-                self.set_block(
-                    vitamin.position(),
-                    Block::Ghost(vitamin.color(), rotation, vitamin.ordinal()),
-                )
+        for hand in 0..self.hands {
+            let rotation = match self.pills[hand] {
+                None => continue,
+                Some(pill) => pill.rotation(),
+            };
+            let (_, vitamins) = self.dropped_vitamins(hand).unwrap();
+            for vitamin in vitamins {
+                if self.block(vitamin.position()) == Block::Empty {
+                    self.set_block(
+                        vitamin.position(),
+                        Block::Ghost(vitamin.color(), rotation, vitamin.ordinal()),
+                    )
+                }
             }
         }
     }
 
-    fn vitamins_collide(&self, vitamins: Vitamins) -> bool {
+    fn vitamins_collide(&self, hand: usize, vitamins: Vitamins) -> bool {
         for vitamin in vitamins {
-            if vitamin.position().y() == BOTTLE_FLOOR as i32 {
+            if vitamin.position().y() == self.size.floor() as i32 {
                 // collided with the floor
                 return true;
             }
-            let block_down = self.block(vitamin.position().translate(0, 1));
-            if block_down.is_destructible() {
-                // collided with a virus or another vitamin
+            if self.is_occupied(hand, vitamin.position().translate(0, 1)) {
+                // collided with a virus, another vitamin or the pill of another hand
                 return true;
             }
         }
@@ -544,12 +807,12 @@ impl Bottle {
 
 impl Debug for Bottle {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "   {}", "-".repeat(BOTTLE_WIDTH as usize))?;
+        writeln!(f, "   {}", "-".repeat(self.size.width() as usize))?;
 
-        for y in 0..BOTTLE_HEIGHT {
+        for y in 0..self.size.height() {
             write!(f, "{:02}|", y)?;
 
-            for x in 0..BOTTLE_WIDTH {
+            for x in 0..self.size.width() {
                 match self.block_at(x, y) {
                     Block::Vitamin(color, _, _)
                     | Block::Stack(color, _, _)
@@ -562,7 +825,7 @@ impl Debug for Bottle {
 
             writeln!(f, "|")?;
         }
-        write!(f, "   {}", "-".repeat(BOTTLE_WIDTH as usize))
+        write!(f, "   {}", "-".repeat(self.size.width() as usize))
     }
 }
 
@@ -570,7 +833,8 @@ impl Debug for Bottle {
 mod tests {
     use super::*;
     use crate::game::geometry::Rotation;
-    use crate::game::pill::VitaminOrdinal;
+    use crate::game::pill::{VitaminOrdinal, VITAMIN_SPAWN_POINTS};
+    use rand::SeedableRng;
     use std::collections::hash_map::RandomState;
 
     #[test]
@@ -588,6 +852,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn spawns_pill_centered_in_wide_bottle() {
+        let mut bottle = Bottle::of_size(BottleSize::new(12, 20));
+        let vitamins = bottle.try_spawn(PillShape::new(VirusColor::Red, VirusColor::Blue));
+        assert!(vitamins.is_some());
+        bottle.has_vitamin_at(5, 0, VirusColor::Red, Rotation::North, VitaminOrdinal::Left);
+        bottle.has_ghost_at(5, 19, VirusColor::Red, Rotation::North, VitaminOrdinal::Left);
+
+        for _ in 0..5 {
+            assert!(bottle.right());
+        }
+        assert!(!bottle.right(), "{:?}", bottle);
+        bottle.has_vitamin_at(
+            11,
+            0,
+            VirusColor::Blue,
+            Rotation::North,
+            VitaminOrdinal::Right,
+        );
+    }
+
+    #[test]
+    fn pills_of_two_hands_collide() {
+        let mut bottle = Bottle::of_size(BottleSize::new(16, 16));
+        bottle.set_hands(2);
+        bottle.try_spawn(PillShape::new(VirusColor::Red, VirusColor::Blue));
+        bottle.select_hand(1);
+        bottle.try_spawn(PillShape::new(VirusColor::Yellow, VirusColor::Yellow));
+        bottle.has_vitamin_at(11, 0, VirusColor::Yellow, Rotation::North, VitaminOrdinal::Left);
+
+        // the right pill moves left until it meets the left pill at x 3 & 4
+        for _ in 0..6 {
+            assert!(bottle.left());
+        }
+        assert!(!bottle.left(), "{:?}", bottle);
+        bottle.has_vitamin_at(5, 0, VirusColor::Yellow, Rotation::North, VitaminOrdinal::Left);
+
+        // both pills render a ghost
+        bottle.has_ghost_at(3, 15, VirusColor::Red, Rotation::North, VitaminOrdinal::Left);
+        bottle.has_ghost_at(
+            5,
+            15,
+            VirusColor::Yellow,
+            Rotation::North,
+            VitaminOrdinal::Left,
+        );
+
+        bottle.select_hand(0);
+        assert!(!bottle.right(), "{:?}", bottle);
+        assert!(bottle.lock().is_some());
+        bottle.select_hand(1);
+        assert!(bottle.step_down_pill());
+    }
+
     #[test]
     fn renders_ghost_to_floor() {
         let mut bottle = Bottle::new();
@@ -629,6 +947,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn no_ghost_when_disabled() {
+        let mut bottle = Bottle::new();
+        bottle.try_spawn(PillShape::new(VirusColor::Red, VirusColor::Blue));
+        bottle.set_ghost(false);
+        assert_eq!(bottle.block_at(3, 15), Block::Empty);
+        assert_eq!(bottle.block_at(4, 15), Block::Empty);
+    }
+
+    #[test]
+    fn send_scattered_garbage() {
+        let mut bottle = Bottle::new();
+        let sent = bottle.send_garbage(
+            vec![VirusColor::Red, VirusColor::Blue],
+            GarbageStyle::Scattered,
+            &mut ChaChaRng::seed_from_u64(123),
+        );
+        assert_eq!(sent.len(), 2);
+        assert_eq!(bottle.row(0).iter().filter(|b| b.is_empty()).count(), 6);
+    }
+
+    #[test]
+    fn send_flush_garbage_row_with_one_gap() {
+        let mut bottle = Bottle::new();
+        let sent = bottle.send_garbage(
+            vec![VirusColor::Red, VirusColor::Blue],
+            GarbageStyle::Flush,
+            &mut ChaChaRng::seed_from_u64(123),
+        );
+        assert_eq!(sent.len(), BOTTLE_WIDTH as usize - 1);
+        assert_eq!(bottle.row(0).iter().filter(|b| b.is_empty()).count(), 1);
+        assert_eq!(
+            sent.iter().filter(|g| g.color == VirusColor::Red).count(),
+            4
+        );
+    }
+
+    #[test]
+    fn counts_viruses_by_color() {
+        let mut bottle = Bottle::new();
+        bottle.having_virus(0, 10, VirusColor::Yellow);
+        bottle.having_virus(1, 10, VirusColor::Red);
+        bottle.having_virus(2, 10, VirusColor::Red);
+        bottle.having_garbage(3, 10, VirusColor::Blue);
+        assert_eq!(bottle.virus_counts(), [1, 0, 2, 0]);
+    }
+
+    #[test]
+    fn discards_pill_that_could_not_spawn() {
+        let mut bottle = Bottle::new();
+        bottle.having_virus(4, 0, VirusColor::Yellow);
+        assert!(bottle
+            .try_spawn(PillShape::new(VirusColor::Red, VirusColor::Blue))
+            .is_none());
+        bottle.discard_pill();
+        bottle.is_empty_at(3, 0);
+        bottle.has_virus_at(4, 0, VirusColor::Yellow);
+        assert!(bottle
+            .try_spawn(PillShape::new(VirusColor::Red, VirusColor::Blue))
+            .is_none());
+    }
+
     #[test]
     fn ignores_move_left_when_no_pill() {
         let mut bottle = Bottle::new();
@@ -866,6 +1246,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn drop_path_to_floor() {
+        let mut bottle = Bottle::new();
+        assert!(bottle.drop_path().is_none());
+        bottle.try_spawn(PillShape::new(VirusColor::Red, VirusColor::Blue));
+        let (vitamins, ghost) = bottle.drop_path().unwrap();
+        assert_eq!(vitamins.map(|v| v.position()), VITAMIN_SPAWN_POINTS);
+        assert_eq!(
+            ghost.map(|v| v.position()),
+            [BottlePoint::new(3, 15), BottlePoint::new(4, 15)]
+        );
+        // previewing does not move the pill
+        let [left, _] = VITAMIN_SPAWN_POINTS;
+        bottle.has_vitamin_at(
+            left.x() as u32,
+            left.y() as u32,
+            VirusColor::Red,
+            Rotation::North,
+            VitaminOrdinal::Left,
+        );
+    }
+
     #[test]
     fn locks() {
         let mut bottle = Bottle::new();
@@ -888,6 +1290,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn locks_power_up() {
+        let mut bottle = Bottle::new();
+        bottle.try_spawn(PillShape::RB.with_effect(PillEffect::Heavy));
+        assert_eq!(bottle.power_ups().len(), 1);
+        bottle.hard_drop();
+        let (_, effect) = bottle.lock().unwrap();
+        assert_eq!(effect, PillEffect::Heavy);
+        assert!(bottle.power_ups().is_empty());
+    }
+
+    #[test]
+    fn bomb_clears_around_the_pill() {
+        let mut bottle = Bottle::new();
+        bottle.having_garbage(4, BOTTLE_FLOOR - 1, VirusColor::Blue);
+        bottle.having_virus(2, BOTTLE_FLOOR - 1, VirusColor::Yellow);
+        bottle.having_virus(1, BOTTLE_FLOOR - 1, VirusColor::Yellow);
+        bottle.try_spawn(PillShape::RB.with_effect(PillEffect::Bomb));
+        bottle.hard_drop();
+        let (vitamins, effect) = bottle.lock().unwrap();
+        let blocks = bottle.power_up(effect, vitamins);
+        // the pill, the garbage it landed on & the virus beside it but not the one 2 columns away
+        assert_eq!(blocks.len(), 4, "{:?}", blocks);
+        bottle.destroy(blocks);
+        bottle.is_empty_at(3, BOTTLE_FLOOR - 2);
+        bottle.is_empty_at(4, BOTTLE_FLOOR - 2);
+        bottle.is_empty_at(4, BOTTLE_FLOOR - 1);
+        bottle.is_empty_at(2, BOTTLE_FLOOR - 1);
+        bottle.has_virus_at(1, BOTTLE_FLOOR - 1, VirusColor::Yellow);
+    }
+
+    #[test]
+    fn lightning_clears_a_color() {
+        let mut bottle = Bottle::new();
+        bottle.having_virus(0, 10, VirusColor::Red);
+        bottle.having_virus(7, 12, VirusColor::Red);
+        bottle.having_virus(5, 12, VirusColor::Blue);
+        bottle.try_spawn(PillShape::RB.with_effect(PillEffect::Lightning));
+        bottle.hard_drop();
+        let (vitamins, effect) = bottle.lock().unwrap();
+        let blocks = bottle.power_up(effect, vitamins);
+        assert_eq!(blocks.len(), 4, "{:?}", blocks);
+        assert!(blocks.iter().all(|b| b.color == VirusColor::Red));
+    }
+
+    #[test]
+    fn heavy_falls_through_garbage() {
+        let mut bottle = Bottle::new();
+        bottle.having_garbage(3, BOTTLE_FLOOR, VirusColor::Yellow);
+        bottle.having_garbage(3, BOTTLE_FLOOR - 1, VirusColor::Blue);
+        bottle.having_virus(4, BOTTLE_FLOOR, VirusColor::Yellow);
+        bottle.try_spawn(PillShape::RB.with_effect(PillEffect::Heavy));
+        bottle.hard_drop();
+        let (vitamins, effect) = bottle.lock().unwrap();
+        let blocks = bottle.power_up(effect, vitamins);
+        assert_eq!(blocks.len(), 2, "{:?}", blocks);
+        bottle.destroy(blocks);
+        bottle.is_empty_at(3, BOTTLE_FLOOR);
+        bottle.has_virus_at(4, BOTTLE_FLOOR, VirusColor::Yellow);
+    }
+
+    #[test]
+    fn viruses_crawl_into_empty_blocks() {
+        let mut bottle = Bottle::new();
+        bottle.having_virus(0, BOTTLE_FLOOR, VirusColor::Red);
+        bottle.having_virus(1, BOTTLE_FLOOR, VirusColor::Blue);
+        bottle.having_virus(4, 10, VirusColor::Yellow);
+        let mut rng = ChaChaRng::seed_from_u64(123);
+        let mut crawls = vec![];
+        for _ in 0..20 {
+            crawls.extend(bottle.crawl_viruses(&mut rng));
+        }
+        assert!(!crawls.is_empty());
+        for crawl in crawls {
+            let dx = (crawl.to.x() - crawl.from.x()).abs();
+            let dy = crawl.to.y() - crawl.from.y();
+            assert!(dx + dy == 1 && dy >= 0, "{:?}", crawl);
+        }
+        assert_eq!(bottle.virus_counts(), [1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn boxed_in_virus_cannot_crawl() {
+        let mut bottle = Bottle::new();
+        bottle.having_virus(0, BOTTLE_FLOOR, VirusColor::Red);
+        bottle.having_garbage(1, BOTTLE_FLOOR, VirusColor::Blue);
+        let mut rng = ChaChaRng::seed_from_u64(123);
+        for _ in 0..20 {
+            assert_eq!(bottle.crawl_viruses(&mut rng), vec![]);
+        }
+        bottle.has_virus_at(0, BOTTLE_FLOOR, VirusColor::Red);
+    }
+
+    #[test]
+    fn top_rows() {
+        let mut bottle = Bottle::new();
+        bottle.having_virus(3, 1, VirusColor::Red);
+        bottle.having_garbage(4, 2, VirusColor::Blue);
+        bottle.having_virus(5, 3, VirusColor::Yellow);
+        assert_eq!(
+            bottle.top_rows(3),
+            vec![
+                ColoredBlock::virus(3, 1, VirusColor::Red),
+                ColoredBlock::from_block(BottlePoint::new(4, 2), Block::Garbage(VirusColor::Blue)),
+            ]
+        );
+    }
+
     #[test]
     fn empty_patterns() {
         let bottle = Bottle::new();
@@ -1239,7 +1749,7 @@ mod tests {
         let mut bottle = Bottle::new();
         let shape = PillShape::new(VirusColor::Red, VirusColor::Blue);
         bottle.try_spawn(shape);
-        assert_eq!(bottle.hold(), Some(shape));
+        assert_eq!(bottle.hold().map(|(held, _)| held), Some(shape));
         bottle.is_empty();
     }
 
@@ -1248,12 +1758,16 @@ mod tests {
         let mut bottle = Bottle::new();
         assert_eq!(
             bottle
-                .send_garbage(vec![VirusColor::Blue, VirusColor::Red])
+                .send_garbage(
+                    vec![VirusColor::Blue, VirusColor::Red],
+                    GarbageStyle::Scattered,
+                    &mut ChaChaRng::seed_from_u64(123)
+                )
                 .len(),
             2
         );
         let mut garbage = HashSet::new();
-        for block in bottle.row(0).into_iter() {
+        for block in bottle.row(0).iter() {
             if let Block::Garbage(color) = block {
                 garbage.insert(*color);
             }
@@ -1308,21 +1822,16 @@ mod tests {
 
     impl BottleTestHarness for Bottle {
         fn is_empty(&self) {
-            assert_eq!(
-                self.blocks,
-                [Block::Empty; TOTAL_BLOCKS as usize],
-                "{:?}",
-                self
-            );
+            assert!(self.blocks.iter().all(|b| b.is_empty()), "{:?}", self);
         }
 
         fn is_empty_at(&self, x: u32, y: u32) {
-            assert_eq!(self.blocks[index_at(x, y)], Block::Empty, "{:?}", self);
+            assert_eq!(self.blocks[self.size.index_at(x, y)], Block::Empty, "{:?}", self);
         }
 
         fn is_garbage_at(&self, x: u32, y: u32, color: VirusColor) {
             assert_eq!(
-                self.blocks[index_at(x, y)],
+                self.blocks[self.size.index_at(x, y)],
                 Block::Garbage(color),
                 "{:?}",
                 self
@@ -1338,7 +1847,7 @@ mod tests {
             ordinal: VitaminOrdinal,
         ) {
             assert_eq!(
-                self.blocks[index_at(x, y)],
+                self.blocks[self.size.index_at(x, y)],
                 Block::Vitamin(color, rotation, ordinal),
                 "{:?}",
                 self
@@ -1354,7 +1863,7 @@ mod tests {
             ordinal: VitaminOrdinal,
         ) {
             assert_eq!(
-                self.blocks[index_at(x, y)],
+                self.blocks[self.size.index_at(x, y)],
                 Block::Stack(color, rotation, ordinal),
                 "{:?}",
                 self
@@ -1363,7 +1872,7 @@ mod tests {
 
         fn has_garbage_at(&self, x: u32, y: u32, color: VirusColor) {
             assert_eq!(
-                self.blocks[index_at(x, y)],
+                self.blocks[self.size.index_at(x, y)],
                 Block::Garbage(color),
                 "{:?}",
                 self
@@ -1379,7 +1888,7 @@ mod tests {
             ordinal: VitaminOrdinal,
         ) {
             assert_eq!(
-                self.blocks[index_at(x, y)],
+                self.blocks[self.size.index_at(x, y)],
                 Block::Ghost(color, rotation, ordinal),
                 "{:?}",
                 self
@@ -1388,7 +1897,7 @@ mod tests {
 
         fn has_virus_at(&self, x: u32, y: u32, color: VirusColor) {
             assert_eq!(
-                self.blocks[index_at(x, y)],
+                self.blocks[self.size.index_at(x, y)],
                 Block::Virus(color),
                 "{:?}",
                 self
@@ -1396,7 +1905,7 @@ mod tests {
         }
 
         fn having_virus(&mut self, x: u32, y: u32, color: VirusColor) {
-            self.blocks[index_at(x, y)] = Block::Virus(color);
+            self.blocks[self.size.index_at(x, y)] = Block::Virus(color);
         }
 
         fn having_stack(
@@ -1407,11 +1916,11 @@ mod tests {
             rotation: Rotation,
             ordinal: VitaminOrdinal,
         ) {
-            self.blocks[index_at(x, y)] = Block::Stack(color, rotation, ordinal);
+            self.blocks[self.size.index_at(x, y)] = Block::Stack(color, rotation, ordinal);
         }
 
         fn having_garbage(&mut self, x: u32, y: u32, color: VirusColor) {
-            self.blocks[index_at(x, y)] = Block::Garbage(color);
+            self.blocks[self.size.index_at(x, y)] = Block::Garbage(color);
         }
     }
 }
@@ -0,0 +1,33 @@
+//! Every change to a running game is a command applied by `Game::apply`.
+//! The state machine in `Update` is deterministic, so the same commands applied to a game
+//! from the same seed always produce the same game, so a log of them can be replayed, rewound
+//! or sent over a network.
+
+use crate::game::bottle::SendGarbage;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameCommand {
+    Left,
+    Right,
+    Rotate { clockwise: bool },
+    SoftDrop { soft_drop: bool },
+    HardDrop,
+    /// First stage of a two-stage hard drop, shows where the pill would land without committing
+    PreviewHardDrop,
+    Hold,
+    /// Manually raises the speed level by one, on top of the speed ups from pills spawned
+    SpeedUp,
+    /// Buffered until the next spawn
+    SendGarbage { garbage: SendGarbage },
+    Update { delta: Duration },
+    NextLevel,
+    /// Reseeds the bottle for sudden death overtime, keeping the score & level
+    Overtime { virus_level: u32 },
+    Rewind,
+    /// Swaps in the pill, queue & hold of the other player of a co-op bottle
+    SwapHands,
+    SaveState { slot: usize },
+    LoadState { slot: usize },
+}
@@ -0,0 +1,305 @@
+//! Pill placement evaluation, used by the attract mode demo & available for building bots
+
+use crate::game::block::Block;
+use crate::game::bottle::BottleSize;
+use crate::game::geometry::Rotation;
+use crate::game::pill::VirusColor;
+use crate::game::Game;
+
+/// Rows below the top of the bottle where the stack starts to block the spawn
+const DANGER_ROWS: u32 = 4;
+
+/// Destructible colors in the bottle indexed by [y][x], the active pill is not included
+pub type Board = Vec<Vec<Option<VirusColor>>>;
+
+pub fn empty_board(size: BottleSize) -> Board {
+    vec![vec![None; size.width() as usize]; size.height() as usize]
+}
+
+fn board_width(board: &Board) -> i32 {
+    board.first().map_or(0, |row| row.len() as i32)
+}
+
+fn board_height(board: &Board) -> i32 {
+    board.len() as i32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub rotation: Rotation,
+    /// leftmost column of the pill
+    pub x: i32,
+}
+
+impl Placement {
+    /// (x, y) offsets of the left & right vitamins from the bottom left of the pill
+    pub fn offsets(&self) -> [(i32, i32); 2] {
+        match self.rotation {
+            Rotation::North => [(0, 0), (1, 0)],
+            Rotation::South => [(1, 0), (0, 0)],
+            Rotation::East => [(0, -1), (0, 0)],
+            Rotation::West => [(0, 0), (0, -1)],
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        match self.rotation {
+            Rotation::North | Rotation::South => 2,
+            Rotation::East | Rotation::West => 1,
+        }
+    }
+
+    /// Every rotation in every column that fits in a bottle of some width
+    pub fn all(width: u32) -> impl Iterator<Item = Placement> {
+        [Rotation::North, Rotation::East, Rotation::South, Rotation::West]
+            .into_iter()
+            .flat_map(move |rotation| {
+                let placement = Placement { rotation, x: 0 };
+                (0..=width as i32 - placement.width()).map(move |x| Placement { rotation, x })
+            })
+    }
+}
+
+/// The active pill as read from the bottle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActivePill {
+    pub colors: [VirusColor; 2],
+    pub rotation: Rotation,
+    /// leftmost column of the pill
+    pub x: i32,
+}
+
+impl ActivePill {
+    pub fn read(game: &Game) -> Option<Self> {
+        let mut colors = [None, None];
+        let mut rotation = Rotation::North;
+        let mut x = i32::MAX;
+        for y in 0..game.bottle_size().height() {
+            for (bx, block) in game.row(y).iter().enumerate() {
+                if let Block::Vitamin(color, r, ordinal) = block {
+                    colors[*ordinal as usize] = Some(*color);
+                    rotation = *r;
+                    x = x.min(bx as i32);
+                }
+            }
+        }
+        match colors {
+            [Some(left), Some(right)] => Some(Self {
+                colors: [left, right],
+                rotation,
+                x,
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub fn read_board(game: &Game) -> Board {
+    let mut board = empty_board(game.bottle_size());
+    for (y, row) in board.iter_mut().enumerate() {
+        for (x, block) in game.row(y as u32).iter().enumerate() {
+            row[x] = block.destructible_color();
+        }
+    }
+    board
+}
+
+/// Color at (x, y) or None when empty or out of the bottle
+pub fn color_at(board: &Board, x: i32, y: i32) -> Option<VirusColor> {
+    if x < 0 || y < 0 || x >= board_width(board) || y >= board_height(board) {
+        None
+    } else {
+        board[y as usize][x as usize]
+    }
+}
+
+fn is_free(board: &Board, x: i32, y: i32) -> bool {
+    // the top of a vertical pill may sit above the bottle
+    y < 0 || (y < board_height(board) && color_at(board, x, y).is_none())
+}
+
+/// Height of the stack in each column, measured from the floor to the highest block
+pub fn column_heights(board: &Board) -> Vec<u32> {
+    (0..board_width(board) as usize)
+        .map(|x| {
+            board
+                .iter()
+                .position(|row| row[x].is_some())
+                .map(|y| (board.len() - y) as u32)
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Number of orthogonal neighbours of (x, y) that match its color
+pub fn color_adjacency(board: &Board, x: i32, y: i32) -> u32 {
+    let color = match color_at(board, x, y) {
+        None => return 0,
+        Some(color) => color,
+    };
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .filter(|(dx, dy)| color_at(board, x + dx, y + dy) == Some(color))
+        .count() as u32
+}
+
+/// Drops the placement from the top of the bottle, returns the row it lands on
+pub fn landing_row(board: &Board, placement: Placement) -> Option<i32> {
+    let fits = |y: i32| {
+        placement
+            .offsets()
+            .iter()
+            .all(|(dx, dy)| is_free(board, placement.x + dx, y + dy))
+    };
+    if !fits(0) {
+        return None;
+    }
+    let mut y = 0;
+    while fits(y + 1) {
+        y += 1;
+    }
+    Some(y)
+}
+
+/// Length of the same colored run through (x, y) in the direction (dx, dy)
+pub fn run_length(board: &Board, x: i32, y: i32, dx: i32, dy: i32) -> i32 {
+    let color = color_at(board, x, y);
+    let count = |sign: i32| {
+        (1..)
+            .take_while(|i| color_at(board, x + dx * i * sign, y + dy * i * sign) == color)
+            .count() as i32
+    };
+    1 + count(1) + count(-1)
+}
+
+/// Greedy score of dropping a pill with the given colors at the placement, higher is better.
+/// None when the placement is blocked.
+pub fn evaluate(board: &Board, placement: Placement, colors: [VirusColor; 2]) -> Option<i32> {
+    let y = landing_row(board, placement)?;
+    let mut next = board.clone();
+    let cells = placement.offsets().map(|(dx, dy)| (placement.x + dx, y + dy));
+    for ((x, y), color) in cells.into_iter().zip(colors) {
+        if y >= 0 {
+            next[y as usize][x as usize] = Some(color);
+        }
+    }
+
+    let mut score = 0;
+    for (x, y) in cells {
+        if y < 0 {
+            // pill would be left poking out of the top of the bottle
+            score -= 100;
+            continue;
+        }
+        for (dx, dy) in [(1, 0), (0, 1)] {
+            let run = run_length(&next, x, y, dx, dy);
+            score += if run >= 4 { 50 } else { run * run };
+        }
+        if let Some(below) = color_at(&next, x, y + 1) {
+            if Some(below) != next[y as usize][x as usize] {
+                // burying a different color
+                score -= 6;
+            }
+        }
+        // tie break on touching the same color, e.g. next to a virus that is not yet in a run
+        score += color_adjacency(&next, x, y) as i32;
+        // prefer to play low in the bottle
+        score += y / 2;
+    }
+
+    // avoid stacking up to the spawn position
+    let max_height = column_heights(&next).into_iter().max().unwrap_or(0);
+    score -= 20 * max_height.saturating_sub(board_height(board) as u32 - DANGER_ROWS) as i32;
+    Some(score)
+}
+
+/// Placement with the highest score from evaluate, falls back to the spawn position
+pub fn best_placement(board: &Board, colors: [VirusColor; 2]) -> Placement {
+    Placement::all(board_width(board) as u32)
+        .filter_map(|p| evaluate(board, p, colors).map(|score| (p, score)))
+        .max_by_key(|(_, score)| *score)
+        .map(|(p, _)| p)
+        .unwrap_or(Placement {
+            rotation: Rotation::North,
+            x: board_width(board) / 2 - 1,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::bottle::BOTTLE_FLOOR;
+
+    const FLOOR: usize = BOTTLE_FLOOR as usize;
+
+    #[test]
+    fn lands_on_floor() {
+        let board = empty_board(BottleSize::CLASSIC);
+        let placement = Placement {
+            rotation: Rotation::East,
+            x: 3,
+        };
+        assert_eq!(landing_row(&board, placement), Some(FLOOR as i32));
+    }
+
+    #[test]
+    fn stacks_on_matching_color() {
+        let mut board = empty_board(BottleSize::CLASSIC);
+        board[FLOOR][2] = Some(VirusColor::Red);
+        board[FLOOR - 1][2] = Some(VirusColor::Red);
+        board[FLOOR][5] = Some(VirusColor::Blue);
+
+        // vertical on top of the reds completes the pattern
+        let placement = best_placement(&board, [VirusColor::Red, VirusColor::Red]);
+        assert_eq!(placement.x, 2);
+        assert_eq!(placement.width(), 1);
+    }
+
+    #[test]
+    fn completes_horizontal_pattern() {
+        let mut board = empty_board(BottleSize::CLASSIC);
+        board[FLOOR][0] = Some(VirusColor::Yellow);
+        board[FLOOR][1] = Some(VirusColor::Yellow);
+
+        let placement = best_placement(&board, [VirusColor::Yellow, VirusColor::Yellow]);
+        assert_eq!(placement.x, 2);
+        assert_eq!(placement.width(), 2);
+    }
+
+    #[test]
+    fn places_across_wide_bottle() {
+        let mut board = empty_board(BottleSize::new(12, 16));
+        board[FLOOR][10] = Some(VirusColor::Blue);
+        board[FLOOR][11] = Some(VirusColor::Blue);
+
+        let placement = best_placement(&board, [VirusColor::Blue, VirusColor::Blue]);
+        assert_eq!(placement.x, 8);
+        assert_eq!(placement.width(), 2);
+    }
+
+    #[test]
+    fn measures_column_heights() {
+        let mut board = empty_board(BottleSize::CLASSIC);
+        board[FLOOR][0] = Some(VirusColor::Red);
+        board[FLOOR - 3][4] = Some(VirusColor::Blue);
+
+        let heights = column_heights(&board);
+        assert_eq!(heights[0], 1);
+        assert_eq!(heights[1], 0);
+        assert_eq!(heights[4], 4);
+    }
+
+    #[test]
+    fn counts_color_adjacency() {
+        let mut board = empty_board(BottleSize::CLASSIC);
+        board[FLOOR][1] = Some(VirusColor::Red);
+        board[FLOOR][0] = Some(VirusColor::Red);
+        board[FLOOR][2] = Some(VirusColor::Blue);
+        board[FLOOR - 1][1] = Some(VirusColor::Red);
+
+        assert_eq!(color_adjacency(&board, 1, FLOOR as i32), 2);
+        assert_eq!(color_adjacency(&board, 2, FLOOR as i32), 0);
+        assert_eq!(color_adjacency(&board, 5, 5), 0);
+    }
+}
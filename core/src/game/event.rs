@@ -0,0 +1,202 @@
+use crate::game::block::Block;
+use crate::game::bottle::SendGarbage;
+use crate::game::geometry::BottlePoint;
+use crate::game::pill::{Garbage, PillEffect, PillShape, VirusColor, Vitamins};
+use crate::game::rules::GarbageStyle;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    Move {
+        player: u32,
+    },
+    Rotate {
+        player: u32,
+    },
+    Hold {
+        player: u32,
+        shape: PillShape,
+        /// where the held pill was in the bottle
+        vitamins: Vitamins,
+        /// the pill held before, spawned in place of this one
+        released: Option<PillShape>,
+    },
+    SoftDrop {
+        player: u32,
+    },
+    HardDrop {
+        player: u32,
+        vitamins: Vitamins,
+        dropped_rows: u32,
+    },
+    HardDropPreview {
+        player: u32,
+        vitamins: Vitamins,
+        ghost: Vitamins,
+    },
+    Fall {
+        player: u32,
+    },
+    /// Restored to the spawn of an earlier pill
+    Rewind {
+        player: u32,
+    },
+    SaveState {
+        player: u32,
+        slot: usize,
+    },
+    /// Restored to a save state
+    LoadState {
+        player: u32,
+        slot: usize,
+    },
+    SendGarbage {
+        player: u32,
+        garbage: SendGarbage,
+    },
+    ReceivedGarbage {
+        player: u32,
+        garbage: Vec<Garbage>,
+        style: GarbageStyle,
+    },
+    DropGarbage {
+        player: u32,
+    },
+    Spawn {
+        player: u32,
+        shape: PillShape,
+        is_hold: bool,
+    },
+    Spawned {
+        player: u32,
+    },
+    SpeedLevelUp {
+        player: u32,
+        /// from the speed up key rather than the pills spawned
+        is_boost: bool,
+    },
+    /// Another pill in a row has cleared a pattern
+    Chain {
+        player: u32,
+        chain: u32,
+    },
+    /// The patterns cleared since the last pill locked have scored
+    ComboScore {
+        player: u32,
+        score: u32,
+    },
+    GameOver {
+        player: u32,
+    },
+    /// Zen cleared the top of the bottle rather than ending the game
+    ToppedOut {
+        player: u32,
+        penalty: u32,
+    },
+    Victory {
+        player: u32,
+    },
+    LevelComplete {
+        player: u32,
+    },
+    /// the last virus of this color has been destroyed
+    VirusColorEliminated {
+        player: u32,
+        color: VirusColor,
+    },
+    Lock {
+        player: u32,
+        vitamins: Vitamins,
+        hard_or_soft_dropped: bool,
+        /// points scored for the rows the pill was dropped, 0 without the drop score rule
+        drop_score: u32,
+    },
+    /// A power-up pill has locked & set off its effect
+    PowerUp {
+        player: u32,
+        effect: PillEffect,
+        vitamins: Vitamins,
+    },
+    Destroy {
+        player: u32,
+        blocks: Vec<ColoredBlock>,
+        is_combo: bool,
+    },
+    /// Viruses have crawled into neighbouring empty blocks
+    VirusesCrawled {
+        player: u32,
+        crawls: Vec<VirusCrawl>,
+    },
+    Paused,
+    UnPaused,
+    NextTheme,
+}
+
+impl GameEvent {
+    /// Player whose game raised the event, None for match events
+    pub fn player(&self) -> Option<u32> {
+        match self {
+            GameEvent::Move { player }
+            | GameEvent::Rotate { player }
+            | GameEvent::Hold { player, .. }
+            | GameEvent::SoftDrop { player }
+            | GameEvent::HardDrop { player, .. }
+            | GameEvent::HardDropPreview { player, .. }
+            | GameEvent::Fall { player }
+            | GameEvent::Rewind { player }
+            | GameEvent::SaveState { player, .. }
+            | GameEvent::LoadState { player, .. }
+            | GameEvent::SendGarbage { player, .. }
+            | GameEvent::ReceivedGarbage { player, .. }
+            | GameEvent::DropGarbage { player }
+            | GameEvent::Spawn { player, .. }
+            | GameEvent::Spawned { player }
+            | GameEvent::SpeedLevelUp { player, .. }
+            | GameEvent::Chain { player, .. }
+            | GameEvent::ComboScore { player, .. }
+            | GameEvent::GameOver { player }
+            | GameEvent::ToppedOut { player, .. }
+            | GameEvent::Victory { player }
+            | GameEvent::LevelComplete { player }
+            | GameEvent::VirusColorEliminated { player, .. }
+            | GameEvent::Lock { player, .. }
+            | GameEvent::PowerUp { player, .. }
+            | GameEvent::Destroy { player, .. }
+            | GameEvent::VirusesCrawled { player, .. } => Some(*player),
+            GameEvent::Paused | GameEvent::UnPaused | GameEvent::NextTheme => None,
+        }
+    }
+}
+
+/// A virus that has crawled from one block to a neighbouring empty block
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VirusCrawl {
+    pub color: VirusColor,
+    pub from: BottlePoint,
+    pub to: BottlePoint,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ColoredBlock {
+    pub position: BottlePoint,
+    pub color: VirusColor,
+    pub is_virus: bool,
+}
+
+impl ColoredBlock {
+    #[cfg(test)]
+    pub fn virus(x: i32, y: i32, color: VirusColor) -> Self {
+        Self {
+            position: BottlePoint::new(x, y),
+            color,
+            is_virus: true,
+        }
+    }
+
+    pub fn from_block(position: BottlePoint, block: Block) -> Self {
+        Self {
+            position,
+            color: block.destructible_color().unwrap(),
+            is_virus: block.is_virus(),
+        }
+    }
+}
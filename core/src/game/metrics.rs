@@ -0,0 +1,187 @@
+use crate::game::adaptive::AdaptiveSpeed;
+use crate::game::bottle::{VirusCounts, BOTTLE_WIDTH};
+use crate::game::pill::{PillShape, VirusColor};
+use crate::game::random::PEEK_SIZE;
+use crate::game::GameSpeed;
+use std::time::Duration;
+
+/// Pending garbage is capped at the most that can land in a single batch
+pub const PENDING_GARBAGE_MAX: usize = BOTTLE_WIDTH as usize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameMetrics {
+    player: u32,
+    virus_level: u32,
+    speed: GameSpeed,
+    viruses: u32,
+    virus_counts: VirusCounts,
+    score: u32,
+    queue: [PillShape; PEEK_SIZE],
+    peek_count: usize,
+    hold: Option<PillShape>,
+    chain: u32,
+    speed_level: u32,
+    adaptive_speed: Option<AdaptiveSpeed>,
+    pending_garbage: [Option<VirusColor>; PENDING_GARBAGE_MAX],
+}
+
+impl GameMetrics {
+    pub fn new(
+        player: u32,
+        virus_level: u32,
+        speed: GameSpeed,
+        viruses: u32,
+        score: u32,
+        queue: [PillShape; PEEK_SIZE],
+        hold: Option<PillShape>,
+    ) -> Self {
+        Self {
+            player,
+            virus_level,
+            speed,
+            viruses,
+            virus_counts: [0; VirusColor::N],
+            score,
+            queue,
+            peek_count: PEEK_SIZE,
+            hold,
+            chain: 0,
+            speed_level: 0,
+            adaptive_speed: None,
+            pending_garbage: [None; PENDING_GARBAGE_MAX],
+        }
+    }
+
+    /// Remaining viruses of each color, indexed by [VirusColor]
+    pub fn with_virus_counts(mut self, virus_counts: VirusCounts) -> Self {
+        self.virus_counts = virus_counts;
+        self
+    }
+
+    pub fn with_chain(mut self, chain: u32) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Number of upcoming pills shown to the player, the rest of the queue is hidden
+    pub fn with_peek_count(mut self, peek_count: usize) -> Self {
+        self.peek_count = peek_count.min(PEEK_SIZE);
+        self
+    }
+
+    pub fn with_speed_level(mut self, speed_level: u32) -> Self {
+        self.speed_level = speed_level;
+        self
+    }
+
+    pub fn with_adaptive_speed(mut self, adaptive_speed: Option<AdaptiveSpeed>) -> Self {
+        self.adaptive_speed = adaptive_speed;
+        self
+    }
+
+    /// Garbage colors in the order they will land
+    pub fn with_pending_garbage<I: IntoIterator<Item = VirusColor>>(mut self, garbage: I) -> Self {
+        self.pending_garbage = [None; PENDING_GARBAGE_MAX];
+        for (pending, color) in self.pending_garbage.iter_mut().zip(garbage) {
+            *pending = Some(color);
+        }
+        self
+    }
+
+    pub fn player(&self) -> u32 {
+        self.player
+    }
+    pub fn virus_level(&self) -> u32 {
+        self.virus_level
+    }
+    pub fn virus_count(&self) -> u32 {
+        self.viruses
+    }
+    /// Remaining viruses of each color, indexed by [VirusColor]
+    pub fn virus_counts(&self) -> VirusCounts {
+        self.virus_counts
+    }
+    /// Remaining viruses of a single color
+    pub fn virus_count_of(&self, color: VirusColor) -> u32 {
+        self.virus_counts[color as usize]
+    }
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+    /// Gravity steps up a level every 10 pills, or with the speed up key,
+    /// adjusted by adaptive speed
+    pub fn speed_level(&self) -> u32 {
+        self.speed_level
+    }
+    /// State of adaptive speed, None unless it is switched on
+    pub fn adaptive_speed(&self) -> Option<AdaptiveSpeed> {
+        self.adaptive_speed
+    }
+    /// Upcoming pills shown to the player, next pill first
+    pub fn queue(&self) -> &[PillShape] {
+        &self.queue[..self.peek_count]
+    }
+    pub fn hold(&self) -> Option<PillShape> {
+        self.hold
+    }
+    /// Consecutive pills that have each cleared at least one pattern
+    pub fn current_chain(&self) -> u32 {
+        self.chain
+    }
+    /// Garbage sent by opponents that is yet to land in the bottle
+    pub fn pending_garbage(&self) -> impl Iterator<Item = VirusColor> + '_ {
+        self.pending_garbage.iter().map_while(|c| *c)
+    }
+}
+
+/// Accumulated over a single level, reset when the next level starts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GameStats {
+    pills: u32,
+    viruses_cleared: u32,
+    max_combo: u32,
+    time: Duration,
+    score: u32,
+    speed_bonus: u32,
+}
+
+impl GameStats {
+    pub fn pills(&self) -> u32 {
+        self.pills
+    }
+    pub fn viruses_cleared(&self) -> u32 {
+        self.viruses_cleared
+    }
+    /// Most patterns destroyed in a single chain
+    pub fn max_combo(&self) -> u32 {
+        self.max_combo
+    }
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+    /// Part of the score earned by playing above low speed
+    pub fn speed_bonus(&self) -> u32 {
+        self.speed_bonus
+    }
+
+    pub fn elapse(&mut self, delta: Duration) {
+        self.time += delta;
+    }
+
+    pub fn spawn_pill(&mut self) {
+        self.pills += 1;
+    }
+
+    pub fn destroy(&mut self, viruses: u32, combo: u32) {
+        self.viruses_cleared += viruses;
+        self.max_combo = self.max_combo.max(combo);
+    }
+
+    pub fn add_score(&mut self, score: u32, speed_bonus: u32) {
+        self.score += score;
+        self.speed_bonus += speed_bonus;
+    }
+}
@@ -0,0 +1,2822 @@
+use crate::game::adaptive::{AdaptiveSpeed, NEAR_TOP_OUT_ROWS};
+use crate::game::block::Block;
+use crate::game::bottle::{BottleSize, SendGarbage, VirusCounts};
+use crate::game::command::GameCommand;
+use crate::game::event::{ColoredBlock, GameEvent};
+
+use crate::game::pill::{PillEffect, PillShape, VirusColor, Vitamins};
+use crate::game::random::{viruses_of_level, BottleSeed, GameRandom, PEEK_SIZE};
+use crate::game::rules::GarbageStyle;
+use crate::game::ruleset::{Features, RuleSet};
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+use crate::game::metrics::{GameMetrics, GameStats};
+use crate::game::modifier::GameplayModifiers;
+
+#[cfg(not(test))]
+use crate::game::bottle::Bottle;
+#[cfg(test)]
+use crate::game::tests::MockBottle as Bottle;
+
+pub mod adaptive;
+pub mod block;
+pub mod bottle;
+pub mod command;
+pub mod eval;
+pub mod event;
+pub mod geometry;
+pub mod metrics;
+pub mod modifier;
+pub mod pill;
+pub mod random;
+pub mod rules;
+pub mod ruleset;
+pub mod seed;
+
+/// Pills that can be rewound in practice
+pub const REWIND_SNAPSHOTS: usize = 10;
+/// Save states that can be loaded in practice
+pub const SAVE_STATE_SLOTS: usize = 3;
+/// Rows cleared from the top of the bottle when zen forgives a top out
+pub const ZEN_TOP_OUT_ROWS: u32 = 4;
+/// Points taken off the score when zen forgives a top out
+pub const ZEN_TOP_OUT_PENALTY: u32 = 1000;
+/// Time between each crawl of the viruses in hard mode
+pub const VIRUS_CRAWL_PERIOD: Duration = Duration::from_secs(4);
+/// Points for each row a pill is soft dropped, with the drop score rule
+pub const SOFT_DROP_SCORE: u32 = 1;
+/// Points for each row a pill is hard dropped, with the drop score rule
+pub const HARD_DROP_SCORE: u32 = 2;
+/// Most points a single pill can score by being dropped
+pub const MAX_DROP_SCORE: u32 = 40;
+
+const SOFT_DROP_STEP_FACTOR: u32 = 20;
+const SOFT_DROP_SPAWN_FACTOR: u32 = 10;
+const GARBAGE_DROP_DURATION: Duration = Duration::from_millis(200);
+const MIN_SPAWN_DELAY: Duration = Duration::from_millis(500);
+const LOCK_DURATION: Duration = Duration::from_millis(500);
+const SOFT_DROP_LOCK_DURATION: Duration = Duration::from_millis(300 / 2);
+const MAX_LOCK_PLACEMENTS: u32 = 15;
+const PILLS_PER_SPEED_LEVEL: usize = 10;
+pub const MAX_SCORE: u32 = 9999999;
+pub const MAX_SPEED_LEVEL: u32 = GameSpeed::MAX_LEVEL as u32;
+
+const SPEED_TABLE: [Duration; 81] = [
+    Duration::from_nanos(1166666667),
+    Duration::from_nanos(1133333333),
+    Duration::from_nanos(1100000000),
+    Duration::from_nanos(1066666667),
+    Duration::from_nanos(1033333333),
+    Duration::from_nanos(1000000000),
+    Duration::from_nanos(966666667),
+    Duration::from_nanos(933333333),
+    Duration::from_nanos(900000000),
+    Duration::from_nanos(866666667),
+    Duration::from_nanos(833333333),
+    Duration::from_nanos(800000000),
+    Duration::from_nanos(766666667),
+    Duration::from_nanos(733333333),
+    Duration::from_nanos(700000000),
+    Duration::from_nanos(666666667),
+    Duration::from_nanos(633333333),
+    Duration::from_nanos(600000000),
+    Duration::from_nanos(566666667),
+    Duration::from_nanos(533333333),
+    Duration::from_nanos(500000000),
+    Duration::from_nanos(466666667),
+    Duration::from_nanos(433333333),
+    Duration::from_nanos(400000000),
+    Duration::from_nanos(366666667),
+    Duration::from_nanos(333333333),
+    Duration::from_nanos(316666667),
+    Duration::from_nanos(300000000),
+    Duration::from_nanos(283333333),
+    Duration::from_nanos(266666667),
+    Duration::from_nanos(250000000),
+    Duration::from_nanos(233333333),
+    Duration::from_nanos(216666667),
+    Duration::from_nanos(200000000),
+    Duration::from_nanos(183333333),
+    Duration::from_nanos(166666667),
+    Duration::from_nanos(166666667),
+    Duration::from_nanos(150000000),
+    Duration::from_nanos(150000000),
+    Duration::from_nanos(133333333),
+    Duration::from_nanos(133333333),
+    Duration::from_nanos(116666667),
+    Duration::from_nanos(116666667),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(100000000),
+    Duration::from_nanos(83333333),
+    Duration::from_nanos(83333333),
+    Duration::from_nanos(83333333),
+    Duration::from_nanos(83333333),
+    Duration::from_nanos(83333333),
+    Duration::from_nanos(66666667),
+    Duration::from_nanos(66666667),
+    Duration::from_nanos(66666667),
+    Duration::from_nanos(66666667),
+    Duration::from_nanos(66666667),
+    Duration::from_nanos(50000000),
+    Duration::from_nanos(50000000),
+    Duration::from_nanos(50000000),
+    Duration::from_nanos(50000000),
+    Duration::from_nanos(50000000),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(33333333),
+    Duration::from_nanos(16666667),
+];
+
+const BASE_SCORE_LOW: u32 = 100;
+const BASE_SCORE_MEDIUM: u32 = 200;
+const BASE_SCORE_HIGH: u32 = 300;
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, strum::IntoStaticStr, strum::EnumIter, strum::EnumString,
+)]
+pub enum GameSpeed {
+    #[strum(serialize = "low")]
+    Low = 0,
+    #[strum(serialize = "medium")]
+    Medium = 1,
+    #[strum(serialize = "high")]
+    High = 2,
+}
+
+impl GameSpeed {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+}
+
+impl GameSpeed {
+    const MAX_LEVEL: usize = 49;
+
+    fn min_drop_duration(&self) -> Duration {
+        self.duration_of_level(Self::MAX_LEVEL)
+    }
+
+    fn duration_of_level(&self, speed_level: usize) -> Duration {
+        SPEED_TABLE[self.speed_index(speed_level)]
+    }
+
+    fn speed_index(&self, speed_level: usize) -> usize {
+        let base = match self {
+            GameSpeed::Low => 15,
+            GameSpeed::Medium => 25,
+            GameSpeed::High => 31,
+        };
+        base + speed_level.min(Self::MAX_LEVEL)
+    }
+
+    fn base_score(&self) -> u32 {
+        match self {
+            GameSpeed::Low => BASE_SCORE_LOW,
+            GameSpeed::Medium => BASE_SCORE_MEDIUM,
+            GameSpeed::High => BASE_SCORE_HIGH,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameState {
+    Spawn(Duration),
+    SpawnHold(Option<PillShape>),
+    Fall(Duration),
+    Lock(Duration),
+    /// check the bottle for patterns to destroy
+    Pattern(Combo),
+    /// destroy marked patterns
+    Destroy(Vec<ColoredBlock>, Combo),
+    DropGarbage(Duration, Combo),
+    GameOver,
+    LevelComplete,
+}
+
+impl GameState {
+    const NEW_LOCK: Self = Self::Lock(Duration::ZERO);
+    const LOCK_NOW: Self = Self::Lock(LOCK_DURATION);
+    const NEW_FALL: Self = Self::Fall(Duration::ZERO);
+    const NEW_SPAWN: Self = Self::Spawn(Duration::ZERO);
+    const NEW_PATTERN: Self = Self::Pattern(Combo::empty());
+
+    fn drop_garbage(combo: Combo) -> Self {
+        Self::DropGarbage(Duration::ZERO, combo)
+    }
+
+    fn is_end(&self) -> bool {
+        matches!(self, Self::GameOver | Self::LevelComplete)
+    }
+
+    /// Patterns of a locked pill are being cleared
+    fn is_resolving(&self) -> bool {
+        matches!(
+            self,
+            Self::Pattern(_) | Self::Destroy(_, _) | Self::DropGarbage(_, _)
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct HoldState {
+    shape: PillShape,
+    locked: bool,
+}
+
+impl HoldState {
+    pub fn locked(shape: PillShape) -> Self {
+        Self {
+            shape,
+            locked: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Combo {
+    patterns: Vec<VirusColor>,
+    viruses: u32,
+}
+
+impl Combo {
+    pub fn new(patterns: Vec<VirusColor>, viruses: u32) -> Self {
+        Self { patterns, viruses }
+    }
+
+    const fn empty() -> Self {
+        Self {
+            patterns: vec![],
+            viruses: 0,
+        }
+    }
+
+    fn into_updated(mut self, patterns: Vec<VirusColor>, viruses: u32) -> Self {
+        for color in patterns {
+            self.patterns.push(color);
+        }
+        self.viruses += viruses;
+        self
+    }
+
+    fn is_combo(&self) -> bool {
+        self.patterns.len() > 1
+    }
+
+    fn score(&self, speed: GameSpeed) -> u32 {
+        if self.viruses == 0 {
+            return 0;
+        }
+        // |NUMBER OF VIRUSES |   LOW   |   MED   |   HIGH   |
+        // |   ELIMINATED     |  SPEED  |  SPEED  |  SPEED   |
+        // |¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯|¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯¯|
+        // |	    1		  |   100   |   200   |   300    |
+        // |        2         |   200   |   400   |   600    |
+        // |        3         |   400   |   800   |  1200    |
+        // |        4         |   800   |  1600   |  2400    |
+        // |        5         |  1600   |  3200   |  4800    |
+        // |        6         |  3200   |  6400   |  9600    |
+        let base_score = speed.base_score();
+        (0..self.viruses).map(|i| base_score * 2_u32.pow(i)).sum()
+    }
+
+    fn garbage(&self) -> SendGarbage {
+        if self.is_combo() {
+            self.patterns.clone()
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// Pill, queue & hold of one player of a co-op bottle, swapped into the game to apply to them
+#[derive(Clone)]
+struct Hand {
+    state: GameState,
+    random: GameRandom,
+    soft_drop: bool,
+    hard_dropped: bool,
+    drop_score: u32,
+    hold: Option<HoldState>,
+}
+
+impl Hand {
+    fn new(random: GameRandom) -> Self {
+        Self {
+            state: GameState::NEW_SPAWN,
+            random,
+            soft_drop: false,
+            hard_dropped: false,
+            drop_score: 0,
+            hold: None,
+        }
+    }
+}
+
+/// The bottle the next level starts with, shown while the player is between levels
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NextLevelPreview {
+    pub virus_level: u32,
+    pub speed: GameSpeed,
+    pub bottle_size: BottleSize,
+    pub viruses: Vec<ColoredBlock>,
+}
+
+/// State restored by a rewind, captured as each pill spawns
+#[derive(Clone)]
+struct GameSnapshot {
+    bottle: Bottle,
+    random: GameRandom,
+    virus_counts: VirusCounts,
+    score: u32,
+    stats: GameStats,
+    chain: u32,
+    total_pills: usize,
+    hold: Option<HoldState>,
+    adaptive: Option<AdaptiveSpeed>,
+}
+
+/// Everything needed to retry from a point mid-level, saved to a slot in practice
+#[derive(Clone)]
+struct SaveState {
+    snapshot: GameSnapshot,
+    virus_level: u32,
+    level_count: u32,
+    state: GameState,
+    resolving_lock: bool,
+    garbage_buffer: Vec<SendGarbage>,
+}
+
+pub struct Game {
+    player: u32,
+    virus_level: u32,
+    level_count: u32,
+    speed: GameSpeed,
+    random: GameRandom,
+    events: Vec<GameEvent>,
+    bottle: Bottle,
+    virus_counts: VirusCounts,
+    state: GameState,
+    score: u32,
+    stats: GameStats,
+    chain: u32,
+    /// the pattern check following a lock is in progress, which may extend or break the chain
+    resolving_lock: bool,
+    total_pills: usize,
+    /// speed levels added with the speed up key, kept through every level of the game
+    speed_boost: usize,
+    /// None unless the speed adapts to how well the player is doing
+    adaptive: Option<AdaptiveSpeed>,
+    soft_drop: bool,
+    hard_dropped: bool,
+    /// points for the rows the current pill has been dropped, scored as it locks
+    drop_score: u32,
+    hold: Option<HoldState>,
+    garbage_buffer: Vec<SendGarbage>,
+    /// outgoing garbage offsets buffered incoming garbage before it is sent
+    garbage_cancellation: bool,
+    garbage_style: GarbageStyle,
+    rule_set: RuleSet,
+    features: Features,
+    peek_count: usize,
+    /// viruses in every bottle, None for the number of the virus level
+    exact_viruses: Option<u32>,
+    bottle_size: BottleSize,
+    /// the other player of a co-op bottle, None for a game of one player
+    partner: Option<Hand>,
+    /// player whose pill, queue & hold are swapped into the game, 1 for the partner
+    hand: usize,
+    /// None when rewind is disabled
+    snapshots: Option<VecDeque<GameSnapshot>>,
+    save_states: [Option<SaveState>; SAVE_STATE_SLOTS],
+    rewound: bool,
+    /// the first bottle was painted in the editor rather than seeded
+    custom_layout: bool,
+    modifiers: GameplayModifiers,
+    /// time since the viruses last crawled, None unless they crawl
+    virus_crawl: Option<Duration>,
+    /// None unless commands are being recorded
+    command_log: Option<Vec<GameCommand>>,
+}
+
+impl Game {
+    pub fn new(
+        player: u32,
+        virus_level: u32,
+        speed: GameSpeed,
+        mut random: GameRandom,
+    ) -> Result<Self, String> {
+        let bottle = Bottle::from_seed(random.bottle_seed(virus_level)?);
+        Ok(Self::from_bottle(
+            player,
+            virus_level,
+            speed,
+            random,
+            bottle,
+        ))
+    }
+
+    pub fn from_bottle(
+        player: u32,
+        virus_level: u32,
+        speed: GameSpeed,
+        random: GameRandom,
+        bottle: Bottle,
+    ) -> Self {
+        Self {
+            player,
+            virus_level,
+            level_count: 0,
+            speed,
+            random,
+            events: vec![],
+            virus_counts: bottle.virus_counts(),
+            bottle,
+            state: GameState::NEW_SPAWN,
+            score: 0,
+            stats: GameStats::default(),
+            chain: 0,
+            resolving_lock: false,
+            total_pills: 0,
+            speed_boost: 0,
+            adaptive: None,
+            soft_drop: false,
+            hard_dropped: false,
+            drop_score: 0,
+            hold: None,
+            garbage_buffer: vec![],
+            garbage_cancellation: false,
+            garbage_style: GarbageStyle::Scattered,
+            rule_set: RuleSet::Modern,
+            features: Features::ALL,
+            peek_count: PEEK_SIZE,
+            exact_viruses: None,
+            bottle_size: BottleSize::CLASSIC,
+            partner: None,
+            hand: 0,
+            snapshots: None,
+            save_states: Default::default(),
+            rewound: false,
+            custom_layout: false,
+            modifiers: vec![],
+            virus_crawl: None,
+            command_log: None,
+        }
+    }
+
+    pub fn with_modifiers(mut self, modifiers: GameplayModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn with_garbage_cancellation(mut self, garbage_cancellation: bool) -> Self {
+        self.garbage_cancellation = garbage_cancellation;
+        self
+    }
+
+    pub fn with_garbage_style(mut self, garbage_style: GarbageStyle) -> Self {
+        self.garbage_style = garbage_style;
+        self
+    }
+
+    pub fn with_rule_set(mut self, rule_set: RuleSet) -> Self {
+        self.rule_set = rule_set;
+        self
+    }
+
+    pub fn with_features(mut self, features: Features) -> Self {
+        self.features = features;
+        self.bottle.set_ghost(features.contains(Features::GHOST));
+        self
+    }
+
+    /// Upcoming pills shown in the metrics, up to the whole queue
+    pub fn with_peek_count(mut self, peek_count: usize) -> Self {
+        self.peek_count = peek_count.min(PEEK_SIZE);
+        self
+    }
+
+    /// Every bottle has an exact number of viruses whatever the virus level,
+    /// the first bottle is reseeded to match
+    pub fn with_exact_viruses(mut self, exact_viruses: Option<u32>) -> Result<Self, String> {
+        self.exact_viruses = exact_viruses;
+        if exact_viruses.is_some() {
+            self.reset_bottle(self.bottle_viruses(self.virus_level))?;
+        }
+        Ok(self)
+    }
+
+    /// Every bottle is of some size, the first bottle is reseeded to match
+    pub fn with_bottle_size(mut self, bottle_size: BottleSize) -> Result<Self, String> {
+        if bottle_size != self.bottle_size {
+            self.bottle_size = bottle_size;
+            self.reset_bottle(self.bottle_viruses(self.virus_level))?;
+        }
+        Ok(self)
+    }
+
+    /// The first bottle is a layout of viruses e.g. from the editor, later bottles are seeded at
+    /// its size
+    pub fn with_layout(mut self, layout: BottleSeed) -> Self {
+        self.bottle_size = layout.size();
+        self.custom_layout = true;
+        self.events.clear();
+        self.reset_to_seed(layout);
+        self
+    }
+
+    /// A second player shares the bottle with their own pill, queue & hold, see `partner`
+    pub fn with_partner(mut self, random: GameRandom) -> Self {
+        self.partner = Some(Hand::new(random));
+        self.bottle.set_hands(2);
+        self
+    }
+
+    /// The speed level ramps up faster while the player is doing well & backs off after a
+    /// near top out, see [AdaptiveSpeed]
+    pub fn with_adaptive_speed(mut self, adaptive_speed: bool) -> Self {
+        self.adaptive = adaptive_speed.then(AdaptiveSpeed::default);
+        self
+    }
+
+    /// Hard mode, viruses crawl into neighbouring empty blocks every so often
+    pub fn with_crawling_viruses(mut self, crawling_viruses: bool) -> Self {
+        self.virus_crawl = crawling_viruses.then_some(Duration::ZERO);
+        self
+    }
+
+    pub fn with_rewind(mut self, rewind: bool) -> Self {
+        self.snapshots = rewind.then(|| VecDeque::with_capacity(REWIND_SNAPSHOTS + 1));
+        self
+    }
+
+    /// Records every applied command, to be replayed into a new game from the same seed
+    pub fn with_command_log(mut self, command_log: bool) -> Self {
+        self.command_log = command_log.then(Vec::new);
+        self
+    }
+
+    pub fn command_log(&self) -> &[GameCommand] {
+        self.command_log.as_deref().unwrap_or_default()
+    }
+
+    /// Applies a command, the only way that a game changes once it is created.
+    /// Returns false when the command had no effect e.g. moving into a wall.
+    pub fn apply(&mut self, command: GameCommand) -> Result<bool, String> {
+        if let Some(command_log) = self.command_log.as_mut() {
+            command_log.push(command.clone());
+        }
+        let applied = match command {
+            GameCommand::Left => self.apply_move(
+                |bottle| bottle.left(),
+                GameEvent::Move {
+                    player: self.player,
+                },
+            ),
+            GameCommand::Right => self.apply_move(
+                |bottle| bottle.right(),
+                GameEvent::Move {
+                    player: self.player,
+                },
+            ),
+            GameCommand::Rotate { clockwise } => self.apply_move(
+                |bottle| bottle.rotate(clockwise),
+                GameEvent::Rotate {
+                    player: self.player,
+                },
+            ),
+            GameCommand::SoftDrop { soft_drop } => self.apply_soft_drop(soft_drop),
+            GameCommand::HardDrop => self.apply_hard_drop(),
+            GameCommand::PreviewHardDrop => self.apply_preview_hard_drop(),
+            GameCommand::Hold => self.apply_hold(),
+            GameCommand::SpeedUp => self.apply_speed_up(),
+            GameCommand::SendGarbage { garbage } => {
+                self.garbage_buffer.push(garbage);
+                true
+            }
+            GameCommand::Update { delta } => {
+                self.apply_update(delta);
+                true
+            }
+            GameCommand::NextLevel => {
+                self.apply_next_level()?;
+                true
+            }
+            GameCommand::Overtime { virus_level } => {
+                self.reset_bottle(viruses_of_level(virus_level))?;
+                true
+            }
+            GameCommand::Rewind => self.apply_rewind(),
+            GameCommand::SwapHands => self.swap_hands(),
+            GameCommand::SaveState { slot } => self.apply_save_state(slot),
+            GameCommand::LoadState { slot } => self.apply_load_state(slot),
+        };
+        Ok(applied)
+    }
+
+    fn apply_infallible(&mut self, command: GameCommand) -> bool {
+        self.apply(command)
+            .expect("only the next level & overtime commands can fail")
+    }
+
+    pub fn next_level(&mut self) -> Result<(), String> {
+        self.apply(GameCommand::NextLevel).map(|_| ())
+    }
+
+    fn apply_next_level(&mut self) -> Result<(), String> {
+        assert_eq!(self.state, GameState::LevelComplete);
+        self.virus_level += 1;
+        self.level_count += 1;
+        self.reset_bottle(self.bottle_viruses(self.virus_level))
+    }
+
+    /// Viruses in each new bottle of a virus level
+    fn bottle_viruses(&self, virus_level: u32) -> u32 {
+        self.exact_viruses
+            .unwrap_or_else(|| viruses_of_level(virus_level))
+    }
+
+    /// The bottle of the next level, seeded from a copy of the random so it is the same bottle
+    /// the next level deals
+    pub fn next_level_preview(&self) -> Result<NextLevelPreview, String> {
+        let virus_level = self.virus_level + 1;
+        let seed = self
+            .random
+            .clone()
+            .bottle_seed_of_viruses(self.bottle_size, self.bottle_viruses(virus_level))?;
+        Ok(NextLevelPreview {
+            virus_level,
+            speed: self.speed,
+            bottle_size: self.bottle_size,
+            viruses: seed.viruses(),
+        })
+    }
+
+    /// Sudden death, a fresh bottle of some virus level from any state, even game over
+    pub fn overtime(&mut self, virus_level: u32) -> Result<(), String> {
+        self.apply(GameCommand::Overtime { virus_level }).map(|_| ())
+    }
+
+    fn reset_bottle(&mut self, viruses: u32) -> Result<(), String> {
+        self.events.clear();
+        if self.hand != 0 {
+            self.swap_hands();
+        }
+        let seed = self.random.bottle_seed_of_viruses(self.bottle_size, viruses)?;
+        self.reset_to_seed(seed);
+        Ok(())
+    }
+
+    /// Starts a new bottle from the first hand
+    fn reset_to_seed(&mut self, seed: BottleSeed) {
+        self.bottle = Bottle::from_seed(seed);
+        if let Some(partner) = self.partner.as_mut() {
+            let random = partner.random.clone();
+            *partner = Hand::new(random);
+            self.bottle.set_hands(2);
+        }
+        self.bottle.set_ghost(self.features.contains(Features::GHOST));
+        self.virus_counts = self.bottle.virus_counts();
+        self.state = GameState::NEW_SPAWN;
+        self.stats = GameStats::default();
+        self.chain = 0;
+        self.resolving_lock = false;
+        self.total_pills = 0;
+        self.soft_drop = false;
+        self.hard_dropped = false;
+        self.drop_score = 0;
+        self.hold = None;
+        self.garbage_buffer.clear();
+        if let Some(virus_crawl) = self.virus_crawl.as_mut() {
+            *virus_crawl = Duration::ZERO;
+        }
+        if let Some(snapshots) = self.snapshots.as_mut() {
+            // cannot rewind into the previous level
+            snapshots.clear();
+        }
+    }
+
+    pub fn viruses(&self) -> Vec<ColoredBlock> {
+        self.bottle.viruses()
+    }
+
+    /// Vitamins of the falling power-up pills
+    pub fn power_ups(&self) -> Vec<(Vitamins, PillEffect)> {
+        self.bottle.power_ups()
+    }
+
+    /// Viruses remaining by color, only updated when blocks are destroyed
+    pub fn virus_counts(&self) -> VirusCounts {
+        self.virus_counts
+    }
+
+    pub fn viruses_remaining(&self, color: VirusColor) -> u32 {
+        self.virus_counts[color as usize]
+    }
+
+    pub fn speed(&self) -> GameSpeed {
+        self.speed
+    }
+
+    pub fn virus_level(&self) -> u32 {
+        self.virus_level
+    }
+
+    pub fn completed_levels(&self) -> u32 {
+        self.level_count
+    }
+
+    /// Speed level of the current level, from pills spawned & speed ups
+    pub fn speed_level(&self) -> u32 {
+        let speed_level = self.total_pills / PILLS_PER_SPEED_LEVEL + self.speed_boost;
+        speed_level.min(GameSpeed::MAX_LEVEL) as u32
+    }
+
+    /// Speed level of the gravity, the speed level adjusted by adaptive speed
+    fn effective_speed_level(&self) -> u32 {
+        let speed_level = self.speed_level() as usize;
+        match self.adaptive {
+            Some(adaptive) => adaptive.speed_level(speed_level) as u32,
+            None => speed_level as u32,
+        }
+    }
+
+    pub fn metrics(&self) -> GameMetrics {
+        GameMetrics::new(
+            self.player,
+            self.virus_level,
+            self.speed,
+            self.bottle.virus_count(),
+            self.score,
+            self.random.peek(),
+            self.hold.map(|h| h.shape),
+        )
+        .with_virus_counts(self.virus_counts)
+        .with_chain(self.chain)
+        .with_peek_count(self.peek_count)
+        .with_speed_level(self.effective_speed_level())
+        .with_adaptive_speed(self.adaptive)
+        // buffered garbage is popped from the back
+        .with_pending_garbage(self.garbage_buffer.iter().rev().flatten().copied())
+    }
+
+    /// Stats for the current level
+    pub fn stats(&self) -> GameStats {
+        self.stats
+    }
+
+    /// This game has been rewound at least once
+    pub fn is_rewound(&self) -> bool {
+        self.rewound
+    }
+
+    /// This game started on a layout of the editor
+    pub fn is_custom_layout(&self) -> bool {
+        self.custom_layout
+    }
+
+    /// Restores the spawn of the previous pill, false if there is nothing to rewind
+    pub fn rewind(&mut self) -> bool {
+        self.apply_infallible(GameCommand::Rewind)
+    }
+
+    fn apply_rewind(&mut self) -> bool {
+        if matches!(self.state, GameState::GameOver | GameState::LevelComplete) {
+            return false;
+        }
+        let snapshots = match self.snapshots.as_mut() {
+            Some(snapshots) if snapshots.len() > 1 => snapshots,
+            _ => return false,
+        };
+        // the latest snapshot is of the current pill
+        snapshots.pop_back();
+        let snapshot = snapshots.pop_back().unwrap();
+
+        self.restore(snapshot);
+        self.state = GameState::NEW_SPAWN;
+        self.resolving_lock = false;
+        self.garbage_buffer.clear();
+        self.events.push(GameEvent::Rewind {
+            player: self.player,
+        });
+        true
+    }
+
+    /// Saves the current game to a slot, false if save states are disabled
+    pub fn save_state(&mut self, slot: usize) -> bool {
+        self.apply_infallible(GameCommand::SaveState { slot })
+    }
+
+    fn apply_save_state(&mut self, slot: usize) -> bool {
+        if self.snapshots.is_none()
+            || slot >= SAVE_STATE_SLOTS
+            || matches!(self.state, GameState::GameOver | GameState::LevelComplete)
+        {
+            return false;
+        }
+        self.save_states[slot] = Some(SaveState {
+            snapshot: self.snapshot(),
+            virus_level: self.virus_level,
+            level_count: self.level_count,
+            state: self.state.clone(),
+            resolving_lock: self.resolving_lock,
+            garbage_buffer: self.garbage_buffer.clone(),
+        });
+        self.events.push(GameEvent::SaveState {
+            player: self.player,
+            slot,
+        });
+        true
+    }
+
+    /// Restores the game saved to a slot, false if the slot is empty
+    pub fn load_state(&mut self, slot: usize) -> bool {
+        self.apply_infallible(GameCommand::LoadState { slot })
+    }
+
+    fn apply_load_state(&mut self, slot: usize) -> bool {
+        if matches!(self.state, GameState::GameOver | GameState::LevelComplete) {
+            return false;
+        }
+        let save_state = match self.save_states.get(slot) {
+            Some(Some(save_state)) => save_state.clone(),
+            _ => return false,
+        };
+
+        self.restore(save_state.snapshot);
+        self.virus_level = save_state.virus_level;
+        self.level_count = save_state.level_count;
+        self.state = save_state.state;
+        self.resolving_lock = save_state.resolving_lock;
+        self.garbage_buffer = save_state.garbage_buffer;
+        if let Some(snapshots) = self.snapshots.as_mut() {
+            // cannot rewind to before the save state was loaded
+            snapshots.clear();
+        }
+        self.events.push(GameEvent::LoadState {
+            player: self.player,
+            slot,
+        });
+        true
+    }
+
+    fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            bottle: self.bottle.clone(),
+            random: self.random.clone(),
+            virus_counts: self.virus_counts,
+            score: self.score,
+            stats: self.stats,
+            chain: self.chain,
+            total_pills: self.total_pills,
+            hold: self.hold,
+            adaptive: self.adaptive,
+        }
+    }
+
+    fn restore(&mut self, snapshot: GameSnapshot) {
+        self.bottle = snapshot.bottle;
+        self.random = snapshot.random;
+        self.virus_counts = snapshot.virus_counts;
+        self.score = snapshot.score;
+        self.stats = snapshot.stats;
+        self.chain = snapshot.chain;
+        self.total_pills = snapshot.total_pills;
+        self.hold = snapshot.hold;
+        self.adaptive = snapshot.adaptive;
+        self.soft_drop = false;
+        self.hard_dropped = false;
+        self.drop_score = 0;
+        self.rewound = true;
+    }
+
+    fn take_snapshot(&mut self) {
+        if self.snapshots.is_none() {
+            return;
+        }
+        let snapshot = self.snapshot();
+        if let Some(snapshots) = self.snapshots.as_mut() {
+            // keep one extra for the current pill
+            if snapshots.len() > REWIND_SNAPSHOTS {
+                snapshots.pop_front();
+            }
+            snapshots.push_back(snapshot);
+        }
+    }
+
+    pub fn row(&self, y: u32) -> &[Block] {
+        self.bottle.row(y)
+    }
+
+    /// Applies input to the pill of the other player of a co-op bottle,
+    /// nothing is applied in a game of one player
+    pub fn partner<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        if self.is_coop() {
+            self.apply_infallible(GameCommand::SwapHands);
+            f(self);
+            self.apply_infallible(GameCommand::SwapHands);
+        }
+    }
+
+    pub fn is_coop(&self) -> bool {
+        self.partner.is_some()
+    }
+
+    fn swap_hands(&mut self) -> bool {
+        let partner = match self.partner.as_mut() {
+            None => return false,
+            Some(partner) => partner,
+        };
+        std::mem::swap(&mut self.state, &mut partner.state);
+        std::mem::swap(&mut self.random, &mut partner.random);
+        std::mem::swap(&mut self.soft_drop, &mut partner.soft_drop);
+        std::mem::swap(&mut self.hard_dropped, &mut partner.hard_dropped);
+        std::mem::swap(&mut self.drop_score, &mut partner.drop_score);
+        std::mem::swap(&mut self.hold, &mut partner.hold);
+        self.hand = 1 - self.hand;
+        self.bottle.select_hand(self.hand);
+        true
+    }
+
+    pub fn bottle_size(&self) -> BottleSize {
+        self.bottle_size
+    }
+
+    pub fn hold(&mut self) {
+        self.apply_infallible(GameCommand::Hold);
+    }
+
+    pub fn speed_up(&mut self) {
+        self.apply_infallible(GameCommand::SpeedUp);
+    }
+
+    fn apply_speed_up(&mut self) -> bool {
+        if matches!(self.state, GameState::GameOver | GameState::LevelComplete)
+            || self.speed_level() as usize >= GameSpeed::MAX_LEVEL
+        {
+            return false;
+        }
+        self.speed_boost += 1;
+        self.events.push(GameEvent::SpeedLevelUp {
+            player: self.player,
+            is_boost: true,
+        });
+        true
+    }
+
+    fn apply_hold(&mut self) -> bool {
+        if !self.features.contains(Features::HOLD) {
+            return false;
+        }
+        if matches!(self.hold, Some(HoldState { locked: true, .. })) {
+            // hold is blocked
+            return false;
+        }
+
+        let (held_shape, vitamins) = match self.bottle.hold() {
+            None => return false,
+            Some(hold) => hold,
+        };
+
+        let released = self.hold.map(|h| h.shape);
+        // the rows the held pill was dropped are not scored
+        self.drop_score = 0;
+        self.state = GameState::SpawnHold(released);
+        self.hold = Some(HoldState::locked(held_shape));
+        self.events.push(GameEvent::Hold {
+            player: self.player,
+            shape: held_shape,
+            vitamins,
+            released,
+        });
+        true
+    }
+
+    pub fn set_soft_drop(&mut self, soft_drop: bool) {
+        self.apply_infallible(GameCommand::SoftDrop { soft_drop });
+    }
+
+    fn apply_soft_drop(&mut self, soft_drop: bool) -> bool {
+        self.soft_drop = soft_drop;
+        if soft_drop {
+            self.events.push(GameEvent::SoftDrop {
+                player: self.player,
+            });
+        }
+        true
+    }
+
+    pub fn hard_drop(&mut self) {
+        self.apply_infallible(GameCommand::HardDrop);
+    }
+
+    fn apply_hard_drop(&mut self) -> bool {
+        if !self.features.contains(Features::HARD_DROP) {
+            return false;
+        }
+        match self.bottle.hard_drop() {
+            Some((dropped_rows, vitamins)) => {
+                self.state = GameState::LOCK_NOW;
+                self.hard_dropped = true;
+                self.drop_score += HARD_DROP_SCORE * dropped_rows;
+                self.events.push(GameEvent::HardDrop {
+                    player: self.player,
+                    dropped_rows,
+                    vitamins,
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// First stage of a two-stage hard drop, shows where the pill would land without committing
+    pub fn preview_hard_drop(&mut self) {
+        self.apply_infallible(GameCommand::PreviewHardDrop);
+    }
+
+    fn apply_preview_hard_drop(&mut self) -> bool {
+        if !self.features.contains(Features::HARD_DROP) {
+            return false;
+        }
+        match self.bottle.drop_path() {
+            Some((vitamins, ghost)) => {
+                self.events.push(GameEvent::HardDropPreview {
+                    player: self.player,
+                    vitamins,
+                    ghost,
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn left(&mut self) {
+        self.apply_infallible(GameCommand::Left);
+    }
+
+    pub fn right(&mut self) {
+        self.apply_infallible(GameCommand::Right);
+    }
+
+    pub fn rotate(&mut self, clockwise: bool) {
+        self.apply_infallible(GameCommand::Rotate { clockwise });
+    }
+
+    fn apply_move<F>(&mut self, f: F, event: GameEvent) -> bool
+    where
+        F: FnMut(&mut Bottle) -> bool,
+    {
+        let moved = self.with_checking_lock(f);
+        if moved {
+            self.events.push(event);
+        }
+        moved
+    }
+
+    pub fn send_garbage(&mut self, garbage: SendGarbage) {
+        self.apply_infallible(GameCommand::SendGarbage { garbage });
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        self.apply_infallible(GameCommand::Update { delta });
+    }
+
+    fn apply_update(&mut self, delta: Duration) {
+        if !self.state.is_end() {
+            self.stats.elapse(delta);
+        }
+        self.crawl_viruses(delta);
+        let partner_state = match self.partner.as_ref() {
+            None => {
+                self.step(delta);
+                return;
+            }
+            Some(partner) => partner.state.clone(),
+        };
+
+        // the bottle is shared so one player's pill waits while the other's lock is resolved,
+        // & the game ends for both as soon as it ends for either
+        if !partner_state.is_resolving() && !partner_state.is_end() {
+            self.step(delta);
+        }
+        if !self.state.is_resolving() && !self.state.is_end() {
+            self.swap_hands();
+            self.step(delta);
+            self.swap_hands();
+        }
+        let partner = self.partner.as_mut().unwrap();
+        if self.state.is_end() {
+            partner.state = self.state.clone();
+        } else if partner.state.is_end() {
+            self.state = partner.state.clone();
+        }
+    }
+
+    /// Viruses crawl while pills fall, never while the patterns of a lock are being cleared
+    fn crawl_viruses(&mut self, delta: Duration) {
+        let partner_resolving = self
+            .partner
+            .as_ref()
+            .is_some_and(|partner| partner.state.is_resolving());
+        let falling = matches!(self.state, GameState::Fall(_) | GameState::Lock(_));
+        let virus_crawl = match self.virus_crawl.as_mut() {
+            Some(virus_crawl) if falling && !partner_resolving => virus_crawl,
+            _ => return,
+        };
+        *virus_crawl += delta;
+        if *virus_crawl < VIRUS_CRAWL_PERIOD {
+            return;
+        }
+        *virus_crawl = Duration::ZERO;
+
+        let crawls = self.bottle.crawl_viruses(self.random.crawl_rng());
+        if !crawls.is_empty() {
+            self.events.push(GameEvent::VirusesCrawled {
+                player: self.player,
+                crawls,
+            });
+        }
+    }
+
+    /// Steps the state machine of the pill that is swapped in
+    fn step(&mut self, delta: Duration) {
+        self.state = match &self.state {
+            GameState::Spawn(duration) => self.next_spawn(*duration + delta),
+            GameState::SpawnHold(Some(shape)) => self.spawn_shape(*shape, true),
+            GameState::SpawnHold(None) => {
+                let shape = self.next_pill();
+                self.spawn_shape(shape, false)
+            }
+            GameState::Fall(duration) => self.next_fall(*duration + delta),
+            GameState::Lock(duration) => self.next_lock(*duration + delta),
+            GameState::Pattern(combo) => self.next_pattern(combo.clone()),
+            GameState::Destroy(blocks, combo) => self.next_destroy(blocks.clone(), combo.clone()),
+            GameState::GameOver => GameState::GameOver,
+            GameState::DropGarbage(duration, combo) => {
+                self.next_drop_garbage(*duration + delta, combo.clone())
+            }
+            GameState::LevelComplete => GameState::LevelComplete,
+        };
+    }
+
+    pub fn consume_events(&mut self, target: &mut Vec<GameEvent>) {
+        for event in self.events.iter().cloned() {
+            for modifier in self.modifiers.iter_mut() {
+                modifier.on_event(&event);
+            }
+            target.push(event);
+        }
+        self.events.clear();
+    }
+
+    fn next_spawn(&mut self, duration: Duration) -> GameState {
+        if let Some(next_garbage) = self.garbage_buffer.pop() {
+            let garbage = self.bottle.send_garbage(
+                next_garbage,
+                self.garbage_style,
+                self.random.garbage_rng(),
+            );
+            self.events.push(GameEvent::ReceivedGarbage {
+                player: self.player,
+                garbage,
+                style: self.garbage_style,
+            });
+            return GameState::drop_garbage(Combo::empty());
+        }
+
+        if !self.hard_dropped && duration < self.spawn_delay() {
+            return GameState::Spawn(duration);
+        }
+        self.hard_dropped = false;
+        if self.snapshots.is_some() {
+            self.take_snapshot();
+        }
+        let shape = self.next_pill();
+        self.spawn_shape(shape, false)
+    }
+
+    fn spawn_shape(&mut self, shape: PillShape, is_hold: bool) -> GameState {
+        if self.bottle.try_spawn(shape).is_some() {
+            self.events.push(GameEvent::Spawn {
+                player: self.player,
+                shape,
+                is_hold,
+            });
+            self.total_pills += 1;
+            self.stats.spawn_pill();
+            if self.total_pills % PILLS_PER_SPEED_LEVEL == 0 {
+                self.events.push(GameEvent::SpeedLevelUp {
+                    player: self.player,
+                    is_boost: false,
+                });
+            }
+            GameState::NEW_FALL
+        } else {
+            self.top_out()
+        }
+    }
+
+    /// Cannot spawn a pill, which is game over unless zen clears the top of the bottle.
+    /// The pill that could not spawn is lost
+    fn top_out(&mut self) -> GameState {
+        let blocks = if self.rule_set.forgives_top_out() {
+            self.bottle.discard_pill();
+            self.bottle.top_rows(ZEN_TOP_OUT_ROWS)
+        } else {
+            vec![]
+        };
+        if blocks.is_empty() {
+            self.events.push(GameEvent::GameOver {
+                player: self.player,
+            });
+            return GameState::GameOver;
+        }
+
+        let penalty = self.score.min(ZEN_TOP_OUT_PENALTY);
+        self.score -= penalty;
+        self.events.push(GameEvent::ToppedOut {
+            player: self.player,
+            penalty,
+        });
+        // viruses cleared this way are not scored
+        GameState::Destroy(blocks, Combo::empty())
+    }
+
+    fn next_fall(&mut self, duration: Duration) -> GameState {
+        if duration < self.step_delay() {
+            return GameState::Fall(duration);
+        }
+
+        if !self.bottle.step_down_pill() {
+            // cannot step down, start lock
+            return if self.features.contains(Features::LOCK_DELAY) {
+                GameState::NEW_LOCK
+            } else {
+                GameState::LOCK_NOW
+            };
+        }
+
+        if self.soft_drop {
+            self.drop_score += SOFT_DROP_SCORE;
+        }
+        self.events.push(GameEvent::Fall {
+            player: self.player,
+        });
+        if !self.features.contains(Features::LOCK_DELAY) {
+            // without a lock delay the pill keeps falling until a step fails
+            GameState::NEW_FALL
+        } else if self.bottle.is_collision() {
+            // step has caused a collision, start a lock
+            if self.bottle.lock_placements() >= MAX_LOCK_PLACEMENTS {
+                GameState::LOCK_NOW
+            } else {
+                GameState::NEW_LOCK
+            }
+        } else {
+            // no collisions, start a new fall step
+            GameState::NEW_FALL
+        }
+    }
+
+    fn next_lock(&mut self, duration: Duration) -> GameState {
+        let max_lock_duration = if self.soft_drop {
+            SOFT_DROP_LOCK_DURATION
+        } else {
+            LOCK_DURATION
+        };
+        if !self.hard_dropped && duration < max_lock_duration {
+            GameState::Lock(duration)
+        } else if self.bottle.is_collision() {
+            // lock timeout and still colliding so lock the piece now
+            // but before locking, need to check for a game over event.
+            let (vitamins, effect) = self.bottle.lock().expect("we must've locked");
+            if let Some(adaptive) = self.adaptive.as_mut() {
+                if !self.bottle.top_rows(NEAR_TOP_OUT_ROWS).is_empty() {
+                    adaptive.near_top_out();
+                }
+            }
+
+            // maybe unlock hold
+            if let Some(hold) = self.hold.as_mut() {
+                hold.locked = false;
+            }
+
+            let drop_score = self.score_drop();
+            self.events.push(GameEvent::Lock {
+                player: self.player,
+                vitamins,
+                hard_or_soft_dropped: self.hard_dropped || self.soft_drop,
+                drop_score,
+            });
+            self.resolving_lock = true;
+
+            if effect.is_power_up() {
+                let blocks = self.bottle.power_up(effect, vitamins);
+                self.events.push(GameEvent::PowerUp {
+                    player: self.player,
+                    effect,
+                    vitamins,
+                });
+                if !blocks.is_empty() {
+                    // power-up clears are destroyed before any patterns, which may then chain
+                    let viruses = blocks.iter().filter(|b| b.is_virus).count() as u32;
+                    return GameState::Destroy(blocks, Combo::new(vec![], viruses));
+                }
+            }
+            GameState::NEW_PATTERN
+        } else {
+            // otherwise must've moved over empty space so start a new fall
+            GameState::NEW_FALL
+        }
+    }
+
+    /// Scores the rows the locked pill was dropped, unless purist scoring is kept
+    fn score_drop(&mut self) -> u32 {
+        let drop_score = std::mem::take(&mut self.drop_score).min(MAX_DROP_SCORE);
+        if !self.features.contains(Features::DROP_SCORE) {
+            return 0;
+        }
+        self.score = (self.score + drop_score).min(MAX_SCORE);
+        self.stats.add_score(drop_score, 0);
+        drop_score
+    }
+
+    fn next_pattern(&mut self, combo: Combo) -> GameState {
+        let (blocks, patterns) = self.bottle.pattern();
+        if !blocks.is_empty() {
+            let viruses = blocks.iter().filter(|b| b.is_virus).count() as u32;
+            return GameState::Destroy(blocks, combo.into_updated(patterns, viruses));
+        }
+
+        // combo over so update the score
+        let base_score = combo.score(self.speed);
+        let combo_score = self
+            .modifiers
+            .iter()
+            .fold(base_score, |score, m| m.modify_score(score));
+        self.score = (self.score + combo_score).min(MAX_SCORE);
+        self.stats.add_score(
+            combo_score,
+            base_score.saturating_sub(combo.score(GameSpeed::Low)),
+        );
+        if combo_score > 0 {
+            self.events.push(GameEvent::ComboScore {
+                player: self.player,
+                score: combo_score,
+            });
+        }
+        if self.resolving_lock {
+            self.resolving_lock = false;
+            let cleared = !combo.patterns.is_empty();
+            self.next_chain(cleared);
+            if let Some(adaptive) = self.adaptive.as_mut() {
+                adaptive.resolve_lock(cleared, self.chain);
+            }
+        }
+
+        let garbage = self.cancel_garbage(combo.garbage());
+        if !garbage.is_empty() {
+            self.events.push(GameEvent::SendGarbage {
+                player: self.player,
+                garbage,
+            });
+        }
+
+        GameState::NEW_SPAWN
+    }
+
+    /// Cancels outgoing garbage color-for-color against buffered incoming garbage,
+    /// returns the remainder to send
+    fn cancel_garbage(&mut self, garbage: SendGarbage) -> SendGarbage {
+        if !self.garbage_cancellation {
+            return garbage;
+        }
+        let mut remainder = vec![];
+        for color in garbage {
+            // buffered garbage is popped from the back so cancel what would land first
+            let incoming = self
+                .garbage_buffer
+                .iter_mut()
+                .rev()
+                .find_map(|batch| batch.iter().position(|c| *c == color).map(|i| (batch, i)));
+            match incoming {
+                Some((batch, i)) => {
+                    batch.remove(i);
+                }
+                None => remainder.push(color),
+            }
+        }
+        self.garbage_buffer.retain(|batch| !batch.is_empty());
+        remainder
+    }
+
+    fn next_chain(&mut self, cleared: bool) {
+        if !cleared {
+            self.chain = 0;
+            return;
+        }
+        self.chain += 1;
+        if self.chain > 1 {
+            self.events.push(GameEvent::Chain {
+                player: self.player,
+                chain: self.chain,
+            });
+        }
+    }
+
+    fn next_destroy(&mut self, blocks: Vec<ColoredBlock>, combo: Combo) -> GameState {
+        self.stats.destroy(
+            blocks.iter().filter(|b| b.is_virus).count() as u32,
+            combo.patterns.len() as u32,
+        );
+        self.bottle.destroy(blocks.clone());
+        self.events.push(GameEvent::Destroy {
+            player: self.player,
+            blocks,
+            is_combo: combo.is_combo(),
+        });
+
+        let virus_counts = self.bottle.virus_counts();
+        for color in VirusColor::iter() {
+            if self.virus_counts[color as usize] > 0 && virus_counts[color as usize] == 0 {
+                self.events.push(GameEvent::VirusColorEliminated {
+                    player: self.player,
+                    color,
+                });
+            }
+        }
+        self.virus_counts = virus_counts;
+
+        if virus_counts.iter().sum::<u32>() == 0 {
+            self.events.push(GameEvent::LevelComplete {
+                player: self.player,
+            });
+            GameState::LevelComplete
+        } else {
+            GameState::drop_garbage(combo)
+        }
+    }
+
+    fn next_drop_garbage(&mut self, duration: Duration, combo: Combo) -> GameState {
+        if duration < GARBAGE_DROP_DURATION {
+            return GameState::DropGarbage(duration, combo);
+        }
+
+        if self.bottle.step_down_garbage() {
+            // garbage dropped so try again
+            self.events.push(GameEvent::DropGarbage {
+                player: self.player,
+            });
+            GameState::drop_garbage(combo)
+        } else {
+            // no garbage to drop so check for patterns
+            GameState::Pattern(combo)
+        }
+    }
+
+    fn with_checking_lock<F>(&mut self, mut f: F) -> bool
+    where
+        F: FnMut(&mut Bottle) -> bool,
+    {
+        if let GameState::Lock(lock_duration) = self.state {
+            // 1. check if the lock is already breached (we send movements before a lock update)
+            if lock_duration >= LOCK_DURATION || !self.features.contains(Features::LOCK_DELAY) {
+                return false;
+            }
+            // 2. check if this pill used all it's lock movements for this altitude
+            if self.bottle.lock_placements() >= MAX_LOCK_PLACEMENTS {
+                // the pill has already run out of lock movements, lock it asap
+                self.state = GameState::LOCK_NOW;
+                return false;
+            }
+            // 3. check the movement was blocked by the board
+            if !f(&mut self.bottle) {
+                return false;
+            }
+            if self.bottle.register_lock_placement() < MAX_LOCK_PLACEMENTS {
+                // movement is allowed under lock, lock is reset
+                self.state = GameState::NEW_FALL;
+            } else {
+                // the pill just ran out of lock movements, lock it asap
+                self.state = GameState::LOCK_NOW;
+            }
+            true
+        } else {
+            // not in lock state, pass through closure
+            f(&mut self.bottle)
+        }
+    }
+
+    fn spawn_delay(&self) -> Duration {
+        self.base_delay(SOFT_DROP_SPAWN_FACTOR).max(MIN_SPAWN_DELAY)
+    }
+
+    fn step_delay(&self) -> Duration {
+        self.modifiers
+            .iter()
+            .fold(self.base_delay(SOFT_DROP_STEP_FACTOR), |step, m| {
+                m.modify_gravity(step)
+            })
+    }
+
+    fn next_pill(&mut self) -> PillShape {
+        let shape = self.random.next_pill();
+        self.modifiers
+            .iter_mut()
+            .fold(shape, |shape, m| m.on_spawn(shape))
+    }
+
+    fn base_delay(&self, soft_drop_factor: u32) -> Duration {
+        let speed_level = self.effective_speed_level() as usize;
+        if self.soft_drop {
+            self.rule_set
+                .soft_drop_gravity(self.speed, speed_level, soft_drop_factor)
+        } else {
+            self.rule_set.gravity(self.speed, speed_level)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pill::{Garbage, Vitamins};
+    use super::random::{BottleSeed, RandomMode};
+    use super::*;
+    use crate::game::pill::Pill;
+    use crate::game::pill::Vitamin;
+    use crate::game::geometry::BottlePoint;
+    use crate::game::modifier::{modifiers_by_name, DoubleScore, SlowGravity};
+    use crate::game::event::VirusCrawl;
+    use mockall::mock;
+    use mockall::predicate::*;
+    use rand_chacha::ChaChaRng;
+
+    mock! {
+        pub Bottle {
+            pub fn from_seed(seed: BottleSeed) -> Self;
+            pub fn pill(&self) -> &Pill;
+            pub fn virus_count(&self) -> u32;
+            pub fn virus_counts(&self) -> VirusCounts;
+            pub fn viruses(&self) -> Vec<ColoredBlock>;
+            pub fn row(&self, y: u32) -> &[Block];
+            pub fn block(&self, point: BottlePoint) -> Block;
+            pub fn left(&mut self) -> bool;
+            pub fn right(&mut self) -> bool;
+            pub fn rotate(&mut self, clockwise: bool) -> bool;
+            pub fn hold(&mut self) -> Option<(PillShape, Vitamins)>;
+            pub fn hard_drop(&mut self) -> Option<(u32, Vitamins)>;
+            pub fn drop_path(&self) -> Option<(Vitamins, Vitamins)>;
+            pub fn register_lock_placement(&mut self) -> u32;
+            pub fn lock_placements(&self) -> u32;
+            pub fn is_collision(&self) -> bool;
+            pub fn send_garbage(
+                &mut self,
+                garbage: SendGarbage,
+                style: GarbageStyle,
+                rng: &mut ChaChaRng,
+            ) -> Vec<Garbage>;
+            pub fn try_spawn(&mut self, shape: PillShape) -> Option<Vitamins>;
+            pub fn discard_pill(&mut self);
+            pub fn crawl_viruses(&mut self, rng: &mut ChaChaRng) -> Vec<VirusCrawl>;
+            pub fn step_down_pill(&mut self) -> bool;
+            pub fn lock(&mut self) -> Option<(Vitamins, PillEffect)>;
+            pub fn power_ups(&self) -> Vec<(Vitamins, PillEffect)>;
+            pub fn power_up(&self, effect: PillEffect, vitamins: Vitamins) -> Vec<ColoredBlock>;
+            pub fn pattern(&self) -> (Vec<ColoredBlock>, Vec<VirusColor>);
+            pub fn destroy(&mut self, points: Vec<ColoredBlock>);
+            pub fn top_rows(&self, rows: u32) -> Vec<ColoredBlock>;
+            pub fn step_down_garbage(&mut self) -> bool;
+            pub fn set_ghost(&mut self, ghost: bool);
+            pub fn set_hands(&mut self, hands: usize);
+            pub fn select_hand(&mut self, hand: usize);
+        }
+        impl Clone for Bottle {
+            fn clone(&self) -> Self;
+        }
+    }
+
+    #[test]
+    fn left_success() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_left().return_once(|| true);
+        });
+        game.left();
+        game.should_have_events(&[GameEvent::Move { player: 0 }]);
+    }
+
+    #[test]
+    fn partner_moves_their_own_pill() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_set_hands().with(eq(2)).times(1).return_const(());
+            bottle.expect_select_hand().with(eq(1)).times(1).return_const(());
+            bottle.expect_select_hand().with(eq(0)).times(1).return_const(());
+            bottle.expect_left().return_once(|| true);
+        })
+        .with_partner(GameRandom::from_u64_seed(54321, RandomMode::Bag));
+        game.partner(|g| g.left());
+        game.should_have_events(&[GameEvent::Move { player: 0 }]);
+    }
+
+    #[test]
+    fn partner_ignored_without_coop() {
+        let mut game = having_bottle(|_| {});
+        game.partner(|g| g.left());
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn left_fail() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_left().return_once(|| false);
+        });
+        game.left();
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn right_success() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_right().return_once(|| true);
+        });
+        game.right();
+        game.should_have_events(&[GameEvent::Move { player: 0 }]);
+    }
+
+    #[test]
+    fn right_fail() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_right().return_once(|| false);
+        });
+        game.right();
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn rotate_success_when_falling() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_rotate().with(eq(true)).return_once(|_| true);
+        });
+        game.state = GameState::NEW_FALL;
+        game.rotate(true);
+        game.should_have_events(&[GameEvent::Rotate { player: 0 }]);
+    }
+
+    #[test]
+    fn rotate_success_and_reset_lock_when_locking() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_rotate().with(eq(true)).return_once(|_| true);
+            bottle.expect_lock_placements().return_once(|| 0);
+            bottle.expect_register_lock_placement().return_once(|| 1);
+        });
+        game.state = GameState::Lock(Duration::from_millis(10));
+        game.rotate(true);
+        game.should_have_events(&[GameEvent::Rotate { player: 0 }]);
+        assert_eq!(game.state, GameState::NEW_FALL);
+    }
+
+    #[test]
+    fn rotate_success_and_lock_with_last_lock_placement() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_rotate().with(eq(true)).return_once(|_| true);
+            bottle
+                .expect_lock_placements()
+                .return_once(|| MAX_LOCK_PLACEMENTS - 1);
+            bottle
+                .expect_register_lock_placement()
+                .return_once(|| MAX_LOCK_PLACEMENTS);
+        });
+        game.state = GameState::Lock(Duration::from_millis(10));
+        game.rotate(true);
+        game.should_have_events(&[GameEvent::Rotate { player: 0 }]);
+        assert_eq!(game.state, GameState::LOCK_NOW);
+    }
+
+    #[test]
+    fn rotate_fail() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_rotate().with(eq(true)).return_once(|_| false);
+        });
+        game.rotate(true);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn rotate_fail_when_locked() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_rotate().with(eq(true)).return_once(|_| true);
+        });
+        game.state = GameState::LOCK_NOW;
+        game.rotate(true);
+        game.should_have_no_events();
+        assert_eq!(game.state, GameState::LOCK_NOW);
+    }
+
+    #[test]
+    fn rotate_fail_when_no_lock_placements_left() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_rotate().with(eq(true)).return_once(|_| true);
+            bottle
+                .expect_lock_placements()
+                .return_once(|| MAX_LOCK_PLACEMENTS);
+        });
+        game.state = GameState::NEW_LOCK;
+        game.rotate(true);
+        game.should_have_no_events();
+        assert_eq!(game.state, GameState::LOCK_NOW);
+    }
+
+    #[test]
+    fn holds_for_first_time() {
+        let vitamins = Pill::new(PillShape::RB).vitamins();
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_hold()
+                .return_once(move || Some((PillShape::RB, vitamins)));
+        });
+        game.hold();
+        game.should_have_events(&[GameEvent::Hold {
+            player: 0,
+            shape: PillShape::RB,
+            vitamins,
+            released: None,
+        }]);
+        assert_eq!(game.state, GameState::SpawnHold(None));
+        assert_eq!(game.hold, Some(HoldState::locked(PillShape::RB)))
+    }
+
+    #[test]
+    fn holds_for_second_time() {
+        let vitamins = Pill::new(PillShape::RB).vitamins();
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_hold()
+                .return_once(move || Some((PillShape::RB, vitamins)));
+        });
+        game.hold = Some(HoldState {
+            shape: PillShape::RR,
+            locked: false,
+        });
+        game.hold();
+        game.should_have_events(&[GameEvent::Hold {
+            player: 0,
+            shape: PillShape::RB,
+            vitamins,
+            released: Some(PillShape::RR),
+        }]);
+        assert_eq!(game.state, GameState::SpawnHold(Some(PillShape::RR)));
+        assert_eq!(game.hold, Some(HoldState::locked(PillShape::RB)))
+    }
+
+    #[test]
+    fn cannot_hold_when_hold_locked() {
+        let mut game = having_bottle(|_| {});
+        game.state = GameState::NEW_FALL;
+        game.hold = Some(HoldState::locked(PillShape::RB));
+        game.hold();
+        game.should_have_no_events();
+        assert_eq!(game.state, GameState::NEW_FALL);
+    }
+
+    #[test]
+    fn cannot_hold_when_bottle_rejected() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_hold().return_once(|| None);
+        });
+        game.state = GameState::NEW_FALL;
+        game.hold();
+        game.should_have_no_events();
+        assert_eq!(game.state, GameState::NEW_FALL);
+    }
+
+    #[test]
+    fn soft_drop_on() {
+        let mut game = having_bottle(|_| {});
+        game.set_soft_drop(true);
+        game.should_have_events(&[GameEvent::SoftDrop { player: 0 }]);
+    }
+
+    #[test]
+    fn soft_drop_off() {
+        let mut game = having_bottle(|_| {});
+        game.set_soft_drop(false);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn hard_drop_success() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_hard_drop()
+                .return_once(|| Some((10, Vitamin::vitamins(PillShape::RB))));
+        });
+        game.hard_drop();
+        game.should_have_events(&[GameEvent::HardDrop {
+            player: 0,
+            vitamins: Vitamin::vitamins(PillShape::RB),
+            dropped_rows: 10,
+        }]);
+        assert_eq!(game.state, GameState::LOCK_NOW);
+        assert!(game.hard_dropped);
+        assert_eq!(game.drop_score, HARD_DROP_SCORE * 10)
+    }
+
+    #[test]
+    fn hard_drop_fail() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_hard_drop().return_once(|| None);
+        });
+        game.state = GameState::NEW_FALL;
+        game.hard_drop();
+        game.should_have_no_events();
+        assert_eq!(game.state, GameState::NEW_FALL);
+    }
+
+    #[test]
+    fn hard_drop_preview() {
+        let vitamins = Vitamin::vitamins(PillShape::RB);
+        let mut ghost = vitamins;
+        ghost.iter_mut().for_each(|v| v.translate(0, 10));
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_drop_path()
+                .return_once(move || Some((vitamins, ghost)));
+        });
+        game.state = GameState::NEW_FALL;
+        game.preview_hard_drop();
+        game.should_have_events(&[GameEvent::HardDropPreview {
+            player: 0,
+            vitamins,
+            ghost,
+        }]);
+        assert_eq!(game.state, GameState::NEW_FALL);
+        assert!(!game.hard_dropped)
+    }
+
+    #[test]
+    fn send_garbage() {
+        let mut game = having_bottle(|_| {});
+        game.send_garbage(vec![VirusColor::Red, VirusColor::Blue]);
+        assert_eq!(
+            game.garbage_buffer,
+            vec![vec![VirusColor::Red, VirusColor::Blue]]
+        );
+    }
+
+    #[test]
+    fn cancel_garbage_color_for_color() {
+        let mut game = having_bottle(|_| {}).with_garbage_cancellation(true);
+        game.send_garbage(vec![VirusColor::Red, VirusColor::Blue]);
+        game.send_garbage(vec![VirusColor::Red]);
+
+        let remainder = game.cancel_garbage(vec![VirusColor::Red, VirusColor::Yellow]);
+        assert_eq!(remainder, vec![VirusColor::Yellow]);
+        // the last batch lands first so is cancelled first
+        assert_eq!(
+            game.garbage_buffer,
+            vec![vec![VirusColor::Red, VirusColor::Blue]]
+        );
+    }
+
+    #[test]
+    fn cancel_garbage_disabled() {
+        let mut game = having_bottle(|_| {});
+        game.send_garbage(vec![VirusColor::Red]);
+
+        let remainder = game.cancel_garbage(vec![VirusColor::Red]);
+        assert_eq!(remainder, vec![VirusColor::Red]);
+        assert_eq!(game.garbage_buffer, vec![vec![VirusColor::Red]]);
+    }
+
+    #[test]
+    fn metrics_pending_garbage_in_landing_order() {
+        let mut game = having_bottle(|b| {
+            b.expect_virus_count().return_const(0u32);
+        });
+        game.send_garbage(vec![VirusColor::Red, VirusColor::Blue]);
+        game.send_garbage(vec![VirusColor::Yellow]);
+        assert_eq!(
+            game.metrics().pending_garbage().collect::<Vec<VirusColor>>(),
+            vec![VirusColor::Yellow, VirusColor::Red, VirusColor::Blue]
+        );
+    }
+
+    #[test]
+    fn metrics_queue_limited_to_peek_count() {
+        let mut game = having_bottle(|b| {
+            b.expect_virus_count().return_const(0u32);
+        });
+        assert_eq!(game.metrics().queue().len(), PEEK_SIZE);
+        game = game.with_peek_count(2);
+        assert_eq!(game.metrics().queue(), &game.random.peek()[..2]);
+        game = game.with_peek_count(0);
+        assert!(game.metrics().queue().is_empty());
+    }
+
+    #[test]
+    fn update_spawn_into_spawn() {
+        let mut game = having_bottle(|_| {});
+        game.state = GameState::Spawn(Duration::from_nanos(1));
+        game.update(Duration::from_nanos(2));
+        assert_eq!(game.state, GameState::Spawn(Duration::from_nanos(3)));
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_spawn_into_fall() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_try_spawn()
+                .with(eq(PillShape::RY))
+                .return_once(|_| Some(Vitamin::vitamins(PillShape::RY)));
+        });
+        game.state = GameState::Spawn(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_FALL);
+        game.should_have_events(&[GameEvent::Spawn {
+            player: 0,
+            shape: PillShape::RY,
+            is_hold: false,
+        }]);
+    }
+
+    #[test]
+    fn update_hard_dropped_spawn_into_fall() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_try_spawn()
+                .with(eq(PillShape::RY))
+                .return_once(|_| Some(Vitamin::vitamins(PillShape::RY)));
+        });
+        game.hard_dropped = true;
+        game.state = GameState::NEW_SPAWN;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_FALL);
+        assert!(!game.hard_dropped);
+        game.should_have_events(&[GameEvent::Spawn {
+            player: 0,
+            shape: PillShape::RY,
+            is_hold: false,
+        }]);
+    }
+
+    #[test]
+    fn rewind_disabled() {
+        let mut game = having_bottle(|_| {});
+        assert!(!game.rewind());
+        assert!(!game.is_rewound());
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn rewind_to_previous_spawn() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_clone().times(2).returning(MockBottle::new);
+            bottle
+                .expect_try_spawn()
+                .times(2)
+                .returning(|shape| Some(Vitamin::vitamins(shape)));
+        })
+        .with_rewind(true);
+        game.state = GameState::NEW_SPAWN;
+        game.hard_dropped = true;
+        game.update(Duration::from_nanos(1));
+        // cannot rewind beyond the first pill
+        assert!(!game.rewind());
+
+        game.score = 100;
+        game.total_pills = 1;
+        game.state = GameState::NEW_SPAWN;
+        game.hard_dropped = true;
+        game.update(Duration::from_nanos(1));
+        game.events.clear();
+
+        assert!(game.rewind());
+        assert!(game.is_rewound());
+        assert_eq!(game.score, 0);
+        assert_eq!(game.total_pills, 0);
+        assert_eq!(game.state, GameState::NEW_SPAWN);
+        game.should_have_events(&[GameEvent::Rewind { player: 0 }]);
+    }
+
+    #[test]
+    fn save_state_disabled() {
+        let mut game = having_bottle(|_| {});
+        assert!(!game.save_state(0));
+        assert!(!game.load_state(0));
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn load_saved_state() {
+        let mut game = having_bottle(|bottle| {
+            // the saved bottle is cloned again by each load
+            bottle.expect_clone().return_once(|| {
+                let mut saved = MockBottle::new();
+                saved.expect_clone().times(1).returning(MockBottle::new);
+                saved
+            });
+        })
+        .with_rewind(true);
+        // nothing saved yet
+        assert!(!game.load_state(0));
+
+        game.state = GameState::Fall(Duration::from_millis(100));
+        game.score = 100;
+        assert!(game.save_state(0));
+        assert!(!game.is_rewound());
+
+        game.state = GameState::NEW_SPAWN;
+        game.score = 500;
+        game.level_count = 1;
+        game.events.clear();
+
+        assert!(game.load_state(0));
+        assert!(game.is_rewound());
+        assert_eq!(game.score, 100);
+        assert_eq!(game.level_count, 0);
+        assert_eq!(game.state, GameState::Fall(Duration::from_millis(100)));
+        game.should_have_events(&[GameEvent::LoadState { player: 0, slot: 0 }]);
+    }
+
+    #[test]
+    fn update_spawn_into_game_over() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_try_spawn()
+                .with(eq(PillShape::RY))
+                .return_once(|_| None);
+        });
+        game.state = GameState::Spawn(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::GameOver);
+        game.should_have_events(&[GameEvent::GameOver { player: 0 }]);
+    }
+
+    #[test]
+    fn zen_top_out_clears_the_top_rows() {
+        let blocks = vec![ColoredBlock::virus(3, 0, VirusColor::Red)];
+        let mut game = having_bottle(|bottle| {
+            let top_rows = blocks.clone();
+            bottle.expect_try_spawn().return_once(|_| None);
+            bottle.expect_discard_pill().times(1).return_const(());
+            bottle
+                .expect_top_rows()
+                .with(eq(ZEN_TOP_OUT_ROWS))
+                .return_once(move |_| top_rows);
+        })
+        .with_rule_set(RuleSet::Zen);
+        game.score = 1500;
+        game.state = GameState::Spawn(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::Destroy(blocks, Combo::empty()));
+        assert_eq!(game.score, 500);
+        game.should_have_events(&[GameEvent::ToppedOut {
+            player: 0,
+            penalty: ZEN_TOP_OUT_PENALTY,
+        }]);
+    }
+
+    #[test]
+    fn zen_game_over_when_nothing_to_clear() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_try_spawn().return_once(|_| None);
+            bottle.expect_discard_pill().return_const(());
+            bottle.expect_top_rows().return_once(|_| vec![]);
+        })
+        .with_rule_set(RuleSet::Zen);
+        game.state = GameState::Spawn(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::GameOver);
+    }
+
+    #[test]
+    fn update_spawn_into_garbage() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_send_garbage()
+                .with(
+                    eq(vec![VirusColor::Red, VirusColor::Yellow]),
+                    eq(GarbageStyle::Scattered),
+                    always(),
+                )
+                .return_once(|_, _, _| {
+                    vec![Garbage::new(VirusColor::Yellow, BottlePoint::new(1, 2))]
+                });
+        });
+        game.garbage_buffer
+            .push(vec![VirusColor::Red, VirusColor::Yellow]);
+        game.state = GameState::NEW_SPAWN;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::drop_garbage(Combo::empty()));
+        game.should_have_events(&[GameEvent::ReceivedGarbage {
+            player: 0,
+            garbage: vec![Garbage::new(VirusColor::Yellow, BottlePoint::new(1, 2))],
+            style: GarbageStyle::Scattered,
+        }]);
+    }
+
+    #[test]
+    fn update_hold_spawn_into_fall() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_try_spawn()
+                .with(eq(PillShape::RB))
+                .return_once(|_| Some(Vitamin::vitamins(PillShape::RB)));
+        });
+        game.state = GameState::SpawnHold(Some(PillShape::RB));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_FALL);
+        game.should_have_events(&[GameEvent::Spawn {
+            player: 0,
+            shape: PillShape::RB,
+            is_hold: true,
+        }]);
+    }
+
+    #[test]
+    fn update_fall_into_fall() {
+        let mut game = having_bottle(|_| {});
+        game.state = GameState::Fall(Duration::from_nanos(1));
+        game.update(Duration::from_nanos(2));
+        assert_eq!(game.state, GameState::Fall(Duration::from_nanos(3)));
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_fall_into_next_fall() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_pill().return_once(|| true);
+            bottle.expect_is_collision().return_once(|| false);
+        });
+        game.state = GameState::Fall(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_FALL);
+        game.should_have_events(&[GameEvent::Fall { player: 0 }]);
+    }
+
+    #[test]
+    fn update_fall_into_lock_by_fail() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_pill().return_once(|| false);
+        });
+        game.state = GameState::Fall(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_LOCK);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_fall_into_lock_by_collision() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_pill().return_once(|| true);
+            bottle.expect_is_collision().return_once(|| true);
+            bottle.expect_lock_placements().return_once(|| 0);
+        });
+        game.state = GameState::Fall(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_LOCK);
+        game.should_have_events(&[GameEvent::Fall { player: 0 }]);
+    }
+
+    #[test]
+    fn update_fall_into_lock_asap_by_collision() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_pill().return_once(|| true);
+            bottle.expect_is_collision().return_once(|| true);
+            bottle
+                .expect_lock_placements()
+                .return_once(|| MAX_LOCK_PLACEMENTS);
+        });
+        game.state = GameState::Fall(GameSpeed::Low.duration_of_level(0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::LOCK_NOW);
+        game.should_have_events(&[GameEvent::Fall { player: 0 }]);
+    }
+
+    #[test]
+    fn update_fall_into_lock_now_by_fail_without_lock_delay() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_pill().return_once(|| false);
+            bottle.expect_set_ghost().return_const(());
+        })
+        .with_rule_set(RuleSet::Classic)
+        .with_features(RuleSet::Classic.features());
+        game.state = GameState::Fall(RuleSet::Classic.gravity(GameSpeed::Low, 0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::LOCK_NOW);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_fall_into_fall_on_collision_without_lock_delay() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_pill().return_once(|| true);
+            bottle.expect_set_ghost().return_const(());
+        })
+        .with_rule_set(RuleSet::Classic)
+        .with_features(RuleSet::Classic.features());
+        game.state = GameState::Fall(RuleSet::Classic.gravity(GameSpeed::Low, 0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_FALL);
+        game.should_have_events(&[GameEvent::Fall { player: 0 }]);
+    }
+
+    #[test]
+    fn cannot_hold_or_hard_drop_when_disabled() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_set_ghost().with(eq(true)).return_const(());
+        })
+        .with_features(Features::GHOST | Features::LOCK_DELAY);
+        game.state = GameState::NEW_FALL;
+        game.hold();
+        game.hard_drop();
+        game.preview_hard_drop();
+        game.should_have_no_events();
+        assert_eq!(game.state, GameState::NEW_FALL);
+    }
+
+    #[test]
+    fn update_lock_into_lock() {
+        let mut game = having_bottle(|_| {});
+        game.state = GameState::Lock(Duration::from_nanos(1));
+        game.update(Duration::from_nanos(2));
+        assert_eq!(game.state, GameState::Lock(Duration::from_nanos(3)));
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_lock_into_pattern() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_is_collision().return_once(|| true);
+            bottle
+                .expect_lock()
+                .return_once(|| Some((Vitamin::vitamins(PillShape::RB), PillEffect::None)));
+        });
+        game.state = GameState::LOCK_NOW;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_PATTERN);
+        game.should_have_events(&[GameEvent::Lock {
+            player: 0,
+            vitamins: Vitamin::vitamins(PillShape::RB),
+            hard_or_soft_dropped: false,
+            drop_score: 0,
+        }]);
+    }
+
+    #[test]
+    fn update_hard_drop_lock_into_pattern() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_is_collision().return_once(|| true);
+            bottle
+                .expect_lock()
+                .return_once(|| Some((Vitamin::vitamins(PillShape::RB), PillEffect::None)));
+        });
+        game.hard_dropped = true;
+        game.drop_score = HARD_DROP_SCORE * 10;
+        game.state = GameState::Lock(Duration::from_nanos(1));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_PATTERN);
+        assert_eq!(game.score, 20);
+        assert_eq!(game.drop_score, 0);
+        game.should_have_events(&[GameEvent::Lock {
+            player: 0,
+            vitamins: Vitamin::vitamins(PillShape::RB),
+            hard_or_soft_dropped: true,
+            drop_score: 20,
+        }])
+    }
+
+    #[test]
+    fn drop_score_capped_and_off_with_purist_scoring() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_is_collision().return_const(true);
+            bottle
+                .expect_lock()
+                .returning(|| Some((Vitamin::vitamins(PillShape::RB), PillEffect::None)));
+        });
+        game.drop_score = MAX_DROP_SCORE * 2;
+        game.state = GameState::LOCK_NOW;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.score, MAX_DROP_SCORE);
+
+        game.features.set(Features::DROP_SCORE, false);
+        game.drop_score = SOFT_DROP_SCORE * 5;
+        game.state = GameState::LOCK_NOW;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.score, MAX_DROP_SCORE);
+        assert_eq!(game.drop_score, 0);
+    }
+
+    #[test]
+    fn update_power_up_lock_into_destroy() {
+        let blocks = vec![ColoredBlock::virus(4, 16, VirusColor::Blue)];
+        let mut game = having_bottle(|bottle| {
+            let destroyed = blocks.clone();
+            bottle.expect_is_collision().return_once(|| true);
+            bottle
+                .expect_lock()
+                .return_once(|| Some((Vitamin::vitamins(PillShape::RB), PillEffect::Bomb)));
+            bottle
+                .expect_power_up()
+                .with(eq(PillEffect::Bomb), always())
+                .return_once(move |_, _| destroyed);
+        });
+        game.state = GameState::LOCK_NOW;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::Destroy(blocks, Combo::new(vec![], 1)));
+        game.should_have_events(&[
+            GameEvent::Lock {
+                player: 0,
+                vitamins: Vitamin::vitamins(PillShape::RB),
+                hard_or_soft_dropped: false,
+                drop_score: 0,
+            },
+            GameEvent::PowerUp {
+                player: 0,
+                effect: PillEffect::Bomb,
+                vitamins: Vitamin::vitamins(PillShape::RB),
+            },
+        ]);
+    }
+
+    #[test]
+    fn viruses_crawl_while_falling() {
+        let crawl = VirusCrawl {
+            color: VirusColor::Red,
+            from: BottlePoint::new(1, 10),
+            to: BottlePoint::new(1, 11),
+        };
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_crawl_viruses()
+                .times(1)
+                .return_once(move |_| vec![crawl]);
+        })
+        .with_crawling_viruses(true);
+        game.state = GameState::NEW_FALL;
+        game.virus_crawl = Some(VIRUS_CRAWL_PERIOD - Duration::from_nanos(2));
+        game.update(Duration::from_nanos(1));
+        game.should_have_no_events();
+        game.update(Duration::from_nanos(1));
+        game.should_have_events(&[GameEvent::VirusesCrawled {
+            player: 0,
+            crawls: vec![crawl],
+        }]);
+    }
+
+    #[test]
+    fn update_lock_into_fall() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_is_collision().return_once(|| false);
+        });
+        game.state = GameState::LOCK_NOW;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_FALL);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_pattern_into_destroy() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| {
+                (
+                    vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+                    vec![VirusColor::Yellow],
+                )
+            });
+        });
+        game.state = GameState::Pattern(Combo::new(vec![VirusColor::Blue], 0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(
+            game.state,
+            GameState::Destroy(
+                vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+                Combo::new(vec![VirusColor::Blue, VirusColor::Yellow], 1)
+            )
+        );
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_pattern_into_spawn() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+            bottle.expect_virus_count().return_once(|| 1);
+        });
+        game.state = GameState::NEW_PATTERN;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_SPAWN);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_pattern_into_spawn_with_garbage() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+            bottle.expect_virus_count().return_once(|| 1);
+        });
+        game.state = GameState::Pattern(Combo::new(vec![VirusColor::Blue, VirusColor::Red], 2));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_SPAWN);
+        assert_eq!(game.score, 300);
+        game.should_have_events(&[
+            GameEvent::ComboScore {
+                player: 0,
+                score: 300,
+            },
+            GameEvent::SendGarbage {
+                player: 0,
+                garbage: vec![VirusColor::Blue, VirusColor::Red],
+            },
+        ]);
+    }
+
+    #[test]
+    fn update_pattern_into_spawn_with_score_modifier() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+        })
+        .with_modifiers(modifiers_by_name(&[DoubleScore::NAME]).unwrap());
+        game.state = GameState::Pattern(Combo::new(vec![VirusColor::Blue], 1));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::NEW_SPAWN);
+        assert_eq!(game.score, 200);
+    }
+
+    #[test]
+    fn step_delay_with_gravity_modifiers() {
+        let game = having_bottle(|_| {});
+        let base = game.step_delay();
+        let game = game.with_modifiers(modifiers_by_name(&[SlowGravity::NAME]).unwrap());
+        assert_eq!(game.step_delay(), base * 2);
+    }
+
+    #[test]
+    fn update_destroy_into_drop_garbage() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_destroy()
+                .with(eq(vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)]))
+                .return_once(|_| ());
+            bottle
+                .expect_block()
+                .with(eq(BottlePoint::new(1, 2)))
+                .return_once(|_| Block::Garbage(VirusColor::Yellow));
+            bottle.expect_virus_counts().return_const([1, 1, 1, 0]);
+        });
+        game.state = GameState::Destroy(
+            vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+            Combo::new(vec![VirusColor::Blue], 2),
+        );
+        game.update(Duration::from_nanos(1));
+        assert_eq!(
+            game.state,
+            GameState::drop_garbage(Combo::new(vec![VirusColor::Blue], 2))
+        );
+        game.should_have_events(&[GameEvent::Destroy {
+            player: 0,
+            blocks: vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+            is_combo: false,
+        }]);
+    }
+
+    #[test]
+    fn update_destroy_into_drop_garbage_with_combo() {
+        let mut game = having_bottle(|bottle| {
+            bottle
+                .expect_destroy()
+                .with(eq(vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)]))
+                .return_once(|_| ());
+            bottle
+                .expect_block()
+                .with(eq(BottlePoint::new(1, 2)))
+                .return_once(|_| Block::Garbage(VirusColor::Yellow));
+            bottle.expect_virus_counts().return_const([1, 1, 1, 0]);
+        });
+        let combo = Combo::new(vec![VirusColor::Red, VirusColor::Blue], 1);
+        game.state = GameState::Destroy(
+            vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+            combo.clone(),
+        );
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::drop_garbage(combo));
+        game.should_have_events(&[GameEvent::Destroy {
+            player: 0,
+            blocks: vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+            is_combo: true,
+        }]);
+    }
+
+    #[test]
+    fn update_destroy_last_virus_of_color() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_destroy().return_once(|_| ());
+            bottle.expect_virus_counts().return_const([0, 1, 1, 0]);
+        });
+        game.state = GameState::Destroy(
+            vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+            Combo::new(vec![VirusColor::Yellow], 1),
+        );
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.virus_counts(), [0, 1, 1, 0]);
+        assert_eq!(game.viruses_remaining(VirusColor::Red), 1);
+        game.should_have_events(&[
+            GameEvent::Destroy {
+                player: 0,
+                blocks: vec![ColoredBlock::virus(1, 2, VirusColor::Yellow)],
+                is_combo: false,
+            },
+            GameEvent::VirusColorEliminated {
+                player: 0,
+                color: VirusColor::Yellow,
+            },
+        ]);
+    }
+
+    #[test]
+    fn update_destroy_last_virus_into_level_complete() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_destroy().return_once(|_| ());
+            bottle.expect_virus_counts().return_const([0, 0, 0, 0]);
+        });
+        game.virus_counts = [0, 0, 1, 0];
+        game.state = GameState::Destroy(
+            vec![ColoredBlock::virus(1, 2, VirusColor::Red)],
+            Combo::new(vec![VirusColor::Red], 1),
+        );
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::LevelComplete);
+        game.should_have_events(&[
+            GameEvent::Destroy {
+                player: 0,
+                blocks: vec![ColoredBlock::virus(1, 2, VirusColor::Red)],
+                is_combo: false,
+            },
+            GameEvent::VirusColorEliminated {
+                player: 0,
+                color: VirusColor::Red,
+            },
+            GameEvent::LevelComplete { player: 0 },
+        ]);
+    }
+
+    #[test]
+    fn stats_destroyed_viruses_and_max_combo() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_destroy().return_once(|_| ());
+            bottle.expect_virus_counts().return_const([1, 1, 1, 0]);
+        });
+        game.state = GameState::Destroy(
+            vec![
+                ColoredBlock::virus(1, 2, VirusColor::Yellow),
+                ColoredBlock::virus(1, 3, VirusColor::Yellow),
+            ],
+            Combo::new(vec![VirusColor::Red, VirusColor::Yellow], 3),
+        );
+        game.update(Duration::from_millis(5));
+        assert_eq!(game.stats().viruses_cleared(), 2);
+        assert_eq!(game.stats().max_combo(), 2);
+        assert_eq!(game.stats().time(), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn stats_speed_bonus() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+        });
+        game.speed = GameSpeed::Medium;
+        game.state = GameState::Pattern(Combo::new(vec![VirusColor::Blue], 2));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.stats().score(), 200 + 400);
+        assert_eq!(game.stats().speed_bonus(), 600 - 300);
+    }
+
+    #[test]
+    fn speed_up_adds_to_pill_speed_level() {
+        let mut game = having_bottle(|_| {});
+        game.total_pills = 25;
+        game.speed_up();
+        assert_eq!(game.speed_level(), 3);
+        game.should_have_events(&[GameEvent::SpeedLevelUp {
+            player: 0,
+            is_boost: true,
+        }]);
+    }
+
+    #[test]
+    fn speed_up_stops_at_max_level() {
+        let mut game = having_bottle(|_| {});
+        game.speed_boost = GameSpeed::MAX_LEVEL;
+        game.speed_up();
+        assert_eq!(game.speed_level(), GameSpeed::MAX_LEVEL as u32);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn chain_extends_when_locked_pill_clears() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+        });
+        game.chain = 1;
+        game.resolving_lock = true;
+        game.state = GameState::Pattern(Combo::new(vec![VirusColor::Blue], 0));
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.chain, 2);
+        game.should_have_events(&[GameEvent::Chain {
+            player: 0,
+            chain: 2,
+        }]);
+    }
+
+    #[test]
+    fn adaptive_speed_ramps_up_on_chain() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+        })
+        .with_adaptive_speed(true);
+        game.chain = 1;
+        game.resolving_lock = true;
+        game.state = GameState::Pattern(Combo::new(vec![VirusColor::Blue], 0));
+        game.update(Duration::from_nanos(1));
+        // a fast clear & a chain
+        assert_eq!(game.speed_level(), 0);
+        assert_eq!(game.effective_speed_level(), 2);
+        assert_eq!(game.step_delay(), GameSpeed::Low.duration_of_level(2));
+    }
+
+    #[test]
+    fn chain_breaks_when_locked_pill_does_not_clear() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+        });
+        game.chain = 3;
+        game.resolving_lock = true;
+        game.state = GameState::NEW_PATTERN;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.chain, 0);
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn chain_unaffected_by_dropped_garbage() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_pattern().return_once(|| (vec![], vec![]));
+        });
+        game.chain = 3;
+        game.state = GameState::NEW_PATTERN;
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.chain, 3);
+    }
+
+    #[test]
+    fn update_drop_garbage_into_drop_garbage() {
+        let mut game = having_bottle(|_| {});
+        let combo = Combo::new(vec![VirusColor::Blue], 2);
+        game.state = GameState::DropGarbage(Duration::from_nanos(2), combo.clone());
+        game.update(Duration::from_nanos(1));
+        assert_eq!(
+            game.state,
+            GameState::DropGarbage(Duration::from_nanos(3), combo)
+        );
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn update_drop_garbage_into_next_drop_garbage() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_garbage().return_once(|| true);
+        });
+        let combo = Combo::new(vec![VirusColor::Blue], 2);
+        game.state = GameState::DropGarbage(GARBAGE_DROP_DURATION, combo.clone());
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::DropGarbage(Duration::ZERO, combo));
+        game.should_have_events(&[GameEvent::DropGarbage { player: 0 }])
+    }
+
+    #[test]
+    fn update_drop_garbage_into_pattern() {
+        let mut game = having_bottle(|bottle| {
+            bottle.expect_step_down_garbage().return_once(|| false);
+        });
+        let combo = Combo::new(vec![VirusColor::Blue], 2);
+        game.state = GameState::DropGarbage(GARBAGE_DROP_DURATION, combo.clone());
+        game.update(Duration::from_nanos(1));
+        assert_eq!(game.state, GameState::Pattern(combo));
+        game.should_have_no_events();
+    }
+
+    #[test]
+    fn score_0_when_empty() {
+        assert_eq!(Combo::empty().score(GameSpeed::Low), 0);
+    }
+
+    #[test]
+    fn score_low() {
+        let score = Combo::new(vec![VirusColor::Blue], 1).score(GameSpeed::Low);
+        assert_eq!(score, 100);
+    }
+
+    #[test]
+    fn score_combo_low() {
+        let score = Combo::new(vec![VirusColor::Blue, VirusColor::Red], 2).score(GameSpeed::Low);
+        assert_eq!(score, 100 + 200);
+    }
+
+    #[test]
+    fn score_medium() {
+        let score = Combo::new(vec![VirusColor::Blue], 1).score(GameSpeed::Medium);
+        assert_eq!(score, 200);
+    }
+
+    #[test]
+    fn score_combo_medium() {
+        let score = Combo::new(vec![VirusColor::Blue, VirusColor::Red], 3).score(GameSpeed::Medium);
+        assert_eq!(score, 200 + 400 + 800);
+    }
+
+    #[test]
+    fn score_high() {
+        let score = Combo::new(vec![VirusColor::Blue], 1).score(GameSpeed::High);
+        assert_eq!(score, 300);
+    }
+
+    #[test]
+    fn score_combo_high() {
+        let score = Combo::new(vec![VirusColor::Blue, VirusColor::Red], 4).score(GameSpeed::High);
+        assert_eq!(score, 300 + 600 + 1200 + 2400);
+    }
+
+    #[test]
+    fn next_level_preview_is_the_next_bottle() {
+        let game = having_bottle(|_| {});
+        let preview = game.next_level_preview().unwrap();
+        assert_eq!(preview.virus_level, 11);
+        assert_eq!(preview.speed, GameSpeed::Low);
+        let next_bottle = GameRandom::from_u64_seed(12345, RandomMode::Bag)
+            .bottle_seed(11)
+            .unwrap();
+        assert_eq!(preview.viruses, next_bottle.viruses());
+        // previewing leaves the random as it was
+        assert_eq!(game.next_level_preview().unwrap(), preview);
+    }
+
+    fn having_bottle<F>(mut f: F) -> Game
+    where
+        F: FnMut(&mut MockBottle),
+    {
+        let mut bottle = MockBottle::new();
+        // counted once when the game is created
+        bottle
+            .expect_virus_counts()
+            .times(1)
+            .return_const([1, 1, 1, 0]);
+        f(&mut bottle);
+        Game::from_bottle(
+            0,
+            10,
+            GameSpeed::Low,
+            GameRandom::from_u64_seed(12345, RandomMode::Bag),
+            bottle,
+        )
+    }
+
+    trait GameTestHarness {
+        fn should_have_no_events(&self);
+        fn should_have_events(&self, events: &[GameEvent]);
+    }
+
+    impl GameTestHarness for Game {
+        fn should_have_no_events(&self) {
+            assert!(self.events.is_empty(), "{:?}", self.events);
+        }
+
+        fn should_have_events(&self, events: &[GameEvent]) {
+            assert_eq!(self.events, events.to_vec());
+        }
+    }
+}
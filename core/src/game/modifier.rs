@@ -0,0 +1,141 @@
+use crate::game::event::GameEvent;
+use crate::game::pill::PillShape;
+use std::time::Duration;
+
+/// Hooks into a single player's game, modifiers are applied in the order they are configured
+pub trait GameplayModifier {
+    fn name(&self) -> &'static str;
+
+    /// Observe every event raised by the game
+    fn on_event(&mut self, _event: &GameEvent) {}
+
+    /// Modify the duration of a single gravity step of the falling pill
+    fn modify_gravity(&self, step: Duration) -> Duration {
+        step
+    }
+
+    /// Modify the score awarded by a completed combo
+    fn modify_score(&self, score: u32) -> u32 {
+        score
+    }
+
+    /// Modify a pill shape taken from the random queue before it is spawned
+    fn on_spawn(&mut self, shape: PillShape) -> PillShape {
+        shape
+    }
+}
+
+pub type GameplayModifiers = Vec<Box<dyn GameplayModifier>>;
+
+pub const MODIFIER_NAMES: [&str; 4] = [
+    SlowGravity::NAME,
+    FastGravity::NAME,
+    DoubleScore::NAME,
+    MonoPills::NAME,
+];
+
+pub fn modifier_by_name(name: &str) -> Result<Box<dyn GameplayModifier>, String> {
+    match name {
+        SlowGravity::NAME => Ok(Box::new(SlowGravity)),
+        FastGravity::NAME => Ok(Box::new(FastGravity)),
+        DoubleScore::NAME => Ok(Box::new(DoubleScore)),
+        MonoPills::NAME => Ok(Box::new(MonoPills)),
+        _ => Err(format!(
+            "unknown gameplay modifier '{}', expected one of {:?}",
+            name, MODIFIER_NAMES
+        )),
+    }
+}
+
+pub fn modifiers_by_name<S: AsRef<str>>(names: &[S]) -> Result<GameplayModifiers, String> {
+    names.iter().map(|n| modifier_by_name(n.as_ref())).collect()
+}
+
+/// Assist: pills fall at half speed
+pub struct SlowGravity;
+
+impl SlowGravity {
+    pub const NAME: &'static str = "slow_gravity";
+}
+
+impl GameplayModifier for SlowGravity {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn modify_gravity(&self, step: Duration) -> Duration {
+        step * 2
+    }
+}
+
+/// Challenge: pills fall at double speed
+pub struct FastGravity;
+
+impl FastGravity {
+    pub const NAME: &'static str = "fast_gravity";
+}
+
+impl GameplayModifier for FastGravity {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn modify_gravity(&self, step: Duration) -> Duration {
+        step / 2
+    }
+}
+
+pub struct DoubleScore;
+
+impl DoubleScore {
+    pub const NAME: &'static str = "double_score";
+}
+
+impl GameplayModifier for DoubleScore {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn modify_score(&self, score: u32) -> u32 {
+        score.saturating_mul(2)
+    }
+}
+
+/// Assist: both halves of every pill are the same color
+pub struct MonoPills;
+
+impl MonoPills {
+    pub const NAME: &'static str = "mono_pills";
+}
+
+impl GameplayModifier for MonoPills {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn on_spawn(&mut self, shape: PillShape) -> PillShape {
+        PillShape::new(shape.left_color(), shape.left_color()).with_effect(shape.effect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_all_modifiers_by_name() {
+        let modifiers = modifiers_by_name(&MODIFIER_NAMES).unwrap();
+        let names: Vec<&str> = modifiers.iter().map(|m| m.name()).collect();
+        assert_eq!(names, MODIFIER_NAMES);
+    }
+
+    #[test]
+    fn unknown_modifier() {
+        assert!(modifier_by_name("moon_gravity").is_err());
+    }
+
+    #[test]
+    fn mono_pills() {
+        assert_eq!(MonoPills.on_spawn(PillShape::YB), PillShape::YY);
+    }
+}
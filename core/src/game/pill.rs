@@ -1,4 +1,5 @@
 use crate::game::geometry::{BottlePoint, Rotation};
+use serde::{Deserialize, Serialize};
 
 const SPAWN_POINT: BottlePoint = BottlePoint::new(3, -1);
 
@@ -20,22 +21,38 @@ fn rotated_pills(rotation: Rotation) -> [BottlePoint; 2] {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, Default, strum::EnumIter, Serialize, Deserialize,
+)]
 pub enum VirusColor {
     #[default]
     Yellow = 0,
     Blue = 1,
     Red = 2,
+    /// Only in play in four color mode
+    Green = 3,
 }
 
 impl VirusColor {
-    pub const N: usize = 3;
+    pub const N: usize = 4;
+    pub const CLASSIC: [Self; 3] = [Self::Yellow, Self::Blue, Self::Red];
+    pub const FOUR_COLORS: [Self; 4] = [Self::Yellow, Self::Blue, Self::Red, Self::Green];
+
+    /// Colors in play, green only joins in four color mode
+    pub fn palette(four_colors: bool) -> &'static [Self] {
+        if four_colors {
+            &Self::FOUR_COLORS
+        } else {
+            &Self::CLASSIC
+        }
+    }
 
     pub fn next(self) -> Self {
         match self {
             VirusColor::Yellow => VirusColor::Blue,
             VirusColor::Blue => VirusColor::Red,
-            VirusColor::Red => VirusColor::Yellow,
+            VirusColor::Red => VirusColor::Green,
+            VirusColor::Green => VirusColor::Yellow,
         }
     }
 }
@@ -48,15 +65,38 @@ impl TryFrom<usize> for VirusColor {
             x if x == VirusColor::Yellow as usize => Ok(VirusColor::Yellow),
             x if x == VirusColor::Red as usize => Ok(VirusColor::Red),
             x if x == VirusColor::Blue as usize => Ok(VirusColor::Blue),
+            x if x == VirusColor::Green as usize => Ok(VirusColor::Green),
             _ => Err(()),
         }
     }
 }
 
+/// Special effect of a power-up pill, applied when it locks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, strum::EnumIter)]
+pub enum PillEffect {
+    #[default]
+    None,
+    /// Clears every block in a 3x3 area around the pill
+    Bomb,
+    /// Clears every block of the pill's color
+    Lightning,
+    /// Falls through any garbage below the pill
+    Heavy,
+}
+
+impl PillEffect {
+    pub const POWER_UPS: [Self; 3] = [Self::Bomb, Self::Lightning, Self::Heavy];
+
+    pub fn is_power_up(&self) -> bool {
+        *self != PillEffect::None
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct PillShape {
     left_color: VirusColor,
     right_color: VirusColor,
+    effect: PillEffect,
 }
 
 impl Default for PillShape {
@@ -70,6 +110,7 @@ impl PillShape {
         Self {
             left_color,
             right_color,
+            effect: PillEffect::None,
         }
     }
 
@@ -85,6 +126,16 @@ impl PillShape {
     pub const RY: Self = Self::new(VirusColor::Red, VirusColor::Yellow);
     pub const RB: Self = Self::new(VirusColor::Red, VirusColor::Blue);
 
+    pub const YG: Self = Self::new(VirusColor::Yellow, VirusColor::Green);
+    pub const BG: Self = Self::new(VirusColor::Blue, VirusColor::Green);
+    pub const RG: Self = Self::new(VirusColor::Red, VirusColor::Green);
+
+    pub const GG: Self = Self::new(VirusColor::Green, VirusColor::Green);
+    pub const GY: Self = Self::new(VirusColor::Green, VirusColor::Yellow);
+    pub const GB: Self = Self::new(VirusColor::Green, VirusColor::Blue);
+    pub const GR: Self = Self::new(VirusColor::Green, VirusColor::Red);
+
+    /// Every pill of the classic three colors
     pub const ALL: [Self; 9] = [
         Self::YY,
         Self::YB,
@@ -97,12 +148,62 @@ impl PillShape {
         Self::RB,
     ];
 
+    /// Every pill of four color mode, the classic pills then those with green
+    pub const FOUR_COLORS: [Self; 16] = [
+        Self::YY,
+        Self::YB,
+        Self::YR,
+        Self::BB,
+        Self::BY,
+        Self::BR,
+        Self::RR,
+        Self::RY,
+        Self::RB,
+        Self::YG,
+        Self::BG,
+        Self::RG,
+        Self::GG,
+        Self::GY,
+        Self::GB,
+        Self::GR,
+    ];
+
+    /// Pills in play, a bag holds one of each
+    pub fn all(four_colors: bool) -> &'static [Self] {
+        if four_colors {
+            &Self::FOUR_COLORS
+        } else {
+            &Self::ALL
+        }
+    }
+
+    pub fn has_green(&self) -> bool {
+        self.left_color == VirusColor::Green || self.right_color == VirusColor::Green
+    }
+
+    /// Turns the pill into a power-up, a lightning pill takes a single color to clear
+    pub fn with_effect(mut self, effect: PillEffect) -> Self {
+        if effect == PillEffect::Lightning {
+            self.right_color = self.left_color;
+        }
+        self.effect = effect;
+        self
+    }
+
+    /// The plain pill of the same colors
+    pub fn without_effect(self) -> Self {
+        Self::new(self.left_color, self.right_color)
+    }
+
     pub fn left_color(&self) -> VirusColor {
         self.left_color
     }
     pub fn right_color(&self) -> VirusColor {
         self.right_color
     }
+    pub fn effect(&self) -> PillEffect {
+        self.effect
+    }
 }
 
 impl VirusColor {
@@ -111,6 +212,17 @@ impl VirusColor {
             VirusColor::Red => 'r',
             VirusColor::Blue => 'b',
             VirusColor::Yellow => 'y',
+            VirusColor::Green => 'g',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_lowercase() {
+            'r' => Some(VirusColor::Red),
+            'b' => Some(VirusColor::Blue),
+            'y' => Some(VirusColor::Yellow),
+            'g' => Some(VirusColor::Green),
+            _ => None,
         }
     }
 }
@@ -209,6 +321,7 @@ pub type Vitamins = [Vitamin; 2];
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Pill {
     vitamins: Vitamins,
+    effect: PillEffect,
     position: BottlePoint,
     rotation: Rotation,
     lock_placements: u32,
@@ -219,6 +332,7 @@ impl Pill {
     pub fn new(shape: PillShape) -> Self {
         Self {
             vitamins: Vitamin::vitamins(shape),
+            effect: shape.effect(),
             position: SPAWN_POINT,
             rotation: Rotation::North,
             lock_placements: 0,
@@ -227,13 +341,17 @@ impl Pill {
     }
 
     pub fn shape(&self) -> PillShape {
-        PillShape::new(self.vitamins[0].color, self.vitamins[1].color)
+        PillShape::new(self.vitamins[0].color, self.vitamins[1].color).with_effect(self.effect)
     }
 
     pub fn vitamins(&self) -> Vitamins {
         self.vitamins
     }
 
+    pub fn effect(&self) -> PillEffect {
+        self.effect
+    }
+
     pub fn available_wall_kicks(&self) -> &[(i32, i32)] {
         match self.rotation {
             Rotation::North | Rotation::South => WALL_KICKS_H_TO_V,
@@ -338,7 +456,33 @@ mod tests {
         assert_eq!(VirusColor::Yellow, 0.try_into().unwrap());
         assert_eq!(VirusColor::Blue, 1.try_into().unwrap());
         assert_eq!(VirusColor::Red, 2.try_into().unwrap());
-        let no_color: Result<VirusColor, ()> = 3.try_into();
+        assert_eq!(VirusColor::Green, 3.try_into().unwrap());
+        let no_color: Result<VirusColor, ()> = 4.try_into();
         assert!(no_color.is_err());
     }
+
+    #[test]
+    fn pills_of_the_palette() {
+        for four_colors in [false, true] {
+            let palette = VirusColor::palette(four_colors);
+            let pills = PillShape::all(four_colors);
+            assert_eq!(pills.len(), palette.len() * palette.len());
+            for pill in pills {
+                assert!(palette.contains(&pill.left_color()));
+                assert!(palette.contains(&pill.right_color()));
+            }
+        }
+        assert!(!PillShape::ALL.iter().any(|p| p.has_green()));
+    }
+
+    #[test]
+    fn power_up_pills() {
+        let bomb = PillShape::RB.with_effect(PillEffect::Bomb);
+        assert_ne!(bomb, PillShape::RB);
+        assert_eq!(bomb.without_effect(), PillShape::RB);
+        assert_eq!(Pill::new(bomb).shape(), bomb);
+
+        let lightning = PillShape::RB.with_effect(PillEffect::Lightning);
+        assert_eq!(lightning.without_effect(), PillShape::RR);
+    }
 }
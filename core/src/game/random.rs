@@ -0,0 +1,722 @@
+use crate::game::block::Block;
+use crate::game::bottle::BottleSize;
+use crate::game::event::ColoredBlock;
+use crate::game::geometry::BottlePoint;
+use crate::game::pill::{PillEffect, PillShape, VirusColor};
+use crate::game::seed::MAX_SEED;
+use rand::prelude::*;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use rand_chacha::{ChaCha8Rng, ChaChaRng};
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+pub const PEEK_SIZE: usize = 5;
+pub const MAX_BOTTLE_SEED_ATTEMPTS: usize = 100_000;
+pub const MIN_VIRUSES: u32 = 4;
+pub const MAX_VIRUSES: u32 = 99;
+/// Chance of each pill being a power-up when they're enabled
+pub const POWER_UP_CHANCE: f64 = 0.1;
+/// Marks an empty block of a layout
+const LAYOUT_EMPTY: char = '.';
+
+/// Viruses in a bottle at a virus level, 4 more every level up to the max
+pub fn viruses_of_level(virus_level: u32) -> u32 {
+    (virus_level * 4 + MIN_VIRUSES).min(MAX_VIRUSES)
+}
+
+/// Highest row viruses are placed on, bottles with more viruses fill higher
+fn max_virus_row(viruses: u32) -> u32 {
+    match viruses {
+        0..=60 => 6,
+        61..=68 => 5,
+        69..=76 => 4,
+        _ => 3,
+    }
+}
+
+/// Rows above this are kept clear of viruses, taller bottles keep the same gap above them
+fn min_virus_y(size: BottleSize, viruses: u32) -> u32 {
+    (max_virus_row(viruses) + size.height()).saturating_sub(BottleSize::CLASSIC.height())
+}
+
+type Seed = <ChaCha8Rng as SeedableRng>::Seed;
+
+fn random_color<R: Rng + ?Sized>(rng: &mut R, palette: &[VirusColor]) -> VirusColor {
+    palette[rng.gen_range(0..palette.len())]
+}
+
+fn random_pill<R: Rng + ?Sized>(rng: &mut R, palette: &[VirusColor]) -> PillShape {
+    let left = random_color(rng, palette);
+    PillShape::new(left, random_color(rng, palette))
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    strum::IntoStaticStr,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+pub enum RandomMode {
+    /// All pills placed in a shuffled "bag" and drawn until the bag is empty, after which a new bag is shuffled
+    #[strum(serialize = "bag")]
+    #[default]
+    Bag = 0,
+
+    /// Random pill every time
+    #[strum(serialize = "true")]
+    True = 1,
+}
+
+impl RandomMode {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+}
+
+/// Every player gets the same bottles & pills, from a fixed seed when given e.g. from a seed code
+pub fn random(count: usize, mode: RandomMode, seed: Option<u32>) -> Vec<GameRandom> {
+    let mut random_seed: Seed = Default::default();
+    thread_rng().fill(&mut random_seed);
+    (0..count)
+        .map(|_| match seed {
+            Some(seed) => GameRandom::from_u64_seed(seed as u64, mode),
+            None => GameRandom::from_seed(random_seed, mode),
+        })
+        .collect()
+}
+
+/// Seed of the daily challenge on some number of days since 1970-01-01,
+/// the same for everyone that plays on that day in UTC
+pub fn daily_seed(days: i64) -> u32 {
+    ChaCha8Rng::seed_from_u64(days as u64).gen_range(0..=MAX_SEED)
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct BottleSeed {
+    size: BottleSize,
+    four_colors: bool,
+    viruses: Vec<Option<VirusColor>>,
+    count: u32,
+}
+
+impl Default for BottleSeed {
+    fn default() -> Self {
+        Self::new(BottleSize::CLASSIC)
+    }
+}
+
+impl BottleSeed {
+    pub fn new(size: BottleSize) -> Self {
+        Self {
+            size,
+            four_colors: false,
+            viruses: vec![None; size.total_blocks()],
+            count: 0,
+        }
+    }
+
+    pub fn with_four_colors(mut self, four_colors: bool) -> Self {
+        self.four_colors = four_colors;
+        self
+    }
+
+    pub fn size(&self) -> BottleSize {
+        self.size
+    }
+
+    pub fn is_four_colors(&self) -> bool {
+        self.four_colors
+    }
+
+    pub fn virus(&self, point: BottlePoint) -> Option<VirusColor> {
+        self.get(point.x(), point.y())
+    }
+
+    /// Paints a virus into a block of the bottle or clears it, e.g. in the editor
+    pub fn paint(&mut self, point: BottlePoint, color: Option<VirusColor>) {
+        if !self.size.contains(point) {
+            return;
+        }
+        let index = self.index(point.x(), point.y());
+        match (self.viruses[index], color) {
+            (None, Some(_)) => self.count += 1,
+            (Some(_), None) => self.count -= 1,
+            _ => {}
+        }
+        self.viruses[index] = color;
+    }
+
+    /// Could the seeder have placed a virus of this color here: not in the gap at the top of the
+    /// bottle, nor three of a color in a line, nor two of a color a block apart
+    pub fn is_legal(&self, point: BottlePoint, color: VirusColor) -> bool {
+        let viruses = self.count + self.virus(point).is_none() as u32;
+        self.size.contains(point)
+            && viruses <= MAX_VIRUSES
+            && point.y() >= min_virus_y(self.size, viruses) as i32
+            && self
+                .get_available_colors(point.x(), point.y())
+                .contains(&color)
+    }
+
+    /// Viruses of the seed, in the order of the blocks of the bottle
+    pub fn viruses(&self) -> Vec<ColoredBlock> {
+        (0..self.size.height() as i32)
+            .flat_map(|y| (0..self.size.width() as i32).map(move |x| (x, y)))
+            .filter_map(|(x, y)| {
+                self.get(x, y).map(|color| ColoredBlock {
+                    position: BottlePoint::new(x, y),
+                    color,
+                    is_virus: true,
+                })
+            })
+            .collect()
+    }
+
+    pub fn into_blocks(self) -> Vec<Block> {
+        self.viruses
+            .into_iter()
+            .map(|c| match c {
+                Some(color) => Block::Virus(color),
+                None => Block::Empty,
+            })
+            .collect()
+    }
+
+    fn get(&self, x: i32, y: i32) -> Option<VirusColor> {
+        if self.size.contains(BottlePoint::new(x, y)) {
+            self.viruses[self.index(x, y)]
+        } else {
+            None
+        }
+    }
+
+    fn get_available_colors(&self, x: i32, y: i32) -> HashSet<VirusColor> {
+        let mut colors = HashSet::from_iter(VirusColor::palette(self.four_colors).iter().copied());
+
+        // 3-Consecutive Rule: viruses of the same color cannot occupy three consecutive cells in the same row or column
+        if let Some(color) = self.get(x - 1, y) {
+            if self.get(x + 1, y) == Some(color) {
+                colors.remove(&color);
+            }
+        }
+        if let Some(color) = self.get(x, y - 1) {
+            if self.get(x, y + 1) == Some(color) {
+                colors.remove(&color);
+            }
+        }
+
+        // 2-Away Rule: viruses of the same color cannot occupy cells at distance two that are on the same row or column
+        self.get(x, y - 2).map(|c| colors.remove(&c)); // top
+        self.get(x + 2, y).map(|c| colors.remove(&c)); // right
+        self.get(x, y + 2).map(|c| colors.remove(&c)); // bottom
+        self.get(x - 2, y).map(|c| colors.remove(&c)); // left
+
+        colors
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: VirusColor) {
+        let index = self.index(x, y);
+        assert!(self.viruses[index].is_none());
+        self.count += 1;
+        self.viruses[index] = Some(color);
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        (y * self.size.width() as i32 + x) as usize
+    }
+}
+
+impl Debug for BottleSeed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "   {}", "-".repeat(self.size.width() as usize))?;
+        for y in 0..self.size.height() {
+            write!(f, "{:02}|", y)?;
+            for x in 0..self.size.width() {
+                match self.get(x as i32, y as i32) {
+                    Some(color) => write!(f, "{}", color.to_char().to_ascii_uppercase())?,
+                    None => write!(f, " ")?,
+                }
+            }
+            writeln!(f, "|")?;
+        }
+        write!(f, "   {}", "-".repeat(self.size.width() as usize))
+    }
+}
+
+/// Layout of the viruses e.g. painted in the editor, a line per row of the bottle with `.` for
+/// an empty block or the color of a virus e.g. `..r..b.y`
+impl Display for BottleSeed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.size.height() as i32 {
+            for x in 0..self.size.width() as i32 {
+                let c = self.get(x, y).map_or(LAYOUT_EMPTY, |color| color.to_char());
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BottleSeed {
+    type Err = String;
+
+    /// Lines starting with `#` are comments, green viruses make it a four color layout
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect::<Vec<&str>>();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 || rows.iter().any(|row| row.chars().count() != width) {
+            return Err("layout rows must all be the same width".to_string());
+        }
+        if width < BottleSize::MIN_WIDTH as usize || rows.len() < BottleSize::MIN_HEIGHT as usize {
+            return Err(format!(
+                "layout must be at least {} wide & {} rows",
+                BottleSize::MIN_WIDTH,
+                BottleSize::MIN_HEIGHT
+            ));
+        }
+        let mut seed = Self::new(BottleSize::new(width as u32, rows.len() as u32));
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == LAYOUT_EMPTY {
+                    continue;
+                }
+                let color = VirusColor::from_char(c)
+                    .ok_or_else(|| format!("invalid virus '{}' on row {}", c, y + 1))?;
+                seed.four_colors |= color == VirusColor::Green;
+                seed.set(x as i32, y as i32, color);
+            }
+        }
+        Ok(seed)
+    }
+}
+
+#[derive(Clone)]
+pub struct GameRandom {
+    mode: RandomMode,
+    four_colors: bool,
+    power_ups: bool,
+    pill_rng: ChaChaRng,
+    bottle_rng: ChaChaRng,
+    /// a separate stream so power-ups leave the pills & bottles of a seed unchanged
+    power_up_rng: ChaChaRng,
+    /// a separate stream for the viruses that crawl in hard mode
+    crawl_rng: ChaChaRng,
+    /// a separate stream for the columns that garbage falls into
+    garbage_rng: ChaChaRng,
+    queue: VecDeque<PillShape>,
+}
+
+impl GameRandom {
+    pub fn from_seed(seed: Seed, mode: RandomMode) -> Self {
+        Self::new(ChaChaRng::from_seed(seed), mode)
+    }
+
+    pub fn from_u64_seed(seed: u64, mode: RandomMode) -> Self {
+        Self::new(ChaChaRng::seed_from_u64(seed), mode)
+    }
+
+    pub fn new(rng: ChaChaRng, mode: RandomMode) -> Self {
+        let mut power_up_rng = rng.clone();
+        power_up_rng.set_stream(1);
+        let mut crawl_rng = rng.clone();
+        crawl_rng.set_stream(2);
+        let mut garbage_rng = rng.clone();
+        garbage_rng.set_stream(3);
+        let mut result = Self {
+            mode,
+            four_colors: false,
+            power_ups: false,
+            pill_rng: rng.clone(),
+            bottle_rng: rng,
+            power_up_rng,
+            crawl_rng,
+            garbage_rng,
+            queue: VecDeque::new(),
+        };
+        result.fill_queue();
+        result
+    }
+
+    /// Draws green pills & viruses along with the classic colors, the upcoming pills are redrawn
+    /// from the start of the seed so this must be set before any bottle is seeded
+    pub fn with_four_colors(mut self, four_colors: bool) -> Self {
+        if four_colors != self.four_colors {
+            self.four_colors = four_colors;
+            self.pill_rng = self.bottle_rng.clone();
+            self.queue.clear();
+            self.fill_queue();
+        }
+        self
+    }
+
+    pub fn four_colors(&self) -> bool {
+        self.four_colors
+    }
+
+    /// Occasionally turns a pill into a power-up, the upcoming pills are rolled again
+    pub fn with_power_ups(mut self, power_ups: bool) -> Self {
+        if power_ups != self.power_ups {
+            self.power_ups = power_ups;
+            let queue = std::mem::take(&mut self.queue);
+            for shape in queue {
+                self.push(shape.without_effect());
+            }
+        }
+        self
+    }
+
+    pub fn power_ups(&self) -> bool {
+        self.power_ups
+    }
+
+    /// Source of the crawling viruses, so they crawl the same way every time from a seed
+    pub fn crawl_rng(&mut self) -> &mut ChaChaRng {
+        &mut self.crawl_rng
+    }
+
+    /// Source of the columns of garbage, so a game with garbage replays the same from its seed
+    pub fn garbage_rng(&mut self) -> &mut ChaChaRng {
+        &mut self.garbage_rng
+    }
+
+    fn push(&mut self, shape: PillShape) {
+        let shape = if self.power_ups && self.power_up_rng.gen_bool(POWER_UP_CHANCE) {
+            let effect = *PillEffect::POWER_UPS
+                .choose(&mut self.power_up_rng)
+                .unwrap();
+            shape.with_effect(effect)
+        } else {
+            shape
+        };
+        self.queue.push_back(shape);
+    }
+
+    fn fill_queue(&mut self) {
+        match self.mode {
+            RandomMode::True => {
+                let palette = VirusColor::palette(self.four_colors);
+                for _ in 0..PEEK_SIZE {
+                    let shape = random_pill(&mut self.pill_rng, palette);
+                    self.push(shape);
+                }
+            }
+            RandomMode::Bag => self.assert_bags(),
+        }
+    }
+
+    fn assert_bags(&mut self) {
+        let all = PillShape::all(self.four_colors);
+        while self.queue.len() <= PEEK_SIZE {
+            let bag = all
+                .choose_multiple(&mut self.pill_rng, all.len())
+                .cloned()
+                .collect::<Vec<PillShape>>();
+            for shape in bag {
+                self.push(shape);
+            }
+        }
+    }
+
+    pub fn peek(&self) -> [PillShape; PEEK_SIZE] {
+        self.queue
+            .iter()
+            .take(PEEK_SIZE)
+            .copied()
+            .collect::<Vec<PillShape>>()
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn next_pill(&mut self) -> PillShape {
+        match self.mode {
+            RandomMode::True => self.next_true(),
+            RandomMode::Bag => self.next_bag(),
+        }
+    }
+
+    fn next_true(&mut self) -> PillShape {
+        let pill = random_pill(&mut self.pill_rng, VirusColor::palette(self.four_colors));
+        self.push(pill);
+        self.queue.pop_front().unwrap()
+    }
+
+    fn next_bag(&mut self) -> PillShape {
+        let result = self.queue.pop_front().unwrap();
+        self.assert_bags();
+        result
+    }
+
+    pub fn bottle_seed(&mut self, virus_level: u32) -> Result<BottleSeed, String> {
+        self.bottle_seed_of_viruses(BottleSize::CLASSIC, viruses_of_level(virus_level))
+    }
+
+    /// A bottle of any size with an exact number of viruses, bypassing the virus level
+    pub fn bottle_seed_of_viruses(
+        &mut self,
+        size: BottleSize,
+        viruses: u32,
+    ) -> Result<BottleSeed, String> {
+        let viruses = viruses.clamp(MIN_VIRUSES, MAX_VIRUSES);
+        for _ in 0..MAX_BOTTLE_SEED_ATTEMPTS {
+            if let Some(seed) = self.try_bottle_seed(size, viruses) {
+                return Ok(seed);
+            }
+        }
+        Err(format!(
+            "failed to generate valid bottle after {} attempts",
+            MAX_BOTTLE_SEED_ATTEMPTS
+        ))
+    }
+
+    fn try_bottle_seed(&mut self, size: BottleSize, target: u32) -> Option<BottleSeed> {
+        let mut bottle = BottleSeed::new(size).with_four_colors(self.four_colors);
+        let palette = VirusColor::palette(self.four_colors);
+        let mut available = (min_virus_y(size, target)..size.height())
+            .flat_map(|y| (0..size.width()).map(move |x| BottlePoint::new(x as i32, y as i32)))
+            .collect::<Vec<BottlePoint>>();
+        available.shuffle(&mut self.bottle_rng);
+
+        for i in 0..target {
+            let point = available.pop()?;
+            let available_colors = bottle.get_available_colors(point.x(), point.y());
+            if available_colors.is_empty() {
+                continue;
+            }
+            // each color in turn then one at random keeps the colors balanced
+            let mut color = match palette.get(i as usize % (palette.len() + 1)) {
+                Some(&color) => color,
+                None => random_color(&mut self.bottle_rng, palette),
+            };
+            while !available_colors.contains(&color) {
+                color = color.next();
+            }
+            bottle.set(point.x(), point.y(), color);
+        }
+
+        if bottle.count < target {
+            None
+        } else {
+            Some(bottle)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bottle::{BOTTLE_HEIGHT, BOTTLE_WIDTH};
+
+    #[test]
+    fn daily_seed_is_stable_within_a_day() {
+        assert_eq!(daily_seed(20_089), daily_seed(20_089));
+        assert_ne!(daily_seed(20_089), daily_seed(20_090));
+        assert!((0..365).all(|day| daily_seed(20_089 + day) <= MAX_SEED));
+    }
+
+    #[test]
+    fn seeds_bottle_at_level_0() {
+        let mut source = GameRandom::from_u64_seed(123546, RandomMode::True);
+        let result = source
+            .bottle_seed(0)
+            .expect("should generate valid bottle for this seed");
+        assert_eq!(result.virus_count(), 4, "{:?}", result);
+    }
+
+    #[test]
+    fn seeds_bottle_with_exact_viruses() {
+        let mut source = GameRandom::from_u64_seed(123546, RandomMode::True);
+        let result = source
+            .bottle_seed_of_viruses(BottleSize::CLASSIC, 37)
+            .expect("should generate valid bottle for this seed");
+        assert_eq!(result.virus_count(), 37, "{:?}", result);
+        assert_eq!(result.viruses().len(), 37);
+    }
+
+    #[test]
+    fn seeds_wide_bottle() {
+        let size = BottleSize::new(12, 20);
+        let mut source = GameRandom::from_u64_seed(123546, RandomMode::True);
+        let result = source
+            .bottle_seed_of_viruses(size, MAX_VIRUSES)
+            .expect("should generate valid bottle for this seed");
+        assert_eq!(result.size(), size);
+        assert_eq!(result.virus_count(), MAX_VIRUSES as usize, "{:?}", result);
+        // the same gap is left above the viruses as in the classic bottle
+        for y in 0..7 {
+            for x in 0..12 {
+                assert_eq!(result.get(x, y), None, "{:?}", result);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_viruses_of_a_level_seed_the_same_bottle() {
+        let mut level = GameRandom::from_u64_seed(123546, RandomMode::True);
+        let mut exact = GameRandom::from_u64_seed(123546, RandomMode::True);
+        for virus_level in [0, 14, 15, 17, 20] {
+            let viruses = viruses_of_level(virus_level);
+            assert_eq!(
+                level.bottle_seed(virus_level).unwrap(),
+                exact
+                    .bottle_seed_of_viruses(BottleSize::CLASSIC, viruses)
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn seeds_bottle_with_99_viruses() {
+        let mut source = GameRandom::from_u64_seed(123546, RandomMode::True);
+        let result = source
+            .bottle_seed(30)
+            .expect("should generate valid bottle for this seed");
+        assert_eq!(result.virus_count(), 99, "{:?}", result);
+        println!("{:?}", result);
+
+        // revalidate all placements
+        for x in 0..BOTTLE_WIDTH as i32 {
+            for y in 0..BOTTLE_HEIGHT as i32 {
+                if let Some(color) = result.get(x, y) {
+                    assert!(result.get_available_colors(x, y).contains(&color));
+                }
+            }
+        }
+
+        // validate 3 in a row rule
+        for color in [VirusColor::Yellow, VirusColor::Red, VirusColor::Blue] {
+            // horizontal
+            for y in 0..BOTTLE_HEIGHT as i32 {
+                let mut count = 0;
+                for x in 0..BOTTLE_WIDTH as i32 {
+                    if result.get(x, y) == Some(color) {
+                        count += 1;
+                    } else {
+                        count = 0;
+                    }
+                    assert!(count < 3, "({}, {}) {:?}", x, y, result);
+                }
+            }
+
+            // vertical
+            for x in 0..BOTTLE_WIDTH as i32 {
+                let mut count = 0;
+                for y in 0..BOTTLE_HEIGHT as i32 {
+                    if result.get(x, y) == Some(color) {
+                        count += 1;
+                    } else {
+                        count = 0;
+                    }
+                    assert!(count < 3, "({}, {}) {:?}", x, y, result);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_seed_repeats_match() {
+        let mut first = random(2, RandomMode::Bag, Some(42));
+        let mut second = random(1, RandomMode::Bag, Some(42));
+        assert_eq!(first[0].peek(), second[0].peek());
+        assert_eq!(first[1].peek(), second[0].peek());
+        assert!(first[0].bottle_seed(10).unwrap() == second[0].bottle_seed(10).unwrap());
+    }
+
+    #[test]
+    fn seeds_four_colors() {
+        let mut source = GameRandom::from_u64_seed(123546, RandomMode::Bag).with_four_colors(true);
+        let result = source
+            .bottle_seed(30)
+            .expect("should generate valid bottle for this seed");
+        assert_eq!(result.virus_count(), 99, "{:?}", result);
+        for color in VirusColor::FOUR_COLORS {
+            let count = result.viruses.iter().filter(|c| **c == Some(color)).count();
+            assert!(count >= 10, "{:?} {:?}", color, result);
+        }
+
+        let bag = (0..PillShape::FOUR_COLORS.len())
+            .map(|_| source.next_pill())
+            .collect::<HashSet<PillShape>>();
+        assert_eq!(bag.len(), PillShape::FOUR_COLORS.len());
+    }
+
+    #[test]
+    fn classic_colors_by_default() {
+        let mut source = GameRandom::from_u64_seed(123546, RandomMode::True);
+        let result = source.bottle_seed(30).unwrap();
+        assert!(!result.viruses.contains(&Some(VirusColor::Green)));
+        assert!((0..100).all(|_| !source.next_pill().has_green()));
+    }
+
+    #[test]
+    fn power_ups_keep_the_colors_of_the_seed() {
+        let mut classic = GameRandom::from_u64_seed(123546, RandomMode::Bag);
+        let mut power_ups = GameRandom::from_u64_seed(123546, RandomMode::Bag).with_power_ups(true);
+        let mut effects = HashSet::new();
+        for _ in 0..200 {
+            let expected = classic.next_pill();
+            let shape = power_ups.next_pill();
+            effects.insert(shape.effect());
+            if shape.effect() != PillEffect::Lightning {
+                assert_eq!(shape.without_effect(), expected);
+            }
+        }
+        assert_eq!(effects.len(), PillEffect::POWER_UPS.len() + 1);
+        assert!(classic.bottle_seed(10).unwrap() == power_ups.bottle_seed(10).unwrap());
+    }
+
+    #[test]
+    fn paints_layout() {
+        let mut seed = BottleSeed::new(BottleSize::CLASSIC);
+        let point = BottlePoint::new(2, 15);
+        assert!(seed.is_legal(point, VirusColor::Red));
+        seed.paint(point, Some(VirusColor::Red));
+        assert_eq!(seed.virus(point), Some(VirusColor::Red));
+        // two of a color a block apart, the gap at the top & green in a three color bottle
+        assert!(!seed.is_legal(BottlePoint::new(4, 15), VirusColor::Red));
+        assert!(seed.is_legal(BottlePoint::new(4, 15), VirusColor::Blue));
+        assert!(!seed.is_legal(BottlePoint::new(4, 0), VirusColor::Blue));
+        assert!(!seed.is_legal(BottlePoint::new(4, 15), VirusColor::Green));
+
+        seed.paint(BottlePoint::new(0, 14), Some(VirusColor::Green));
+        let parsed = seed.to_string().parse::<BottleSeed>().unwrap();
+        assert_eq!(parsed.viruses(), seed.viruses());
+        assert!(parsed.four_colors);
+
+        seed.paint(point, None);
+        assert_eq!(seed.virus(point), None);
+        assert_eq!(seed.viruses().len(), 1);
+        assert!("..r\n..".parse::<BottleSeed>().is_err());
+        assert!("..r".parse::<BottleSeed>().is_err());
+        assert!(["....\n"; 7].concat().parse::<BottleSeed>().is_err());
+        let invalid_virus = ["....\n"; 7].concat() + "..x.";
+        assert_eq!(
+            invalid_virus.parse::<BottleSeed>().err(),
+            Some("invalid virus 'x' on row 8".to_string())
+        );
+        let valid = ["....\n"; 7].concat() + "..r.";
+        assert_eq!(valid.parse::<BottleSeed>().unwrap().viruses().len(), 1);
+    }
+
+    trait BottleSeedTestHarness {
+        fn virus_count(&self) -> usize;
+    }
+
+    impl BottleSeedTestHarness for BottleSeed {
+        fn virus_count(&self) -> usize {
+            self.viruses.iter().filter(|x| x.is_some()).count()
+        }
+    }
+}
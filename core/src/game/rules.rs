@@ -0,0 +1,668 @@
+use crate::game::bottle::BottleSize;
+use crate::game::random::{RandomMode, MAX_VIRUSES, MIN_VIRUSES, PEEK_SIZE};
+use crate::game::ruleset::{Features, RuleSet};
+use crate::game::GameSpeed;
+use num_format::{Locale, ToFormattedString};
+use rand::{thread_rng, Rng};
+use strum::IntoEnumIterator;
+
+pub const MAX_VIRUS_LEVEL: u32 = 30;
+/// Most players supported in a match, some platforms allow fewer
+pub const MAX_MATCH_PLAYERS: usize = 2;
+/// Most wins a vs. series can be played to
+pub const MAX_SERIES_WINS: u32 = 5;
+/// Pills shown by default, the pill in the dr's hand & 2 more
+pub const DEFAULT_PEEK_COUNT: usize = 3;
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, strum::IntoStaticStr, strum::EnumIter, strum::EnumString,
+)]
+pub enum MatchThemes {
+    /// Run themes in order, switching at the next level
+    #[strum(serialize = "all")]
+    All = 0,
+
+    #[strum(serialize = "nes")]
+    Nes = 1,
+
+    #[strum(serialize = "snes")]
+    Snes = 2,
+
+    #[strum(serialize = "n64")]
+    N64 = 3,
+
+    #[strum(serialize = "gameboy")]
+    GameBoy = 4,
+
+    #[strum(serialize = "arcade")]
+    Arcade = 5,
+
+    #[strum(serialize = "particle")]
+    Particle = 6,
+}
+
+impl MatchThemes {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+    pub fn count() -> usize {
+        Self::iter().filter(|i| *i as usize > 0).count()
+    }
+    /// Index of a single built-in theme in the order they are played, None for all themes
+    pub fn index(&self) -> Option<usize> {
+        (*self as usize).checked_sub(1)
+    }
+}
+
+/// Order that themes are switched in every level when playing all themes
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, strum::IntoStaticStr, strum::EnumIter, strum::EnumString,
+)]
+pub enum ThemeOrder {
+    #[strum(serialize = "in order")]
+    InOrder = 0,
+
+    #[strum(serialize = "reverse")]
+    Reverse = 1,
+
+    /// Random theme every level, never the same one twice in a row
+    #[strum(serialize = "random")]
+    Random = 2,
+
+    /// Sequence of theme indices from the config, in order when it is empty
+    #[strum(serialize = "custom")]
+    Custom = 3,
+}
+
+impl ThemeOrder {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+
+    /// Index of the theme to start a match with out of count themes
+    pub fn first(&self, count: usize, sequence: &[usize]) -> usize {
+        match self {
+            ThemeOrder::Custom => sequence.iter().copied().find(|&i| i < count).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Index of the theme to switch to from current out of count themes
+    pub fn next(&self, current: usize, count: usize, sequence: &[usize]) -> usize {
+        match self {
+            _ if count < 2 => 0,
+            ThemeOrder::InOrder => (current + 1) % count,
+            ThemeOrder::Reverse => (current + count - 1) % count,
+            ThemeOrder::Random => (current + thread_rng().gen_range(1..count)) % count,
+            ThemeOrder::Custom => {
+                let sequence = sequence
+                    .iter()
+                    .copied()
+                    .filter(|&i| i < count)
+                    .collect::<Vec<_>>();
+                match sequence.iter().position(|&i| i == current) {
+                    Some(position) => sequence[(position + 1) % sequence.len()],
+                    None if sequence.is_empty() => (current + 1) % count,
+                    None => sequence[0],
+                }
+            }
+        }
+    }
+}
+
+/// Game music tracks, as in the music select of the original games
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MusicTrack {
+    Fever,
+    Chill,
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, strum::IntoStaticStr, strum::EnumIter, strum::EnumString,
+)]
+pub enum MusicSelect {
+    #[strum(serialize = "fever")]
+    Fever = 0,
+
+    #[strum(serialize = "chill")]
+    Chill = 1,
+
+    /// Alternate fever & chill, switching at the next level
+    #[strum(serialize = "cycle")]
+    Cycle = 2,
+
+    /// Random track every level
+    #[strum(serialize = "random")]
+    Random = 3,
+
+    #[strum(serialize = "off")]
+    Off = 4,
+}
+
+impl MusicSelect {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+
+    /// Track to play after some number of completed levels, None when the music is off
+    pub fn track(&self, completed_levels: u32) -> Option<MusicTrack> {
+        match self {
+            MusicSelect::Fever => Some(MusicTrack::Fever),
+            MusicSelect::Chill => Some(MusicTrack::Chill),
+            MusicSelect::Cycle if completed_levels.is_multiple_of(2) => Some(MusicTrack::Fever),
+            MusicSelect::Cycle => Some(MusicTrack::Chill),
+            MusicSelect::Random if rand::random() => Some(MusicTrack::Fever),
+            MusicSelect::Random => Some(MusicTrack::Chill),
+            MusicSelect::Off => None,
+        }
+    }
+}
+
+/// How the garbage sent by a combo lands in the opponent's bottle
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum::IntoStaticStr,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+pub enum GarbageStyle {
+    /// A single block for each color sent, scattered along the top row
+    #[default]
+    #[strum(serialize = "scattered")]
+    Scattered = 0,
+
+    /// A whole row but for one gap, filled with the colors sent
+    #[strum(serialize = "flush")]
+    Flush = 1,
+}
+
+impl GarbageStyle {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+}
+
+/// Size of every player's bottle
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum::IntoStaticStr,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+pub enum BottleShape {
+    /// 8 wide & 16 high as in the original games
+    #[default]
+    #[strum(serialize = "classic")]
+    Classic = 0,
+
+    #[strum(serialize = "wide")]
+    Wide = 1,
+
+    #[strum(serialize = "tall")]
+    Tall = 2,
+}
+
+impl BottleShape {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+
+    pub fn size(&self) -> BottleSize {
+        match self {
+            BottleShape::Classic => BottleSize::CLASSIC,
+            BottleShape::Wide => BottleSize::new(12, 16),
+            BottleShape::Tall => BottleSize::new(8, 20),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchRules {
+    /// Endless game, player with the highest score at the end wins
+    Marathon,
+    /// Race to a certain number of virus levels
+    LevelSprint { levels: u32 },
+    /// Race to some score
+    ScoreSprint { score: u32 },
+    /// Race through all of the themes, one per virus level
+    ThemeSprint,
+}
+
+impl MatchRules {
+    pub const ONE_LEVEL_SPRINT: Self = Self::LevelSprint { levels: 1 };
+    pub const DEFAULT_SCORE_SPRINT: Self = Self::ScoreSprint { score: 10_000 };
+
+    pub const VS_MODES: [Self; 3] = [
+        Self::ONE_LEVEL_SPRINT,
+        Self::ThemeSprint,
+        Self::DEFAULT_SCORE_SPRINT,
+    ];
+    pub const SINGLE_PLAYER_MODES: [Self; 4] = [
+        Self::Marathon,
+        Self::ONE_LEVEL_SPRINT,
+        Self::ThemeSprint,
+        Self::DEFAULT_SCORE_SPRINT,
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            MatchRules::Marathon => "marathon".to_string(),
+            MatchRules::LevelSprint { levels } => format!("{} level sprint", levels),
+            MatchRules::ScoreSprint { score } => {
+                format!("{} point sprint", score.to_formatted_string(&Locale::en))
+            }
+            MatchRules::ThemeSprint => "theme sprint".to_string(),
+        }
+    }
+
+    pub fn allow_manual_theme_change(&self) -> bool {
+        self != &Self::ThemeSprint
+    }
+
+    pub fn default_by_players(players: u32) -> Self {
+        if players == 1 {
+            MatchRules::Marathon
+        } else {
+            MatchRules::ONE_LEVEL_SPRINT
+        }
+    }
+}
+
+/// Starting virus level & speed of a player, which differ between handicapped vs. players
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Handicap {
+    virus_level: u32,
+    speed: GameSpeed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameConfig {
+    players: u32,
+    handicaps: [Handicap; MAX_MATCH_PLAYERS],
+    themes: MatchThemes,
+    rules: MatchRules,
+    random: RandomMode,
+    garbage_cancellation: bool,
+    garbage_style: GarbageStyle,
+    rule_set: RuleSet,
+    features: Features,
+    peek_count: usize,
+    exact_viruses: Option<u32>,
+    bottle: BottleShape,
+    coop: bool,
+    four_colors: bool,
+    power_ups: bool,
+    crawling_viruses: bool,
+    adaptive_speed: bool,
+    rewind: bool,
+    seed: Option<u32>,
+    music: MusicSelect,
+    theme_order: ThemeOrder,
+    series_wins: u32,
+}
+
+impl GameConfig {
+    pub fn new(
+        players: u32,
+        virus_level: u32,
+        speed: GameSpeed,
+        themes: MatchThemes,
+        rules: MatchRules,
+        random: RandomMode,
+    ) -> Self {
+        Self {
+            players,
+            handicaps: [Handicap { virus_level, speed }; MAX_MATCH_PLAYERS],
+            themes,
+            rules,
+            random,
+            garbage_cancellation: false,
+            garbage_style: GarbageStyle::Scattered,
+            rule_set: RuleSet::Modern,
+            features: Features::ALL,
+            peek_count: DEFAULT_PEEK_COUNT,
+            exact_viruses: None,
+            bottle: BottleShape::Classic,
+            coop: false,
+            four_colors: false,
+            power_ups: false,
+            crawling_viruses: false,
+            adaptive_speed: false,
+            rewind: false,
+            seed: None,
+            music: MusicSelect::Fever,
+            theme_order: ThemeOrder::InOrder,
+            series_wins: 1,
+        }
+    }
+
+    pub fn players(&self) -> u32 {
+        self.players
+    }
+
+    pub fn is_single_player(&self) -> bool {
+        self.players == 1
+    }
+
+    /// Speed of the first player, which sets the scene shared by all players
+    pub fn speed(&self) -> GameSpeed {
+        self.handicaps[0].speed
+    }
+    pub fn player_virus_level(&self, player: u32) -> u32 {
+        self.handicaps[player as usize].virus_level
+    }
+    pub fn player_speed(&self, player: u32) -> GameSpeed {
+        self.handicaps[player as usize].speed
+    }
+    pub fn themes(&self) -> MatchThemes {
+        if self.rules == MatchRules::ThemeSprint {
+            MatchThemes::All
+        } else {
+            self.themes
+        }
+    }
+    pub fn rules(&self) -> MatchRules {
+        self.rules
+    }
+    pub fn random(&self) -> RandomMode {
+        self.random
+    }
+    /// Outgoing garbage cancels buffered incoming garbage, vs. matches only
+    pub fn garbage_cancellation(&self) -> bool {
+        self.garbage_cancellation && !self.is_single_player()
+    }
+    /// Garbage sent as scattered blocks or as rows, vs. matches only
+    pub fn garbage_style(&self) -> GarbageStyle {
+        if self.is_single_player() {
+            GarbageStyle::Scattered
+        } else {
+            self.garbage_style
+        }
+    }
+    /// Modern or classic NES handling of every player's pills
+    /// Zen is single player only, a vs. game falls back to the modern rules
+    pub fn rule_set(&self) -> RuleSet {
+        if self.rule_set == RuleSet::Zen && !self.is_single_player() {
+            RuleSet::Modern
+        } else {
+            self.rule_set
+        }
+    }
+    /// Hold, hard drop, ghost & lock delay, each on or off
+    pub fn features(&self) -> Features {
+        self.features
+    }
+    /// Upcoming pills shown to every player, 0 hides them all
+    pub fn peek_count(&self) -> usize {
+        self.peek_count
+    }
+    /// Viruses in every bottle of every player, None for the number of their virus level
+    pub fn exact_viruses(&self) -> Option<u32> {
+        self.exact_viruses
+    }
+    pub fn bottle(&self) -> BottleShape {
+        self.bottle
+    }
+    /// Two players share a single bottle, each with their own pill, queue & hold
+    pub fn coop(&self) -> bool {
+        self.coop && self.is_single_player()
+    }
+    /// Size of every bottle, a co-op bottle is twice as wide with a lane for each player
+    pub fn bottle_size(&self) -> BottleSize {
+        let size = self.bottle.size();
+        if self.coop() {
+            BottleSize::new(size.width() * 2, size.height())
+        } else {
+            size
+        }
+    }
+    /// Green viruses & pills join the classic three colors
+    pub fn four_colors(&self) -> bool {
+        self.four_colors
+    }
+    /// Occasional bomb, lightning & heavy pills
+    pub fn power_ups(&self) -> bool {
+        self.power_ups
+    }
+    /// Hard mode, viruses crawl into neighbouring empty blocks every few seconds
+    pub fn crawling_viruses(&self) -> bool {
+        self.crawling_viruses
+    }
+    /// The speed level of every player ramps up faster while they are doing well
+    pub fn adaptive_speed(&self) -> bool {
+        self.adaptive_speed
+    }
+    /// Pills can be rewound, single player practice only
+    pub fn rewind(&self) -> bool {
+        self.rewind && self.is_single_player() && !self.coop()
+    }
+    /// Fixed seed of the bottles & pills, None for a random match
+    pub fn seed(&self) -> Option<u32> {
+        self.seed
+    }
+    pub fn music(&self) -> MusicSelect {
+        self.music
+    }
+    pub fn theme_order(&self) -> ThemeOrder {
+        self.theme_order
+    }
+    /// Wins needed to take a vs. series e.g. 3 for first to 3, always 1 for single player
+    pub fn series_wins(&self) -> u32 {
+        if self.is_single_player() {
+            1
+        } else {
+            self.series_wins
+        }
+    }
+
+    pub fn set_players(&mut self, players: u32) {
+        self.players = players;
+    }
+    /// Sets the virus level of every player
+    pub fn set_virus_level(&mut self, virus_level: u32) {
+        for player in 0..MAX_MATCH_PLAYERS {
+            self.set_player_virus_level(player as u32, virus_level);
+        }
+    }
+    /// Sets the speed of every player
+    pub fn set_speed(&mut self, speed: GameSpeed) {
+        for player in 0..MAX_MATCH_PLAYERS {
+            self.set_player_speed(player as u32, speed);
+        }
+    }
+    pub fn set_player_virus_level(&mut self, player: u32, virus_level: u32) {
+        self.handicaps[player as usize].virus_level = virus_level.min(MAX_VIRUS_LEVEL);
+    }
+    pub fn set_player_speed(&mut self, player: u32, speed: GameSpeed) {
+        self.handicaps[player as usize].speed = speed;
+    }
+    pub fn set_themes(&mut self, themes: MatchThemes) {
+        self.themes = themes;
+    }
+    pub fn set_rules(&mut self, rules: MatchRules) {
+        self.rules = rules;
+    }
+    pub fn set_random(&mut self, random: RandomMode) {
+        self.random = random;
+    }
+    pub fn set_garbage_cancellation(&mut self, garbage_cancellation: bool) {
+        self.garbage_cancellation = garbage_cancellation;
+    }
+    pub fn set_garbage_style(&mut self, garbage_style: GarbageStyle) {
+        self.garbage_style = garbage_style;
+    }
+    /// Sets the rule set along with its features, which can then be tuned one at a time
+    pub fn set_rule_set(&mut self, rule_set: RuleSet) {
+        self.rule_set = rule_set;
+        self.features = rule_set.features();
+    }
+    pub fn set_feature(&mut self, feature: Features, enabled: bool) {
+        self.features.set(feature, enabled);
+    }
+    pub fn set_peek_count(&mut self, peek_count: usize) {
+        self.peek_count = peek_count.min(PEEK_SIZE);
+    }
+    pub fn set_exact_viruses(&mut self, exact_viruses: Option<u32>) {
+        self.exact_viruses = exact_viruses.map(|v| v.clamp(MIN_VIRUSES, MAX_VIRUSES));
+    }
+    pub fn set_bottle(&mut self, bottle: BottleShape) {
+        self.bottle = bottle;
+    }
+    pub fn set_coop(&mut self, coop: bool) {
+        self.coop = coop;
+    }
+    pub fn set_four_colors(&mut self, four_colors: bool) {
+        self.four_colors = four_colors;
+    }
+    pub fn set_power_ups(&mut self, power_ups: bool) {
+        self.power_ups = power_ups;
+    }
+    pub fn set_crawling_viruses(&mut self, crawling_viruses: bool) {
+        self.crawling_viruses = crawling_viruses;
+    }
+    pub fn set_adaptive_speed(&mut self, adaptive_speed: bool) {
+        self.adaptive_speed = adaptive_speed;
+    }
+    pub fn set_rewind(&mut self, rewind: bool) {
+        self.rewind = rewind;
+    }
+    pub fn set_seed(&mut self, seed: Option<u32>) {
+        self.seed = seed;
+    }
+    pub fn set_music(&mut self, music: MusicSelect) {
+        self.music = music;
+    }
+    pub fn set_theme_order(&mut self, theme_order: ThemeOrder) {
+        self.theme_order = theme_order;
+    }
+    pub fn set_series_wins(&mut self, series_wins: u32) {
+        self.series_wins = series_wins.clamp(1, MAX_SERIES_WINS);
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self::new(
+            1,
+            0,
+            GameSpeed::Medium,
+            MatchThemes::All,
+            MatchRules::Marathon,
+            RandomMode::default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_count() {
+        assert_eq!(MatchThemes::count(), 6);
+    }
+
+    #[test]
+    fn handicap_per_player() {
+        let mut config = GameConfig::default();
+        config.set_virus_level(10);
+        config.set_player_virus_level(1, 50);
+        config.set_player_speed(1, GameSpeed::High);
+
+        assert_eq!(config.player_virus_level(0), 10);
+        assert_eq!(config.player_virus_level(1), MAX_VIRUS_LEVEL);
+        assert_eq!(config.player_speed(0), GameSpeed::Medium);
+        assert_eq!(config.player_speed(1), GameSpeed::High);
+        assert_eq!(config.speed(), GameSpeed::Medium);
+    }
+
+    #[test]
+    fn music_track_per_level() {
+        let cycle = (0..4)
+            .map(|level| MusicSelect::Cycle.track(level))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cycle,
+            [MusicTrack::Fever, MusicTrack::Chill, MusicTrack::Fever, MusicTrack::Chill]
+                .map(Some)
+        );
+        assert_eq!(MusicSelect::Chill.track(3), Some(MusicTrack::Chill));
+        assert!(MusicSelect::Random.track(0).is_some());
+        assert_eq!(MusicSelect::Off.track(0), None);
+    }
+
+    #[test]
+    fn next_theme_by_order() {
+        assert_eq!(ThemeOrder::InOrder.next(5, 6, &[]), 0);
+        assert_eq!(ThemeOrder::Reverse.next(0, 6, &[]), 5);
+        assert_eq!(ThemeOrder::Custom.first(6, &[9, 3, 1]), 3);
+        assert_eq!(ThemeOrder::Custom.next(3, 6, &[9, 3, 1]), 1);
+        assert_eq!(ThemeOrder::Custom.next(1, 6, &[9, 3, 1]), 3);
+        assert_eq!(ThemeOrder::Custom.next(2, 6, &[3, 1]), 3);
+        assert_eq!(ThemeOrder::Custom.next(2, 6, &[]), 3);
+        for current in 0..6 {
+            assert_ne!(ThemeOrder::Random.next(current, 6, &[]), current);
+        }
+        assert_eq!(MatchThemes::All.index(), None);
+        assert_eq!(MatchThemes::Snes.index(), Some(1));
+    }
+
+    #[test]
+    fn series_wins_in_vs_only() {
+        let mut config = GameConfig::default();
+        config.set_series_wins(3);
+        assert_eq!(config.series_wins(), 1);
+        config.set_players(2);
+        assert_eq!(config.series_wins(), 3);
+        config.set_series_wins(99);
+        assert_eq!(config.series_wins(), MAX_SERIES_WINS);
+    }
+    #[test]
+    fn rule_set_resets_features() {
+        let mut config = GameConfig::default();
+        config.set_rule_set(RuleSet::Classic);
+        config.set_feature(Features::GHOST, true);
+        assert_eq!(config.features(), Features::GHOST);
+        config.set_rule_set(RuleSet::Modern);
+        assert_eq!(config.features(), Features::ALL);
+    }
+    #[test]
+    fn zen_single_player_only() {
+        let mut config = GameConfig::default();
+        config.set_rule_set(RuleSet::Zen);
+        assert_eq!(config.rule_set(), RuleSet::Zen);
+        config.set_players(2);
+        assert_eq!(config.rule_set(), RuleSet::Modern);
+    }
+    #[test]
+    fn coop_bottle_twice_as_wide() {
+        let mut config = GameConfig::default();
+        config.set_rewind(true);
+        config.set_coop(true);
+        assert_eq!(config.bottle_size(), BottleSize::new(16, 16));
+        assert!(!config.rewind());
+        config.set_players(2);
+        assert!(!config.coop());
+        assert_eq!(config.bottle_size(), BottleSize::CLASSIC);
+    }
+    #[test]
+    fn exact_viruses_clamped() {
+        let mut config = GameConfig::default();
+        config.set_exact_viruses(Some(1));
+        assert_eq!(config.exact_viruses(), Some(MIN_VIRUSES));
+        config.set_exact_viruses(Some(150));
+        assert_eq!(config.exact_viruses(), Some(MAX_VIRUSES));
+        config.set_exact_viruses(None);
+        assert_eq!(config.exact_viruses(), None);
+    }
+}
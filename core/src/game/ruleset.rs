@@ -0,0 +1,207 @@
+use crate::game::GameSpeed;
+use std::ops::BitOr;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+/// One frame of the NTSC NES, which runs slightly faster than 60Hz
+const NES_FRAME: Duration = Duration::from_nanos(16_639_267);
+
+/// Frames a pill takes to fall one row on the NES, by speed index
+const NES_GRAVITY_FRAMES: [u32; 81] = [
+    70, 68, 66, 64, 62, 60, 58, 56, 54, 52, 50, 48, 46, 44, 42, 40, 38, 36, 34, 32, 30, 28, 26, 24,
+    22, 20, 19, 18, 17, 16, 15, 14, 13, 12, 11, 10, 10, 9, 9, 8, 8, 7, 7, 6, 6, 6, 6, 6, 6, 6, 6, 6,
+    6, 6, 6, 5, 5, 5, 5, 5, 4, 4, 4, 4, 4, 3, 3, 3, 3, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1,
+];
+
+/// Holding down on the NES drops the pill a row every other frame
+const NES_SOFT_DROP_FRAMES: u32 = 2;
+
+/// Zen climbs the speed levels at a fraction of the modern pace
+const ZEN_SPEED_LEVEL_DIVISOR: usize = 2;
+
+/// How pills fall, lock & what the player is allowed to do with them
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    strum::IntoStaticStr,
+    strum::EnumIter,
+    strum::EnumString,
+)]
+pub enum RuleSet {
+    /// Modern gravity with every feature
+    #[default]
+    #[strum(serialize = "modern")]
+    Modern = 0,
+
+    /// Frame accurate NES gravity without any modern features
+    #[strum(serialize = "classic")]
+    Classic = 1,
+
+    /// Relaxed single player, slower gravity & topping out clears the top of the bottle
+    /// for a score penalty rather than ending the game
+    #[strum(serialize = "zen")]
+    Zen = 2,
+}
+
+impl RuleSet {
+    pub fn names() -> Vec<&'static str> {
+        Self::iter().map(|e| e.into()).collect()
+    }
+
+    /// Features that come with the rule set, each can be tuned afterwards
+    pub fn features(&self) -> Features {
+        match self {
+            RuleSet::Modern | RuleSet::Zen => Features::ALL,
+            RuleSet::Classic => Features::NONE,
+        }
+    }
+
+    /// Topping out clears the top of the bottle rather than ending the game
+    pub fn forgives_top_out(&self) -> bool {
+        *self == RuleSet::Zen
+    }
+
+    /// Time taken for a pill to fall one row
+    pub fn gravity(&self, speed: GameSpeed, speed_level: usize) -> Duration {
+        match self {
+            RuleSet::Modern => speed.duration_of_level(speed_level),
+            RuleSet::Classic => NES_FRAME * NES_GRAVITY_FRAMES[speed.speed_index(speed_level)],
+            RuleSet::Zen => speed.duration_of_level(speed_level / ZEN_SPEED_LEVEL_DIVISOR),
+        }
+    }
+
+    /// Time taken for a pill to fall one row while soft dropping
+    pub fn soft_drop_gravity(
+        &self,
+        speed: GameSpeed,
+        speed_level: usize,
+        soft_drop_factor: u32,
+    ) -> Duration {
+        let gravity = self.gravity(speed, speed_level);
+        match self {
+            RuleSet::Modern | RuleSet::Zen => {
+                (gravity / soft_drop_factor).max(speed.min_drop_duration())
+            }
+            RuleSet::Classic => gravity.min(NES_FRAME * NES_SOFT_DROP_FRAMES),
+        }
+    }
+}
+
+/// Optional gameplay features as a set of bit flags
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Features(u8);
+
+impl Features {
+    pub const NONE: Self = Self(0);
+    pub const HOLD: Self = Self(1 << 0);
+    pub const HARD_DROP: Self = Self(1 << 1);
+    /// Shadow of where the pill will land
+    pub const GHOST: Self = Self(1 << 2);
+    /// A landed pill waits before locking & can be moved or rotated in the meantime,
+    /// otherwise it locks as soon as it cannot fall
+    pub const LOCK_DELAY: Self = Self(1 << 3);
+    /// Points for each row a pill is soft or hard dropped, scored as it locks
+    pub const DROP_SCORE: Self = Self(1 << 4);
+    pub const ALL: Self = Self(
+        Self::HOLD.0 | Self::HARD_DROP.0 | Self::GHOST.0 | Self::LOCK_DELAY.0 | Self::DROP_SCORE.0,
+    );
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn set(&mut self, other: Self, enabled: bool) {
+        if enabled {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for Features {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::SPEED_TABLE;
+
+    #[test]
+    fn features_set_and_contains() {
+        let mut features = Features::HOLD | Features::GHOST;
+        assert!(features.contains(Features::HOLD));
+        assert!(!features.contains(Features::HARD_DROP));
+        assert!(!features.contains(Features::HOLD | Features::HARD_DROP));
+
+        features.set(Features::HARD_DROP, true);
+        features.set(Features::HOLD, false);
+        assert_eq!(features, Features::HARD_DROP | Features::GHOST);
+    }
+
+    #[test]
+    fn rule_set_features() {
+        assert_eq!(RuleSet::Modern.features(), Features::ALL);
+        assert!(!RuleSet::Classic.features().contains(Features::LOCK_DELAY));
+    }
+
+    #[test]
+    fn zen_gravity_climbs_slower() {
+        assert_eq!(
+            RuleSet::Zen.gravity(GameSpeed::Medium, 0),
+            RuleSet::Modern.gravity(GameSpeed::Medium, 0)
+        );
+        assert_eq!(
+            RuleSet::Zen.gravity(GameSpeed::Medium, 20),
+            RuleSet::Modern.gravity(GameSpeed::Medium, 10)
+        );
+        assert!(RuleSet::Zen.forgives_top_out());
+        assert!(!RuleSet::Modern.forgives_top_out());
+    }
+
+    #[test]
+    fn classic_gravity_in_nes_frames() {
+        assert_eq!(RuleSet::Classic.gravity(GameSpeed::Low, 0), NES_FRAME * 40);
+        assert_eq!(RuleSet::Classic.gravity(GameSpeed::High, 0), NES_FRAME * 14);
+        assert_eq!(
+            RuleSet::Classic.gravity(GameSpeed::High, GameSpeed::MAX_LEVEL),
+            NES_FRAME
+        );
+    }
+
+    #[test]
+    fn classic_gravity_matches_modern_frame_counts() {
+        let frame_60hz = Duration::from_secs(1) / 60;
+        for (duration, frames) in SPEED_TABLE.iter().zip(NES_GRAVITY_FRAMES) {
+            let modern_frames = (duration.as_secs_f64() / frame_60hz.as_secs_f64()).round();
+            assert_eq!(modern_frames as u32, frames);
+        }
+    }
+
+    #[test]
+    fn classic_soft_drop_every_other_frame() {
+        assert_eq!(
+            RuleSet::Classic.soft_drop_gravity(GameSpeed::Low, 0, 20),
+            NES_FRAME * 2
+        );
+        assert_eq!(
+            RuleSet::Classic.soft_drop_gravity(GameSpeed::High, GameSpeed::MAX_LEVEL, 20),
+            NES_FRAME
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Short alphanumeric codes for match seeds, so players can share & race the same game
+
+use rand::{thread_rng, Rng};
+
+pub const SEED_CODE_LENGTH: usize = 6;
+/// Crockford base 32, without the easily confused I, L, O & U
+pub const SEED_ALPHABET: [char; 32] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J',
+    'K', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'V', 'W', 'X', 'Y', 'Z',
+];
+const BITS_PER_CHAR: usize = 5;
+/// Every seed that can be written as a code
+pub const MAX_SEED: u32 = (1 << (BITS_PER_CHAR * SEED_CODE_LENGTH)) - 1;
+
+pub fn random_seed() -> u32 {
+    thread_rng().gen_range(0..=MAX_SEED)
+}
+
+/// Most significant character first
+pub fn encode(seed: u32) -> String {
+    (0..SEED_CODE_LENGTH)
+        .rev()
+        .map(|i| SEED_ALPHABET[(seed >> (i * BITS_PER_CHAR)) as usize & 0x1f])
+        .collect()
+}
+
+/// Case insensitive, reads the confused characters O as 0 and I & L as 1
+pub fn decode(code: &str) -> Result<u32, String> {
+    let code = code.trim();
+    if code.chars().count() != SEED_CODE_LENGTH {
+        return Err(format!(
+            "seed must be {} characters: {}",
+            SEED_CODE_LENGTH, code
+        ));
+    }
+    code.chars().try_fold(0, |seed, c| {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let value = SEED_ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or_else(|| format!("invalid seed character: {}", c))?;
+        Ok((seed << BITS_PER_CHAR) | value as u32)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for seed in [0, 1, 12345, MAX_SEED] {
+            let code = encode(seed);
+            assert_eq!(code.len(), SEED_CODE_LENGTH);
+            assert_eq!(decode(&code), Ok(seed));
+        }
+        assert_eq!(encode(MAX_SEED), "ZZZZZZ");
+    }
+
+    #[test]
+    fn decodes_confused_characters() {
+        assert_eq!(decode("o0illz"), decode("00111Z"));
+    }
+
+    #[test]
+    fn rejects_invalid_codes() {
+        assert!(decode("ABC").is_err());
+        assert!(decode("ABCDEFG").is_err());
+        assert!(decode("ABCDE!").is_err());
+        assert!(decode("ABCDEU").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Matches played by the demo bot without a window, audio or textures.
+//! Fast enough to check the rules in CI or to simulate thousands of games for bot authors.
+
+use crate::demo::DemoPlayer;
+use crate::game::event::GameEvent;
+use crate::player::Match;
+use std::time::Duration;
+
+/// Fixed step of a 60hz display
+pub const STEP: Duration = Duration::from_nanos(16_666_667);
+/// Marathon matches between good bots may never end
+const MAX_MATCH_TIME: Duration = Duration::from_secs(60 * 60);
+
+pub struct Simulation {
+    fixture: Match,
+    bots: Vec<DemoPlayer>,
+    time: Duration,
+}
+
+impl Simulation {
+    /// Every player of the match is a demo bot
+    pub fn new(fixture: Match) -> Self {
+        let players = fixture.players.len() as u32;
+        Self {
+            fixture,
+            bots: (0..players).map(DemoPlayer::new).collect(),
+            time: Duration::ZERO,
+        }
+    }
+
+    pub fn fixture(&self) -> &Match {
+        &self.fixture
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.fixture.state().is_game_over() || self.time >= MAX_MATCH_TIME
+    }
+
+    /// Same order as the game loop: input, game updates then match events.
+    /// There are no animations to wait on, so levels start as soon as they are complete.
+    pub fn step(&mut self, delta: Duration) -> Result<(), String> {
+        self.time += delta;
+        // counts down the overtime banner
+        self.fixture.update(delta);
+        self.fixture.unset_flags();
+        for (player, bot) in self.bots.iter_mut().enumerate() {
+            for key in bot.update(delta, self.fixture.player(player as u32).game()) {
+                self.fixture.apply_key(key);
+            }
+        }
+
+        let mut events = vec![];
+        if self.fixture.state().is_normal() {
+            for player in self.fixture.players.iter_mut() {
+                let game = player.game_mut();
+                game.update(delta);
+                game.consume_events(&mut events);
+            }
+        }
+
+        let players = self.bots.len() as u32;
+        for event in events {
+            match event {
+                GameEvent::LevelComplete { player } => {
+                    if self.fixture.next_level_ends_match(player) {
+                        self.fixture.set_winner(player);
+                    } else {
+                        self.fixture.player_mut(player).game_mut().next_level()?;
+                    }
+                }
+                GameEvent::GameOver { player } => {
+                    if players == 1 {
+                        self.fixture.maybe_set_game_over();
+                    } else {
+                        for maybe_winner in (0..players).filter(|p| *p != player) {
+                            self.fixture.set_winner(maybe_winner);
+                        }
+                    }
+                }
+                GameEvent::SendGarbage { player, garbage } => {
+                    self.fixture.send_garbage(player, garbage)
+                }
+                _ => {}
+            }
+        }
+
+        if self.fixture.is_draw() {
+            self.fixture.start_overtime()?;
+        } else if self.fixture.check_for_winning_player().is_some() {
+            self.fixture.maybe_set_game_over();
+        }
+        Ok(())
+    }
+
+    pub fn summary(&self) -> String {
+        let players = self
+            .fixture
+            .players
+            .iter()
+            .map(|p| {
+                format!(
+                    "player {}{}: level {}, score {}",
+                    p.player() + 1,
+                    if p.is_winner() { " (winner)" } else { "" },
+                    p.game().virus_level(),
+                    p.game().metrics().score()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let timeout = if self.fixture.state().is_game_over() {
+            ""
+        } else {
+            " (timed out)"
+        };
+        format!("{:.1}s{}, {}", self.time.as_secs_f64(), timeout, players)
+    }
+}
@@ -0,0 +1,132 @@
+use crate::high_score::date_of;
+use crate::profile::Profile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const CONFIG_NAME: &str = "daily_scores";
+/// Days of history shown, four weeks
+pub const HISTORY_DAYS: usize = 28;
+
+/// Best result of a daily challenge
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyScore {
+    pub score: u32,
+    /// levels cleared before the game over
+    pub levels: u32,
+    pub attempts: u32,
+}
+
+/// Results of the daily challenges of player 1, kept separate from the high scores
+/// as every daily is a different bottle
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyTable {
+    /// by date as yyyy-mm-dd, which sorts oldest first
+    scores: BTreeMap<String, DailyScore>,
+}
+
+impl DailyTable {
+    pub fn load(profile: &Profile) -> Result<Self, String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::load_path(config_path).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, profile: &Profile) -> Result<(), String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::store_path(config_path, self).map_err(|e| e.to_string())
+    }
+
+    pub fn best(&self, days: i64) -> Option<DailyScore> {
+        self.scores.get(&date_of(days)).copied()
+    }
+
+    /// Adds an attempt at the daily on some day, true when it is the best of the day so far
+    pub fn record(&mut self, days: i64, score: u32, levels: u32) -> bool {
+        let best = self.scores.entry(date_of(days)).or_default();
+        best.attempts += 1;
+        let is_best = best.attempts == 1 || score > best.score;
+        if is_best {
+            best.score = score;
+            best.levels = levels;
+        }
+        is_best
+    }
+
+    /// Best result of each of the last few days up to & including today, oldest first
+    pub fn history(&self, today: i64) -> Vec<(String, Option<DailyScore>)> {
+        (0..HISTORY_DAYS as i64)
+            .rev()
+            .map(|ago| {
+                let date = date_of(today - ago);
+                let best = self.scores.get(&date).copied();
+                (date, best)
+            })
+            .collect()
+    }
+
+    /// Consecutive days played up to today, or yesterday when today is yet to be played
+    pub fn streak(&self, today: i64) -> u32 {
+        let start = if self.best(today).is_some() {
+            today
+        } else {
+            today - 1
+        };
+        (0..=start)
+            .rev()
+            .take_while(|&days| self.best(days).is_some())
+            .count() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODAY: i64 = 20_089;
+
+    #[test]
+    fn records_best_attempt() {
+        let mut table = DailyTable::default();
+        assert!(table.record(TODAY, 100, 1));
+        assert!(table.record(TODAY, 300, 2));
+        assert!(!table.record(TODAY, 200, 3));
+        assert_eq!(
+            table.best(TODAY),
+            Some(DailyScore {
+                score: 300,
+                levels: 2,
+                attempts: 3
+            })
+        );
+        assert_eq!(table.best(TODAY - 1), None);
+    }
+
+    #[test]
+    fn history_ends_today() {
+        let mut table = DailyTable::default();
+        table.record(TODAY, 100, 0);
+        table.record(TODAY - 2, 200, 0);
+        let history = table.history(TODAY);
+        assert_eq!(history.len(), HISTORY_DAYS);
+        assert_eq!(history.last().unwrap().0, "2025-01-01");
+        let played = history
+            .iter()
+            .rev()
+            .take(3)
+            .map(|(_, best)| best.is_some())
+            .collect::<Vec<bool>>();
+        assert_eq!(played, vec![true, false, true]);
+    }
+
+    #[test]
+    fn streaks() {
+        let mut table = DailyTable::default();
+        assert_eq!(table.streak(TODAY), 0);
+        table.record(TODAY - 1, 0, 0);
+        table.record(TODAY - 2, 0, 0);
+        table.record(TODAY - 4, 0, 0);
+        assert_eq!(table.streak(TODAY), 2);
+        table.record(TODAY, 0, 0);
+        assert_eq!(table.streak(TODAY), 3);
+        assert_eq!(table.streak(TODAY + 2), 0);
+    }
+}
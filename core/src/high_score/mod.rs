@@ -0,0 +1,80 @@
+use crate::game::rules::MatchRules;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub mod daily;
+pub mod table;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NewHighScore {
+    pub player: u32,
+    pub score: u32,
+    pub rules: MatchRules,
+    /// number of other players in the match, 0 for a single player game
+    pub opponents: u32,
+}
+
+impl NewHighScore {
+    pub fn new(player: u32, score: u32, rules: MatchRules, opponents: u32) -> Self {
+        Self {
+            player,
+            score,
+            rules,
+            opponents,
+        }
+    }
+}
+
+/// Today's date in UTC as yyyy-mm-dd
+pub fn today() -> String {
+    date_of(days_today())
+}
+
+/// Days since 1970-01-01 in UTC
+pub fn days_today() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() / 86_400)
+        .unwrap_or(0) as i64
+}
+
+/// Date of a number of days since 1970-01-01 as yyyy-mm-dd
+pub fn date_of(days: i64) -> String {
+    let (year, month, day) = civil_date(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Year, month & day of a number of days since 1970-01-01 in the proleptic Gregorian calendar,
+/// see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_date(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(59), (1970, 3, 1));
+        assert_eq!(civil_date(11_016), (2000, 2, 29));
+        assert_eq!(civil_date(20_089), (2025, 1, 1));
+        assert_eq!(civil_date(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(date_of(0), "1970-01-01");
+        assert_eq!(date_of(20_089), "2025-01-01");
+    }
+}
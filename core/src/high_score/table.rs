@@ -1,25 +1,61 @@
 use serde::{Deserialize, Serialize};
-use crate::config::config_path;
+use crate::high_score::NewHighScore;
+use crate::profile::Profile;
 
 const MAX_HIGH_SCORES: usize = 5;
-const CONFIG_NAME: &str = "high_scores";
+pub const MAX_NAME_LENGTH: usize = 12;
+pub const CONFIG_NAME: &str = "high_scores";
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HighScore {
     pub name: String,
     pub score: u32,
+    /// profile of player 1 when they set the score, none for the default profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// name of the match rules, none for scores from before they were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<String>,
+    #[serde(default)]
+    pub opponents: u32,
+    /// yyyy-mm-dd
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
 }
 
 impl HighScore {
     pub fn new(name: &str, score: u32) -> Self {
+        Self::from_string(name.to_string(), score)
+    }
+
+    pub fn from_string(name: String, score: u32) -> Self {
         Self {
-            name: name.to_string(),
+            name,
             score,
+            profile: None,
+            rules: None,
+            opponents: 0,
+            date: None,
         }
     }
 
-    pub fn from_string(name: String, score: u32) -> Self {
-        Self { name, score }
+    /// Attributes the score to the match it was set in
+    pub fn with_match(mut self, new_high_score: NewHighScore, date: String) -> Self {
+        self.rules = Some(new_high_score.rules.name());
+        self.opponents = new_high_score.opponents;
+        self.date = Some(date);
+        self
+    }
+
+    pub fn with_profile(mut self, profile: Option<&str>) -> Self {
+        self.profile = profile.map(|profile| profile.to_string());
+        self
+    }
+
+    fn truncate_name(&mut self) {
+        if let Some((index, _)) = self.name.char_indices().nth(MAX_NAME_LENGTH) {
+            self.name.truncate(index);
+        }
     }
 }
 
@@ -43,16 +79,20 @@ impl Default for HighScoreTable {
 }
 
 impl HighScoreTable {
-    pub fn load() -> Result<Self, String> {
-        let config_path = config_path(CONFIG_NAME)?;
+    pub fn load(profile: &Profile) -> Result<Self, String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
         let mut result: Self = confy::load_path(config_path).map_err(|e| e.to_string())?;
         result.sorted();
         result.scores = result.scores.into_iter().take(MAX_HIGH_SCORES).collect();
+        // names may have been edited by hand to be longer than can be entered
+        for score in result.scores.iter_mut() {
+            score.truncate_name();
+        }
         Ok(result)
     }
 
-    pub fn save(&self) -> Result<(), String> {
-        let config_path = config_path(CONFIG_NAME)?;
+    pub fn save(&self, profile: &Profile) -> Result<(), String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
         confy::store_path(config_path, self).map_err(|e| e.to_string())
     }
 
@@ -64,7 +104,8 @@ impl HighScoreTable {
         self.try_get_score_index(new_score).is_some()
     }
 
-    pub fn add_high_score(&mut self, new_score: HighScore) {
+    pub fn add_high_score(&mut self, mut new_score: HighScore) {
+        new_score.truncate_name();
         let index = self
             .try_get_score_index(new_score.score)
             .expect("not a high score");
@@ -133,6 +174,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn truncates_long_name() {
+        let mut table = new(vec![]);
+        table.add_high_score(HighScore::new("DR. RUSTARIO & FRIENDS", 0));
+        assert_eq!(table.scores, vec![HighScore::new("DR. RUSTARIO", 0)]);
+    }
+
     #[test]
     fn not_a_high_score() {
         let table = new(vec![
@@ -0,0 +1,45 @@
+/// Game input, parsed from the keyboard or played by a bot
+#[derive(Hash, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameInputKey {
+    MoveLeft { player: u32 },
+    MoveRight { player: u32 },
+    SoftDrop { player: u32 },
+    HardDrop { player: u32 },
+    HardDropPreview { player: u32 },
+    RotateClockwise { player: u32 },
+    RotateAnticlockwise { player: u32 },
+    Hold { player: u32 },
+    SpeedUp { player: u32 },
+    Pause,
+    ReturnToMenu,
+    Quit,
+    NextTheme,
+    Rewind,
+    SaveState { slot: usize },
+    LoadState { slot: usize },
+    ToggleCrtFilter,
+    ToggleMute,
+    Restart,
+    ToggleDebugOverlay,
+    /// Saves a PNG of the window
+    Screenshot,
+    /// Saves a GIF of the last few seconds of the game
+    RecordGif,
+}
+
+impl GameInputKey {
+    pub fn player(&self) -> Option<u32> {
+        match self {
+            GameInputKey::MoveLeft { player }
+            | GameInputKey::MoveRight { player }
+            | GameInputKey::SoftDrop { player }
+            | GameInputKey::HardDrop { player }
+            | GameInputKey::HardDropPreview { player }
+            | GameInputKey::RotateClockwise { player }
+            | GameInputKey::RotateAnticlockwise { player }
+            | GameInputKey::Hold { player }
+            | GameInputKey::SpeedUp { player } => Some(*player),
+            _ => None,
+        }
+    }
+}
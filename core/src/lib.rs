@@ -0,0 +1,14 @@
+//! Dr. Rustario game engine: the rules, matches & high scores without any dependency on SDL,
+//! so that they can be driven by other frontends and tested quickly.
+
+pub mod demo;
+pub mod game;
+pub mod headless;
+pub mod high_score;
+pub mod input;
+pub mod paths;
+pub mod player;
+pub mod profile;
+pub mod session;
+pub mod render;
+pub mod stats;
@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+/// Config files are shared with the frontend, so are named after the game rather than this crate
+pub const APP_NAME: &str = "dr-rustario";
+
+#[cfg(feature = "retro_handheld")]
+pub fn config_path(name: &str) -> Result<PathBuf, String> {
+    let mut absolute = std::env::current_dir().map_err(|e| e.to_string())?;
+    absolute.push(format!("{}.yml", name));
+    Ok(absolute)
+}
+
+#[cfg(not(feature = "retro_handheld"))]
+pub fn config_path(name: &str) -> Result<PathBuf, String> {
+    confy::get_configuration_file_path(APP_NAME, name).map_err(|e| e.to_string())
+}
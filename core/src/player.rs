@@ -0,0 +1,559 @@
+use crate::game::event::GameEvent;
+use crate::game::random::{random, BottleSeed, GameRandom};
+use crate::game::Game;
+use crate::input::GameInputKey;
+use crate::high_score::table::HighScoreTable;
+use crate::high_score::NewHighScore;
+use crate::profile::Profile;
+
+use crate::game::bottle::SendGarbage;
+use crate::game::metrics::GameMetrics;
+use crate::game::modifier::{modifiers_by_name, GameplayModifiers};
+use crate::game::rules::{GameConfig, MatchRules, MatchThemes};
+use rand::prelude::ThreadRng;
+use rand::{thread_rng, Rng};
+use std::time::Duration;
+
+/// Time between unpausing and the simulation resuming
+const RESUME_COUNTDOWN: Duration = Duration::from_secs(3);
+/// Time the overtime banner is shown before sudden death begins
+pub const OVERTIME_BANNER_DURATION: Duration = Duration::from_millis(2500);
+/// Virus level of every bottle in sudden death overtime, just 4 viruses
+const OVERTIME_VIRUS_LEVEL: u32 = 0;
+
+pub struct Player {
+    player: u32,
+    game: Game,
+    winner: bool,
+}
+
+impl Player {
+    pub fn new(
+        player: u32,
+        random: GameRandom,
+        game_config: GameConfig,
+        modifiers: GameplayModifiers,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            player,
+            game: Game::new(
+                player,
+                game_config.player_virus_level(player),
+                game_config.player_speed(player),
+                random,
+            )?
+            .with_modifiers(modifiers)
+            .with_garbage_cancellation(game_config.garbage_cancellation())
+            .with_garbage_style(game_config.garbage_style())
+            .with_rule_set(game_config.rule_set())
+            .with_features(game_config.features())
+            .with_peek_count(game_config.peek_count())
+            .with_crawling_viruses(game_config.crawling_viruses())
+            .with_adaptive_speed(game_config.adaptive_speed())
+            .with_rewind(game_config.rewind())
+            .with_bottle_size(game_config.bottle_size())?
+            .with_exact_viruses(game_config.exact_viruses())?,
+            winner: false,
+        })
+    }
+
+    pub fn player(&self) -> u32 {
+        self.player
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn game_mut(&mut self) -> &mut Game {
+        &mut self.game
+    }
+
+    pub fn is_winner(&self) -> bool {
+        self.winner
+    }
+
+    fn set_winner(&mut self) {
+        self.winner = true;
+    }
+
+    fn with_layout(mut self, layout: &BottleSeed) -> Self {
+        self.game = self.game.with_layout(layout.clone());
+        self
+    }
+
+    /// A second player shares the bottle of a co-op game with their own pills
+    fn with_partner(mut self, random: GameRandom) -> Self {
+        self.game = self.game.with_partner(random);
+        self
+    }
+
+    fn start_overtime(&mut self) -> Result<(), String> {
+        self.winner = false;
+        self.game.overtime(OVERTIME_VIRUS_LEVEL)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchState {
+    Normal,
+    Paused,
+    /// Counting down to resume after a pause, all input is ignored
+    Resuming { remaining: Duration },
+    /// A vs. game was drawn, bottles are reseeded & the first to clear wins once the banner ends
+    Overtime { remaining: Duration },
+    GameOver { high_score: Option<NewHighScore> },
+}
+
+impl MatchState {
+    pub fn is_paused(&self) -> bool {
+        self == &MatchState::Paused
+    }
+
+    pub fn is_resuming(&self) -> bool {
+        matches!(self, MatchState::Resuming { .. })
+    }
+
+    /// Paused or counting down to resume i.e. the simulation is frozen
+    pub fn is_suspended(&self) -> bool {
+        self.is_paused() || self.is_resuming()
+    }
+
+    /// Whole seconds left to count down before resuming e.g. 3, 2, 1
+    pub fn resume_countdown(&self) -> Option<u32> {
+        match self {
+            MatchState::Resuming { remaining } => {
+                Some(remaining.as_millis().div_ceil(1000).max(1) as u32)
+            }
+            _ => None,
+        }
+    }
+
+    /// Progress through the overtime banner from 0.0 to 1.0
+    pub fn overtime_banner(&self) -> Option<f64> {
+        match self {
+            MatchState::Overtime { remaining } => Some(
+                1.0 - remaining.as_secs_f64() / OVERTIME_BANNER_DURATION.as_secs_f64(),
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        matches!(self, MatchState::GameOver { .. })
+    }
+
+    pub fn is_normal(&self) -> bool {
+        self == &MatchState::Normal
+    }
+}
+
+pub struct Match {
+    pub players: Vec<Player>,
+    high_scores: HighScoreTable,
+    state: MatchState,
+    game_config: GameConfig,
+    modifiers: Vec<String>,
+    rng: ThreadRng,
+    /// wins of each player over the games of a vs. series
+    series_wins: Vec<u32>,
+    /// best high score of the games of the series so far
+    series_high_score: Option<NewHighScore>,
+    /// sudden death, the first player to clear their bottle wins
+    overtime: bool,
+}
+
+impl Match {
+    pub fn new(game_config: GameConfig, modifiers: &[String]) -> Self {
+        Self::new_with_high_scores(
+            game_config,
+            modifiers,
+            HighScoreTable::load(&Profile::default()).unwrap(),
+        )
+    }
+
+    /// A match checked against a table of high scores rather than loading the default profile's
+    pub fn new_with_high_scores(
+        game_config: GameConfig,
+        modifiers: &[String],
+        high_scores: HighScoreTable,
+    ) -> Self {
+        assert!(game_config.players() > 0);
+        Self {
+            players: new_players(game_config, modifiers, game_config.seed()),
+            high_scores,
+            state: MatchState::Normal,
+            game_config,
+            modifiers: modifiers.to_vec(),
+            rng: thread_rng(),
+            series_wins: vec![0; game_config.players() as usize],
+            series_high_score: None,
+            overtime: false,
+        }
+    }
+
+    /// Checks for new high scores against the table of a profile rather than the default
+    pub fn with_high_scores(mut self, high_scores: HighScoreTable) -> Self {
+        self.high_scores = high_scores;
+        self
+    }
+
+    /// Every player starts on a layout of viruses e.g. from the editor rather than a seeded bottle
+    pub fn with_layout(mut self, layout: &BottleSeed) -> Self {
+        self.players = self
+            .players
+            .into_iter()
+            .map(|p| p.with_layout(layout))
+            .collect();
+        self
+    }
+
+    pub fn unset_flags(&mut self) {
+        for player in self.players.iter_mut() {
+            player.game.set_soft_drop(false);
+            player.game.partner(|g| g.set_soft_drop(false));
+        }
+    }
+
+    pub fn toggle_paused(&mut self) -> Option<GameEvent> {
+        match self.state {
+            MatchState::Normal => {
+                self.state = MatchState::Paused;
+                Some(GameEvent::Paused)
+            }
+            MatchState::Paused => {
+                // the match is unpaused once the countdown completes
+                self.state = MatchState::Resuming {
+                    remaining: RESUME_COUNTDOWN,
+                };
+                None
+            }
+            MatchState::Resuming { .. } => {
+                self.state = MatchState::Paused;
+                Some(GameEvent::Paused)
+            }
+            _ => None,
+        }
+    }
+
+    /// Runs the resume countdown, returns the unpaused event once it completes
+    pub fn update(&mut self, delta: Duration) -> Option<GameEvent> {
+        match self.state {
+            MatchState::Resuming { remaining } if remaining > delta => {
+                self.state = MatchState::Resuming {
+                    remaining: remaining - delta,
+                };
+                None
+            }
+            MatchState::Resuming { .. } => {
+                self.state = MatchState::Normal;
+                Some(GameEvent::UnPaused)
+            }
+            MatchState::Overtime { remaining } if remaining > delta => {
+                self.state = MatchState::Overtime {
+                    remaining: remaining - delta,
+                };
+                None
+            }
+            MatchState::Overtime { .. } => {
+                self.state = MatchState::Normal;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    pub fn state(&self) -> MatchState {
+        self.state
+    }
+
+    pub fn next_level_ends_match(&self, player: u32) -> bool {
+        if self.overtime {
+            return true;
+        }
+        match self.game_config.rules() {
+            MatchRules::LevelSprint {
+                levels: sprint_levels,
+            } => self.player(player).game().completed_levels() + 1 >= sprint_levels,
+            MatchRules::ThemeSprint => {
+                self.player(player).game().completed_levels() + 1 >= MatchThemes::count() as u32
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_winner(&mut self, player: u32) {
+        // todo move to a SelectWinner { player: u32 } state
+        self.player_mut(player).set_winner();
+    }
+
+    pub fn check_for_winning_player(&self) -> Option<u32> {
+        if self.state.is_game_over() {
+            return None;
+        }
+
+        if self.is_draw() {
+            return None;
+        }
+
+        if let Some(winner) = self.players.iter().find(|p| p.winner) {
+            return Some(winner.player);
+        }
+
+        if self.overtime {
+            // only a clear wins in sudden death
+            return None;
+        }
+
+        match self.game_config.rules() {
+            MatchRules::ScoreSprint {
+                score: sprint_score,
+            } => {
+                let best_game = self.highest_score();
+                if best_game.score() >= sprint_score {
+                    Some(best_game.player())
+                } else {
+                    None
+                }
+            }
+            MatchRules::LevelSprint {
+                levels: sprint_levels,
+            } => {
+                // players may start on different levels when handicapped
+                let best_player = self.most_completed_levels();
+                if best_player.game.completed_levels() >= sprint_levels {
+                    Some(best_player.player)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub fn maybe_set_game_over(&mut self) -> bool {
+        if self.state.is_game_over() {
+            return false;
+        }
+
+        let best_game = self.highest_score();
+
+        // practice games that were rewound or on a layout of the editor do not count
+        let practice = self
+            .players
+            .iter()
+            .any(|p| p.game.is_rewound() || p.game.is_custom_layout());
+        if !practice
+            && self.high_scores.is_high_score(best_game.score())
+            && self
+                .series_high_score
+                .is_none_or(|high_score| best_game.score() > high_score.score)
+        {
+            self.series_high_score = Some(NewHighScore::new(
+                best_game.player(),
+                best_game.score(),
+                self.game_config.rules(),
+                self.players.len() as u32 - 1,
+            ));
+        }
+
+        self.state = MatchState::GameOver {
+            high_score: self.series_high_score,
+        };
+        true
+    }
+
+    /// True when a vs. game has no single winner: every player topped out on the same frame
+    /// or both reached the sprint score with the same score
+    pub fn is_draw(&self) -> bool {
+        if self.players.len() < 2 || !self.state.is_normal() {
+            return false;
+        }
+        if self.players.iter().all(|p| p.winner) {
+            return true;
+        }
+        match self.game_config.rules() {
+            MatchRules::ScoreSprint {
+                score: sprint_score,
+            } if !self.overtime => {
+                let best = self.highest_score().score();
+                best >= sprint_score
+                    && self
+                        .players
+                        .iter()
+                        .filter(|p| p.game.metrics().score() == best)
+                        .count()
+                        > 1
+            }
+            _ => false,
+        }
+    }
+
+    /// Reseeds every bottle with a few viruses for sudden death, after showing the banner
+    pub fn start_overtime(&mut self) -> Result<(), String> {
+        for player in self.players.iter_mut() {
+            player.start_overtime()?;
+        }
+        self.overtime = true;
+        self.state = MatchState::Overtime {
+            remaining: OVERTIME_BANNER_DURATION,
+        };
+        Ok(())
+    }
+
+    pub fn is_overtime(&self) -> bool {
+        self.overtime
+    }
+
+    /// Counts a game won towards the series, call once per game after setting game over
+    pub fn record_series_win(&mut self, player: u32) {
+        self.series_wins[player as usize] += 1;
+    }
+
+    pub fn series_wins(&self, player: u32) -> u32 {
+        self.series_wins[player as usize]
+    }
+
+    /// True once a player has the wins needed to take the series, always after a single game
+    pub fn is_series_decided(&self) -> bool {
+        let needed = self.game_config.series_wins();
+        needed <= 1 || self.series_wins.iter().any(|&wins| wins >= needed)
+    }
+
+    /// Starts the next game of an undecided series with fresh bottles, keeping the wins so far.
+    /// A seeded series gets a different seed for every game so that it is still repeatable.
+    pub fn next_game(&mut self) {
+        let games = self.series_wins.iter().sum::<u32>();
+        let seed = self.game_config.seed().map(|seed| seed.wrapping_add(games));
+        self.players = new_players(self.game_config, &self.modifiers, seed);
+        self.state = MatchState::Normal;
+        self.overtime = false;
+    }
+
+    pub fn mut_game<F>(&mut self, player: u32, mut f: F)
+    where
+        F: FnMut(&mut Game),
+    {
+        if self.state.is_normal() {
+            let player = self.players.get_mut(player as usize).unwrap();
+            f(&mut player.game)
+        }
+    }
+
+    /// Applies some input of a player to their pill,
+    /// the second player of a co-op game has the partner pill of the only game
+    fn mut_pill<F>(&mut self, player: u32, f: F)
+    where
+        F: FnMut(&mut Game),
+    {
+        if !self.game_config.coop() {
+            self.mut_game(player, f);
+        } else if player == 0 {
+            self.mut_game(0, f);
+        } else {
+            let mut f = f;
+            self.mut_game(0, |g| g.partner(&mut f));
+        }
+    }
+
+    /// Applies player input to their game, global keys are left to the game loop
+    pub fn apply_key(&mut self, key: GameInputKey) {
+        match key {
+            GameInputKey::MoveLeft { player } => self.mut_pill(player, |g| g.left()),
+            GameInputKey::MoveRight { player } => self.mut_pill(player, |g| g.right()),
+            GameInputKey::SoftDrop { player } => self.mut_pill(player, |g| g.set_soft_drop(true)),
+            GameInputKey::HardDrop { player } => self.mut_pill(player, |g| g.hard_drop()),
+            GameInputKey::HardDropPreview { player } => {
+                self.mut_pill(player, |g| g.preview_hard_drop())
+            }
+            GameInputKey::RotateClockwise { player } => self.mut_pill(player, |g| g.rotate(true)),
+            GameInputKey::RotateAnticlockwise { player } => {
+                self.mut_pill(player, |g| g.rotate(false))
+            }
+            GameInputKey::Hold { player } => self.mut_pill(player, |g| g.hold()),
+            GameInputKey::SpeedUp { player } => self.mut_pill(player, |g| g.speed_up()),
+            _ => {}
+        }
+    }
+
+    pub fn player(&self, player: u32) -> &Player {
+        self.players.get(player as usize).unwrap()
+    }
+
+    pub fn player_mut(&mut self, player: u32) -> &mut Player {
+        self.players.get_mut(player as usize).unwrap()
+    }
+
+    /// The only other player of a two player match, where garbage is always sent
+    pub fn opponent(&self, player: u32) -> Option<u32> {
+        if self.players.len() == 2 {
+            Some(1 - player)
+        } else {
+            None
+        }
+    }
+
+    pub fn send_garbage(&mut self, from_player: u32, garbage: SendGarbage) {
+        if self.players.len() < 2 {
+            return;
+        }
+
+        let other_players = (0..self.players.len())
+            .filter(|&p| p != from_player as usize)
+            .collect::<Vec<usize>>();
+
+        let pid = if other_players.len() == 1 {
+            other_players[0]
+        } else {
+            other_players[self.rng.gen_range(0..other_players.len())]
+        };
+        self.players
+            .get_mut(pid)
+            .unwrap()
+            .game
+            .send_garbage(garbage);
+    }
+
+    fn highest_score(&self) -> GameMetrics {
+        self.players
+            .iter()
+            .map(|p| p.game.metrics())
+            .max_by(|x, y| x.score().cmp(&y.score()))
+            .unwrap()
+    }
+
+    fn most_completed_levels(&self) -> &Player {
+        self.players
+            .iter()
+            .max_by_key(|p| p.game.completed_levels())
+            .unwrap()
+    }
+}
+
+fn new_players(game_config: GameConfig, modifiers: &[String], seed: Option<u32>) -> Vec<Player> {
+    let players = game_config.players() as usize;
+    // the partner of a co-op game gets the next source
+    let hands = players + game_config.coop() as usize;
+    let mut randoms = random(hands, game_config.random(), seed)
+        .into_iter()
+        .map(|r| {
+            r.with_four_colors(game_config.four_colors())
+                .with_power_ups(game_config.power_ups())
+        })
+        .collect::<Vec<GameRandom>>();
+    let partner = (hands > players).then(|| randoms.pop().unwrap());
+    let mut players = randoms
+        .into_iter()
+        .enumerate()
+        .map(|(pid, rand)| {
+            // each player gets their own modifier instances as they may be stateful
+            let modifiers = modifiers_by_name(modifiers).unwrap();
+            Player::new(pid as u32, rand, game_config, modifiers).unwrap()
+        })
+        .collect::<Vec<Player>>();
+    if let Some(partner) = partner {
+        let player = players.pop().unwrap();
+        players.push(player.with_partner(partner));
+    }
+    players
+}
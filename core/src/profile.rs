@@ -0,0 +1,139 @@
+//! Named profiles for the players sharing a machine, each keeps their own high scores,
+//! statistics & input bindings. Without a profile the shared files are used as before.
+
+use crate::paths::config_path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+pub const CONFIG_NAME: &str = "profiles";
+pub const MAX_PROFILE_NAME: usize = 8;
+/// characters of a profile name, it is also the name of a directory so must be path safe
+pub const PROFILE_ALPHABET: [char; 36] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R',
+    'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Profile {
+    /// none for the shared default profile
+    name: Option<String>,
+}
+
+impl Profile {
+    pub fn named(name: &str) -> Result<Self, String> {
+        validate_name(name)?;
+        Ok(Self {
+            name: Some(name.to_string()),
+        })
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Path of a config file of this profile, the default profile uses the shared files
+    pub fn config_path(&self, name: &str) -> Result<PathBuf, String> {
+        match self.name.as_ref() {
+            None => config_path(name),
+            Some(profile) => config_path(&format!("{}/{}/{}", CONFIG_NAME, profile, name)),
+        }
+    }
+}
+
+/// Names of all profiles & the one last selected, kept across sessions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profiles {
+    selected: Option<String>,
+    names: BTreeSet<String>,
+}
+
+impl Profiles {
+    pub fn load() -> Result<Self, String> {
+        let config_path = config_path(CONFIG_NAME)?;
+        let mut result: Self = confy::load_path(config_path).map_err(|e| e.to_string())?;
+        // a hand edited file may select a profile that doesn't exist
+        if result.selected.as_ref().is_some_and(|name| !result.names.contains(name)) {
+            result.selected = None;
+        }
+        Ok(result)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let config_path = config_path(CONFIG_NAME)?;
+        confy::store_path(config_path, self).map_err(|e| e.to_string())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|name| name.as_str())
+    }
+
+    pub fn selected(&self) -> Profile {
+        Profile {
+            name: self.selected.clone(),
+        }
+    }
+
+    /// Selects a profile by name or the default profile with none
+    pub fn select(&mut self, name: Option<&str>) -> Result<(), String> {
+        if let Some(name) = name {
+            if !self.names.contains(name) {
+                return Err(format!("unknown profile: {}", name));
+            }
+        }
+        self.selected = name.map(|name| name.to_string());
+        Ok(())
+    }
+
+    /// Adds & selects a new profile
+    pub fn create(&mut self, name: &str) -> Result<Profile, String> {
+        let profile = Profile::named(name)?;
+        if !self.names.insert(name.to_string()) {
+            return Err(format!("profile already exists: {}", name));
+        }
+        self.selected = Some(name.to_string());
+        Ok(profile)
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().count() > MAX_PROFILE_NAME {
+        return Err(format!(
+            "profile name must be 1 to {} characters",
+            MAX_PROFILE_NAME
+        ));
+    }
+    match name.chars().find(|c| !PROFILE_ALPHABET.contains(c)) {
+        Some(c) => Err(format!("bad character in profile name: {}", c)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_and_selects() {
+        let mut profiles = Profiles::default();
+        assert_eq!(profiles.selected(), Profile::default());
+        profiles.create("MOLLY").unwrap();
+        profiles.create("ALEX").unwrap();
+        assert_eq!(profiles.selected().name(), Some("ALEX"));
+        assert_eq!(profiles.names().collect::<Vec<_>>(), vec!["ALEX", "MOLLY"]);
+
+        assert!(profiles.create("ALEX").is_err());
+        assert!(profiles.select(Some("ESME")).is_err());
+        profiles.select(None).unwrap();
+        assert_eq!(profiles.selected().name(), None);
+    }
+
+    #[test]
+    fn path_safe_names() {
+        assert!(Profile::named("MOGS2").is_ok());
+        assert!(Profile::named("").is_err());
+        assert!(Profile::named("../..").is_err());
+        assert!(Profile::named("molly").is_err());
+        assert!(Profile::named("ABCDEFGHI").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! The drawing primitives of a frontend without the themes, the html canvas in the browser build,
+//! plus a flat scene of the bottles that only needs those.
+
+use crate::game::block::Block;
+use crate::game::pill::VirusColor;
+use crate::game::Game;
+
+pub type Rgb = (u8, u8, u8);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+pub trait Renderer {
+    fn output_size(&self) -> Result<(u32, u32), String>;
+    fn clear(&mut self, color: Rgb);
+    fn fill_rect(&mut self, rect: Rect, color: Rgb) -> Result<(), String>;
+    fn present(&mut self);
+}
+
+const BACKGROUND: Rgb = (0, 0, 0);
+const BOTTLE: Rgb = (32, 32, 48);
+const GHOST: Rgb = (64, 64, 80);
+/// Bottles are separated by one block
+const GUTTER_BLOCKS: u32 = 1;
+
+fn virus_color(color: VirusColor) -> Rgb {
+    match color {
+        VirusColor::Yellow => (248, 216, 0),
+        VirusColor::Blue => (56, 120, 248),
+        VirusColor::Red => (232, 40, 40),
+        VirusColor::Green => (40, 184, 72),
+    }
+}
+
+/// Every bottle side by side with solid blocks, viruses are drawn smaller than vitamins
+pub fn draw_flat_scene<R: Renderer>(renderer: &mut R, games: &[&Game]) -> Result<(), String> {
+    renderer.clear(BACKGROUND);
+    if games.is_empty() {
+        return Ok(());
+    }
+
+    let (width, height) = renderer.output_size()?;
+    let players = games.len() as u32;
+    // every player has the same size bottle
+    let size = games[0].bottle_size();
+    let blocks_wide = players * (size.width() + GUTTER_BLOCKS) + GUTTER_BLOCKS;
+    let blocks_high = size.height() + 2 * GUTTER_BLOCKS;
+    let block = (width / blocks_wide).min(height / blocks_high).max(1);
+    let offset_x = (width - (block * blocks_wide).min(width)) / 2;
+    let offset_y = (height - (block * blocks_high).min(height)) / 2;
+
+    for (index, game) in games.iter().enumerate() {
+        let bottle_x =
+            offset_x + block * (GUTTER_BLOCKS + index as u32 * (size.width() + GUTTER_BLOCKS));
+        let bottle_y = offset_y + block * GUTTER_BLOCKS;
+        renderer.fill_rect(
+            Rect::new(
+                bottle_x as i32,
+                bottle_y as i32,
+                block * size.width(),
+                block * size.height(),
+            ),
+            BOTTLE,
+        )?;
+
+        for y in 0..size.height() {
+            for (x, value) in game.row(y).iter().enumerate() {
+                let (color, inset) = match value {
+                    Block::Empty => continue,
+                    Block::Vitamin(color, _, _)
+                    | Block::Stack(color, _, _)
+                    | Block::Garbage(color) => (virus_color(*color), 0),
+                    Block::Virus(color) => (virus_color(*color), block / 6),
+                    Block::Ghost(_, _, _) => (GHOST, 0),
+                };
+                renderer.fill_rect(
+                    Rect::new(
+                        (bottle_x + x as u32 * block + inset) as i32,
+                        (bottle_y + y * block + inset) as i32,
+                        block - 2 * inset,
+                        block - 2 * inset,
+                    ),
+                    color,
+                )?;
+            }
+        }
+    }
+    renderer.present();
+    Ok(())
+}
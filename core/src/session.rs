@@ -0,0 +1,67 @@
+use crate::game::rules::{GameConfig, MatchRules};
+
+/// Wins of each player over the vs. matches played back to back, it is kept for as long as the
+/// game is running and starts over whenever the number of players or the rules change
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Session {
+    wins: Vec<u32>,
+    rules: Option<MatchRules>,
+    last_winner: Option<u32>,
+}
+
+impl Session {
+    pub fn record_win(&mut self, game_config: GameConfig, winner: u32) {
+        let players = game_config.players() as usize;
+        if self.wins.len() != players || self.rules != Some(game_config.rules()) {
+            self.wins = vec![0; players];
+            self.rules = Some(game_config.rules());
+        }
+        self.wins[winner as usize] += 1;
+        self.last_winner = Some(winner);
+    }
+
+    /// Wins by player
+    pub fn wins(&self) -> &[u32] {
+        &self.wins
+    }
+
+    pub fn matches(&self) -> u32 {
+        self.wins.iter().sum()
+    }
+
+    pub fn last_winner(&self) -> Option<u32> {
+        self.last_winner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vs(players: u32, rules: MatchRules) -> GameConfig {
+        let mut game_config = GameConfig::default();
+        game_config.set_players(players);
+        game_config.set_rules(rules);
+        game_config
+    }
+
+    #[test]
+    fn tallies_wins() {
+        let game_config = vs(2, MatchRules::ONE_LEVEL_SPRINT);
+        let mut session = Session::default();
+        session.record_win(game_config, 1);
+        session.record_win(game_config, 0);
+        session.record_win(game_config, 1);
+        assert_eq!(session.wins(), &[1, 2]);
+        assert_eq!(session.matches(), 3);
+        assert_eq!(session.last_winner(), Some(1));
+    }
+
+    #[test]
+    fn starts_over_when_rules_change() {
+        let mut session = Session::default();
+        session.record_win(vs(2, MatchRules::ONE_LEVEL_SPRINT), 1);
+        session.record_win(vs(2, MatchRules::ThemeSprint), 0);
+        assert_eq!(session.wins(), &[1, 0]);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::game::metrics::GameStats;
+use crate::profile::Profile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+pub const CONFIG_NAME: &str = "stats";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeRecord {
+    pub wins: u32,
+    pub losses: u32,
+}
+
+/// Lifetime statistics of player 1, kept across sessions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    games_played: u32,
+    viruses_destroyed: u32,
+    highest_combo: u32,
+    play_time_millis: u64,
+    /// wins & losses by the name of the match rules
+    modes: BTreeMap<String, ModeRecord>,
+}
+
+impl PlayerStats {
+    pub fn load(profile: &Profile) -> Result<Self, String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::load_path(config_path).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, profile: &Profile) -> Result<(), String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::store_path(config_path, self).map_err(|e| e.to_string())
+    }
+
+    pub fn games_played(&self) -> u32 {
+        self.games_played
+    }
+    pub fn viruses_destroyed(&self) -> u32 {
+        self.viruses_destroyed
+    }
+    pub fn highest_combo(&self) -> u32 {
+        self.highest_combo
+    }
+    pub fn play_time(&self) -> Duration {
+        Duration::from_millis(self.play_time_millis)
+    }
+    pub fn modes(&self) -> impl Iterator<Item = (&str, ModeRecord)> {
+        self.modes.iter().map(|(mode, record)| (mode.as_str(), *record))
+    }
+
+    /// Adds a level that has ended, either cleared or the last of a match
+    pub fn record_level(&mut self, stats: GameStats) {
+        self.viruses_destroyed += stats.viruses_cleared();
+        self.highest_combo = self.highest_combo.max(stats.max_combo());
+        self.play_time_millis += stats.time().as_millis() as u64;
+    }
+
+    /// Adds a finished match, including its last level
+    pub fn record_match(&mut self, mode: &str, last_level: GameStats, won: bool) {
+        self.record_level(last_level);
+        self.games_played += 1;
+        let record = self.modes.entry(mode.to_string()).or_default();
+        if won {
+            record.wins += 1;
+        } else {
+            record.losses += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_matches_by_mode() {
+        let mut stats = PlayerStats::default();
+        stats.record_match("marathon", GameStats::default(), false);
+        stats.record_match("1 level sprint", GameStats::default(), true);
+        stats.record_match("1 level sprint", GameStats::default(), false);
+
+        assert_eq!(stats.games_played(), 3);
+        assert_eq!(
+            stats.modes().collect::<Vec<_>>(),
+            vec![
+                ("1 level sprint", ModeRecord { wins: 1, losses: 1 }),
+                ("marathon", ModeRecord { wins: 0, losses: 1 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn accumulates_levels() {
+        let mut level = GameStats::default();
+        level.destroy(4, 2);
+        level.elapse(Duration::from_secs(30));
+        let mut stats = PlayerStats::default();
+        stats.record_level(level);
+        level.destroy(1, 3);
+        stats.record_match("marathon", level, false);
+
+        assert_eq!(stats.viruses_destroyed(), 9);
+        assert_eq!(stats.highest_combo(), 3);
+        assert_eq!(stats.play_time(), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,228 @@
+//! Games replayed from their commands must be identical to the games that logged them.
+//! These run against the real bottle, the unit tests in the crate use a mock.
+
+use dr_rustario_core::game::block::Block;
+use dr_rustario_core::game::command::GameCommand;
+use dr_rustario_core::game::eval::{best_placement, read_board, ActivePill};
+use dr_rustario_core::game::event::GameEvent;
+use dr_rustario_core::game::pill::VirusColor;
+use dr_rustario_core::game::random::{random, RandomMode};
+use dr_rustario_core::game::ruleset::RuleSet;
+use dr_rustario_core::game::{Game, GameSpeed};
+use std::time::Duration;
+
+const SEED: u32 = 0xbeef;
+const VIRUS_LEVEL: u32 = 5;
+const FRAME: Duration = Duration::from_micros(16_667);
+/// Five minutes of game time
+const FRAMES: usize = 60 * 60 * 5;
+
+#[derive(Debug, PartialEq)]
+struct GameView {
+    rows: Vec<Vec<Block>>,
+    score: u32,
+    virus_level: u32,
+    events: Vec<GameEvent>,
+}
+
+impl GameView {
+    fn consume(game: &mut Game) -> Self {
+        let mut events = vec![];
+        game.consume_events(&mut events);
+        Self {
+            rows: (0..game.bottle_size().height()).map(|y| game.row(y).to_vec()).collect(),
+            score: game.metrics().score(),
+            virus_level: game.virus_level(),
+            events,
+        }
+    }
+
+    fn is_level_complete(&self) -> bool {
+        self.events
+            .iter()
+            .any(|e| matches!(e, GameEvent::LevelComplete { .. }))
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.events
+            .iter()
+            .any(|e| matches!(e, GameEvent::GameOver { .. }))
+    }
+}
+
+fn new_game() -> Game {
+    let random = random(1, RandomMode::Bag, Some(SEED)).pop().unwrap();
+    Game::new(0, VIRUS_LEVEL, GameSpeed::Medium, random).unwrap()
+}
+
+/// One input per frame toward the best placement, then a hard drop
+fn bot_command(game: &Game) -> Option<GameCommand> {
+    let pill = ActivePill::read(game)?;
+    let target = best_placement(&read_board(game), pill.colors);
+    let command = if pill.rotation != target.rotation {
+        GameCommand::Rotate { clockwise: true }
+    } else if pill.x < target.x {
+        GameCommand::Right
+    } else if pill.x > target.x {
+        GameCommand::Left
+    } else {
+        GameCommand::HardDrop
+    };
+    Some(command)
+}
+
+fn apply_with_api(game: &mut Game, command: GameCommand) {
+    match command {
+        GameCommand::Left => game.left(),
+        GameCommand::Right => game.right(),
+        GameCommand::Rotate { clockwise } => game.rotate(clockwise),
+        GameCommand::HardDrop => game.hard_drop(),
+        GameCommand::Update { delta } => game.update(delta),
+        GameCommand::NextLevel => game.next_level().unwrap(),
+        GameCommand::Rewind => {
+            game.rewind();
+        }
+        GameCommand::SaveState { slot } => {
+            game.save_state(slot);
+        }
+        GameCommand::LoadState { slot } => {
+            game.load_state(slot);
+        }
+        command => panic!("not played by the bot: {:?}", command),
+    }
+}
+
+/// Plays with the bot, occasionally rewinding & loading a save state like a practice player
+fn play<F>(game: &mut Game, mut apply: F) -> Vec<GameView>
+where
+    F: FnMut(&mut Game, GameCommand),
+{
+    let mut views = vec![];
+    for frame in 0..FRAMES {
+        if let Some(command) = bot_command(game) {
+            apply(game, command);
+        }
+        match frame % 1500 {
+            300 => apply(game, GameCommand::SaveState { slot: 0 }),
+            700 => apply(game, GameCommand::Rewind),
+            1100 => apply(game, GameCommand::LoadState { slot: 0 }),
+            _ => {}
+        }
+        apply(game, GameCommand::Update { delta: FRAME });
+
+        let view = GameView::consume(game);
+        let is_level_complete = view.is_level_complete();
+        let is_game_over = view.is_game_over();
+        views.push(view);
+        if is_game_over {
+            break;
+        }
+        if is_level_complete {
+            apply(game, GameCommand::NextLevel);
+        }
+    }
+    views
+}
+
+#[test]
+fn replays_command_log_with_garbage() {
+    let mut game = new_game().with_command_log(true);
+    let mut received_garbage = 0;
+    for frame in 0..FRAMES / 5 {
+        if let Some(command) = bot_command(&game) {
+            game.apply(command).unwrap();
+        }
+        if frame % 300 == 0 {
+            game.send_garbage(vec![VirusColor::Red, VirusColor::Blue, VirusColor::Yellow]);
+        }
+        game.update(FRAME);
+
+        let view = GameView::consume(&mut game);
+        received_garbage += view
+            .events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::ReceivedGarbage { .. }))
+            .count();
+        if view.is_game_over() || view.is_level_complete() {
+            break;
+        }
+    }
+    assert!(received_garbage > 1);
+    let expected = GameView::consume(&mut game);
+
+    // the columns of the garbage are drawn from the seed, so they land in the same place
+    let mut replayed = new_game();
+    for command in game.command_log() {
+        replayed.apply(command.clone()).unwrap();
+    }
+    let mut actual = GameView::consume(&mut replayed);
+    actual.events.clear();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn replays_command_log() {
+    let mut game = new_game().with_rewind(true).with_command_log(true);
+    play(&mut game, apply_with_api);
+    let expected = GameView::consume(&mut game);
+    assert!(expected.score > 0);
+
+    let mut replayed = new_game().with_rewind(true);
+    for command in game.command_log() {
+        replayed.apply(command.clone()).unwrap();
+    }
+    let mut actual = GameView::consume(&mut replayed);
+    // only the events since the last frame were left to consume in the original game
+    actual.events.clear();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn plays_past_zen_top_out() {
+    let mut game = new_game().with_rule_set(RuleSet::Zen);
+    let mut topped_out = 0;
+    // without input the pills stack up under the spawn point until they top out
+    for _ in 0..FRAMES {
+        game.update(FRAME);
+        let view = GameView::consume(&mut game);
+        assert!(!view.is_game_over());
+        topped_out += view
+            .events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::ToppedOut { .. }))
+            .count();
+    }
+    assert!(topped_out > 1);
+}
+
+#[test]
+fn command_log_is_disabled_by_default() {
+    let mut game = new_game();
+    game.update(FRAME);
+    assert!(game.command_log().is_empty());
+}
+
+#[test]
+fn replays_coop_command_log() {
+    let partner = random(2, RandomMode::Bag, Some(SEED)).pop().unwrap();
+    let mut game = new_game()
+        .with_partner(partner.clone())
+        .with_command_log(true);
+    for frame in 0..FRAMES / 10 {
+        match frame % 40 {
+            10 => game.partner(|g| g.left()),
+            20 => game.rotate(true),
+            30 => game.partner(|g| g.hard_drop()),
+            _ => {}
+        }
+        game.update(FRAME);
+    }
+    let expected = GameView::consume(&mut game);
+    assert!(game.command_log().contains(&GameCommand::SwapHands));
+
+    let mut replayed = new_game().with_partner(partner);
+    for command in game.command_log() {
+        replayed.apply(command.clone()).unwrap();
+    }
+    assert_eq!(GameView::consume(&mut replayed), expected);
+}
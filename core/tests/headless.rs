@@ -0,0 +1,34 @@
+//! Matches of the demo bot are repeatable from a seed, so that bots can be compared.
+
+use dr_rustario_core::game::random::RandomMode;
+use dr_rustario_core::game::rules::{GameConfig, MatchRules, MatchThemes};
+use dr_rustario_core::game::GameSpeed;
+use dr_rustario_core::headless::{Simulation, STEP};
+use dr_rustario_core::high_score::table::HighScoreTable;
+use dr_rustario_core::player::Match;
+
+fn simulate(seed: u32) -> Simulation {
+    let mut game_config = GameConfig::new(
+        1,
+        0,
+        GameSpeed::Low,
+        MatchThemes::Nes,
+        MatchRules::ONE_LEVEL_SPRINT,
+        RandomMode::default(),
+    );
+    game_config.set_seed(Some(seed));
+    let fixture = Match::new_with_high_scores(game_config, &[], HighScoreTable::default());
+    let mut simulation = Simulation::new(fixture);
+    while !simulation.is_over() {
+        simulation.step(STEP).unwrap();
+    }
+    simulation
+}
+
+#[test]
+fn seeded_matches_play_out_the_same() {
+    let simulation = simulate(1234);
+    assert!(simulation.fixture().state().is_game_over());
+    assert!(simulation.fixture().players.iter().any(|p| p.is_winner()));
+    assert_eq!(simulate(1234).summary(), simulation.summary());
+}
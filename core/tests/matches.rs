@@ -0,0 +1,87 @@
+//! Draws & sudden death overtime of vs. matches, against the real bottle.
+
+use dr_rustario_core::game::random::RandomMode;
+use dr_rustario_core::game::rules::{GameConfig, MatchRules, MatchThemes};
+use dr_rustario_core::game::GameSpeed;
+use dr_rustario_core::high_score::table::HighScoreTable;
+use dr_rustario_core::input::GameInputKey;
+use dr_rustario_core::player::{Match, MatchState, OVERTIME_BANNER_DURATION};
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_micros(16_667);
+
+/// Both players have the same seed, so the same bottle & pills
+fn vs_match(rules: MatchRules) -> Match {
+    let mut game_config = GameConfig::new(
+        2,
+        5,
+        GameSpeed::Low,
+        MatchThemes::Nes,
+        rules,
+        RandomMode::default(),
+    );
+    game_config.set_seed(Some(1234));
+    Match::new_with_high_scores(game_config, &[], HighScoreTable::default())
+}
+
+fn update(fixture: &mut Match, frames: usize) {
+    for _ in 0..frames {
+        for player in fixture.players.iter_mut() {
+            player.game_mut().update(FRAME);
+        }
+    }
+}
+
+#[test]
+fn draws_when_every_player_tops_out_together() {
+    let mut fixture = vs_match(MatchRules::default_by_players(2));
+    // the game over of each player makes the others winners
+    fixture.set_winner(1);
+    assert!(!fixture.is_draw());
+    assert_eq!(fixture.check_for_winning_player(), Some(1));
+    fixture.set_winner(0);
+    assert!(fixture.is_draw());
+    assert_eq!(fixture.check_for_winning_player(), None);
+}
+
+#[test]
+fn draws_on_a_score_sprint_tie() {
+    let mut fixture = vs_match(MatchRules::ScoreSprint { score: 1 });
+    update(&mut fixture, 60);
+    assert!(!fixture.is_draw());
+
+    // the same drop of the same pill scores the same
+    for player in 0..2 {
+        fixture.apply_key(GameInputKey::HardDrop { player });
+    }
+    update(&mut fixture, 60);
+    let score = fixture.player(0).game().metrics().score();
+    assert!(score > 0);
+    assert_eq!(fixture.player(1).game().metrics().score(), score);
+    assert!(fixture.is_draw());
+    assert_eq!(fixture.check_for_winning_player(), None);
+}
+
+#[test]
+fn overtime_reseeds_bottles_for_sudden_death() {
+    let mut fixture = vs_match(MatchRules::default_by_players(2));
+    fixture.set_winner(0);
+    fixture.set_winner(1);
+    fixture.start_overtime().unwrap();
+
+    assert!(fixture.is_overtime());
+    assert!(matches!(fixture.state(), MatchState::Overtime { .. }));
+    for player in fixture.players.iter() {
+        assert!(!player.is_winner());
+        assert_eq!(player.game().metrics().virus_count(), 4);
+    }
+    // only a clear wins, which ends the match
+    assert_eq!(fixture.check_for_winning_player(), None);
+    assert!(fixture.next_level_ends_match(0));
+
+    fixture.update(OVERTIME_BANNER_DURATION);
+    assert!(fixture.state().is_normal());
+    assert!(!fixture.is_draw());
+    fixture.set_winner(1);
+    assert_eq!(fixture.check_for_winning_player(), Some(1));
+}
@@ -0,0 +1,60 @@
+//! The flat scene against the real bottle, the unit tests in the crate use a mock.
+
+use dr_rustario_core::game::random::{random, RandomMode};
+use dr_rustario_core::game::{Game, GameSpeed};
+use dr_rustario_core::render::{draw_flat_scene, Rect, Renderer, Rgb};
+
+/// Bottle background in the flat scene
+const BOTTLE: Rgb = (32, 32, 48);
+
+struct RecordingRenderer {
+    size: (u32, u32),
+    rects: Vec<(Rect, Rgb)>,
+    presented: bool,
+}
+
+impl Renderer for RecordingRenderer {
+    fn output_size(&self) -> Result<(u32, u32), String> {
+        Ok(self.size)
+    }
+
+    fn clear(&mut self, _color: Rgb) {
+        self.rects.clear();
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Rgb) -> Result<(), String> {
+        self.rects.push((rect, color));
+        Ok(())
+    }
+
+    fn present(&mut self) {
+        self.presented = true;
+    }
+}
+
+#[test]
+fn draws_every_bottle_inside_the_output() {
+    let games = random(2, RandomMode::Bag, Some(1))
+        .into_iter()
+        .map(|r| Game::new(0, 5, GameSpeed::Medium, r).unwrap())
+        .collect::<Vec<Game>>();
+    let viruses = games[0].metrics().virus_count() as usize;
+
+    let mut renderer = RecordingRenderer {
+        size: (640, 480),
+        rects: vec![],
+        presented: false,
+    };
+    let games = games.iter().collect::<Vec<&Game>>();
+    draw_flat_scene(&mut renderer, &games).unwrap();
+
+    assert!(renderer.presented);
+    let bottles = renderer.rects.iter().filter(|(_, c)| *c == BOTTLE).count();
+    assert_eq!(bottles, 2);
+    assert!(renderer.rects.len() > 2 + viruses);
+    for (rect, _) in renderer.rects {
+        assert!(rect.x >= 0 && rect.y >= 0);
+        assert!(rect.x as u32 + rect.width <= 640);
+        assert!(rect.y as u32 + rect.height <= 480);
+    }
+}
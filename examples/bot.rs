@@ -0,0 +1,105 @@
+//! Plays a single player game with a greedy bot, printing the result of each level.
+//!
+//! ```shell
+//! cargo run --example bot -- 10
+//! ```
+//!
+//! The game engine in `dr-rustario-core` has no dependency on SDL,
+//! swap `best_placement` for your own evaluator from `game::eval` to build a stronger bot.
+
+use dr_rustario_core::game::eval::{best_placement, read_board, ActivePill};
+use dr_rustario_core::game::event::GameEvent;
+use dr_rustario_core::game::random::{random, RandomMode};
+use dr_rustario_core::game::rules::MAX_VIRUS_LEVEL;
+use dr_rustario_core::game::{Game, GameSpeed};
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_micros(16_667);
+/// Inputs made per pill before giving up on reaching the target, e.g. when blocked by the stack
+const MAX_ACTIONS: u32 = 16;
+/// Give up on a level that takes longer than this much game time
+const MAX_LEVEL_TIME: Duration = Duration::from_secs(60 * 30);
+
+/// Steers the active pill straight to the best placement & hard drops it
+fn play_pill(game: &mut Game) {
+    let pill = match ActivePill::read(game) {
+        None => return,
+        Some(pill) => pill,
+    };
+    let target = best_placement(&read_board(game), pill.colors);
+
+    for _ in 0..MAX_ACTIONS {
+        let pill = match ActivePill::read(game) {
+            None => return,
+            Some(pill) => pill,
+        };
+        if pill.rotation != target.rotation {
+            game.rotate(true);
+        } else if pill.x < target.x {
+            game.right();
+        } else if pill.x > target.x {
+            game.left();
+        } else {
+            break;
+        }
+    }
+    game.hard_drop();
+}
+
+fn main() -> Result<(), String> {
+    let virus_level = match std::env::args().nth(1) {
+        None => 0,
+        Some(level) => level
+            .parse::<u32>()
+            .map_err(|e| e.to_string())?
+            .min(MAX_VIRUS_LEVEL),
+    };
+
+    let random = random(1, RandomMode::Bag, None).pop().unwrap();
+    let mut game = Game::new(0, virus_level, GameSpeed::Medium, random)?;
+    let mut events = vec![];
+    let mut level_time = Duration::ZERO;
+
+    loop {
+        play_pill(&mut game);
+        game.update(FRAME);
+        level_time += FRAME;
+
+        events.clear();
+        game.consume_events(&mut events);
+        for event in events.iter() {
+            match event {
+                GameEvent::LevelComplete { .. } => {
+                    let stats = game.stats();
+                    println!(
+                        "level {} cleared in {:.1}s, {} pills, max combo {}, score {}",
+                        game.virus_level(),
+                        stats.time().as_secs_f64(),
+                        stats.pills(),
+                        stats.max_combo(),
+                        game.metrics().score()
+                    );
+                    if game.virus_level() >= MAX_VIRUS_LEVEL {
+                        return Ok(());
+                    }
+                    game.next_level()?;
+                    level_time = Duration::ZERO;
+                }
+                GameEvent::GameOver { .. } => {
+                    println!(
+                        "game over on level {}, score {}",
+                        game.virus_level(),
+                        game.metrics().score()
+                    );
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        if level_time > MAX_LEVEL_TIME {
+            println!("gave up on level {}", game.virus_level());
+            return Ok(());
+        }
+    }
+}
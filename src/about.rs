@@ -0,0 +1,194 @@
+use crate::build_info;
+use crate::config::config_path;
+use crate::font::{FontTexture, FontType};
+use crate::menu_input::MenuInputKey;
+use crate::profile::Profile;
+use crate::scene::{Scene, SceneInput};
+use crate::theme::external::themes_dir;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+use sdl2::AudioSubsystem;
+
+const FONT_COLOR: Color = Color::WHITE;
+const LABEL_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Label & value pairs describing this build and the platform it is running on, useful for bug
+/// reports. External themes that failed to load are listed last.
+pub fn about_info(
+    canvas: &WindowCanvas,
+    audio: &AudioSubsystem,
+    theme_errors: &[String],
+    profile: &Profile,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let path = |name: &str| -> Result<String, String> {
+        Ok(config_path(name)?.to_string_lossy().to_string())
+    };
+    let profile_path = |name: &str| -> Result<String, String> {
+        Ok(profile.config_path(name)?.to_string_lossy().to_string())
+    };
+    let mut info = vec![
+        ("version", build_info::PKG_VERSION.to_string()),
+        ("commit", build_info::git_hash()),
+        ("built", build_info::BUILT_TIME_UTC.to_string()),
+        (
+            "target",
+            format!("{} {}", build_info::TARGET, build_info::PROFILE),
+        ),
+        ("config", path("config")?),
+        ("profile", profile.name().unwrap_or(DEFAULT_PROFILE).to_string()),
+        ("high scores", profile_path(crate::high_score::table::CONFIG_NAME)?),
+        ("daily scores", profile_path(crate::high_score::daily::CONFIG_NAME)?),
+        ("statistics", profile_path(dr_rustario_core::stats::CONFIG_NAME)?),
+        ("achievements", profile_path(crate::achievements::CONFIG_NAME)?),
+        ("cheats", profile_path(crate::cheat::CONFIG_NAME)?),
+        ("themes", themes_dir()?.to_string_lossy().to_string()),
+        ("sdl", sdl2::version::version().to_string()),
+        ("mixer", sdl2::mixer::get_linked_version().to_string()),
+        (
+            "video",
+            canvas
+                .window()
+                .subsystem()
+                .current_video_driver()
+                .to_string(),
+        ),
+        ("renderer", canvas.info().name.to_string()),
+        ("audio", audio.current_audio_driver().to_string()),
+        ("source", build_info::PKG_REPOSITORY.to_string()),
+    ];
+    info.extend(theme_errors.iter().map(|error| ("theme error", error.clone())));
+    Ok(info)
+}
+
+struct AboutRow<'a> {
+    label: FontTexture<'a>,
+    value: FontTexture<'a>,
+}
+
+pub struct AboutScreen<'a> {
+    title: FontTexture<'a>,
+    title_rect: Rect,
+    rows: Vec<AboutRow<'a>>,
+    label_width: u32,
+    row_height: u32,
+    padding: u32,
+    window_size: (u32, u32),
+}
+
+impl<'a> AboutScreen<'a> {
+    pub fn new<L: AsRef<str>>(
+        title: &str,
+        info: &[(L, String)],
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        (window_width, window_height): (u32, u32),
+    ) -> Result<Self, String> {
+        // small enough to fit long config paths
+        let font_size = window_height / (info.len() as u32 + 10);
+        let label_font = FontType::Bold.load(ttf, font_size)?;
+        let value_font = FontType::Mono.load(ttf, font_size)?;
+        let title_font = FontType::Retro.load(ttf, window_width / 24)?;
+        let padding = font_size / 2;
+
+        let rows = info
+            .iter()
+            .map(|(label, value)| {
+                Ok(AboutRow {
+                    label: FontTexture::from_string(
+                        &label_font,
+                        texture_creator,
+                        label.as_ref(),
+                        LABEL_COLOR,
+                    )?,
+                    value: FontTexture::from_string(
+                        &value_font,
+                        texture_creator,
+                        value,
+                        FONT_COLOR,
+                    )?,
+                })
+            })
+            .collect::<Result<Vec<AboutRow>, String>>()?;
+
+        let title = FontTexture::from_string(&title_font, texture_creator, title, FONT_COLOR)?;
+        let title_rect = Rect::new(
+            (window_width - title.width) as i32 / 2,
+            padding as i32,
+            title.width,
+            title.height,
+        );
+
+        let label_width = rows.iter().map(|r| r.label.width).max().unwrap_or(0);
+        let row_height = rows
+            .iter()
+            .map(|r| r.label.height.max(r.value.height))
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self {
+            title,
+            title_rect,
+            rows,
+            label_width,
+            row_height,
+            padding,
+            window_size: (window_width, window_height),
+        })
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        canvas.copy(&self.title.texture, None, self.title_rect)?;
+
+        let (window_width, window_height) = self.window_size;
+        let value_width = self.rows.iter().map(|r| r.value.width).max().unwrap_or(0);
+        let width = self.label_width + self.padding * 2 + value_width;
+        let n_rows = self.rows.len() as u32;
+        let height = n_rows * self.row_height + n_rows.saturating_sub(1) * self.padding;
+
+        // center the table, keeping it below the title
+        let x = (window_width as i32 - width as i32).max(0) / 2;
+        let mut y = ((window_height as i32 - height as i32) / 2).max(self.title_rect.bottom());
+        for row in self.rows.iter() {
+            // labels are right aligned against the values
+            canvas.copy(
+                &row.label.texture,
+                None,
+                Rect::new(
+                    x + (self.label_width - row.label.width) as i32,
+                    y,
+                    row.label.width,
+                    row.label.height,
+                ),
+            )?;
+            canvas.copy(
+                &row.value.texture,
+                None,
+                Rect::new(
+                    x + (self.label_width + self.padding * 2) as i32,
+                    y,
+                    row.value.width,
+                    row.value.height,
+                ),
+            )?;
+            y += (self.row_height + self.padding) as i32;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for AboutScreen<'_> {
+    type Output = ();
+
+    /// Closed by any button press
+    fn handle_input(&mut self, _key: MenuInputKey) -> SceneInput<()> {
+        SceneInput::Exit(())
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        AboutScreen::draw(self, canvas)
+    }
+}
@@ -0,0 +1,220 @@
+//! Achievements unlocked by player 1 from the events of their games, kept across sessions.
+//! Progress toward an achievement only counts within a single match.
+
+pub mod toast;
+
+use crate::game::event::GameEvent;
+use crate::profile::Profile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use strum::IntoEnumIterator;
+
+pub const CONFIG_NAME: &str = "achievements";
+const BIG_CLEAR_VIRUSES: usize = 4;
+const LONG_CHAIN: u32 = 3;
+const HIGH_SPEED_LEVEL: u32 = 20;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    strum::EnumIter,
+    Serialize,
+    Deserialize,
+)]
+pub enum Achievement {
+    LevelClear,
+    BigClear,
+    LongChain,
+    NoHoldVictory,
+    HighSpeed,
+}
+
+impl Achievement {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::LevelClear => "house call",
+            Achievement::BigClear => "outbreak",
+            Achievement::LongChain => "chain reaction",
+            Achievement::NoHoldVictory => "no second opinion",
+            Achievement::HighSpeed => "adrenaline",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::LevelClear => "clear a level",
+            Achievement::BigClear => "destroy 4 viruses at once",
+            Achievement::LongChain => "clear with 3 pills in a row",
+            Achievement::NoHoldVictory => "win a vs. match without hold",
+            Achievement::HighSpeed => "reach speed level 20 in a match",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementStore {
+    unlocked: BTreeSet<Achievement>,
+}
+
+impl AchievementStore {
+    pub fn load(profile: &Profile) -> Result<Self, String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::load_path(config_path).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, profile: &Profile) -> Result<(), String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::store_path(config_path, self).map_err(|e| e.to_string())
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    /// Label & value pairs of every achievement, for the achievements screen
+    pub fn info(&self) -> Vec<(&'static str, String)> {
+        Achievement::iter()
+            .map(|achievement| {
+                let state = if self.is_unlocked(achievement) {
+                    "unlocked"
+                } else {
+                    "locked"
+                };
+                (
+                    achievement.name(),
+                    format!("{} - {}", state, achievement.description()),
+                )
+            })
+            .collect()
+    }
+
+    /// True if the achievement was not already unlocked
+    fn unlock(&mut self, achievement: Achievement) -> bool {
+        self.unlocked.insert(achievement)
+    }
+}
+
+/// Progress of player 1 through the current match
+#[derive(Clone, Copy, Debug, Default)]
+struct MatchProgress {
+    holds: u32,
+    speed_levels: u32,
+}
+
+pub struct Achievements {
+    store: AchievementStore,
+    progress: MatchProgress,
+    is_single_player: bool,
+}
+
+impl Achievements {
+    pub fn new(store: AchievementStore, is_single_player: bool) -> Self {
+        Self {
+            store,
+            progress: MatchProgress::default(),
+            is_single_player,
+        }
+    }
+
+    pub fn store(&self) -> &AchievementStore {
+        &self.store
+    }
+
+    /// Achievements newly unlocked by the event
+    pub fn receive_event(&mut self, event: &GameEvent) -> Vec<Achievement> {
+        if event.player() != Some(0) {
+            return vec![];
+        }
+        let achievement = match event {
+            GameEvent::Hold { .. } => {
+                self.progress.holds += 1;
+                None
+            }
+            // the speed up key is not an achievement
+            GameEvent::SpeedLevelUp {
+                is_boost: false, ..
+            } => {
+                self.progress.speed_levels += 1;
+                Some(Achievement::HighSpeed)
+                    .filter(|_| self.progress.speed_levels >= HIGH_SPEED_LEVEL)
+            }
+            GameEvent::LevelComplete { .. } => Some(Achievement::LevelClear),
+            GameEvent::Destroy { blocks, .. } => Some(Achievement::BigClear)
+                .filter(|_| blocks.iter().filter(|b| b.is_virus).count() >= BIG_CLEAR_VIRUSES),
+            GameEvent::Chain { chain, .. } => {
+                Some(Achievement::LongChain).filter(|_| *chain >= LONG_CHAIN)
+            }
+            GameEvent::Victory { .. } => Some(Achievement::NoHoldVictory)
+                .filter(|_| !self.is_single_player && self.progress.holds == 0),
+            _ => None,
+        };
+        achievement
+            .filter(|&achievement| self.store.unlock(achievement))
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::pill::{Pill, PillShape};
+
+    #[test]
+    fn unlocks_once() {
+        let mut achievements = Achievements::new(AchievementStore::default(), true);
+        let event = GameEvent::LevelComplete { player: 0 };
+        assert_eq!(achievements.receive_event(&event), vec![Achievement::LevelClear]);
+        assert!(achievements.receive_event(&event).is_empty());
+        assert!(achievements.store().is_unlocked(Achievement::LevelClear));
+    }
+
+    #[test]
+    fn victory_without_hold() {
+        let victory = GameEvent::Victory { player: 0 };
+        let hold = |player| GameEvent::Hold {
+            player,
+            shape: PillShape::RB,
+            vitamins: Pill::new(PillShape::RB).vitamins(),
+            released: None,
+        };
+        let mut achievements = Achievements::new(AchievementStore::default(), false);
+        achievements.receive_event(&hold(1));
+        assert_eq!(achievements.receive_event(&victory), vec![Achievement::NoHoldVictory]);
+
+        let mut achievements = Achievements::new(AchievementStore::default(), false);
+        achievements.receive_event(&hold(0));
+        assert!(achievements.receive_event(&victory).is_empty());
+    }
+
+    #[test]
+    fn speed_level_counts_up() {
+        let mut achievements = Achievements::new(AchievementStore::default(), true);
+        let event = GameEvent::SpeedLevelUp {
+            player: 0,
+            is_boost: false,
+        };
+        for _ in 1..HIGH_SPEED_LEVEL {
+            assert!(achievements.receive_event(&event).is_empty());
+        }
+        assert_eq!(achievements.receive_event(&event), vec![Achievement::HighSpeed]);
+    }
+
+    #[test]
+    fn speed_up_key_does_not_count() {
+        let mut achievements = Achievements::new(AchievementStore::default(), true);
+        let event = GameEvent::SpeedLevelUp {
+            player: 0,
+            is_boost: true,
+        };
+        for _ in 0..HIGH_SPEED_LEVEL {
+            assert!(achievements.receive_event(&event).is_empty());
+        }
+    }
+}
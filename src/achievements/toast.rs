@@ -0,0 +1,117 @@
+use crate::achievements::Achievement;
+use crate::font::{FontTexture, FontType};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+const TOAST_DURATION: Duration = Duration::from_millis(3000);
+const TOAST_FADE: Duration = Duration::from_millis(500);
+const TITLE: &str = "achievement unlocked";
+const TITLE_COLOR: Color = Color::RGB(0xff, 0xd7, 0x00);
+
+/// Notifies each newly unlocked achievement in turn at the top of the window
+pub struct AchievementToasts<'a> {
+    title: FontTexture<'a>,
+    names: HashMap<Achievement, FontTexture<'a>>,
+    queue: VecDeque<Achievement>,
+    elapsed: Duration,
+}
+
+impl<'a> AchievementToasts<'a> {
+    pub fn new(
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        window_height: u32,
+    ) -> Result<Self, String> {
+        let title_font = FontType::Bold.load(ttf, (window_height / 48).max(8))?;
+        let name_font = FontType::Retro.load(ttf, (window_height / 24).max(8))?;
+        let title = FontTexture::from_string(&title_font, texture_creator, TITLE, TITLE_COLOR)?;
+        let names = Achievement::iter()
+            .map(|achievement| {
+                let name = FontTexture::from_string(
+                    &name_font,
+                    texture_creator,
+                    achievement.name(),
+                    Color::WHITE,
+                )?;
+                Ok((achievement, name))
+            })
+            .collect::<Result<HashMap<Achievement, FontTexture>, String>>()?;
+        Ok(Self {
+            title,
+            names,
+            queue: VecDeque::new(),
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    pub fn push(&mut self, achievement: Achievement) {
+        self.queue.push_back(achievement);
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        if self.queue.is_empty() {
+            return;
+        }
+        self.elapsed += delta;
+        if self.elapsed >= TOAST_DURATION {
+            self.queue.pop_front();
+            self.elapsed = Duration::ZERO;
+        }
+    }
+
+    pub fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let achievement = match self.queue.front() {
+            None => return Ok(()),
+            Some(achievement) => *achievement,
+        };
+        let remaining = TOAST_DURATION.saturating_sub(self.elapsed);
+        let alpha = if remaining >= TOAST_FADE {
+            u8::MAX
+        } else {
+            (255.0 * remaining.as_secs_f64() / TOAST_FADE.as_secs_f64()) as u8
+        };
+
+        let name = self.names.get_mut(&achievement).unwrap();
+        let padding = self.title.height / 2;
+        let width = self.title.width.max(name.width) + padding * 4;
+        let height = self.title.height + name.height + padding * 2;
+        let (window_width, _) = canvas.output_size()?;
+        let x = (window_width as i32 - width as i32) / 2;
+        let y = padding as i32;
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, (alpha as f64 * 0.8) as u8));
+        canvas.fill_rect(Rect::new(x, y, width, height))?;
+        canvas.set_blend_mode(BlendMode::None);
+
+        let center_x = x + width as i32 / 2;
+        self.title.texture.set_alpha_mod(alpha);
+        canvas.copy(
+            &self.title.texture,
+            None,
+            Rect::new(
+                center_x - self.title.width as i32 / 2,
+                y + padding as i32,
+                self.title.width,
+                self.title.height,
+            ),
+        )?;
+        name.texture.set_alpha_mod(alpha);
+        canvas.copy(
+            &name.texture,
+            None,
+            Rect::new(
+                center_x - name.width as i32 / 2,
+                y + (padding + self.title.height) as i32,
+                name.width,
+                name.height,
+            ),
+        )
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+const POP_DURATION: Duration = Duration::from_millis(300);
+/// Extra zoom on the chain counter at the start of a pop
+const POP_ZOOM: f64 = 0.6;
+
+#[derive(Clone, Copy, Debug)]
+struct State {
+    duration: Duration,
+}
+
+impl State {
+    /// Zoom factor for the chain counter, eases back to 1.0 over the pop
+    fn zoom(&self) -> f64 {
+        let t = (self.duration.as_secs_f64() / POP_DURATION.as_secs_f64()).min(1.0);
+        1.0 + POP_ZOOM * (1.0 - t) * (1.0 - t)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChainAnimation {
+    state: Option<State>,
+}
+
+impl ChainAnimation {
+    pub fn new() -> Self {
+        Self { state: None }
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        if let Some(state) = self.state.as_mut() {
+            state.duration += delta;
+            if state.duration >= POP_DURATION {
+                self.state = None;
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+
+    /// Pops the counter when the chain is extended
+    pub fn chain(&mut self) {
+        self.state = Some(State {
+            duration: Duration::ZERO,
+        });
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.state.map(|s| s.zoom()).unwrap_or(1.0)
+    }
+}
@@ -0,0 +1,129 @@
+use crate::game::event::VirusCrawl;
+use crate::game::geometry::BottlePoint;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+const CRAWL_DURATION: Duration = Duration::from_millis(300);
+/// Steps a hopping virus takes between blocks
+const HOP_STEPS: f64 = 4.0;
+/// Height of a hop as a ratio of a block
+const HOP_HEIGHT: f64 = 0.25;
+
+/// How a theme animates a virus crawling into the next block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VirusCrawlType {
+    /// Hops across in a few steps, in keeping with the retro sprites
+    Hop,
+    /// Slides smoothly across
+    Slide,
+}
+
+#[derive(Clone, Debug)]
+pub struct State {
+    crawl_type: VirusCrawlType,
+    crawls: Vec<VirusCrawl>,
+    duration: Duration,
+}
+
+impl State {
+    /// Offset of a virus that is still crawling from the block it is now in, as a ratio of a block
+    pub fn offset(&self, point: BottlePoint) -> Option<(f64, f64)> {
+        let crawl = self.crawls.iter().find(|c| c.to == point)?;
+        let progress = (self.duration.as_secs_f64() / CRAWL_DURATION.as_secs_f64()).min(1.0);
+        let dx = (crawl.from.x() - crawl.to.x()) as f64;
+        let dy = (crawl.from.y() - crawl.to.y()) as f64;
+        Some(match self.crawl_type {
+            VirusCrawlType::Hop => {
+                let remaining = ((1.0 - progress) * HOP_STEPS).ceil() / HOP_STEPS;
+                let hop = HOP_HEIGHT * (progress * PI).sin();
+                (dx * remaining, dy * remaining - hop)
+            }
+            VirusCrawlType::Slide => {
+                // eases in & out
+                let eased = progress * progress * (3.0 - 2.0 * progress);
+                (dx * (1.0 - eased), dy * (1.0 - eased))
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VirusCrawlAnimation {
+    crawl_type: VirusCrawlType,
+    state: Option<State>,
+}
+
+impl VirusCrawlAnimation {
+    pub fn new(crawl_type: VirusCrawlType) -> Self {
+        Self {
+            crawl_type,
+            state: None,
+        }
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        if let Some(state) = self.state.as_mut() {
+            state.duration += delta;
+            if state.duration >= CRAWL_DURATION {
+                self.state = None;
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+
+    pub fn crawl(&mut self, crawls: Vec<VirusCrawl>) {
+        self.state = Some(State {
+            crawl_type: self.crawl_type,
+            crawls,
+            duration: Duration::ZERO,
+        });
+    }
+
+    pub fn state(&self) -> Option<&State> {
+        self.state.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::pill::VirusColor;
+
+    fn crawling(crawl_type: VirusCrawlType, duration: Duration) -> VirusCrawlAnimation {
+        let mut animation = VirusCrawlAnimation::new(crawl_type);
+        animation.crawl(vec![VirusCrawl {
+            color: VirusColor::Red,
+            from: BottlePoint::new(2, 10),
+            to: BottlePoint::new(3, 10),
+        }]);
+        animation.update(duration);
+        animation
+    }
+
+    #[test]
+    fn starts_from_the_previous_block() {
+        for crawl_type in [VirusCrawlType::Hop, VirusCrawlType::Slide] {
+            let animation = crawling(crawl_type, Duration::ZERO);
+            let state = animation.state().unwrap();
+            assert_eq!(state.offset(BottlePoint::new(3, 10)), Some((-1.0, 0.0)));
+            assert_eq!(state.offset(BottlePoint::new(2, 10)), None);
+        }
+    }
+
+    #[test]
+    fn hops_in_steps() {
+        let animation = crawling(VirusCrawlType::Hop, CRAWL_DURATION / 2);
+        let (dx, dy) = animation.state().unwrap().offset(BottlePoint::new(3, 10)).unwrap();
+        assert_eq!(dx, -0.5);
+        assert!(dy < 0.0);
+    }
+
+    #[test]
+    fn finishes() {
+        let animation = crawling(VirusCrawlType::Slide, CRAWL_DURATION);
+        assert!(animation.state().is_none());
+    }
+}
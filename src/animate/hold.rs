@@ -0,0 +1,105 @@
+use crate::game::pill::PillShape;
+use sdl2::rect::Point;
+use std::time::Duration;
+
+const HOLD_DURATION: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug)]
+pub struct State {
+    duration: Duration,
+    shape: PillShape,
+    from: Point,
+    released: Option<PillShape>,
+}
+
+impl State {
+    /// From 0 as the pill leaves the bottle to 1 as it lands in the hold box
+    pub fn progress(&self) -> f64 {
+        (self.duration.as_secs_f64() / HOLD_DURATION.as_secs_f64()).min(1.0)
+    }
+
+    pub fn shape(&self) -> PillShape {
+        self.shape
+    }
+
+    pub fn released(&self) -> Option<PillShape> {
+        self.released
+    }
+}
+
+/// The held pill flies from the bottle into the hold box as the pill held before flies out of it
+/// to the dr's hand, to be thrown back into the bottle
+#[derive(Clone, Debug)]
+pub struct HoldAnimation {
+    state: Option<State>,
+    hold_point: Point,
+    throw_start: Point,
+}
+
+impl HoldAnimation {
+    pub fn new(hold_point: Point, throw_start: Point) -> Self {
+        Self {
+            state: None,
+            hold_point,
+            throw_start,
+        }
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        if let Some(state) = self.state.as_mut() {
+            state.duration += delta;
+            if state.duration >= HOLD_DURATION {
+                self.state = None;
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+
+    /// A pill was held from a point of the background
+    pub fn hold(&mut self, shape: PillShape, from: Point, released: Option<PillShape>) {
+        self.state = Some(State {
+            duration: Duration::ZERO,
+            shape,
+            from,
+            released,
+        });
+    }
+
+    pub fn state(&self) -> Option<&State> {
+        self.state.as_ref()
+    }
+
+    pub fn held_position(&self, state: &State) -> Point {
+        lerp(state.from, self.hold_point, state.progress())
+    }
+
+    pub fn released_position(&self, state: &State) -> Point {
+        lerp(self.hold_point, self.throw_start, state.progress())
+    }
+}
+
+fn lerp(from: Point, to: Point, t: f64) -> Point {
+    let lerp = |from: i32, to: i32| (from as f64 + (to - from) as f64 * t).round() as i32;
+    Point::new(lerp(from.x(), to.x()), lerp(from.y(), to.y()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flies_between_bottle_hold_box_and_hand() {
+        let mut animation = HoldAnimation::new(Point::new(10, 10), Point::new(110, 50));
+        animation.hold(PillShape::RB, Point::new(50, 90), Some(PillShape::RR));
+        animation.update(HOLD_DURATION / 2);
+        let state = *animation.state().unwrap();
+        assert_eq!(animation.held_position(&state), Point::new(30, 50));
+        assert_eq!(animation.released_position(&state), Point::new(60, 30));
+
+        animation.update(HOLD_DURATION / 2);
+        assert!(animation.state().is_none());
+    }
+}
@@ -16,6 +16,7 @@ pub struct ImpactAnimation {
     offset_x: f64,
     offset_y: f64,
     state: State,
+    intensity: f64,
 }
 
 impl ImpactAnimation {
@@ -24,9 +25,15 @@ impl ImpactAnimation {
             offset_x: 0.0,
             offset_y: 0.0,
             state: State::Rest,
+            intensity: 1.0,
         }
     }
 
+    /// Scales the shake, 0.0 keeps the bottle still
+    pub fn set_intensity(&mut self, intensity: f64) {
+        self.intensity = intensity;
+    }
+
     pub fn update(&mut self, delta: Duration) {
         self.state = match self.state {
             State::Rest => State::Rest,
@@ -92,6 +99,6 @@ impl ImpactAnimation {
     }
 
     pub fn current_offset(&self) -> (f64, f64) {
-        (self.offset_x, self.offset_y)
+        (self.offset_x * self.intensity, self.offset_y * self.intensity)
     }
 }
@@ -0,0 +1,157 @@
+use crate::game::bottle::VirusCounts;
+use crate::game::pill::VirusColor;
+use std::f64::consts::TAU;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+/// Time taken for the viruses to circle the glass once
+const LAP_DURATION: Duration = Duration::from_secs(12);
+/// Distance of the viruses from the center of the glass, as a fraction of its radius
+const LAP_RADIUS: f64 = 0.45;
+/// A virus winces when viruses of its color are cleared
+const WINCE_DURATION: Duration = Duration::from_millis(600);
+/// Shakes of a wincing virus, each side to side
+const WINCE_SHAKES: f64 = 4.0;
+/// Furthest a wincing virus shakes, as a fraction of its size
+const WINCE_SHAKE: f64 = 0.15;
+/// A virus pops once the last of its color is cleared
+const POP_DURATION: Duration = Duration::from_millis(600);
+
+#[derive(Clone, Copy, Debug, Default)]
+struct LoungeState {
+    /// time since viruses of this color were last cleared
+    wince: Option<Duration>,
+    /// time since the last virus of this color was cleared
+    eliminated: Option<Duration>,
+}
+
+/// A virus lounging in the glass
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoungeVirus {
+    pub color: VirusColor,
+    /// from the center of the glass, as a fraction of its radius
+    pub offset: (f64, f64),
+    /// side to side while wincing, as a fraction of the virus size
+    pub shake: f64,
+    /// frame of the pop once the last of its color is cleared
+    pub pop_frame: Option<usize>,
+}
+
+/// The viruses lounging in the magnifying glass of the NES & arcade, one of each color still in the
+/// bottle. They wince when their color is cleared & pop when it is eliminated
+#[derive(Clone, Debug)]
+pub struct VirusLoungeAnimation {
+    pop_frames: usize,
+    duration: Duration,
+    states: [LoungeState; VirusColor::N],
+}
+
+impl VirusLoungeAnimation {
+    pub fn new(pop_frames: usize) -> Self {
+        assert!(pop_frames > 0);
+        Self {
+            pop_frames,
+            duration: Duration::ZERO,
+            states: Default::default(),
+        }
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        self.duration += delta;
+        for state in self.states.iter_mut() {
+            state.wince = state
+                .wince
+                .map(|d| d + delta)
+                .filter(|d| *d < WINCE_DURATION);
+            state.eliminated = state
+                .eliminated
+                .map(|d| d + delta)
+                .filter(|d| *d < POP_DURATION);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.states = Default::default();
+    }
+
+    /// Viruses of a color have been cleared
+    pub fn wince(&mut self, color: VirusColor) {
+        self.states[color as usize].wince = Some(Duration::ZERO);
+    }
+
+    /// The last virus of a color has been cleared
+    pub fn eliminate(&mut self, color: VirusColor) {
+        self.states[color as usize].eliminated = Some(Duration::ZERO);
+    }
+
+    /// A virus for each color still in the bottle or popping, spread evenly around the glass
+    pub fn viruses(&self, virus_counts: VirusCounts) -> Vec<LoungeVirus> {
+        let colors = VirusColor::iter()
+            .filter(|&c| {
+                virus_counts[c as usize] > 0 || self.states[c as usize].eliminated.is_some()
+            })
+            .collect::<Vec<VirusColor>>();
+        let lap = self.duration.as_secs_f64() / LAP_DURATION.as_secs_f64();
+        colors
+            .iter()
+            .enumerate()
+            .map(|(i, &color)| {
+                let state = self.states[color as usize];
+                let angle = TAU * (lap + i as f64 / colors.len() as f64);
+                let shake = state.wince.map_or(0.0, |d| {
+                    let t = d.as_secs_f64() / WINCE_DURATION.as_secs_f64();
+                    WINCE_SHAKE * (1.0 - t) * (TAU * WINCE_SHAKES * t).sin()
+                });
+                let pop_frame = state.eliminated.map(|d| {
+                    let t = d.as_secs_f64() / POP_DURATION.as_secs_f64();
+                    ((t * self.pop_frames as f64) as usize).min(self.pop_frames - 1)
+                });
+                LoungeVirus {
+                    color,
+                    offset: (LAP_RADIUS * angle.cos(), LAP_RADIUS * angle.sin()),
+                    shake,
+                    pop_frame,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lounges_a_virus_of_each_color_left() {
+        let lounge = VirusLoungeAnimation::new(2);
+        let colors = lounge
+            .viruses([1, 0, 2, 0])
+            .into_iter()
+            .map(|v| v.color)
+            .collect::<Vec<VirusColor>>();
+        assert_eq!(colors, vec![VirusColor::Yellow, VirusColor::Red]);
+    }
+
+    #[test]
+    fn winces_then_settles() {
+        let mut lounge = VirusLoungeAnimation::new(2);
+        lounge.wince(VirusColor::Blue);
+        lounge.update(WINCE_DURATION / 8);
+        assert_ne!(lounge.viruses([1, 1, 1, 0])[1].shake, 0.0);
+        lounge.update(WINCE_DURATION);
+        assert_eq!(lounge.viruses([1, 1, 1, 0])[1].shake, 0.0);
+    }
+
+    #[test]
+    fn pops_when_eliminated() {
+        let mut lounge = VirusLoungeAnimation::new(2);
+        lounge.eliminate(VirusColor::Yellow);
+        lounge.update(POP_DURATION * 3 / 4);
+        let viruses = lounge.viruses([0, 1, 1, 0]);
+        assert_eq!(viruses.len(), 3);
+        assert_eq!(viruses[0].pop_frame, Some(1));
+
+        lounge.update(POP_DURATION);
+        assert_eq!(lounge.viruses([0, 1, 1, 0]).len(), 2);
+    }
+}
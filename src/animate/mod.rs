@@ -1,26 +1,36 @@
+pub mod chain;
+pub mod crawl;
 pub mod destroy;
 pub mod dr;
 pub mod event;
 pub mod game_over;
 pub mod hard_drop;
+pub mod hold;
 pub mod idle;
 pub mod impact;
 pub mod lock;
+pub mod lounge;
 pub mod next_level;
 pub mod next_level_interstitial;
+pub mod score_popup;
 pub mod throw;
 pub mod victory;
 pub mod virus;
 
+use crate::animate::chain::ChainAnimation;
+use crate::animate::crawl::VirusCrawlAnimation;
 use crate::animate::destroy::DestroyAnimation;
 use crate::animate::event::{AnimationEvent, AnimationType};
 use crate::animate::game_over::GameOverAnimation;
 use crate::animate::hard_drop::HardDropAnimation;
+use crate::animate::hold::HoldAnimation;
 use crate::animate::idle::IdleAnimation;
 use crate::animate::impact::ImpactAnimation;
 use crate::animate::lock::LockAnimation;
+use crate::animate::lounge::VirusLoungeAnimation;
 use crate::animate::next_level::NextLevelAnimation;
 use crate::animate::next_level_interstitial::NextLevelInterstitialAnimation;
+use crate::animate::score_popup::ScorePopupAnimation;
 use crate::animate::throw::ThrowAnimation;
 use crate::animate::victory::VictoryAnimation;
 use crate::animate::virus::VirusAnimation;
@@ -32,11 +42,16 @@ pub struct PlayerAnimations {
     player: u32,
     idle: IdleAnimation,
     virus: VirusAnimation,
+    crawl: VirusCrawlAnimation,
+    lounge: VirusLoungeAnimation,
     destroy: DestroyAnimation,
     impact: ImpactAnimation,
     lock: LockAnimation,
     hard_drop: HardDropAnimation,
+    chain: ChainAnimation,
+    score_popup: ScorePopupAnimation,
     throw: ThrowAnimation,
+    hold: HoldAnimation,
     game_over: GameOverAnimation,
     victory: VictoryAnimation,
     next_level: NextLevelAnimation,
@@ -51,12 +66,17 @@ impl PlayerAnimations {
             meta.red_virus_frames,
             meta.blue_virus_frames,
             meta.yellow_virus_frames,
+            meta.green_virus_frames,
             meta.virus_type,
         );
+        let crawl = VirusCrawlAnimation::new(meta.virus_crawl_type);
+        let lounge = VirusLoungeAnimation::new(meta.virus_pop_frames);
         let destroy = DestroyAnimation::new(meta.vitamin_pop_frames, meta.virus_pop_frames);
         let impact = ImpactAnimation::new();
         let lock = LockAnimation::new();
         let hard_drop = HardDropAnimation::new();
+        let chain = ChainAnimation::new();
+        let score_popup = ScorePopupAnimation::new(theme.geometry().block_size());
         let throw = ThrowAnimation::new(
             meta.throw_start,
             meta.throw_end,
@@ -64,6 +84,7 @@ impl PlayerAnimations {
             meta.dr_throw_frames,
             meta.dr_throw_type,
         );
+        let hold = HoldAnimation::new(meta.hold_point, meta.throw_start);
         let game_over = GameOverAnimation::new(
             meta.game_over_screen_frames,
             meta.dr_game_over_type,
@@ -81,11 +102,16 @@ impl PlayerAnimations {
             player,
             idle,
             virus,
+            crawl,
+            lounge,
             destroy,
             impact,
             lock,
             hard_drop,
+            chain,
+            score_popup,
             throw,
+            hold,
             game_over,
             victory,
             next_level,
@@ -96,11 +122,16 @@ impl PlayerAnimations {
     pub fn reset(&mut self) {
         self.idle.reset();
         self.virus.reset();
+        self.crawl.reset();
+        self.lounge.reset();
         self.destroy.reset();
         self.impact.reset();
         self.lock.reset();
         self.hard_drop.reset();
+        self.chain.reset();
+        self.score_popup.reset();
         self.throw.reset();
+        self.hold.reset();
     }
 
     pub fn update(&mut self, delta: Duration) -> Vec<AnimationEvent> {
@@ -111,16 +142,21 @@ impl PlayerAnimations {
         let mut events = vec![];
         self.idle.update(delta);
         self.virus.update(delta);
+        self.crawl.update(delta);
+        self.lounge.update(delta);
         self.destroy.update(delta);
         self.impact.update(delta);
         self.lock.update(delta);
         self.hard_drop.update(delta);
+        self.chain.update(delta);
+        self.score_popup.update(delta);
         if self.throw.update(delta) {
             events.push(AnimationEvent::Finished {
                 animation: AnimationType::Throw,
                 player: self.player,
             });
         }
+        self.hold.update(delta);
         self.game_over.update(delta);
         self.victory.update(delta);
         self.next_level.update(delta);
@@ -128,6 +164,7 @@ impl PlayerAnimations {
         events
     }
 
+    /// Animations that pause the game, the hold animation is drawn over play that carries on
     pub fn is_animating(&self) -> bool {
         self.destroy.state().is_some()
             || self.lock.state().is_some()
@@ -147,6 +184,22 @@ impl PlayerAnimations {
         &self.virus
     }
 
+    pub fn crawl(&self) -> &VirusCrawlAnimation {
+        &self.crawl
+    }
+
+    pub fn crawl_mut(&mut self) -> &mut VirusCrawlAnimation {
+        &mut self.crawl
+    }
+
+    pub fn lounge(&self) -> &VirusLoungeAnimation {
+        &self.lounge
+    }
+
+    pub fn lounge_mut(&mut self) -> &mut VirusLoungeAnimation {
+        &mut self.lounge
+    }
+
     pub fn destroy(&self) -> &DestroyAnimation {
         &self.destroy
     }
@@ -179,6 +232,22 @@ impl PlayerAnimations {
         &mut self.hard_drop
     }
 
+    pub fn chain(&self) -> &ChainAnimation {
+        &self.chain
+    }
+
+    pub fn chain_mut(&mut self) -> &mut ChainAnimation {
+        &mut self.chain
+    }
+
+    pub fn score_popup(&self) -> &ScorePopupAnimation {
+        &self.score_popup
+    }
+
+    pub fn score_popup_mut(&mut self) -> &mut ScorePopupAnimation {
+        &mut self.score_popup
+    }
+
     pub fn throw(&self) -> &ThrowAnimation {
         &self.throw
     }
@@ -187,6 +256,14 @@ impl PlayerAnimations {
         &mut self.throw
     }
 
+    pub fn hold(&self) -> &HoldAnimation {
+        &self.hold
+    }
+
+    pub fn hold_mut(&mut self) -> &mut HoldAnimation {
+        &mut self.hold
+    }
+
     pub fn game_over(&self) -> &GameOverAnimation {
         &self.game_over
     }
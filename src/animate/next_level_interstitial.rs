@@ -1,21 +1,29 @@
 use crate::animate::dr::{DrAnimation, DrAnimationType};
+use crate::game::event::ColoredBlock;
+use crate::game::NextLevelPreview;
 use std::time::Duration;
 
 const INTERSTITIAL_ITERATION_DURATION: Duration = Duration::from_millis(600);
+/// Time taken to seed the viruses of the preview bottle, one at a time from the top
+const PREVIEW_SEED_DURATION: Duration = Duration::from_millis(1500);
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct State {
     duration: Duration,
     dr: DrAnimation,
     interstitial_frame: usize,
+    preview: NextLevelPreview,
+    seeded: usize,
 }
 
 impl State {
-    fn new(dr: DrAnimation) -> Self {
+    fn new(dr: DrAnimation, preview: NextLevelPreview) -> Self {
         Self {
             duration: Duration::ZERO,
             dr,
             interstitial_frame: 0,
+            preview,
+            seeded: 0,
         }
     }
 
@@ -26,8 +34,22 @@ impl State {
     pub fn dr_frame(&self) -> usize {
         self.dr.frame()
     }
+
+    pub fn preview(&self) -> &NextLevelPreview {
+        &self.preview
+    }
+
+    /// Viruses of the preview bottle seeded so far
+    pub fn seeded_viruses(&self) -> &[ColoredBlock] {
+        &self.preview.viruses[..self.seeded]
+    }
+
+    fn is_seeded(&self) -> bool {
+        self.seeded == self.preview.viruses.len()
+    }
 }
 
+/// The dr celebrating between levels, with a preview of the next bottle seeded virus by virus
 #[derive(Clone, Debug)]
 pub struct NextLevelInterstitialAnimation {
     state: Option<State>,
@@ -56,6 +78,12 @@ impl NextLevelInterstitialAnimation {
             state.interstitial_frame =
                 (state.duration.as_millis() / interstitial_frame_duration.as_millis()) as usize
                     % self.interstitial_frames;
+
+            let viruses = state.preview.viruses.len();
+            let progress = state.duration.as_secs_f64() / PREVIEW_SEED_DURATION.as_secs_f64();
+            let seeded = ((progress * viruses as f64) as usize).min(viruses);
+            // seeding is skipped by the first key press
+            state.seeded = state.seeded.max(seeded);
         }
     }
 
@@ -63,17 +91,68 @@ impl NextLevelInterstitialAnimation {
         self.state.as_ref()
     }
 
+    /// The first key press seeds the rest of the preview bottle, the next dismisses it
     pub fn dismiss(&mut self) -> bool {
-        if self.state.is_some() {
-            self.state = None;
-            true
-        } else {
-            false
+        match self.state.as_mut() {
+            Some(state) if !state.is_seeded() => {
+                state.seeded = state.preview.viruses.len();
+                false
+            }
+            Some(_) => {
+                self.state = None;
+                true
+            }
+            None => false,
         }
     }
 
-    pub fn display(&mut self) {
+    pub fn display(&mut self, preview: NextLevelPreview) {
         let dr = DrAnimation::new(self.dr_type, self.dr_frames);
-        self.state = Some(State::new(dr));
+        self.state = Some(State::new(dr, preview));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bottle::BottleSize;
+    use crate::game::pill::VirusColor;
+    use crate::game::GameSpeed;
+
+    fn preview() -> NextLevelPreview {
+        NextLevelPreview {
+            virus_level: 1,
+            speed: GameSpeed::Low,
+            bottle_size: BottleSize::CLASSIC,
+            viruses: (0..4)
+                .map(|x| ColoredBlock {
+                    position: (x, 15).into(),
+                    color: VirusColor::Red,
+                    is_virus: true,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn seeds_preview_over_time() {
+        let mut animation = NextLevelInterstitialAnimation::new(DrAnimationType::Static, 1, 1);
+        animation.display(preview());
+        animation.update(PREVIEW_SEED_DURATION / 2);
+        assert_eq!(animation.state().unwrap().seeded_viruses().len(), 2);
+        animation.update(PREVIEW_SEED_DURATION);
+        assert_eq!(animation.state().unwrap().seeded_viruses().len(), 4);
+    }
+
+    #[test]
+    fn first_press_seeds_then_dismisses() {
+        let mut animation = NextLevelInterstitialAnimation::new(DrAnimationType::Static, 1, 1);
+        animation.display(preview());
+        assert!(!animation.dismiss());
+        assert_eq!(animation.state().unwrap().seeded_viruses().len(), 4);
+        animation.update(Duration::from_millis(1));
+        assert_eq!(animation.state().unwrap().seeded_viruses().len(), 4);
+        assert!(animation.dismiss());
+        assert!(animation.state().is_none());
     }
 }
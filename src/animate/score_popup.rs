@@ -0,0 +1,100 @@
+use sdl2::rect::Point;
+use std::time::Duration;
+
+const POPUP_DURATION: Duration = Duration::from_millis(800);
+/// Distance a popup rises over its duration, in blocks
+const POPUP_RISE: f64 = 1.5;
+/// Fraction of the duration a popup is fully opaque before it fades
+const POPUP_HOLD: f64 = 0.4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScorePopup {
+    score: u32,
+    from: Point,
+    duration: Duration,
+}
+
+impl ScorePopup {
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn progress(&self) -> f64 {
+        (self.duration.as_secs_f64() / POPUP_DURATION.as_secs_f64()).min(1.0)
+    }
+
+    /// Fully opaque at first, then fades out as it finishes rising
+    pub fn alpha(&self) -> u8 {
+        let t = ((self.progress() - POPUP_HOLD) / (1.0 - POPUP_HOLD)).max(0.0);
+        (0xff as f64 * (1.0 - t)).round() as u8
+    }
+}
+
+/// The score of a combo floating up from where its patterns were cleared, e.g. "+400"
+#[derive(Clone, Debug)]
+pub struct ScorePopupAnimation {
+    block_size: u32,
+    popups: Vec<ScorePopup>,
+}
+
+impl ScorePopupAnimation {
+    pub fn new(block_size: u32) -> Self {
+        Self {
+            block_size,
+            popups: vec![],
+        }
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        for popup in self.popups.iter_mut() {
+            popup.duration += delta;
+        }
+        self.popups.retain(|p| p.duration < POPUP_DURATION);
+    }
+
+    pub fn reset(&mut self) {
+        self.popups.clear();
+    }
+
+    /// A combo has scored, centered on a point of the bottle
+    pub fn popup(&mut self, score: u32, from: Point) {
+        self.popups.push(ScorePopup {
+            score,
+            from,
+            duration: Duration::ZERO,
+        });
+    }
+
+    pub fn popups(&self) -> &[ScorePopup] {
+        &self.popups
+    }
+
+    /// Center of the popup, rising from where it was scored
+    pub fn position(&self, popup: &ScorePopup) -> Point {
+        let rise = POPUP_RISE * self.block_size as f64 * popup.progress();
+        popup.from.offset(0, -rise.round() as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rises_and_fades() {
+        let mut animation = ScorePopupAnimation::new(10);
+        animation.popup(400, Point::new(50, 100));
+        let popup = animation.popups()[0];
+        assert_eq!(popup.score(), 400);
+        assert_eq!(popup.alpha(), 0xff);
+        assert_eq!(animation.position(&popup), Point::new(50, 100));
+
+        animation.update(POPUP_DURATION / 2);
+        let popup = animation.popups()[0];
+        assert!(popup.alpha() < 0xff);
+        assert_eq!(animation.position(&popup), Point::new(50, 92));
+
+        animation.update(POPUP_DURATION / 2);
+        assert!(animation.popups().is_empty());
+    }
+}
@@ -84,6 +84,7 @@ pub struct VirusAnimation {
     red: VirusAnimationState,
     blue: VirusAnimationState,
     yellow: VirusAnimationState,
+    green: VirusAnimationState,
     animation_type: VirusAnimationType,
     frame_duration: Duration,
 }
@@ -93,6 +94,7 @@ impl VirusAnimation {
         red_frames: usize,
         blue_frames: usize,
         yellow_frames: usize,
+        green_frames: usize,
         animation_type: VirusAnimationType,
     ) -> Self {
         let frame_duration = animation_type.frame_duration();
@@ -100,6 +102,7 @@ impl VirusAnimation {
             red: VirusAnimationState::new(red_frames),
             blue: VirusAnimationState::new(blue_frames),
             yellow: VirusAnimationState::new(yellow_frames),
+            green: VirusAnimationState::new(green_frames),
             animation_type,
             frame_duration,
         }
@@ -110,12 +113,14 @@ impl VirusAnimation {
         self.red.update(delta, self.frame_duration, is_yo_yo);
         self.blue.update(delta, self.frame_duration, is_yo_yo);
         self.yellow.update(delta, self.frame_duration, is_yo_yo);
+        self.green.update(delta, self.frame_duration, is_yo_yo);
     }
 
     pub fn reset(&mut self) {
         self.red.reset();
         self.blue.reset();
         self.yellow.reset();
+        self.green.reset();
     }
 
     pub fn frame(&self, color: VirusColor) -> usize {
@@ -123,6 +128,7 @@ impl VirusAnimation {
             VirusColor::Yellow => self.yellow.frame(),
             VirusColor::Blue => self.blue.frame(),
             VirusColor::Red => self.red.frame(),
+            VirusColor::Green => self.green.frame(),
         }
     }
 }
@@ -0,0 +1,175 @@
+//! Screenshots of the window & GIFs of the last few seconds of a game. Frames of the game are read
+//! back from the canvas a few times a second, downscaled & kept in a ring buffer, then only encoded
+//! when a GIF is saved.
+
+use crate::config::CaptureConfig;
+use sdl2::image::SaveSurface;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::WindowCanvas;
+use sdl2::surface::Surface;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Length of gameplay kept for a GIF
+const GIF_DURATION: Duration = Duration::from_secs(10);
+/// Delay between the frames of a GIF, which are counted in 1/100ths of a second
+const GIF_FRAME_DELAY: u16 = 10;
+const GIF_FRAME_DURATION: Duration = Duration::from_millis(GIF_FRAME_DELAY as u64 * 10);
+/// Frames are downscaled to at most this wide
+const GIF_MAX_WIDTH: u32 = 320;
+/// Speed of the color quantization, 1 (best) to 30 (fastest)
+const GIF_QUANTIZE_SPEED: i32 = 10;
+/// 4 bytes per pixel, read back in the byte order of RGBA
+const PIXEL_FORMAT: PixelFormatEnum = PixelFormatEnum::ABGR8888;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GifFrame {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+pub struct Capture {
+    directory: PathBuf,
+    gif: bool,
+    frames: VecDeque<GifFrame>,
+    since_frame: Duration,
+}
+
+impl Capture {
+    pub fn new(config: &CaptureConfig) -> Result<Self, String> {
+        Ok(Self {
+            directory: config.directory()?,
+            gif: config.gif,
+            frames: VecDeque::new(),
+            since_frame: GIF_FRAME_DURATION,
+        })
+    }
+
+    /// Records a frame of the canvas for the next GIF, every so often. Call before presenting
+    pub fn update(&mut self, canvas: &WindowCanvas, delta: Duration) -> Result<(), String> {
+        if !self.gif {
+            return Ok(());
+        }
+        self.since_frame += delta;
+        if self.since_frame < GIF_FRAME_DURATION {
+            return Ok(());
+        }
+        self.since_frame = Duration::ZERO;
+        let (width, height) = canvas.output_size()?;
+        let rgba = canvas.read_pixels(None, PIXEL_FORMAT)?;
+        let max_frames = GIF_DURATION.as_millis() / GIF_FRAME_DURATION.as_millis();
+        if self.frames.len() as u128 >= max_frames {
+            self.frames.pop_front();
+        }
+        self.frames
+            .push_back(downscale(&rgba, width, height, GIF_MAX_WIDTH));
+        Ok(())
+    }
+
+    /// Saves the canvas as a PNG, call before presenting
+    pub fn screenshot(&self, canvas: &WindowCanvas) -> Result<PathBuf, String> {
+        let (width, height) = canvas.output_size()?;
+        let mut pixels = canvas.read_pixels(None, PIXEL_FORMAT)?;
+        let surface = Surface::from_data(&mut pixels, width, height, width * 4, PIXEL_FORMAT)?;
+        let path = self.path("png")?;
+        surface.save(&path)?;
+        Ok(path)
+    }
+
+    /// Encodes the recorded frames as a GIF in the background, failures are logged. There are no
+    /// frames unless GIFs are on
+    pub fn save_gif(&self) -> Result<Option<PathBuf>, String> {
+        if self.frames.is_empty() {
+            return Ok(None);
+        }
+        let path = self.path("gif")?;
+        let frames = self.frames.clone();
+        let gif_path = path.clone();
+        thread::spawn(move || {
+            if let Err(error) = encode_gif(&gif_path, frames) {
+                println!("failed to save gif {}: {}", gif_path.display(), error);
+            }
+        });
+        Ok(Some(path))
+    }
+
+    fn path(&self, extension: &str) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(&self.directory).map_err(|e| e.to_string())?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis();
+        Ok(self
+            .directory
+            .join(format!("dr-rustario-{}.{}", millis, extension)))
+    }
+}
+
+/// Nearest neighbour downscale by a whole number to fit a width
+fn downscale(rgba: &[u8], width: u32, height: u32, max_width: u32) -> GifFrame {
+    let scale = width.div_ceil(max_width).max(1);
+    let scaled_width = width / scale;
+    let scaled_height = height / scale;
+    let mut result = Vec::with_capacity((scaled_width * scaled_height * 4) as usize);
+    for y in 0..scaled_height {
+        for x in 0..scaled_width {
+            let i = (((y * scale) * width + x * scale) * 4) as usize;
+            result.extend_from_slice(&rgba[i..i + 4]);
+        }
+    }
+    GifFrame {
+        width: scaled_width,
+        height: scaled_height,
+        rgba: result,
+    }
+}
+
+fn encode_gif(path: &Path, frames: VecDeque<GifFrame>) -> Result<(), String> {
+    // the window may have been resized while recording, the GIF is the size of the latest frame
+    let (width, height) = frames
+        .back()
+        .map(|f| (f.width, f.height))
+        .ok_or("no frames")?;
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder =
+        gif::Encoder::new(file, width as u16, height as u16, &[]).map_err(|e| e.to_string())?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|e| e.to_string())?;
+    for mut frame in frames
+        .into_iter()
+        .filter(|f| f.width == width && f.height == height)
+    {
+        let mut gif_frame = gif::Frame::from_rgba_speed(
+            width as u16,
+            height as u16,
+            &mut frame.rgba,
+            GIF_QUANTIZE_SPEED,
+        );
+        gif_frame.delay = GIF_FRAME_DELAY;
+        encoder.write_frame(&gif_frame).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downscales_to_fit_width() {
+        // 4x2 pixels, each pixel's bytes are its index
+        let rgba = (0..8u8).flat_map(|i| [i; 4]).collect::<Vec<u8>>();
+        let frame = downscale(&rgba, 4, 2, 2);
+        assert_eq!(frame.width, 2);
+        assert_eq!(frame.height, 1);
+        assert_eq!(frame.rgba, vec![0, 0, 0, 0, 2, 2, 2, 2]);
+
+        let frame = downscale(&rgba, 4, 2, 320);
+        assert_eq!(frame.rgba, rgba);
+    }
+}
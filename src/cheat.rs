@@ -0,0 +1,253 @@
+use crate::menu_input::MenuInputKey;
+use crate::profile::Profile;
+use sdl2::pixels::Color;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const CONFIG_NAME: &str = "cheats";
+const RAINBOW_PERIOD_SECS: f64 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cheat {
+    BigHead,
+    RainbowPills,
+    DebugOverlay,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheatCode {
+    pub cheat: Cheat,
+    pub sequence: Vec<MenuInputKey>,
+}
+
+impl CheatCode {
+    pub fn new(cheat: Cheat, sequence: &[MenuInputKey]) -> Self {
+        Self {
+            cheat,
+            sequence: sequence.to_vec(),
+        }
+    }
+}
+
+/// Snapshot of the enabled cheats, cheap to copy into the game loop
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Cheats {
+    pub big_head: bool,
+    pub rainbow_pills: bool,
+    pub debug_overlay: bool,
+}
+
+impl Cheats {
+    pub fn dr_scale(&self) -> f64 {
+        if self.big_head {
+            1.5
+        } else {
+            1.0
+        }
+    }
+
+    /// Cycles through the hue wheel every couple of seconds
+    pub fn rainbow_color(elapsed: Duration) -> Color {
+        let hue = (elapsed.as_secs_f64() / RAINBOW_PERIOD_SECS).fract() * 6.0;
+        let x = 1.0 - (hue % 2.0 - 1.0).abs();
+        let (r, g, b) = match hue as u32 {
+            0 => (1.0, x, 0.0),
+            1 => (x, 1.0, 0.0),
+            2 => (0.0, 1.0, x),
+            3 => (0.0, x, 1.0),
+            4 => (x, 0.0, 1.0),
+            _ => (1.0, 0.0, x),
+        };
+        Color::RGB(
+            (r * 255.0).round() as u8,
+            (g * 255.0).round() as u8,
+            (b * 255.0).round() as u8,
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheatTable {
+    codes: Vec<CheatCode>,
+    enabled: Vec<Cheat>,
+}
+
+impl Default for CheatTable {
+    fn default() -> Self {
+        use MenuInputKey::*;
+        Self {
+            codes: vec![
+                CheatCode::new(Cheat::BigHead, &[Up, Up, Down, Down, Left, Right, Left, Right]),
+                CheatCode::new(
+                    Cheat::RainbowPills,
+                    &[Left, Left, Right, Right, Up, Down, Up, Down],
+                ),
+                CheatCode::new(
+                    Cheat::DebugOverlay,
+                    &[Down, Down, Up, Up, Right, Left, Right, Left],
+                ),
+            ],
+            enabled: vec![],
+        }
+    }
+}
+
+impl CheatTable {
+    pub fn load(profile: &Profile) -> Result<Self, String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::load_path(config_path).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, profile: &Profile) -> Result<(), String> {
+        let config_path = profile.config_path(CONFIG_NAME)?;
+        confy::store_path(config_path, self).map_err(|e| e.to_string())
+    }
+
+    pub fn codes(&self) -> &[CheatCode] {
+        self.codes.as_slice()
+    }
+
+    pub fn is_enabled(&self, cheat: Cheat) -> bool {
+        self.enabled.contains(&cheat)
+    }
+
+    /// Toggles the cheat, returns true if it is now enabled
+    pub fn toggle(&mut self, cheat: Cheat) -> bool {
+        if self.is_enabled(cheat) {
+            self.enabled.retain(|c| *c != cheat);
+            false
+        } else {
+            self.enabled.push(cheat);
+            true
+        }
+    }
+
+    pub fn cheats(&self) -> Cheats {
+        Cheats {
+            big_head: self.is_enabled(Cheat::BigHead),
+            rainbow_pills: self.is_enabled(Cheat::RainbowPills),
+            debug_overlay: self.is_enabled(Cheat::DebugOverlay),
+        }
+    }
+}
+
+/// Matches the most recent menu inputs against a set of cheat code sequences
+pub struct CheatListener {
+    codes: Vec<CheatCode>,
+    buffer: Vec<MenuInputKey>,
+    max_len: usize,
+}
+
+impl CheatListener {
+    pub fn new(codes: &[CheatCode]) -> Self {
+        let codes: Vec<CheatCode> = codes
+            .iter()
+            .filter(|c| !c.sequence.is_empty())
+            .cloned()
+            .collect();
+        let max_len = codes.iter().map(|c| c.sequence.len()).max().unwrap_or(0);
+        Self {
+            codes,
+            buffer: Vec::with_capacity(max_len),
+            max_len,
+        }
+    }
+
+    pub fn receive(&mut self, key: MenuInputKey) -> Option<Cheat> {
+        if self.max_len == 0 {
+            return None;
+        }
+        if self.buffer.len() == self.max_len {
+            self.buffer.remove(0);
+        }
+        self.buffer.push(key);
+
+        let cheat = self
+            .codes
+            .iter()
+            .find(|c| self.buffer.ends_with(&c.sequence))
+            .map(|c| c.cheat);
+        if cheat.is_some() {
+            self.buffer.clear();
+        }
+        cheat
+    }
+
+    /// Have the latest keys started a code, at least its first two keys. The first key alone is
+    /// not enough as every code starts with a direction that moves around the menu
+    pub fn is_typing(&self) -> bool {
+        self.codes
+            .iter()
+            .any(|c| (2..c.sequence.len()).any(|len| self.buffer.ends_with(&c.sequence[..len])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MenuInputKey::*;
+
+    fn listener() -> CheatListener {
+        CheatListener::new(&[
+            CheatCode::new(Cheat::BigHead, &[Up, Up, Down]),
+            CheatCode::new(Cheat::DebugOverlay, &[Left, Right]),
+        ])
+    }
+
+    fn receive_all(listener: &mut CheatListener, keys: &[MenuInputKey]) -> Vec<Cheat> {
+        keys.iter().filter_map(|k| listener.receive(*k)).collect()
+    }
+
+    #[test]
+    fn matches_sequence() {
+        let mut listener = listener();
+        assert_eq!(receive_all(&mut listener, &[Up, Up, Down]), vec![Cheat::BigHead]);
+    }
+
+    #[test]
+    fn matches_sequence_after_noise() {
+        let mut listener = listener();
+        assert_eq!(
+            receive_all(&mut listener, &[Down, Up, Up, Up, Down]),
+            vec![Cheat::BigHead]
+        );
+    }
+
+    #[test]
+    fn does_not_match_broken_sequence() {
+        let mut listener = listener();
+        assert!(receive_all(&mut listener, &[Up, Start, Up, Down]).is_empty());
+    }
+
+    #[test]
+    fn resets_after_match() {
+        let mut listener = listener();
+        assert_eq!(
+            receive_all(&mut listener, &[Left, Right, Right, Left, Right]),
+            vec![Cheat::DebugOverlay, Cheat::DebugOverlay]
+        );
+    }
+
+    #[test]
+    fn types_sequence() {
+        let mut listener = listener();
+        listener.receive(Up);
+        assert!(!listener.is_typing());
+        listener.receive(Up);
+        assert!(listener.is_typing());
+        assert_eq!(listener.receive(Down), Some(Cheat::BigHead));
+        assert!(!listener.is_typing());
+        listener.receive(Up);
+        listener.receive(Start);
+        assert!(!listener.is_typing());
+    }
+
+    #[test]
+    fn toggles_cheat() {
+        let mut table = CheatTable::default();
+        assert!(table.toggle(Cheat::RainbowPills));
+        assert!(table.cheats().rainbow_pills);
+        assert!(!table.toggle(Cheat::RainbowPills));
+        assert_eq!(table.cheats(), Cheats::default());
+    }
+}
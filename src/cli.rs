@@ -0,0 +1,159 @@
+//! Command line arguments, none are required to play normally
+
+use crate::game::rules::MAX_MATCH_PLAYERS;
+use crate::input_tape::InputTapeMode;
+use std::path::PathBuf;
+
+const RECORD_INPUTS_ARG: &str = "--record-inputs";
+const PLAY_INPUTS_ARG: &str = "--play-inputs";
+const HEADLESS_ARG: &str = "--headless";
+const PLAYERS_ARG: &str = "--players";
+const TUI_ARG: &str = "--tui";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeadlessArgs {
+    pub matches: u32,
+    pub players: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Args {
+    pub input_tape: Option<InputTapeMode>,
+    pub headless: Option<HeadlessArgs>,
+    /// Play in the terminal rather than a window
+    pub tui: bool,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut result = Self::default();
+        let mut players = None;
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} requires a value", arg));
+            match arg.as_str() {
+                RECORD_INPUTS_ARG | PLAY_INPUTS_ARG if result.input_tape.is_some() => {
+                    return Err(format!(
+                        "only one of {} or {} is allowed",
+                        RECORD_INPUTS_ARG, PLAY_INPUTS_ARG
+                    ));
+                }
+                RECORD_INPUTS_ARG => {
+                    result.input_tape = Some(InputTapeMode::Record(PathBuf::from(value()?)))
+                }
+                PLAY_INPUTS_ARG => {
+                    result.input_tape = Some(InputTapeMode::Play(PathBuf::from(value()?)))
+                }
+                HEADLESS_ARG => {
+                    let matches = parse_number(&arg, value()?, 1..=u32::MAX)?;
+                    result.headless = Some(HeadlessArgs {
+                        matches,
+                        players: 1,
+                    });
+                }
+                PLAYERS_ARG => {
+                    players = Some(parse_number(&arg, value()?, 1..=MAX_MATCH_PLAYERS as u32)?)
+                }
+                TUI_ARG => result.tui = true,
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        match (result.headless.as_mut(), players) {
+            (Some(headless), Some(players)) => headless.players = players,
+            (None, Some(_)) => return Err(format!("{} requires {}", PLAYERS_ARG, HEADLESS_ARG)),
+            _ => {}
+        }
+        if result.headless.is_some() && result.input_tape.is_some() {
+            return Err(format!("input tapes cannot be used with {}", HEADLESS_ARG));
+        }
+        if result.tui && (result.headless.is_some() || result.input_tape.is_some()) {
+            return Err(format!(
+                "{} cannot be used with {} or input tapes",
+                TUI_ARG, HEADLESS_ARG
+            ));
+        }
+        Ok(result)
+    }
+}
+
+fn parse_number(
+    arg: &str,
+    value: String,
+    range: std::ops::RangeInclusive<u32>,
+) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|n| range.contains(n))
+        .ok_or_else(|| {
+            format!(
+                "{} must be between {} and {}: {}",
+                arg,
+                range.start(),
+                range.end(),
+                value
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(values: &[&str]) -> Result<Args, String> {
+        Args::parse(values.iter().map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn no_args() {
+        assert_eq!(parse(&[]), Ok(Args::default()));
+    }
+
+    #[test]
+    fn input_tapes() {
+        assert_eq!(
+            parse(&["--record-inputs", "a.tape"]).unwrap().input_tape,
+            Some(InputTapeMode::Record(PathBuf::from("a.tape")))
+        );
+        assert_eq!(
+            parse(&["--play-inputs", "a.tape"]).unwrap().input_tape,
+            Some(InputTapeMode::Play(PathBuf::from("a.tape")))
+        );
+        assert!(parse(&["--play-inputs"]).is_err());
+        assert!(parse(&["--record-inputs", "a.tape", "--play-inputs", "b.tape"]).is_err());
+    }
+
+    #[test]
+    fn headless() {
+        assert_eq!(
+            parse(&["--headless", "100"]).unwrap().headless,
+            Some(HeadlessArgs {
+                matches: 100,
+                players: 1
+            })
+        );
+        assert_eq!(
+            parse(&["--players", "2", "--headless", "5"]).unwrap().headless,
+            Some(HeadlessArgs {
+                matches: 5,
+                players: 2
+            })
+        );
+        assert!(parse(&["--headless", "0"]).is_err());
+        assert!(parse(&["--headless", "1", "--players", "3"]).is_err());
+        assert!(parse(&["--players", "2"]).is_err());
+        assert!(parse(&["--headless", "1", "--play-inputs", "a.tape"]).is_err());
+    }
+
+    #[test]
+    fn tui() {
+        assert!(parse(&["--tui"]).unwrap().tui);
+        assert!(parse(&["--tui", "--headless", "1"]).is_err());
+        assert!(parse(&["--record-inputs", "a.tape", "--tui"]).is_err());
+    }
+
+    #[test]
+    fn unknown_args() {
+        assert!(parse(&["--bogus"]).is_err());
+    }
+}
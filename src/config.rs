@@ -1,10 +1,17 @@
+use crate::game::rules::MatchThemes;
+use crate::game::SAVE_STATE_SLOTS;
 use crate::game_input::GameInputKey;
 use crate::menu_input::MenuInputKey;
+use crate::profile::Profile;
+use crate::theme::transition::ThemeTransition;
+pub use dr_rustario_core::paths::config_path;
 use sdl2::keyboard::Keycode;
 use sdl2::mixer::MAX_VOLUME;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 use confy::ConfyError;
 use sdl2::sys;
 use strum::IntoEnumIterator;
@@ -16,11 +23,100 @@ pub enum VideoMode {
     FullScreenDesktop,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
     pub video: VideoConfig,
     pub audio: AudioConfig,
+    #[serde(default)]
+    pub music: UserMusicConfig,
     pub input: InputConfig,
+    #[serde(default)]
+    pub gameplay: GameplayConfig,
+    #[serde(default)]
+    pub online: Option<OnlineConfig>,
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    #[serde(default)]
+    pub telemetry: Option<TelemetryConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OnlineConfig {
+    /// leaderboard url, scores are fetched with GET and submitted with POST
+    pub endpoint: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct AccessibilityConfig {
+    /// overlay a distinct glyph on the blocks & viruses of each color, for color vision deficiency
+    #[serde(default)]
+    pub color_blind_patterns: bool,
+    /// no screen shake, camera zoom or theme transition effects & fewer background particles
+    #[serde(default)]
+    pub reduced_motion: bool,
+    /// intensity of the bottle shake when a pill lands, 0.0 (off) to 1.0
+    #[serde(default = "default_screen_shake")]
+    pub screen_shake: f64,
+    /// caption the audio-only cues of a game, e.g. incoming garbage, for deaf & hard of hearing
+    #[serde(default)]
+    pub audio_cues: bool,
+}
+
+fn default_screen_shake() -> f64 {
+    1.0
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            color_blind_patterns: false,
+            reduced_motion: false,
+            screen_shake: default_screen_shake(),
+            audio_cues: false,
+        }
+    }
+}
+
+impl AccessibilityConfig {
+    pub fn screen_shake(&self) -> f64 {
+        if self.reduced_motion {
+            0.0
+        } else {
+            self.screen_shake.clamp(0.0, 1.0)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GameplayConfig {
+    /// names of the built-in gameplay modifiers to apply to every game, see game::modifier
+    pub modifiers: Vec<String>,
+    /// single player games can be rewound a few pills, high scores are then not recorded
+    #[serde(default)]
+    pub rewind: bool,
+    /// names of the themes to play in the custom theme order of all themes, e.g. nes, gameboy
+    #[serde(default)]
+    pub theme_sequence: Vec<String>,
+}
+
+impl GameplayConfig {
+    /// Indices of the themes in the custom theme sequence, unknown names are skipped
+    pub fn theme_sequence(&self) -> Vec<usize> {
+        self.theme_sequence
+            .iter()
+            .filter_map(|name| {
+                let index = MatchThemes::from_str(name)
+                    .ok()
+                    .and_then(|themes| themes.index());
+                if index.is_none() {
+                    println!("skipping unknown theme in sequence: {}", name);
+                }
+                index
+            })
+            .collect()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -42,6 +138,42 @@ pub struct GameInputConfig {
     pub rotate_clockwise: GameKey,
     pub rotate_anticlockwise: GameKey,
     pub hold: GameKey,
+    /// raises the speed level mid-game, unbound by default
+    #[serde(default)]
+    pub speed_up: Option<GameKey>,
+    #[serde(default)]
+    pub auto_repeat: AutoRepeatConfig,
+    /// two-stage hard drop: holding hard drop previews the landing position, releasing commits it
+    #[serde(default)]
+    pub hard_drop_preview: bool,
+}
+
+/// Delayed auto shift (DAS) of left & right movement when the key is held
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoRepeatConfig {
+    /// initial delay before the first repeat
+    pub delay_ms: u64,
+    /// delay between each subsequent repeat (ARR)
+    pub rate_ms: u64,
+}
+
+impl AutoRepeatConfig {
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms)
+    }
+
+    pub fn rate(&self) -> Duration {
+        Duration::from_millis(self.rate_ms)
+    }
+}
+
+impl Default for AutoRepeatConfig {
+    fn default() -> Self {
+        Self {
+            delay_ms: 300,
+            rate_ms: 25,
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -52,6 +184,67 @@ pub struct InputConfig {
     pub pause: GameKey,
     pub quit: GameKey,
     pub next_theme: GameKey,
+    #[serde(default = "default_rewind_key")]
+    pub rewind: GameKey,
+    #[serde(default = "default_save_state_keys")]
+    pub save_state: [GameKey; SAVE_STATE_SLOTS],
+    #[serde(default = "default_load_state_keys")]
+    pub load_state: [GameKey; SAVE_STATE_SLOTS],
+    #[serde(default = "default_crt_filter_key")]
+    pub crt_filter: GameKey,
+    #[serde(default = "default_mute_key")]
+    pub mute: GameKey,
+    #[serde(default = "default_restart_key")]
+    pub restart: GameKey,
+    #[serde(default = "default_debug_overlay_key")]
+    pub debug_overlay: GameKey,
+    #[serde(default = "default_screenshot_key")]
+    pub screenshot: GameKey,
+    #[serde(default = "default_record_gif_key")]
+    pub record_gif: GameKey,
+    /// intensity of controller rumble, 0.0 (off) to 1.0
+    #[serde(default = "default_rumble")]
+    pub rumble: f64,
+}
+
+fn default_rewind_key() -> GameKey {
+    GameKey::Backspace
+}
+
+fn default_crt_filter_key() -> GameKey {
+    GameKey::F8
+}
+
+fn default_mute_key() -> GameKey {
+    GameKey::M
+}
+
+fn default_restart_key() -> GameKey {
+    GameKey::F3
+}
+
+fn default_debug_overlay_key() -> GameKey {
+    GameKey::Backquote
+}
+
+fn default_screenshot_key() -> GameKey {
+    GameKey::F12
+}
+
+fn default_record_gif_key() -> GameKey {
+    GameKey::F4
+}
+
+fn default_rumble() -> f64 {
+    1.0
+}
+
+fn default_save_state_keys() -> [GameKey; SAVE_STATE_SLOTS] {
+    [GameKey::F5, GameKey::F6, GameKey::F7]
+}
+
+fn default_load_state_keys() -> [GameKey; SAVE_STATE_SLOTS] {
+    [GameKey::F9, GameKey::F10, GameKey::F11]
 }
 
 impl InputConfig {
@@ -72,6 +265,13 @@ impl InputConfig {
             (self.quit.into(), GameInputKey::ReturnToMenu),
             (self.pause.into(), GameInputKey::Pause),
             (self.next_theme.into(), GameInputKey::NextTheme),
+            (self.rewind.into(), GameInputKey::Rewind),
+            (self.crt_filter.into(), GameInputKey::ToggleCrtFilter),
+            (self.mute.into(), GameInputKey::ToggleMute),
+            (self.restart.into(), GameInputKey::Restart),
+            (self.debug_overlay.into(), GameInputKey::ToggleDebugOverlay),
+            (self.screenshot.into(), GameInputKey::Screenshot),
+            (self.record_gif.into(), GameInputKey::RecordGif),
             (self.player1.move_left.into(), GameInputKey::MoveLeft { player: 0 }),
             (
                 self.player1.move_right.into(),
@@ -90,6 +290,15 @@ impl InputConfig {
             (self.player1.hold.into(), GameInputKey::Hold { player: 0 }),
         ]);
 
+        if let Some(speed_up) = self.player1.speed_up {
+            result.insert(speed_up.into(), GameInputKey::SpeedUp { player: 0 });
+        }
+
+        for slot in 0..SAVE_STATE_SLOTS {
+            result.insert(self.save_state[slot].into(), GameInputKey::SaveState { slot });
+            result.insert(self.load_state[slot].into(), GameInputKey::LoadState { slot });
+        }
+
         match self.player2 {
             None => {}
             Some(p2) => {
@@ -106,6 +315,9 @@ impl InputConfig {
                     GameInputKey::RotateClockwise { player: 1 },
                 );
                 result.insert(p2.hold.into(), GameInputKey::Hold { player: 1 });
+                if let Some(speed_up) = p2.speed_up {
+                    result.insert(speed_up.into(), GameInputKey::SpeedUp { player: 1 });
+                }
             }
         }
 
@@ -117,24 +329,131 @@ impl InputConfig {
 pub struct AudioConfig {
     pub music_volume: f64,
     pub effects_volume: f64,
+    /// fraction of the music volume while a combo jingle is playing
+    #[serde(default = "default_ducking")]
+    pub ducking: f64,
+    /// master mute, toggled with the mute key during a game so never saved
+    #[serde(skip)]
+    pub muted: bool,
+}
+
+fn default_ducking() -> f64 {
+    0.4
 }
 
 impl AudioConfig {
     pub fn music_volume(&self) -> i32 {
-        (self.music_volume * MAX_VOLUME as f64).round() as i32
+        self.volume(self.music_volume)
     }
 
     pub fn effects_volume(&self) -> i32 {
-        (self.effects_volume * MAX_VOLUME as f64).round() as i32
+        self.volume(self.effects_volume)
+    }
+
+    fn volume(&self, volume: f64) -> i32 {
+        if self.muted {
+            0
+        } else {
+            (volume * MAX_VOLUME as f64).round() as i32
+        }
+    }
+
+    /// Applies the volume to the mixer, effects are scaled per channel so this can be called at any time
+    pub fn apply(&self) {
+        self.apply_ducked(false);
+    }
+
+    /// Applies the volume with the music lowered while ducked
+    pub fn apply_ducked(&self, ducked: bool) {
+        let music_volume = if ducked {
+            (self.music_volume() as f64 * self.ducking.clamp(0.0, 1.0)).round() as i32
+        } else {
+            self.music_volume()
+        };
+        sdl2::mixer::Music::set_volume(music_volume);
+        sdl2::mixer::Channel::all().set_volume(self.effects_volume());
+    }
+}
+
+/// OGG or MP3 files in the `music` directory next to the config, replacing the music of every theme
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UserMusicConfig {
+    pub game: Option<String>,
+    pub next_level: Option<String>,
+    pub game_over: Option<String>,
+    pub victory: Option<String>,
+}
+
+impl UserMusicConfig {
+    pub fn path(&self, file_name: &str) -> Result<PathBuf, String> {
+        let mut path = config_path("config")?;
+        path.set_file_name("music");
+        path.push(file_name);
+        Ok(path)
     }
 }
 
+/// Screenshots & GIFs are saved to `directory`, otherwise to `captures` next to the config.
+/// Recording frames for GIFs reads back the window, so it is off unless `gif` is set
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CaptureConfig {
+    pub directory: Option<PathBuf>,
+    #[serde(default)]
+    pub gif: bool,
+}
+
+impl CaptureConfig {
+    pub fn directory(&self) -> Result<PathBuf, String> {
+        if let Some(directory) = self.directory.as_ref() {
+            return Ok(directory.clone());
+        }
+        let mut path = config_path("config")?;
+        path.set_file_name("captures");
+        Ok(path)
+    }
+}
+
+/// Live scores, levels & virus counts of each player are written to `directory` every second,
+/// for stream overlays
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    pub directory: PathBuf,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct VideoConfig {
     pub mode: VideoMode,
     pub vsync: bool,
+    /// cap the frame rate when vsync is disabled
+    #[serde(default)]
+    pub max_fps: Option<u32>,
+    /// run the game logic in fixed 60hz steps, independent of the refresh rate of the display
+    #[serde(default)]
+    pub fixed_timestep: bool,
     pub disable_screensaver: bool,
-    pub integer_scale: bool
+    pub integer_scale: bool,
+    /// integer scale the retro themes to the largest multiple that fits, centered with black bars
+    #[serde(default)]
+    pub letterbox: bool,
+    /// zoom toward the winning or losing player's bottle when the match ends
+    #[serde(default = "default_match_end_zoom")]
+    pub match_end_zoom: bool,
+    /// cap menus at a low frame rate & freeze their particles to save battery
+    #[serde(default)]
+    pub low_power: bool,
+    /// tint each player's background in vs. matches so spectators can tell the boards apart
+    #[serde(default)]
+    pub player_tint: bool,
+    /// scanlines & vignette over the whole screen, best with the retro themes
+    #[serde(default)]
+    pub crt_filter: bool,
+    /// effect between themes when switching theme every level
+    #[serde(default)]
+    pub theme_transition: ThemeTransition,
+}
+
+fn default_match_end_zoom() -> bool {
+    true
 }
 
 impl VideoConfig {
@@ -163,16 +482,27 @@ impl Default for Config {
                     height: 480,
                 },
                 vsync: true,
+                max_fps: None,
+                fixed_timestep: false,
                 disable_screensaver: true,
 
                 // disable integer scaling to better fill small retro handheld screen
                 // otherwise keep it enabled as it does look better
-                integer_scale: !cfg!(feature = "retro_handheld")
+                integer_scale: !cfg!(feature = "retro_handheld"),
+                letterbox: false,
+                match_end_zoom: default_match_end_zoom(),
+                low_power: false,
+                player_tint: false,
+                crt_filter: false,
+                theme_transition: ThemeTransition::default(),
             },
             audio: AudioConfig {
                 music_volume: 0.5,
                 effects_volume: 1.0,
+                ducking: default_ducking(),
+                muted: false,
             },
+            music: UserMusicConfig::default(),
             /*
               ArkOS Default Controls:
               A= Keycode::X
@@ -203,6 +533,9 @@ impl Default for Config {
                     rotate_clockwise: GameKey::X,
                     rotate_anticlockwise: GameKey::Z,
                     hold: GameKey::LShift,
+                    speed_up: None,
+                    auto_repeat: AutoRepeatConfig::default(),
+                    hard_drop_preview: false,
                 },
                 player2: None,
                 #[cfg(feature = "retro_handheld")] pause: GameKey::Return,
@@ -210,23 +543,26 @@ impl Default for Config {
                 #[cfg(feature = "retro_handheld")] next_theme: GameKey::RShift,
                 #[cfg(not(feature = "retro_handheld"))] next_theme: GameKey::F2,
                 quit: GameKey::Escape,
+                rewind: default_rewind_key(),
+                save_state: default_save_state_keys(),
+                load_state: default_load_state_keys(),
+                crt_filter: default_crt_filter_key(),
+                mute: default_mute_key(),
+                restart: default_restart_key(),
+                debug_overlay: default_debug_overlay_key(),
+                screenshot: default_screenshot_key(),
+                record_gif: default_record_gif_key(),
+                rumble: default_rumble(),
             },
+            gameplay: GameplayConfig::default(),
+            online: None,
+            accessibility: AccessibilityConfig::default(),
+            capture: CaptureConfig::default(),
+            telemetry: None,
         }
     }
 }
 
-#[cfg(feature = "retro_handheld")]
-pub fn config_path(name: &str) -> Result<PathBuf, String> {
-    let mut absolute = std::env::current_dir().map_err(|e| e.to_string())?;
-    absolute.push(format!("{}.yml", name));
-    Ok(absolute)
-}
-
-#[cfg(not(feature = "retro_handheld"))]
-pub fn config_path(name: &str) -> Result<PathBuf, String> {
-    confy::get_configuration_file_path(crate::build_info::PKG_NAME, name)
-        .map_err(|e| e.to_string())
-}
 
 impl Config {
     pub fn load() -> Result<Self, String> {
@@ -244,6 +580,42 @@ impl Config {
             Err(error) => Err(format!("{}", error)),
         }
     }
+
+    pub fn save(&self) -> Result<(), String> {
+        let config_path = config_path("config")?;
+        confy::store_path(config_path, self).map_err(|e| e.to_string())
+    }
+}
+
+pub const PROFILE_INPUT_CONFIG_NAME: &str = "input";
+
+/// Input bindings of a named profile, the shared bindings are used until they are set
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct ProfileInputConfig {
+    #[serde(default)]
+    input: Option<InputConfig>,
+}
+
+impl InputConfig {
+    /// Bindings of the profile, the default profile always uses these shared bindings
+    pub fn load_profile(&self, profile: &Profile) -> Result<Self, String> {
+        if profile.name().is_none() {
+            return Ok(*self);
+        }
+        let config_path = profile.config_path(PROFILE_INPUT_CONFIG_NAME)?;
+        let config: ProfileInputConfig =
+            confy::load_path(config_path).map_err(|e| e.to_string())?;
+        Ok(config.input.unwrap_or(*self))
+    }
+
+    /// Copies these bindings to a named profile, where they can be edited for that profile only
+    pub fn save_profile(&self, profile: &Profile) -> Result<(), String> {
+        let config_path = profile.config_path(PROFILE_INPUT_CONFIG_NAME)?;
+        let config = ProfileInputConfig {
+            input: Some(*self),
+        };
+        confy::store_path(config_path, config).map_err(|e| e.to_string())
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize, strum::EnumIter)]
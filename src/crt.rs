@@ -0,0 +1,101 @@
+//! An optional CRT look over the final canvas: scanlines at the resolution of the NES & SNES,
+//! darkening toward the corners to fake the curvature of the tube.
+//! The overlay is generated once per game at the window size & blended over every frame.
+
+use sdl2::pixels::PixelFormatEnum::RGBA8888;
+use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
+use sdl2::video::WindowContext;
+
+/// Visible lines on the NES & SNES
+const SOURCE_LINES: u32 = 224;
+const SCANLINE_ALPHA: f64 = 0.35;
+const VIGNETTE_ALPHA: f64 = 0.6;
+/// Distance from the center, as a fraction of the corner, where the vignette starts
+const VIGNETTE_START: f64 = 0.55;
+
+pub struct CrtFilter<'a> {
+    overlay: Texture<'a>,
+    enabled: bool,
+}
+
+impl<'a> CrtFilter<'a> {
+    pub fn new(
+        texture_creator: &'a TextureCreator<WindowContext>,
+        (width, height): (u32, u32),
+        enabled: bool,
+    ) -> Result<Self, String> {
+        let mut overlay = texture_creator
+            .create_texture_static(RGBA8888, width, height)
+            .map_err(|e| e.to_string())?;
+        overlay.set_blend_mode(BlendMode::Blend);
+        // black, so the alpha is the only channel that is set
+        let pixels = overlay_alpha(width, height)
+            .into_iter()
+            .flat_map(|alpha| (alpha as u32).to_ne_bytes())
+            .collect::<Vec<u8>>();
+        overlay
+            .update(None, &pixels, width as usize * 4)
+            .map_err(|e| e.to_string())?;
+        Ok(Self { overlay, enabled })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        if self.enabled {
+            canvas.copy(&self.overlay, None, None)?;
+        }
+        Ok(())
+    }
+}
+
+/// The last row of every scanline is darkened, so each line is at least two pixels high
+fn overlay_alpha(width: u32, height: u32) -> Vec<u8> {
+    let line_height = (height / SOURCE_LINES).max(2);
+    let (center_x, center_y) = (width as f64 / 2.0, height as f64 / 2.0);
+    let corner = (center_x * center_x + center_y * center_y).sqrt().max(1.0);
+
+    let mut result = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        let scanline = if y % line_height == line_height - 1 {
+            SCANLINE_ALPHA
+        } else {
+            0.0
+        };
+        for x in 0..width {
+            let (dx, dy) = (x as f64 + 0.5 - center_x, y as f64 + 0.5 - center_y);
+            let distance = (dx * dx + dy * dy).sqrt() / corner;
+            let t = ((distance - VIGNETTE_START) / (1.0 - VIGNETTE_START)).clamp(0.0, 1.0);
+            // smoothstep
+            let vignette = VIGNETTE_ALPHA * t * t * (3.0 - 2.0 * t);
+            let alpha = 1.0 - (1.0 - scanline) * (1.0 - vignette);
+            result.push((alpha * 255.0).round() as u8);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scanlines_and_vignette() {
+        let (width, height) = (640, 480);
+        let alpha = overlay_alpha(width, height);
+        let at = |x: u32, y: u32| alpha[(y * width + x) as usize];
+
+        // center of the screen on a lit line & the scanline below it
+        assert_eq!(at(320, 240), 0);
+        assert_eq!(at(320, 241), (SCANLINE_ALPHA * 255.0).round() as u8);
+        // the corners are darker than the edges
+        assert!(at(0, 0) > at(320, 0));
+        assert!(at(0, 0) > at(0, 240));
+    }
+}
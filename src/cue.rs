@@ -0,0 +1,189 @@
+//! Optional captions for the audio-only cues of a game, for deaf & hard of hearing players.
+//! Each cue is shown as a small toast at the top of the player's bottle, fading out after a moment.
+
+use crate::font::{FontTexture, FontType};
+use crate::game::event::GameEvent;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+use std::time::Duration;
+
+const TOAST_DURATION: Duration = Duration::from_millis(2000);
+const TOAST_FADE: Duration = Duration::from_millis(500);
+const TOAST_BACKGROUND_ALPHA: f64 = 0.7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioCue {
+    GarbageIncoming = 0,
+    SpeedUp = 1,
+    NextLevel = 2,
+}
+
+impl AudioCue {
+    const ALL: [Self; 3] = [Self::GarbageIncoming, Self::SpeedUp, Self::NextLevel];
+
+    /// Player & cue of the events that are otherwise only heard
+    pub fn from_event(event: &GameEvent) -> Option<(u32, Self)> {
+        match event {
+            GameEvent::ReceivedGarbage { player, .. } => Some((*player, Self::GarbageIncoming)),
+            GameEvent::SpeedLevelUp { player, .. } => Some((*player, Self::SpeedUp)),
+            GameEvent::LevelComplete { player } => Some((*player, Self::NextLevel)),
+            _ => None,
+        }
+    }
+
+    fn caption(&self) -> &'static str {
+        match self {
+            AudioCue::GarbageIncoming => "[garbage incoming]",
+            AudioCue::SpeedUp => "[speed up]",
+            AudioCue::NextLevel => "[next level jingle]",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Toast {
+    player: u32,
+    cue: AudioCue,
+    elapsed: Duration,
+}
+
+pub struct CueToasts<'a> {
+    captions: Vec<FontTexture<'a>>,
+    toasts: Vec<Toast>,
+    enabled: bool,
+}
+
+impl<'a> CueToasts<'a> {
+    pub fn new(
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        window_height: u32,
+        enabled: bool,
+    ) -> Result<Self, String> {
+        let font = FontType::Bold.load(ttf, (window_height / 40).max(8))?;
+        let captions = AudioCue::ALL
+            .iter()
+            .map(|cue| {
+                FontTexture::from_string(&font, texture_creator, cue.caption(), Color::WHITE)
+            })
+            .collect::<Result<Vec<FontTexture>, String>>()?;
+        Ok(Self {
+            captions,
+            toasts: vec![],
+            enabled,
+        })
+    }
+
+    pub fn receive_event(&mut self, event: &GameEvent) {
+        if !self.enabled {
+            return;
+        }
+        if let Some((player, cue)) = AudioCue::from_event(event) {
+            push_toast(&mut self.toasts, player, cue);
+        }
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        for toast in self.toasts.iter_mut() {
+            toast.elapsed += delta;
+        }
+        self.toasts.retain(|toast| toast.elapsed < TOAST_DURATION);
+    }
+
+    /// Draws the toasts of each player stacked down from the top center of their anchor
+    pub fn draw<F: Fn(u32) -> Rect>(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        anchor: F,
+    ) -> Result<(), String> {
+        let mut stacked = vec![];
+        for toast in self.toasts.iter() {
+            let caption = &mut self.captions[toast.cue as usize];
+            let padding = caption.height / 4;
+            let width = caption.width + padding * 2;
+            let height = caption.height + padding;
+
+            let anchor = anchor(toast.player);
+            let index = stacked.iter().filter(|&&p| p == toast.player).count() as i32;
+            stacked.push(toast.player);
+            let x = anchor.center().x() - width as i32 / 2;
+            let y = anchor.y() + padding as i32 + index * (height + padding) as i32;
+
+            let alpha = toast_alpha(toast.elapsed);
+            canvas.set_blend_mode(BlendMode::Blend);
+            canvas.set_draw_color(Color::RGBA(
+                0,
+                0,
+                0,
+                (alpha as f64 * TOAST_BACKGROUND_ALPHA) as u8,
+            ));
+            canvas.fill_rect(Rect::new(x, y, width, height))?;
+            canvas.set_blend_mode(BlendMode::None);
+
+            caption.texture.set_alpha_mod(alpha);
+            canvas.copy(
+                &caption.texture,
+                None,
+                Rect::new(
+                    x + padding as i32,
+                    y + padding as i32 / 2,
+                    caption.width,
+                    caption.height,
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A cue that is already showing for the player starts over rather than stacking up
+fn push_toast(toasts: &mut Vec<Toast>, player: u32, cue: AudioCue) {
+    match toasts
+        .iter_mut()
+        .find(|toast| toast.player == player && toast.cue == cue)
+    {
+        Some(toast) => toast.elapsed = Duration::ZERO,
+        None => toasts.push(Toast {
+            player,
+            cue,
+            elapsed: Duration::ZERO,
+        }),
+    }
+}
+
+/// Opaque until the last moments of the toast, then fading out
+fn toast_alpha(elapsed: Duration) -> u8 {
+    let remaining = TOAST_DURATION.saturating_sub(elapsed);
+    if remaining >= TOAST_FADE {
+        u8::MAX
+    } else {
+        (255.0 * remaining.as_secs_f64() / TOAST_FADE.as_secs_f64()) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_cue_restarts_toast() {
+        let mut toasts = vec![];
+        push_toast(&mut toasts, 0, AudioCue::SpeedUp);
+        toasts[0].elapsed = Duration::from_secs(1);
+        push_toast(&mut toasts, 0, AudioCue::SpeedUp);
+        push_toast(&mut toasts, 1, AudioCue::SpeedUp);
+        assert_eq!(toasts.len(), 2);
+        assert_eq!(toasts[0].elapsed, Duration::ZERO);
+    }
+
+    #[test]
+    fn toast_fades_out() {
+        assert_eq!(toast_alpha(Duration::ZERO), u8::MAX);
+        assert_eq!(toast_alpha(TOAST_DURATION - TOAST_FADE), u8::MAX);
+        assert_eq!(toast_alpha(TOAST_DURATION - TOAST_FADE / 2), 127);
+        assert_eq!(toast_alpha(TOAST_DURATION), 0);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::game::random::daily_seed;
+use crate::game::seed;
+use crate::high_score::daily::DailyTable;
+use num_format::{Locale, ToFormattedString};
+
+const DAYS_PER_WEEK: usize = 7;
+const PLAYED: &str = "o";
+const MISSED: &str = "-";
+const NOT_YET_PLAYED: &str = ".";
+
+/// Today's daily & a calendar of the last few weeks, a row per week with a mark per day
+pub fn daily_info(table: &DailyTable, today: i64) -> Vec<(String, String)> {
+    let best = match table.best(today) {
+        Some(best) => format!(
+            "{} in {}",
+            best.score.to_formatted_string(&Locale::en),
+            count(best.attempts, "try", "tries")
+        ),
+        None => "not played".to_string(),
+    };
+    let mut info = vec![
+        ("seed".to_string(), seed::encode(daily_seed(today))),
+        ("today".to_string(), best),
+        (
+            "streak".to_string(),
+            count(table.streak(today), "day", "days"),
+        ),
+    ];
+
+    let history = table.history(today);
+    let last = history.len() - 1;
+    let marks = history
+        .iter()
+        .enumerate()
+        .map(|(index, (_, best))| match best {
+            Some(_) => PLAYED,
+            None if index == last => NOT_YET_PLAYED,
+            None => MISSED,
+        })
+        .collect::<Vec<&str>>();
+    info.extend(
+        history
+            .chunks(DAYS_PER_WEEK)
+            .zip(marks.chunks(DAYS_PER_WEEK))
+            .map(|(week, marks)| {
+                // week of mm-dd
+                (week[0].0[5..].to_string(), marks.join(" "))
+            }),
+    );
+    info
+}
+
+fn count(n: u32, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendar_of_weeks() {
+        let today = 20_089;
+        let mut table = DailyTable::default();
+        table.record(today - 1, 1200, 1);
+        let info = daily_info(&table, today);
+        assert_eq!(info[1], ("today".to_string(), "not played".to_string()));
+        assert_eq!(info[2], ("streak".to_string(), "1 day".to_string()));
+        assert_eq!(
+            info.last().unwrap(),
+            &("12-26".to_string(), "- - - - - o .".to_string())
+        );
+    }
+}
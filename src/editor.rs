@@ -0,0 +1,466 @@
+//! Paints viruses into a bottle to practice on, saved as a layout next to the config. With the
+//! rules on, only viruses the seeder could have placed are painted.
+
+use crate::config::config_path;
+use crate::font::{FontTexture, FontType};
+use crate::game::bottle::BottleSize;
+use crate::game::geometry::BottlePoint;
+use crate::game::pill::VirusColor;
+use crate::game::random::BottleSeed;
+use crate::game::rules::BottleShape;
+use crate::menu_input::MenuInputKey;
+use crate::scene::{Scene, SceneInput};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+use std::path::PathBuf;
+use strum::IntoEnumIterator;
+
+const FONT_COLOR: Color = Color::WHITE;
+const HINT_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+const BOTTLE_COLOR: Color = Color::RGB(0x10, 0x10, 0x10);
+const GRID_COLOR: Color = Color::RGB(0x30, 0x30, 0x30);
+const TITLE: &str = "editor";
+const HINT: &str =
+    "arrows: move    select: paint    wheel: brush    start: play    back: save & exit";
+const LAYOUT_HEADER: &str =
+    "# virus layout, a row per line: . empty, r red, b blue, y yellow, g green";
+/// Brushes in the order they are cycled, none erases
+const BRUSHES: [Option<VirusColor>; 5] = [
+    Some(VirusColor::Yellow),
+    Some(VirusColor::Blue),
+    Some(VirusColor::Red),
+    Some(VirusColor::Green),
+    None,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditorAction {
+    /// practice the layout, which has at least one virus
+    Play,
+    Back,
+}
+
+/// Actions listed to the right of the bottle, in order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tool {
+    Brush,
+    Rules,
+    Clear,
+    Play,
+    Back,
+}
+
+const TOOLS: [Tool; 5] = [
+    Tool::Brush,
+    Tool::Rules,
+    Tool::Clear,
+    Tool::Play,
+    Tool::Back,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cursor {
+    Bottle(BottlePoint),
+    Tool(usize),
+}
+
+pub fn layout_path() -> Result<PathBuf, String> {
+    let mut path = config_path("config")?;
+    path.set_file_name("layout.txt");
+    Ok(path)
+}
+
+/// The saved layout, if there is one
+pub fn load_layout() -> Result<Option<BottleSeed>, String> {
+    let path = layout_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let layout = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    layout.parse::<BottleSeed>().map(Some)
+}
+
+/// The bottle a layout is played in, hand edited layouts may not fit any
+pub fn bottle_shape(layout: &BottleSeed) -> Option<BottleShape> {
+    BottleShape::iter().find(|b| b.size() == layout.size())
+}
+
+pub fn save_layout(layout: &BottleSeed) -> Result<(), String> {
+    let path = layout_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, format!("{}\n{}", LAYOUT_HEADER, layout)).map_err(|e| e.to_string())
+}
+
+fn virus_color(color: VirusColor) -> Color {
+    match color {
+        VirusColor::Yellow => Color::RGB(0xf8, 0xd8, 0x00),
+        VirusColor::Blue => Color::RGB(0x00, 0x78, 0xf8),
+        VirusColor::Red => Color::RGB(0xf8, 0x38, 0x00),
+        VirusColor::Green => Color::RGB(0x00, 0xb8, 0x00),
+    }
+}
+
+fn brush_name(brush: Option<VirusColor>) -> &'static str {
+    match brush {
+        Some(VirusColor::Yellow) => "yellow",
+        Some(VirusColor::Blue) => "blue",
+        Some(VirusColor::Red) => "red",
+        Some(VirusColor::Green) => "green",
+        None => "erase",
+    }
+}
+
+/// Paints a block with the brush, a virus of the same color is erased instead.
+/// Returns false when the rules forbid the virus
+fn stroke(
+    layout: &mut BottleSeed,
+    point: BottlePoint,
+    brush: Option<VirusColor>,
+    rules: bool,
+) -> bool {
+    let color = brush.filter(|&c| layout.virus(point) != Some(c));
+    if let Some(color) = color {
+        if rules && !layout.is_legal(point, color) {
+            return false;
+        }
+    }
+    layout.paint(point, color);
+    true
+}
+
+pub struct EditorScreen<'a> {
+    layout: BottleSeed,
+    brush: usize,
+    rules: bool,
+    cursor: Cursor,
+    title: FontTexture<'a>,
+    hint: FontTexture<'a>,
+    /// rendered once for every brush
+    brush_labels: Vec<FontTexture<'a>>,
+    /// rendered for the rules off & on
+    rules_labels: [FontTexture<'a>; 2],
+    clear_label: FontTexture<'a>,
+    play_label: FontTexture<'a>,
+    back_label: FontTexture<'a>,
+    block_size: u32,
+    padding: u32,
+    window_size: (u32, u32),
+}
+
+impl<'a> EditorScreen<'a> {
+    pub fn new(
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        (window_width, window_height): (u32, u32),
+    ) -> Result<Self, String> {
+        let title_font = FontType::Retro.load(ttf, window_width / 24)?;
+        let tool_font = FontType::Retro.load(ttf, window_width / 32)?;
+        let hint_font = FontType::Bold.load(ttf, window_height / 40)?;
+        let label =
+            |text: &str| FontTexture::from_string(&tool_font, texture_creator, text, FONT_COLOR);
+
+        let title = FontTexture::from_string(&title_font, texture_creator, TITLE, FONT_COLOR)?;
+        let hint = FontTexture::from_string(&hint_font, texture_creator, HINT, HINT_COLOR)?;
+        let brush_labels = BRUSHES
+            .iter()
+            .map(|b| label(&format!("brush: {}", brush_name(*b))))
+            .collect::<Result<Vec<FontTexture>, String>>()?;
+
+        // a layout that no longer parses is started over rather than stopping the editor
+        let layout = match load_layout() {
+            Ok(Some(layout)) if bottle_shape(&layout).is_none() => {
+                println!(
+                    "layout is {}x{}, not the size of a bottle, starting an empty bottle",
+                    layout.size().width(),
+                    layout.size().height()
+                );
+                BottleSeed::default()
+            }
+            Ok(layout) => layout.unwrap_or_default(),
+            Err(error) => {
+                println!("bad layout file, starting an empty bottle: {}", error);
+                BottleSeed::default()
+            }
+        };
+        // green may always be painted, it makes the layout four color
+        let layout = layout.with_four_colors(true);
+
+        let padding = window_height / 32;
+        let size = layout.size();
+        let block_size = window_height
+            .saturating_sub(title.height + hint.height + padding * 4)
+            .max(size.height())
+            / size.height();
+        Ok(Self {
+            layout,
+            brush: 0,
+            rules: true,
+            cursor: Cursor::Bottle(BottlePoint::new(0, size.height() as i32 - 1)),
+            title,
+            hint,
+            brush_labels,
+            rules_labels: [label("rules: off")?, label("rules: on")?],
+            clear_label: label("clear")?,
+            play_label: label("play")?,
+            back_label: label("back")?,
+            block_size,
+            padding,
+            window_size: (window_width, window_height),
+        })
+    }
+
+    pub fn layout(&self) -> &BottleSeed {
+        &self.layout
+    }
+
+    fn size(&self) -> BottleSize {
+        self.layout.size()
+    }
+
+    /// The bottle is left of the center of the window, the tools to the right
+    fn bottle_rect(&self) -> Rect {
+        let (window_width, _) = self.window_size;
+        let width = self.block_size * self.size().width();
+        let height = self.block_size * self.size().height();
+        Rect::new(
+            window_width as i32 / 2 - width as i32 - self.padding as i32,
+            (self.padding * 2 + self.title.height) as i32,
+            width,
+            height,
+        )
+    }
+
+    fn block_rect(&self, point: BottlePoint) -> Rect {
+        let bottle = self.bottle_rect();
+        Rect::new(
+            bottle.x() + point.x() * self.block_size as i32,
+            bottle.y() + point.y() * self.block_size as i32,
+            self.block_size,
+            self.block_size,
+        )
+    }
+
+    fn tool_label(&self, tool: Tool) -> &FontTexture<'a> {
+        match tool {
+            Tool::Brush => &self.brush_labels[self.brush],
+            Tool::Rules => &self.rules_labels[self.rules as usize],
+            Tool::Clear => &self.clear_label,
+            Tool::Play => &self.play_label,
+            Tool::Back => &self.back_label,
+        }
+    }
+
+    fn tool_rect(&self, index: usize) -> Rect {
+        let (window_width, _) = self.window_size;
+        let bottle = self.bottle_rect();
+        let label = self.tool_label(TOOLS[index]);
+        let line_height = self.brush_labels[0].height + self.padding;
+        Rect::new(
+            window_width as i32 / 2 + self.padding as i32,
+            bottle.y() + (line_height * index as u32) as i32,
+            label.width,
+            label.height,
+        )
+    }
+
+    fn cycle_brush(&mut self, up: bool) {
+        let delta = if up { BRUSHES.len() - 1 } else { 1 };
+        self.brush = (self.brush + delta) % BRUSHES.len();
+    }
+
+    fn paint(&mut self, point: BottlePoint) -> SceneInput<EditorAction> {
+        self.cursor = Cursor::Bottle(point);
+        if stroke(&mut self.layout, point, BRUSHES[self.brush], self.rules) {
+            SceneInput::Handled
+        } else {
+            SceneInput::Ignored
+        }
+    }
+
+    fn activate(&mut self, index: usize) -> SceneInput<EditorAction> {
+        self.cursor = Cursor::Tool(index);
+        match TOOLS[index] {
+            Tool::Brush => self.cycle_brush(false),
+            Tool::Rules => self.rules = !self.rules,
+            Tool::Clear => self.layout = BottleSeed::new(self.size()).with_four_colors(true),
+            Tool::Play => return self.play(),
+            Tool::Back => return SceneInput::Exit(EditorAction::Back),
+        }
+        SceneInput::Handled
+    }
+
+    fn play(&self) -> SceneInput<EditorAction> {
+        if self.layout.viruses().is_empty() {
+            SceneInput::Ignored
+        } else {
+            SceneInput::Exit(EditorAction::Play)
+        }
+    }
+
+    fn move_cursor(&mut self, dx: i32, dy: i32) -> SceneInput<EditorAction> {
+        let (width, height) = (self.size().width() as i32, self.size().height() as i32);
+        self.cursor = match self.cursor {
+            // right of the last column are the tools
+            Cursor::Bottle(point) if point.x() + dx >= width => Cursor::Tool(0),
+            Cursor::Bottle(point) => Cursor::Bottle(BottlePoint::new(
+                (point.x() + dx).clamp(0, width - 1),
+                (point.y() + dy).clamp(0, height - 1),
+            )),
+            Cursor::Tool(_) if dx < 0 => Cursor::Bottle(BottlePoint::new(width - 1, height - 1)),
+            Cursor::Tool(index) => {
+                Cursor::Tool((index as i32 + dy).clamp(0, TOOLS.len() as i32 - 1) as usize)
+            }
+        };
+        SceneInput::Handled
+    }
+
+    fn click(&mut self, x: i32, y: i32) -> SceneInput<EditorAction> {
+        let bottle = self.bottle_rect();
+        if bottle.contains_point((x, y)) {
+            let point = BottlePoint::new(
+                (x - bottle.x()) / self.block_size as i32,
+                (y - bottle.y()) / self.block_size as i32,
+            );
+            return self.paint(point);
+        }
+        match (0..TOOLS.len()).find(|&i| self.tool_rect(i).contains_point((x, y))) {
+            Some(index) => self.activate(index),
+            None => SceneInput::Ignored,
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let (window_width, window_height) = self.window_size;
+        let centered_x = |width: u32| (window_width as i32 - width as i32).max(0) / 2;
+
+        canvas.copy(
+            &self.title.texture,
+            None,
+            Rect::new(
+                centered_x(self.title.width),
+                self.padding as i32,
+                self.title.width,
+                self.title.height,
+            ),
+        )?;
+
+        // bottle, a grid of blocks with the viruses filled in
+        canvas.set_draw_color(BOTTLE_COLOR);
+        canvas.fill_rect(self.bottle_rect())?;
+        for y in 0..self.size().height() as i32 {
+            for x in 0..self.size().width() as i32 {
+                let point = BottlePoint::new(x, y);
+                let rect = self.block_rect(point);
+                canvas.set_draw_color(GRID_COLOR);
+                canvas.draw_rect(rect)?;
+                if let Some(color) = self.layout.virus(point) {
+                    let inset = (self.block_size / 8).max(1);
+                    canvas.set_draw_color(virus_color(color));
+                    canvas.fill_rect(Rect::new(
+                        rect.x() + inset as i32,
+                        rect.y() + inset as i32,
+                        self.block_size - inset * 2,
+                        self.block_size - inset * 2,
+                    ))?;
+                }
+            }
+        }
+
+        // tools, with a caret under the current one like the seed entry
+        let caret_height = (self.padding / 4).max(1);
+        for (index, tool) in TOOLS.iter().enumerate() {
+            let label = self.tool_label(*tool);
+            let rect = self.tool_rect(index);
+            canvas.copy(&label.texture, None, rect)?;
+            if self.cursor == Cursor::Tool(index) {
+                canvas.set_draw_color(FONT_COLOR);
+                canvas.fill_rect(Rect::new(
+                    rect.x(),
+                    rect.bottom(),
+                    rect.width(),
+                    caret_height,
+                ))?;
+            }
+        }
+
+        // cursor, outlined in the color of the brush
+        if let Cursor::Bottle(point) = self.cursor {
+            canvas.set_draw_color(BRUSHES[self.brush].map_or(FONT_COLOR, virus_color));
+            let rect = self.block_rect(point);
+            canvas.draw_rect(rect)?;
+            canvas.draw_rect(Rect::new(
+                rect.x() + 1,
+                rect.y() + 1,
+                rect.width().saturating_sub(2),
+                rect.height().saturating_sub(2),
+            ))?;
+        }
+
+        canvas.copy(
+            &self.hint.texture,
+            None,
+            Rect::new(
+                centered_x(self.hint.width),
+                (window_height - self.padding - self.hint.height) as i32,
+                self.hint.width,
+                self.hint.height,
+            ),
+        )
+    }
+}
+
+impl Scene for EditorScreen<'_> {
+    type Output = EditorAction;
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<EditorAction> {
+        match key {
+            MenuInputKey::Up => self.move_cursor(0, -1),
+            MenuInputKey::Down => self.move_cursor(0, 1),
+            MenuInputKey::Left => self.move_cursor(-1, 0),
+            MenuInputKey::Right => self.move_cursor(1, 0),
+            MenuInputKey::Select => match self.cursor {
+                Cursor::Bottle(point) => self.paint(point),
+                Cursor::Tool(index) => self.activate(index),
+            },
+            MenuInputKey::Start => self.play(),
+            MenuInputKey::Back | MenuInputKey::Quit => SceneInput::Exit(EditorAction::Back),
+            MenuInputKey::Wheel { up } => {
+                self.cycle_brush(up);
+                SceneInput::Handled
+            }
+            MenuInputKey::Click { x, y } => self.click(x, y),
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        EditorScreen::draw(self, canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strokes_toggle_viruses_within_the_rules() {
+        let mut layout = BottleSeed::default().with_four_colors(true);
+        let point = BottlePoint::new(0, 15);
+        assert!(stroke(&mut layout, point, Some(VirusColor::Red), true));
+        assert_eq!(layout.virus(point), Some(VirusColor::Red));
+        // the same color again erases it
+        assert!(stroke(&mut layout, point, Some(VirusColor::Red), true));
+        assert_eq!(layout.virus(point), None);
+
+        // no viruses in the gap at the top of the bottle, unless the rules are off
+        let top = BottlePoint::new(0, 0);
+        assert!(!stroke(&mut layout, top, Some(VirusColor::Blue), true));
+        assert!(stroke(&mut layout, top, Some(VirusColor::Blue), false));
+        assert!(stroke(&mut layout, top, None, true));
+        assert!(layout.viruses().is_empty());
+    }
+}
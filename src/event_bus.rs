@@ -0,0 +1,246 @@
+//! Routes the game events of each frame to the systems that react to them, so a new system
+//! subscribes a sink rather than adding to the game loop. The loop itself only handles the events
+//! that change the match e.g. sending garbage or ending the game.
+
+use crate::achievements::toast::AchievementToasts;
+use crate::achievements::Achievements;
+use crate::config::AudioConfig;
+use crate::cue::CueToasts;
+use crate::game::event::GameEvent;
+use crate::game::geometry::BottlePoint;
+use crate::game::pill::VirusColor;
+use crate::game::GameSpeed;
+use crate::particles::prescribed::PlayerTargetedParticles;
+use crate::player::Match;
+use crate::profile::Profile;
+use crate::rumble::{Rumble, RumblePulse};
+use crate::theme::sound::MusicDucking;
+use crate::themes::ThemeContext;
+use dr_rustario_core::stats::PlayerStats;
+use std::collections::HashMap;
+
+/// State of the game loop the sinks act on, borrowed for the dispatch of each event
+pub struct EventContext<'a, 't> {
+    pub themes: &'a mut ThemeContext<'t>,
+    pub fixture: &'a Match,
+    pub speed: GameSpeed,
+    pub profile: &'a Profile,
+    pub audio: &'a AudioConfig,
+    pub ducking: &'a mut MusicDucking,
+    pub cue_toasts: &'a mut CueToasts<'t>,
+    /// none for the demo
+    pub achievements: &'a mut Option<Achievements>,
+    pub achievement_toasts: &'a mut AchievementToasts<'t>,
+    /// none for the demo
+    pub player_stats: &'a mut Option<PlayerStats>,
+    /// emitted once the events are dispatched, when the particle scale is known
+    pub particles: &'a mut Vec<PlayerTargetedParticles>,
+    pub rumble: &'a mut Rumble,
+}
+
+pub trait EventSink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String>;
+}
+
+#[derive(Default)]
+pub struct EventBus {
+    sinks: Vec<Box<dyn EventSink>>,
+}
+
+impl EventBus {
+    /// Sinks of a game, in the order they receive each event
+    pub fn game() -> Self {
+        Self::default()
+            .with_sink(AudioSink)
+            .with_sink(RumbleSink)
+            .with_sink(AchievementSink)
+            .with_sink(ParticleSink::default())
+            .with_sink(AnimationSink::default())
+            .with_sink(StatsSink)
+    }
+
+    pub fn with_sink<S: EventSink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    pub fn dispatch(
+        &mut self,
+        event: &GameEvent,
+        context: &mut EventContext,
+    ) -> Result<(), String> {
+        for sink in self.sinks.iter_mut() {
+            sink.receive(event, context)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sound effects of the theme, ducking the music under combo jingles & the captions of audio cues
+pub struct AudioSink;
+
+impl EventSink for AudioSink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String> {
+        let audio = context.themes.theme().audio();
+        if let Some(duration) = audio.ducking_duration(event) {
+            context.ducking.duck(duration, context.audio);
+        }
+        audio.receive_event(event.clone())?;
+        context.cue_toasts.receive_event(event);
+        Ok(())
+    }
+}
+
+/// Haptic feedback of the controller of each player, felt alongside the sound effects
+pub struct RumbleSink;
+
+impl EventSink for RumbleSink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String> {
+        if let Some((player, pulse)) = RumblePulse::of_event(event) {
+            context.rumble.pulse(player, pulse);
+        }
+        Ok(())
+    }
+}
+
+/// Unlocks & saves achievements, each with a toast
+pub struct AchievementSink;
+
+impl EventSink for AchievementSink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String> {
+        if let Some(achievements) = context.achievements.as_mut() {
+            for achievement in achievements.receive_event(event) {
+                context.achievement_toasts.push(achievement);
+                achievements.store().save(context.profile)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Particles of the theme's scene, including the souls of the blocks cleared by a combo that
+/// fly to the opponent with its garbage
+#[derive(Default)]
+pub struct ParticleSink {
+    /// blocks cleared by each player since their last pill locked
+    cleared: HashMap<u32, Vec<BottlePoint>>,
+}
+
+impl EventSink for ParticleSink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String> {
+        let scene = context.themes.theme().scene(context.speed);
+        if let Some(emit) = scene.emit_particles(event.clone()) {
+            context.particles.push(emit);
+        }
+
+        match event {
+            GameEvent::Lock { player, .. } => {
+                self.cleared.remove(player);
+            }
+            GameEvent::Destroy { player, blocks, .. } => self
+                .cleared
+                .entry(*player)
+                .or_default()
+                .extend(blocks.iter().map(|b| b.position)),
+            GameEvent::SendGarbage { player, .. } => {
+                let cleared = self.cleared.remove(player).unwrap_or_default();
+                if let Some(to) = context.fixture.opponent(*player) {
+                    if !cleared.is_empty() {
+                        if let Some(emit) = scene.emit_soul(*player, cleared, to) {
+                            context.particles.push(emit);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Player animations of the bottle, those that drive the match e.g. game over are left to the loop
+#[derive(Default)]
+pub struct AnimationSink {
+    /// blocks cleared by each player since their last pill locked, where their score pops up
+    cleared: HashMap<u32, Vec<BottlePoint>>,
+}
+
+impl EventSink for AnimationSink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String> {
+        let themes = &mut context.themes;
+        match event {
+            GameEvent::Destroy { player, blocks, .. } => {
+                self.cleared
+                    .entry(*player)
+                    .or_default()
+                    .extend(blocks.iter().map(|b| b.position));
+                themes.animate_destroy(*player, blocks.clone());
+                let colors = blocks
+                    .iter()
+                    .filter(|b| b.is_virus)
+                    .map(|b| b.color)
+                    .collect::<Vec<VirusColor>>();
+                themes.animate_lounge_wince(*player, &colors);
+            }
+            GameEvent::VirusColorEliminated { player, color } => {
+                themes.animate_lounge_eliminate(*player, *color)
+            }
+            GameEvent::VirusesCrawled { player, crawls } => {
+                themes.animate_virus_crawl(*player, crawls.clone());
+            }
+            GameEvent::Chain { player, .. } => themes.animate_chain(*player),
+            GameEvent::ComboScore { player, score } => {
+                let cleared = self.cleared.remove(player).unwrap_or_default();
+                themes.animate_score_popup(*player, *score, &cleared);
+            }
+            GameEvent::Lock {
+                player,
+                vitamins,
+                hard_or_soft_dropped,
+                ..
+            } => {
+                self.cleared.remove(player);
+                if *hard_or_soft_dropped {
+                    themes.animate_impact(*player);
+                }
+                themes.animate_lock(*player, *vitamins);
+            }
+            GameEvent::Hold {
+                player,
+                shape,
+                vitamins,
+                released,
+            } => themes.animate_hold(*player, *shape, *vitamins, *released),
+            GameEvent::Spawn {
+                player,
+                shape,
+                is_hold,
+                ..
+            } => {
+                themes.animate_spawn(*player, *shape, *is_hold);
+            }
+            GameEvent::Rewind { player } | GameEvent::LoadState { player, .. } => {
+                themes.animate_rewind(*player)
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Lifetime statistics of player 1 by level, matches are recorded by the loop as they end
+pub struct StatsSink;
+
+impl EventSink for StatsSink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String> {
+        if let GameEvent::LevelComplete { player: 0 } = event {
+            // the last level of a match is recorded with the match
+            if !context.fixture.next_level_ends_match(0) {
+                if let Some(player_stats) = context.player_stats.as_mut() {
+                    player_stats.record_level(context.fixture.player(0).game().stats());
+                }
+            }
+        }
+        Ok(())
+    }
+}
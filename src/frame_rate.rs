@@ -1,23 +1,95 @@
+use crate::profiling;
 use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Copy, Clone)]
 pub struct FrameRate {
     t0: SystemTime,
+    min_frame_time: Option<Duration>,
 }
 
 impl FrameRate {
     pub fn new() -> Self {
         Self {
             t0: SystemTime::now(),
+            min_frame_time: None,
+        }
+    }
+
+    /// Sleeps in update to keep the frame rate at or below max_fps
+    pub fn limited(max_fps: u32) -> Self {
+        Self {
+            t0: SystemTime::now(),
+            min_frame_time: Some(Duration::from_secs(1) / max_fps.max(1)),
         }
     }
 
     /// Registers the start of a new frame, returns the time since the last frame
     pub fn update(&mut self) -> Result<Duration, String> {
-        // TODO have option of limiting/recording the effective framerate
-        let now = SystemTime::now();
+        let mut now = SystemTime::now();
+        if let Some(min_frame_time) = self.min_frame_time {
+            let elapsed = now.duration_since(self.t0).map_err(|e| e.to_string())?;
+            if elapsed < min_frame_time {
+                std::thread::sleep(min_frame_time - elapsed);
+                now = SystemTime::now();
+            }
+        }
         let delta = now.duration_since(self.t0).map_err(|e| e.to_string())?;
         self.t0 = now;
+        profiling::new_frame();
         Ok(delta)
     }
 }
+
+/// Game logic at a fixed 60hz on any display: each frame is split into whole steps and the
+/// remainder is carried over to the next frame, so the game plays the same at 60, 120 or 144hz
+#[derive(Debug, Copy, Clone)]
+pub struct FixedTimestep {
+    accumulator: Duration,
+}
+
+impl FixedTimestep {
+    pub const STEP: Duration = Duration::from_nanos(16_666_667);
+    /// Time beyond this many steps is dropped after a stall rather than played back all at once
+    const MAX_STEPS: u32 = 8;
+
+    pub fn new() -> Self {
+        Self {
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Number of steps to run for this frame, possibly none on a fast display
+    pub fn steps(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+        let steps = (self.accumulator.as_nanos() / Self::STEP.as_nanos()) as u32;
+        if steps > Self::MAX_STEPS {
+            self.accumulator = Duration::ZERO;
+            Self::MAX_STEPS
+        } else {
+            self.accumulator -= Self::STEP * steps;
+            steps
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_steps_on_any_refresh_rate() {
+        for hz in [60, 120, 144, 240] {
+            let mut timestep = FixedTimestep::new();
+            let frame = Duration::from_secs(1) / hz;
+            let steps: u32 = (0..hz).map(|_| timestep.steps(frame)).sum();
+            assert!((59..=60).contains(&steps), "{} steps at {}hz", steps, hz);
+        }
+    }
+
+    #[test]
+    fn drops_time_after_a_stall() {
+        let mut timestep = FixedTimestep::new();
+        assert_eq!(timestep.steps(Duration::from_secs(1)), FixedTimestep::MAX_STEPS);
+        assert_eq!(timestep.steps(Duration::from_millis(1)), 0);
+    }
+}
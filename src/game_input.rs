@@ -1,41 +1,12 @@
-use crate::config::InputConfig;
+use crate::config::{AutoRepeatConfig, InputConfig};
+pub use dr_rustario_core::input::GameInputKey;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use std::collections::HashMap;
 use std::time::Duration;
 
-const AUTO_REPEAT_DELAY: Duration = Duration::from_millis(300);
-const AUTO_REPEAT_ITERATION: Duration = Duration::from_millis(25);
-
-#[derive(Hash, Clone, Copy, Debug, PartialEq, Eq)]
-pub enum GameInputKey {
-    MoveLeft { player: u32 },
-    MoveRight { player: u32 },
-    SoftDrop { player: u32 },
-    HardDrop { player: u32 },
-    RotateClockwise { player: u32 },
-    RotateAnticlockwise { player: u32 },
-    Hold { player: u32 },
-    Pause,
-    ReturnToMenu,
-    Quit,
-    NextTheme,
-}
-
-impl GameInputKey {
-    pub fn player(&self) -> Option<u32> {
-        match self {
-            GameInputKey::MoveLeft { player }
-            | GameInputKey::MoveRight { player }
-            | GameInputKey::SoftDrop { player }
-            | GameInputKey::HardDrop { player }
-            | GameInputKey::RotateClockwise { player }
-            | GameInputKey::RotateAnticlockwise { player }
-            | GameInputKey::Hold { player } => Some(*player),
-            _ => None,
-        }
-    }
-}
+/// How often the hard drop preview is refreshed while hard drop is held
+const HARD_DROP_PREVIEW_STEP: Duration = Duration::from_millis(50);
 
 #[derive(Hash, Clone, Copy, Debug, PartialEq, Eq)]
 struct GameInput {
@@ -54,32 +25,87 @@ impl GameInput {
     }
 }
 
+/// A game key & how far into the frame it was pressed, so that it is applied between the fixed
+/// steps of the game in the order it was pressed rather than all at the start of the frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimedKey {
+    pub key: GameInputKey,
+    pub offset: Duration,
+}
+
+impl TimedKey {
+    /// At the start of the frame, e.g. held keys, the pause menu & bots
+    pub fn new(key: GameInputKey) -> Self {
+        Self {
+            key,
+            offset: Duration::ZERO,
+        }
+    }
+
+    fn pressed(key: GameInputKey, delta: Duration, age: Duration) -> Self {
+        Self {
+            key,
+            offset: delta.saturating_sub(age),
+        }
+    }
+
+    /// Index of the step of a frame that the key is applied before
+    pub fn step(&self, delta: Duration, steps: usize) -> usize {
+        if delta.is_zero() {
+            return 0;
+        }
+        let step = self.offset.as_nanos() * steps as u128 / delta.as_nanos();
+        (step as usize).min(steps.saturating_sub(1))
+    }
+}
+
 type KeyMapping = HashMap<Keycode, GameInputKey>;
 
 enum MaybeKey {
-    Down(GameInputKey),
-    Up(GameInputKey),
+    /// with the SDL timestamp of the event in millis
+    Down(GameInputKey, u32),
+    Up(GameInputKey, u32),
     None,
 }
 
 pub struct GameInputContext {
     mapping: KeyMapping,
     current: HashMap<GameInputKey, GameInput>,
+    auto_repeat: Vec<AutoRepeatConfig>,
+    hard_drop_preview: Vec<bool>,
 }
 
 impl GameInputContext {
     pub fn new(config: InputConfig) -> Self {
+        let mut auto_repeat = vec![config.player1.auto_repeat];
+        let mut hard_drop_preview = vec![config.player1.hard_drop_preview];
+        if let Some(player2) = config.player2 {
+            auto_repeat.push(player2.auto_repeat);
+            hard_drop_preview.push(player2.hard_drop_preview);
+        }
         Self {
             mapping: config.game_map(),
             current: HashMap::new(),
+            auto_repeat,
+            hard_drop_preview,
         }
     }
 
-    pub fn update<I>(&mut self, delta: Duration, sdl_events: I) -> Vec<GameInputKey>
+    fn is_hard_drop_preview(&self, player: u32) -> bool {
+        self.hard_drop_preview
+            .get(player as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Keys of the events polled at `polled_at` SDL ticks, at the end of a frame of `delta`
+    pub fn update<I>(&mut self, delta: Duration, polled_at: u32, sdl_events: I) -> Vec<TimedKey>
     where
         I: Iterator<Item = Event>,
     {
-        let mut result: Vec<GameInputKey> = vec![];
+        let mut result: Vec<TimedKey> = vec![];
+        let age =
+            |timestamp: u32| Duration::from_millis(polled_at.saturating_sub(timestamp) as u64);
 
         // update any keys that might still be held with the delta
         for event in self.current.values_mut() {
@@ -89,13 +115,29 @@ impl GameInputContext {
         for sdl_event in sdl_events {
             match self.map_from_sdl_event(sdl_event) {
                 MaybeKey::None => {}
-                MaybeKey::Down(key) => {
+                MaybeKey::Down(key, timestamp) => {
                     let event = GameInput::new(key);
                     self.current.insert(key, event);
-                    result.push(key);
+                    let age = age(timestamp);
+                    match key {
+                        GameInputKey::HardDrop { player } if self.is_hard_drop_preview(player) => {
+                            // first stage, the drop is committed when the key is released
+                            let preview = GameInputKey::HardDropPreview { player };
+                            result.push(TimedKey::pressed(preview, delta, age));
+                        }
+                        _ => result.push(TimedKey::pressed(key, delta, age)),
+                    }
                 }
-                MaybeKey::Up(key) => {
-                    self.current.remove(&key);
+                MaybeKey::Up(key, timestamp) => {
+                    let was_held = self.current.remove(&key).is_some();
+                    match key {
+                        GameInputKey::HardDrop { player }
+                            if was_held && self.is_hard_drop_preview(player) =>
+                        {
+                            result.push(TimedKey::pressed(key, delta, age(timestamp)));
+                        }
+                        _ => {}
+                    }
                 }
             };
         }
@@ -103,21 +145,34 @@ impl GameInputContext {
         // check for any held keys that have triggered a repeat
         for event in self.current.values_mut() {
             match event.key {
-                GameInputKey::MoveLeft { .. } | GameInputKey::MoveRight { .. } => {
+                GameInputKey::MoveLeft { player } | GameInputKey::MoveRight { player } => {
                     // check auto-repeat
+                    let auto_repeat = self
+                        .auto_repeat
+                        .get(player as usize)
+                        .copied()
+                        .unwrap_or_default();
                     if event.repeating {
-                        if event.duration >= AUTO_REPEAT_ITERATION {
+                        if event.duration >= auto_repeat.rate() {
                             event.duration = Duration::ZERO;
-                            result.push(event.key);
+                            result.push(TimedKey::new(event.key));
                         }
-                    } else if event.duration >= AUTO_REPEAT_DELAY {
+                    } else if event.duration >= auto_repeat.delay() {
                         event.duration = Duration::ZERO;
                         event.repeating = true;
-                        result.push(event.key);
+                        result.push(TimedKey::new(event.key));
                     }
                 }
                 GameInputKey::SoftDrop { player } => {
-                    result.push(GameInputKey::SoftDrop { player });
+                    result.push(TimedKey::new(GameInputKey::SoftDrop { player }));
+                }
+                GameInputKey::HardDrop { player }
+                    if event.duration >= HARD_DROP_PREVIEW_STEP
+                        && self.hard_drop_preview.get(player as usize) == Some(&true) =>
+                {
+                    // keep the preview following the pill while it is moved
+                    event.duration = Duration::ZERO;
+                    result.push(TimedKey::new(GameInputKey::HardDropPreview { player }));
                 }
                 _ => {}
             }
@@ -128,24 +183,48 @@ impl GameInputContext {
 
     fn map_from_sdl_event(&self, event: Event) -> MaybeKey {
         match event {
-            Event::Quit { .. } => MaybeKey::Down(GameInputKey::Quit),
+            Event::Quit { timestamp } => MaybeKey::Down(GameInputKey::Quit, timestamp),
             Event::KeyDown {
+                timestamp,
                 keycode: Some(keycode),
                 repeat: false,
                 ..
             } => match self.mapping.get(&keycode) {
                 None => MaybeKey::None,
-                Some(key) => MaybeKey::Down(*key),
+                Some(key) => MaybeKey::Down(*key, timestamp),
             },
             Event::KeyUp {
+                timestamp,
                 keycode: Some(keycode),
                 repeat: false,
                 ..
             } => match self.mapping.get(&keycode) {
                 None => MaybeKey::None,
-                Some(key) => MaybeKey::Up(*key),
+                Some(key) => MaybeKey::Up(*key, timestamp),
             },
             _ => MaybeKey::None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_of_keys_pressed_through_a_frame() {
+        let key = GameInputKey::MoveLeft { player: 0 };
+        let delta = Duration::from_millis(50);
+        let step =
+            |age: u64| TimedKey::pressed(key, delta, Duration::from_millis(age)).step(delta, 3);
+        assert_eq!(step(50), 0);
+        assert_eq!(step(30), 1);
+        assert_eq!(step(10), 2);
+        // pressed as the events were polled
+        assert_eq!(step(0), 2);
+        // queued since before the frame
+        assert_eq!(step(80), 0);
+        assert_eq!(TimedKey::new(key).step(delta, 0), 0);
+        assert_eq!(TimedKey::new(key).step(Duration::ZERO, 3), 0);
+    }
+}
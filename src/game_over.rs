@@ -0,0 +1,210 @@
+//! Summary of a single player game once the game over animation is dismissed, offering a retry
+//! with the same options or a return to the game menu rather than dropping straight to the menu.
+
+use crate::about::AboutScreen;
+use crate::font::{FontTexture, FontType};
+use crate::menu_input::MenuInputKey;
+use crate::scene::{Scene, SceneInput};
+use num_format::{Locale, ToFormattedString};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+
+const SELECTED_COLOR: Color = Color::WHITE;
+const UNSELECTED_COLOR: Color = Color::RGB(0x66, 0x66, 0x66);
+const OPTIONS: [(&str, GameOverAction); 2] = [
+    ("retry", GameOverAction::Retry),
+    ("menu", GameOverAction::Menu),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOverAction {
+    /// play again with the same options
+    Retry,
+    Menu,
+}
+
+/// How far player 1 got before the game ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameOverSummary {
+    /// the match was won e.g. a sprint was completed, rather than topped out
+    pub won: bool,
+    pub score: u32,
+    pub virus_level: u32,
+    /// over every level of the game
+    pub viruses_cleared: u32,
+}
+
+impl GameOverSummary {
+    pub fn title(&self) -> &'static str {
+        if self.won {
+            "Clear"
+        } else {
+            "Game Over"
+        }
+    }
+
+    pub fn info(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("score", self.score.to_formatted_string(&Locale::en)),
+            ("level", self.virus_level.to_string()),
+            (
+                "viruses",
+                self.viruses_cleared.to_formatted_string(&Locale::en),
+            ),
+        ]
+    }
+}
+
+/// An option rendered both selected & not, so moving between them doesn't render any text
+struct GameOverOption<'a> {
+    selected: FontTexture<'a>,
+    unselected: FontTexture<'a>,
+}
+
+pub struct GameOverScreen<'a> {
+    summary: AboutScreen<'a>,
+    options: Vec<GameOverOption<'a>>,
+    current: usize,
+    padding: u32,
+    window_size: (u32, u32),
+}
+
+impl<'a> GameOverScreen<'a> {
+    pub fn new(
+        summary: GameOverSummary,
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        window_size: (u32, u32),
+    ) -> Result<Self, String> {
+        let (window_width, window_height) = window_size;
+        let font = FontType::Retro.load(ttf, window_width / 32)?;
+        let options = OPTIONS
+            .iter()
+            .map(|(name, _)| {
+                Ok(GameOverOption {
+                    selected: FontTexture::from_string(
+                        &font,
+                        texture_creator,
+                        name,
+                        SELECTED_COLOR,
+                    )?,
+                    unselected: FontTexture::from_string(
+                        &font,
+                        texture_creator,
+                        name,
+                        UNSELECTED_COLOR,
+                    )?,
+                })
+            })
+            .collect::<Result<Vec<GameOverOption>, String>>()?;
+        Ok(Self {
+            summary: AboutScreen::new(
+                summary.title(),
+                &summary.info(),
+                ttf,
+                texture_creator,
+                window_size,
+            )?,
+            options,
+            current: 0,
+            padding: window_height / 16,
+            window_size,
+        })
+    }
+
+    /// Returns the action when an option is chosen
+    pub fn read_key(&mut self, key: MenuInputKey) -> Option<GameOverAction> {
+        match key {
+            MenuInputKey::Up | MenuInputKey::Left => self.move_current(false),
+            MenuInputKey::Down | MenuInputKey::Right => self.move_current(true),
+            MenuInputKey::Wheel { up } => self.move_current(!up),
+            MenuInputKey::Select | MenuInputKey::Start => return Some(OPTIONS[self.current].1),
+            MenuInputKey::Back | MenuInputKey::Quit => return Some(GameOverAction::Menu),
+            MenuInputKey::Click { .. } => {}
+        }
+        None
+    }
+
+    fn move_current(&mut self, next: bool) {
+        self.current = if next {
+            (self.current + 1).min(OPTIONS.len() - 1)
+        } else {
+            self.current.saturating_sub(1)
+        };
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        self.summary.draw(canvas)?;
+
+        // options side by side along the bottom, the current one highlighted
+        let (window_width, window_height) = self.window_size;
+        let textures = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, option)| {
+                if i == self.current {
+                    &option.selected
+                } else {
+                    &option.unselected
+                }
+            })
+            .collect::<Vec<&FontTexture>>();
+        let width = textures.iter().map(|t| t.width).sum::<u32>()
+            + self.padding * (textures.len() as u32 - 1);
+        let height = textures.iter().map(|t| t.height).max().unwrap_or(0);
+        let mut x = (window_width as i32 - width as i32).max(0) / 2;
+        let y = (window_height - self.padding - height) as i32;
+        for texture in textures {
+            canvas.copy(
+                &texture.texture,
+                None,
+                Rect::new(x, y, texture.width, texture.height),
+            )?;
+            x += (texture.width + self.padding) as i32;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for GameOverScreen<'_> {
+    type Output = GameOverAction;
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<GameOverAction> {
+        match self.read_key(key) {
+            Some(action) => SceneInput::Exit(action),
+            None => SceneInput::Handled,
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        GameOverScreen::draw(self, canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_of_a_cleared_sprint() {
+        let summary = GameOverSummary {
+            won: true,
+            score: 123_400,
+            virus_level: 12,
+            viruses_cleared: 1_024,
+        };
+        assert_eq!(summary.title(), "Clear");
+        assert_eq!(
+            summary.info(),
+            vec![
+                ("score", "123,400".to_string()),
+                ("level", "12".to_string()),
+                ("viruses", "1,024".to_string()),
+            ]
+        );
+    }
+}
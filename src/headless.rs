@@ -0,0 +1,32 @@
+//! Simulates matches from the command line, see `Simulation` in the core crate.
+
+use crate::cli::HeadlessArgs;
+use crate::game::rules::{GameConfig, MatchRules};
+use crate::player::Match;
+use dr_rustario_core::headless::{Simulation, STEP};
+use std::time::Instant;
+
+/// Plays every match to the end, printing a summary of each
+pub fn run(args: HeadlessArgs, modifiers: &[String]) -> Result<(), String> {
+    let mut game_config = GameConfig::default();
+    game_config.set_players(args.players);
+    game_config.set_rules(MatchRules::default_by_players(args.players));
+
+    let started = Instant::now();
+    for index in 0..args.matches {
+        let mut simulation = Simulation::new(Match::new(game_config, modifiers));
+        while !simulation.is_over() {
+            simulation.step(STEP)?;
+        }
+        println!("match {}: {}", index + 1, simulation.summary());
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    println!(
+        "{} matches in {:.2}s, {:.1} matches per second",
+        args.matches,
+        elapsed,
+        args.matches as f64 / elapsed
+    );
+    Ok(())
+}
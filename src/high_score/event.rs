@@ -2,6 +2,9 @@
 pub enum HighScoreEntryEvent {
     CursorRight,
     CursorLeft,
+    CursorUp,
+    CursorDown,
     ChangeChar,
+    DeleteChar,
     Finished,
 }
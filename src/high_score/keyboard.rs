@@ -0,0 +1,153 @@
+/// Rows of characters on the on screen keyboard, followed by a row of the special keys
+const CHARACTER_ROWS: [&str; 4] = ["ABCDEFGHIJ", "KLMNOPQRST", "UVWXYZ.-_!", "0123456789"];
+const SPECIAL_KEYS: [KeyboardKey; 3] = [KeyboardKey::Space, KeyboardKey::Delete, KeyboardKey::End];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyboardKey {
+    Char(char),
+    Space,
+    Delete,
+    End,
+}
+
+impl KeyboardKey {
+    pub fn label(&self) -> String {
+        match self {
+            KeyboardKey::Char(c) => c.to_string(),
+            KeyboardKey::Space => "SPACE".to_string(),
+            KeyboardKey::Delete => "DEL".to_string(),
+            KeyboardKey::End => "END".to_string(),
+        }
+    }
+}
+
+/// Grid of keys navigated with the d-pad, wrapping around at every edge.
+/// Rows may have different numbers of keys, each row is spread across the full width.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnScreenKeyboard {
+    rows: Vec<Vec<KeyboardKey>>,
+    row: usize,
+    column: usize,
+}
+
+impl Default for OnScreenKeyboard {
+    fn default() -> Self {
+        let mut rows = CHARACTER_ROWS
+            .iter()
+            .map(|row| row.chars().map(KeyboardKey::Char).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        rows.push(SPECIAL_KEYS.to_vec());
+        Self {
+            rows,
+            row: 0,
+            column: 0,
+        }
+    }
+}
+
+impl OnScreenKeyboard {
+    pub fn rows(&self) -> &[Vec<KeyboardKey>] {
+        &self.rows
+    }
+
+    /// Row & column of the selected key
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.column)
+    }
+
+    pub fn current(&self) -> KeyboardKey {
+        self.rows[self.row][self.column]
+    }
+
+    pub fn up(&mut self) {
+        let row = (self.row + self.rows.len() - 1) % self.rows.len();
+        self.move_to_row(row);
+    }
+
+    pub fn down(&mut self) {
+        let row = (self.row + 1) % self.rows.len();
+        self.move_to_row(row);
+    }
+
+    pub fn left(&mut self) {
+        let len = self.rows[self.row].len();
+        self.column = (self.column + len - 1) % len;
+    }
+
+    pub fn right(&mut self) {
+        self.column = (self.column + 1) % self.rows[self.row].len();
+    }
+
+    /// Selects the key at a row & column, e.g. under the mouse
+    pub fn move_to(&mut self, (row, column): (usize, usize)) {
+        self.row = row.min(self.rows.len() - 1);
+        self.column = column.min(self.rows[self.row].len() - 1);
+    }
+
+    /// Selects the end key, once there is no more room in the name
+    pub fn select_end(&mut self) {
+        self.row = self.rows.len() - 1;
+        self.column = self.rows[self.row]
+            .iter()
+            .position(|key| *key == KeyboardKey::End)
+            .unwrap();
+    }
+
+    /// Moves to the key in the other row that is closest to the center of the selected key
+    fn move_to_row(&mut self, row: usize) {
+        let from = self.rows[self.row].len();
+        let to = self.rows[row].len();
+        self.column = ((self.column * 2 + 1) * to / (from * 2)).min(to - 1);
+        self.row = row;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_around() {
+        let mut keyboard = OnScreenKeyboard::default();
+        keyboard.left();
+        assert_eq!(keyboard.current(), KeyboardKey::Char('J'));
+        keyboard.right();
+        keyboard.up();
+        assert_eq!(keyboard.current(), KeyboardKey::Space);
+        // the space key is a third of the row wide, B is closest to its center
+        keyboard.down();
+        assert_eq!(keyboard.current(), KeyboardKey::Char('B'));
+    }
+
+    #[test]
+    fn moves_to_closest_key_between_rows() {
+        let mut keyboard = OnScreenKeyboard::default();
+        for _ in 0..3 {
+            keyboard.down();
+        }
+        for _ in 0..5 {
+            keyboard.right();
+        }
+        assert_eq!(keyboard.current(), KeyboardKey::Char('5'));
+        keyboard.down();
+        assert_eq!(keyboard.current(), KeyboardKey::Delete);
+        keyboard.up();
+        assert_eq!(keyboard.current(), KeyboardKey::Char('5'));
+    }
+
+    #[test]
+    fn moves_to_key_within_row() {
+        let mut keyboard = OnScreenKeyboard::default();
+        keyboard.move_to((0, 1));
+        assert_eq!(keyboard.current(), KeyboardKey::Char('B'));
+        keyboard.move_to((99, 99));
+        assert_eq!(keyboard.cursor().0, keyboard.rows().len() - 1);
+    }
+
+    #[test]
+    fn selects_end() {
+        let mut keyboard = OnScreenKeyboard::default();
+        keyboard.select_end();
+        assert_eq!(keyboard.current(), KeyboardKey::End);
+    }
+}
@@ -1,15 +1,6 @@
 pub mod event;
+pub mod keyboard;
+pub mod online;
 pub mod render;
-pub mod table;
+pub use dr_rustario_core::high_score::{daily, days_today, table, today, NewHighScore};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct NewHighScore {
-    pub player: u32,
-    pub score: u32,
-}
-
-impl NewHighScore {
-    pub fn new(player: u32, score: u32) -> Self {
-        Self { player, score }
-    }
-}
@@ -0,0 +1,115 @@
+use crate::config::OnlineConfig;
+use crate::high_score::table::HighScore;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+
+pub const GLOBAL_HIGH_SCORES: usize = 20;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OnlineLeaderboardState {
+    Loading,
+    Loaded(Vec<HighScore>),
+    Offline,
+}
+
+/// Global leaderboard fetched on a background thread so the render loop never blocks on the network
+pub struct OnlineLeaderboard {
+    receiver: Receiver<Result<Vec<HighScore>, String>>,
+    state: OnlineLeaderboardState,
+}
+
+impl OnlineLeaderboard {
+    pub fn fetch(config: &OnlineConfig) -> Self {
+        let (sender, receiver) = channel();
+        let config = config.clone();
+        thread::spawn(move || {
+            // nothing to do if the receiver has gone away
+            let _ = sender.send(client::fetch(&config, GLOBAL_HIGH_SCORES));
+        });
+        Self {
+            receiver,
+            state: OnlineLeaderboardState::Loading,
+        }
+    }
+
+    /// Checks for a response, returns true if the state has changed
+    pub fn poll(&mut self) -> bool {
+        if self.state != OnlineLeaderboardState::Loading {
+            return false;
+        }
+        self.state = match self.receiver.try_recv() {
+            Ok(Ok(scores)) => OnlineLeaderboardState::Loaded(scores),
+            Ok(Err(error)) => {
+                println!("failed to fetch online high scores: {}", error);
+                OnlineLeaderboardState::Offline
+            }
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => OnlineLeaderboardState::Offline,
+        };
+        true
+    }
+
+    pub fn state(&self) -> &OnlineLeaderboardState {
+        &self.state
+    }
+}
+
+/// Submits a new high score in the background, failures are logged and otherwise ignored
+pub fn submit(config: &OnlineConfig, high_score: HighScore) {
+    let config = config.clone();
+    thread::spawn(move || {
+        if let Err(error) = client::submit(&config, &high_score) {
+            println!("failed to submit online high score: {}", error);
+        }
+    });
+}
+
+#[cfg(feature = "online")]
+mod client {
+    use crate::config::OnlineConfig;
+    use crate::high_score::table::HighScore;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn agent() -> ureq::Agent {
+        ureq::AgentBuilder::new().timeout(TIMEOUT).build()
+    }
+
+    pub fn fetch(config: &OnlineConfig, limit: usize) -> Result<Vec<HighScore>, String> {
+        let mut scores: Vec<HighScore> = agent()
+            .get(&config.endpoint)
+            .query("limit", &limit.to_string())
+            .call()
+            .map_err(|e| e.to_string())?
+            .into_json()
+            .map_err(|e| e.to_string())?;
+        scores.sort_by(|x, y| y.score.cmp(&x.score));
+        scores.truncate(limit);
+        Ok(scores)
+    }
+
+    pub fn submit(config: &OnlineConfig, high_score: &HighScore) -> Result<(), String> {
+        agent()
+            .post(&config.endpoint)
+            .send_json(high_score)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "online"))]
+mod client {
+    use crate::config::OnlineConfig;
+    use crate::high_score::table::HighScore;
+
+    const NOT_SUPPORTED: &str = "built without the online feature";
+
+    pub fn fetch(_config: &OnlineConfig, _limit: usize) -> Result<Vec<HighScore>, String> {
+        Err(NOT_SUPPORTED.to_string())
+    }
+
+    pub fn submit(_config: &OnlineConfig, _high_score: &HighScore) -> Result<(), String> {
+        Err(NOT_SUPPORTED.to_string())
+    }
+}
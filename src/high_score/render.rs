@@ -1,19 +1,27 @@
-use crate::high_score::table::{HighScore, HighScoreTable};
+use crate::high_score::table::{HighScore, HighScoreTable, MAX_NAME_LENGTH};
 
 use crate::font::{FontTexture, FontType};
 use crate::high_score::event::HighScoreEntryEvent;
-use crate::high_score::NewHighScore;
+use crate::high_score::keyboard::{KeyboardKey, OnScreenKeyboard};
+use crate::high_score::online::{OnlineLeaderboard, OnlineLeaderboardState, GLOBAL_HIGH_SCORES};
+use crate::high_score::{today, NewHighScore};
+use crate::menu_input::MenuInputKey;
+use crate::scene::{Scene, SceneInput};
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
 use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
 use sdl2::ttf::{Font, Sdl2TtfContext};
 use sdl2::video::WindowContext;
-use std::cmp::min;
+use std::time::Duration;
 use sdl2::pixels::PixelFormatEnum::RGBA8888;
 
-const NAME_CHARACTERS: usize = 5;
 const CARET_HEIGHT: u32 = 2;
 const FONT_COLOR: Color = Color::WHITE;
+const KEY_COLOR: Color = Color::RGB(0x40, 0x40, 0x40);
+const HINT_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+const DETAIL_HEADERS: [&str; 4] = ["Mode", "Vs", "Date", "Profile"];
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+const SPINNER_FRAME_DURATION: Duration = Duration::from_millis(100);
 
 fn char_carets(font: &Font, text: &str) -> Result<Vec<Rect>, String> {
     let mut char_carets = vec![];
@@ -35,6 +43,8 @@ struct HighScoreTableRow<'a> {
     ordinal: FontTexture<'a>,
     name: FontTexture<'a>,
     score: FontTexture<'a>,
+    /// attribution columns of the local table, in the order of DETAIL_HEADERS
+    details: Vec<FontTexture<'a>>,
 }
 
 impl<'a> HighScoreTableRow<'a> {
@@ -49,22 +59,220 @@ impl<'a> HighScoreTableRow<'a> {
             ordinal: FontTexture::from_string(font, texture_creator, ordinal, FONT_COLOR)?,
             name: FontTexture::from_string(font, texture_creator, name, FONT_COLOR)?,
             score: FontTexture::from_string(font, texture_creator, score, FONT_COLOR)?,
+            details: vec![],
         })
     }
 
+    fn with_details<S: AsRef<str>>(
+        mut self,
+        font: &Font,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        details: &[S],
+    ) -> Result<Self, String> {
+        self.details = details
+            .iter()
+            .map(|d| FontTexture::from_string(font, texture_creator, d.as_ref(), FONT_COLOR))
+            .collect::<Result<Vec<FontTexture>, String>>()?;
+        Ok(self)
+    }
+
+    /// Name, score then details
+    fn column(&self, index: usize) -> &FontTexture<'a> {
+        match index {
+            0 => &self.name,
+            1 => &self.score,
+            _ => &self.details[index - 2],
+        }
+    }
+
     fn height(&self) -> u32 {
         self.ordinal
             .height
             .max(self.name.height)
             .max(self.score.height)
+            .max(self.details.iter().map(|d| d.height).max().unwrap_or(0))
+    }
+}
+
+/// Attribution of a high score, "-" where it is not known
+fn details(high_score: &HighScore) -> [String; 4] {
+    let or_dash = |value: Option<&String>| value.cloned().unwrap_or("-".to_string());
+    [
+        or_dash(high_score.rules.as_ref()),
+        if high_score.opponents > 0 {
+            high_score.opponents.to_string()
+        } else {
+            "-".to_string()
+        },
+        or_dash(high_score.date.as_ref()),
+        or_dash(high_score.profile.as_ref()),
+    ]
+}
+
+/// Splits the columns into pages that fit the max width, the ordinal is repeated on every page.
+/// A column that is too wide on its own gets a page to itself.
+fn paginate(
+    ordinal_width: u32,
+    column_widths: &[u32],
+    padding: u32,
+    max_width: u32,
+) -> Vec<Vec<usize>> {
+    let mut pages: Vec<Vec<usize>> = vec![];
+    let mut width = 0;
+    for (index, column_width) in column_widths.iter().enumerate() {
+        match pages.last_mut() {
+            Some(page) if width + padding + column_width <= max_width => {
+                page.push(index);
+                width += padding + column_width;
+            }
+            _ => {
+                pages.push(vec![index]);
+                width = ordinal_width + padding + column_width;
+            }
+        }
+    }
+    pages
+}
+
+enum OnlinePanelState<'a> {
+    Loading {
+        frames: Vec<FontTexture<'a>>,
+        duration: Duration,
+    },
+    Loaded(Vec<HighScoreTableRow<'a>>),
+    Offline(FontTexture<'a>),
+}
+
+/// Global top scores shown alongside the local table
+struct OnlinePanel<'a> {
+    title: FontTexture<'a>,
+    state: OnlinePanelState<'a>,
+}
+
+impl<'a> OnlinePanel<'a> {
+    fn new(
+        font: &Font,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        state: &OnlineLeaderboardState,
+    ) -> Result<Self, String> {
+        let state = match state {
+            OnlineLeaderboardState::Loading => OnlinePanelState::Loading {
+                frames: SPINNER
+                    .iter()
+                    .map(|s| FontTexture::from_string(font, texture_creator, s, FONT_COLOR))
+                    .collect::<Result<Vec<FontTexture>, String>>()?,
+                duration: Duration::ZERO,
+            },
+            OnlineLeaderboardState::Loaded(scores) => OnlinePanelState::Loaded(
+                scores
+                    .iter()
+                    .take(GLOBAL_HIGH_SCORES)
+                    .enumerate()
+                    .map(|(i, row)| {
+                        HighScoreTableRow::new(
+                            font,
+                            texture_creator,
+                            &(i + 1).to_string(),
+                            &row.name,
+                            &row.score.to_string(),
+                        )
+                    })
+                    .collect::<Result<Vec<HighScoreTableRow>, String>>()?,
+            ),
+            OnlineLeaderboardState::Offline => OnlinePanelState::Offline(
+                FontTexture::from_string(font, texture_creator, "offline", FONT_COLOR)?,
+            ),
+        };
+        Ok(Self {
+            title: FontTexture::from_string(font, texture_creator, "Global", FONT_COLOR)?,
+            state,
+        })
+    }
+
+    fn update(&mut self, delta: Duration) {
+        if let OnlinePanelState::Loading { duration, .. } = &mut self.state {
+            *duration += delta;
+        }
+    }
+
+    fn draw(&self, canvas: &mut WindowCanvas, rect: Rect, padding: u32) -> Result<(), String> {
+        let title_rect = Rect::new(
+            rect.center().x() - self.title.width as i32 / 2,
+            rect.y(),
+            self.title.width,
+            self.title.height,
+        );
+        canvas.copy(&self.title.texture, None, title_rect)?;
+        let y = title_rect.bottom() + padding as i32;
+
+        match &self.state {
+            OnlinePanelState::Loading { frames, duration } => {
+                let frame = (duration.as_millis() / SPINNER_FRAME_DURATION.as_millis()) as usize
+                    % frames.len();
+                let texture = &frames[frame];
+                canvas.copy(
+                    &texture.texture,
+                    None,
+                    Rect::new(
+                        rect.center().x() - texture.width as i32 / 2,
+                        y,
+                        texture.width,
+                        texture.height,
+                    ),
+                )
+            }
+            OnlinePanelState::Offline(texture) => canvas.copy(
+                &texture.texture,
+                None,
+                Rect::new(
+                    rect.center().x() - texture.width as i32 / 2,
+                    y,
+                    texture.width,
+                    texture.height,
+                ),
+            ),
+            OnlinePanelState::Loaded(rows) => {
+                let ordinal_width = rows.iter().map(|r| r.ordinal.width).max().unwrap_or(0);
+                let mut y = y;
+                for row in rows.iter() {
+                    canvas.copy(
+                        &row.ordinal.texture,
+                        None,
+                        Rect::new(rect.x(), y, row.ordinal.width, row.ordinal.height),
+                    )?;
+                    canvas.copy(
+                        &row.name.texture,
+                        None,
+                        Rect::new(
+                            rect.x() + (ordinal_width + padding) as i32,
+                            y,
+                            row.name.width,
+                            row.name.height,
+                        ),
+                    )?;
+                    canvas.copy(
+                        &row.score.texture,
+                        None,
+                        Rect::new(
+                            rect.right() - row.score.width as i32,
+                            y,
+                            row.score.width,
+                            row.score.height,
+                        ),
+                    )?;
+                    y += row.height() as i32;
+                }
+                Ok(())
+            }
+        }
     }
 }
 
 struct Entry {
     ordinal: usize,
     high_score: NewHighScore,
-    name: [char; NAME_CHARACTERS],
-    current_char: usize,
+    name: String,
+    keyboard: OnScreenKeyboard,
     char_carets: Vec<Rect>,
 }
 
@@ -73,85 +281,102 @@ impl Entry {
         Ok(Self {
             ordinal,
             high_score,
-            name: [' '; NAME_CHARACTERS],
-            current_char: 0,
-            char_carets: char_carets(font, &" ".repeat(NAME_CHARACTERS))?,
+            name: String::new(),
+            keyboard: OnScreenKeyboard::default(),
+            char_carets: char_carets(font, &" ".repeat(MAX_NAME_LENGTH))?,
         })
     }
 
     fn update_carets(&mut self, font: &Font) -> Result<(), String> {
-        self.char_carets = char_carets(font, &self.name())?;
+        self.char_carets = char_carets(font, &self.padded_name())?;
         Ok(())
     }
 
+    /// Under the next character to be entered or the last once the name is full
     fn current_caret(&self) -> Rect {
-        *self.char_carets.get(self.current_char).unwrap()
+        let index = self.name.chars().count().min(MAX_NAME_LENGTH - 1);
+        *self.char_carets.get(index).unwrap()
     }
 
     fn title_text(&self) -> String {
         format!("New High Score Player {}", self.high_score.player + 1)
     }
 
-    fn name(&self) -> String {
-        self.name.iter().collect::<String>()
+    /// Padded to the max length so the table doesn't change size as the name is entered
+    fn padded_name(&self) -> String {
+        format!("{:<width$}", self.name, width = MAX_NAME_LENGTH)
     }
 
     fn to_high_score(&self) -> Option<HighScore> {
-        let name = self.name().trim().to_string();
+        let name = self.name.trim().to_string();
         if name.is_empty() {
             None
         } else {
-            Some(HighScore::from_string(name, self.high_score.score))
+            Some(
+                HighScore::from_string(name, self.high_score.score)
+                    .with_match(self.high_score, today()),
+            )
         }
     }
 
     fn up(&mut self) -> Option<HighScoreEntryEvent> {
-        self.move_char(-1)
+        self.keyboard.up();
+        Some(HighScoreEntryEvent::CursorUp)
     }
 
     fn down(&mut self) -> Option<HighScoreEntryEvent> {
-        self.move_char(1)
+        self.keyboard.down();
+        Some(HighScoreEntryEvent::CursorDown)
     }
 
     fn left(&mut self) -> Option<HighScoreEntryEvent> {
-        if self.current_char > 0 {
-            self.current_char -= 1;
-            Some(HighScoreEntryEvent::CursorLeft)
-        } else {
-            None
-        }
+        self.keyboard.left();
+        Some(HighScoreEntryEvent::CursorLeft)
     }
 
     fn right(&mut self) -> Option<HighScoreEntryEvent> {
-        self.current_char = min(self.current_char + 1, NAME_CHARACTERS);
-        if self.current_char == NAME_CHARACTERS {
-            Some(HighScoreEntryEvent::Finished)
-        } else {
-            Some(HighScoreEntryEvent::CursorRight)
-        }
+        self.keyboard.right();
+        Some(HighScoreEntryEvent::CursorRight)
     }
 
-    fn move_char(&mut self, value: i32) -> Option<HighScoreEntryEvent> {
-        let current = self.name[self.current_char];
-        let mut next = if current == ' ' {
-            if value > 0 {
-                'A'
-            } else {
-                'Z'
+    /// Presses the selected key of the on screen keyboard
+    fn select(&mut self) -> Option<HighScoreEntryEvent> {
+        let c = match self.keyboard.current() {
+            KeyboardKey::Char(c) => c,
+            KeyboardKey::Space => ' ',
+            KeyboardKey::Delete => {
+                return self.name.pop().map(|_| HighScoreEntryEvent::DeleteChar);
             }
-        } else {
-            char::from_u32((current as i32 + value) as u32).unwrap()
+            KeyboardKey::End => return Some(HighScoreEntryEvent::Finished),
         };
-        if next > 'Z' {
-            next = 'A';
-        } else if next < 'A' {
-            next = 'Z';
+        if self.name.chars().count() >= MAX_NAME_LENGTH {
+            return None;
+        }
+        self.name.push(c);
+        if self.name.chars().count() == MAX_NAME_LENGTH {
+            self.keyboard.select_end();
         }
-        self.name[self.current_char] = next;
         Some(HighScoreEntryEvent::ChangeChar)
     }
 }
 
+/// "< 1/2 >" for each page, none when the table fits on one
+fn page_hints<'a>(
+    font: &Font,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    pages: usize,
+) -> Result<Vec<FontTexture<'a>>, String> {
+    if pages < 2 {
+        return Ok(vec![]);
+    }
+    (1..=pages)
+        .map(|page| {
+            let text = format!("< {}/{} >", page, pages);
+            FontTexture::from_string(font, texture_creator, &text, HINT_COLOR)
+        })
+        .collect()
+}
+
 pub struct HighScoreRender<'a, 'ttf> {
     texture_creator: &'a TextureCreator<WindowContext>,
     rows: Vec<HighScoreTableRow<'a>>,
@@ -160,11 +385,23 @@ pub struct HighScoreRender<'a, 'ttf> {
     title_rect: Rect,
     row_height: u32,
     ordinal_column_width: u32,
+    /// widths of the name, score & detail columns
+    column_widths: Vec<u32>,
+    pages: Vec<Vec<usize>>,
+    page: usize,
+    /// "< 1/2 >" for each page, when there is more than one
+    page_hints: Vec<FontTexture<'a>>,
+    hint_font: Font<'ttf, 'ttf>,
     padding: u32,
-    width: u32,
     rect: Rect,
     entry: Option<Entry>,
+    /// labels of the on screen keyboard by row, only when entering a name
+    keys: Vec<Vec<FontTexture<'a>>>,
+    key_size: (u32, u32),
     font: Font<'ttf, 'ttf>,
+    online_font: Font<'ttf, 'ttf>,
+    online: Option<OnlinePanel<'a>>,
+    window_size: (u32, u32),
 }
 
 /// TODO music
@@ -180,16 +417,19 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
         let font_header = FontType::Bold.load(ttf, font_size)?;
         let font_body = FontType::Mono.load(ttf, font_size)?;
         let font_title = FontType::Retro.load(ttf, window_width / 24)?;
+        let hint_font = FontType::Bold.load(ttf, window_height / 40)?;
+        // small enough to fit the full global table under the title
+        let online_font = FontType::Mono.load(ttf, window_height / (GLOBAL_HIGH_SCORES as u32 + 8))?;
 
         let (table, entry) = if let Some(new_high_score) = new_high_score {
             let score_index = table
                 .try_get_score_index(new_high_score.score)
                 .expect("not a high score");
             let mut new_table = table;
-            new_table.add_high_score(HighScore::new(
-                &" ".repeat(NAME_CHARACTERS),
-                new_high_score.score,
-            ));
+            new_table.add_high_score(
+                HighScore::new(&" ".repeat(MAX_NAME_LENGTH), new_high_score.score)
+                    .with_match(new_high_score, today()),
+            );
             (
                 new_table,
                 Some(Entry::new(score_index, new_high_score, &font_body)?),
@@ -204,15 +444,19 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
             "#",
             "Name",
             "Score",
-        )?];
+        )?
+        .with_details(&font_header, texture_creator, &DETAIL_HEADERS)?];
         for (i, row) in table.entries().iter().enumerate() {
-            rows.push(HighScoreTableRow::new(
-                &font_body,
-                texture_creator,
-                &(i + 1).to_string(),
-                &row.name,
-                &row.score.to_string(),
-            )?);
+            rows.push(
+                HighScoreTableRow::new(
+                    &font_body,
+                    texture_creator,
+                    &(i + 1).to_string(),
+                    &row.name,
+                    &row.score.to_string(),
+                )?
+                .with_details(&font_body, texture_creator, &details(row))?,
+            );
         }
 
         let n_rows = rows.len() as u32;
@@ -221,11 +465,19 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
         }
 
         let ordinal_column_width = rows.iter().map(|x| x.ordinal.width).max().unwrap();
-        let name_column_width = rows.iter().map(|x| x.name.width).max().unwrap();
-        let score_column_width = rows.iter().map(|x| x.score.width).max().unwrap();
+        let column_widths = (0..DETAIL_HEADERS.len() + 2)
+            .map(|column| rows.iter().map(|x| x.column(column).width).max().unwrap())
+            .collect::<Vec<u32>>();
         let padding = font_size / 2;
-        let width =
-            ordinal_column_width + padding + name_column_width + padding + score_column_width;
+        let pages = paginate(
+            ordinal_column_width,
+            &column_widths,
+            padding,
+            window_width - padding * 2,
+        );
+        // sized for the widest page, narrower pages are copied from the left of the texture
+        let width = ordinal_column_width
+            + column_widths.iter().map(|w| w + padding).sum::<u32>();
         // all rows will be same height as the tallest row
         let row_height = rows.iter().map(|r| r.height()).max().unwrap();
         let height = n_rows * row_height + (n_rows - 1) * padding;
@@ -233,11 +485,6 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
             .create_texture_target(RGBA8888, width, height)
             .map_err(|e| e.to_string())?;
         texture.set_blend_mode(BlendMode::Blend);
-        let rect = Rect::from_center(
-            Point::new(window_width as i32 / 2, window_height as i32 / 2),
-            width,
-            height,
-        );
 
         let title_text = entry
             .as_ref()
@@ -252,6 +499,53 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
             title.height,
         );
 
+        let keys = if entry.is_some() {
+            OnScreenKeyboard::default()
+                .rows()
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|key| {
+                            FontTexture::from_string(
+                                &font_body,
+                                texture_creator,
+                                &key.label(),
+                                FONT_COLOR,
+                            )
+                        })
+                        .collect::<Result<Vec<FontTexture>, String>>()
+                })
+                .collect::<Result<Vec<_>, String>>()?
+        } else {
+            vec![]
+        };
+        // keys are sized to fit the widest label of the rows with the most keys
+        let columns = keys.iter().map(|row| row.len()).max().unwrap_or(0);
+        let key_width = keys
+            .iter()
+            .filter(|row| row.len() == columns)
+            .flatten()
+            .map(|key| key.width)
+            .max()
+            .unwrap_or(0);
+        let key_size = (key_width + padding, row_height + padding / 2);
+
+        let rect = if entry.is_some() {
+            // the table is under the title to make room for the keyboard under it
+            Rect::new(
+                window_width as i32 / 2 - width as i32 / 2,
+                title_rect.bottom() + padding as i32,
+                width,
+                height,
+            )
+        } else {
+            Rect::from_center(
+                Point::new(window_width as i32 / 2, window_height as i32 / 2),
+                width,
+                height,
+            )
+        };
+
         Ok(Self {
             texture_creator,
             rows,
@@ -259,15 +553,85 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
             title_texture: title.texture,
             title_rect,
             row_height,
-            width,
             padding,
             ordinal_column_width,
+            column_widths,
+            page_hints: page_hints(&hint_font, texture_creator, pages.len())?,
+            pages,
+            page: 0,
+            hint_font,
             rect,
             entry,
+            keys,
+            key_size,
             font: font_body,
+            online_font,
+            online: None,
+            window_size: (window_width, window_height),
         })
     }
 
+    /// Shows the global leaderboard alongside the local table
+    pub fn set_online_state(&mut self, state: &OnlineLeaderboardState) -> Result<(), String> {
+        let (window_width, _) = self.window_size;
+        if self.online.is_none() {
+            // make room on the right for the global table
+            self.rect = Rect::new(
+                (window_width / 3) as i32 - self.rect.width() as i32 / 2,
+                self.rect.y(),
+                self.rect.width(),
+                self.rect.height(),
+            );
+        }
+        self.online = Some(OnlinePanel::new(
+            &self.online_font,
+            self.texture_creator,
+            state,
+        )?);
+        // the table must fit to the left of the global table
+        self.pages = paginate(
+            self.ordinal_column_width,
+            &self.column_widths,
+            self.padding,
+            window_width * 2 / 5,
+        );
+        self.page = self.page.min(self.pages.len() - 1);
+        self.page_hints = page_hints(&self.hint_font, self.texture_creator, self.pages.len())?;
+        Ok(())
+    }
+
+    /// Pages through the columns when they don't all fit, returns true if the page changed
+    pub fn next_page(&mut self) -> bool {
+        self.turn_page(self.page + 1)
+    }
+
+    pub fn previous_page(&mut self) -> bool {
+        self.turn_page(self.page + self.pages.len() - 1)
+    }
+
+    fn turn_page(&mut self, page: usize) -> bool {
+        // the name being entered is on the first page
+        if self.entry.is_some() || self.pages.len() < 2 {
+            return false;
+        }
+        self.page = page % self.pages.len();
+        true
+    }
+
+    fn page_width(&self) -> u32 {
+        self.ordinal_column_width
+            + self.pages[self.page]
+                .iter()
+                .map(|&column| self.padding + self.column_widths[column])
+                .sum::<u32>()
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        if let Some(online) = self.online.as_mut() {
+            online.update(delta);
+        }
+    }
+
     pub fn up(&mut self) -> Option<HighScoreEntryEvent> {
         self.update_entry_texture(|e| e.up())
     }
@@ -284,6 +648,30 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
         self.update_entry_texture(|e| e.right())
     }
 
+    pub fn select(&mut self) -> Option<HighScoreEntryEvent> {
+        self.update_entry_texture(|e| e.select())
+    }
+
+    /// Moves the keyboard cursor to the key under the mouse, returns true if it moved
+    pub fn hover(&mut self, point: Point) -> bool {
+        let Some(cursor) = self.key_at(point) else {
+            return false;
+        };
+        let keyboard = &mut self.entry.as_mut().expect("no new high score").keyboard;
+        let moved = keyboard.cursor() != cursor;
+        keyboard.move_to(cursor);
+        moved
+    }
+
+    /// Presses the key under the mouse
+    pub fn click(&mut self, point: Point) -> Option<HighScoreEntryEvent> {
+        let cursor = self.key_at(point)?;
+        self.update_entry_texture(|e| {
+            e.keyboard.move_to(cursor);
+            e.select()
+        })
+    }
+
     pub fn new_entry(&self) -> Option<HighScore> {
         self.entry
             .as_ref()
@@ -302,7 +690,7 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
             row.name = FontTexture::from_string(
                 &self.font,
                 self.texture_creator,
-                &entry.name(),
+                &entry.padded_name(),
                 FONT_COLOR,
             )
             .unwrap();
@@ -311,7 +699,65 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
         result
     }
 
+    /// Cells of the on screen keyboard centered under the table by row & column
+    fn key_cells(&self) -> Vec<((usize, usize), Rect)> {
+        let (key_width, key_height) = self.key_size;
+        let columns = self.keys.iter().map(|row| row.len()).max().unwrap_or(0) as u32;
+        let width = key_width * columns;
+        let x = self.rect.center().x() - width as i32 / 2;
+        let mut y = self.rect.bottom() + self.padding as i32;
+        let mut cells = vec![];
+        for (i, row) in self.keys.iter().enumerate() {
+            // each row is spread across the full width, so fewer keys are wider
+            let cell_width = width / row.len() as u32;
+            for j in 0..row.len() {
+                let cell = Rect::new(x + (j as u32 * cell_width) as i32, y, cell_width, key_height);
+                cells.push(((i, j), cell));
+            }
+            y += key_height as i32;
+        }
+        cells
+    }
+
+    /// Row & column of the key under a point of the window, only when entering a name
+    fn key_at(&self, point: Point) -> Option<(usize, usize)> {
+        self.entry.as_ref()?;
+        self.key_cells()
+            .into_iter()
+            .find(|(_, cell)| cell.contains_point(point))
+            .map(|(cursor, _)| cursor)
+    }
+
+    /// Keys of the on screen keyboard, with the selected key highlighted
+    fn draw_keyboard(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let entry = match self.entry.as_ref() {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+        for ((i, j), cell) in self.key_cells() {
+            let key = &self.keys[i][j];
+            canvas.set_draw_color(if entry.keyboard.cursor() == (i, j) {
+                Color::RED
+            } else {
+                KEY_COLOR
+            });
+            canvas.fill_rect(Rect::new(
+                cell.x() + 1,
+                cell.y() + 1,
+                cell.width() - 2,
+                cell.height() - 2,
+            ))?;
+            canvas.copy(
+                &key.texture,
+                None,
+                Rect::from_center(cell.center(), key.width, key.height),
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let page = &self.pages[self.page];
         canvas
             .with_texture_canvas(&mut self.texture, |c| {
                 c.set_draw_color(Color::RGBA(0, 0, 0, 0));
@@ -326,44 +772,196 @@ impl<'a, 'ttf> HighScoreRender<'a, 'ttf> {
                     )
                     .unwrap();
 
-                    let name_rect = Rect::new(
-                        (self.ordinal_column_width + self.padding) as i32,
-                        y,
-                        row.name.width,
-                        row.name.height,
-                    );
-                    c.copy(&row.name.texture, None, name_rect).unwrap();
-
-                    if let Some(entry) = self.entry.as_ref() {
-                        if entry.ordinal + 1 == i {
-                            c.set_draw_color(Color::RED);
-                            let caret = entry.current_caret();
-                            c.fill_rect(Rect::new(
-                                name_rect.x() + caret.x(),
-                                name_rect.y() + caret.y(),
-                                caret.width(),
-                                caret.height(),
-                            ))
-                            .unwrap();
+                    let mut x = self.ordinal_column_width as i32;
+                    for &column in page.iter() {
+                        x += self.padding as i32;
+                        let cell = row.column(column);
+                        let column_width = self.column_widths[column];
+                        // scores are right aligned, everything else left
+                        let cell_x = if column == 1 {
+                            x + (column_width - cell.width) as i32
+                        } else {
+                            x
+                        };
+                        let cell_rect = Rect::new(cell_x, y, cell.width, cell.height);
+                        c.copy(&cell.texture, None, cell_rect).unwrap();
+
+                        if let Some(entry) = self.entry.as_ref() {
+                            if column == 0 && entry.ordinal + 1 == i {
+                                c.set_draw_color(Color::RED);
+                                let caret = entry.current_caret();
+                                c.fill_rect(Rect::new(
+                                    cell_rect.x() + caret.x(),
+                                    cell_rect.y() + caret.y(),
+                                    caret.width(),
+                                    caret.height(),
+                                ))
+                                .unwrap();
+                            }
                         }
+                        x += column_width as i32;
                     }
-
-                    c.copy(
-                        &row.score.texture,
-                        None,
-                        Rect::new(
-                            self.width as i32 - row.score.width as i32,
-                            y,
-                            row.score.width,
-                            row.score.height,
-                        ),
-                    )
-                    .unwrap();
                     y += self.row_height as i32;
                 }
             })
             .map_err(|e| e.to_string())?;
-        canvas.copy(&self.texture, None, self.rect)?;
-        canvas.copy(&self.title_texture, None, self.title_rect)
+
+        // the page is drawn from the left of the texture, centered where the full table would be
+        let page_width = self.page_width();
+        let height = self.rect.height();
+        canvas.copy(
+            &self.texture,
+            Rect::new(0, 0, page_width, height),
+            Rect::new(
+                self.rect.center().x() - page_width as i32 / 2,
+                self.rect.y(),
+                page_width,
+                height,
+            ),
+        )?;
+        canvas.copy(&self.title_texture, None, self.title_rect)?;
+        self.draw_keyboard(canvas)?;
+
+        if let Some(hint) = self.page_hints.get(self.page).filter(|_| self.entry.is_none()) {
+            canvas.copy(
+                &hint.texture,
+                None,
+                Rect::new(
+                    self.rect.center().x() - hint.width as i32 / 2,
+                    self.rect.bottom() + self.padding as i32,
+                    hint.width,
+                    hint.height,
+                ),
+            )?;
+        }
+
+        if let Some(online) = self.online.as_ref() {
+            let (window_width, window_height) = self.window_size;
+            let width = window_width / 4;
+            let y = self.title_rect.bottom() + self.padding as i32;
+            let rect = Rect::new(
+                (window_width * 2 / 3) as i32 - width as i32 / 2,
+                y,
+                width,
+                window_height - y as u32,
+            );
+            online.draw(canvas, rect, self.padding)?;
+        }
+        Ok(())
+    }
+}
+
+/// The table paged through with left & right, any other key leaves. The global table is shown
+/// once it has been fetched
+pub struct HighScoreView<'a, 'ttf> {
+    table: HighScoreRender<'a, 'ttf>,
+    online: Option<OnlineLeaderboard>,
+}
+
+impl<'a, 'ttf> HighScoreView<'a, 'ttf> {
+    pub fn new(
+        mut table: HighScoreRender<'a, 'ttf>,
+        online: Option<OnlineLeaderboard>,
+    ) -> Result<Self, String> {
+        if let Some(online) = online.as_ref() {
+            table.set_online_state(online.state())?;
+        }
+        Ok(Self { table, online })
+    }
+}
+
+impl Scene for HighScoreView<'_, '_> {
+    type Output = ();
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<()> {
+        let turned = match key {
+            MenuInputKey::Left | MenuInputKey::Wheel { up: true } => self.table.previous_page(),
+            MenuInputKey::Right | MenuInputKey::Wheel { up: false } => self.table.next_page(),
+            _ => false,
+        };
+        if turned {
+            SceneInput::Handled
+        } else {
+            // any other button press
+            SceneInput::Exit(())
+        }
+    }
+
+    fn update(&mut self, delta: Duration) {
+        if let Some(online) = self.online.as_mut() {
+            if online.poll() {
+                if let Err(error) = self.table.set_online_state(online.state()) {
+                    println!("failed to show online high scores: {}", error);
+                }
+            }
+        }
+        self.table.update(delta);
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        self.table.draw(canvas)
+    }
+}
+
+/// Name entry of a new high score on the table, exits with true if the entry is to be kept
+pub struct HighScoreEntry<'a, 'ttf> {
+    table: HighScoreRender<'a, 'ttf>,
+}
+
+impl<'a, 'ttf> HighScoreEntry<'a, 'ttf> {
+    pub fn new(table: HighScoreRender<'a, 'ttf>) -> Self {
+        Self { table }
+    }
+
+    pub fn new_entry(&self) -> Option<HighScore> {
+        self.table.new_entry()
+    }
+}
+
+impl Scene for HighScoreEntry<'_, '_> {
+    type Output = bool;
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<bool> {
+        let event = match key {
+            MenuInputKey::Up => self.table.up(),
+            MenuInputKey::Down => self.table.down(),
+            MenuInputKey::Left => self.table.left(),
+            MenuInputKey::Right => self.table.right(),
+            MenuInputKey::Select => self.table.select(),
+            MenuInputKey::Click { x, y } => self.table.click(Point::new(x, y)),
+            MenuInputKey::Start => return SceneInput::Exit(true),
+            MenuInputKey::Back => return SceneInput::Exit(false),
+            _ => None,
+        };
+        match event {
+            None => SceneInput::Ignored,
+            Some(HighScoreEntryEvent::Finished) => SceneInput::Exit(true),
+            Some(_) => SceneInput::Handled,
+        }
+    }
+
+    fn hover(&mut self, point: Point) {
+        self.table.hover(point);
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        self.table.draw(canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paginates_columns_to_fit() {
+        let widths = [60, 40, 80, 20, 50];
+        // ordinal 10 & padding 5, so a page of all columns is 10 + 5 * 5 + 250 = 285 wide
+        assert_eq!(paginate(10, &widths, 5, 285), vec![vec![0, 1, 2, 3, 4]]);
+        assert_eq!(paginate(10, &widths, 5, 205), vec![vec![0, 1, 2], vec![3, 4]]);
+        assert_eq!(
+            paginate(10, &widths, 5, 50),
+            vec![vec![0], vec![1], vec![2], vec![3], vec![4]]
+        );
     }
 }
@@ -0,0 +1,113 @@
+//! Average time from the press of a drop key to the lock of the pill it dropped, for the debug
+//! overlay. It is the latency as it is perceived: the wait for the next frame, the steps of the
+//! game before the key is applied & the hard drop animation are all included.
+
+use crate::game::event::GameEvent;
+use crate::game_input::GameInputKey;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The average is of this many of the latest locks
+const SAMPLES: usize = 16;
+
+#[derive(Debug, Default)]
+pub struct InputLatency {
+    /// when each player first pressed a drop key for their current pill
+    pressed: HashMap<u32, Instant>,
+    samples: VecDeque<Duration>,
+}
+
+impl InputLatency {
+    /// A key applied to the pill of a player, pressed this long ago. A held soft drop is sent
+    /// every frame, only the first press counts until the pill locks
+    pub fn press(&mut self, key: GameInputKey, age: Duration) {
+        if let GameInputKey::SoftDrop { player } | GameInputKey::HardDrop { player } = key {
+            let now = Instant::now();
+            self.pressed
+                .entry(player)
+                .or_insert(now.checked_sub(age).unwrap_or(now));
+        }
+    }
+
+    pub fn receive_event(&mut self, event: &GameEvent) {
+        if let GameEvent::Lock {
+            player,
+            hard_or_soft_dropped,
+            ..
+        } = event
+        {
+            // a pill that fell into place is not down to a key
+            if let Some(pressed) = self
+                .pressed
+                .remove(player)
+                .filter(|_| *hard_or_soft_dropped)
+            {
+                if self.samples.len() >= SAMPLES {
+                    self.samples.pop_front();
+                }
+                self.samples.push_back(pressed.elapsed());
+            }
+        }
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<Duration>() / self.samples.len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::pill::{Pill, PillShape};
+
+    #[test]
+    fn averages_drops_to_lock() {
+        let lock = |hard_or_soft_dropped| GameEvent::Lock {
+            player: 0,
+            vitamins: Pill::new(PillShape::RB).vitamins(),
+            hard_or_soft_dropped,
+            drop_score: 0,
+        };
+        let mut latency = InputLatency::default();
+        latency.receive_event(&lock(true));
+        assert_eq!(latency.average(), None);
+
+        latency.press(GameInputKey::MoveLeft { player: 0 }, Duration::from_secs(1));
+        latency.press(
+            GameInputKey::HardDrop { player: 0 },
+            Duration::from_millis(100),
+        );
+        latency.receive_event(&lock(true));
+        let average = latency.average().unwrap();
+        assert!(average >= Duration::from_millis(100) && average < Duration::from_secs(1));
+
+        latency.press(
+            GameInputKey::SoftDrop { player: 0 },
+            Duration::from_secs(10),
+        );
+        latency.receive_event(&lock(false));
+        assert_eq!(latency.average(), Some(average));
+    }
+
+    #[test]
+    fn held_soft_drop_counts_from_first_press() {
+        let mut latency = InputLatency::default();
+        latency.press(
+            GameInputKey::SoftDrop { player: 0 },
+            Duration::from_millis(500),
+        );
+        for _ in 0..10 {
+            latency.press(GameInputKey::SoftDrop { player: 0 }, Duration::ZERO);
+        }
+        latency.receive_event(&GameEvent::Lock {
+            player: 0,
+            vitamins: Pill::new(PillShape::RB).vitamins(),
+            hard_or_soft_dropped: true,
+            drop_score: 0,
+        });
+        assert!(latency.average().unwrap() >= Duration::from_millis(500));
+    }
+}
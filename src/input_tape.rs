@@ -0,0 +1,262 @@
+//! Frame accurate recordings of parsed game input, for regression testing & tool assisted runs
+
+use crate::game::seed::{decode, encode};
+use crate::game_input::{GameInputKey, TimedKey};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+const HEADER: &str =
+    "# dr-rustario input tape: <frame> <delta nanoseconds> [<key>[@<offset nanoseconds>]...]";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputTapeMode {
+    /// Record every game to the file, overwriting the previous game
+    Record(PathBuf),
+    /// Feed the file back in place of the event pump
+    Play(PathBuf),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TapeFrame {
+    pub delta: Duration,
+    pub keys: Vec<TimedKey>,
+}
+
+/// Every frame of a single game, the seed makes the viruses & pills repeat on playback
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputTape {
+    seed: u32,
+    frames: VecDeque<TapeFrame>,
+    playing: bool,
+}
+
+impl InputTape {
+    pub fn record(seed: u32) -> Self {
+        Self {
+            seed,
+            frames: VecDeque::new(),
+            playing: false,
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut tape = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?
+            .parse::<Self>()?;
+        tape.playing = true;
+        Ok(tape)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string())
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn push(&mut self, delta: Duration, keys: &[TimedKey]) {
+        self.frames.push_back(TapeFrame {
+            delta,
+            keys: keys.to_vec(),
+        });
+    }
+
+    pub fn next_frame(&mut self) -> Option<TapeFrame> {
+        self.frames.pop_front()
+    }
+}
+
+impl Display for InputTape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "seed {}", encode(self.seed))?;
+        for (index, frame) in self.frames.iter().enumerate() {
+            write!(f, "{} {}", index, frame.delta.as_nanos())?;
+            for key in frame.keys.iter() {
+                write!(f, " {}", key_name(key.key))?;
+                if !key.offset.is_zero() {
+                    write!(f, "@{}", key.offset.as_nanos())?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InputTape {
+    type Err = String;
+
+    /// Frame numbers are for reading only, so frames can be inserted or removed by hand
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut seed = None;
+        let mut frames = VecDeque::new();
+        for (line_number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", line_number + 1, message);
+            let mut tokens = line.split_whitespace();
+            let first = tokens.next().unwrap();
+            if first == "seed" {
+                let code = tokens.next().ok_or_else(|| error("missing seed".to_string()))?;
+                seed = Some(decode(code).map_err(error)?);
+                continue;
+            }
+            first
+                .parse::<u64>()
+                .map_err(|_| error(format!("invalid frame: {}", first)))?;
+            let delta = tokens
+                .next()
+                .and_then(|t| t.parse::<u64>().ok())
+                .map(Duration::from_nanos)
+                .ok_or_else(|| error("missing or invalid delta".to_string()))?;
+            let keys = tokens
+                .map(|t| parse_timed_key(t).map_err(error))
+                .collect::<Result<Vec<TimedKey>, String>>()?;
+            frames.push_back(TapeFrame { delta, keys });
+        }
+        Ok(Self {
+            seed: seed.ok_or_else(|| "input tape has no seed".to_string())?,
+            frames,
+            playing: false,
+        })
+    }
+}
+
+fn key_name(key: GameInputKey) -> String {
+    let (name, index) = match key {
+        GameInputKey::MoveLeft { player } => ("left", Some(player)),
+        GameInputKey::MoveRight { player } => ("right", Some(player)),
+        GameInputKey::SoftDrop { player } => ("soft_drop", Some(player)),
+        GameInputKey::HardDrop { player } => ("hard_drop", Some(player)),
+        GameInputKey::HardDropPreview { player } => ("hard_drop_preview", Some(player)),
+        GameInputKey::RotateClockwise { player } => ("rotate_clockwise", Some(player)),
+        GameInputKey::RotateAnticlockwise { player } => ("rotate_anticlockwise", Some(player)),
+        GameInputKey::Hold { player } => ("hold", Some(player)),
+        GameInputKey::SpeedUp { player } => ("speed_up", Some(player)),
+        GameInputKey::Pause => ("pause", None),
+        GameInputKey::ReturnToMenu => ("return_to_menu", None),
+        GameInputKey::Quit => ("quit", None),
+        GameInputKey::NextTheme => ("next_theme", None),
+        GameInputKey::Rewind => ("rewind", None),
+        GameInputKey::SaveState { slot } => ("save_state", Some(slot as u32)),
+        GameInputKey::LoadState { slot } => ("load_state", Some(slot as u32)),
+        GameInputKey::ToggleCrtFilter => ("crt_filter", None),
+        GameInputKey::ToggleMute => ("mute", None),
+        GameInputKey::Restart => ("restart", None),
+        GameInputKey::ToggleDebugOverlay => ("debug_overlay", None),
+        GameInputKey::Screenshot => ("screenshot", None),
+        GameInputKey::RecordGif => ("record_gif", None),
+    };
+    match index {
+        // players & slots are 1-indexed, like the config
+        Some(index) => format!("{}:{}", name, index + 1),
+        None => name.to_string(),
+    }
+}
+
+/// Keys pressed part way through a frame have their offset into it, keys without are at the start
+fn parse_timed_key(value: &str) -> Result<TimedKey, String> {
+    match value.split_once('@') {
+        Some((key, offset)) => Ok(TimedKey {
+            key: parse_key(key)?,
+            offset: offset
+                .parse::<u64>()
+                .map(Duration::from_nanos)
+                .map_err(|_| format!("invalid offset: {}", value))?,
+        }),
+        None => parse_key(value).map(TimedKey::new),
+    }
+}
+
+fn parse_key(value: &str) -> Result<GameInputKey, String> {
+    let (name, index) = match value.split_once(':') {
+        Some((name, index)) => {
+            let index = index
+                .parse::<u32>()
+                .ok()
+                .filter(|i| *i > 0)
+                .ok_or_else(|| format!("invalid player or slot: {}", value))?;
+            (name, Some(index - 1))
+        }
+        None => (value, None),
+    };
+    let key = match (name, index) {
+        ("left", Some(player)) => GameInputKey::MoveLeft { player },
+        ("right", Some(player)) => GameInputKey::MoveRight { player },
+        ("soft_drop", Some(player)) => GameInputKey::SoftDrop { player },
+        ("hard_drop", Some(player)) => GameInputKey::HardDrop { player },
+        ("hard_drop_preview", Some(player)) => GameInputKey::HardDropPreview { player },
+        ("rotate_clockwise", Some(player)) => GameInputKey::RotateClockwise { player },
+        ("rotate_anticlockwise", Some(player)) => GameInputKey::RotateAnticlockwise { player },
+        ("hold", Some(player)) => GameInputKey::Hold { player },
+        ("speed_up", Some(player)) => GameInputKey::SpeedUp { player },
+        ("pause", None) => GameInputKey::Pause,
+        ("return_to_menu", None) => GameInputKey::ReturnToMenu,
+        ("quit", None) => GameInputKey::Quit,
+        ("next_theme", None) => GameInputKey::NextTheme,
+        ("rewind", None) => GameInputKey::Rewind,
+        ("crt_filter", None) => GameInputKey::ToggleCrtFilter,
+        ("mute", None) => GameInputKey::ToggleMute,
+        ("restart", None) => GameInputKey::Restart,
+        ("debug_overlay", None) => GameInputKey::ToggleDebugOverlay,
+        ("screenshot", None) => GameInputKey::Screenshot,
+        ("record_gif", None) => GameInputKey::RecordGif,
+        ("save_state", Some(slot)) => GameInputKey::SaveState {
+            slot: slot as usize,
+        },
+        ("load_state", Some(slot)) => GameInputKey::LoadState {
+            slot: slot as usize,
+        },
+        _ => return Err(format!("invalid key: {}", value)),
+    };
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut tape = InputTape::record(12345);
+        tape.push(Duration::from_nanos(16_666_667), &[]);
+        tape.push(
+            Duration::from_nanos(16_700_000),
+            &[
+                TimedKey::new(GameInputKey::MoveLeft { player: 0 }),
+                TimedKey {
+                    key: GameInputKey::Hold { player: 1 },
+                    offset: Duration::from_nanos(8_000_000),
+                },
+                TimedKey::new(GameInputKey::Pause),
+                TimedKey::new(GameInputKey::LoadState { slot: 2 }),
+            ],
+        );
+        let parsed = tape.to_string().parse::<InputTape>().unwrap();
+        assert_eq!(parsed, tape);
+    }
+
+    #[test]
+    fn rejects_invalid_tapes() {
+        assert!("0 100".parse::<InputTape>().is_err());
+        assert!("seed 000001\n0 100 left".parse::<InputTape>().is_err());
+        assert!("seed 000001\n0 100 left:0".parse::<InputTape>().is_err());
+        assert!("seed 000001\n0 100 pause:1".parse::<InputTape>().is_err());
+        assert!("seed 000001\n0 x".parse::<InputTape>().is_err());
+        assert!("seed 000001\n0 100 left:1@x".parse::<InputTape>().is_err());
+    }
+}
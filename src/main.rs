@@ -1,41 +1,92 @@
 #![windows_subsystem = "windows"]
 
+use crate::about::{about_info, AboutScreen, DEFAULT_PROFILE};
 use crate::animate::event::{AnimationEvent, AnimationType};
-use crate::config::{Config, VideoMode};
-use crate::frame_rate::FrameRate;
+use crate::achievements::toast::AchievementToasts;
+use crate::achievements::{AchievementStore, Achievements};
+use crate::capture::Capture;
+use crate::cheat::{CheatListener, CheatTable, Cheats};
+use crate::cli::Args;
+use crate::config::{Config, InputConfig, VideoMode};
+use crate::crt::CrtFilter;
+use crate::cue::CueToasts;
+use crate::daily::daily_info;
+use crate::editor::{bottle_shape, load_layout, save_layout, EditorAction, EditorScreen};
+use crate::event_bus::{EventBus, EventContext};
+use crate::font::{FontTexture, FontType};
+use crate::frame_rate::{FixedTimestep, FrameRate};
 use crate::game::event::GameEvent;
-use crate::game::random::RandomMode;
-use crate::game::rules::{GameConfig, MatchRules, MatchThemes};
+use crate::game::modifier::modifiers_by_name;
+use crate::game::random::{
+    daily_seed, BottleSeed, RandomMode, MAX_VIRUSES, MIN_VIRUSES, PEEK_SIZE,
+};
+use crate::game::rules::{
+    BottleShape, GameConfig, GarbageStyle, MatchRules, MatchThemes, MusicSelect, ThemeOrder,
+    MAX_SERIES_WINS, MAX_VIRUS_LEVEL,
+};
+use crate::game::ruleset::{Features, RuleSet};
+use crate::game::seed;
+use crate::game_over::{GameOverAction, GameOverScreen, GameOverSummary};
 use crate::game::GameSpeed;
-use crate::game_input::{GameInputContext, GameInputKey};
-use crate::high_score::event::HighScoreEntryEvent;
-use crate::high_score::render::HighScoreRender;
+use crate::game_input::{GameInputContext, GameInputKey, TimedKey};
+use crate::high_score::daily::DailyTable;
+use crate::high_score::days_today;
+use crate::high_score::online::{self, OnlineLeaderboard};
+use crate::high_score::render::{HighScoreEntry, HighScoreRender, HighScoreView};
 use crate::high_score::table::HighScoreTable;
 use crate::high_score::NewHighScore;
 use crate::icon::app_icon;
+use crate::input_latency::InputLatency;
+use crate::input_tape::{InputTape, InputTapeMode};
+use crate::manual::ManualScreen;
 use crate::menu::sound::MenuSound;
-use crate::menu::{Menu, MenuItem};
+use crate::menu::title::TitleScene;
+use crate::menu::{Menu, MenuItem, MenuStyle};
 use crate::menu_input::{MenuInputContext, MenuInputKey};
+use crate::overtime::OvertimeBanner;
 use crate::particles::prescribed::{
-    prescribed_fireworks, prescribed_orbit, prescribed_vitamin_race, PlayerTargetedParticles,
+    prescribed_falling_pills, prescribed_fireworks, prescribed_vitamin_race,
+    PlayerTargetedParticles,
 };
 use crate::particles::render::ParticleRender;
 use crate::particles::source::ParticleSource;
 use crate::particles::Particles;
 use crate::player::{Match, MatchState};
+use crate::profiling::{profile_scope, Profiler};
+use crate::scale::Scale;
+use crate::profile::{Profile, Profiles};
+use crate::profile_entry::{ProfileEntryAction, ProfileEntryScreen};
+use crate::restart::RestartPrompt;
+use crate::rumble::Rumble;
+use crate::scene::{Scene, SceneInput};
+use crate::seed_entry::{SeedEntryAction, SeedEntryScreen};
+use crate::stats::stats_info;
+use crate::telemetry::TelemetrySink;
 use crate::theme::all::{AllThemeMeta, AllThemes};
-use crate::theme::pause::PausedScreen;
+use crate::theme::level_stats::LevelStatsScreen;
+use crate::theme::pause::{PauseAction, PausedScreen};
+use crate::theme::sound::{pan_player_channels, MusicDucking};
 use crate::themes::{PlayerTextures, TextureMode, ThemeContext};
+use crate::window_events::WindowEvents;
+use dr_rustario_core::demo::DemoPlayer;
+use dr_rustario_core::session::Session;
+use dr_rustario_core::stats::PlayerStats;
+use dr_rustario_core::{game, player, profile};
+use sdl2::event::{Event, WindowEvent};
 use sdl2::image::{InitFlag as ImageInitFlag, Sdl2ImageContext};
 use sdl2::mixer::{InitFlag as MixerInitFlag, DEFAULT_CHANNELS, DEFAULT_FORMAT};
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, WindowCanvas};
+use sdl2::render::{Texture, TextureCreator, WindowCanvas};
 use sdl2::sys::mixer::MIX_CHANNELS;
 use sdl2::ttf::Sdl2TtfContext;
-use sdl2::{AudioSubsystem, EventPump, Sdl};
+use sdl2::video::{FullscreenType, WindowContext, WindowPos};
+use sdl2::{AudioSubsystem, Sdl};
 use std::str::FromStr;
+use std::time::Duration;
 
+mod about;
+mod achievements;
 mod animate;
 mod build_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
@@ -43,21 +94,52 @@ mod build_info {
     pub fn nice_app_name() -> String {
         titlecase::titlecase(&PKG_NAME.replace("-", ". "))
     }
+
+    /// Short commit hash this binary was built from, flagged if the working tree had local changes
+    pub fn git_hash() -> String {
+        match (GIT_COMMIT_HASH_SHORT, GIT_DIRTY) {
+            (Some(hash), Some(true)) => format!("{} (dirty)", hash),
+            (Some(hash), _) => hash.to_string(),
+            _ => "unknown".to_string(),
+        }
+    }
 }
+mod capture;
+mod cheat;
+mod cli;
 mod config;
+mod crt;
+mod cue;
+mod daily;
+mod editor;
+mod event_bus;
 mod font;
 mod frame_rate;
-mod game;
 mod game_input;
+mod game_over;
+mod headless;
 mod high_score;
 mod icon;
+mod input_latency;
+mod input_tape;
+mod manual;
 mod menu;
 mod menu_input;
+mod overtime;
 mod particles;
-mod player;
+mod profiling;
 mod scale;
+mod profile_entry;
+mod restart;
+mod rumble;
+mod scene;
+mod seed_entry;
+mod stats;
+mod telemetry;
 mod theme;
 mod themes;
+mod tui;
+mod window_events;
 
 #[cfg(not(feature = "retro_handheld"))]
 const MAX_PLAYERS: u32 = 2;
@@ -67,38 +149,102 @@ const MAX_PLAYERS: u32 = 1;
 
 const MAX_PARTICLES_PER_PLAYER: usize = 100000;
 const MAX_BACKGROUND_PARTICLES: usize = 100000;
+const MAX_REDUCED_MOTION_BACKGROUND_PARTICLES: usize = 250;
+
+/// Idle time on the title menu before the attract mode demo starts
+const ATTRACT_MODE_DELAY: Duration = Duration::from_secs(30);
+const DEMO_DURATION: Duration = Duration::from_secs(90);
+const DEMO_VIRUS_LEVEL: u32 = 10;
+const DAILY_VIRUS_LEVEL: u32 = 10;
+/// Menu frame rate cap when the low power video option is set
+const LOW_POWER_MENU_FPS: u32 = 30;
+/// The stats of the debug overlay are sampled this often so they can be read
+const DEBUG_OVERLAY_PERIOD: Duration = Duration::from_millis(250);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum MainMenuAction {
     Start,
+    Daily,
+    DailyHistory,
+    Editor,
     ViewHighScores,
+    Statistics,
+    Achievements,
+    Options,
+    Manual,
+    About,
+    Demo,
+    SeedEntry,
+    NewProfile,
     Back,
     Quit,
 }
 
+/// Screens of the main loop, each runs until the player moves on to the next
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Title,
+    /// game menu of the mode, players & options
+    GameMenu,
+    Game,
+    Daily,
+    /// painting a layout of viruses
+    Editor,
+    /// practicing the layout of the editor
+    Practice,
+    Quit,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum PostGameAction {
-    NewHighScore(NewHighScore),
+    /// a vs. match has a winner, any high score is entered before the results
+    VsResults(Option<NewHighScore>),
+    Restart,
+    /// chosen from the game over summary of a single player game
+    Retry,
     ReturnToMenu,
     Quit,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ResultsAction {
+    Rematch,
+    ChangeSettings,
+    Menu,
+    Quit,
+}
+
 struct DrRustario {
     config: Config,
+    profile: Profile,
+    /// bindings of the selected profile, edits to the config would otherwise be saved over
+    input: InputConfig,
     _sdl: Sdl,
     ttf: Sdl2TtfContext,
     _image: Sdl2ImageContext,
     canvas: WindowCanvas,
-    event_pump: EventPump,
-    _audio: AudioSubsystem,
+    events: WindowEvents,
+    audio: AudioSubsystem,
     menu_sound: MenuSound,
     game_config: GameConfig,
     particle_scale: particles::scale::Scale,
+    cheats: CheatTable,
+    input_tape: Option<InputTapeMode>,
+    session: Session,
+    /// day of the daily challenge being played, as days since 1970-01-01
+    daily: Option<i64>,
+    /// layout of the editor being practiced
+    practice_layout: Option<BottleSeed>,
 }
 
 impl DrRustario {
-    pub fn new() -> Result<Self, String> {
+    pub fn new(input_tape: Option<InputTapeMode>) -> Result<Self, String> {
         let config = Config::load()?;
+        // fail fast on misconfigured modifiers rather than at the start of a game
+        modifiers_by_name(&config.gameplay.modifiers)?;
+        let profile = Profiles::load()?.selected();
+        let cheats = CheatTable::load(&profile)?;
+        let input = config.input.load_profile(&profile)?;
         let sdl = sdl2::init()?;
         let image = sdl2::image::init(ImageInitFlag::PNG)?;
         let video = sdl.video()?;
@@ -128,7 +274,9 @@ impl DrRustario {
             VideoMode::FullScreenDesktop => {
                 window_builder.fullscreen_desktop();
             }
-            _ => {}
+            VideoMode::Window { .. } => {
+                window_builder.resizable();
+            }
         };
 
         let mut window = window_builder
@@ -150,28 +298,113 @@ impl DrRustario {
         .map_err(|e| e.to_string())?;
 
         let event_pump = sdl.event_pump()?;
+        let timer = sdl.timer()?;
+        let rumble = Rumble::new(sdl.game_controller()?, input.rumble);
 
         let audio = sdl.audio()?;
         sdl2::mixer::open_audio(44_100, DEFAULT_FORMAT, DEFAULT_CHANNELS, 512)?;
-        let _mixer_context = sdl2::mixer::init(MixerInitFlag::OGG)?;
+        // mp3 is only used by user music & is not supported by every build of SDL_mixer
+        let _mixer_context = sdl2::mixer::init(MixerInitFlag::OGG | MixerInitFlag::MP3)
+            .or_else(|_| sdl2::mixer::init(MixerInitFlag::OGG))?;
         sdl2::mixer::allocate_channels((MAX_PLAYERS * MIX_CHANNELS) as i32);
-        sdl2::mixer::Music::set_volume(config.audio.music_volume());
+        config.audio.apply();
         let menu_sound = MenuSound::new(config.audio)?;
 
         Ok(Self {
             config,
+            profile,
+            input,
             _sdl: sdl,
             ttf,
             _image: image,
             canvas,
-            event_pump,
-            _audio: audio,
+            events: WindowEvents::new(event_pump, timer, (width, height), rumble),
+            audio,
             menu_sound,
             game_config: Default::default(),
             particle_scale: particles::scale::Scale::new((width, height)),
+            cheats,
+            input_tape,
+            session: Session::default(),
+            daily: None,
+            practice_layout: None,
         })
     }
 
+    /// Lays out the next screen at the current window size
+    fn reset_layout(&mut self, particles: &mut ParticleRender) -> Result<(), String> {
+        let window_size = self.events.reset_layout(&mut self.canvas)?;
+        self.particle_scale = particles::scale::Scale::new(window_size);
+        particles.set_scale(self.particle_scale);
+        Ok(())
+    }
+
+    /// Themes of a new game at the current window size
+    fn theme_context<'a>(
+        &self,
+        all_themes: &'a AllThemes,
+        texture_creator: &'a TextureCreator<WindowContext>,
+    ) -> Result<ThemeContext<'a>, String> {
+        ThemeContext::new(
+            all_themes,
+            texture_creator,
+            self.game_config,
+            self.canvas.window().size(),
+            self.config.video,
+            self.config.gameplay.theme_sequence(),
+            self.config.accessibility,
+        )
+    }
+
+    fn menu_frame_rate(&self) -> FrameRate {
+        if self.config.video.low_power {
+            FrameRate::limited(LOW_POWER_MENU_FPS)
+        } else {
+            self.game_frame_rate()
+        }
+    }
+
+    /// Vsync paces the frames on its own, otherwise they are capped at max_fps if set
+    fn game_frame_rate(&self) -> FrameRate {
+        match self.config.video.max_fps {
+            Some(max_fps) if !self.config.video.vsync => FrameRate::limited(max_fps),
+            _ => FrameRate::new(),
+        }
+    }
+
+    /// Menu style of the theme selected in the game menu, also sets the menu chime
+    fn apply_menu_style(&mut self, all_themes: &AllThemes) -> MenuStyle {
+        let style = all_themes
+            .selected(self.game_config.themes())
+            .map(|theme| theme.menu_style())
+            .unwrap_or_default();
+        self.menu_sound.set_chime(style.chime);
+        style
+    }
+
+    /// Scene of the theme selected in the game menu, drawn behind the menus
+    fn draw_menu_scene(&mut self, all_themes: &AllThemes) -> Result<(), String> {
+        if let Some(theme) = all_themes.selected(self.game_config.themes()) {
+            let scale = Scale::new(
+                1,
+                theme.background_size(),
+                self.events.layout_size(),
+                theme.geometry().block_size(),
+                self.config.video,
+                theme.name(),
+            );
+            theme.scene(GameSpeed::Low).draw(&mut self.canvas, &scale)?;
+        }
+        Ok(())
+    }
+
+    /// Menu particles are frozen in low power mode
+    fn update_menu_particles(&self, particles: &mut ParticleRender, delta: Duration) {
+        if !self.config.video.low_power {
+            particles.update(delta);
+        }
+    }
+
     fn vitamin_race_particle_source(&self, theme_meta: AllThemeMeta) -> Box<dyn ParticleSource> {
         let (window_width, window_height) = self.canvas.window().size();
         prescribed_vitamin_race(
@@ -181,6 +414,14 @@ impl DrRustario {
         )
     }
 
+    fn falling_pills_particle_source(&self) -> Box<dyn ParticleSource> {
+        let (window_width, window_height) = self.canvas.window().size();
+        prescribed_falling_pills(
+            Rect::new(0, 0, window_width, window_height),
+            &self.particle_scale,
+        )
+    }
+
     fn fireworks_particle_source(&self) -> Box<dyn ParticleSource> {
         let (window_width, window_height) = self.canvas.window().size();
         prescribed_fireworks(
@@ -189,9 +430,10 @@ impl DrRustario {
         )
     }
 
-    fn orbit_particle_source(&self) -> Box<dyn ParticleSource> {
+    /// Background of the scene of the current theme, for themes with a particle scene
+    fn scene_particle_source(&self, themes: &ThemeContext) -> Option<Box<dyn ParticleSource>> {
         let (window_width, window_height) = self.canvas.window().size();
-        prescribed_orbit(
+        themes.theme().scene(self.game_config.speed()).particle_source(
             Rect::new(0, 0, window_width, window_height),
             &self.particle_scale,
         )
@@ -203,53 +445,127 @@ impl DrRustario {
         particles: &mut ParticleRender,
     ) -> Result<MainMenuAction, String> {
         const PLAYERS: &str = "players";
+        const PLAYERS_COOP: &str = "co-op";
+        const PROFILE: &str = "profile";
+        const NEW_PROFILE: &str = "new profile";
         const HIGH_SCORES: &str = "high scores";
+        const DAILY: &str = "daily challenge";
+        const DAILY_HISTORY: &str = "daily history";
+        const EDITOR: &str = "editor";
+        const STATISTICS: &str = "statistics";
+        const ACHIEVEMENTS: &str = "achievements";
+        const OPTIONS: &str = "options";
+        const MANUAL: &str = "manual";
+        const ABOUT: &str = "about";
         const START: &str = "start";
         const QUIT: &str = "quit";
 
+        self.reset_layout(particles)?;
         let texture_creator = self.canvas.texture_creator();
-        let inputs = MenuInputContext::new(self.config.input);
+        let mut inputs = MenuInputContext::new(self.input);
+        let mut cheat_listener = CheatListener::new(self.cheats.codes());
+
+        // the default profile is listed first
+        let mut profiles = Profiles::load()?;
+        let profile_names = std::iter::once(DEFAULT_PROFILE)
+            .chain(profiles.names())
+            .map(|name| name.to_string())
+            .collect::<Vec<String>>();
+        let current_profile = self
+            .profile
+            .name()
+            .and_then(|name| profile_names.iter().position(|n| n == name))
+            .unwrap_or(0);
 
         let mut menu_items = vec![
+            MenuItem::select_list(PROFILE, profile_names, current_profile),
+            MenuItem::select(NEW_PROFILE),
             MenuItem::select(HIGH_SCORES),
+            MenuItem::select(DAILY)
+                .with_description("today's bottle & pills, the same for everyone"),
+            MenuItem::select(DAILY_HISTORY),
+            MenuItem::select(EDITOR).with_description("paint a bottle of viruses to practice on"),
+            MenuItem::select(STATISTICS),
+            MenuItem::select(ACHIEVEMENTS),
+            MenuItem::select(OPTIONS),
+            MenuItem::select(MANUAL),
+            MenuItem::select(ABOUT),
             MenuItem::select(START),
             MenuItem::select(QUIT),
         ];
 
         if MAX_PLAYERS > 1 {
+            // co-op is listed after the vs. player counts
+            let players = if self.game_config.coop() {
+                MAX_PLAYERS as usize
+            } else {
+                self.game_config.players() as usize - 1
+            };
             menu_items.insert(
                 0,
                 MenuItem::select_list(
                     PLAYERS,
                     (1..=MAX_PLAYERS)
-                        .into_iter()
                         .map(|i| i.to_string())
+                        .chain(std::iter::once(PLAYERS_COOP.to_string()))
                         .collect::<Vec<String>>(),
-                    self.game_config.players() as usize - 1,
+                    players,
                 )
+                .with_description("co-op shares one wide bottle between two players"),
             )
         }
 
+        // the errors themselves are too long for the menu, they are listed on the about screen
+        let subtitle = match all_themes.external_errors().len() {
+            0 => None,
+            1 => Some("1 theme failed to load, see about".to_string()),
+            n => Some(format!("{} themes failed to load, see about", n)),
+        };
+        let style = self.apply_menu_style(all_themes);
+        // the title is drawn by the title scene
         let mut menu = Menu::new(
             menu_items,
             &mut self.canvas,
             &self.ttf,
             &texture_creator,
             build_info::nice_app_name(),
-            None,
+            subtitle,
+            style,
+        )?
+        .with_hidden_title();
+        let mut title_scene = TitleScene::new(
+            &mut self.canvas,
+            &self.ttf,
+            &texture_creator,
+            &build_info::nice_app_name(),
+            style,
         )?;
 
         particles.clear();
-        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
+        particles.add_source(self.falling_pills_particle_source());
 
-        let mut frame_rate = FrameRate::new();
+        let mut frame_rate = self.menu_frame_rate();
+        let mut idle = Duration::ZERO;
         self.menu_sound.play_title_music()?;
         loop {
             let delta = frame_rate.update()?;
-            for key in inputs.parse(self.event_pump.poll_iter()).into_iter() {
+            idle += delta;
+            for key in inputs.parse(self.events.poll(&mut self.canvas)?.into_iter()).into_iter() {
                 if key == MenuInputKey::Quit {
                     return Ok(MainMenuAction::Quit);
                 }
+                idle = Duration::ZERO;
+
+                if let Some(cheat) = cheat_listener.receive(key) {
+                    self.cheats.toggle(cheat);
+                    self.cheats.save(&self.profile)?;
+                    self.menu_sound.play_select()?;
+                    continue;
+                }
+                // keys part way through a code, past its first, are kept from the menu
+                if cheat_listener.is_typing() {
+                    continue;
+                }
 
                 match menu.read_key(key) {
                     None => match key {
@@ -262,12 +578,33 @@ impl DrRustario {
                     },
                     Some((name, action)) => match name {
                         PLAYERS => {
-                            let players = action.parse::<u32>().unwrap();
+                            let players = action.parse::<u32>().unwrap_or(1);
+                            self.game_config.set_coop(action == PLAYERS_COOP);
                             self.game_config.set_players(players);
                             self.game_config
                                 .set_rules(MatchRules::default_by_players(players));
                         }
+                        PROFILE => {
+                            let name = Some(action).filter(|&name| name != DEFAULT_PROFILE);
+                            profiles.select(name)?;
+                            profiles.save()?;
+                            self.select_profile(profiles.selected())?;
+                            inputs = MenuInputContext::new(self.input);
+                            cheat_listener = CheatListener::new(self.cheats.codes());
+                        }
+                        NEW_PROFILE => return Ok(MainMenuAction::NewProfile),
                         HIGH_SCORES => return Ok(MainMenuAction::ViewHighScores),
+                        DAILY => {
+                            self.menu_sound.play_select()?;
+                            return Ok(MainMenuAction::Daily);
+                        }
+                        DAILY_HISTORY => return Ok(MainMenuAction::DailyHistory),
+                        EDITOR => return Ok(MainMenuAction::Editor),
+                        STATISTICS => return Ok(MainMenuAction::Statistics),
+                        ACHIEVEMENTS => return Ok(MainMenuAction::Achievements),
+                        OPTIONS => return Ok(MainMenuAction::Options),
+                        MANUAL => return Ok(MainMenuAction::Manual),
+                        ABOUT => return Ok(MainMenuAction::About),
                         START => {
                             self.menu_sound.play_select()?;
                             return Ok(MainMenuAction::Start);
@@ -279,14 +616,29 @@ impl DrRustario {
                 self.menu_sound.play_chime()?;
             }
 
+            if idle >= ATTRACT_MODE_DELAY {
+                return Ok(MainMenuAction::Demo);
+            }
+
             self.canvas.set_draw_color(Color::BLACK);
             self.canvas.clear();
+            self.draw_menu_scene(all_themes)?;
 
             // particles
-            particles.update(delta);
+            self.update_menu_particles(particles, delta);
             particles.draw(&mut self.canvas)?;
 
+            // title, frozen like the particles in low power mode
+            if !self.config.video.low_power {
+                title_scene.update(delta);
+            }
+            title_scene.draw(&mut self.canvas)?;
+
             // menu
+            if let Some(point) = inputs.take_hover() {
+                idle = Duration::ZERO;
+                menu.hover(point);
+            }
             menu.draw(&mut self.canvas)?;
 
             self.canvas.present();
@@ -299,10 +651,53 @@ impl DrRustario {
         particles: &mut ParticleRender,
     ) -> Result<MainMenuAction, String> {
         const THEMES: &str = "themes";
+        const THEME_ORDER: &str = "theme order";
         const MODE: &str = "mode";
         const LEVEL: &str = "level";
         const SPEED: &str = "speed";
+        const P1_LEVEL: &str = "p1 level";
+        const P1_SPEED: &str = "p1 speed";
+        const P2_LEVEL: &str = "p2 level";
+        const P2_SPEED: &str = "p2 speed";
+        const GAMEPLAY: &str = "gameplay";
+        const RULES: &str = "rules";
+        const HOLD: &str = "hold";
+        const HARD_DROP: &str = "hard drop";
+        const GHOST: &str = "ghost";
+        const LOCK_DELAY: &str = "lock delay";
+        const DROP_SCORE: &str = "drop score";
+        const FEATURE_OFF: &str = "off";
+        const FEATURE_ON: &str = "on";
+        const PREVIEWS: &str = "previews";
+        const EXACT_VIRUSES: &str = "exact viruses";
+        const EXACT_VIRUSES_OFF: &str = "off";
+        const BOTTLE: &str = "bottle";
+        const COLORS: &str = "colors";
+        const COLORS_CLASSIC: &str = "3";
+        const COLORS_FOUR: &str = "4";
+        const POWER_UPS: &str = "power-ups";
+        const CRAWLING_VIRUSES: &str = "crawling viruses";
+        const ADAPTIVE_SPEED: &str = "adaptive speed";
+        // each feature is reset by the rules then can be switched on or off
+        const FEATURES: [(&str, Features); 5] = [
+            (HOLD, Features::HOLD),
+            (HARD_DROP, Features::HARD_DROP),
+            (GHOST, Features::GHOST),
+            (LOCK_DELAY, Features::LOCK_DELAY),
+            (DROP_SCORE, Features::DROP_SCORE),
+        ];
         const RANDOM: &str = "random";
+        const MUSIC: &str = "music";
+        const VERSUS: &str = "vs. options";
+        const GARBAGE: &str = "garbage";
+        const GARBAGE_CLASSIC: &str = "classic";
+        const GARBAGE_CANCEL: &str = "cancel";
+        const ATTACK: &str = "attack";
+        const SERIES: &str = "series";
+        const SERIES_SINGLE: &str = "single game";
+        const SEED: &str = "seed";
+        const SEED_RANDOM: &str = "random";
+        const SEED_ENTER: &str = "enter";
         const START: &str = "start";
         const BACK: &str = "back";
 
@@ -312,10 +707,11 @@ impl DrRustario {
             MatchRules::VS_MODES.to_vec()
         };
 
+        self.reset_layout(particles)?;
         let texture_creator = self.canvas.texture_creator();
-        let inputs = MenuInputContext::new(self.config.input);
+        let inputs = MenuInputContext::new(self.input);
 
-        let menu_items = vec![
+        let mut menu_items = vec![
             MenuItem::select_list(
                 THEMES,
                 MatchThemes::names()
@@ -324,6 +720,17 @@ impl DrRustario {
                     .collect(),
                 self.game_config.themes() as usize,
             ),
+            // only used when switching through all themes
+            MenuItem::select_list(
+                THEME_ORDER,
+                ThemeOrder::names()
+                    .into_iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                self.game_config.theme_order() as usize,
+            )
+            .with_description("order of the themes when switching through all of them")
+            .with_disabled(self.game_config.themes() != MatchThemes::All),
             MenuItem::select_list(
                 MODE,
                 modes.iter().map(|m| m.name()).collect(),
@@ -332,19 +739,119 @@ impl DrRustario {
                     .position(|&m| m == self.game_config.rules())
                     .unwrap(),
             ),
+        ];
+        let level_item = |name: &str, player: u32| {
             MenuItem::select_list(
-                LEVEL,
-                (0..=30).map(|i| i.to_string()).collect(),
-                self.game_config.virus_level() as usize,
-            ),
+                name,
+                (0..=MAX_VIRUS_LEVEL).map(|i| i.to_string()).collect(),
+                self.game_config.player_virus_level(player) as usize,
+            )
+        };
+        let speed_item = |name: &str, player: u32| {
             MenuItem::select_list(
-                SPEED,
+                name,
                 GameSpeed::names()
                     .into_iter()
                     .map(|s| s.to_string())
                     .collect(),
-                self.game_config.speed() as usize,
-            ),
+                self.game_config.player_speed(player) as usize,
+            )
+        };
+        if self.game_config.is_single_player() {
+            menu_items.push(level_item(LEVEL, 0));
+            menu_items.push(speed_item(SPEED, 0));
+        } else {
+            // each player can be handicapped with their own level & speed
+            menu_items.push(level_item(P1_LEVEL, 0));
+            menu_items.push(speed_item(P1_SPEED, 0));
+            menu_items.push(level_item(P2_LEVEL, 1));
+            menu_items.push(speed_item(P2_SPEED, 1));
+        }
+        // rule set, features & previews are tuned in their own page
+        let mut gameplay_items = vec![MenuItem::select_list(
+            RULES,
+            RuleSet::names()
+                .into_iter()
+                .map(|s| s.to_string())
+                .collect(),
+            self.game_config.rule_set() as usize,
+        )
+        .with_description("modern, classic nes gravity or relaxed zen, resets the features")];
+        gameplay_items.extend(FEATURES.map(|(name, feature)| {
+            MenuItem::select_list(
+                name,
+                vec![FEATURE_OFF.to_string(), FEATURE_ON.to_string()],
+                self.game_config.features().contains(feature) as usize,
+            )
+        }));
+        gameplay_items.push(
+            MenuItem::select_list(
+                PREVIEWS,
+                (0..=PEEK_SIZE).map(|i| i.to_string()).collect(),
+                self.game_config.peek_count(),
+            )
+            .with_description("number of upcoming pills shown"),
+        );
+        let exact_viruses = std::iter::once(EXACT_VIRUSES_OFF.to_string())
+            .chain((MIN_VIRUSES..=MAX_VIRUSES).map(|i| i.to_string()))
+            .collect();
+        gameplay_items.push(
+            MenuItem::select_list(
+                EXACT_VIRUSES,
+                exact_viruses,
+                self.game_config
+                    .exact_viruses()
+                    .map(|v| (v - MIN_VIRUSES) as usize + 1)
+                    .unwrap_or(0),
+            )
+            .with_description("viruses in every bottle, overrides the level"),
+        );
+        gameplay_items.push(
+            MenuItem::select_list(
+                BOTTLE,
+                BottleShape::names()
+                    .into_iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                self.game_config.bottle() as usize,
+            )
+            .with_description("classic 8x16, wide 12x16 or tall 8x20"),
+        );
+        gameplay_items.push(
+            MenuItem::select_list(
+                COLORS,
+                vec![COLORS_CLASSIC.to_string(), COLORS_FOUR.to_string()],
+                self.game_config.four_colors() as usize,
+            )
+            .with_description("a fourth green virus color joins yellow, blue & red"),
+        );
+        gameplay_items.push(
+            MenuItem::select_list(
+                POWER_UPS,
+                vec![FEATURE_OFF.to_string(), FEATURE_ON.to_string()],
+                self.game_config.power_ups() as usize,
+            )
+            .with_description("occasional bomb, lightning & heavy pills"),
+        );
+        gameplay_items.push(
+            MenuItem::select_list(
+                CRAWLING_VIRUSES,
+                vec![FEATURE_OFF.to_string(), FEATURE_ON.to_string()],
+                self.game_config.crawling_viruses() as usize,
+            )
+            .with_description("hard mode, viruses crawl into empty blocks every few seconds"),
+        );
+        gameplay_items.push(
+            MenuItem::select_list(
+                ADAPTIVE_SPEED,
+                vec![FEATURE_OFF.to_string(), FEATURE_ON.to_string()],
+                self.game_config.adaptive_speed() as usize,
+            )
+            .with_description("speeds up while you clear fast & chain, slows after a near miss"),
+        );
+        menu_items.extend([
+            MenuItem::submenu(GAMEPLAY, gameplay_items)
+                .with_description("rules, features, previews, the bottle & special modes"),
             MenuItem::select_list(
                 RANDOM,
                 RandomMode::names()
@@ -353,14 +860,74 @@ impl DrRustario {
                     .collect(),
                 self.game_config.random() as usize,
             ),
+            MenuItem::select_list(
+                MUSIC,
+                MusicSelect::names()
+                    .into_iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                self.game_config.music() as usize,
+            ),
+            // a fixed seed is shown as its code so it can be shared
+            MenuItem::select_list(
+                SEED,
+                vec![
+                    SEED_RANDOM.to_string(),
+                    self.game_config
+                        .seed()
+                        .map(seed::encode)
+                        .unwrap_or_else(|| SEED_ENTER.to_string()),
+                ],
+                self.game_config.seed().is_some() as usize,
+            ),
             MenuItem::select(START),
             MenuItem::select(BACK),
-        ];
-        let subtitle = if self.game_config.is_single_player() {
+        ]);
+        // one game or a series of games, first to some number of wins
+        let series = (1..=MAX_SERIES_WINS)
+            .map(|wins| match wins {
+                1 => SERIES_SINGLE.to_string(),
+                _ => format!("first to {}", wins),
+            })
+            .collect::<Vec<String>>();
+        if !self.game_config.is_single_player() {
+            menu_items.insert(
+                menu_items.len() - 2,
+                MenuItem::submenu(
+                    VERSUS,
+                    vec![
+                        MenuItem::select_list(
+                            GARBAGE,
+                            vec![GARBAGE_CLASSIC.to_string(), GARBAGE_CANCEL.to_string()],
+                            self.game_config.garbage_cancellation() as usize,
+                        )
+                        .with_description("cancel incoming garbage by clearing first"),
+                        MenuItem::select_list(
+                            ATTACK,
+                            GarbageStyle::names()
+                                .into_iter()
+                                .map(|s| s.to_string())
+                                .collect(),
+                            self.game_config.garbage_style() as usize,
+                        ),
+                        MenuItem::select_list(
+                            SERIES,
+                            series.clone(),
+                            self.game_config.series_wins() as usize - 1,
+                        ),
+                    ],
+                )
+                .with_description("garbage, attacks & series"),
+            );
+        }
+        let subtitle = if self.game_config.coop() {
+            "co-op".to_string()
+        } else if self.game_config.is_single_player() {
             "single player".to_string()
         } else {
             format!("{}-player vs.", self.game_config.players())
         };
+        let style = self.apply_menu_style(all_themes);
         let mut menu = Menu::new(
             menu_items,
             &mut self.canvas,
@@ -368,17 +935,18 @@ impl DrRustario {
             &texture_creator,
             build_info::nice_app_name(),
             Some(subtitle),
+            style,
         )?;
 
         particles.clear();
         particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
 
-        let mut frame_rate = FrameRate::new();
+        let mut frame_rate = self.menu_frame_rate();
         self.menu_sound.play_menu_music()?;
         loop {
             let delta = frame_rate.update()?;
 
-            for key in inputs.parse(self.event_pump.poll_iter()).into_iter() {
+            for key in inputs.parse(self.events.poll(&mut self.canvas)?.into_iter()).into_iter() {
                 if key == MenuInputKey::Quit {
                     return Ok(MainMenuAction::Quit);
                 }
@@ -392,9 +960,19 @@ impl DrRustario {
                         _ => {}
                     },
                     Some((name, action)) => match name {
-                        THEMES => self
+                        THEMES => {
+                            self.game_config
+                                .set_themes(MatchThemes::from_str(action).unwrap());
+                            let style = self.apply_menu_style(all_themes);
+                            menu.restyle(&mut self.canvas, &self.ttf, &texture_creator, style)?;
+                            menu.set_disabled(
+                                THEME_ORDER,
+                                self.game_config.themes() != MatchThemes::All,
+                            );
+                        }
+                        THEME_ORDER => self
                             .game_config
-                            .set_themes(MatchThemes::from_str(action).unwrap()),
+                            .set_theme_order(ThemeOrder::from_str(action).unwrap()),
                         MODE => {
                             let mode_index =
                                 modes.iter().position(|&m| m.name() == action).unwrap();
@@ -406,9 +984,64 @@ impl DrRustario {
                         SPEED => self
                             .game_config
                             .set_speed(GameSpeed::from_str(action).unwrap()),
+                        P1_LEVEL | P2_LEVEL => {
+                            let player = if name == P1_LEVEL { 0 } else { 1 };
+                            self.game_config
+                                .set_player_virus_level(player, action.parse::<u32>().unwrap())
+                        }
+                        P1_SPEED | P2_SPEED => {
+                            let player = if name == P1_SPEED { 0 } else { 1 };
+                            self.game_config
+                                .set_player_speed(player, GameSpeed::from_str(action).unwrap())
+                        }
+                        RULES => {
+                            self.game_config
+                                .set_rule_set(RuleSet::from_str(action).unwrap());
+                            for (name, feature) in FEATURES {
+                                let enabled = self.game_config.features().contains(feature);
+                                menu.set_current(name, enabled as usize);
+                            }
+                        }
+                        HOLD | HARD_DROP | GHOST | LOCK_DELAY | DROP_SCORE => {
+                            let (_, feature) =
+                                FEATURES.into_iter().find(|(n, _)| *n == name).unwrap();
+                            self.game_config.set_feature(feature, action == FEATURE_ON)
+                        }
+                        PREVIEWS => self
+                            .game_config
+                            .set_peek_count(action.parse::<usize>().unwrap()),
+                        EXACT_VIRUSES => self
+                            .game_config
+                            .set_exact_viruses(action.parse::<u32>().ok()),
+                        BOTTLE => self
+                            .game_config
+                            .set_bottle(BottleShape::from_str(action).unwrap()),
+                        COLORS => self.game_config.set_four_colors(action == COLORS_FOUR),
+                        POWER_UPS => self.game_config.set_power_ups(action == FEATURE_ON),
+                        CRAWLING_VIRUSES => self
+                            .game_config
+                            .set_crawling_viruses(action == FEATURE_ON),
+                        ADAPTIVE_SPEED => self
+                            .game_config
+                            .set_adaptive_speed(action == FEATURE_ON),
                         RANDOM => self
                             .game_config
                             .set_random(RandomMode::from_str(action).unwrap()),
+                        MUSIC => self
+                            .game_config
+                            .set_music(MusicSelect::from_str(action).unwrap()),
+                        GARBAGE => self
+                            .game_config
+                            .set_garbage_cancellation(action == GARBAGE_CANCEL),
+                        ATTACK => self
+                            .game_config
+                            .set_garbage_style(GarbageStyle::from_str(action).unwrap()),
+                        SERIES => {
+                            let index = series.iter().position(|s| s == action).unwrap();
+                            self.game_config.set_series_wins(index as u32 + 1)
+                        }
+                        SEED if action == SEED_RANDOM => self.game_config.set_seed(None),
+                        SEED => return Ok(MainMenuAction::SeedEntry),
                         START => return Ok(MainMenuAction::Start),
                         BACK => {
                             self.menu_sound.play_select()?;
@@ -423,150 +1056,746 @@ impl DrRustario {
 
             self.canvas.set_draw_color(Color::BLACK);
             self.canvas.clear();
+            self.draw_menu_scene(all_themes)?;
 
             // particles
-            particles.update(delta);
+            self.update_menu_particles(particles, delta);
             particles.draw(&mut self.canvas)?;
 
             // menu
+            if let Some(point) = inputs.take_hover() {
+                menu.hover(point);
+            }
             menu.draw(&mut self.canvas)?;
 
             self.canvas.present();
         }
     }
 
-    pub fn view_high_score(&mut self, particles: &mut ParticleRender) -> Result<(), String> {
+    pub fn options_menu(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        const MUSIC_VOLUME: &str = "music volume";
+        const EFFECTS_VOLUME: &str = "effects volume";
+        const DISPLAY: &str = "display";
+        const BACK: &str = "back";
+        const WINDOW: &str = "window";
+        const FULLSCREEN: &str = "fullscreen";
+
+        fn volume_item(name: &str, volume: f64) -> MenuItem {
+            MenuItem::select_list(
+                name,
+                (0..=10).map(|i| format!("{}%", i * 10)).collect(),
+                (volume * 10.0).round() as usize,
+            )
+        }
+
+        fn parse_volume(action: &str) -> f64 {
+            action.trim_end_matches('%').parse::<f64>().unwrap() / 100.0
+        }
+
+        self.reset_layout(particles)?;
         let texture_creator = self.canvas.texture_creator();
-        let inputs = MenuInputContext::new(self.config.input);
-        let high_scores = HighScoreTable::load()?;
-        if high_scores.entries().is_empty() {
-            return Ok(());
+        let inputs = MenuInputContext::new(self.input);
+
+        let mut menu_items = vec![
+            volume_item(MUSIC_VOLUME, self.config.audio.music_volume),
+            volume_item(EFFECTS_VOLUME, self.config.audio.effects_volume),
+            MenuItem::select(BACK),
+        ];
+
+        if !cfg!(feature = "retro_handheld") {
+            let is_fullscreen = !matches!(self.config.video.mode, VideoMode::Window { .. });
+            menu_items.insert(
+                2,
+                MenuItem::select_list(
+                    DISPLAY,
+                    vec![WINDOW.to_string(), FULLSCREEN.to_string()],
+                    is_fullscreen as usize,
+                ),
+            );
         }
 
-        let mut view = HighScoreRender::new(
-            high_scores,
+        let style = self.apply_menu_style(all_themes);
+        let mut menu = Menu::new(
+            menu_items,
+            &mut self.canvas,
             &self.ttf,
             &texture_creator,
-            self.canvas.window().size(),
-            None,
+            build_info::nice_app_name(),
+            Some("options".to_string()),
+            style,
         )?;
 
         particles.clear();
-        particles.add_source(self.fireworks_particle_source());
+        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
 
-        let mut frame_rate = FrameRate::new();
-        self.menu_sound.play_high_score_music()?;
+        // restore the configured window size when leaving fullscreen
+        let (window_width, window_height) = match self.config.video.mode {
+            VideoMode::Window { width, height } => (width, height),
+            VideoMode::FullScreen { width, height } => (width, height),
+            VideoMode::FullScreenDesktop => (1280, 720),
+        };
+
+        let mut frame_rate = self.menu_frame_rate();
+        let mut changed = false;
         'menu: loop {
             let delta = frame_rate.update()?;
-            let events = inputs.parse(self.event_pump.poll_iter());
-            if !events.is_empty() {
-                // any button press
-                break 'menu;
+
+            for key in inputs.parse(self.events.poll(&mut self.canvas)?.into_iter()).into_iter() {
+                if key == MenuInputKey::Quit {
+                    break 'menu;
+                }
+                match menu.read_key(key) {
+                    None => {
+                        if key == MenuInputKey::Back {
+                            break 'menu;
+                        }
+                    }
+                    Some((name, action)) => {
+                        match name {
+                            MUSIC_VOLUME => {
+                                self.config.audio.music_volume = parse_volume(action);
+                            }
+                            EFFECTS_VOLUME => {
+                                self.config.audio.effects_volume = parse_volume(action);
+                            }
+                            DISPLAY => {
+                                let window = self.canvas.window_mut();
+                                if action == FULLSCREEN {
+                                    window.set_fullscreen(FullscreenType::Desktop)?;
+                                    self.config.video.mode = VideoMode::FullScreenDesktop;
+                                } else {
+                                    window.set_fullscreen(FullscreenType::Off)?;
+                                    window
+                                        .set_size(window_width, window_height)
+                                        .map_err(|e| e.to_string())?;
+                                    window.set_position(
+                                        WindowPos::Centered,
+                                        WindowPos::Centered,
+                                    );
+                                    self.config.video.mode = VideoMode::Window {
+                                        width: window_width,
+                                        height: window_height,
+                                    };
+                                }
+                            }
+                            BACK => {
+                                self.menu_sound.play_select()?;
+                                break 'menu;
+                            }
+                            _ => {}
+                        }
+                        self.config.audio.apply();
+                        changed = true;
+                    }
+                }
+                self.menu_sound.play_chime()?;
             }
+
             self.canvas.set_draw_color(Color::BLACK);
             self.canvas.clear();
+            self.draw_menu_scene(all_themes)?;
 
             // particles
-            particles.update(delta);
+            self.update_menu_particles(particles, delta);
             particles.draw(&mut self.canvas)?;
 
-            view.draw(&mut self.canvas)?;
+            // menu
+            if let Some(point) = inputs.take_hover() {
+                menu.hover(point);
+            }
+            menu.draw(&mut self.canvas)?;
 
             self.canvas.present();
         }
-        Ok(())
+
+        if changed {
+            self.config.save()
+        } else {
+            Ok(())
+        }
     }
 
-    pub fn new_high_score(
-        &mut self,
-        new_high_score: NewHighScore,
-        particles: &mut ParticleRender,
-    ) -> Result<(), String> {
+    pub fn view_high_score(&mut self, particles: &mut ParticleRender) -> Result<(), String> {
+        self.reset_layout(particles)?;
         let texture_creator = self.canvas.texture_creator();
-        let inputs = MenuInputContext::new(self.config.input);
-        let high_scores = HighScoreTable::load()?;
+        let high_scores = HighScoreTable::load(&self.profile)?;
         if high_scores.entries().is_empty() {
             return Ok(());
         }
 
-        let mut table = HighScoreRender::new(
+        let table = HighScoreRender::new(
             high_scores,
             &self.ttf,
             &texture_creator,
             self.canvas.window().size(),
-            Some(new_high_score),
+            None,
         )?;
+        let online = self.config.online.as_ref().map(OnlineLeaderboard::fetch);
+        let mut view = HighScoreView::new(table, online)?;
 
         particles.clear();
         particles.add_source(self.fireworks_particle_source());
-
-        let mut frame_rate = FrameRate::new();
         self.menu_sound.play_high_score_music()?;
-        'menu: loop {
-            let delta = frame_rate.update()?;
-
-            for key in inputs.parse(self.event_pump.poll_iter()) {
-                let event = match key {
-                    MenuInputKey::Up => table.up(),
-                    MenuInputKey::Down => table.down(),
-                    MenuInputKey::Left => table.left(),
-                    MenuInputKey::Right => table.right(),
-                    MenuInputKey::Start => break 'menu,
-                    MenuInputKey::Back => return Ok(()),
-                    _ => None,
-                };
-                if event.is_none() {
-                    continue;
-                }
-                match event.unwrap() {
-                    HighScoreEntryEvent::Finished => break 'menu,
-                    _ => self.menu_sound.play_chime()?,
-                }
-            }
+        self.run_scene(&mut view, particles)
+    }
 
-            self.canvas.set_draw_color(Color::BLACK);
-            self.canvas.clear();
+    pub fn manual(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        self.reset_layout(particles)?;
+        let texture_creator = self.canvas.texture_creator();
+        let mut view = ManualScreen::new(
+            &self.input,
+            &self.ttf,
+            &texture_creator,
+            self.canvas.window().size(),
+        )?;
 
-            // particles
-            particles.update(delta);
-            particles.draw(&mut self.canvas)?;
+        particles.clear();
+        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
+        self.run_scene(&mut view, particles)
+    }
 
-            table.draw(&mut self.canvas)?;
+    pub fn seed_entry(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        self.reset_layout(particles)?;
+        let texture_creator = self.canvas.texture_creator();
+        let seed = self.game_config.seed().unwrap_or_else(seed::random_seed);
+        let mut view =
+            SeedEntryScreen::new(seed, &self.ttf, &texture_creator, self.canvas.window().size())?;
 
-            self.canvas.present();
+        particles.clear();
+        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
+        if let SeedEntryAction::Confirm(seed) = self.run_scene(&mut view, particles)? {
+            self.menu_sound.play_select()?;
+            self.game_config.set_seed(Some(seed));
         }
+        Ok(())
+    }
 
-        if let Some(new_entry) = table.new_entry() {
-            let mut high_scores = HighScoreTable::load().unwrap();
-            high_scores.add_high_score(new_entry);
-            high_scores.save()
-        } else {
-            Ok(())
-        }
+    fn select_profile(&mut self, profile: Profile) -> Result<(), String> {
+        self.input = self.config.input.load_profile(&profile)?;
+        self.events.rumble_mut().set_intensity(self.input.rumble);
+        self.cheats = CheatTable::load(&profile)?;
+        self.profile = profile;
+        Ok(())
     }
 
-    pub fn game(
+    /// Creates & selects a profile, it starts with a copy of the shared input bindings
+    pub fn new_profile(
         &mut self,
         all_themes: &AllThemes,
-        fg_particles: &mut ParticleRender,
-        bg_particles: &mut ParticleRender,
-    ) -> Result<PostGameAction, String> {
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        self.reset_layout(particles)?;
         let texture_creator = self.canvas.texture_creator();
-        let mut inputs = GameInputContext::new(self.config.input);
-        let mut fixture = Match::new(self.game_config);
-        let window_size = self.canvas.window().size();
-        let mut themes =
-            ThemeContext::new(all_themes, &texture_creator, self.game_config, window_size, self.config.video)?;
-        let mut player_textures = (0..self.game_config.players())
-            .map(|_| {
-                PlayerTextures::new(
-                    &texture_creator,
-                    themes.max_background_size(),
+        let mut view =
+            ProfileEntryScreen::new(&self.ttf, &texture_creator, self.canvas.window().size())?;
+
+        particles.clear();
+        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
+        while let ProfileEntryAction::Create(name) = self.run_scene(&mut view, particles)? {
+            let mut profiles = Profiles::load()?;
+            // an empty or existing name is refused, the entry stays open to fix it
+            let Ok(profile) = profiles.create(&name) else {
+                self.menu_sound.play_chime()?;
+                continue;
+            };
+            self.menu_sound.play_select()?;
+            self.config.input.save_profile(&profile)?;
+            profiles.save()?;
+            return self.select_profile(profile);
+        }
+        Ok(())
+    }
+
+    pub fn about(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        let info = about_info(
+            &self.canvas,
+            &self.audio,
+            all_themes.external_errors(),
+            &self.profile,
+        )?;
+        self.info_screen("About", &info, all_themes, particles)
+    }
+
+    pub fn statistics(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        let info = stats_info(&PlayerStats::load(&self.profile)?);
+        self.info_screen("Statistics", &info, all_themes, particles)
+    }
+
+    pub fn achievements(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        let info = AchievementStore::load(&self.profile)?.info();
+        self.info_screen("Achievements", &info, all_themes, particles)
+    }
+
+    /// Table of label & value pairs until any button is pressed
+    fn info_screen<L: AsRef<str>>(
+        &mut self,
+        title: &str,
+        info: &[(L, String)],
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        self.reset_layout(particles)?;
+        let texture_creator = self.canvas.texture_creator();
+        let mut view = AboutScreen::new(
+            title,
+            info,
+            &self.ttf,
+            &texture_creator,
+            self.canvas.window().size(),
+        )?;
+
+        particles.clear();
+        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
+        self.run_scene(&mut view, particles)
+    }
+
+    /// Runs a scene over the background particles until it exits
+    fn run_scene<S: Scene>(
+        &mut self,
+        scene: &mut S,
+        particles: &mut ParticleRender,
+    ) -> Result<S::Output, String> {
+        let inputs = MenuInputContext::new(self.input);
+        let mut frame_rate = self.menu_frame_rate();
+        loop {
+            let delta = frame_rate.update()?;
+            for key in inputs.parse(self.events.poll(&mut self.canvas)?.into_iter()) {
+                match scene.handle_input(key) {
+                    SceneInput::Exit(output) => return Ok(output),
+                    SceneInput::Handled => self.menu_sound.play_chime()?,
+                    SceneInput::Ignored => {}
+                }
+            }
+            if let Some(point) = inputs.take_hover() {
+                scene.hover(point);
+            }
+            scene.update(delta);
+
+            self.canvas.set_draw_color(Color::BLACK);
+            self.canvas.clear();
+
+            // particles
+            self.update_menu_particles(particles, delta);
+            particles.draw(&mut self.canvas)?;
+
+            scene.draw(&mut self.canvas)?;
+
+            self.canvas.present();
+        }
+    }
+
+    pub fn new_high_score(
+        &mut self,
+        new_high_score: NewHighScore,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        self.reset_layout(particles)?;
+        let texture_creator = self.canvas.texture_creator();
+        let high_scores = HighScoreTable::load(&self.profile)?;
+        if high_scores.entries().is_empty() {
+            return Ok(());
+        }
+
+        let mut entry = HighScoreEntry::new(HighScoreRender::new(
+            high_scores,
+            &self.ttf,
+            &texture_creator,
+            self.canvas.window().size(),
+            Some(new_high_score),
+        )?);
+
+        particles.clear();
+        particles.add_source(self.fireworks_particle_source());
+        self.menu_sound.play_high_score_music()?;
+        if !self.run_scene(&mut entry, particles)? {
+            return Ok(());
+        }
+
+        if let Some(new_entry) = entry.new_entry() {
+            // only player 1 plays as the selected profile
+            let profile = self.profile.name().filter(|_| new_high_score.player == 0);
+            let new_entry = new_entry.with_profile(profile);
+            if let Some(online) = self.config.online.as_ref() {
+                online::submit(online, new_entry.clone());
+            }
+            let mut high_scores = HighScoreTable::load(&self.profile).unwrap();
+            high_scores.add_high_score(new_entry);
+            high_scores.save(&self.profile)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// High score entry & the game over summary for single player, vs. matches go on to the
+    /// results
+    fn post_match_action(
+        &mut self,
+        high_score: Option<NewHighScore>,
+        summary: GameOverSummary,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<PostGameAction, String> {
+        if !self.game_config.is_single_player() {
+            return Ok(PostGameAction::VsResults(high_score));
+        }
+        // the daily is recorded in its own table
+        if let Some(high_score) = high_score.filter(|_| self.daily.is_none()) {
+            self.new_high_score(high_score, particles)?;
+        }
+        self.game_over(summary, all_themes, particles)
+    }
+
+    /// Score, level & viruses of a single player game, then a retry or back to the menu
+    fn game_over(
+        &mut self,
+        summary: GameOverSummary,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<PostGameAction, String> {
+        self.reset_layout(particles)?;
+        let texture_creator = self.canvas.texture_creator();
+        let mut view = GameOverScreen::new(
+            summary,
+            &self.ttf,
+            &texture_creator,
+            self.canvas.window().size(),
+        )?;
+
+        particles.clear();
+        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
+        match self.run_scene(&mut view, particles)? {
+            GameOverAction::Retry => {
+                self.menu_sound.play_select()?;
+                Ok(PostGameAction::Retry)
+            }
+            GameOverAction::Menu => Ok(PostGameAction::ReturnToMenu),
+        }
+    }
+
+    /// Winner & session tally of a vs. match, then what to play next
+    pub fn vs_results(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<ResultsAction, String> {
+        const REMATCH: &str = "rematch";
+        const CHANGE_SETTINGS: &str = "change settings";
+        const MENU: &str = "menu";
+
+        self.reset_layout(particles)?;
+        let texture_creator = self.canvas.texture_creator();
+        let inputs = MenuInputContext::new(self.input);
+
+        let title = match self.session.last_winner() {
+            Some(winner) => format!("Player {} Wins", winner + 1),
+            None => "Results".to_string(),
+        };
+        let tally = self
+            .session
+            .wins()
+            .iter()
+            .enumerate()
+            .map(|(player, wins)| format!("p{} {}", player + 1, wins))
+            .collect::<Vec<String>>()
+            .join("   ");
+        let subtitle = format!("{} of {} matches", tally, self.session.matches());
+
+        let style = self.apply_menu_style(all_themes);
+        let mut menu = Menu::new(
+            vec![
+                MenuItem::select(REMATCH),
+                MenuItem::select(CHANGE_SETTINGS),
+                MenuItem::select(MENU),
+            ],
+            &mut self.canvas,
+            &self.ttf,
+            &texture_creator,
+            title,
+            Some(subtitle),
+            style,
+        )?;
+
+        particles.clear();
+        particles.add_source(self.fireworks_particle_source());
+
+        let mut frame_rate = self.menu_frame_rate();
+        loop {
+            let delta = frame_rate.update()?;
+
+            for key in inputs.parse(self.events.poll(&mut self.canvas)?.into_iter()).into_iter() {
+                if key == MenuInputKey::Quit {
+                    return Ok(ResultsAction::Quit);
+                }
+                match menu.read_key(key) {
+                    None => match key {
+                        MenuInputKey::Start => {
+                            self.menu_sound.play_select()?;
+                            return Ok(ResultsAction::Rematch);
+                        }
+                        MenuInputKey::Back => return Ok(ResultsAction::ChangeSettings),
+                        _ => {}
+                    },
+                    Some((name, _)) => match name {
+                        REMATCH => {
+                            self.menu_sound.play_select()?;
+                            return Ok(ResultsAction::Rematch);
+                        }
+                        CHANGE_SETTINGS => return Ok(ResultsAction::ChangeSettings),
+                        MENU => return Ok(ResultsAction::Menu),
+                        _ => {}
+                    },
+                }
+                self.menu_sound.play_chime()?;
+            }
+
+            self.canvas.set_draw_color(Color::BLACK);
+            self.canvas.clear();
+            self.draw_menu_scene(all_themes)?;
+
+            // particles
+            self.update_menu_particles(particles, delta);
+            particles.draw(&mut self.canvas)?;
+
+            if let Some(point) = inputs.take_hover() {
+                menu.hover(point);
+            }
+            menu.draw(&mut self.canvas)?;
+
+            self.canvas.present();
+        }
+    }
+
+    pub fn game(
+        &mut self,
+        all_themes: &AllThemes,
+        fg_particles: &mut ParticleRender,
+        bg_particles: &mut ParticleRender,
+    ) -> Result<PostGameAction, String> {
+        let result = match self.input_tape.clone() {
+            Some(InputTapeMode::Record(path)) => {
+                let seed = self.game_config.seed().unwrap_or_else(seed::random_seed);
+                let mut tape = InputTape::record(seed);
+                let result =
+                    self.play(all_themes, fg_particles, bg_particles, None, Some(&mut tape));
+                tape.save(path)?;
+                result
+            }
+            Some(InputTapeMode::Play(path)) => {
+                let mut tape = InputTape::load(path)?;
+                self.play(all_themes, fg_particles, bg_particles, None, Some(&mut tape))
+            }
+            None => self.play(all_themes, fg_particles, bg_particles, None, None),
+        };
+        // the game may have ended while the music was ducked
+        self.config.audio.apply();
+        result
+    }
+
+    /// Attract mode, a single player game played by the CPU until any key is pressed
+    pub fn demo(
+        &mut self,
+        all_themes: &AllThemes,
+        fg_particles: &mut ParticleRender,
+        bg_particles: &mut ParticleRender,
+    ) -> Result<PostGameAction, String> {
+        let game_config = self.game_config;
+        self.game_config = GameConfig::new(
+            1,
+            DEMO_VIRUS_LEVEL,
+            GameSpeed::Medium,
+            game_config.themes(),
+            MatchRules::Marathon,
+            RandomMode::default(),
+        );
+        let result = self.play(
+            all_themes,
+            fg_particles,
+            bg_particles,
+            Some(DemoPlayer::new(0)),
+            None,
+        );
+        self.game_config = game_config;
+        self.config.audio.apply();
+        result
+    }
+
+    /// Single player marathon of today's seed, the best result is recorded in the daily table
+    /// rather than the high scores
+    pub fn daily_challenge(
+        &mut self,
+        all_themes: &AllThemes,
+        fg_particles: &mut ParticleRender,
+        bg_particles: &mut ParticleRender,
+    ) -> Result<PostGameAction, String> {
+        let today = days_today();
+        let game_config = self.game_config;
+        self.game_config = GameConfig::new(
+            1,
+            DAILY_VIRUS_LEVEL,
+            GameSpeed::Medium,
+            game_config.themes(),
+            MatchRules::Marathon,
+            RandomMode::default(),
+        );
+        self.game_config.set_seed(Some(daily_seed(today)));
+        self.daily = Some(today);
+        let result = self.play(all_themes, fg_particles, bg_particles, None, None);
+        self.daily = None;
+        self.game_config = game_config;
+        self.config.audio.apply();
+        result
+    }
+
+    pub fn daily_history(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<(), String> {
+        let info = daily_info(&DailyTable::load(&self.profile)?, days_today());
+        self.info_screen("Daily Challenge", &info, all_themes, particles)
+    }
+
+    pub fn editor(
+        &mut self,
+        all_themes: &AllThemes,
+        particles: &mut ParticleRender,
+    ) -> Result<EditorAction, String> {
+        self.reset_layout(particles)?;
+        let texture_creator = self.canvas.texture_creator();
+        let mut view = EditorScreen::new(&self.ttf, &texture_creator, self.canvas.window().size())?;
+
+        particles.clear();
+        particles.add_source(self.vitamin_race_particle_source(all_themes.meta()));
+        let action = self.run_scene(&mut view, particles)?;
+        save_layout(view.layout())?;
+        if action == EditorAction::Play {
+            self.menu_sound.play_select()?;
+        }
+        Ok(action)
+    }
+
+    /// Single level of the layout saved by the editor, rewinding is always on & it is kept out of
+    /// the high scores
+    pub fn practice(
+        &mut self,
+        all_themes: &AllThemes,
+        fg_particles: &mut ParticleRender,
+        bg_particles: &mut ParticleRender,
+    ) -> Result<PostGameAction, String> {
+        // the editor starts over rather than playing a layout that cannot be practiced
+        let layout = match load_layout() {
+            Ok(layout) => layout,
+            Err(error) => {
+                println!("cannot practice the layout: {}", error);
+                None
+            }
+        };
+        let Some((layout, bottle)) =
+            layout.and_then(|layout| bottle_shape(&layout).map(|bottle| (layout, bottle)))
+        else {
+            return Ok(PostGameAction::ReturnToMenu);
+        };
+        let game_config = self.game_config;
+        self.game_config = GameConfig::new(
+            1,
+            0,
+            game_config.speed(),
+            game_config.themes(),
+            MatchRules::ONE_LEVEL_SPRINT,
+            RandomMode::default(),
+        );
+        self.game_config.set_bottle(bottle);
+        self.game_config.set_four_colors(layout.is_four_colors());
+        self.practice_layout = Some(layout);
+        let result = self.play(all_themes, fg_particles, bg_particles, None, None);
+        self.practice_layout = None;
+        self.game_config = game_config;
+        self.config.audio.apply();
+        result
+    }
+
+    fn play(
+        &mut self,
+        all_themes: &AllThemes,
+        fg_particles: &mut ParticleRender,
+        bg_particles: &mut ParticleRender,
+        mut demo: Option<DemoPlayer>,
+        mut tape: Option<&mut InputTape>,
+    ) -> Result<PostGameAction, String> {
+        self.reset_layout(bg_particles)?;
+        fg_particles.set_scale(self.particle_scale);
+        let texture_creator = self.canvas.texture_creator();
+        let mut inputs = GameInputContext::new(self.input);
+        // the daily is the same bottle & pills for everyone
+        let modifiers: &[String] = if demo.is_some() || self.daily.is_some() {
+            &[]
+        } else {
+            &self.config.gameplay.modifiers
+        };
+        let mut game_config = self.game_config;
+        // everyone plays the daily without rewinding, a layout is there to be practiced
+        game_config.set_rewind(
+            demo.is_none()
+                && self.daily.is_none()
+                && (self.config.gameplay.rewind || self.practice_layout.is_some()),
+        );
+        if let Some(tape) = tape.as_ref() {
+            game_config.set_seed(Some(tape.seed()));
+        }
+        let mut fixture = Match::new(game_config, modifiers)
+            .with_high_scores(HighScoreTable::load(&self.profile)?);
+        if let Some(layout) = self.practice_layout.as_ref() {
+            fixture = fixture.with_layout(layout);
+        }
+        let window_size = self.canvas.window().size();
+        let mut themes = self.theme_context(all_themes, &texture_creator)?;
+        let mut player_textures = (0..self.game_config.players())
+            .map(|_| {
+                PlayerTextures::new(
+                    &texture_creator,
+                    themes.max_background_size(),
                     themes.max_bottle_size(),
                 )
                 .unwrap()
             })
             .collect::<Vec<PlayerTextures>>();
+        let mut crt_filter = CrtFilter::new(
+            &texture_creator,
+            self.canvas.output_size()?,
+            self.config.video.crt_filter,
+        )?;
+        let mut capture = Capture::new(&self.config.capture)?;
+        // taken once the frame is drawn
+        let mut take_screenshot = false;
+        if self.config.video.player_tint && !self.game_config.is_single_player() {
+            for (player, textures) in player_textures.iter_mut().enumerate() {
+                textures.set_player_tint(player as u32);
+            }
+        }
 
         // push mut refs of all textures and their render modes into a single vector so we can render to texture in one loop
         let mut texture_refs: Vec<(&mut Texture, TextureMode)> = vec![];
@@ -581,10 +1810,56 @@ impl DrRustario {
             ));
         }
 
-        let paused_screen =
-            PausedScreen::new(&mut self.canvas, &self.ttf, &texture_creator, window_size)?;
+        let menu_inputs = MenuInputContext::new(self.input);
+        let mut paused_screen = PausedScreen::new(
+            &mut self.canvas,
+            &self.ttf,
+            &texture_creator,
+            &self.input,
+            window_size,
+            self.game_config.rules().allow_manual_theme_change(),
+            themes.theme().menu_style(),
+        )?;
+        let mut level_stats: Vec<Option<LevelStatsScreen>> =
+            (0..self.game_config.players()).map(|_| None).collect();
+        // lifetime statistics & achievements are of player 1 and not recorded for the demo
+        let mut player_stats = if demo.is_none() {
+            Some(PlayerStats::load(&self.profile)?)
+        } else {
+            None
+        };
+        let mut achievements = if demo.is_none() {
+            Some(Achievements::new(
+                AchievementStore::load(&self.profile)?,
+                self.game_config.is_single_player(),
+            ))
+        } else {
+            None
+        };
+        let mut achievement_toasts =
+            AchievementToasts::new(&self.ttf, &texture_creator, window_size.1)?;
+        let mut restart_prompt = RestartPrompt::new(&self.ttf, &texture_creator, window_size.1)?;
+        let overtime_banner = OvertimeBanner::new(&self.ttf, &texture_creator, window_size.1)?;
+        let mut cue_toasts = CueToasts::new(
+            &self.ttf,
+            &texture_creator,
+            window_size.1,
+            self.config.accessibility.audio_cues,
+        )?;
+
+        let cheats = self.cheats.cheats();
+        let debug_font = FontType::Mono.load(&self.ttf, 16)?;
+        // toggled in game, the cheat only sets whether it starts on
+        let mut debug_overlay = cheats.debug_overlay;
+        // rendered again only when the sampled text changes
+        let mut debug_text = String::new();
+        let mut debug_texture: Option<FontTexture> = None;
+        let mut since_debug_text = DEBUG_OVERLAY_PERIOD;
+        let mut input_latency = InputLatency::default();
+        let mut elapsed = Duration::ZERO;
 
-        let mut frame_rate = FrameRate::new();
+        let mut frame_rate = self.game_frame_rate();
+        let mut fixed_timestep = FixedTimestep::new();
 
         for player in 0..self.game_config.players() {
             let viruses = fixture.player(player).game().viruses();
@@ -593,298 +1868,745 @@ impl DrRustario {
 
         fg_particles.clear();
         bg_particles.clear();
-        bg_particles.add_source(self.orbit_particle_source());
+        if let Some(source) = self.scene_particle_source(&themes) {
+            bg_particles.add_source(source);
+        }
 
-        themes.theme().audio().play_game_music()?;
+        // the music select picks a track for every level the leading player reaches
+        let mut music_track = self.game_config.music().track(0);
+        themes.theme().audio().play_game_music(music_track)?;
 
-        let mut max_virus_level = self.game_config.virus_level();
+        pan_player_channels(self.game_config.players())?;
+        let mut ducking = MusicDucking::default();
+        let mut event_bus = EventBus::game();
+        if let Some(telemetry) = self.config.telemetry.as_ref() {
+            event_bus = event_bus.with_sink(TelemetrySink::new(telemetry)?);
+        }
+        let mut max_completed_levels = 0;
+        // by player 1 in the levels before the current one, for the game over summary
+        let mut viruses_cleared = 0;
+        // paused automatically when the window lost focus, resumed when it is regained
+        let mut focus_paused = false;
 
         loop {
-            let delta = frame_rate.update()?;
+            let mut delta = frame_rate.update()?;
+            elapsed += delta;
+            // the jingles play in real time, even when paused
+            ducking.update(delta, &self.config.audio);
             fixture.unset_flags();
 
             let mut to_emit_particles: Vec<PlayerTargetedParticles> = vec![];
 
             let mut events = vec![];
-            for key in inputs.update(delta, self.event_pump.poll_iter()) {
-                if let Some(player) = key.player() {
-                    if themes.current().is_pause_required_for_animation(player) {
-                        if themes.maybe_dismiss_next_level_interstitial(player) {
-                            let game = fixture.player_mut(player).game_mut();
-                            game.next_level()?;
-
-                            let next_level = game.virus_level();
-                            let is_first_to_next_level = next_level > max_virus_level;
-                            max_virus_level = next_level;
-
-                            if self.game_config.themes() == MatchThemes::All {
-                                if is_first_to_next_level {
-                                    events.push(GameEvent::NextTheme);
+            // player keys, applied between the steps of the game they were pressed in
+            let mut step_keys: Vec<TimedKey> = vec![];
+            {
+                profile_scope!("input");
+                let was_paused = fixture.state().is_paused();
+                let keys = match (demo.as_mut(), tape.as_deref_mut()) {
+                    (None, Some(tape)) if tape.is_playing() => {
+                        // any key stops the playback
+                        for event in self.events.poll(&mut self.canvas)? {
+                            match event {
+                                Event::Quit { .. } => return Ok(PostGameAction::Quit),
+                                Event::KeyDown { .. } => return Ok(PostGameAction::ReturnToMenu),
+                                _ => {}
+                            }
+                        }
+                        match tape.next_frame() {
+                            Some(frame) => {
+                                // the recorded delta keeps the game logic frame accurate
+                                delta = frame.delta;
+                                frame.keys
+                            }
+                            None => return Ok(PostGameAction::ReturnToMenu),
+                        }
+                    }
+                    (None, _) => {
+                        // pause menu actions are sent as keys so that they are recorded
+                        let mut keys = vec![];
+                        let sdl_events: Vec<Event> = self.events.poll(&mut self.canvas)?;
+                        for event in sdl_events.iter() {
+                            match event {
+                                Event::Window {
+                                    win_event: WindowEvent::FocusLost,
+                                    ..
+                                } if fixture.state().is_normal() => {
+                                    keys.push(TimedKey::new(GameInputKey::Pause));
+                                    focus_paused = true;
+                                }
+                                Event::Window {
+                                    win_event: WindowEvent::FocusGained,
+                                    ..
+                                } if focus_paused => {
+                                    focus_paused = false;
+                                    if fixture.state().is_paused() {
+                                        // start the resume countdown
+                                        keys.push(TimedKey::new(GameInputKey::Pause));
+                                    }
                                 }
-                            } else if self.game_config.is_single_player() {
-                                // only start game music here if on single player and not switching themes
-                                themes.theme().audio().play_game_music()?;
+                                _ => {}
                             }
-                            themes.animate_next_level(player, game.viruses().as_slice());
+                        }
+                        let mut is_menu_input = false;
+                        if was_paused {
+                            let menu_keys = menu_inputs.parse(sdl_events.iter().cloned());
+                            is_menu_input = !menu_keys.is_empty();
+                            for key in menu_keys {
+                                if key == MenuInputKey::Quit {
+                                    return Ok(PostGameAction::Quit);
+                                }
+                                match paused_screen.read_key(key) {
+                                    None => self.menu_sound.play_chime()?,
+                                    Some(PauseAction::Resume) => {
+                                        keys.push(TimedKey::new(GameInputKey::Pause))
+                                    }
+                                    Some(PauseAction::Restart) => {
+                                        return Ok(PostGameAction::Restart)
+                                    }
+                                    Some(PauseAction::NextTheme) => {
+                                        keys.push(TimedKey::new(GameInputKey::NextTheme))
+                                    }
+                                    Some(PauseAction::ReturnToMenu) => {
+                                        return Ok(PostGameAction::ReturnToMenu)
+                                    }
+                                }
+                            }
+                            if let Some(point) = menu_inputs.take_hover() {
+                                paused_screen.hover(point);
+                            }
+                        }
+                        let polled_at = self.events.polled_at();
+                        let game_keys = inputs.update(delta, polled_at, sdl_events.into_iter());
+                        if was_paused {
+                            // the pause menu owns the keys it reads e.g. escape resumes rather than
+                            // also returning to the menu, a pause key it doesn't read still resumes
+                            keys.extend(game_keys.into_iter().filter(|k| {
+                                k.key == GameInputKey::Quit
+                                    || (k.key == GameInputKey::Pause && !is_menu_input)
+                            }));
                         } else {
-                            themes.maybe_dismiss_game_over();
+                            keys.extend(game_keys);
                         }
-                        // animating, ignore all player game input
-                        continue;
+                        keys
                     }
+                    (Some(demo), _) => {
+                        // any key returns to the menu
+                        for event in self.events.poll(&mut self.canvas)? {
+                            match event {
+                                Event::Quit { .. } => return Ok(PostGameAction::Quit),
+                                Event::KeyDown { .. } => return Ok(PostGameAction::ReturnToMenu),
+                                _ => {}
+                            }
+                        }
+                        if elapsed >= DEMO_DURATION {
+                            return Ok(PostGameAction::ReturnToMenu);
+                        }
+                        demo.update(delta, fixture.player(0).game())
+                            .into_iter()
+                            .map(TimedKey::new)
+                            .collect()
+                    }
+                };
+                if let Some(tape) = tape.as_deref_mut().filter(|t| !t.is_playing()) {
+                    tape.push(delta, &keys);
                 }
+                for timed_key in keys {
+                    let key = timed_key.key;
+                    if let Some(player) = key.player() {
+                        // both players of a co-op game share the one bottle
+                        let player = if self.game_config.coop() { 0 } else { player };
+                        if was_paused {
+                            // player keys are navigating the pause menu
+                            continue;
+                        }
+                        if fixture.state().is_resuming() {
+                            // ignore input until the countdown completes
+                            continue;
+                        }
+                        if themes.current().is_pause_required_for_animation(player) {
+                            if themes.maybe_dismiss_next_level_interstitial(player) {
+                                level_stats[player as usize] = None;
+                                let game = fixture.player_mut(player).game_mut();
+                                game.next_level()?;
+
+                                let completed_levels = game.completed_levels();
+                                let is_first_to_next_level = completed_levels > max_completed_levels;
+                                max_completed_levels = max_completed_levels.max(completed_levels);
+                                if is_first_to_next_level {
+                                    music_track =
+                                        self.game_config.music().track(max_completed_levels);
+                                }
 
-                match key {
-                    GameInputKey::MoveLeft { player } => fixture.mut_game(player, |g| g.left()),
-                    GameInputKey::MoveRight { player } => fixture.mut_game(player, |g| g.right()),
-                    GameInputKey::SoftDrop { player } => {
-                        fixture.mut_game(player, |g| g.set_soft_drop(true))
-                    }
-                    GameInputKey::HardDrop { player } => {
-                        fixture.mut_game(player, |g| g.hard_drop())
-                    }
-                    GameInputKey::RotateClockwise { player } => {
-                        fixture.mut_game(player, |g| g.rotate(true))
-                    }
-                    GameInputKey::RotateAnticlockwise { player } => {
-                        fixture.mut_game(player, |g| g.rotate(false))
-                    }
-                    GameInputKey::Hold { player } => fixture.mut_game(player, |g| g.hold()),
-                    GameInputKey::Pause => {
-                        if matches!(fixture.state(), MatchState::Normal | MatchState::Paused) {
-                            fixture.toggle_paused().map(|e| events.push(e));
-                        } else {
-                            return Ok(PostGameAction::ReturnToMenu);
+                                if self.game_config.themes() == MatchThemes::All {
+                                    if is_first_to_next_level {
+                                        events.push(GameEvent::NextTheme);
+                                    }
+                                } else if self.game_config.is_single_player() {
+                                    // only start game music here if on single player and not switching themes
+                                    themes.theme().audio().play_game_music(music_track)?;
+                                }
+                                themes.animate_next_level(player, game.viruses().as_slice());
+                            } else {
+                                themes.maybe_dismiss_game_over();
+                            }
+                            // animating, ignore all player game input
+                            continue;
                         }
                     }
-                    GameInputKey::ReturnToMenu => return Ok(PostGameAction::ReturnToMenu),
-                    GameInputKey::Quit => return Ok(PostGameAction::Quit),
-                    GameInputKey::NextTheme => {
-                        if self.game_config.rules().allow_manual_theme_change() {
-                            events.push(GameEvent::NextTheme)
+
+                    match key {
+                        GameInputKey::Pause => {
+                            if matches!(
+                                fixture.state(),
+                                MatchState::Normal
+                                    | MatchState::Paused
+                                    | MatchState::Resuming { .. }
+                                    | MatchState::Overtime { .. }
+                            ) {
+                                paused_screen.reset();
+                                fixture.toggle_paused().map(|e| events.push(e));
+                            } else {
+                                return Ok(PostGameAction::ReturnToMenu);
+                            }
+                        }
+                        GameInputKey::ReturnToMenu => return Ok(PostGameAction::ReturnToMenu),
+                        GameInputKey::Restart => {
+                            let in_progress = !fixture.state().is_game_over();
+                            if restart_prompt.press(in_progress) {
+                                return Ok(PostGameAction::Restart);
+                            }
+                        }
+                        GameInputKey::Quit => return Ok(PostGameAction::Quit),
+                        GameInputKey::NextTheme => {
+                            if self.game_config.rules().allow_manual_theme_change() {
+                                events.push(GameEvent::NextTheme)
+                            }
+                        }
+                        GameInputKey::ToggleCrtFilter => crt_filter.toggle(),
+                        GameInputKey::ToggleDebugOverlay => debug_overlay = !debug_overlay,
+                        GameInputKey::Screenshot => take_screenshot = true,
+                        GameInputKey::RecordGif => match capture.save_gif() {
+                            Ok(Some(path)) => println!("saving gif: {}", path.display()),
+                            Ok(None) => println!("no gif to save, is capture.gif on?"),
+                            Err(error) => println!("failed to save gif: {}", error),
+                        },
+                        GameInputKey::ToggleMute => {
+                            self.config.audio.muted = !self.config.audio.muted;
+                            self.config.audio.apply_ducked(ducking.is_ducked());
+                        }
+                        GameInputKey::Rewind => fixture.mut_game(0, |g| {
+                            g.rewind();
+                        }),
+                        GameInputKey::SaveState { slot } => fixture.mut_game(0, |g| {
+                            g.save_state(slot);
+                        }),
+                        GameInputKey::LoadState { slot } => fixture.mut_game(0, |g| {
+                            g.load_state(slot);
+                        }),
+                        _ => {
+                            input_latency.press(key, delta.saturating_sub(timed_key.offset));
+                            step_keys.push(timed_key);
                         }
                     }
                 }
             }
 
-            match fixture.state() {
-                MatchState::GameOver {
-                    high_score: Some(high_score),
-                } if themes.is_all_post_game_animation_complete() => {
-                    // start high score entry
-                    return Ok(PostGameAction::NewHighScore(high_score));
-                }
-                MatchState::GameOver { high_score } if themes.is_any_game_over_dismissed() => {
-                    return if let Some(high_score) = high_score {
-                        // start high score entry
-                        Ok(PostGameAction::NewHighScore(high_score))
-                    } else {
-                        Ok(PostGameAction::ReturnToMenu)
-                    };
+            if self.events.is_resized() {
+                // the fonts borrow self, so the layout is reset field by field
+                let window_size = self.events.reset_layout(&mut self.canvas)?;
+                self.particle_scale = particles::scale::Scale::new(window_size);
+                fg_particles.set_scale(self.particle_scale);
+                bg_particles.set_scale(self.particle_scale);
+                themes.resize(&texture_creator, window_size, self.config.video)?;
+                crt_filter = CrtFilter::new(
+                    &texture_creator,
+                    self.canvas.output_size()?,
+                    crt_filter.is_enabled(),
+                )?;
+                paused_screen = PausedScreen::new(
+                    &mut self.canvas,
+                    &self.ttf,
+                    &texture_creator,
+                    &self.input,
+                    window_size,
+                    self.game_config.rules().allow_manual_theme_change(),
+                    themes.theme().menu_style(),
+                )?;
+            }
+
+            // game logic either runs once per frame or in fixed steps, animations always per frame
+            let game_deltas = if self.config.video.fixed_timestep {
+                vec![FixedTimestep::STEP; fixed_timestep.steps(delta) as usize]
+            } else {
+                vec![delta]
+            };
+
+            {
+                profile_scope!("update");
+                if let Some(event) = fixture.update(delta) {
+                    events.push(event);
                 }
-                MatchState::Normal if !themes.is_fading() => {
-                    for player in fixture.players.iter_mut() {
-                        if themes
-                            .current()
-                            .is_pause_required_for_animation(player.player())
-                        {
-                            continue;
+                match fixture.state() {
+                    MatchState::GameOver { .. }
+                        if demo.is_some() && themes.is_all_post_game_animation_complete() =>
+                    {
+                        // demo games never record a high score
+                        return Ok(PostGameAction::ReturnToMenu);
+                    }
+                    MatchState::GameOver { .. }
+                        if !fixture.is_series_decided()
+                            && (themes.is_all_post_game_animation_complete()
+                                || themes.is_any_game_over_dismissed()) =>
+                    {
+                        // on to the next game of the series with fresh themes & bottles
+                        fixture.next_game();
+                        themes = self.theme_context(all_themes, &texture_creator)?;
+                        for player in 0..self.game_config.players() {
+                            let viruses = fixture.player(player).game().viruses();
+                            themes.animate_next_level(player, viruses.as_slice());
                         }
+                        level_stats.iter_mut().for_each(|stats| *stats = None);
+                        fg_particles.clear();
+                        max_completed_levels = 0;
+                        music_track = self.game_config.music().track(0);
+                        themes.theme().audio().play_game_music(music_track)?;
+                    }
+                    MatchState::GameOver { high_score }
+                        if (high_score.is_some()
+                            && themes.is_all_post_game_animation_complete())
+                            || themes.is_any_game_over_dismissed() =>
+                    {
+                        let player = fixture.player(0);
+                        let game = player.game();
+                        let summary = GameOverSummary {
+                            won: player.is_winner(),
+                            score: game.metrics().score(),
+                            virus_level: game.virus_level(),
+                            viruses_cleared: viruses_cleared + game.stats().viruses_cleared(),
+                        };
+                        return self.post_match_action(
+                            high_score,
+                            summary,
+                            all_themes,
+                            bg_particles,
+                        );
+                    }
+                    MatchState::Normal if !themes.is_fading() => {
+                        // a last pass without a step consumes the events of the final keys
+                        let steps = game_deltas.len();
+                        // players skip the rest of the frame once animating e.g. a hard drop
+                        let mut is_updating = vec![true; fixture.players.len()];
+                        for step in 0..=steps {
+                            let (due, later): (Vec<TimedKey>, Vec<TimedKey>) = step_keys
+                                .into_iter()
+                                .partition(|k| k.step(delta, steps) <= step);
+                            step_keys = later;
+                            for key in due {
+                                fixture.apply_key(key.key);
+                            }
 
-                        let mut skip_update = false;
-                        let game = player.game_mut();
-                        game.consume_events(&mut events);
-                        // pre-update actions
-                        for event in events.iter() {
-                            match event {
-                                GameEvent::HardDrop {
-                                    player,
-                                    vitamins,
-                                    dropped_rows,
-                                } => {
-                                    themes.animate_hard_drop(*player, *vitamins, *dropped_rows);
-                                    skip_update = true;
+                            for (index, player) in fixture.players.iter_mut().enumerate() {
+                                if !is_updating[index]
+                                    || themes
+                                        .current()
+                                        .is_pause_required_for_animation(player.player())
+                                {
+                                    is_updating[index] = false;
+                                    continue;
                                 }
-                                _ => {}
-                            }
-                        }
 
-                        if !skip_update {
-                            game.update(delta);
-                            game.consume_events(&mut events);
+                                let game = player.game_mut();
+                                let consumed = events.len();
+                                game.consume_events(&mut events);
+                                // pre-update actions
+                                for event in events[consumed..].iter() {
+                                    if let GameEvent::HardDrop {
+                                        player,
+                                        vitamins,
+                                        dropped_rows,
+                                    } = event
+                                    {
+                                        themes.animate_hard_drop(*player, *vitamins, *dropped_rows);
+                                        is_updating[index] = false;
+                                    }
+                                }
+
+                                if let Some(game_delta) =
+                                    game_deltas.get(step).filter(|_| is_updating[index])
+                                {
+                                    game.update(*game_delta);
+                                    game.consume_events(&mut events);
+                                }
+                            }
                         }
                     }
+                    _ => {}
+                }
+                // e.g. while paused or fading between themes
+                for key in step_keys {
+                    fixture.apply_key(key.key);
                 }
-                _ => {}
             }
 
+            restart_prompt.update(delta);
+
             // update animations
-            if !fixture.state().is_paused() {
-                let animation_events = themes.update_animations(delta);
-                for event in animation_events.into_iter() {
-                    match event {
-                        AnimationEvent::Finished { player, animation }
-                            if animation == AnimationType::Throw =>
-                        {
-                            events.push(GameEvent::Spawned { player });
+            {
+                profile_scope!("animations");
+                if !fixture.state().is_suspended() {
+                    let animation_events = themes.update_animations(delta);
+                    cue_toasts.update(delta);
+                    achievement_toasts.update(delta);
+                    for event in animation_events.into_iter() {
+                        match event {
+                            AnimationEvent::Finished { player, animation }
+                                if animation == AnimationType::Throw =>
+                            {
+                                events.push(GameEvent::Spawned { player });
+                            }
+                            _ => {}
                         }
-                        _ => {}
                     }
                 }
             }
 
             // post-update events
-            for event in events {
-                themes.theme().audio().receive_event(event.clone())?;
-                if let Some(emit) = themes
-                    .theme()
-                    .scene(self.game_config.speed())
-                    .emit_particles(event.clone())
-                {
-                    to_emit_particles.push(emit);
-                }
-                match event {
-                    GameEvent::LevelComplete { player } => {
-                        if fixture.next_level_ends_match(player) {
-                            fixture.set_winner(player);
-                        } else {
-                            if self.game_config.is_single_player() {
-                                themes.theme().audio().play_next_level_music()?;
+            {
+                profile_scope!("events");
+                for event in events {
+                    input_latency.receive_event(&event);
+                    event_bus.dispatch(
+                        &event,
+                        &mut EventContext {
+                            themes: &mut themes,
+                            fixture: &fixture,
+                            speed: self.game_config.speed(),
+                            profile: &self.profile,
+                            audio: &self.config.audio,
+                            ducking: &mut ducking,
+                            cue_toasts: &mut cue_toasts,
+                            achievements: &mut achievements,
+                            achievement_toasts: &mut achievement_toasts,
+                            player_stats: &mut player_stats,
+                            particles: &mut to_emit_particles,
+                            rumble: self.events.rumble_mut(),
+                        },
+                    )?;
+                    match event {
+                        GameEvent::LevelComplete { player } => {
+                            if fixture.next_level_ends_match(player) {
+                                fixture.set_winner(player);
                             } else {
-                                themes.theme().audio().play_next_level_jingle()?;
+                                if self.game_config.is_single_player() {
+                                    themes.theme().audio().play_next_level_music()?;
+                                } else {
+                                    themes.theme().audio().play_next_level_jingle()?;
+                                }
+                                let game = fixture.player(player).game();
+                                let preview = game.next_level_preview()?;
+                                themes.animate_next_level_interstitial(player, &preview);
+                                if player == 0 {
+                                    viruses_cleared += game.stats().viruses_cleared();
+                                }
+                                level_stats[player as usize] = Some(LevelStatsScreen::new(
+                                    &mut self.canvas,
+                                    &self.ttf,
+                                    &texture_creator,
+                                    themes.player_stats_snip(player),
+                                    game.virus_level(),
+                                    game.stats(),
+                                    &preview,
+                                )?);
                             }
-                            themes.animate_next_level_interstitial(player);
                         }
-                    }
-                    GameEvent::GameOver { player } => {
-                        if self.game_config.is_single_player() {
-                            // single player is a simple game over
-                            themes.animate_game_over(player);
-                            fixture.maybe_set_game_over();
-                            themes.theme().audio().play_game_over_music()?;
-                        } else {
-                            for maybe_winner in 0..self.game_config.players() {
-                                if maybe_winner != player {
-                                    fixture.set_winner(maybe_winner);
+                        GameEvent::GameOver { player } => {
+                            if self.game_config.is_single_player() {
+                                // single player is a simple game over
+                                themes.animate_game_over(player);
+                                if fixture.maybe_set_game_over() {
+                                    if let Some(player_stats) = player_stats.as_mut() {
+                                        player_stats.record_match(
+                                            &self.game_config.rules().name(),
+                                            fixture.player(0).game().stats(),
+                                            false,
+                                        );
+                                        player_stats.save(&self.profile)?;
+                                    }
+                                    if let Some(today) = self.daily {
+                                        let game = fixture.player(0).game();
+                                        let mut daily = DailyTable::load(&self.profile)?;
+                                        daily.record(
+                                            today,
+                                            game.metrics().score(),
+                                            game.completed_levels(),
+                                        );
+                                        daily.save(&self.profile)?;
+                                    }
+                                }
+                                themes.theme().audio().play_game_over_music()?;
+                            } else {
+                                for maybe_winner in 0..self.game_config.players() {
+                                    if maybe_winner != player {
+                                        fixture.set_winner(maybe_winner);
+                                    }
                                 }
                             }
                         }
+                        GameEvent::SendGarbage { player, garbage } => {
+                            fixture.send_garbage(player, garbage);
+                        }
+                        GameEvent::SaveState { .. } => self.menu_sound.play_chime()?,
+                        GameEvent::NextTheme => {
+                            themes.fade_into_next_theme(
+                                &mut self.canvas,
+                                fixture.state(),
+                                self.game_config.is_single_player(),
+                                music_track,
+                            )?;
+                            bg_particles.clear();
+                            if let Some(source) = self.scene_particle_source(&themes) {
+                                bg_particles.add_source(source);
+                            }
+                        }
+                        _ => {}
                     }
-                    GameEvent::Destroy { player, blocks, .. } => {
-                        themes.animate_destroy(player, blocks);
-                    }
-                    GameEvent::SendGarbage { player, garbage } => {
-                        fixture.send_garbage(player, garbage);
-                    }
-                    GameEvent::Lock {
-                        player,
-                        vitamins,
-                        hard_or_soft_dropped,
-                    } => {
-                        if hard_or_soft_dropped {
-                            themes.animate_impact(player);
-                        }
-                        themes.animate_lock(player, vitamins);
-                    }
-                    GameEvent::Spawn {
-                        player,
-                        shape,
-                        is_hold,
-                        ..
-                    } => {
-                        themes.animate_spawn(player, shape, is_hold);
-                    }
-                    GameEvent::NextTheme => {
-                        themes.fade_into_next_theme(
-                            &mut self.canvas,
-                            fixture.state(),
-                            self.game_config.is_single_player(),
-                        )?;
-                    }
-                    _ => {}
                 }
-            }
 
-            // check for a match winner
-            if let Some(winner) = fixture.check_for_winning_player() {
-                if fixture.maybe_set_game_over() {
-                    themes.animate_victory(winner);
-                    let event = GameEvent::Victory { player: winner };
-                    if let Some(emit) = themes
-                        .theme()
-                        .scene(self.game_config.speed())
-                        .emit_particles(event)
-                    {
-                        to_emit_particles.push(emit);
+                // a drawn vs. game goes to sudden death
+                if fixture.is_draw() {
+                    fixture.start_overtime()?;
+                    for player in 0..self.game_config.players() {
+                        let viruses = fixture.player(player).game().viruses();
+                        themes.animate_next_level(player, viruses.as_slice());
                     }
-                    for pid in 0..self.game_config.players() {
-                        if pid != winner {
-                            themes.animate_game_over(pid);
+                    level_stats.iter_mut().for_each(|stats| *stats = None);
+                }
+
+                // check for a match winner
+                if let Some(winner) = fixture.check_for_winning_player() {
+                    if fixture.maybe_set_game_over() {
+                        if let Some(player_stats) = player_stats.as_mut() {
+                            player_stats.record_match(
+                                &self.game_config.rules().name(),
+                                fixture.player(0).game().stats(),
+                                winner == 0,
+                            );
+                            player_stats.save(&self.profile)?;
                         }
+                        fixture.record_series_win(winner);
+                        if demo.is_none()
+                            && !self.game_config.is_single_player()
+                            && fixture.is_series_decided()
+                        {
+                            self.session.record_win(self.game_config, winner);
+                        }
+                        themes.animate_victory(winner);
+                        event_bus.dispatch(
+                            &GameEvent::Victory { player: winner },
+                            &mut EventContext {
+                                themes: &mut themes,
+                                fixture: &fixture,
+                                speed: self.game_config.speed(),
+                                profile: &self.profile,
+                                audio: &self.config.audio,
+                                ducking: &mut ducking,
+                                cue_toasts: &mut cue_toasts,
+                                achievements: &mut achievements,
+                                achievement_toasts: &mut achievement_toasts,
+                                player_stats: &mut player_stats,
+                                particles: &mut to_emit_particles,
+                                rumble: self.events.rumble_mut(),
+                            },
+                        )?;
+                        for pid in 0..self.game_config.players() {
+                            if pid != winner {
+                                themes.animate_game_over(pid);
+                            }
+                        }
+                        themes.theme().audio().play_victory_music()?;
                     }
-                    themes.theme().audio().play_victory_music()?;
                 }
             }
 
             // update particles
-            if !fixture.state().is_paused() {
-                fg_particles.update(delta);
+            {
+                profile_scope!("particles");
+                if !fixture.state().is_suspended() {
+                    fg_particles.update(delta);
 
-                if themes.render_scene_particles() {
-                    bg_particles.update(delta);
+                    if themes.render_scene_particles(self.game_config.speed()) {
+                        bg_particles.update(delta);
+                    }
+                }
+                for emit in to_emit_particles.into_iter() {
+                    fg_particles.add_source(emit.into_source(&themes, &self.particle_scale));
                 }
-            }
-            for emit in to_emit_particles.into_iter() {
-                fg_particles.add_source(emit.into_source(&themes, &self.particle_scale));
             }
 
             // clear
-            self.canvas.set_draw_color(Color::BLACK); // TODO
-            self.canvas.clear();
+            {
+                profile_scope!("draw");
+                self.canvas.set_draw_color(Color::BLACK); // TODO
+                self.canvas.clear();
 
-            // draw scene
-            themes.draw_scene(&mut self.canvas, self.game_config.speed())?;
-
-            // draw bg particles
-            if themes.render_scene_particles() {
-                bg_particles.draw(&mut self.canvas)?;
-            }
-
-            // draw the game
-            self.canvas
-                .with_multiple_texture_canvas(
-                    texture_refs.iter(),
-                    |texture_canvas, texture_mode| match texture_mode {
-                        TextureMode::Background(player_id) => {
-                            let player = fixture.player(*player_id);
-                            let animations = themes.player_animations(*player_id);
-                            themes
-                                .theme()
-                                .draw_background(texture_canvas, &player.game(), animations)
-                                .unwrap();
-                        }
-                        TextureMode::Bottle(player_id) => {
-                            let player = fixture.player(*player_id);
-                            let animations = themes.player_animations(*player_id);
-                            themes
-                                .theme()
-                                .draw_bottle(texture_canvas, &player.game(), animations)
-                                .unwrap();
-                        }
-                    },
-                )
-                .map_err(|e| e.to_string())?;
+                // draw scene
+                themes.draw_scene(&mut self.canvas, self.game_config.speed())?;
 
-            themes.draw_players(&mut self.canvas, &mut texture_refs, delta)?;
+                // draw bg particles
+                if themes.render_scene_particles(self.game_config.speed()) {
+                    bg_particles.draw(&mut self.canvas)?;
+                }
 
-            // fg particles
-            fg_particles.draw(&mut self.canvas)?;
+                // draw the game
+                let vitamin_tint = cheats.rainbow_pills.then(|| Cheats::rainbow_color(elapsed));
+                self.canvas
+                    .with_multiple_texture_canvas(
+                        texture_refs.iter(),
+                        |texture_canvas, texture_mode| match texture_mode {
+                            TextureMode::Background(player_id) => {
+                                let player = fixture.player(*player_id);
+                                let animations = themes.player_animations(*player_id);
+                                themes
+                                    .theme()
+                                    .draw_background(texture_canvas, &player.game(), animations, cheats)
+                                    .unwrap();
+                                themes
+                                    .theme()
+                                    .draw_series_wins(
+                                        texture_canvas,
+                                        fixture.series_wins(*player_id),
+                                        self.game_config.series_wins(),
+                                    )
+                                    .unwrap();
+                            }
+                            TextureMode::Bottle(player_id) => {
+                                let player = fixture.player(*player_id);
+                                let animations = themes.player_animations(*player_id);
+                                themes
+                                    .theme()
+                                    .draw_bottle(
+                                        texture_canvas,
+                                        player.game(),
+                                        animations,
+                                        vitamin_tint,
+                                    )
+                                    .unwrap();
+                                themes
+                                    .theme()
+                                    .draw_chain(texture_canvas, player.game().metrics(), animations)
+                                    .unwrap();
+                                themes
+                                    .theme()
+                                    .draw_score_popups(texture_canvas, animations)
+                                    .unwrap();
+                                themes
+                                    .theme()
+                                    .draw_pending_garbage(texture_canvas, player.game().metrics())
+                                    .unwrap();
+                                if let Some(seconds) = fixture.state().resume_countdown() {
+                                    themes
+                                        .theme()
+                                        .draw_countdown(texture_canvas, seconds)
+                                        .unwrap();
+                                }
+                            }
+                        },
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                themes.draw_players(&mut self.canvas, &mut texture_refs, delta)?;
+
+                // fg particles
+                fg_particles.draw(&mut self.canvas)?;
+
+                let bars = themes.letterbox_bars();
+                if !bars.is_empty() {
+                    self.canvas.set_draw_color(Color::BLACK);
+                    self.canvas.fill_rects(&bars)?;
+                }
+
+                for (player, screen) in level_stats.iter().enumerate() {
+                    if let Some(screen) = screen {
+                        screen.draw(&mut self.canvas)?;
+                        themes.draw_next_level_preview(
+                            &mut self.canvas,
+                            player as u32,
+                            screen.preview_dest(),
+                        )?;
+                    }
+                }
+                cue_toasts.draw(&mut self.canvas, |player| themes.player_stats_snip(player))?;
+                achievement_toasts.draw(&mut self.canvas)?;
+
+                if let Some(progress) = fixture.state().overtime_banner() {
+                    overtime_banner.draw(&mut self.canvas, progress)?;
+                }
+                if fixture.state().is_paused() {
+                    paused_screen.draw(&mut self.canvas)?;
+                }
+                // over the pause screen, restart can be confirmed while paused
+                restart_prompt.draw(&mut self.canvas)?;
+
+                crt_filter.draw(&mut self.canvas)?;
+
+                since_debug_text += delta;
+                if debug_overlay && since_debug_text >= DEBUG_OVERLAY_PERIOD {
+                    since_debug_text = Duration::ZERO;
+                    let fps = 1.0 / delta.as_secs_f64().max(f64::EPSILON);
+                    let latency = input_latency
+                        .average()
+                        .map(|l| format!("{:.1} ms", l.as_secs_f64() * 1000.0))
+                        .unwrap_or_else(|| "-".to_string());
+                    let text = format!(
+                        "{:.0} fps {:.2} ms {}/{} particles {} particle buffers {} input to lock",
+                        fps,
+                        delta.as_secs_f64() * 1000.0,
+                        fg_particles.live_particles() + bg_particles.live_particles(),
+                        fg_particles.max_particles() + bg_particles.max_particles(),
+                        fg_particles.pool().allocations() + bg_particles.pool().allocations(),
+                        latency
+                    );
+                    if text != debug_text {
+                        debug_texture = Some(FontTexture::from_string(
+                            &debug_font,
+                            &texture_creator,
+                            &text,
+                            Color::WHITE,
+                        )?);
+                        debug_text = text;
+                    }
+                }
+                if let Some(overlay) = debug_texture.as_ref().filter(|_| debug_overlay) {
+                    self.canvas.copy(
+                        &overlay.texture,
+                        None,
+                        Rect::new(8, 8, overlay.width, overlay.height),
+                    )?;
+                }
+            }
 
-            if fixture.state().is_paused() {
-                paused_screen.draw(&mut self.canvas)?;
+            {
+                profile_scope!("capture");
+                // failing to capture is logged rather than ending the game
+                if !fixture.state().is_suspended() {
+                    if let Err(error) = capture.update(&self.canvas, delta) {
+                        println!("failed to capture a frame: {}", error);
+                    }
+                }
+                if take_screenshot {
+                    take_screenshot = false;
+                    match capture.screenshot(&self.canvas) {
+                        Ok(path) => println!("saved screenshot: {}", path.display()),
+                        Err(error) => println!("failed to save screenshot: {}", error),
+                    }
+                }
             }
 
-            self.canvas.present();
+            {
+                profile_scope!("present");
+                self.canvas.present();
+            }
         }
     }
 }
@@ -892,13 +2614,22 @@ impl DrRustario {
 fn main() -> Result<(), String> {
     // return demo::main();
 
-    let mut dr_rustario = DrRustario::new()?;
+    let args = Args::parse(std::env::args().skip(1))?;
+    if let Some(headless) = args.headless {
+        return headless::run(headless, &Config::load()?.gameplay.modifiers);
+    }
+    if args.tui {
+        return tui::run(&Config::load()?.gameplay.modifiers);
+    }
+
+    let _profiler = Profiler::start()?;
+    let mut dr_rustario = DrRustario::new(args.input_tape)?;
     let texture_creator = dr_rustario.canvas.texture_creator();
     let all_themes = AllThemes::new(
         &mut dr_rustario.canvas,
         &texture_creator,
         &dr_rustario.ttf,
-        dr_rustario.config,
+        &dr_rustario.config,
     )?;
 
     let mut fg_particles = ParticleRender::new(
@@ -909,36 +2640,116 @@ fn main() -> Result<(), String> {
         vec![],
     )?;
 
+    let max_background_particles = if dr_rustario.config.accessibility.reduced_motion {
+        MAX_REDUCED_MOTION_BACKGROUND_PARTICLES
+    } else {
+        MAX_BACKGROUND_PARTICLES
+    };
     let mut bg_particles = ParticleRender::new(
         &mut dr_rustario.canvas,
-        Particles::new(MAX_BACKGROUND_PARTICLES),
+        Particles::new(max_background_particles),
         &texture_creator,
         dr_rustario.particle_scale,
         all_themes.all(),
     )?;
 
-    'title: loop {
-        match dr_rustario.title_menu(&all_themes, &mut bg_particles)? {
-            MainMenuAction::Start => 'select: loop {
-                match dr_rustario.main_menu(&all_themes, &mut bg_particles)? {
-                    MainMenuAction::Start => {
-                        match dr_rustario.game(&all_themes, &mut fg_particles, &mut bg_particles)? {
-                            PostGameAction::NewHighScore(high_score) => {
-                                dr_rustario.new_high_score(high_score, &mut bg_particles)?
-                            }
-                            PostGameAction::ReturnToMenu => (),
-                            PostGameAction::Quit => return Ok(()),
-                        }
+    let mut phase = Phase::Title;
+    while phase != Phase::Quit {
+        phase = match phase {
+            Phase::Title => match dr_rustario.title_menu(&all_themes, &mut bg_particles)? {
+                MainMenuAction::Start => Phase::GameMenu,
+                MainMenuAction::Daily => Phase::Daily,
+                MainMenuAction::Editor => Phase::Editor,
+                MainMenuAction::DailyHistory => {
+                    dr_rustario.daily_history(&all_themes, &mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::ViewHighScores => {
+                    dr_rustario.view_high_score(&mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::Options => {
+                    dr_rustario.options_menu(&all_themes, &mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::Manual => {
+                    dr_rustario.manual(&all_themes, &mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::Statistics => {
+                    dr_rustario.statistics(&all_themes, &mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::Achievements => {
+                    dr_rustario.achievements(&all_themes, &mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::About => {
+                    dr_rustario.about(&all_themes, &mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::NewProfile => {
+                    dr_rustario.new_profile(&all_themes, &mut bg_particles)?;
+                    Phase::Title
+                }
+                MainMenuAction::Demo => {
+                    match dr_rustario.demo(&all_themes, &mut fg_particles, &mut bg_particles)? {
+                        PostGameAction::Quit => Phase::Quit,
+                        _ => Phase::Title,
                     }
-                    MainMenuAction::Back => break 'select,
-                    MainMenuAction::Quit => return Ok(()),
-                    _ => unreachable!(),
                 }
+                MainMenuAction::Back | MainMenuAction::Quit => Phase::Quit,
+                MainMenuAction::SeedEntry => unreachable!(),
             },
-            MainMenuAction::ViewHighScores => dr_rustario.view_high_score(&mut bg_particles)?,
-            MainMenuAction::Back => break 'title,
-            MainMenuAction::Quit => return Ok(()),
-        }
+            Phase::GameMenu => match dr_rustario.main_menu(&all_themes, &mut bg_particles)? {
+                MainMenuAction::Start => Phase::Game,
+                MainMenuAction::SeedEntry => {
+                    dr_rustario.seed_entry(&all_themes, &mut bg_particles)?;
+                    Phase::GameMenu
+                }
+                MainMenuAction::Back => Phase::Title,
+                MainMenuAction::Quit => Phase::Quit,
+                _ => unreachable!(),
+            },
+            Phase::Game => match dr_rustario.game(&all_themes, &mut fg_particles, &mut bg_particles)? {
+                PostGameAction::VsResults(high_score) => {
+                    if let Some(high_score) = high_score {
+                        dr_rustario.new_high_score(high_score, &mut bg_particles)?;
+                    }
+                    match dr_rustario.vs_results(&all_themes, &mut bg_particles)? {
+                        ResultsAction::Rematch => Phase::Game,
+                        ResultsAction::ChangeSettings => Phase::GameMenu,
+                        ResultsAction::Menu => Phase::Title,
+                        ResultsAction::Quit => Phase::Quit,
+                    }
+                }
+                PostGameAction::Restart | PostGameAction::Retry => Phase::Game,
+                PostGameAction::ReturnToMenu => Phase::GameMenu,
+                PostGameAction::Quit => Phase::Quit,
+            },
+            Phase::Daily => {
+                match dr_rustario.daily_challenge(&all_themes, &mut fg_particles, &mut bg_particles)? {
+                    PostGameAction::Restart | PostGameAction::Retry => Phase::Daily,
+                    PostGameAction::Quit => Phase::Quit,
+                    _ => {
+                        dr_rustario.daily_history(&all_themes, &mut bg_particles)?;
+                        Phase::Title
+                    }
+                }
+            }
+            Phase::Editor => match dr_rustario.editor(&all_themes, &mut bg_particles)? {
+                EditorAction::Play => Phase::Practice,
+                EditorAction::Back => Phase::Title,
+            },
+            Phase::Practice => {
+                match dr_rustario.practice(&all_themes, &mut fg_particles, &mut bg_particles)? {
+                    PostGameAction::Restart | PostGameAction::Retry => Phase::Practice,
+                    PostGameAction::Quit => Phase::Quit,
+                    _ => Phase::Editor,
+                }
+            }
+            Phase::Quit => unreachable!(),
+        };
     }
     Ok(())
 }
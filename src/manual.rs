@@ -0,0 +1,341 @@
+use crate::config::{GameInputConfig, GameKey, InputConfig};
+use crate::font::{FontTexture, FontType};
+use crate::game::SAVE_STATE_SLOTS;
+use crate::menu_input::MenuInputKey;
+use crate::scene::{Scene, SceneInput};
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+
+const FONT_COLOR: Color = Color::WHITE;
+const HINT_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+const HINT: &str = "left/right: page    up/down: scroll    back: close";
+
+// all manual text lives here so it can be reviewed & translated in one place
+const RULES_TITLE: &str = "rules";
+const RULES: [&str; 11] = [
+    "clear every virus from the bottle to complete the level",
+    "line up 4 or more blocks of the same color in a row or column",
+    "viruses and pills in a line are destroyed",
+    "unsupported pill halves fall until they land",
+    "the game is over when a new pill is blocked from spawning",
+    "hold swaps the current pill with the held pill",
+    "hold cannot be used again until the next pill locks",
+    "higher virus levels start with more viruses",
+    "pills fall faster every 10 pills, or sooner with the speed up key",
+    "classic rules: nes gravity, no hold, no hard drop, pills lock on landing",
+    "hold, hard drop, ghost and lock delay can each be switched off",
+];
+
+const GARBAGE_TITLE: &str = "garbage";
+const GARBAGE: [&str; 9] = [
+    "garbage is only sent in matches with 2 or more players",
+    "clearing 2 or more lines from one pill is a combo",
+    "a combo sends one garbage block per line to your opponents",
+    "each garbage block is the color of the line that sent it",
+    "garbage lands in random free columns at the top of the bottle",
+    "garbage falls before the next pill spawns",
+    "a single line sends no garbage",
+    "with cancel garbage, a combo first cancels incoming garbage",
+    "with flush attack, a combo sends a whole row with one gap",
+];
+
+const SCORING_TITLE: &str = "scoring";
+const SCORING: [&str; 7] = [
+    "points are only scored for destroying viruses",
+    "the first virus destroyed by a pill scores",
+    "    100 at low speed, 200 at medium, 300 at high",
+    "each extra virus destroyed by the same pill doubles",
+    "pills that clear one after another build a chain",
+    "hard drops lock the pill at once and skip the spawn delay",
+    "the double score modifier doubles every score",
+];
+
+struct ManualPage<'a> {
+    title: FontTexture<'a>,
+    lines: Vec<FontTexture<'a>>,
+}
+
+/// Controls & rules reference, scrolled with up/down and paged with left/right
+pub struct ManualScreen<'a> {
+    pages: Vec<ManualPage<'a>>,
+    page_numbers: Vec<FontTexture<'a>>,
+    hint: FontTexture<'a>,
+    page: usize,
+    scroll: usize,
+    line_height: u32,
+    padding: u32,
+    window_size: (u32, u32),
+}
+
+impl<'a> ManualScreen<'a> {
+    pub fn new(
+        input: &InputConfig,
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        (window_width, window_height): (u32, u32),
+    ) -> Result<Self, String> {
+        let title_font = FontType::Retro.load(ttf, window_width / 24)?;
+        let font = FontType::Normal.load(ttf, window_height / 28)?;
+        let hint_font = FontType::Bold.load(ttf, window_height / 40)?;
+
+        let pages = manual_pages(input)
+            .into_iter()
+            .map(|(title, lines)| {
+                Ok(ManualPage {
+                    title: FontTexture::from_string(
+                        &title_font,
+                        texture_creator,
+                        &title,
+                        FONT_COLOR,
+                    )?,
+                    lines: lines
+                        .iter()
+                        .map(|line| {
+                            // sdl ttf cannot render an empty string
+                            let line = if line.is_empty() { " " } else { line.as_str() };
+                            FontTexture::from_string(&font, texture_creator, line, FONT_COLOR)
+                        })
+                        .collect::<Result<Vec<FontTexture>, String>>()?,
+                })
+            })
+            .collect::<Result<Vec<ManualPage>, String>>()?;
+
+        let page_numbers = (1..=pages.len())
+            .map(|i| {
+                FontTexture::from_string(
+                    &hint_font,
+                    texture_creator,
+                    &format!("{} / {}", i, pages.len()),
+                    HINT_COLOR,
+                )
+            })
+            .collect::<Result<Vec<FontTexture>, String>>()?;
+        let hint = FontTexture::from_string(&hint_font, texture_creator, HINT, HINT_COLOR)?;
+
+        Ok(Self {
+            pages,
+            page_numbers,
+            hint,
+            page: 0,
+            scroll: 0,
+            line_height: font.recommended_line_spacing().max(1) as u32,
+            padding: window_height / 32,
+            window_size: (window_width, window_height),
+        })
+    }
+
+    /// Returns to the first page, called each time the manual is opened
+    pub fn reset(&mut self) {
+        self.page = 0;
+        self.scroll = 0;
+    }
+
+    /// Returns true when the manual should be closed
+    pub fn read_key(&mut self, key: MenuInputKey) -> bool {
+        match key {
+            MenuInputKey::Up | MenuInputKey::Wheel { up: true } => {
+                self.scroll = self.scroll.saturating_sub(1)
+            }
+            MenuInputKey::Down | MenuInputKey::Wheel { up: false } => {
+                self.scroll = (self.scroll + 1).min(self.max_scroll())
+            }
+            MenuInputKey::Left => {
+                self.page = (self.page + self.pages.len() - 1) % self.pages.len();
+                self.scroll = 0;
+            }
+            MenuInputKey::Right | MenuInputKey::Select | MenuInputKey::Click { .. } => {
+                self.page = (self.page + 1) % self.pages.len();
+                self.scroll = 0;
+            }
+            MenuInputKey::Start | MenuInputKey::Back | MenuInputKey::Quit => return true,
+        }
+        false
+    }
+
+    fn title_bottom(&self) -> u32 {
+        self.padding * 2 + self.pages[self.page].title.height
+    }
+
+    fn footer_top(&self) -> u32 {
+        self.window_size.1 - self.padding * 2 - self.hint.height
+    }
+
+    fn visible_lines(&self) -> usize {
+        let body_height = self.footer_top().saturating_sub(self.title_bottom());
+        (body_height / self.line_height).max(1) as usize
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.pages[self.page]
+            .lines
+            .len()
+            .saturating_sub(self.visible_lines())
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let (window_width, _) = self.window_size;
+        let page = &self.pages[self.page];
+        let centered_x = |width: u32| (window_width as i32 - width as i32).max(0) / 2;
+
+        canvas.copy(
+            &page.title.texture,
+            None,
+            Rect::new(
+                centered_x(page.title.width),
+                self.padding as i32,
+                page.title.width,
+                page.title.height,
+            ),
+        )?;
+
+        // left align the body as a block in the center of the window
+        let body_width = page.lines.iter().map(|l| l.width).max().unwrap_or(0);
+        let x = centered_x(body_width);
+        let mut y = self.title_bottom() as i32;
+        for line in page.lines.iter().skip(self.scroll).take(self.visible_lines()) {
+            canvas.copy(
+                &line.texture,
+                None,
+                Rect::new(x, y, line.width, line.height),
+            )?;
+            y += self.line_height as i32;
+        }
+
+        let footer_y = (self.footer_top() + self.padding) as i32;
+        let page_number = &self.page_numbers[self.page];
+        canvas.copy(
+            &page_number.texture,
+            None,
+            Rect::new(
+                (window_width - self.padding - page_number.width) as i32,
+                footer_y,
+                page_number.width,
+                page_number.height,
+            ),
+        )?;
+        canvas.copy(
+            &self.hint.texture,
+            None,
+            Rect::new(self.padding as i32, footer_y, self.hint.width, self.hint.height),
+        )
+    }
+}
+
+impl Scene for ManualScreen<'_> {
+    type Output = ();
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<()> {
+        if self.read_key(key) {
+            SceneInput::Exit(())
+        } else {
+            SceneInput::Handled
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        ManualScreen::draw(self, canvas)
+    }
+}
+
+fn key_name(key: GameKey) -> String {
+    let keycode: Keycode = key.into();
+    keycode.name().to_lowercase()
+}
+
+fn player_controls(input: &GameInputConfig) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "move: {} / {}",
+            key_name(input.move_left),
+            key_name(input.move_right)
+        ),
+        format!(
+            "rotate: {} / {}",
+            key_name(input.rotate_anticlockwise),
+            key_name(input.rotate_clockwise)
+        ),
+        format!("soft drop: {}", key_name(input.soft_drop)),
+        format!("hard drop: {}", key_name(input.hard_drop)),
+        format!("hold: {}", key_name(input.hold)),
+    ];
+    if let Some(speed_up) = input.speed_up {
+        lines.push(format!("speed up: {}", key_name(speed_up)));
+    }
+    if input.hard_drop_preview {
+        lines.push("hold hard drop to preview, release to drop".to_string());
+    }
+    lines
+}
+
+fn controls(input: &InputConfig) -> Vec<String> {
+    let mut lines = vec![];
+    match input.player2.as_ref() {
+        None => lines.extend(player_controls(&input.player1)),
+        Some(player2) => {
+            lines.push("player 1".to_string());
+            lines.extend(player_controls(&input.player1));
+            lines.push(String::new());
+            lines.push("player 2".to_string());
+            lines.extend(player_controls(player2));
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!("pause: {}", key_name(input.pause)));
+    lines.push(format!("next theme: {}", key_name(input.next_theme)));
+    lines.push(format!("quit: {}", key_name(input.quit)));
+    lines.push(format!("crt filter: {}", key_name(input.crt_filter)));
+    lines.push(format!("mute: {}", key_name(input.mute)));
+    lines.push(format!("restart: {}", key_name(input.restart)));
+    lines.push(format!("debug overlay: {}", key_name(input.debug_overlay)));
+    lines.push(format!("screenshot: {}", key_name(input.screenshot)));
+    lines.push(format!("record gif: {}", key_name(input.record_gif)));
+    lines.push(format!("rewind (practice): {}", key_name(input.rewind)));
+    for slot in 0..SAVE_STATE_SLOTS {
+        lines.push(format!(
+            "save/load state {} (practice): {}/{}",
+            slot + 1,
+            key_name(input.save_state[slot]),
+            key_name(input.load_state[slot])
+        ));
+    }
+    lines
+}
+
+fn manual_pages(input: &InputConfig) -> Vec<(String, Vec<String>)> {
+    let page = |title: &str, lines: &[&str]| {
+        (
+            title.to_string(),
+            lines.iter().map(|l| l.to_string()).collect(),
+        )
+    };
+    vec![
+        ("controls".to_string(), controls(input)),
+        page(RULES_TITLE, &RULES),
+        page(GARBAGE_TITLE, &GARBAGE),
+        page(SCORING_TITLE, &SCORING),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn controls_include_second_player() {
+        let mut input = Config::default().input;
+        input.player2 = None;
+        let single = controls(&input);
+        assert!(!single.contains(&"player 1".to_string()));
+
+        input.player2 = Some(input.player1);
+        let versus = controls(&input);
+        assert!(versus.contains(&"player 1".to_string()));
+        assert!(versus.contains(&"player 2".to_string()));
+        assert!(versus.len() > single.len());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod sound;
+pub mod title;
 
 use crate::font::{FontTexture, FontType};
 use crate::menu_input::MenuInputKey;
@@ -6,54 +7,122 @@ use sdl2::gfx::primitives::DrawRenderer;
 
 use sdl2::pixels::Color;
 use sdl2::pixels::PixelFormatEnum::RGBA8888;
-use sdl2::rect::Rect;
+use sdl2::rect::{Point, Rect};
 use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
 use sdl2::ttf::{Font, Sdl2TtfContext};
 use sdl2::video::WindowContext;
 use crate::build_info;
 use crate::theme::helper::TextureFactory;
 
+/// Shown beside rows that open a submenu
+const SUBMENU_MARKER: &str = ">";
+/// Opacity of disabled rows
+const DISABLED_ALPHA: u8 = 0x60;
+
+/// Chime played when moving around the menus
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MenuChime {
+    #[default]
+    Modern,
+    Retro,
+}
+
+/// Look & sound of the menus, provided by the theme selected in the game menu
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MenuStyle {
+    pub font: FontType,
+    pub text_color: Color,
+    /// text of the selected row
+    pub selected_color: Color,
+    /// background of the selected row
+    pub highlight_color: Color,
+    pub chime: MenuChime,
+}
+
+impl Default for MenuStyle {
+    fn default() -> Self {
+        Self {
+            font: FontType::Retro,
+            text_color: Color::WHITE,
+            selected_color: Color::BLACK,
+            highlight_color: Color::WHITE,
+            chime: MenuChime::Modern,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MenuAction {
     Select,
     SelectList { items: Vec<String>, current: usize },
+    /// Opens a nested menu, "back" returns to the parent
+    Submenu(Vec<MenuItem>),
 }
 
 impl MenuAction {
     fn is_select(&self) -> bool {
         self == &Self::Select
     }
+
+    fn is_select_list(&self) -> bool {
+        matches!(self, Self::SelectList { .. })
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MenuItem {
     name: String,
     action: MenuAction,
+    description: Option<String>,
+    disabled: bool,
 }
 
 impl MenuItem {
     pub fn select(name: &str) -> Self {
-        Self {
-            name: name.to_string(),
-            action: MenuAction::Select,
-        }
+        Self::new(name, MenuAction::Select)
     }
 
     pub fn select_list(name: &str, items: Vec<String>, current: usize) -> Self {
+        Self::new(name, MenuAction::SelectList { items, current })
+    }
+
+    pub fn submenu(name: &str, items: Vec<MenuItem>) -> Self {
+        assert!(!items.is_empty());
+        Self::new(name, MenuAction::Submenu(items))
+    }
+
+    fn new(name: &str, action: MenuAction) -> Self {
         Self {
             name: name.to_string(),
-            action: MenuAction::SelectList { items, current },
+            action,
+            description: None,
+            disabled: false,
         }
     }
+
+    /// Text shown at the bottom of the window while the item is selected
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Disabled items are greyed out & skipped over
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
 }
 
 struct MenuRow<'a> {
+    /// the item, submenu items are held in children rather than the action
     item: MenuItem,
     name_texture: Texture<'a>,
     name_height: u32,
     name_width: u32,
     selected_texture: Texture<'a>,
     action_textures: Vec<FontTexture<'a>>,
+    description_texture: Option<FontTexture<'a>>,
+    children: Vec<MenuRow<'a>>,
 }
 
 impl<'a> MenuRow<'a> {
@@ -61,14 +130,16 @@ impl<'a> MenuRow<'a> {
         canvas: &mut WindowCanvas,
         texture_creator: &'a TextureCreator<WindowContext>,
         font: &Font,
-        item: MenuItem,
+        description_font: &Font,
+        mut item: MenuItem,
+        style: MenuStyle,
     ) -> Result<Self, String> {
         let name_texture = Self::name_texture(
             canvas,
             texture_creator,
             font,
             &item.name,
-            Color::WHITE,
+            style.text_color,
             None,
         )?;
         let selected_texture = Self::name_texture(
@@ -76,22 +147,54 @@ impl<'a> MenuRow<'a> {
             texture_creator,
             font,
             &item.name,
-            Color::BLACK,
-            Some(Color::WHITE),
+            style.selected_color,
+            Some(style.highlight_color),
         )?;
 
         let mut action_textures = vec![];
-        if let MenuAction::SelectList { items, current: _ } = &item.action {
-            for text in items {
+        let mut children = vec![];
+        match &mut item.action {
+            MenuAction::Select => {}
+            MenuAction::SelectList { items, current: _ } => {
+                for text in items {
+                    action_textures.push(FontTexture::from_string(
+                        font,
+                        texture_creator,
+                        text,
+                        style.text_color,
+                    )?);
+                }
+            }
+            MenuAction::Submenu(items) => {
                 action_textures.push(FontTexture::from_string(
                     font,
                     texture_creator,
-                    text,
-                    Color::WHITE,
+                    SUBMENU_MARKER,
+                    style.text_color,
                 )?);
+                for child in std::mem::take(items) {
+                    children.push(Self::new(
+                        canvas,
+                        texture_creator,
+                        font,
+                        description_font,
+                        child,
+                        style,
+                    )?);
+                }
             }
         }
 
+        let description_texture = match &item.description {
+            Some(text) => Some(FontTexture::from_string(
+                description_font,
+                texture_creator,
+                text,
+                style.text_color,
+            )?),
+            None => None,
+        };
+
         let name_query = name_texture.query();
         Ok(Self {
             item,
@@ -100,9 +203,20 @@ impl<'a> MenuRow<'a> {
             name_height: name_query.height,
             selected_texture,
             action_textures,
+            description_texture,
+            children,
         })
     }
 
+    /// Rebuilds the item with the current selections of all nested rows
+    fn to_item(&self) -> MenuItem {
+        let mut item = self.item.clone();
+        if let MenuAction::Submenu(items) = &mut item.action {
+            *items = self.children.iter().map(|r| r.to_item()).collect();
+        }
+        item
+    }
+
     fn max_action_width(&self) -> u32 {
         self.action_textures
             .iter()
@@ -115,9 +229,38 @@ impl<'a> MenuRow<'a> {
         match &self.item.action {
             MenuAction::Select => None,
             MenuAction::SelectList { current, .. } => Some(*current),
+            MenuAction::Submenu(_) => Some(0),
         }
     }
 
+    fn find_mut<'r>(rows: &'r mut [MenuRow<'a>], name: &str) -> Option<&'r mut MenuRow<'a>> {
+        for row in rows {
+            if row.item.name == name {
+                return Some(row);
+            }
+            if let Some(child) = Self::find_mut(&mut row.children, name) {
+                return Some(child);
+            }
+        }
+        None
+    }
+
+    /// Every row including those in submenus
+    fn flatten<'r>(rows: &'r [MenuRow<'a>]) -> Vec<&'r MenuRow<'a>> {
+        rows.iter()
+            .flat_map(|r| std::iter::once(r).chain(Self::flatten(&r.children)))
+            .collect()
+    }
+
+    /// Number of rows in the longest page of the menu
+    fn max_page_len(rows: &[MenuRow<'a>]) -> usize {
+        rows.iter()
+            .map(|r| Self::max_page_len(&r.children))
+            .max()
+            .unwrap_or(0)
+            .max(rows.len())
+    }
+
     fn name_texture(
         canvas: &mut WindowCanvas,
         texture_creator: &'a TextureCreator<WindowContext>,
@@ -177,13 +320,24 @@ impl<'a> SnippedTexture<'a> {
 
 pub struct Menu<'a> {
     rows: Vec<MenuRow<'a>>,
+    /// index of each submenu row opened to reach the current page
+    path: Vec<usize>,
+    /// rects of the rows visible on the body, from the first visible row
     row_rects: Vec<Rect>,
     current_row_id: usize,
+    /// first row of the current page visible on the body
+    scroll: usize,
     title: SnippedTexture<'a>,
+    /// false when the title is drawn by something else e.g. the title scene
+    show_title: bool,
     subtitle: Option<SnippedTexture<'a>>,
     body: SnippedTexture<'a>,
     watermark: SnippedTexture<'a>,
+    description_center: Point,
     select_list_background: Texture<'a>,
+    style: MenuStyle,
+    title_text: String,
+    subtitle_text: Option<String>,
 }
 
 impl<'a> Menu<'a> {
@@ -194,50 +348,35 @@ impl<'a> Menu<'a> {
         texture_creator: &'a TextureCreator<WindowContext>,
         title_text: String,
         subtitle_text: ST,
+        style: MenuStyle,
     ) -> Result<Self, String> {
         assert!(!menu_items.is_empty());
+        let subtitle_text = subtitle_text.into();
 
         let (window_width, window_height) = canvas.window().size();
         let font_size = window_width / 32;
-        let font = FontType::Retro.load(ttf, font_size)?;
+        let font = style.font.load(ttf, font_size)?;
+        let description_font = style.font.load(ttf, 3 * font_size / 5)?;
 
         let vertical_gutter = font_size / 3;
         let horizontal_gutter = font_size * 2;
 
         let rows: Vec<MenuRow> = menu_items
             .into_iter()
-            .map(|mi| MenuRow::new(canvas, texture_creator, &font, mi).unwrap())
+            .map(|mi| {
+                MenuRow::new(canvas, texture_creator, &font, &description_font, mi, style).unwrap()
+            })
             .collect();
 
-        let row_height = rows.iter().map(|r| r.name_height).max().unwrap();
-        let body_height =
-            row_height * rows.len() as u32 + vertical_gutter * (rows.len() - 1) as u32;
-
-        let name_width = rows.iter().map(|r| r.name_width).max().unwrap();
-        let max_action_width = rows.iter().map(|r| r.max_action_width()).max().unwrap();
-        // + body height as buffer for select list bg
-        let body_width = name_width + horizontal_gutter + max_action_width + row_height / 2;
-
-        let body_rect = Rect::from_center(
-            Rect::new(0, 0, window_width, window_height).center(),
-            body_width,
-            body_height,
-        );
-
-        let mut row_rects = vec![];
-        let mut y = 0;
-        for _ in 0..rows.len() {
-            row_rects.push(Rect::new(0, y, body_width, row_height));
-            y += row_height as i32 + vertical_gutter as i32;
-        }
-
-        let body_texture = texture_creator.create_texture_target_blended(body_width, body_height)?;
-
         let watermark_font_size = 3 * font_size / 5;
         let watermark_font = FontType::Retro.load(ttf, watermark_font_size)?;
         let watermark = format!("{} v{} by {}", build_info::PKG_NAME, build_info::PKG_VERSION, build_info::PKG_AUTHORS);
-        let watermark_texture =
-            FontTexture::from_string(&watermark_font, texture_creator, &watermark, Color::WHITE)?;
+        let watermark_texture = FontTexture::from_string(
+            &watermark_font,
+            texture_creator,
+            &watermark,
+            style.text_color,
+        )?;
         let watermark_rect = Rect::new(
             (window_width - watermark_texture.width - watermark_font_size) as i32,
             (window_height - watermark_texture.height - watermark_font_size) as i32,
@@ -246,9 +385,9 @@ impl<'a> Menu<'a> {
         );
 
         let title_font_size = window_width / 24;
-        let title_font = FontType::Retro.load(ttf, title_font_size)?;
+        let title_font = style.font.load(ttf, title_font_size)?;
         let title_texture =
-            FontTexture::from_string(&title_font, texture_creator, &title_text, Color::WHITE)?;
+            FontTexture::from_string(&title_font, texture_creator, &title_text, style.text_color)?;
         let title_rect = Rect::from_center(
             Rect::new(
                 0,
@@ -261,9 +400,9 @@ impl<'a> Menu<'a> {
             title_texture.height,
         );
 
-        let subtitle = subtitle_text.into().map(|text| {
+        let subtitle = subtitle_text.as_ref().map(|text| {
             let texture =
-                FontTexture::from_string(&font, texture_creator, &text, Color::WHITE).unwrap();
+                FontTexture::from_string(&font, texture_creator, text, style.text_color).unwrap();
             let rect = Rect::from_center(
                 Rect::new(
                     0,
@@ -278,6 +417,52 @@ impl<'a> Menu<'a> {
             SnippedTexture::new(texture.texture, rect)
         });
 
+        // descriptions sit between the body & the watermark
+        let description_height = description_font.height() as u32;
+        let description_center = Point::new(
+            window_width as i32 / 2,
+            watermark_rect.top() - (description_height / 2 + vertical_gutter) as i32,
+        );
+
+        let all_rows = MenuRow::flatten(&rows);
+        let row_height = all_rows.iter().map(|r| r.name_height).max().unwrap();
+        let name_width = all_rows.iter().map(|r| r.name_width).max().unwrap();
+        let max_action_width = all_rows.iter().map(|r| r.max_action_width()).max().unwrap();
+        // + body height as buffer for select list bg
+        let body_width = name_width + horizontal_gutter + max_action_width + row_height / 2;
+
+        // scroll when the longest page does not fit between the titles & the description
+        let body_top = subtitle
+            .as_ref()
+            .map(|s| s.snip.bottom())
+            .unwrap_or(title_rect.bottom())
+            + vertical_gutter as i32;
+        let body_bottom =
+            description_center.y() - (description_height / 2 + vertical_gutter) as i32;
+        let fit_rows = ((body_bottom - body_top).max(0) as u32 + vertical_gutter)
+            / (row_height + vertical_gutter);
+        let visible_rows = MenuRow::max_page_len(&rows).min(fit_rows as usize).max(1);
+        let body_height =
+            row_height * visible_rows as u32 + vertical_gutter * (visible_rows - 1) as u32;
+
+        let mut body_rect = Rect::from_center(
+            Rect::new(0, 0, window_width, window_height).center(),
+            body_width,
+            body_height,
+        );
+        if body_rect.top() < body_top {
+            body_rect.set_y(body_top);
+        }
+
+        let mut row_rects = vec![];
+        let mut y = 0;
+        for _ in 0..visible_rows {
+            row_rects.push(Rect::new(0, y, body_width, row_height));
+            y += row_height as i32 + vertical_gutter as i32;
+        }
+
+        let body_texture = texture_creator.create_texture_target_blended(body_width, body_height)?;
+
         let mut select_list_background = texture_creator.create_texture_target_blended(body_width, row_height)?;
         canvas
             .with_texture_canvas(&mut select_list_background, |c| {
@@ -295,34 +480,200 @@ impl<'a> Menu<'a> {
                     bottom_left.x() as i16,
                     bottom_left.y() as i16,
                     rad as i16,
-                    Color::RGBA(0xff, 0xff, 0xff, 0x80),
+                    Color::RGBA(
+                        style.highlight_color.r,
+                        style.highlight_color.g,
+                        style.highlight_color.b,
+                        0x80,
+                    ),
                 )
                 .unwrap();
             })
             .map_err(|e| e.to_string())?;
 
-        Ok(Self {
+        let mut menu = Self {
             rows,
+            path: vec![],
             row_rects,
             current_row_id: 0,
+            scroll: 0,
             title: SnippedTexture::new(title_texture.texture, title_rect),
+            show_title: true,
             subtitle,
             body: SnippedTexture::new(body_texture, body_rect),
             watermark: SnippedTexture::new(watermark_texture.texture, watermark_rect),
+            description_center,
             select_list_background,
-        })
+            style,
+            title_text,
+            subtitle_text,
+        };
+        menu.reset();
+        Ok(menu)
+    }
+
+    /// Rebuilds the menu in a new style, keeping the current row & selections
+    pub fn restyle(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        style: MenuStyle,
+    ) -> Result<(), String> {
+        let menu_items = self.rows.iter().map(|r| r.to_item()).collect();
+        let path = std::mem::take(&mut self.path);
+        let current_row_id = self.current_row_id;
+        let show_title = self.show_title;
+        *self = Self::new(
+            menu_items,
+            canvas,
+            ttf,
+            texture_creator,
+            self.title_text.clone(),
+            self.subtitle_text.clone(),
+            style,
+        )?;
+        self.show_title = show_title;
+        self.path = path;
+        self.current_row_id = current_row_id;
+        self.scroll_to_current();
+        Ok(())
+    }
+
+    /// Leaves room for the title but does not draw it
+    pub fn with_hidden_title(mut self) -> Self {
+        self.show_title = false;
+        self
+    }
+
+    /// Moves a select list to another item, for rows that follow changes made to other rows
+    pub fn set_current(&mut self, name: &str, current: usize) {
+        let action = MenuRow::find_mut(&mut self.rows, name).map(|r| &mut r.item.action);
+        if let Some(MenuAction::SelectList { current: c, .. }) = action {
+            *c = current;
+        }
+    }
+
+    /// Enables or disables a row, for rows that only apply to some values of other rows
+    pub fn set_disabled(&mut self, name: &str, disabled: bool) {
+        if let Some(row) = MenuRow::find_mut(&mut self.rows, name) {
+            row.item.disabled = disabled;
+        }
+        if self.page()[self.current_row_id].item.disabled {
+            self.down();
+        }
+    }
+
+    /// Returns to the first enabled row of the top level menu
+    pub fn reset(&mut self) {
+        self.path.clear();
+        self.current_row_id = 0;
+        self.scroll = 0;
+        if self.rows[0].item.disabled {
+            self.down();
+        }
+    }
+
+    /// Rows of the current page, the top level menu or an open submenu
+    fn page(&self) -> &[MenuRow<'a>] {
+        self.path
+            .iter()
+            .fold(&self.rows, |rows, &row_id| &rows[row_id].children)
+    }
+
+    fn page_mut(&mut self) -> &mut [MenuRow<'a>] {
+        self.path
+            .iter()
+            .fold(&mut self.rows, |rows, &row_id| &mut rows[row_id].children)
+    }
+
+    fn current_row(&self) -> &MenuRow<'a> {
+        &self.page()[self.current_row_id]
+    }
+
+    /// Row in the parent page of the open submenu
+    fn submenu_row(&self) -> Option<&MenuRow<'a>> {
+        let (&row_id, parents) = self.path.split_last()?;
+        let parent = parents
+            .iter()
+            .fold(&self.rows, |rows, &row_id| &rows[row_id].children);
+        Some(&parent[row_id])
+    }
+
+    fn open_submenu(&mut self) {
+        self.path.push(self.current_row_id);
+        self.current_row_id = 0;
+        self.scroll = 0;
+        if self.page()[0].item.disabled {
+            self.down();
+        }
+    }
+
+    /// Returns to the parent of the open submenu with the submenu row selected
+    fn close_submenu(&mut self) -> bool {
+        match self.path.pop() {
+            Some(row_id) => {
+                self.current_row_id = row_id;
+                self.scroll = 0;
+                self.scroll_to_current();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Scrolls the body just enough for the current row to be visible
+    fn scroll_to_current(&mut self) {
+        let visible_rows = self.row_rects.len();
+        if self.current_row_id < self.scroll {
+            self.scroll = self.current_row_id;
+        } else if self.current_row_id >= self.scroll + visible_rows {
+            self.scroll = self.current_row_id + 1 - visible_rows;
+        }
+    }
+
+    /// Row of the current page under a point of the window
+    fn row_at(&self, point: Point) -> Option<usize> {
+        let body = self.body.snip;
+        let point = point - body.top_left();
+        self.row_rects
+            .iter()
+            .position(|r| r.contains_point(point))
+            .map(|slot| slot + self.scroll)
+            .filter(|&row_id| row_id < self.page().len() && !self.page()[row_id].item.disabled)
+    }
+
+    /// Highlights the row under the mouse, returns true if it moved to another row
+    pub fn hover(&mut self, point: Point) -> bool {
+        match self.row_at(point) {
+            Some(row_id) if row_id != self.current_row_id => {
+                self.current_row_id = row_id;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves through the current page by some number of rows, skipping disabled rows
+    fn step(&mut self, step: isize) {
+        let len = self.page().len();
+        let mut row_id = self.current_row_id;
+        for _ in 0..len {
+            row_id = (row_id as isize + step).rem_euclid(len as isize) as usize;
+            if !self.page()[row_id].item.disabled {
+                break;
+            }
+        }
+        self.current_row_id = row_id;
+        self.scroll_to_current();
     }
 
     pub fn up(&mut self) {
-        self.current_row_id = match self.current_row_id {
-            0 => self.rows.len() - 1,
-            _ => self.current_row_id - 1,
-        };
+        self.step(-1);
     }
 
     pub fn down(&mut self) {
-        self.current_row_id += 1;
-        self.current_row_id %= self.rows.len();
+        self.step(1);
     }
 
     pub fn left(&mut self) -> Option<(&str, &str)> {
@@ -346,16 +697,32 @@ impl<'a> Menu<'a> {
             MenuInputKey::Left => self.left(),
             MenuInputKey::Right => self.right(),
             MenuInputKey::Select => self.select(),
-            // special case for pressing "start" on an action e.g. "quit" I would expect it to quit
-            MenuInputKey::Start if self.rows[self.current_row_id].item.action.is_select() => {
+            MenuInputKey::Click { x, y } => {
+                self.current_row_id = self.row_at(Point::new(x, y))?;
                 self.select()
             }
+            // the wheel changes the value of the select list under the mouse
+            MenuInputKey::Wheel { up } => self.direction(if up { 1 } else { -1 }),
+            // special case for pressing "start" on an action e.g. "quit" I would expect it to quit
+            MenuInputKey::Start if self.current_row().item.action.is_select() => self.select(),
+            // back out of a submenu rather than the whole menu
+            MenuInputKey::Back => {
+                if self.close_submenu() {
+                    Some((&self.current_row().item.name, ""))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
 
     fn direction(&mut self, direction: i32) -> Option<(&str, &str)> {
-        let row = self.rows.get_mut(self.current_row_id).unwrap();
+        let current_row_id = self.current_row_id;
+        let row = self.page_mut().get_mut(current_row_id).unwrap();
+        if row.item.disabled {
+            return None;
+        }
         let result = match &mut row.item.action {
             MenuAction::SelectList { items, current } => {
                 let current_plus = *current as i32 + direction;
@@ -373,9 +740,17 @@ impl<'a> Menu<'a> {
     }
 
     pub fn select(&mut self) -> Option<(&str, &str)> {
-        let row = self.rows.get_mut(self.current_row_id).unwrap();
+        if self.current_row().item.disabled {
+            return None;
+        }
+        if let MenuAction::Submenu(_) = self.current_row().item.action {
+            self.open_submenu();
+            return self.submenu_row().map(|r| (&r.item.name as &str, ""));
+        }
+        let current_row_id = self.current_row_id;
+        let row = self.page_mut().get_mut(current_row_id).unwrap();
         let result = match &mut row.item.action {
-            MenuAction::Select => "",
+            MenuAction::Select | MenuAction::Submenu(_) => "",
             MenuAction::SelectList { items, current } => {
                 *current = (*current + 1) % items.len();
                 &items[*current]
@@ -385,25 +760,37 @@ impl<'a> Menu<'a> {
     }
 
     pub fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
-        canvas.copy(&self.title.texture, None, self.title.snip)?;
+        if self.show_title {
+            canvas.copy(&self.title.texture, None, self.title.snip)?;
+        }
 
         if let Some(subtitle) = self.subtitle.as_ref() {
             canvas.copy(&subtitle.texture, None, subtitle.snip)?;
         }
 
+        let current_row_id = self.current_row_id;
+        let scroll = self.scroll;
+        let page = self
+            .path
+            .iter()
+            .fold(&mut self.rows, |rows, &row_id| &mut rows[row_id].children);
+        let page_len = page.len();
+        let row_rects = &self.row_rects;
+        let select_list_background = &self.select_list_background;
         canvas
             .with_texture_canvas(&mut self.body.texture, |tc| {
                 tc.set_draw_color(Color::RGBA(0, 0, 0, 0));
                 tc.clear();
 
-                for (row_id, (row, row_rect)) in
-                    self.rows.iter().zip(self.row_rects.iter()).enumerate()
+                for ((row_id, row), row_rect) in
+                    page.iter_mut().enumerate().skip(scroll).zip(row_rects.iter())
                 {
-                    let is_selected = row_id == self.current_row_id;
+                    let is_selected = row_id == current_row_id;
+                    let alpha = if row.item.disabled { DISABLED_ALPHA } else { u8::MAX };
 
                     // draw select list background
-                    if is_selected && !row.item.action.is_select() {
-                        tc.copy(&self.select_list_background, None, *row_rect)
+                    if is_selected && row.item.action.is_select_list() {
+                        tc.copy(select_list_background, None, *row_rect)
                             .unwrap();
                     }
 
@@ -411,15 +798,16 @@ impl<'a> Menu<'a> {
                     let name_rect =
                         Rect::new(row_rect.x, row_rect.y, row.name_width, row.name_height);
                     let name_texture = if is_selected {
-                        &row.selected_texture
+                        &mut row.selected_texture
                     } else {
-                        &row.name_texture
+                        &mut row.name_texture
                     };
+                    name_texture.set_alpha_mod(alpha);
                     tc.copy(name_texture, None, name_rect).unwrap();
 
                     // draw value
                     if let Some(current_action) = row.current_action_id() {
-                        let texture = &row.action_textures[current_action];
+                        let texture = &mut row.action_textures[current_action];
                         let offset_by_action_width = -1 * texture.width as i32;
                         let mut rect = Rect::from_enclose_points(
                             &[
@@ -433,6 +821,7 @@ impl<'a> Menu<'a> {
                         .unwrap();
                         // move left a bit for the bg buffer
                         rect.offset(-1 * row_rect.height() as i32 / 2, 0);
+                        texture.texture.set_alpha_mod(alpha);
                         tc.copy(&texture.texture, None, rect).unwrap();
                     }
                 }
@@ -440,6 +829,44 @@ impl<'a> Menu<'a> {
             .map_err(|e| e.to_string())?;
 
         canvas.copy(&self.body.texture, None, self.body.snip)?;
+
+        // arrows beside the first & last visible rows when there are more rows to scroll to
+        let body = self.body.snip;
+        let arrow = body.height().min(self.row_rects[0].height()) as i16 / 3;
+        let x = (body.right() + self.row_rects[0].height() as i32 / 2) as i16;
+        if scroll > 0 {
+            let y = (body.top() + self.row_rects[0].height() as i32 / 2) as i16;
+            canvas.filled_trigon(
+                x - arrow / 2,
+                y + arrow / 2,
+                x + arrow / 2,
+                y + arrow / 2,
+                x,
+                y - arrow / 2,
+                self.style.text_color,
+            )?;
+        }
+        if scroll + self.row_rects.len() < page_len {
+            let y = (body.bottom() - self.row_rects[0].height() as i32 / 2) as i16;
+            canvas.filled_trigon(
+                x - arrow / 2,
+                y - arrow / 2,
+                x + arrow / 2,
+                y - arrow / 2,
+                x,
+                y + arrow / 2,
+                self.style.text_color,
+            )?;
+        }
+
+        if let Some(description) = self.current_row().description_texture.as_ref() {
+            canvas.copy(
+                &description.texture,
+                None,
+                Rect::from_center(self.description_center, description.width, description.height),
+            )?;
+        }
+
         canvas.copy(&self.watermark.texture, None, self.watermark.snip)
     }
 }
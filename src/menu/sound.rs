@@ -1,9 +1,10 @@
 use crate::config::AudioConfig;
+use crate::menu::MenuChime;
 use crate::theme::sound::{LoadSound, Playable, StructuredMusic};
 use sdl2::mixer::Chunk;
 use std::rc::Rc;
 
-// const CHIME: &[u8] = include_bytes!("retro/chime.ogg");
+const RETRO_CHIME: &[u8] = include_bytes!("retro/chime.ogg");
 // const TITLE_INTRO: &'static [u8] = include_bytes!("retro/title-intro.ogg");
 // const TITLE_REPEAT: &'static [u8] = include_bytes!("retro/title-repeat.ogg");
 // const MENU_INTRO: &'static [u8] = include_bytes!("retro/menu-intro.ogg");
@@ -20,6 +21,8 @@ const HIGH_SCORE_REPEAT: &'static [u8] = include_bytes!("modern/high-score-repea
 
 pub struct MenuSound {
     chime: Chunk,
+    retro_chime: Chunk,
+    chime_type: MenuChime,
     select: Chunk,
     menu_music: Rc<StructuredMusic>,
     title_music: Rc<StructuredMusic>,
@@ -30,6 +33,8 @@ impl MenuSound {
     pub fn new(config: AudioConfig) -> Result<Self, String> {
         Ok(Self {
             chime: config.load_chunk(CHIME)?,
+            retro_chime: config.load_chunk(RETRO_CHIME)?,
+            chime_type: MenuChime::default(),
             select: config.load_chunk(SELECT)?,
             menu_music: StructuredMusic::repeat(MENU)?.into_rc(),
             title_music: StructuredMusic::repeat(TITLE)?.into_rc(),
@@ -37,8 +42,16 @@ impl MenuSound {
         })
     }
 
+    /// Chime of the menu style of the selected theme
+    pub fn set_chime(&mut self, chime_type: MenuChime) {
+        self.chime_type = chime_type;
+    }
+
     pub fn play_chime(&self) -> Result<(), String> {
-        self.chime.play()
+        match self.chime_type {
+            MenuChime::Modern => self.chime.play(),
+            MenuChime::Retro => self.retro_chime.play(),
+        }
     }
 
     pub fn play_select(&self) -> Result<(), String> {
@@ -0,0 +1,143 @@
+//! Animated title drawn behind the title menu: the logo bobbing over the modern dr,
+//! who idles then throws a pill on a loop, and a pulsing prompt to press start.
+
+use crate::font::FontTexture;
+use crate::menu::MenuStyle;
+use crate::theme::animation::{AnimationSpriteSheet, AnimationSpriteSheetData};
+use crate::theme::particle::sprites;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+const PROMPT: &str = "press start";
+/// Times the dr idles between each throw
+const IDLE_LOOPS: usize = 2;
+/// Time for the prompt to fade out & back in
+const PULSE_PERIOD: Duration = Duration::from_millis(1600);
+/// Time for the logo to bob up & back down
+const BOB_PERIOD: Duration = Duration::from_millis(3000);
+const MIN_PROMPT_ALPHA: f64 = 0.25;
+
+pub struct TitleScene<'a> {
+    dr_idle: AnimationSpriteSheet<'a>,
+    dr_throw: AnimationSpriteSheet<'a>,
+    dr_rect: Rect,
+    logo: FontTexture<'a>,
+    logo_rect: Rect,
+    /// distance the logo bobs above & below its rect
+    bob_height: f64,
+    prompt: FontTexture<'a>,
+    prompt_rect: Rect,
+    elapsed: Duration,
+}
+
+impl<'a> TitleScene<'a> {
+    pub fn new(
+        canvas: &mut WindowCanvas,
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        title: &str,
+        style: MenuStyle,
+    ) -> Result<Self, String> {
+        let (window_width, window_height) = canvas.window().size();
+
+        // the dr stands in the bottom left corner, beside the menu
+        let dr_size = window_height / 3;
+        let dr_rect = Rect::new(
+            (window_width / 16) as i32,
+            (window_height - dr_size - window_height / 8) as i32,
+            dr_size,
+            dr_size,
+        );
+        let scale = dr_size as f64 / sprites::SRC_DR_WIDTH as f64;
+        let mut dr_sheet = |data: AnimationSpriteSheetData| {
+            let (sheet, _) = data.sprite_sheet(texture_creator, scale)?;
+            sheet.scale(canvas, texture_creator, dr_size, dr_size)
+        };
+        let dr_idle = dr_sheet(AnimationSpriteSheetData::exclusive_table(
+            sprites::DR_IDLE,
+            12,
+            11,
+            123,
+        ))?;
+        let dr_throw = dr_sheet(AnimationSpriteSheetData::exclusive_table(
+            sprites::DR_THROW,
+            7,
+            7,
+            46,
+        ))?;
+
+        let logo_font = style.font.load(ttf, window_width / 20)?;
+        let logo = FontTexture::from_string(&logo_font, texture_creator, title, style.text_color)?;
+        let bob_height = (logo.height / 8) as f64;
+        let logo_rect = Rect::new(
+            (window_width as i32 - logo.width as i32) / 2,
+            bob_height as i32,
+            logo.width,
+            logo.height,
+        );
+
+        let prompt_font = style.font.load(ttf, window_width / 40)?;
+        let prompt =
+            FontTexture::from_string(&prompt_font, texture_creator, PROMPT, style.text_color)?;
+        let prompt_rect = Rect::from_center(
+            Point::new(window_width as i32 / 2, (7 * window_height / 8) as i32),
+            prompt.width,
+            prompt.height,
+        );
+
+        Ok(Self {
+            dr_idle,
+            dr_throw,
+            dr_rect,
+            logo,
+            logo_rect,
+            bob_height,
+            prompt,
+            prompt_rect,
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    /// Sheet & frame of the dr, idling a few times before each throw
+    fn dr_frame(&self) -> (&AnimationSpriteSheet<'a>, usize) {
+        let idle_frames = self.dr_idle.frame_count() * IDLE_LOOPS;
+        let loop_frames = idle_frames + self.dr_throw.frame_count();
+        let frame = (self.elapsed.as_secs_f64() * sprites::DR_FPS as f64) as usize % loop_frames;
+        if frame < idle_frames {
+            (&self.dr_idle, frame % self.dr_idle.frame_count())
+        } else {
+            (&self.dr_throw, frame - idle_frames)
+        }
+    }
+
+    /// Phase of a repeating animation from 0.0 to 1.0
+    fn phase(&self, period: Duration) -> f64 {
+        (self.elapsed.as_secs_f64() / period.as_secs_f64()).fract()
+    }
+
+    pub fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let (dr, frame) = self.dr_frame();
+        dr.draw_frame_scaled(canvas, self.dr_rect, frame)?;
+
+        let bob = (self.phase(BOB_PERIOD) * 2.0 * PI).sin() * self.bob_height;
+        let mut logo_rect = self.logo_rect;
+        logo_rect.offset(0, bob.round() as i32);
+        canvas.copy(&self.logo.texture, None, logo_rect)?;
+
+        // fades from fully opaque down to the min alpha & back
+        let pulse = 0.5 + 0.5 * (self.phase(PULSE_PERIOD) * 2.0 * PI).cos();
+        let alpha = MIN_PROMPT_ALPHA + (1.0 - MIN_PROMPT_ALPHA) * pulse;
+        self.prompt
+            .texture
+            .set_alpha_mod((alpha * u8::MAX as f64).round() as u8);
+        canvas.copy(&self.prompt.texture, None, self.prompt_rect)
+    }
+}
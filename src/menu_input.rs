@@ -1,9 +1,13 @@
 use crate::config::InputConfig;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::rect::Point;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::collections::HashMap;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MenuInputKey {
     Up,
     Down,
@@ -13,16 +17,23 @@ pub enum MenuInputKey {
     Select,
     Back,
     Quit,
+    /// Left mouse button pressed at a point of the window
+    Click { x: i32, y: i32 },
+    /// Mouse wheel scrolled up or down
+    Wheel { up: bool },
 }
 
 pub struct MenuInputContext {
     mapping: HashMap<Keycode, MenuInputKey>,
+    /// last point the mouse moved to, kept out of the keys so hovering is silent
+    hover: Cell<Option<Point>>,
 }
 
 impl MenuInputContext {
     pub fn new(config: InputConfig) -> Self {
         Self {
             mapping: config.menu_map(),
+            hover: Cell::new(None),
         }
     }
 
@@ -38,6 +49,17 @@ impl MenuInputContext {
                     ..
                 } => self.mapping.get(&keycode).copied(),
                 Event::Quit { .. } => Some(MenuInputKey::Quit),
+                Event::MouseMotion { x, y, .. } => {
+                    self.hover.set(Some(Point::new(x, y)));
+                    None
+                }
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => Some(MenuInputKey::Click { x, y }),
+                Event::MouseWheel { y, .. } if y != 0 => Some(MenuInputKey::Wheel { up: y > 0 }),
                 _ => None,
             };
             if let Some(key) = maybe_key {
@@ -46,4 +68,9 @@ impl MenuInputContext {
         }
         result
     }
+
+    /// Where the mouse moved to since this was last called
+    pub fn take_hover(&self) -> Option<Point> {
+        self.hover.take()
+    }
 }
@@ -0,0 +1,83 @@
+//! Banner shown across the window when a drawn vs. game goes to sudden death overtime.
+//! The banner slides in, holds while the players read it then slides out as the bottles go live.
+
+use crate::font::{FontTexture, FontType};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+
+const TITLE: &str = "overtime";
+const SUBTITLE: &str = "sudden death - first to clear wins";
+const BANNER_BACKGROUND: Color = Color::RGBA(0, 0, 0, 0xc0);
+/// Fraction of the banner spent sliding in & out
+const SLIDE: f64 = 0.15;
+
+pub struct OvertimeBanner<'a> {
+    title: FontTexture<'a>,
+    subtitle: FontTexture<'a>,
+}
+
+impl<'a> OvertimeBanner<'a> {
+    pub fn new(
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        window_height: u32,
+    ) -> Result<Self, String> {
+        let title_font = FontType::Bold.load(ttf, (window_height / 10).max(16))?;
+        let subtitle_font = FontType::Normal.load(ttf, (window_height / 30).max(8))?;
+        Ok(Self {
+            title: FontTexture::from_string(&title_font, texture_creator, TITLE, Color::WHITE)?,
+            subtitle: FontTexture::from_string(
+                &subtitle_font,
+                texture_creator,
+                SUBTITLE,
+                Color::WHITE,
+            )?,
+        })
+    }
+
+    /// Draws the banner across the middle of the window at some progress from 0.0 to 1.0
+    pub fn draw(&self, canvas: &mut WindowCanvas, progress: f64) -> Result<(), String> {
+        let (window_width, window_height) = canvas.output_size()?;
+        let padding = self.subtitle.height;
+        let height = self.title.height + self.subtitle.height + padding * 2;
+        let y = (window_height as i32 - height as i32) / 2;
+
+        // slide in from the left & out to the right
+        let offset = if progress < SLIDE {
+            -(1.0 - progress / SLIDE)
+        } else if progress > 1.0 - SLIDE {
+            (progress - 1.0 + SLIDE) / SLIDE
+        } else {
+            0.0
+        };
+        let x = (offset * window_width as f64).round() as i32;
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(BANNER_BACKGROUND);
+        canvas.fill_rect(Rect::new(x, y, window_width, height))?;
+        canvas.set_blend_mode(BlendMode::None);
+        canvas.copy(
+            &self.title.texture,
+            None,
+            Rect::new(
+                x + (window_width as i32 - self.title.width as i32) / 2,
+                y + padding as i32,
+                self.title.width,
+                self.title.height,
+            ),
+        )?;
+        canvas.copy(
+            &self.subtitle.texture,
+            None,
+            Rect::new(
+                x + (window_width as i32 - self.subtitle.width as i32) / 2,
+                y + (padding + self.title.height) as i32,
+                self.subtitle.width,
+                self.subtitle.height,
+            ),
+        )
+    }
+}
@@ -82,7 +82,7 @@ impl ParticleSprite {
     ];
     pub const HOLLOW_CIRCLES: [ParticleSprite; 4] = [Circle01, Circle02, Circle03, Circle04];
 
-    const fn theme_sprites(theme: ThemeName) -> ThemePills {
+    pub const fn theme_sprites(theme: ThemeName) -> ThemePills {
         [
             Pill(theme, PillShape::YY),
             Pill(theme, PillShape::YB),
@@ -99,6 +99,7 @@ impl ParticleSprite {
     pub const NES_PILLS: ThemePills = Self::theme_sprites(ThemeName::Nes);
     pub const SNES_PILLS: ThemePills = Self::theme_sprites(ThemeName::Snes);
     pub const N64_PILLS: ThemePills = Self::theme_sprites(ThemeName::N64);
+    pub const GAMEBOY_PILLS: ThemePills = Self::theme_sprites(ThemeName::GameBoy);
     pub const MODERN_PILLS: ThemePills = Self::theme_sprites(ThemeName::Particle);
 
     pub fn animation(&self) -> Option<ParticleAnimationType> {
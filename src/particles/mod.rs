@@ -1,3 +1,4 @@
+use crate::particles::pool::ParticlePool;
 use crate::particles::source::ParticleSource;
 use particle::{Particle, ParticleGroup};
 
@@ -7,15 +8,21 @@ pub mod color;
 pub mod geometry;
 mod meta;
 pub mod particle;
+pub mod pool;
 pub mod prescribed;
 pub mod quantity;
 pub mod render;
 pub mod scale;
 pub mod source;
 
+/// Number of particle buffers to pre-allocate, roughly the number of concurrent emissions
+const POOL_BUFFERS: usize = 64;
+const POOL_BUFFER_CAPACITY: usize = 256;
+
 pub struct Particles {
     particles: Vec<ParticleGroup>,
     sources: Vec<Box<dyn ParticleSource>>,
+    pool: ParticlePool,
     max_particles: usize,
 }
 
@@ -23,13 +30,27 @@ impl Particles {
     pub fn new(max_particles: usize) -> Self {
         Self {
             sources: vec![],
-            particles: vec![],
+            particles: Vec::with_capacity(POOL_BUFFERS),
+            pool: ParticlePool::new(POOL_BUFFERS, POOL_BUFFER_CAPACITY),
             max_particles,
         }
     }
 
-    pub fn particles(&self) -> Vec<&Particle> {
-        self.particles.iter().flat_map(|g| g.particles()).collect()
+    pub fn particles(&self) -> impl Iterator<Item = Particle> + '_ {
+        self.particles.iter().flat_map(|g| g.particles())
+    }
+
+    /// Number of live particles
+    pub fn len(&self) -> usize {
+        self.particles.iter().map(|g| g.len()).sum()
+    }
+
+    pub fn max_particles(&self) -> usize {
+        self.max_particles
+    }
+
+    pub fn pool(&self) -> &ParticlePool {
+        &self.pool
     }
 
     pub fn update(&mut self, delta: Duration) {
@@ -40,21 +61,24 @@ impl Particles {
     }
 
     pub fn clear(&mut self) {
-        self.particles.clear();
+        for group in self.particles.drain(..) {
+            self.pool.recycle(group.into_particles());
+        }
         self.sources.clear();
     }
 
     fn update_life(&mut self, delta_time: f64) {
-        let mut to_remove = vec![];
-        for (i, group) in self.particles.iter_mut().enumerate() {
+        let mut index = 0;
+        while index < self.particles.len() {
+            let group = &mut self.particles[index];
             group.update_life(delta_time);
             if group.is_empty() {
-                to_remove.push(i);
+                let group = self.particles.remove(index);
+                self.pool.recycle(group.into_particles());
+            } else {
+                index += 1;
             }
         }
-        for i in to_remove.into_iter().rev() {
-            self.particles.remove(i);
-        }
     }
 
     fn update_particles(&mut self, delta_time: f64) {
@@ -64,27 +88,32 @@ impl Particles {
     }
 
     fn emit_particles(&mut self, delta: Duration) {
-        let current_particles = self.particles.iter().map(|g| g.len()).sum::<usize>() as i32;
-        let mut max_particles = self.max_particles as i32 - current_particles;
+        let mut max_particles = self.max_particles as i32 - self.len() as i32;
 
-        let mut to_remove = vec![];
-        for (index, source) in self.sources.iter_mut().enumerate() {
+        let mut index = 0;
+        while index < self.sources.len() {
             if max_particles <= 0 {
                 return;
             }
 
-            for group in source.update(delta, max_particles as u32) {
+            let source = &mut self.sources[index];
+            let emitted_from = self.particles.len();
+            source.update(
+                delta,
+                max_particles as u32,
+                &mut self.pool,
+                &mut self.particles,
+            );
+            for group in self.particles[emitted_from..].iter() {
                 max_particles -= group.len() as i32;
-                self.particles.push(group);
             }
 
             if source.is_complete() {
-                to_remove.push(index);
+                self.sources.remove(index);
+            } else {
+                index += 1;
             }
         }
-        for index in to_remove.into_iter().rev() {
-            self.sources.remove(index);
-        }
     }
 }
 
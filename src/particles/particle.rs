@@ -1,7 +1,10 @@
 use crate::particles::color::ParticleColor;
-use crate::particles::geometry::Vec2D;
+use crate::particles::geometry::{RectF, Vec2D};
 use crate::particles::meta::ParticleSprite;
 
+/// Fraction of the speed kept by a particle bouncing off its bounds
+const RESTITUTION: f64 = 0.5;
+
 /// A particle wave modelled as a sin function magnitude * sin(frequency * lifetime)
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ParticleWave {
@@ -69,11 +72,68 @@ impl ParticleAnimation {
             animation_type,
         }
     }
+
+    fn update(&mut self, lifetime: f64) {
+        match self.animation_type {
+            ParticleAnimationType::Static => {}
+            ParticleAnimationType::Linear { frames, .. }
+            | ParticleAnimationType::YoYo { frames, .. } => {
+                self.frame = (lifetime / self.frame_duration).floor() as usize % frames;
+                self.iteration = (lifetime / (self.frame_duration * frames as f64)).floor() as u32;
+            }
+        }
+    }
+}
+
+/// checks if a particle is out of bounds (0-1) and trajectory will not bring it back
+fn is_escaped(position: Vec2D, velocity: Vec2D, acceleration: Vec2D) -> bool {
+    const THRESHOLD_MAX: f64 = 1.05;
+    const THRESHOLD_MIN: f64 = -0.05;
+    (position.x() > THRESHOLD_MAX && velocity.x() >= 0.0 && acceleration.x() >= 0.0)
+        || (position.x() < THRESHOLD_MIN && velocity.x() <= 0.0 && acceleration.x() <= 0.0)
+        || (position.y() > THRESHOLD_MAX && velocity.y() >= 0.0 && acceleration.y() >= 0.0)
+        || (position.y() < THRESHOLD_MIN && velocity.y() <= 0.0 && acceleration.y() <= 0.0)
+}
+
+/// Reflects a particle that has crossed the edge of the bounds back inside, losing some speed
+fn bounce(bounds: RectF, position: Vec2D, velocity: Vec2D) -> (Vec2D, Vec2D) {
+    fn reflect(value: f64, velocity: f64, min: f64, max: f64) -> (f64, f64) {
+        if value < min && velocity < 0.0 {
+            ((2.0 * min - value).min(max), -velocity * RESTITUTION)
+        } else if value > max && velocity > 0.0 {
+            ((2.0 * max - value).max(min), -velocity * RESTITUTION)
+        } else {
+            (value, velocity)
+        }
+    }
+    let (x, vx) = reflect(
+        position.x(),
+        velocity.x(),
+        bounds.x(),
+        bounds.x() + bounds.width(),
+    );
+    let (y, vy) = reflect(
+        position.y(),
+        velocity.y(),
+        bounds.y(),
+        bounds.y() + bounds.height(),
+    );
+    (Vec2D::new(x, y), Vec2D::new(vx, vy))
+}
+
+/// Position along the path of a particle from its origin to the target, eased in & out.
+/// Paths arc through the bend of the particle at their midpoint, the bend is stored as its velocity
+fn follow_path(origin: Vec2D, target: Vec2D, bend: Vec2D, progress: f64) -> Vec2D {
+    let eased = progress * progress * (3.0 - 2.0 * progress);
+    origin + (target - origin) * eased + bend * (4.0 * progress * (1.0 - progress))
 }
 
+/// A single particle as it is emitted & drawn, groups store them as a [ParticleBuffer]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Particle {
     position: Vec2D,
+    /// where the particle was emitted, the start of its path
+    origin: Vec2D,
     velocity: Vec2D,
     acceleration: Vec2D,
     max_alpha: f64,
@@ -105,6 +165,7 @@ impl Particle {
         let animation = sprite.animation().map(|pa| ParticleAnimation::new(pa));
         Self {
             position,
+            origin: position,
             velocity,
             acceleration,
             max_alpha,
@@ -120,42 +181,6 @@ impl Particle {
         }
     }
 
-    /// checks if the particle is out of bounds (0-1) and trajectory will not bring it back
-    pub fn is_escaped(&self) -> bool {
-        const THRESHOLD_MAX: f64 = 1.05;
-        const THRESHOLD_MIN: f64 = -0.05;
-        (self.position.x() > THRESHOLD_MAX
-            && self.velocity.x() >= 0.0
-            && self.acceleration.x() >= 0.0)
-            || (self.position.x() < THRESHOLD_MIN
-                && self.velocity.x() <= 0.0
-                && self.acceleration.x() <= 0.0)
-            || (self.position.y() > THRESHOLD_MAX
-                && self.velocity.y() >= 0.0
-                && self.acceleration.y() >= 0.0)
-            || (self.position.y() < THRESHOLD_MIN
-                && self.velocity.y() <= 0.0
-                && self.acceleration.y() <= 0.0)
-    }
-
-    pub fn update(&mut self, delta_time: f64, lifetime: f64) {
-        self.velocity += self.acceleration * delta_time;
-        self.position += self.velocity * delta_time;
-        self.rotation += self.angular_velocity * delta_time;
-        if let Some(animation) = self.animation.as_mut() {
-            match animation.animation_type {
-                ParticleAnimationType::Static => {}
-                ParticleAnimationType::Linear { frames, .. }
-                | ParticleAnimationType::YoYo { frames, .. } => {
-                    animation.frame =
-                        (lifetime / animation.frame_duration).floor() as usize % frames;
-                    animation.iteration =
-                        (lifetime / (animation.frame_duration * frames as f64)).floor() as u32;
-                }
-            }
-        }
-    }
-
     pub fn position(&self) -> Vec2D {
         self.position
     }
@@ -185,6 +210,157 @@ impl Particle {
     }
 }
 
+/// Particles stored as a struct of arrays, each property packed in its own buffer so the
+/// per-frame updates only walk the properties they change
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParticleBuffer {
+    position: Vec<Vec2D>,
+    origin: Vec<Vec2D>,
+    velocity: Vec<Vec2D>,
+    acceleration: Vec<Vec2D>,
+    max_alpha: Vec<f64>,
+    alpha: Vec<f64>,
+    pulse: Vec<Option<ParticleWave>>,
+    color: Vec<ParticleColor>,
+    time_to_live: Vec<Option<f64>>,
+    sprite: Vec<ParticleSprite>,
+    size: Vec<f64>,
+    rotation: Vec<f64>,
+    angular_velocity: Vec<f64>,
+    animation: Vec<Option<ParticleAnimation>>,
+}
+
+impl ParticleBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            position: Vec::with_capacity(capacity),
+            origin: Vec::with_capacity(capacity),
+            velocity: Vec::with_capacity(capacity),
+            acceleration: Vec::with_capacity(capacity),
+            max_alpha: Vec::with_capacity(capacity),
+            alpha: Vec::with_capacity(capacity),
+            pulse: Vec::with_capacity(capacity),
+            color: Vec::with_capacity(capacity),
+            time_to_live: Vec::with_capacity(capacity),
+            sprite: Vec::with_capacity(capacity),
+            size: Vec::with_capacity(capacity),
+            rotation: Vec::with_capacity(capacity),
+            angular_velocity: Vec::with_capacity(capacity),
+            animation: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.position.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn capacity(&self) -> usize {
+        self.position.capacity()
+    }
+
+    pub fn push(&mut self, particle: Particle) {
+        self.position.push(particle.position);
+        self.origin.push(particle.origin);
+        self.velocity.push(particle.velocity);
+        self.acceleration.push(particle.acceleration);
+        self.max_alpha.push(particle.max_alpha);
+        self.alpha.push(particle.alpha);
+        self.pulse.push(particle.pulse);
+        self.color.push(particle.color);
+        self.time_to_live.push(particle.time_to_live);
+        self.sprite.push(particle.sprite);
+        self.size.push(particle.size);
+        self.rotation.push(particle.rotation);
+        self.angular_velocity.push(particle.angular_velocity);
+        self.animation.push(particle.animation);
+    }
+
+    pub fn get(&self, index: usize) -> Particle {
+        Particle {
+            position: self.position[index],
+            origin: self.origin[index],
+            velocity: self.velocity[index],
+            acceleration: self.acceleration[index],
+            max_alpha: self.max_alpha[index],
+            alpha: self.alpha[index],
+            pulse: self.pulse[index],
+            color: self.color[index],
+            time_to_live: self.time_to_live[index],
+            sprite: self.sprite[index],
+            size: self.size[index],
+            rotation: self.rotation[index],
+            angular_velocity: self.angular_velocity[index],
+            animation: self.animation[index],
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Particle> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Keeps the particles at the indices the predicate is true for, in order & in place
+    fn retain<F: FnMut(&Self, usize) -> bool>(&mut self, mut predicate: F) {
+        let mut kept = 0;
+        for index in 0..self.len() {
+            if !predicate(self, index) {
+                continue;
+            }
+            if kept != index {
+                self.position[kept] = self.position[index];
+                self.origin[kept] = self.origin[index];
+                self.velocity[kept] = self.velocity[index];
+                self.acceleration[kept] = self.acceleration[index];
+                self.max_alpha[kept] = self.max_alpha[index];
+                self.alpha[kept] = self.alpha[index];
+                self.pulse[kept] = self.pulse[index];
+                self.color[kept] = self.color[index];
+                self.time_to_live[kept] = self.time_to_live[index];
+                self.sprite[kept] = self.sprite[index];
+                self.size[kept] = self.size[index];
+                self.rotation[kept] = self.rotation[index];
+                self.angular_velocity[kept] = self.angular_velocity[index];
+                self.animation[kept] = self.animation[index];
+            }
+            kept += 1;
+        }
+        self.truncate(kept);
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.position.truncate(len);
+        self.origin.truncate(len);
+        self.velocity.truncate(len);
+        self.acceleration.truncate(len);
+        self.max_alpha.truncate(len);
+        self.alpha.truncate(len);
+        self.pulse.truncate(len);
+        self.color.truncate(len);
+        self.time_to_live.truncate(len);
+        self.sprite.truncate(len);
+        self.size.truncate(len);
+        self.rotation.truncate(len);
+        self.angular_velocity.truncate(len);
+        self.animation.truncate(len);
+    }
+}
+
+impl Extend<Particle> for ParticleBuffer {
+    fn extend<T: IntoIterator<Item = Particle>>(&mut self, iter: T) {
+        for particle in iter {
+            self.push(particle);
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ParticleGroup {
     lifetime: f64,
@@ -192,7 +368,9 @@ pub struct ParticleGroup {
     fade_in: Option<f64>,
     fade_out: bool,
     orbit: Option<Vec2D>,
-    particles: Vec<Particle>,
+    bounds: Option<RectF>,
+    path: Option<Vec2D>,
+    particles: ParticleBuffer,
 }
 
 impl ParticleGroup {
@@ -201,7 +379,9 @@ impl ParticleGroup {
         fade_in: Option<f64>,
         fade_out: bool,
         orbit: Option<Vec2D>,
-        particles: Vec<Particle>,
+        bounds: Option<RectF>,
+        path: Option<Vec2D>,
+        particles: ParticleBuffer,
     ) -> Self {
         Self {
             lifetime: 0.0,
@@ -209,6 +389,8 @@ impl ParticleGroup {
             fade_in,
             fade_out,
             orbit,
+            bounds,
+            path,
             particles,
         }
     }
@@ -216,20 +398,14 @@ impl ParticleGroup {
     pub fn update_life(&mut self, delta_time: f64) {
         self.lifetime += delta_time;
 
-        // remove dead particles
-        let mut to_remove = vec![];
-        for (index, particle) in self.particles.iter().enumerate() {
-            if particle.is_escaped() {
-                to_remove.push(index);
-            } else if let Some(time_to_live) = particle.time_to_live {
-                if self.lifetime >= time_to_live {
-                    to_remove.push(index);
-                }
-            }
-        }
-        for index in to_remove.into_iter().rev() {
-            self.particles.remove(index);
-        }
+        // remove dead particles in place, keeps the buffer capacity for reuse
+        let lifetime = self.lifetime;
+        self.particles.retain(|p, i| {
+            !is_escaped(p.position[i], p.velocity[i], p.acceleration[i])
+                && p.time_to_live[i]
+                    .map(|time_to_live| lifetime < time_to_live)
+                    .unwrap_or(true)
+        });
     }
 
     pub fn is_empty(&self) -> bool {
@@ -241,6 +417,8 @@ impl ParticleGroup {
     }
 
     pub fn update_particles(&mut self, delta_time: f64) {
+        let p = &mut self.particles;
+
         // spatial
         if let Some(anchor_for) = self.anchor_for {
             self.anchor_for = if delta_time >= anchor_for {
@@ -249,22 +427,50 @@ impl ParticleGroup {
                 Some(anchor_for - delta_time)
             }
         } else {
+            // path
+            if let Some(target) = self.path {
+                let positions = p.position.iter_mut().zip(p.origin.iter());
+                let paths = p.velocity.iter().zip(p.time_to_live.iter());
+                for ((position, origin), (bend, time_to_live)) in positions.zip(paths) {
+                    let progress = time_to_live
+                        .map(|ttl| self.lifetime.min(ttl) / ttl)
+                        .unwrap_or(1.0);
+                    *position = follow_path(*origin, target, *bend, progress);
+                }
+            }
             // orbit
-            if let Some(orbit) = self.orbit {
-                for particle in self.particles.iter_mut() {
-                    let delta = particle.position - orbit;
+            else if let Some(orbit) = self.orbit {
+                for (position, velocity) in p.position.iter().zip(p.velocity.iter_mut()) {
+                    let delta = *position - orbit;
                     let magnitude_squared = delta.magnitude_squared();
                     // only apply gravitation when particle is sufficiently distant as this approximation breaks down for small distances
                     if magnitude_squared > 0.001 {
                         // Vector form of Newtons law of gravitation with empirically ideal G * m
                         let f = delta.unit_vector() * (-0.001 / magnitude_squared);
-                        particle.velocity += f * delta_time;
+                        *velocity += f * delta_time;
                     }
                 }
             }
 
-            for particle in self.particles.iter_mut() {
-                particle.update(delta_time, self.lifetime);
+            if self.path.is_none() {
+                for (velocity, acceleration) in p.velocity.iter_mut().zip(p.acceleration.iter()) {
+                    *velocity += *acceleration * delta_time;
+                }
+                for (position, velocity) in p.position.iter_mut().zip(p.velocity.iter()) {
+                    *position += *velocity * delta_time;
+                }
+            }
+            if let Some(bounds) = self.bounds {
+                for (position, velocity) in p.position.iter_mut().zip(p.velocity.iter_mut()) {
+                    (*position, *velocity) = bounce(bounds, *position, *velocity);
+                }
+            }
+            for (rotation, angular_velocity) in p.rotation.iter_mut().zip(p.angular_velocity.iter())
+            {
+                *rotation += angular_velocity * delta_time;
+            }
+            for animation in p.animation.iter_mut().flatten() {
+                animation.update(self.lifetime);
             }
         }
 
@@ -273,32 +479,119 @@ impl ParticleGroup {
             if self.lifetime >= fade_in {
                 self.fade_in = None;
             } else {
-                for particle in self.particles.iter_mut() {
-                    particle.alpha = particle.max_alpha * self.lifetime.min(fade_in) / fade_in;
+                let fraction = self.lifetime.min(fade_in) / fade_in;
+                for (alpha, max_alpha) in p.alpha.iter_mut().zip(p.max_alpha.iter()) {
+                    *alpha = max_alpha * fraction;
                 }
             }
         }
         // fade out
         else if self.fade_out {
-            for particle in self.particles.iter_mut() {
-                if let Some(ttl) = particle.time_to_live {
-                    particle.alpha = particle.max_alpha * (1.0 - self.lifetime.min(ttl) / ttl);
+            let alphas = p.alpha.iter_mut().zip(p.max_alpha.iter());
+            for ((alpha, max_alpha), time_to_live) in alphas.zip(p.time_to_live.iter()) {
+                if let Some(ttl) = time_to_live {
+                    *alpha = max_alpha * (1.0 - self.lifetime.min(*ttl) / ttl);
                 }
             }
         }
 
-        for particle in self.particles.iter_mut() {
-            // pulse
-            if let Some(pulse) = particle.pulse.as_ref() {
+        // pulse
+        let alphas = p.alpha.iter_mut().zip(p.max_alpha.iter());
+        for ((alpha, max_alpha), pulse) in alphas.zip(p.pulse.iter()) {
+            if let Some(pulse) = pulse {
                 let pulse_magnitude = pulse.next(self.lifetime);
-                particle.alpha = (particle.alpha + pulse_magnitude)
-                    .min(particle.max_alpha)
-                    .max(0.0);
+                *alpha = (*alpha + pulse_magnitude).min(*max_alpha).max(0.0);
             }
         }
     }
 
-    pub fn particles(&self) -> &[Particle] {
-        self.particles.as_slice()
+    pub fn particles(&self) -> impl Iterator<Item = Particle> + '_ {
+        self.particles.iter()
+    }
+
+    pub fn into_particles(self) -> ParticleBuffer {
+        self.particles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particles::meta::ParticleSprite;
+
+    fn particle(position: Vec2D, velocity: Vec2D, time_to_live: Option<f64>) -> Particle {
+        Particle::new(
+            position,
+            velocity,
+            Vec2D::ZERO,
+            1.0,
+            1.0,
+            None,
+            ParticleColor::WHITE,
+            time_to_live,
+            ParticleSprite::Circle01,
+            1.0,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn buffer_round_trips_particles() {
+        let mut buffer = ParticleBuffer::with_capacity(2);
+        let first = particle(Vec2D::new(0.1, 0.2), Vec2D::ZERO, None);
+        let second = particle(Vec2D::new(0.3, 0.4), Vec2D::new(1.0, 0.0), Some(1.0));
+        buffer.extend([first, second]);
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(
+            buffer.iter().collect::<Vec<Particle>>(),
+            vec![first, second]
+        );
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 2);
+    }
+
+    #[test]
+    fn update_life_removes_dead_particles_in_order() {
+        let mut buffer = ParticleBuffer::with_capacity(3);
+        let kept = [
+            particle(Vec2D::new(0.5, 0.5), Vec2D::ZERO, None),
+            particle(Vec2D::new(0.6, 0.5), Vec2D::ZERO, Some(10.0)),
+        ];
+        buffer.extend([
+            kept[0],
+            particle(Vec2D::new(0.5, 0.5), Vec2D::ZERO, Some(0.5)),
+            particle(Vec2D::new(1.5, 0.5), Vec2D::new(1.0, 0.0), None),
+            kept[1],
+        ]);
+        let mut group = ParticleGroup::new(None, None, false, None, None, None, buffer);
+        group.update_life(1.0);
+        assert_eq!(group.particles().collect::<Vec<Particle>>(), kept);
+    }
+
+    #[test]
+    fn follows_path_to_target() {
+        let origin = Vec2D::new(0.25, 0.5);
+        let target = Vec2D::new(0.75, 0.5);
+        let bend = Vec2D::new(0.0, -0.25);
+        assert_eq!(follow_path(origin, target, bend, 0.0), origin);
+        assert_eq!(
+            follow_path(origin, target, bend, 0.5),
+            Vec2D::new(0.5, 0.25)
+        );
+        assert_eq!(follow_path(origin, target, bend, 1.0), target);
+    }
+
+    #[test]
+    fn bounces_off_bounds() {
+        let bounds = RectF::new(0.25, 0.25, 0.5, 0.5);
+        let (position, velocity) = bounce(bounds, Vec2D::new(0.8, 0.5), Vec2D::new(0.2, -0.1));
+        assert_eq!(position, Vec2D::new(0.7, 0.5));
+        assert_eq!(velocity, Vec2D::new(-0.2 * RESTITUTION, -0.1));
+
+        // heading back inside is left alone
+        let (position, velocity) = bounce(bounds, Vec2D::new(0.5, 0.2), Vec2D::new(0.0, 0.1));
+        assert_eq!(position, Vec2D::new(0.5, 0.2));
+        assert_eq!(velocity, Vec2D::new(0.0, 0.1));
     }
 }
@@ -0,0 +1,70 @@
+use crate::particles::particle::ParticleBuffer;
+
+/// Freelist of particle buffers, dead groups hand their buffers back here so emitting new
+/// particles does not need to hit the allocator every frame.
+#[derive(Debug, Clone, Default)]
+pub struct ParticlePool {
+    free: Vec<ParticleBuffer>,
+    buffer_capacity: usize,
+    allocations: usize,
+}
+
+impl ParticlePool {
+    pub fn new(buffers: usize, buffer_capacity: usize) -> Self {
+        Self {
+            free: (0..buffers)
+                .map(|_| ParticleBuffer::with_capacity(buffer_capacity))
+                .collect(),
+            buffer_capacity,
+            allocations: buffers,
+        }
+    }
+
+    pub fn take(&mut self) -> ParticleBuffer {
+        match self.free.pop() {
+            Some(buffer) => buffer,
+            None => {
+                self.allocations += 1;
+                ParticleBuffer::with_capacity(self.buffer_capacity)
+            }
+        }
+    }
+
+    pub fn recycle(&mut self, mut buffer: ParticleBuffer) {
+        buffer.clear();
+        self.free.push(buffer);
+    }
+
+    /// Total number of buffers ever allocated by this pool
+    pub fn allocations(&self) -> usize {
+        self.allocations
+    }
+
+    pub fn free_buffers(&self) -> usize {
+        self.free.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_recycled_buffers() {
+        let mut pool = ParticlePool::new(1, 8);
+        let buffer = pool.take();
+        assert_eq!(pool.free_buffers(), 0);
+        pool.recycle(buffer);
+        let buffer = pool.take();
+        assert!(buffer.capacity() >= 8);
+        assert_eq!(pool.allocations(), 1);
+    }
+
+    #[test]
+    fn allocates_when_empty() {
+        let mut pool = ParticlePool::new(0, 8);
+        let _ = pool.take();
+        let _ = pool.take();
+        assert_eq!(pool.allocations(), 2);
+    }
+}
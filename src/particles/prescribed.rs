@@ -12,6 +12,7 @@ use crate::particles::source::{
     RandomParticleSource,
 };
 use crate::theme::all::AllThemeMeta;
+use crate::theme::gameboy::BLOCK_SIZE as GAMEBOY_BLOCK_SIZE;
 use crate::theme::n64::BLOCK_SIZE as N64_BLOCK_SIZE;
 use crate::theme::nes::BLOCK_SIZE as NES_BLOCK_SIZE;
 use crate::theme::particle::sprites::SRC_BLOCK_SIZE as MODERN_BLOCK_SIZE;
@@ -23,23 +24,45 @@ use sdl2::rect::{Point, Rect};
 use std::time::Duration;
 use crate::theme::sprite_sheet::DrType;
 
+const BEAM_PARTICLES_PER_BLOCK: u32 = 3;
+const SOUL_PARTICLES_PER_BLOCK: u32 = 6;
+const CONFETTI_PARTICLES: u32 = 60;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum PlayerParticleTarget {
     Vitamins(Vitamins),
     Blocks(Vec<BottlePoint>),
     Garbage(Vec<Garbage>),
+    /// A single rect around a row of garbage
+    GarbageRow(Vec<Garbage>),
     MaskedBlocks(Vec<ColoredBlock>),
+    /// Columns from the active vitamins down to their ghosts
+    DropPath { vitamins: Vitamins, ghost: Vitamins },
+    /// From the blocks cleared by a combo to the bottle of the player receiving its garbage
+    Soul { blocks: Vec<BottlePoint>, to: u32 },
+    /// The whole background of the player, around the bottle
+    Background,
     Bottle,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PrescribedParticles {
-    FadeInLatticeBurstAndFall { fade_in: Duration, color: Color },
+    /// Bouncing particles stay inside the bottle & fade out rather than falling through it
+    FadeInLatticeBurstAndFall {
+        fade_in: Duration,
+        color: Color,
+        bounce: bool,
+    },
     LightBurstUpAndOut { color: Color },
     BurstUp { color: Color },
     BurstDown { color: Color },
     PerimeterBurst { color: Color },
     PerimeterSpray { color: Color },
+    Beam { color: Color },
+    /// A stream that arcs from the targets to a point
+    Soul { color: Color },
+    /// Pills of the theme thrown up from the top of the target & tumbling down
+    Confetti { theme: ThemeName },
 }
 
 impl PrescribedParticles {
@@ -61,9 +84,14 @@ impl PrescribedParticles {
         mut lattice: Vec<Point>,
         n_blocks: u32,
         is_horizontal: bool,
+        bottle: Rect,
     ) -> Box<dyn ParticleSource> {
         match self {
-            PrescribedParticles::FadeInLatticeBurstAndFall { fade_in, color } => {
+            PrescribedParticles::FadeInLatticeBurstAndFall {
+                fade_in,
+                color,
+                bounce,
+            } => {
                 let limit = lattice.len() as u32 / n_blocks;
                 if is_horizontal {
                     lattice.sort_by(|p1, p2| p2.x().cmp(&p1.x()));
@@ -71,7 +99,7 @@ impl PrescribedParticles {
                     lattice.sort_by(|p1, p2| p1.y().cmp(&p2.y()));
                 }
 
-                RandomParticleSource::new(
+                let source = RandomParticleSource::new(
                     scale.build_ephemeral_lattice(lattice.into_iter()),
                     ParticleModulation::Constant {
                         count: limit,
@@ -88,17 +116,90 @@ impl PrescribedParticles {
                 .with_acceleration(Vec2D::new(0.0, 1.5)) // gravity
                 .with_anchor(fade_in)
                 .with_fade_in(fade_in)
-                .with_alpha((0.9, 0.1))
-                .into_box()
+                .with_alpha((0.9, 0.1));
+                bounce_off_bottle(source, scale, bottle, bounce).into_box()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn into_path_source(
+        self,
+        scale: &Scale,
+        rects: &[Rect],
+        to: Point,
+    ) -> Box<dyn ParticleSource> {
+        match self {
+            PrescribedParticles::Soul { color } => {
+                let color = ParticleColor::from_sdl(color);
+                let to = scale.point_to_particle_space(to);
+                let sources = rects
+                    .iter()
+                    .map(|r| {
+                        RandomParticleSource::new(
+                            scale.rect_source(*r),
+                            ParticleModulation::CascadeLimit {
+                                count: SOUL_PARTICLES_PER_BLOCK,
+                            },
+                        )
+                        .with_static_properties(ParticleSprite::Circle05, color, (0.6, 0.2), 0.0)
+                        // arcs up & over, staggered arrivals draw out the stream
+                        .with_velocity((Vec2D::new(0.0, -0.15), Vec2D::new(0.05, 0.05)))
+                        .with_path(to, (0.6, 0.2))
+                        .with_alpha((0.8, 0.2))
+                    })
+                    .collect();
+                AggregateParticleSource::new(sources).into_box()
             }
             _ => unreachable!(),
         }
     }
 
-    pub fn into_source(self, scale: &Scale, rects: &[Rect]) -> Box<dyn ParticleSource> {
+    /// Confetti sized by the scale of the theme's sprites to the window
+    pub fn into_confetti_source(
+        self,
+        scale: &Scale,
+        rect: Rect,
+        sprite_scale: f64,
+    ) -> Box<dyn ParticleSource> {
         match self {
-            PrescribedParticles::FadeInLatticeBurstAndFall { fade_in, color } => {
+            PrescribedParticles::Confetti { theme } => {
+                let top = Rect::new(rect.x(), rect.y(), rect.width(), rect.height() / 10);
                 RandomParticleSource::new(
+                    scale.rect_source(top),
+                    ParticleModulation::CascadeLimit {
+                        count: CONFETTI_PARTICLES,
+                    },
+                )
+                .with_properties(ProbabilityTable::identity(
+                    ParticleProperties::simple(
+                        &ParticleSprite::theme_sprites(theme),
+                        (sprite_scale, sprite_scale / 5.0),
+                    )
+                    .angular_velocity((0.0, 180.0)),
+                ))
+                .with_velocity((Vec2D::new(0.0, -0.2), Vec2D::new(0.1, 0.1)))
+                .with_acceleration(Vec2D::new(0.0, 0.4)) // gravity
+                .with_fade_out((3.0, 1.0))
+                .into_box()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn into_source(
+        self,
+        scale: &Scale,
+        rects: &[Rect],
+        bottle: Rect,
+    ) -> Box<dyn ParticleSource> {
+        match self {
+            PrescribedParticles::FadeInLatticeBurstAndFall {
+                fade_in,
+                color,
+                bounce,
+            } => {
+                let source = RandomParticleSource::new(
                     scale.rect_lattice_source(rects),
                     ParticleModulation::Cascade,
                 )
@@ -112,8 +213,8 @@ impl PrescribedParticles {
                 .with_acceleration(Vec2D::new(0.0, 1.5)) // gravity
                 .with_anchor(fade_in)
                 .with_fade_in(fade_in)
-                .with_alpha((0.9, 0.1))
-                .into_box()
+                .with_alpha((0.9, 0.1));
+                bounce_off_bottle(source, scale, bottle, bounce).into_box()
             }
             PrescribedParticles::LightBurstUpAndOut { color } => RandomParticleSource::burst(
                 scale.rect_lattice_source(rects),
@@ -164,10 +265,48 @@ impl PrescribedParticles {
                     .collect();
                 AggregateParticleSource::new(sources).into_box()
             }
+            PrescribedParticles::Soul { .. } | PrescribedParticles::Confetti { .. } => {
+                unreachable!()
+            }
+            PrescribedParticles::Beam { color } => {
+                let color = ParticleColor::from_sdl(color);
+                let sources = rects
+                    .iter()
+                    .map(|r| {
+                        // denser beams for longer drops
+                        let count = BEAM_PARTICLES_PER_BLOCK * (r.height() / r.width()).max(1);
+                        RandomParticleSource::new(
+                            scale.rect_source(*r),
+                            ParticleModulation::CascadeLimit { count },
+                        )
+                        .with_static_properties(ParticleSprite::Circle05, color, (0.5, 0.2), 0.0)
+                        .with_velocity((Vec2D::new(0.0, 0.15), Vec2D::new(0.01, 0.05)))
+                        .with_fade_out((0.2, 0.05))
+                        .with_alpha((0.6, 0.2))
+                    })
+                    .collect();
+                AggregateParticleSource::new(sources).into_box()
+            }
         }
     }
 }
 
+/// Bounced particles never escape the bottle so fade out instead
+fn bounce_off_bottle(
+    source: RandomParticleSource,
+    scale: &Scale,
+    bottle: Rect,
+    bounce: bool,
+) -> RandomParticleSource {
+    if bounce {
+        source
+            .with_bounds(scale.rect_to_particle_space(bottle))
+            .with_fade_out((1.5, 0.5))
+    } else {
+        source
+    }
+}
+
 pub fn prescribed_fireworks(window: Rect, scale: &Scale) -> Box<dyn ParticleSource> {
     let modulation = ParticleModulation::Constant {
         count: 100,
@@ -212,15 +351,18 @@ pub fn prescribed_vitamin_race(
         50,
         window.height() - 2 * buffer_y,
     );
-    let nes_block_scale = MODERN_BLOCK_SIZE as f64 / NES_BLOCK_SIZE as f64 / 2.0;
+    let nes_block_scale = MODERN_BLOCK_SIZE as f64 / NES_BLOCK_SIZE as f64;
     let nes_dr_scale = nes_block_scale / 2.0;
     let nes_scale = (nes_block_scale, nes_block_scale / 5.0);
-    let snes_block_scale = MODERN_BLOCK_SIZE as f64 / SNES_BLOCK_SIZE as f64 / 2.0;
+    let snes_block_scale = MODERN_BLOCK_SIZE as f64 / SNES_BLOCK_SIZE as f64;
     let snes_dr_scale = snes_block_scale / 2.0;
     let snes_scale = (snes_block_scale, snes_block_scale / 5.0);
-    let n64_block_scale = MODERN_BLOCK_SIZE as f64 / N64_BLOCK_SIZE as f64 / 2.0;
+    let n64_block_scale = MODERN_BLOCK_SIZE as f64 / N64_BLOCK_SIZE as f64;
     let n64_dr_scale = n64_block_scale / 2.0;
     let n64_scale = (n64_block_scale, n64_block_scale / 5.0);
+    let gameboy_block_scale = MODERN_BLOCK_SIZE as f64 / GAMEBOY_BLOCK_SIZE as f64;
+    let gameboy_dr_scale = gameboy_block_scale / 2.0;
+    let gameboy_scale = (gameboy_block_scale, gameboy_block_scale / 5.0);
     let modern_scale = (1.0, 0.2);
     let rotation = (0.0, 30.0);
     let p_virus = 1.0 / 3.0;
@@ -409,6 +551,50 @@ pub fn prescribed_vitamin_race(
                         (n64_dr_scale, n64_dr_scale / 5.0),
                     ).angular_velocity(rotation),
                     p_dr,
+                )
+
+                .with_1(
+                    ParticleProperties::simple(&ParticleSprite::GAMEBOY_PILLS, gameboy_scale)
+                        .angular_velocity(rotation),
+                )
+                .with(
+                    ParticleProperties::simple(
+                        &[
+                            ParticleSprite::Virus(
+                                ThemeName::GameBoy,
+                                VirusColor::Red,
+                                theme_meta.gameboy.virus_particle_animation(VirusColor::Red),
+                            ),
+                            ParticleSprite::Virus(
+                                ThemeName::GameBoy,
+                                VirusColor::Blue,
+                                theme_meta.gameboy.virus_particle_animation(VirusColor::Blue),
+                            ),
+                            ParticleSprite::Virus(
+                                ThemeName::GameBoy,
+                                VirusColor::Yellow,
+                                theme_meta.gameboy.virus_particle_animation(VirusColor::Yellow),
+                            ),
+                        ],
+                        gameboy_scale,
+                    )
+                    .angular_velocity(rotation),
+                    p_virus,
+                )
+                .with(
+                    ParticleProperties::simple(
+                        &[
+                            ParticleSprite::Dr(
+                                ThemeName::GameBoy,
+                                DrType::Victory,
+                                theme_meta
+                                    .gameboy
+                                    .dr_particle_animation(DrType::Victory),
+                            )
+                        ],
+                        (gameboy_dr_scale, gameboy_dr_scale / 5.0),
+                    ).angular_velocity(rotation),
+                    p_dr,
                 ),
         )
         .with_velocity((Vec2D::new(0.2, 0.0), Vec2D::new(0.05, 0.02)))
@@ -416,6 +602,78 @@ pub fn prescribed_vitamin_race(
         .into_box()
 }
 
+/// Modern pills falling from the top of the window
+pub fn prescribed_falling_pills(window: Rect, scale: &Scale) -> Box<dyn ParticleSource> {
+    let modulation = ParticleModulation::Constant {
+        count: 1,
+        step: Duration::from_millis(400),
+    };
+    let rect = Rect::new(window.left(), window.top() - 50, window.width(), 50);
+    RandomParticleSource::new(scale.rect_source(rect), modulation)
+        .with_properties(ProbabilityTable::new().with_1(
+            ParticleProperties::simple(&ParticleSprite::MODERN_PILLS, (0.8, 0.2))
+                .angular_velocity((0.0, 30.0)),
+        ))
+        .with_velocity((Vec2D::new(0.0, 0.15), Vec2D::new(0.02, 0.05)))
+        .with_alpha((0.9, 0.1))
+        .into_box()
+}
+
+pub fn prescribed_snow(window: Rect, scale: &Scale, color: Color) -> Box<dyn ParticleSource> {
+    let modulation = ParticleModulation::Constant {
+        count: 3,
+        step: Duration::from_millis(200),
+    };
+    let rect = Rect::new(window.left(), window.top() - 20, window.width(), 20);
+    RandomParticleSource::new(scale.rect_source(rect), modulation)
+        .with_static_properties(
+            ParticleSprite::Circle05,
+            ParticleColor::from_sdl(color),
+            (0.4, 0.2),
+            0.0,
+        )
+        .with_velocity((Vec2D::new(0.0, 0.05), Vec2D::new(0.02, 0.02)))
+        .with_alpha((0.7, 0.2))
+        .into_box()
+}
+
+pub fn prescribed_bubbles(window: Rect, scale: &Scale, color: Color) -> Box<dyn ParticleSource> {
+    let modulation = ParticleModulation::Constant {
+        count: 2,
+        step: Duration::from_millis(250),
+    };
+    let rect = Rect::new(window.left(), window.bottom(), window.width(), 20);
+    RandomParticleSource::new(scale.rect_source(rect), modulation)
+        .with_properties(ProbabilityTable::identity(ParticleProperties::new(
+            &ParticleSprite::HOLLOW_CIRCLES,
+            ParticleColor::from_sdl(color),
+            (1.0, 0.5),
+            0.0,
+        )))
+        .with_velocity((Vec2D::new(0.0, -0.1), Vec2D::new(0.01, 0.04)))
+        .with_pulse(ParticleWave::new(0.2, 3.0))
+        .with_alpha((0.5, 0.2))
+        .into_box()
+}
+
+pub fn prescribed_starfield(window: Rect, scale: &Scale, color: Color) -> Box<dyn ParticleSource> {
+    let modulation = ParticleModulation::Constant {
+        count: 3,
+        step: Duration::from_millis(100),
+    };
+    let rect = Rect::new(window.right(), window.top(), 20, window.height());
+    RandomParticleSource::new(scale.rect_source(rect), modulation)
+        .with_properties(ProbabilityTable::identity(ParticleProperties::new(
+            &ParticleSprite::STARS,
+            ParticleColor::from_sdl(color),
+            (0.5, 0.3),
+            0.0,
+        )))
+        .with_velocity((Vec2D::new(-0.5, 0.0), Vec2D::new(0.3, 0.0)))
+        .with_alpha((0.7, 0.3))
+        .into_box()
+}
+
 pub fn prescribed_orbit(window: Rect, scale: &Scale) -> Box<dyn ParticleSource> {
     const V: f64 = 0.05;
     let [top_left, top_right, bottom_right, bottom_left] = rect_quadrants(window);
@@ -541,12 +799,40 @@ impl PlayerTargetedParticles {
         themes: &ThemeContext,
         particle_scale: &Scale,
     ) -> Box<dyn ParticleSource> {
+        let bottle = themes.player_bottle_snip(self.player);
         let target_rects = match self.target {
-            PlayerParticleTarget::Bottle => vec![themes.player_bottle_snip(self.player)],
+            PlayerParticleTarget::Bottle => vec![bottle],
             PlayerParticleTarget::Vitamins(vitamins) => {
                 themes.player_vitamin_snips(self.player, vitamins).to_vec()
             }
             PlayerParticleTarget::Blocks(blocks) => themes.player_block_snips(self.player, blocks),
+            PlayerParticleTarget::DropPath { vitamins, ghost } => {
+                let from = themes.player_vitamin_snips(self.player, vitamins);
+                let to = themes.player_vitamin_snips(self.player, ghost);
+                from.into_iter()
+                    .zip(to)
+                    .map(|(from, to)| from.union(to))
+                    .collect()
+            }
+            PlayerParticleTarget::Soul { blocks, to } => {
+                let rects = themes.player_block_snips(self.player, blocks);
+                // garbage drops in from the top of the bottle
+                let to = themes.player_bottle_snip(to);
+                let to = Point::new(to.center().x(), to.top());
+                return self.particles.into_path_source(particle_scale, &rects, to);
+            }
+            PlayerParticleTarget::Background => {
+                // the scale of a block of the theme in the window
+                let block = themes.player_block_snips(self.player, vec![BottlePoint::new(0, 0)]);
+                let sprite_scale =
+                    block[0].width() as f64 / themes.theme().geometry().block_size() as f64;
+                let background = themes.player_background_snip(self.player);
+                return self.particles.into_confetti_source(
+                    particle_scale,
+                    background,
+                    sprite_scale,
+                );
+            }
             PlayerParticleTarget::MaskedBlocks(blocks) => {
                 let is_horizontal = iter_all_eq(blocks.iter().map(|b| b.position.y()));
                 let n_blocks = blocks.len();
@@ -556,16 +842,26 @@ impl PlayerTargetedParticles {
                     points,
                     n_blocks as u32,
                     is_horizontal,
+                    bottle,
                 );
             }
             PlayerParticleTarget::Garbage(garbage) => themes.player_block_snips(
                 self.player,
                 garbage.into_iter().map(|g| g.position).collect(),
             ),
+            PlayerParticleTarget::GarbageRow(garbage) => themes
+                .player_block_snips(
+                    self.player,
+                    garbage.into_iter().map(|g| g.position).collect(),
+                )
+                .into_iter()
+                .reduce(|row, block| row.union(block))
+                .into_iter()
+                .collect(),
         };
 
         self.particles
-            .into_source(particle_scale, target_rects.as_slice())
+            .into_source(particle_scale, target_rects.as_slice(), bottle)
     }
 }
 
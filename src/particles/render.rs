@@ -1,4 +1,5 @@
 use crate::particles::meta::ParticleSprite;
+use crate::particles::pool::ParticlePool;
 use crate::particles::scale::Scale;
 use crate::particles::source::ParticleSource;
 use crate::particles::Particles;
@@ -9,8 +10,10 @@ use crate::theme::{Theme, ThemeName};
 use sdl2::image::LoadTexture;
 use sdl2::rect::{Point, Rect};
 use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
+use sdl2::sys::{SDL_Color, SDL_FPoint, SDL_RenderGeometry, SDL_Vertex};
 use sdl2::video::WindowContext;
 use std::collections::HashMap;
+use std::os::raw::c_int;
 use std::time::Duration;
 use strum::IntoEnumIterator;
 use crate::particles::particle::Particle;
@@ -19,10 +22,80 @@ use crate::theme::animation::AnimationSpriteSheet;
 const SPRITES: &[u8] = include_bytes!("sprites.png");
 const BASE_SCALE: f64 = 0.05;
 
+/// Quads of the sprite atlas drawn in a single call, tinted per vertex rather than by
+/// setting the color mod of the atlas for every particle
+struct SpriteBatch {
+    atlas_width: f32,
+    atlas_height: f32,
+    vertices: Vec<SDL_Vertex>,
+    indices: Vec<c_int>,
+}
+
+impl SpriteBatch {
+    fn new(atlas: &Texture) -> Self {
+        let query = atlas.query();
+        Self {
+            atlas_width: query.width as f32,
+            atlas_height: query.height as f32,
+            vertices: vec![],
+            indices: vec![],
+        }
+    }
+
+    fn push(&mut self, dst: Rect, src: Rect, (r, g, b): (u8, u8, u8), alpha: u8) {
+        let color = SDL_Color { r, g, b, a: alpha };
+        let corners = [
+            (dst.left(), dst.top(), src.left(), src.top()),
+            (dst.right(), dst.top(), src.right(), src.top()),
+            (dst.right(), dst.bottom(), src.right(), src.bottom()),
+            (dst.left(), dst.bottom(), src.left(), src.bottom()),
+        ];
+        let first = self.vertices.len() as c_int;
+        self.vertices
+            .extend(corners.into_iter().map(|(x, y, u, v)| SDL_Vertex {
+                position: SDL_FPoint {
+                    x: x as f32,
+                    y: y as f32,
+                },
+                color,
+                tex_coord: SDL_FPoint {
+                    x: u as f32 / self.atlas_width,
+                    y: v as f32 / self.atlas_height,
+                },
+            }));
+        self.indices
+            .extend([0, 1, 2, 0, 2, 3].map(|index| first + index));
+    }
+
+    fn flush(&mut self, canvas: &mut WindowCanvas, atlas: &Texture) -> Result<(), String> {
+        if self.vertices.is_empty() {
+            return Ok(());
+        }
+        let result = unsafe {
+            SDL_RenderGeometry(
+                canvas.raw(),
+                atlas.raw(),
+                self.vertices.as_ptr(),
+                self.vertices.len() as c_int,
+                self.indices.as_ptr(),
+                self.indices.len() as c_int,
+            )
+        };
+        self.vertices.clear();
+        self.indices.clear();
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(sdl2::get_error())
+        }
+    }
+}
+
 pub struct ParticleRender<'a> {
     scale: Scale,
     sprites: Texture<'a>,
     sprite_snips: HashMap<ParticleSprite, Rect>,
+    batch: SpriteBatch,
     particles: Particles,
     theme_sprites: HashMap<ThemeName, FlatVitaminSpriteSheet<'a>>,
 }
@@ -56,6 +129,7 @@ impl<'a> ParticleRender<'a> {
         Ok(Self {
             scale,
             particles,
+            batch: SpriteBatch::new(&sprites),
             sprites,
             sprite_snips,
             theme_sprites: vitamin_sprites,
@@ -66,6 +140,10 @@ impl<'a> ParticleRender<'a> {
         self.particles.clear();
     }
 
+    pub fn set_scale(&mut self, scale: Scale) {
+        self.scale = scale;
+    }
+
     pub fn add_source(&mut self, source: Box<dyn ParticleSource>) {
         self.particles.sources.push(source);
     }
@@ -74,21 +152,27 @@ impl<'a> ParticleRender<'a> {
         self.particles.update(delta)
     }
 
+    pub fn pool(&self) -> &ParticlePool {
+        self.particles.pool()
+    }
+
+    /// Number of live particles
+    pub fn live_particles(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn max_particles(&self) -> usize {
+        self.particles.max_particles()
+    }
+
     pub fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
         for particle in self.particles.particles() {
-            let (r, g, b): (u8, u8, u8) = particle.color().into();
-            self.sprites.set_color_mod(r, g, b);
-            if particle.alpha() < 1.0 {
-                self.sprites
-                    .set_alpha_mod((255.0 * particle.alpha()).round() as u8);
-            } else {
-                self.sprites.set_alpha_mod(255);
-            }
-
             let point = self.scale.point_to_render_space(particle.position());
 
             match particle.sprite() {
                 ParticleSprite::Pill(theme, shape) => {
+                    // keep the draw order of the batched particles underneath
+                    self.batch.flush(canvas, &self.sprites)?;
                     let sprite_sheet = &self.theme_sprites[&theme];
                     let snip = sprite_sheet.snip(shape);
                     let scale = particle.size();
@@ -113,18 +197,20 @@ impl<'a> ParticleRender<'a> {
                     }
                 }
                 ParticleSprite::Virus(theme, color, _) => {
+                    self.batch.flush(canvas, &self.sprites)?;
                     Self::draw_animated_particle(
                         canvas,
                         self.theme_sprites[&theme].virus(color),
-                        particle,
+                        &particle,
                         point
                     )?;
                 }
                 ParticleSprite::Dr(theme, dr_type, _) => {
+                    self.batch.flush(canvas, &self.sprites)?;
                     Self::draw_animated_particle(
                         canvas,
                         self.theme_sprites[&theme].dr(dr_type),
-                        particle,
+                        &particle,
                         point
                     )?;
                 }
@@ -136,14 +222,15 @@ impl<'a> ParticleRender<'a> {
                             (scale * snip.width() as f64).round() as u32,
                             (scale * snip.height() as f64).round() as u32,
                         );
-                        canvas.copy(&self.sprites, *snip, rect)?;
+                        let alpha = (255.0 * particle.alpha().min(1.0)).round() as u8;
+                        self.batch.push(rect, *snip, particle.color().into(), alpha);
                     } else {
                         unreachable!();
                     }
                 }
             }
         }
-        Ok(())
+        self.batch.flush(canvas, &self.sprites)
     }
 
     fn draw_animated_particle(canvas: &mut WindowCanvas, sprite_sheet: &AnimationSpriteSheet, particle: &Particle, dest: Point) -> Result<(), String> {
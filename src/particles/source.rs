@@ -2,6 +2,7 @@ use crate::particles::color::ParticleColor;
 use crate::particles::geometry::{RectF, Vec2D};
 use crate::particles::meta::ParticleSprite;
 use crate::particles::particle::{Particle, ParticleGroup, ParticleWave};
+use crate::particles::pool::ParticlePool;
 use crate::particles::quantity::{ProbabilityTable, VariableQuantity};
 use rand::rngs::ThreadRng;
 use rand::{thread_rng, Rng};
@@ -106,7 +107,14 @@ impl ParticleProperties {
 
 pub trait ParticleSource {
     fn is_complete(&self) -> bool;
-    fn update(&mut self, delta_time: Duration, max_particles: u32) -> Vec<ParticleGroup>;
+    /// Emits new particle groups into `groups`, particle buffers should be taken from the pool
+    fn update(
+        &mut self,
+        delta_time: Duration,
+        max_particles: u32,
+        pool: &mut ParticlePool,
+        groups: &mut Vec<ParticleGroup>,
+    );
 }
 
 #[derive(Debug, Clone)]
@@ -123,7 +131,10 @@ pub struct RandomParticleSource {
     acceleration: VariableQuantity<Vec2D>,
     alpha: VariableQuantity<f64>,
     orbit: Option<Vec2D>,
+    bounds: Option<RectF>,
+    path: Option<Vec2D>,
     properties: ProbabilityTable<ParticleProperties>,
+    positions: Vec<Vec2D>,
 }
 
 impl ParticleSource for RandomParticleSource {
@@ -131,9 +142,15 @@ impl ParticleSource for RandomParticleSource {
         self.state == ParticleSourceState::Complete
     }
 
-    fn update(&mut self, delta_time: Duration, max_particles: u32) -> Vec<ParticleGroup> {
+    fn update(
+        &mut self,
+        delta_time: Duration,
+        max_particles: u32,
+        pool: &mut ParticlePool,
+        groups: &mut Vec<ParticleGroup>,
+    ) {
         if self.state == ParticleSourceState::Complete {
-            return vec![];
+            return;
         }
         let _limit_emitted: Option<u32> = None;
         let emit_particles = match self.modulation {
@@ -144,39 +161,42 @@ impl ParticleSource for RandomParticleSource {
         .min(max_particles);
 
         if emit_particles == 0 {
-            return vec![];
+            return;
         }
 
-        let particles: Vec<Particle> = match &mut self.position_source {
+        // positions are buffered separately as generating particles needs a mutable borrow of self
+        let mut positions = std::mem::take(&mut self.positions);
+        match &mut self.position_source {
             ParticlePositionSource::EphemeralLattice(points) => {
                 let new_length = points.len() - (emit_particles as usize).min(points.len());
                 if new_length == 0 {
                     self.state = ParticleSourceState::Complete;
                 }
-                points.drain(new_length..).collect::<Vec<Vec2D>>()
+                positions.extend(points.drain(new_length..));
+            }
+            ParticlePositionSource::Lattice(points) => {
+                positions.extend(points.iter().take(emit_particles as usize).copied())
             }
-            ParticlePositionSource::Lattice(points) => points
-                .iter()
-                .take(emit_particles as usize)
-                .copied()
-                .collect::<Vec<Vec2D>>(),
             ParticlePositionSource::RandomCascade(_rect) => {
                 let point = self.next_position();
-                (0..emit_particles).map(|_| point).collect()
+                positions.extend((0..emit_particles).map(|_| point));
             }
-            _ => (0..emit_particles).map(|_| self.next_position()).collect(),
+            _ => positions.extend((0..emit_particles).map(|_| self.next_position())),
         }
-        .into_iter()
-        .map(|p| self.next_particle(p))
-        .collect();
 
-        vec![ParticleGroup::new(
+        let mut particles = pool.take();
+        particles.extend(positions.drain(..).map(|p| self.next_particle(p)));
+        self.positions = positions;
+
+        groups.push(ParticleGroup::new(
             self.anchor_for.map(|d| d.as_secs_f64()),
             self.fade_in.map(|d| d.as_secs_f64()),
             self.fade_out,
             self.orbit,
+            self.bounds,
+            self.path,
             particles,
-        )]
+        ));
     }
 }
 
@@ -196,7 +216,10 @@ impl RandomParticleSource {
             acceleration: VariableQuantity::new(Vec2D::ZERO, Vec2D::ZERO),
             alpha: VariableQuantity::new(1.0, 0.0),
             orbit: None,
+            bounds: None,
+            path: None,
             properties: ProbabilityTable::identity(ParticleProperties::default()),
+            positions: vec![],
         }
     }
 
@@ -227,12 +250,15 @@ impl RandomParticleSource {
             acceleration: VariableQuantity::new(Vec2D::ZERO, Vec2D::ZERO),
             alpha: alpha.into(),
             orbit: None,
+            bounds: None,
+            path: None,
             properties: ProbabilityTable::identity(ParticleProperties::new(
                 &[sprite],
                 color,
                 1.0,
                 0.0,
             )),
+            positions: vec![],
         }
     }
 
@@ -306,6 +332,24 @@ impl RandomParticleSource {
         self
     }
 
+    /// Particles bounce off the inside of the bounds rather than flying through them
+    pub fn with_bounds<B: Into<RectF>>(mut self, value: B) -> Self {
+        self.bounds = Some(value.into());
+        self
+    }
+
+    /// Particles travel from where they are emitted to the target, arriving at the end of
+    /// their lifetime. Velocity bends the path rather than moving the particle.
+    pub fn with_path<T, L>(mut self, target: T, travel_secs: L) -> Self
+    where
+        T: Into<Vec2D>,
+        L: Into<VariableQuantity<f64>>,
+    {
+        self.path = Some(target.into());
+        self.lifetime_secs = Some(travel_secs.into());
+        self
+    }
+
     pub fn with_pulse<P: Into<VariableQuantity<ParticleWave>>>(mut self, value: P) -> Self {
         self.pulse = Some(value.into());
         self
@@ -389,19 +433,23 @@ impl ParticleSource for AggregateParticleSource {
         self.sources.iter().all(|s| s.is_complete())
     }
 
-    fn update(&mut self, delta_time: Duration, max_particles: u32) -> Vec<ParticleGroup> {
-        let mut result = vec![];
+    fn update(
+        &mut self,
+        delta_time: Duration,
+        max_particles: u32,
+        pool: &mut ParticlePool,
+        groups: &mut Vec<ParticleGroup>,
+    ) {
         let mut max_particles = max_particles;
         for source in self.sources.iter_mut() {
             if max_particles == 0 {
                 break;
             }
-            let groups = source.update(delta_time, max_particles);
-            for group in groups {
+            let emitted_from = groups.len();
+            source.update(delta_time, max_particles, pool, groups);
+            for group in groups[emitted_from..].iter() {
                 max_particles -= (group.len() as u32).min(max_particles);
-                result.push(group)
             }
         }
-        result
     }
 }
@@ -0,0 +1,187 @@
+use crate::font::{FontTexture, FontType};
+use crate::menu_input::MenuInputKey;
+use crate::scene::{Scene, SceneInput};
+use crate::profile::{MAX_PROFILE_NAME, PROFILE_ALPHABET};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+
+const FONT_COLOR: Color = Color::WHITE;
+const HINT_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+const TITLE: &str = "new profile";
+const HINT: &str = "up/down: change    left/right: move    start: create    back: cancel";
+/// the first glyph is a blank, blanks are dropped from the name so it can be shorter than max
+const BLANK: char = ' ';
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProfileEntryAction {
+    Create(String),
+    Cancel,
+}
+
+/// Profile name entry, one character at a time like a high score name
+pub struct ProfileEntryScreen<'a> {
+    title: FontTexture<'a>,
+    hint: FontTexture<'a>,
+    /// rendered once for the blank & every character of the profile alphabet
+    glyphs: Vec<FontTexture<'a>>,
+    glyph_width: u32,
+    glyph_height: u32,
+    name: [usize; MAX_PROFILE_NAME],
+    current_char: usize,
+    padding: u32,
+    window_size: (u32, u32),
+}
+
+impl<'a> ProfileEntryScreen<'a> {
+    pub fn new(
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        (window_width, window_height): (u32, u32),
+    ) -> Result<Self, String> {
+        let title_font = FontType::Retro.load(ttf, window_width / 24)?;
+        let name_font = FontType::Retro.load(ttf, window_width / 16)?;
+        let hint_font = FontType::Bold.load(ttf, window_height / 40)?;
+
+        let title = FontTexture::from_string(&title_font, texture_creator, TITLE, FONT_COLOR)?;
+        let hint = FontTexture::from_string(&hint_font, texture_creator, HINT, HINT_COLOR)?;
+        let glyphs = alphabet()
+            .map(|c| {
+                FontTexture::from_string(&name_font, texture_creator, &c.to_string(), FONT_COLOR)
+            })
+            .collect::<Result<Vec<FontTexture>, String>>()?;
+
+        // start on an 'A' rather than a blank so the caret is visible
+        let mut name = [0; MAX_PROFILE_NAME];
+        name[0] = 1;
+
+        Ok(Self {
+            title,
+            hint,
+            glyph_width: glyphs.iter().map(|g| g.width).max().unwrap_or(0),
+            glyph_height: glyphs.iter().map(|g| g.height).max().unwrap_or(0),
+            glyphs,
+            name,
+            current_char: 0,
+            padding: window_height / 32,
+            window_size: (window_width, window_height),
+        })
+    }
+
+    fn name(&self) -> String {
+        entered_name(&self.name)
+    }
+
+    /// Returns the action when the entry is closed
+    pub fn read_key(&mut self, key: MenuInputKey) -> Option<ProfileEntryAction> {
+        match key {
+            MenuInputKey::Up => self.move_char(1),
+            MenuInputKey::Down => self.move_char(-1),
+            MenuInputKey::Left => self.current_char = self.current_char.saturating_sub(1),
+            MenuInputKey::Right | MenuInputKey::Select => {
+                self.current_char = (self.current_char + 1).min(MAX_PROFILE_NAME - 1)
+            }
+            MenuInputKey::Start => return Some(ProfileEntryAction::Create(self.name())),
+            MenuInputKey::Back | MenuInputKey::Quit => return Some(ProfileEntryAction::Cancel),
+            MenuInputKey::Wheel { up } => self.move_char(if up { 1 } else { -1 }),
+            MenuInputKey::Click { .. } => {}
+        }
+        None
+    }
+
+    fn move_char(&mut self, value: i32) {
+        let len = PROFILE_ALPHABET.len() as i32 + 1;
+        let current = self.name[self.current_char] as i32;
+        self.name[self.current_char] = (current + value).rem_euclid(len) as usize;
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let (window_width, window_height) = self.window_size;
+        let centered_x = |width: u32| (window_width as i32 - width as i32).max(0) / 2;
+
+        canvas.copy(
+            &self.title.texture,
+            None,
+            Rect::new(
+                centered_x(self.title.width),
+                self.padding as i32,
+                self.title.width,
+                self.title.height,
+            ),
+        )?;
+
+        // each character is centered in a fixed width cell with a caret under the current one
+        let cell_width = self.glyph_width + self.padding / 2;
+        let mut x = centered_x(cell_width * MAX_PROFILE_NAME as u32);
+        let y = (window_height as i32 - self.glyph_height as i32) / 2;
+        canvas.set_draw_color(FONT_COLOR);
+        for (i, glyph) in self.name.iter().map(|c| &self.glyphs[*c]).enumerate() {
+            let glyph_x = x + (cell_width as i32 - glyph.width as i32) / 2;
+            canvas.copy(
+                &glyph.texture,
+                None,
+                Rect::new(glyph_x, y, glyph.width, glyph.height),
+            )?;
+            if i == self.current_char {
+                // as wide as the widest glyph, the blank has next to no width of its own
+                canvas.fill_rect(Rect::new(
+                    x + (cell_width - self.glyph_width) as i32 / 2,
+                    y + self.glyph_height as i32,
+                    self.glyph_width,
+                    (self.padding / 4).max(1),
+                ))?;
+            }
+            x += cell_width as i32;
+        }
+
+        canvas.copy(
+            &self.hint.texture,
+            None,
+            Rect::new(
+                centered_x(self.hint.width),
+                (window_height - self.padding - self.hint.height) as i32,
+                self.hint.width,
+                self.hint.height,
+            ),
+        )
+    }
+}
+
+impl Scene for ProfileEntryScreen<'_> {
+    type Output = ProfileEntryAction;
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<ProfileEntryAction> {
+        match self.read_key(key) {
+            Some(action) => SceneInput::Exit(action),
+            None => SceneInput::Handled,
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        ProfileEntryScreen::draw(self, canvas)
+    }
+}
+
+fn alphabet() -> impl Iterator<Item = char> {
+    std::iter::once(BLANK).chain(PROFILE_ALPHABET)
+}
+
+fn entered_name(name: &[usize]) -> String {
+    name.iter()
+        .filter_map(|&i| alphabet().nth(i))
+        .filter(|&c| c != BLANK)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blanks_are_dropped() {
+        assert_eq!(entered_name(&[1, 0, 2, 0, 0, 0, 0, 36]), "AB9");
+        assert_eq!(entered_name(&[0; MAX_PROFILE_NAME]), "");
+    }
+}
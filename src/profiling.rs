@@ -0,0 +1,44 @@
+//! Optional puffin instrumentation, enabled with `--features profiling`.
+//! Connect with `puffin_viewer --url 127.0.0.1:8585` to view the traces.
+
+/// Opens a named profiling zone until the end of the current scope, compiles to nothing without the feature
+macro_rules! profile_scope {
+    ($name:expr) => {
+        #[cfg(feature = "profiling")]
+        puffin::profile_scope!($name);
+    };
+}
+
+pub(crate) use profile_scope;
+
+#[cfg(feature = "profiling")]
+pub struct Profiler {
+    _server: puffin_http::Server,
+}
+
+#[cfg(feature = "profiling")]
+impl Profiler {
+    pub fn start() -> Result<Self, String> {
+        let address = format!("127.0.0.1:{}", puffin_http::DEFAULT_PORT);
+        let server = puffin_http::Server::new(&address).map_err(|e| e.to_string())?;
+        puffin::set_scopes_on(true);
+        println!("profiling: puffin server listening on {}", address);
+        Ok(Self { _server: server })
+    }
+}
+
+#[cfg(not(feature = "profiling"))]
+pub struct Profiler;
+
+#[cfg(not(feature = "profiling"))]
+impl Profiler {
+    pub fn start() -> Result<Self, String> {
+        Ok(Self)
+    }
+}
+
+/// Marks the end of a frame, called once per frame by FrameRate
+pub fn new_frame() {
+    #[cfg(feature = "profiling")]
+    puffin::GlobalProfiler::lock().new_frame();
+}
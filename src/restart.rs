@@ -0,0 +1,123 @@
+//! Quick restart of the current match from the restart key, without going back through the menus.
+//! While a game is in progress the key has to be pressed twice so a match isn't lost by accident.
+
+use crate::font::{FontTexture, FontType};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+use std::time::Duration;
+
+const CONFIRM_DURATION: Duration = Duration::from_millis(2000);
+const PROMPT: &str = "press restart again to restart";
+const PROMPT_BACKGROUND: Color = Color::RGBA(0, 0, 0, 0xb0);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct RestartConfirm {
+    /// time left to press restart again
+    remaining: Option<Duration>,
+}
+
+impl RestartConfirm {
+    /// True if the match should restart now
+    fn press(&mut self, in_progress: bool) -> bool {
+        if !in_progress || self.remaining.is_some() {
+            self.remaining = None;
+            true
+        } else {
+            self.remaining = Some(CONFIRM_DURATION);
+            false
+        }
+    }
+
+    fn update(&mut self, delta: Duration) {
+        self.remaining = self
+            .remaining
+            .and_then(|remaining| remaining.checked_sub(delta))
+            .filter(|remaining| !remaining.is_zero());
+    }
+}
+
+pub struct RestartPrompt<'a> {
+    prompt: FontTexture<'a>,
+    confirm: RestartConfirm,
+}
+
+impl<'a> RestartPrompt<'a> {
+    pub fn new(
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        window_height: u32,
+    ) -> Result<Self, String> {
+        let font = FontType::Bold.load(ttf, (window_height / 30).max(8))?;
+        Ok(Self {
+            prompt: FontTexture::from_string(&font, texture_creator, PROMPT, Color::WHITE)?,
+            confirm: RestartConfirm::default(),
+        })
+    }
+
+    /// The restart key was pressed, true if the match should restart now
+    pub fn press(&mut self, in_progress: bool) -> bool {
+        self.confirm.press(in_progress)
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        self.confirm.update(delta);
+    }
+
+    /// Draws the prompt centered at the top of the window while waiting for confirmation
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        if self.confirm.remaining.is_none() {
+            return Ok(());
+        }
+        let (window_width, _) = canvas.output_size()?;
+        let padding = self.prompt.height / 2;
+        let width = self.prompt.width + padding * 2;
+        let height = self.prompt.height + padding;
+        let x = (window_width as i32 - width as i32) / 2;
+        let y = padding as i32;
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(PROMPT_BACKGROUND);
+        canvas.fill_rect(Rect::new(x, y, width, height))?;
+        canvas.set_blend_mode(BlendMode::None);
+        canvas.copy(
+            &self.prompt.texture,
+            None,
+            Rect::new(
+                x + padding as i32,
+                y + padding as i32 / 2,
+                self.prompt.width,
+                self.prompt.height,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restarts_immediately_when_over() {
+        let mut confirm = RestartConfirm::default();
+        assert!(confirm.press(false));
+    }
+
+    #[test]
+    fn confirms_in_progress() {
+        let mut confirm = RestartConfirm::default();
+        assert!(!confirm.press(true));
+        confirm.update(Duration::from_millis(500));
+        assert!(confirm.press(true));
+    }
+
+    #[test]
+    fn confirmation_expires() {
+        let mut confirm = RestartConfirm::default();
+        assert!(!confirm.press(true));
+        confirm.update(CONFIRM_DURATION);
+        assert!(!confirm.press(true));
+    }
+}
@@ -0,0 +1,131 @@
+//! Haptic feedback of game controllers. Controls are keyboard only e.g. the buttons of a handheld
+//! mapped to keys, so controllers are opened just to rumble, one per player in the order they
+//! were connected.
+
+use crate::game::event::GameEvent;
+use sdl2::controller::GameController;
+use sdl2::event::Event;
+use sdl2::GameControllerSubsystem;
+use std::time::Duration;
+
+/// Short & light as a pill locks
+const LOCK_PULSE: RumblePulse = RumblePulse::new(0.0, 0.3, Duration::from_millis(60));
+/// Stronger as a dropped pill hits the bottom
+const IMPACT_PULSE: RumblePulse = RumblePulse::new(0.6, 0.6, Duration::from_millis(120));
+/// Long & heavy on game over
+const GAME_OVER_PULSE: RumblePulse = RumblePulse::new(1.0, 0.5, Duration::from_millis(800));
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RumblePulse {
+    /// strength of the low frequency motor, 0.0 to 1.0
+    low: f64,
+    /// strength of the high frequency motor, 0.0 to 1.0
+    high: f64,
+    duration: Duration,
+}
+
+impl RumblePulse {
+    const fn new(low: f64, high: f64, duration: Duration) -> Self {
+        Self {
+            low,
+            high,
+            duration,
+        }
+    }
+
+    /// The pulse felt by the player of a game event, if any
+    pub fn of_event(event: &GameEvent) -> Option<(u32, Self)> {
+        match event {
+            GameEvent::Lock {
+                player,
+                hard_or_soft_dropped,
+                ..
+            } => {
+                let pulse = if *hard_or_soft_dropped {
+                    IMPACT_PULSE
+                } else {
+                    LOCK_PULSE
+                };
+                Some((*player, pulse))
+            }
+            GameEvent::GameOver { player } => Some((*player, GAME_OVER_PULSE)),
+            _ => None,
+        }
+    }
+
+    /// Strength of the low & high frequency motors scaled by an intensity
+    fn motors(&self, intensity: f64) -> (u16, u16) {
+        let motor = |strength: f64| {
+            (u16::MAX as f64 * (strength * intensity).clamp(0.0, 1.0)).round() as u16
+        };
+        (motor(self.low), motor(self.high))
+    }
+}
+
+pub struct Rumble {
+    subsystem: GameControllerSubsystem,
+    controllers: Vec<GameController>,
+    /// 0.0 (off) to 1.0
+    intensity: f64,
+}
+
+impl Rumble {
+    pub fn new(subsystem: GameControllerSubsystem, intensity: f64) -> Self {
+        Self {
+            subsystem,
+            controllers: vec![],
+            intensity,
+        }
+    }
+
+    pub fn set_intensity(&mut self, intensity: f64) {
+        self.intensity = intensity;
+    }
+
+    /// Opens controllers as they are connected, those connected at startup are added by SDL too
+    pub fn receive_event(&mut self, event: &Event) {
+        match event {
+            Event::ControllerDeviceAdded { which, .. } => {
+                if let Ok(controller) = self.subsystem.open(*which) {
+                    self.controllers.push(controller);
+                }
+            }
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.controllers.retain(|c| c.instance_id() != *which)
+            }
+            _ => {}
+        }
+    }
+
+    pub fn pulse(&mut self, player: u32, pulse: RumblePulse) {
+        if self.intensity <= 0.0 {
+            return;
+        }
+        let (low, high) = pulse.motors(self.intensity);
+        if let Some(controller) = self.controllers.get_mut(player as usize) {
+            // not every controller can rumble
+            let _ = controller.set_rumble(low, high, pulse.duration.as_millis() as u32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::pill::{Pill, PillShape};
+
+    #[test]
+    fn pulses_of_events() {
+        let lock = |hard_or_soft_dropped| GameEvent::Lock {
+            player: 1,
+            vitamins: Pill::new(PillShape::RB).vitamins(),
+            hard_or_soft_dropped,
+            drop_score: 0,
+        };
+        assert_eq!(RumblePulse::of_event(&lock(false)), Some((1, LOCK_PULSE)));
+        assert_eq!(RumblePulse::of_event(&lock(true)), Some((1, IMPACT_PULSE)));
+        assert_eq!(GAME_OVER_PULSE.motors(1.0), (u16::MAX, 32768));
+        assert_eq!(GAME_OVER_PULSE.motors(0.0), (0, 0));
+        assert_eq!(RumblePulse::of_event(&GameEvent::Fall { player: 0 }), None);
+    }
+}
@@ -4,14 +4,52 @@ use crate::config::VideoConfig;
 use crate::theme::ThemeName;
 
 const PLAYER_BUFFER_PCT: f64 = 0.002;
+/// windows at least this much wider than they are tall are ultrawide, e.g. 21:9 & 32:9
+const ULTRAWIDE_ASPECT_RATIO: f64 = 2.0;
+
+/// Arrangement of the players in the window, selected from its aspect ratio
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// players side by side, e.g. 16:9
+    Landscape,
+    /// players side by side filling the full height, level stats are shown beside the boards
+    Ultrawide,
+    /// players stacked vertically
+    Portrait,
+}
+
+impl Layout {
+    pub fn from_window_size((window_width, window_height): (u32, u32)) -> Self {
+        let aspect_ratio = window_width as f64 / window_height.max(1) as f64;
+        if aspect_ratio < 1.0 {
+            Layout::Portrait
+        } else if aspect_ratio >= ULTRAWIDE_ASPECT_RATIO {
+            Layout::Ultrawide
+        } else {
+            Layout::Landscape
+        }
+    }
+
+    /// columns & rows of player windows
+    fn grid(&self, players: u32) -> (u32, u32) {
+        match self {
+            Layout::Portrait => (1, players),
+            Layout::Landscape | Layout::Ultrawide => (players, 1),
+        }
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Scale {
-    players: u32,
+    layout: Layout,
+    columns: u32,
+    rows: u32,
     scale: f64,
     integer_scale: Option<u32>,
     window_width: u32,
     window_height: u32,
+    /// area of the window the players are drawn in, smaller than the window when letterboxed
+    viewport: Rect,
     block_size: f64,
     player_buffer_width: u32,
     player_buffer_height: u32,
@@ -31,44 +69,75 @@ impl Scale {
         let effective_bg_width = bg_width + 2 * player_buffer_width;
         let effective_bg_height = bg_height + 2 * player_buffer_height;
 
+        let layout = Layout::from_window_size((window_width, window_height));
+        let (columns, rows) = layout.grid(players);
+
         // the modern theme does it's own scaling
-        let is_integer_scale = theme == ThemeName::Particle || config.integer_scale;
+        let is_integer_scale =
+            theme == ThemeName::Particle || config.integer_scale || config.letterbox;
 
-        let (scale, integer_scale) = if is_integer_scale {
-            let scale = min(window_width / (effective_bg_width * players), window_height / effective_bg_height);
-            (scale as f64, Some(scale))
+        // fall back to fractional scaling when the players do not fit at 1x, e.g. in portrait
+        let integer_scale = min(window_width / (effective_bg_width * columns), window_height / (effective_bg_height * rows));
+        let integer_scale = Some(integer_scale).filter(|scale| is_integer_scale && *scale > 0);
+
+        let scale = if let Some(integer_scale) = integer_scale {
+            integer_scale as f64
         } else {
             let padded_window_width = window_width as f64 - (2.0 * config.screen_padding_pct() * window_width as f64);
-            let scale_x = padded_window_width / (effective_bg_width as f64 * players as f64);
+            let scale_x = padded_window_width / (effective_bg_width as f64 * columns as f64);
+
+            // ultrawide has space to spare either side so boards use the full height
+            let padding_y_pct = if layout == Layout::Ultrawide { 0.0 } else { config.screen_padding_pct() };
+            let padded_window_height = window_height as f64 - (2.0 * padding_y_pct * window_height as f64);
+            let scale_y = padded_window_height / (effective_bg_height as f64 * rows as f64);
 
-            let padded_window_height = window_height as f64 - (2.0 * config.screen_padding_pct() * window_height as f64);
-            let scale_y = padded_window_height / effective_bg_height as f64;
+            scale_x.min(scale_y)
+        };
 
-            let scale = scale_x.min(scale_y);
-            (scale, None)
+        let viewport = match integer_scale {
+            Some(integer_scale) if config.letterbox && theme != ThemeName::Particle => {
+                let mut viewport = Rect::new(
+                    0,
+                    0,
+                    integer_scale * effective_bg_width * columns,
+                    integer_scale * effective_bg_height * rows,
+                );
+                viewport.center_on((window_width as i32 / 2, window_height as i32 / 2));
+                viewport
+            }
+            _ => Rect::new(0, 0, window_width, window_height),
         };
 
         Self {
-            players,
+            layout,
+            columns,
+            rows,
             scale,
             integer_scale,
             window_width,
             window_height,
+            viewport,
             block_size: block_size as f64 * scale,
             player_buffer_width,
             player_buffer_height,
         }
     }
 
-    /// splits the entire window up into horizontally stacked chunks equally between players
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// splits the entire viewport up into a grid of chunks equally between players
     pub fn player_window(&self, player: u32) -> Rect {
-        let player_chunk_width = self.window_width / self.players;
-        let x = player_chunk_width * player + self.player_buffer_width;
+        let player_chunk_width = self.viewport.width() / self.columns;
+        let player_chunk_height = self.viewport.height() / self.rows;
+        let x = player_chunk_width * (player % self.columns) + self.player_buffer_width;
+        let y = player_chunk_height * (player / self.columns) + self.player_buffer_height;
         Rect::new(
-            x as i32,
-            self.player_buffer_height as i32,
+            self.viewport.x() + x as i32,
+            self.viewport.y() + y as i32,
             player_chunk_width,
-            self.window_height,
+            player_chunk_height,
         )
     }
 
@@ -107,8 +176,33 @@ impl Scale {
         )
     }
 
-    pub fn window_size(&self) -> (u32, u32) {
-        (self.window_width, self.window_height)
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+
+    /// Black bars either side of a letterboxed viewport, empty when the viewport fills the window
+    pub fn letterbox_bars(&self) -> Vec<Rect> {
+        let (width, height) = (self.window_width as i32, self.window_height as i32);
+        let viewport = self.viewport;
+        [
+            Rect::new(0, 0, viewport.left().max(0) as u32, height as u32),
+            Rect::new(
+                viewport.right(),
+                0,
+                (width - viewport.right()).max(0) as u32,
+                height as u32,
+            ),
+            Rect::new(0, 0, width as u32, viewport.top().max(0) as u32),
+            Rect::new(
+                0,
+                viewport.bottom(),
+                width as u32,
+                (height - viewport.bottom()).max(0) as u32,
+            ),
+        ]
+        .into_iter()
+        .filter(|bar| bar.width() > 0 && bar.height() > 0)
+        .collect()
     }
 
     fn scale_length(&self, value: u32) -> u32 {
@@ -127,3 +221,43 @@ impl Scale {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn layout_from_aspect_ratio() {
+        assert_eq!(Layout::from_window_size((1920, 1080)), Layout::Landscape);
+        assert_eq!(Layout::from_window_size((3440, 1440)), Layout::Ultrawide);
+        assert_eq!(Layout::from_window_size((1080, 1920)), Layout::Portrait);
+    }
+
+    #[test]
+    fn portrait_stacks_players() {
+        let config = Config::default().video;
+        let scale = Scale::new(2, (256, 224), (1080, 1920), 8, config, ThemeName::Nes);
+        let (player1, player2) = (scale.player_window(0), scale.player_window(1));
+        assert_eq!(player1.x(), player2.x());
+        assert!(player2.y() >= player1.bottom());
+        assert!(scale.scale_rect(Rect::new(0, 0, 256, 224)).height() <= 960);
+    }
+
+    #[test]
+    fn letterbox_centers_integer_multiple() {
+        let mut config = Config::default().video;
+        config.integer_scale = false;
+        config.letterbox = true;
+        let scale = Scale::new(1, (256, 224), (1280, 720), 8, config, ThemeName::Nes);
+        // 3x fits 720p with the player buffer, centered
+        assert_eq!(scale.viewport(), Rect::new(247, 21, 786, 678));
+        assert_eq!(scale.scale_rect(Rect::new(0, 0, 256, 224)), Rect::new(0, 0, 768, 672));
+        assert_eq!(scale.letterbox_bars().len(), 4);
+
+        config.letterbox = false;
+        let scale = Scale::new(1, (256, 224), (1280, 720), 8, config, ThemeName::Nes);
+        assert_eq!(scale.viewport(), Rect::new(0, 0, 1280, 720));
+        assert!(scale.letterbox_bars().is_empty());
+    }
+}
@@ -0,0 +1,34 @@
+//! Full window screens run by the one menu loop of `DrRustario::run_scene`, so a new screen
+//! implements [Scene] rather than writing out the frame rate, input & particles of its own loop.
+//! The title, game & options menus change the config as they are navigated & the game has its own
+//! input & timing, so those are still loops of their own, run as the phases of `main`.
+
+use crate::menu_input::MenuInputKey;
+use sdl2::rect::Point;
+use sdl2::render::WindowCanvas;
+use std::time::Duration;
+
+/// What became of a key pressed on a scene
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SceneInput<T> {
+    /// the key does nothing on this scene
+    Ignored,
+    /// the key changed the scene, acknowledged with a chime
+    Handled,
+    /// the scene is finished
+    Exit(T),
+}
+
+pub trait Scene {
+    type Output;
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<Self::Output>;
+
+    /// The mouse moved over the scene
+    fn hover(&mut self, _point: Point) {}
+
+    fn update(&mut self, _delta: Duration) {}
+
+    /// Draws over the cleared window & background particles
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String>;
+}
@@ -0,0 +1,166 @@
+use crate::font::{FontTexture, FontType};
+use crate::game::seed::{decode, encode, SEED_ALPHABET, SEED_CODE_LENGTH};
+use crate::menu_input::MenuInputKey;
+use crate::scene::{Scene, SceneInput};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+
+const FONT_COLOR: Color = Color::WHITE;
+const HINT_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+const TITLE: &str = "seed";
+const HINT: &str = "up/down: change    left/right: move    start: confirm    back: cancel";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedEntryAction {
+    Confirm(u32),
+    Cancel,
+}
+
+/// Seed code entry, one character at a time like a high score name
+pub struct SeedEntryScreen<'a> {
+    title: FontTexture<'a>,
+    hint: FontTexture<'a>,
+    /// rendered once for every character of the seed alphabet
+    glyphs: Vec<FontTexture<'a>>,
+    glyph_width: u32,
+    glyph_height: u32,
+    code: [usize; SEED_CODE_LENGTH],
+    current_char: usize,
+    padding: u32,
+    window_size: (u32, u32),
+}
+
+impl<'a> SeedEntryScreen<'a> {
+    pub fn new(
+        seed: u32,
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        (window_width, window_height): (u32, u32),
+    ) -> Result<Self, String> {
+        let title_font = FontType::Retro.load(ttf, window_width / 24)?;
+        let code_font = FontType::Retro.load(ttf, window_width / 12)?;
+        let hint_font = FontType::Bold.load(ttf, window_height / 40)?;
+
+        let title = FontTexture::from_string(&title_font, texture_creator, TITLE, FONT_COLOR)?;
+        let hint = FontTexture::from_string(&hint_font, texture_creator, HINT, HINT_COLOR)?;
+        let glyphs = SEED_ALPHABET
+            .iter()
+            .map(|c| {
+                FontTexture::from_string(&code_font, texture_creator, &c.to_string(), FONT_COLOR)
+            })
+            .collect::<Result<Vec<FontTexture>, String>>()?;
+
+        let mut code = [0; SEED_CODE_LENGTH];
+        for (i, c) in encode(seed).chars().enumerate() {
+            code[i] = SEED_ALPHABET.iter().position(|a| *a == c).unwrap();
+        }
+
+        Ok(Self {
+            title,
+            hint,
+            glyph_width: glyphs.iter().map(|g| g.width).max().unwrap_or(0),
+            glyph_height: glyphs.iter().map(|g| g.height).max().unwrap_or(0),
+            glyphs,
+            code,
+            current_char: 0,
+            padding: window_height / 32,
+            window_size: (window_width, window_height),
+        })
+    }
+
+    fn seed(&self) -> u32 {
+        let code = self.code.map(|i| SEED_ALPHABET[i]).iter().collect::<String>();
+        decode(&code).unwrap()
+    }
+
+    /// Returns the action when the entry is closed
+    pub fn read_key(&mut self, key: MenuInputKey) -> Option<SeedEntryAction> {
+        match key {
+            MenuInputKey::Up => self.move_char(1),
+            MenuInputKey::Down => self.move_char(-1),
+            MenuInputKey::Left => self.current_char = self.current_char.saturating_sub(1),
+            MenuInputKey::Right | MenuInputKey::Select => {
+                self.current_char = (self.current_char + 1).min(SEED_CODE_LENGTH - 1)
+            }
+            MenuInputKey::Start => return Some(SeedEntryAction::Confirm(self.seed())),
+            MenuInputKey::Back | MenuInputKey::Quit => return Some(SeedEntryAction::Cancel),
+            MenuInputKey::Wheel { up } => self.move_char(if up { 1 } else { -1 }),
+            MenuInputKey::Click { .. } => {}
+        }
+        None
+    }
+
+    fn move_char(&mut self, value: i32) {
+        let len = SEED_ALPHABET.len() as i32;
+        let current = self.code[self.current_char] as i32;
+        self.code[self.current_char] = (current + value).rem_euclid(len) as usize;
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        let (window_width, window_height) = self.window_size;
+        let centered_x = |width: u32| (window_width as i32 - width as i32).max(0) / 2;
+
+        canvas.copy(
+            &self.title.texture,
+            None,
+            Rect::new(
+                centered_x(self.title.width),
+                self.padding as i32,
+                self.title.width,
+                self.title.height,
+            ),
+        )?;
+
+        // each character is centered in a fixed width cell with a caret under the current one
+        let cell_width = self.glyph_width + self.padding / 2;
+        let mut x = centered_x(cell_width * SEED_CODE_LENGTH as u32);
+        let y = (window_height as i32 - self.glyph_height as i32) / 2;
+        canvas.set_draw_color(FONT_COLOR);
+        for (i, glyph) in self.code.iter().map(|c| &self.glyphs[*c]).enumerate() {
+            let glyph_x = x + (cell_width as i32 - glyph.width as i32) / 2;
+            canvas.copy(
+                &glyph.texture,
+                None,
+                Rect::new(glyph_x, y, glyph.width, glyph.height),
+            )?;
+            if i == self.current_char {
+                canvas.fill_rect(Rect::new(
+                    glyph_x,
+                    y + self.glyph_height as i32,
+                    glyph.width,
+                    (self.padding / 4).max(1),
+                ))?;
+            }
+            x += cell_width as i32;
+        }
+
+        canvas.copy(
+            &self.hint.texture,
+            None,
+            Rect::new(
+                centered_x(self.hint.width),
+                (window_height - self.padding - self.hint.height) as i32,
+                self.hint.width,
+                self.hint.height,
+            ),
+        )
+    }
+}
+
+impl Scene for SeedEntryScreen<'_> {
+    type Output = SeedEntryAction;
+
+    fn handle_input(&mut self, key: MenuInputKey) -> SceneInput<SeedEntryAction> {
+        match self.read_key(key) {
+            Some(action) => SceneInput::Exit(action),
+            None => SceneInput::Handled,
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        SeedEntryScreen::draw(self, canvas)
+    }
+}
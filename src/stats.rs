@@ -0,0 +1,45 @@
+use dr_rustario_core::stats::PlayerStats;
+use num_format::{Locale, ToFormattedString};
+use std::time::Duration;
+
+/// Label & value pairs of the lifetime statistics, followed by the record of each mode played
+pub fn stats_info(stats: &PlayerStats) -> Vec<(String, String)> {
+    let mut info = vec![
+        (
+            "games played".to_string(),
+            stats.games_played().to_formatted_string(&Locale::en),
+        ),
+        (
+            "viruses".to_string(),
+            stats.viruses_destroyed().to_formatted_string(&Locale::en),
+        ),
+        (
+            "highest combo".to_string(),
+            stats.highest_combo().to_formatted_string(&Locale::en),
+        ),
+        ("play time".to_string(), format_play_time(stats.play_time())),
+    ];
+    info.extend(stats.modes().map(|(mode, record)| {
+        (
+            mode.to_string(),
+            format!("{} won {} lost", record.wins, record.losses),
+        )
+    }));
+    info
+}
+
+fn format_play_time(time: Duration) -> String {
+    let minutes = time.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_play_time() {
+        assert_eq!(format_play_time(Duration::from_secs(59)), "0h 00m");
+        assert_eq!(format_play_time(Duration::from_secs(3 * 3600 + 5 * 60)), "3h 05m");
+    }
+}
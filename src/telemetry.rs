@@ -0,0 +1,135 @@
+//! Live game state written to files for streamers to pull into overlays, e.g. the text sources of
+//! OBS. `telemetry.json` has every player while `p1_score.txt` etc. each hold a single value.
+
+use crate::config::TelemetryConfig;
+use crate::event_bus::{EventContext, EventSink};
+use crate::game::event::GameEvent;
+use crate::game::metrics::GameMetrics;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Files are written at most this often while events flow, the end of a level or game is always
+/// written straight away
+const WRITE_PERIOD: Duration = Duration::from_secs(1);
+
+pub struct TelemetrySink {
+    directory: PathBuf,
+    last_write: Option<Instant>,
+}
+
+impl TelemetrySink {
+    pub fn new(config: &TelemetryConfig) -> Result<Self, String> {
+        std::fs::create_dir_all(&config.directory).map_err(|e| e.to_string())?;
+        Ok(Self {
+            directory: config.directory.clone(),
+            last_write: None,
+        })
+    }
+
+    /// Each file is written to a temporary file then renamed over it, so a file is never read
+    /// half written
+    fn write(&self, metrics: &[GameMetrics]) -> Result<(), String> {
+        let mut files = text_files(metrics);
+        files.push(("telemetry.json".to_string(), to_json(metrics)));
+        for (name, contents) in files {
+            let path = self.directory.join(name);
+            let temp_path = path.with_extension("tmp");
+            std::fs::write(&temp_path, contents).map_err(|e| e.to_string())?;
+            std::fs::rename(&temp_path, &path).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+impl EventSink for TelemetrySink {
+    fn receive(&mut self, event: &GameEvent, context: &mut EventContext) -> Result<(), String> {
+        let is_due = self
+            .last_write
+            .map_or(true, |t| t.elapsed() >= WRITE_PERIOD);
+        let is_final = matches!(
+            event,
+            GameEvent::LevelComplete { .. }
+                | GameEvent::GameOver { .. }
+                | GameEvent::Victory { .. }
+        );
+        if is_due || is_final {
+            let metrics = context
+                .fixture
+                .players
+                .iter()
+                .map(|p| p.game().metrics())
+                .collect::<Vec<GameMetrics>>();
+            // e.g. a locked file or an unreachable share, the overlay is not worth ending the game
+            if let Err(error) = self.write(&metrics) {
+                println!("failed to write telemetry: {}", error);
+            }
+            self.last_write = Some(Instant::now());
+        }
+        Ok(())
+    }
+}
+
+/// The values of each player, numbered from 1 like the config
+fn values(metrics: &GameMetrics) -> [(&'static str, u32); 4] {
+    [
+        ("score", metrics.score()),
+        ("level", metrics.virus_level()),
+        ("viruses", metrics.virus_count()),
+        ("speed", metrics.speed_level()),
+    ]
+}
+
+fn text_files(metrics: &[GameMetrics]) -> Vec<(String, String)> {
+    metrics
+        .iter()
+        .flat_map(|m| {
+            values(m)
+                .into_iter()
+                .map(move |(name, value)| (format!("p{}_{}.txt", m.player() + 1, name), value))
+        })
+        .map(|(file_name, value)| (file_name, value.to_string()))
+        .collect()
+}
+
+fn to_json(metrics: &[GameMetrics]) -> String {
+    let players = metrics
+        .iter()
+        .map(|m| {
+            let fields = values(m)
+                .iter()
+                .map(|(name, value)| format!("\"{}\":{}", name, value))
+                .collect::<Vec<String>>()
+                .join(",");
+            format!("{{\"player\":{},{}}}", m.player() + 1, fields)
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    format!("{{\"players\":[{}]}}", players)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::pill::PillShape;
+    use crate::game::random::PEEK_SIZE;
+    use crate::game::GameSpeed;
+
+    #[test]
+    fn writes_values_of_each_player() {
+        let queue = [PillShape::RB; PEEK_SIZE];
+        let metrics = [
+            GameMetrics::new(0, 5, GameSpeed::Low, 12, 300, queue, None).with_speed_level(2),
+            GameMetrics::new(1, 7, GameSpeed::Low, 20, 0, queue, None),
+        ];
+        assert_eq!(
+            to_json(&metrics),
+            "{\"players\":[\
+            {\"player\":1,\"score\":300,\"level\":5,\"viruses\":12,\"speed\":2},\
+            {\"player\":2,\"score\":0,\"level\":7,\"viruses\":20,\"speed\":0}]}"
+        );
+        let files = text_files(&metrics);
+        assert_eq!(files.len(), 8);
+        assert_eq!(files[0], ("p1_score.txt".to_string(), "300".to_string()));
+        assert_eq!(files[6], ("p2_viruses.txt".to_string(), "20".to_string()));
+    }
+}
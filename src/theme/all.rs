@@ -1,8 +1,13 @@
 use crate::config::Config;
+use crate::game::rules::MatchThemes;
+use crate::theme::arcade::arcade_theme;
+use crate::theme::external::external_themes;
+use crate::theme::gameboy::gameboy_theme;
 use crate::theme::n64::n64_theme;
 use crate::theme::nes::nes_theme;
 use crate::theme::particle::particle_theme;
 use crate::theme::snes::snes_theme;
+use crate::theme::sound::UserMusic;
 use crate::theme::{AnimationMeta, Theme};
 use sdl2::render::{TextureCreator, WindowCanvas};
 use sdl2::ttf::Sdl2TtfContext;
@@ -13,6 +18,7 @@ pub struct AllThemeMeta {
     pub nes: AnimationMeta,
     pub snes: AnimationMeta,
     pub n64: AnimationMeta,
+    pub gameboy: AnimationMeta,
     pub particle: AnimationMeta,
 }
 
@@ -20,7 +26,11 @@ pub struct AllThemes<'a> {
     nes: Theme<'a>,
     snes: Theme<'a>,
     n64: Theme<'a>,
+    gameboy: Theme<'a>,
+    arcade: Theme<'a>,
     particle: Theme<'a>,
+    external: Vec<Theme<'a>>,
+    external_errors: Vec<String>,
     meta: AllThemeMeta,
 }
 
@@ -29,29 +39,79 @@ impl<'a> AllThemes<'a> {
         canvas: &mut WindowCanvas,
         texture_creator: &'a TextureCreator<WindowContext>,
         ttf: &Sdl2TtfContext,
-        config: Config,
+        config: &Config,
     ) -> Result<Self, String> {
-        let nes = nes_theme(canvas, texture_creator, config)?;
-        let snes = snes_theme(canvas, texture_creator, config)?;
-        let n64 = n64_theme(canvas, texture_creator, config)?;
-        let particle = particle_theme(canvas, texture_creator, ttf, config)?;
+        let mut nes = nes_theme(canvas, texture_creator, config)?;
+        let mut snes = snes_theme(canvas, texture_creator, config)?;
+        let mut n64 = n64_theme(canvas, texture_creator, config)?;
+        let mut gameboy = gameboy_theme(canvas, texture_creator, config)?;
+        let mut arcade = arcade_theme(canvas, texture_creator, config)?;
+        let mut particle = particle_theme(canvas, texture_creator, ttf, config)?;
+        let (mut external, external_errors) = external_themes(canvas, texture_creator, config);
+        let user_music = UserMusic::load(&config.music);
+        for theme in [
+            &mut nes,
+            &mut snes,
+            &mut n64,
+            &mut gameboy,
+            &mut arcade,
+            &mut particle,
+        ]
+        .into_iter()
+        .chain(external.iter_mut())
+        {
+            theme.audio.set_user_music(&user_music);
+        }
         let meta = AllThemeMeta {
             nes: nes.animation_meta.clone(),
             snes: snes.animation_meta.clone(),
             n64: n64.animation_meta.clone(),
+            gameboy: gameboy.animation_meta.clone(),
             particle: particle.animation_meta.clone(),
         };
         Ok(Self {
             nes,
             snes,
             n64,
+            gameboy,
+            arcade,
             particle,
+            external,
+            external_errors,
             meta,
         })
     }
 
+    /// The built-in themes followed by any external themes
     pub fn all(&self) -> Vec<&Theme<'a>> {
-        vec![&self.nes, &self.snes, &self.n64, &self.particle]
+        let mut all = vec![
+            &self.nes,
+            &self.snes,
+            &self.n64,
+            &self.gameboy,
+            &self.arcade,
+            &self.particle,
+        ];
+        all.extend(self.external.iter());
+        all
+    }
+
+    /// External themes that failed to load, these are left out of the game
+    pub fn external_errors(&self) -> &[String] {
+        &self.external_errors
+    }
+
+    /// Theme picked in the game menu, None when running all themes
+    pub fn selected(&self, themes: MatchThemes) -> Option<&Theme<'a>> {
+        match themes {
+            MatchThemes::All => None,
+            MatchThemes::Nes => Some(&self.nes),
+            MatchThemes::Snes => Some(&self.snes),
+            MatchThemes::N64 => Some(&self.n64),
+            MatchThemes::GameBoy => Some(&self.gameboy),
+            MatchThemes::Arcade => Some(&self.arcade),
+            MatchThemes::Particle => Some(&self.particle),
+        }
     }
 
     pub fn meta(&self) -> AllThemeMeta {
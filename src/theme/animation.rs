@@ -1,11 +1,67 @@
 use sdl2::pixels::Color;
 use crate::theme::block_mask::BlockMask;
 use crate::theme::helper::{TextureFactory, TextureQuery};
+use crate::theme::hue::rotate_texture_hue;
 
 use sdl2::rect::{Point, Rect};
 use sdl2::render::{Texture, TextureCreator, WindowCanvas};
 use sdl2::video::WindowContext;
 
+/// Slight upscaling of a variant is allowed before a higher resolution is used
+const VARIANT_UPSCALE_TOLERANCE: f64 = 1.1;
+
+/// A sprite sheet file at one or more source resolutions e.g. 1x, 2x & 4x,
+/// points & sizes describing the sheet are always given at 1x
+#[derive(Debug, Clone)]
+pub struct SpriteFile {
+    /// files by resolution, lowest first
+    variants: Vec<(u32, &'static [u8])>,
+}
+
+impl SpriteFile {
+    pub fn new(file: &'static [u8]) -> Self {
+        Self {
+            variants: vec![(1, file)],
+        }
+    }
+
+    pub fn with_variant(mut self, resolution: u32, file: &'static [u8]) -> Self {
+        assert!(resolution > 0);
+        self.variants.retain(|(r, _)| *r != resolution);
+        self.variants.push((resolution, file));
+        self.variants.sort_by_key(|(r, _)| *r);
+        self
+    }
+
+    /// The lowest resolution that is large enough to draw the sheet at scale times its 1x size,
+    /// so large windows get crisp sprites & small windows do not scale down huge textures.
+    /// Falls back to the highest resolution when none are large enough
+    pub fn variant(&self, scale: f64) -> (u32, &'static [u8]) {
+        self.variants
+            .iter()
+            .copied()
+            .find(|(resolution, _)| *resolution as f64 * VARIANT_UPSCALE_TOLERANCE >= scale)
+            .unwrap_or(*self.variants.last().unwrap())
+    }
+}
+
+impl From<&'static [u8]> for SpriteFile {
+    fn from(file: &'static [u8]) -> Self {
+        Self::new(file)
+    }
+}
+
+/// Scales a 1x snip of a sprite sheet into a variant at the resolution
+pub fn snip_at_resolution(rect: Rect, resolution: u32) -> Rect {
+    let resolution = resolution as i32;
+    Rect::new(
+        rect.x() * resolution,
+        rect.y() * resolution,
+        rect.width() * resolution as u32,
+        rect.height() * resolution as u32,
+    )
+}
+
 #[derive(Debug, Clone)]
 enum FrameFormat {
     /// texture only contains a linear set of square frames, nothing else
@@ -35,30 +91,35 @@ enum FrameFormat {
 
 #[derive(Debug, Clone)]
 pub struct AnimationSpriteSheetData {
-    file: &'static [u8],
+    file: SpriteFile,
     format: FrameFormat,
 }
 
 impl AnimationSpriteSheetData {
-    pub fn exclusive_square_linear(file: &'static [u8]) -> Self {
+    pub fn exclusive_square_linear<F: Into<SpriteFile>>(file: F) -> Self {
         Self {
-            file,
+            file: file.into(),
             format: FrameFormat::ExclusiveSquareLinear,
         }
     }
 
-    pub fn exclusive_linear(file: &'static [u8], frames: u32) -> Self {
+    pub fn exclusive_linear<F: Into<SpriteFile>>(file: F, frames: u32) -> Self {
         assert!(frames > 0);
         Self {
-            file,
+            file: file.into(),
             format: FrameFormat::ExclusiveLinear { count: frames },
         }
     }
 
-    pub fn exclusive_table(file: &'static [u8], rows: u32, cols: u32, frames: u32) -> Self {
+    pub fn exclusive_table<F: Into<SpriteFile>>(
+        file: F,
+        rows: u32,
+        cols: u32,
+        frames: u32,
+    ) -> Self {
         assert!(rows > 0 && cols > 0);
         Self {
-            file,
+            file: file.into(),
             format: FrameFormat::ExclusiveTable {
                 rows,
                 cols,
@@ -67,8 +128,8 @@ impl AnimationSpriteSheetData {
         }
     }
 
-    pub fn non_exclusive_linear(
-        file: &'static [u8],
+    pub fn non_exclusive_linear<F: Into<SpriteFile>>(
+        file: F,
         start: Point,
         frames: u32,
         frame_width: u32,
@@ -76,7 +137,7 @@ impl AnimationSpriteSheetData {
     ) -> Self {
         assert!(frames > 0 && frame_width > 0 && frame_height > 0);
         Self {
-            file,
+            file: file.into(),
             format: FrameFormat::NonExclusiveLinear {
                 start,
                 count: frames,
@@ -86,9 +147,9 @@ impl AnimationSpriteSheetData {
         }
     }
 
-    pub fn static_first_square_frame(file: &'static [u8]) -> Self {
+    pub fn static_first_square_frame<F: Into<SpriteFile>>(file: F) -> Self {
         Self {
-            file,
+            file: file.into(),
             format: FrameFormat::StaticExclusiveSquare { frame: 0 },
         }
     }
@@ -102,11 +163,15 @@ impl AnimationSpriteSheetData {
         }
     }
 
+    /// Loads the variant of the sheet for drawing at scale times its 1x size,
+    /// returned with its resolution as the frames are still to be scaled the rest of the way
     pub fn sprite_sheet<'a>(
         &self,
         texture_creator: &'a TextureCreator<WindowContext>,
-    ) -> Result<AnimationSpriteSheet<'a>, String> {
-        let texture = texture_creator.load_texture_bytes_blended(self.file)?;
+        scale: f64,
+    ) -> Result<(AnimationSpriteSheet<'a>, u32), String> {
+        let (resolution, file) = self.file.variant(scale);
+        let texture = texture_creator.load_texture_bytes_blended(file)?;
         let (texture_width, texture_height) = texture.size();
 
         let frames = match self.format {
@@ -123,12 +188,13 @@ impl AnimationSpriteSheetData {
                 height: frame_height,
             } => (0..count)
                 .map(|i| {
-                    Rect::new(
+                    let frame = Rect::new(
                         (i * frame_width) as i32 + start.x,
                         start.y,
                         frame_width,
                         frame_height,
-                    )
+                    );
+                    snip_at_resolution(frame, resolution)
                 })
                 .collect(),
             FrameFormat::ExclusiveSquareLinear => {
@@ -173,7 +239,7 @@ impl AnimationSpriteSheetData {
             }
         };
 
-        Ok(AnimationSpriteSheet::new(texture, frames))
+        Ok((AnimationSpriteSheet::new(texture, frames), resolution))
     }
 }
 
@@ -307,6 +373,28 @@ impl<'a> AnimationSpriteSheet<'a> {
         Ok(AnimationSpriteSheet::new(texture, self.frames.clone()))
     }
 
+    /// Draws over every frame in place, e.g. to mark the frames for accessibility
+    pub fn overlay_frames<F: Fn(&mut WindowCanvas, Rect) -> Result<(), String>>(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        overlay: F,
+    ) -> Result<(), String> {
+        let frames = &self.frames;
+        canvas
+            .with_texture_canvas(&mut self.texture, |c| {
+                for frame in frames.iter() {
+                    overlay(c, *frame).unwrap();
+                }
+            })
+            .map_err(|e| e.to_string())
+    }
+
+    /// Rotates the hue of every frame in place, e.g. to tint a copy of one color into another
+    pub fn rotate_hue(&mut self, canvas: &mut WindowCanvas, degrees: f64) -> Result<(), String> {
+        let (width, height) = self.texture.size();
+        rotate_texture_hue(canvas, &mut self.texture, Rect::new(0, 0, width, height), degrees)
+    }
+
     pub fn block_mask(
         &mut self,
         canvas: &mut WindowCanvas,
@@ -315,3 +403,29 @@ impl<'a> AnimationSpriteSheet<'a> {
         BlockMask::from_texture(canvas, &mut self.texture, self.frames[frame])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOW: &[u8] = &[1];
+    const HIGH: &[u8] = &[2];
+
+    #[test]
+    fn variant_by_scale() {
+        let file = SpriteFile::new(LOW).with_variant(2, HIGH);
+        assert_eq!(file.variant(0.5), (1, LOW));
+        // slight upscaling keeps the lower resolution
+        assert_eq!(file.variant(1.05), (1, LOW));
+        assert_eq!(file.variant(1.5), (2, HIGH));
+        assert_eq!(file.variant(4.0), (2, HIGH));
+        assert_eq!(SpriteFile::new(LOW).variant(4.0), (1, LOW));
+    }
+
+    #[test]
+    fn snip_scaled_to_resolution() {
+        let snip = Rect::new(10, 5, 7, 9);
+        assert_eq!(snip_at_resolution(snip, 1), snip);
+        assert_eq!(snip_at_resolution(snip, 4), Rect::new(40, 20, 28, 36));
+    }
+}
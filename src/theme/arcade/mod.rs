@@ -0,0 +1,61 @@
+use crate::config::Config;
+use crate::menu::{MenuChime, MenuStyle};
+use crate::theme::nes::nes_theme_options;
+use crate::theme::retro::{retro_theme, RetroThemeOptions};
+use crate::theme::scene::SceneType;
+use crate::theme::{Theme, ThemeName};
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::video::WindowContext;
+
+mod sprites {
+    /// Both player panels side by side, split by the vs. column
+    pub const BACKGROUND: &[u8] = include_bytes!("background.png");
+}
+
+const PANEL_WIDTH: u32 = 156;
+const PANEL_HEIGHT: u32 = 176;
+const VS_COLUMN_WIDTH: i32 = 24;
+
+/// Vs. Dr. Mario, the arcade release on the NES hardware. Both bottles share one cabinet background
+pub fn arcade_theme<'a>(
+    canvas: &mut WindowCanvas,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    config: &Config,
+) -> Result<Theme<'a>, String> {
+    let options = RetroThemeOptions {
+        name: ThemeName::Arcade,
+        scene_low: SceneType::Checkerboard {
+            width: 8,
+            height: 8,
+            colors: [Color::BLACK, Color::RGB(0x50, 0x00, 0x00)],
+        },
+        scene_medium: SceneType::Checkerboard {
+            width: 8,
+            height: 8,
+            colors: [Color::BLACK, Color::RGB(0x00, 0x1f, 0x5f)],
+        },
+        scene_high: SceneType::Checkerboard {
+            width: 8,
+            height: 8,
+            colors: [Color::BLACK, Color::RGB(0x4f, 0x3f, 0x00)],
+        },
+        background_file: sprites::BACKGROUND,
+        virus_counters: None,
+        menu_style: MenuStyle {
+            highlight_color: Color::RGB(0xc4, 0xb2, 0x00),
+            selected_color: Color::BLACK,
+            chime: MenuChime::Retro,
+            ..MenuStyle::default()
+        },
+        ..nes_theme_options(texture_creator, config)?
+    };
+
+    let panels = vec![
+        Point::new(0, 0),
+        Point::new(PANEL_WIDTH as i32 + VS_COLUMN_WIDTH, 0),
+    ];
+    Ok(retro_theme(canvas, texture_creator, options)?
+        .with_shared_background((PANEL_WIDTH, PANEL_HEIGHT), panels))
+}
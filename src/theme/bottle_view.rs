@@ -0,0 +1,182 @@
+use crate::animate::PlayerAnimations;
+use crate::game::block::Block;
+use crate::game::bottle::{BottleSize, BOTTLE_HEIGHT, BOTTLE_WIDTH, TOTAL_BLOCKS};
+use crate::game::geometry::BottlePoint;
+use crate::game::pill::{PillEffect, Vitamins};
+use crate::game::{Game, GameSpeed};
+use crate::theme::helper::TextureFactory;
+use crate::theme::Theme;
+use sdl2::rect::Rect;
+use sdl2::render::{Texture, TextureCreator, WindowCanvas};
+use sdl2::video::WindowContext;
+use std::time::Duration;
+
+/// Anything that can be drawn into a themed bottle
+pub trait BottleBlocks {
+    fn row(&self, y: u32) -> &[Block];
+    fn speed(&self) -> GameSpeed;
+    fn size(&self) -> BottleSize;
+
+    /// Falling power-up pills, marked over their vitamins
+    fn power_ups(&self) -> Vec<(Vitamins, PillEffect)> {
+        vec![]
+    }
+}
+
+impl BottleBlocks for Game {
+    fn row(&self, y: u32) -> &[Block] {
+        Game::row(self, y)
+    }
+
+    fn speed(&self) -> GameSpeed {
+        Game::speed(self)
+    }
+
+    fn size(&self) -> BottleSize {
+        self.bottle_size()
+    }
+
+    fn power_ups(&self) -> Vec<(Vitamins, PillEffect)> {
+        Game::power_ups(self)
+    }
+}
+
+/// A static board that is not attached to a running game e.g. a level preview,
+/// always of the classic size
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BottleSnapshot {
+    blocks: [Block; TOTAL_BLOCKS as usize],
+    speed: GameSpeed,
+}
+
+impl BottleSnapshot {
+    pub fn new(speed: GameSpeed) -> Self {
+        Self {
+            blocks: [Block::Empty; TOTAL_BLOCKS as usize],
+            speed,
+        }
+    }
+
+    /// Copies the blocks of any bottle, clipped to the classic size
+    pub fn capture<B: BottleBlocks>(source: &B) -> Self {
+        let mut snapshot = Self::new(source.speed());
+        let size = source.size();
+        for y in 0..BOTTLE_HEIGHT.min(size.height()) {
+            for (x, block) in source.row(y).iter().take(BOTTLE_WIDTH as usize).enumerate() {
+                snapshot.set_block(BottlePoint::new(x as i32, y as i32), *block);
+            }
+        }
+        snapshot
+    }
+
+    pub fn set_block(&mut self, point: BottlePoint, block: Block) {
+        self.blocks[Self::index(point)] = block;
+    }
+
+    fn index(point: BottlePoint) -> usize {
+        debug_assert!(point.x() >= 0 && point.x() < BOTTLE_WIDTH as i32);
+        debug_assert!(point.y() >= 0 && point.y() < BOTTLE_HEIGHT as i32);
+        (point.y() as u32 * BOTTLE_WIDTH + point.x() as u32) as usize
+    }
+}
+
+impl BottleBlocks for BottleSnapshot {
+    fn row(&self, y: u32) -> &[Block] {
+        let start = (y * BOTTLE_WIDTH) as usize;
+        &self.blocks[start..start + BOTTLE_WIDTH as usize]
+    }
+
+    fn speed(&self) -> GameSpeed {
+        self.speed
+    }
+
+    fn size(&self) -> BottleSize {
+        BottleSize::CLASSIC
+    }
+}
+
+/// Draws a themed bottle at any size or position, outside of a match.
+/// Owns its own animations so viruses keep dancing without a running game.
+pub struct BottleView<'a> {
+    theme: &'a Theme<'a>,
+    texture: Texture<'a>,
+    animations: PlayerAnimations,
+}
+
+impl<'a> BottleView<'a> {
+    pub fn new(
+        theme: &'a Theme<'a>,
+        texture_creator: &'a TextureCreator<WindowContext>,
+    ) -> Result<Self, String> {
+        let (width, height) = Self::theme_size(theme);
+        Ok(Self {
+            theme,
+            texture: texture_creator.create_texture_target_blended(width, height)?,
+            animations: PlayerAnimations::new(0, theme),
+        })
+    }
+
+    fn theme_size(theme: &Theme) -> (u32, u32) {
+        let snip = theme.bottle_snip();
+        (snip.width(), snip.height())
+    }
+
+    /// Native size of the bottle in the current theme
+    pub fn size(&self) -> (u32, u32) {
+        Self::theme_size(self.theme)
+    }
+
+    /// Largest rect with the native aspect ratio that fits centered in the bounds
+    pub fn fit(&self, bounds: Rect) -> Rect {
+        let (width, height) = self.size();
+        let scale = (bounds.width() as f64 / width as f64)
+            .min(bounds.height() as f64 / height as f64);
+        let mut rect = Rect::new(
+            0,
+            0,
+            (width as f64 * scale).round() as u32,
+            (height as f64 * scale).round() as u32,
+        );
+        rect.center_on(bounds.center());
+        rect
+    }
+
+    pub fn update(&mut self, delta: Duration) {
+        self.animations.update(delta);
+    }
+
+    pub fn draw<B: BottleBlocks>(
+        &mut self,
+        canvas: &mut WindowCanvas,
+        blocks: &B,
+        dest: Rect,
+    ) -> Result<(), String> {
+        let theme = self.theme;
+        let animations = &self.animations;
+        let mut result = Ok(());
+        canvas
+            .with_texture_canvas(&mut self.texture, |texture_canvas| {
+                result = theme.draw_bottle(texture_canvas, blocks, animations, None);
+            })
+            .map_err(|e| e.to_string())?;
+        result?;
+
+        let (width, height) = self.size();
+        canvas.copy(&self.texture, Rect::new(0, 0, width, height), dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::pill::VirusColor;
+
+    #[test]
+    fn snapshot_rows() {
+        let mut snapshot = BottleSnapshot::new(GameSpeed::Low);
+        snapshot.set_block(BottlePoint::new(3, 2), Block::Virus(VirusColor::Red));
+        assert_eq!(snapshot.row(2)[3], Block::Virus(VirusColor::Red));
+        assert!(snapshot.row(1).iter().all(|b| b.is_empty()));
+        assert_eq!(BottleSnapshot::capture(&snapshot), snapshot);
+    }
+}
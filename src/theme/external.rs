@@ -0,0 +1,779 @@
+//! Retro themes loaded at startup from `themes/<theme>/theme.toml` next to the config,
+//! so new skins can be added without recompiling.
+//! The descriptor mirrors `RetroThemeOptions`, with every file relative to the theme directory.
+//! Sounds & music that are left out are borrowed from the NES theme.
+
+use crate::animate::dr::DrAnimationType;
+use crate::animate::virus::VirusAnimationType;
+use crate::config::{config_path, Config};
+use crate::game::random::MAX_VIRUSES;
+use crate::game::rules::{MusicTrack, MAX_VIRUS_LEVEL};
+use crate::game::{MAX_SCORE, MAX_SPEED_LEVEL};
+use crate::menu::{MenuChime, MenuStyle};
+use crate::theme::animation::AnimationSpriteSheetData;
+use crate::theme::font::{FontRenderOptions, FontThemeOptions, MetricSnips, ThemedNumeric};
+use crate::theme::gameboy::sound as gameboy_sound;
+use crate::theme::geometry::BottleGeometry;
+use crate::theme::nes::sound as nes_sound;
+use crate::theme::retro::{retro_theme, RetroThemeOptions};
+use crate::theme::scene::SceneType;
+use crate::theme::sound::AudioTheme;
+use crate::theme::sprite_sheet::{pills, BlockAnimationsData, BlockPoints, VitaminSpriteSheetData};
+use crate::theme::{SpeedVariants, Theme, ThemeName};
+use sdl2::pixels::Color;
+use sdl2::rect::Point;
+use sdl2::render::{TextureCreator, WindowCanvas};
+use sdl2::video::WindowContext;
+use serde::{Deserialize, Deserializer};
+use std::path::{Path, PathBuf};
+
+pub const DESCRIPTOR_NAME: &str = "theme.toml";
+
+type Position = [i32; 2];
+
+fn point([x, y]: Position) -> Point {
+    Point::new(x, y)
+}
+
+/// Colors are written as hex e.g. "#d82800"
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct HexColor(Color);
+
+impl<'de> Deserialize<'de> for HexColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        parse_color(&value).map(HexColor).map_err(serde::de::Error::custom)
+    }
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    let hex = value
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6)
+        .ok_or_else(|| format!("invalid color {}, expected #rrggbb", value))?;
+    let rgb = u32::from_str_radix(hex, 16).map_err(|_| format!("invalid color {}", value))?;
+    Ok(Color::RGB((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeDescriptor {
+    name: String,
+    /// size of a single block in the vitamins sprite sheet
+    block_size: u32,
+    /// alpha of the hard drop preview
+    #[serde(default = "default_ghost_alpha")]
+    ghost_alpha: u8,
+    vitamins: VitaminsDescriptor,
+    dr: DrDescriptor,
+    files: FilesDescriptor,
+    bottles: SpeedVariantsDescriptor,
+    match_end: MatchEndDescriptor,
+    layout: LayoutDescriptor,
+    scenes: ScenesDescriptor,
+    #[serde(default)]
+    menu: MenuDescriptor,
+    #[serde(default)]
+    sounds: SoundsDescriptor,
+    #[serde(default)]
+    music: MusicDescriptor,
+}
+
+fn default_ghost_alpha() -> u8 {
+    0x40
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VitaminsDescriptor {
+    file: String,
+    pill_size: [u32; 2],
+    pills: PillsDescriptor,
+    virus_idle_frames: u32,
+    virus_pop_frames: u32,
+    vitamin_pop_frames: u32,
+    yellow: BlocksDescriptor,
+    red: BlocksDescriptor,
+    blue: BlocksDescriptor,
+    /// Only used in four color mode, the blue blocks are tinted green when not given
+    green: Option<BlocksDescriptor>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PillsDescriptor {
+    yy: Position,
+    yb: Position,
+    yr: Position,
+    bb: Position,
+    by: Position,
+    br: Position,
+    rr: Position,
+    ry: Position,
+    rb: Position,
+}
+
+/// Top left of each block of one color, animation frames run left to right
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlocksDescriptor {
+    north: [Position; 2],
+    east: [Position; 2],
+    south: [Position; 2],
+    west: [Position; 2],
+    garbage: Position,
+    virus_idle: Position,
+    virus_pop: Position,
+    vitamin_pop: Position,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DrDescriptor {
+    throw: AnimationDescriptor,
+    game_over: AnimationDescriptor,
+    victory: AnimationDescriptor,
+    idle: AnimationDescriptor,
+}
+
+/// Frames of the same size, side by side
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnimationDescriptor {
+    file: String,
+    frames: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FilesDescriptor {
+    background: String,
+    bottles: String,
+    /// digits 0-9 of equal width
+    font: String,
+    match_end: String,
+}
+
+/// Top left of the bottle art in the bottles file for each speed
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpeedVariantsDescriptor {
+    low: Position,
+    medium: Position,
+    high: Position,
+}
+
+/// Frames of the game over & next level screens in the match end file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MatchEndDescriptor {
+    game_over: Vec<Position>,
+    next_level: Vec<Position>,
+}
+
+/// Positions relative to the background
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayoutDescriptor {
+    bottle: Position,
+    bottle_size: [u32; 2],
+    /// top left of the blocks relative to the bottle
+    blocks: Position,
+    #[serde(default)]
+    block_overlap: i32,
+    dr_hand: Position,
+    dr_throw: Position,
+    #[serde(default)]
+    dr_throw_end_offset: Position,
+    dr_game_over: Position,
+    dr_victory: Position,
+    #[serde(default)]
+    dr_order_first: bool,
+    hold: Position,
+    peek: Position,
+    peek_offset: i32,
+    peek_max: u32,
+    #[serde(default)]
+    peek_scale: Option<f64>,
+    garbage: Position,
+    score: Position,
+    virus_level: Position,
+    virus_count: Position,
+    /// the speed level is not shown unless there is somewhere to put it
+    #[serde(default)]
+    speed_level: Option<Position>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenesDescriptor {
+    low: SceneDescriptor,
+    medium: SceneDescriptor,
+    high: SceneDescriptor,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum SceneDescriptor {
+    Checkerboard { size: [u32; 2], colors: [HexColor; 2] },
+    Tile { file: String },
+    Particles { color: HexColor },
+    Snow { color: HexColor },
+    Bubbles { color: HexColor },
+    Starfield { color: HexColor },
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MenuDescriptor {
+    highlight_color: Option<HexColor>,
+    selected_color: Option<HexColor>,
+    text_color: Option<HexColor>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SoundsDescriptor {
+    #[serde(rename = "move")]
+    move_pill: Option<String>,
+    rotate: Option<String>,
+    drop: Option<String>,
+    hard_drop: Option<String>,
+    destroy_virus: Option<String>,
+    destroy_virus_combo: Option<String>,
+    destroy_vitamin: Option<String>,
+    destroy_vitamin_combo: Option<String>,
+    pause: Option<String>,
+    speed_level_up: Option<String>,
+    receive_garbage: Option<String>,
+    next_level_jingle: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MusicDescriptor {
+    fever: Option<MusicFile>,
+    chill: Option<MusicFile>,
+    next_level: Option<MusicFile>,
+    game_over: Option<MusicFile>,
+    victory: Option<MusicFile>,
+}
+
+/// The file is played first then the repeat loops.
+/// Without a repeat the file loops or plays once, the same as the embedded music.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MusicFile {
+    file: String,
+    repeat: Option<String>,
+}
+
+impl ThemeDescriptor {
+    fn parse(toml: &str) -> Result<Self, String> {
+        let descriptor: Self = toml::from_str(toml).map_err(|e| e.to_string())?;
+        descriptor.validate()?;
+        Ok(descriptor)
+    }
+
+    /// The sprite sheet loaders assert on these so they are checked up front
+    fn validate(&self) -> Result<(), String> {
+        let vitamins = &self.vitamins;
+        let layout = &self.layout;
+        let non_zero = [
+            ("block_size", self.block_size),
+            ("vitamins.pill_size", vitamins.pill_size[0].min(vitamins.pill_size[1])),
+            ("vitamins.virus_idle_frames", vitamins.virus_idle_frames),
+            ("vitamins.virus_pop_frames", vitamins.virus_pop_frames),
+            ("vitamins.vitamin_pop_frames", vitamins.vitamin_pop_frames),
+            ("dr.throw.frames", self.dr.throw.frames),
+            ("dr.game_over.frames", self.dr.game_over.frames),
+            ("dr.victory.frames", self.dr.victory.frames),
+            ("dr.idle.frames", self.dr.idle.frames),
+            ("layout.bottle_size", layout.bottle_size[0].min(layout.bottle_size[1])),
+        ];
+        if let Some((name, _)) = non_zero.iter().find(|(_, value)| *value == 0) {
+            return Err(format!("{} must be greater than zero", name));
+        }
+        if self.block_size as i32 + layout.block_overlap <= 0 {
+            return Err("layout.block_overlap must be smaller than block_size".to_string());
+        }
+        if self.match_end.game_over.is_empty() || self.match_end.next_level.is_empty() {
+            return Err("match_end needs at least one game_over & next_level frame".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Each theme is a directory in here with a descriptor
+pub fn themes_dir() -> Result<PathBuf, String> {
+    let mut path = config_path("config")?;
+    path.set_file_name("themes");
+    Ok(path)
+}
+
+/// Loads every external theme, any that fail are skipped & returned as errors to show in the menu
+pub fn external_themes<'a>(
+    canvas: &mut WindowCanvas,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    config: &Config,
+) -> (Vec<Theme<'a>>, Vec<String>) {
+    let mut themes = vec![];
+    let mut errors = vec![];
+    let themes_dir = match themes_dir() {
+        Ok(dir) => dir,
+        Err(error) => return (themes, vec![error]),
+    };
+    // no themes directory is the same as no themes
+    let mut dirs = std::fs::read_dir(themes_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.join(DESCRIPTOR_NAME).is_file())
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    // sorted by name so the order is stable
+    dirs.sort();
+
+    for dir in dirs {
+        let index = themes.len();
+        match external_theme(canvas, texture_creator, config, &dir, index) {
+            Ok(theme) => themes.push(theme),
+            Err(error) => {
+                let name = dir.file_name().unwrap_or_default().to_string_lossy();
+                let error = format!("{}: {}", name, error);
+                println!("cannot load theme {}", error);
+                errors.push(error);
+            }
+        }
+    }
+    (themes, errors)
+}
+
+/// Files are kept for the lifetime of the game like the embedded themes, loaders need static bytes
+fn read_file(dir: &Path, file_name: &str) -> Result<&'static [u8], String> {
+    let bytes = std::fs::read(dir.join(file_name)).map_err(|e| format!("{}: {}", file_name, e))?;
+    Ok(Box::leak(bytes.into_boxed_slice()))
+}
+
+fn read_optional(dir: &Path, file_name: &Option<String>) -> Result<Option<&'static [u8]>, String> {
+    file_name.as_ref().map(|f| read_file(dir, f)).transpose()
+}
+
+fn read_or(
+    dir: &Path,
+    file_name: &Option<String>,
+    default: &'static [u8],
+) -> Result<&'static [u8], String> {
+    Ok(read_optional(dir, file_name)?.unwrap_or(default))
+}
+
+/// Music file & the optional repeat
+type MusicBytes = (&'static [u8], Option<&'static [u8]>);
+
+fn read_music(dir: &Path, music: &Option<MusicFile>) -> Result<Option<MusicBytes>, String> {
+    music
+        .as_ref()
+        .map(|music| Ok((read_file(dir, &music.file)?, read_optional(dir, &music.repeat)?)))
+        .transpose()
+}
+
+fn external_theme<'a>(
+    canvas: &mut WindowCanvas,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    config: &Config,
+    dir: &Path,
+    index: usize,
+) -> Result<Theme<'a>, String> {
+    let toml = std::fs::read_to_string(dir.join(DESCRIPTOR_NAME))
+        .map_err(|e| format!("{}: {}", DESCRIPTOR_NAME, e))?;
+    let descriptor = ThemeDescriptor::parse(&toml)?;
+
+    let vitamins = &descriptor.vitamins;
+    let vitamins_file = read_file(dir, &vitamins.file)?;
+    let blocks = |blocks: &BlocksDescriptor| {
+        BlockPoints::new(
+            blocks.north.map(point),
+            blocks.east.map(point),
+            blocks.south.map(point),
+            blocks.west.map(point),
+            point(blocks.garbage),
+        )
+    };
+    let animations = |blocks: &BlocksDescriptor| {
+        BlockAnimationsData::non_exclusive_linear(
+            vitamins_file,
+            point(blocks.virus_idle),
+            vitamins.virus_idle_frames,
+            point(blocks.virus_pop),
+            vitamins.virus_pop_frames,
+            point(blocks.vitamin_pop),
+            vitamins.vitamin_pop_frames,
+            descriptor.block_size,
+        )
+    };
+    let [pill_width, pill_height] = vitamins.pill_size;
+    let dr = |animation: &AnimationDescriptor| -> Result<AnimationSpriteSheetData, String> {
+        Ok(AnimationSpriteSheetData::exclusive_linear(
+            read_file(dir, &animation.file)?,
+            animation.frames,
+        ))
+    };
+    let sprites = VitaminSpriteSheetData::new(
+        vitamins_file,
+        pills(
+            pill_width,
+            pill_height,
+            point(vitamins.pills.yy),
+            point(vitamins.pills.yb),
+            point(vitamins.pills.yr),
+            point(vitamins.pills.bb),
+            point(vitamins.pills.by),
+            point(vitamins.pills.br),
+            point(vitamins.pills.rr),
+            point(vitamins.pills.ry),
+            point(vitamins.pills.rb),
+        ),
+        (pill_width, pill_height),
+        blocks(&vitamins.yellow),
+        animations(&vitamins.yellow),
+        blocks(&vitamins.red),
+        animations(&vitamins.red),
+        blocks(&vitamins.blue),
+        animations(&vitamins.blue),
+        descriptor.block_size,
+        descriptor.ghost_alpha,
+        dr(&descriptor.dr.throw)?,
+        dr(&descriptor.dr.game_over)?,
+        dr(&descriptor.dr.victory)?,
+        dr(&descriptor.dr.idle)?,
+        None,
+    );
+    let sprites = match &vitamins.green {
+        Some(green) => sprites.with_green(blocks(green), animations(green)),
+        None => sprites,
+    };
+
+    let sounds = &descriptor.sounds;
+    let music = &descriptor.music;
+    let mut audio = AudioTheme::new(
+        config.audio,
+        read_or(dir, &sounds.move_pill, nes_sound::MOVE_PILL)?,
+        read_or(dir, &sounds.rotate, nes_sound::ROTATE)?,
+        read_or(dir, &sounds.drop, nes_sound::DROP)?,
+        read_or(dir, &sounds.destroy_virus, nes_sound::DESTROY_VIRUS)?,
+        read_or(dir, &sounds.destroy_virus_combo, nes_sound::DESTROY_VIRUS_COMBO)?,
+        read_or(dir, &sounds.destroy_vitamin, nes_sound::DESTROY_VITAMIN)?,
+        read_or(dir, &sounds.destroy_vitamin_combo, nes_sound::DESTROY_VITAMIN_COMBO)?,
+        read_or(dir, &sounds.pause, nes_sound::PAUSE)?,
+        read_or(dir, &sounds.speed_level_up, nes_sound::SPEED_LEVEL_UP)?,
+        read_or(dir, &sounds.receive_garbage, nes_sound::RECEIVE_GARBAGE)?,
+        read_or(dir, &sounds.next_level_jingle, nes_sound::NEXT_LEVEL_JINGLE)?,
+        read_optional(dir, &sounds.hard_drop)?,
+    )?;
+    audio = match read_music(dir, &music.fever)? {
+        Some((intro, repeat)) => audio.with_game_music(MusicTrack::Fever, intro, repeat)?,
+        None => audio.with_game_music(
+            MusicTrack::Fever,
+            nes_sound::FEVER_INTRO,
+            nes_sound::FEVER_REPEAT,
+        )?,
+    };
+    audio = match read_music(dir, &music.chill)? {
+        Some((intro, repeat)) => audio.with_game_music(MusicTrack::Chill, intro, repeat)?,
+        None => audio.with_game_music(MusicTrack::Chill, gameboy_sound::CHILL, None)?,
+    };
+    audio = match read_music(dir, &music.next_level)? {
+        Some((music, repeat)) => audio.with_next_level_music(music, repeat)?,
+        None => audio.with_next_level_music(
+            nes_sound::FEVER_NEXT_LEVEL_INTRO,
+            nes_sound::FEVER_NEXT_LEVEL_REPEAT,
+        )?,
+    };
+    audio = match read_music(dir, &music.game_over)? {
+        Some((music, repeat)) => audio.with_game_over_music(music, repeat)?,
+        None => {
+            audio.with_game_over_music(nes_sound::GAME_OVER_INTRO, nes_sound::GAME_OVER_REPEAT)?
+        }
+    };
+    audio = match read_music(dir, &music.victory)? {
+        Some((music, repeat)) => audio.with_victory_music(music, repeat)?,
+        None => audio.with_victory_music(nes_sound::VICTORY_INTRO, nes_sound::VICTORY_REPEAT)?,
+    };
+
+    let scene = |scene: &SceneDescriptor| -> Result<SceneType, String> {
+        Ok(match scene {
+            SceneDescriptor::Checkerboard {
+                size: [width, height],
+                colors: [a, b],
+            } => SceneType::Checkerboard {
+                width: *width,
+                height: *height,
+                colors: [a.0, b.0],
+            },
+            SceneDescriptor::Tile { file } => SceneType::Tile {
+                texture: read_file(dir, file)?,
+            },
+            SceneDescriptor::Particles { color } => SceneType::Particles {
+                base_color: color.0,
+            },
+            SceneDescriptor::Snow { color } => SceneType::Snow {
+                base_color: color.0,
+            },
+            SceneDescriptor::Bubbles { color } => SceneType::Bubbles {
+                base_color: color.0,
+            },
+            SceneDescriptor::Starfield { color } => SceneType::Starfield {
+                base_color: color.0,
+            },
+        })
+    };
+
+    let layout = &descriptor.layout;
+    let mut font = FontThemeOptions::simple(
+        FontRenderOptions::numeric_sprites(
+            read_file(dir, &descriptor.files.font)?,
+            texture_creator,
+            1,
+        )?,
+        MetricSnips::zero_fill(point(layout.score), MAX_SCORE),
+        MetricSnips::zero_fill(point(layout.virus_level), MAX_VIRUS_LEVEL),
+        MetricSnips::zero_fill(point(layout.virus_count), MAX_VIRUSES),
+    );
+    if let Some(speed_level) = layout.speed_level {
+        font = font.with_speed_level(ThemedNumeric::new(
+            0,
+            MetricSnips::zero_fill(point(speed_level), MAX_SPEED_LEVEL),
+        ));
+    }
+    let menu_defaults = MenuStyle {
+        highlight_color: Color::RGB(0xd8, 0x28, 0x00),
+        selected_color: Color::WHITE,
+        chime: MenuChime::Retro,
+        ..MenuStyle::default()
+    };
+    let options = RetroThemeOptions {
+        name: ThemeName::External(index),
+        scene_low: scene(&descriptor.scenes.low)?,
+        scene_medium: scene(&descriptor.scenes.medium)?,
+        scene_high: scene(&descriptor.scenes.high)?,
+        virus_animation_type: VirusAnimationType::LINEAR_STANDARD,
+        dr_idle_animation_type: DrAnimationType::Static,
+        dr_throw_animation_type: DrAnimationType::RETRO_THROW,
+        dr_victory_animation_type: DrAnimationType::NES_SNES_VICTORY,
+        dr_game_over_animation_type: DrAnimationType::Static,
+        sprites,
+        geometry: BottleGeometry::new(
+            descriptor.block_size,
+            layout.block_overlap,
+            point(layout.blocks),
+        ),
+        audio,
+        font,
+        bottles_file: read_file(dir, &descriptor.files.bottles)?,
+        bottles: SpeedVariants::new(
+            point(descriptor.bottles.low),
+            point(descriptor.bottles.medium),
+            point(descriptor.bottles.high),
+        ),
+        bottle_width: layout.bottle_size[0],
+        bottle_height: layout.bottle_size[1],
+        background_file: read_file(dir, &descriptor.files.background)?,
+        bottle_point: point(layout.bottle),
+        match_end_file: read_file(dir, &descriptor.files.match_end)?,
+        game_over_points: descriptor.match_end.game_over.iter().copied().map(point).collect(),
+        next_level_points: descriptor.match_end.next_level.iter().copied().map(point).collect(),
+        dr_throw_point: point(layout.dr_throw),
+        dr_throw_end_offset: point(layout.dr_throw_end_offset),
+        dr_game_over_point: point(layout.dr_game_over),
+        dr_victory_point: point(layout.dr_victory),
+        dr_order_first: layout.dr_order_first,
+        dr_hand_point: point(layout.dr_hand),
+        hold_point: point(layout.hold),
+        peek_point: point(layout.peek),
+        peek_offset: layout.peek_offset,
+        peek_max: layout.peek_max,
+        peek_scale: layout.peek_scale,
+        garbage_point: point(layout.garbage),
+        virus_counters: None,
+        virus_lounge: None,
+        menu_style: MenuStyle {
+            highlight_color: descriptor
+                .menu
+                .highlight_color
+                .map_or(menu_defaults.highlight_color, |c| c.0),
+            selected_color: descriptor
+                .menu
+                .selected_color
+                .map_or(menu_defaults.selected_color, |c| c.0),
+            text_color: descriptor
+                .menu
+                .text_color
+                .map_or(menu_defaults.text_color, |c| c.0),
+            ..menu_defaults
+        },
+        color_blind_patterns: config.accessibility.color_blind_patterns,
+    };
+
+    retro_theme(canvas, texture_creator, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The layout of the NES theme
+    const NES_DESCRIPTOR: &str = r##"
+name = "nes"
+block_size = 7
+
+[vitamins]
+file = "vitamins.png"
+pill_size = [17, 9]
+virus_idle_frames = 2
+virus_pop_frames = 1
+vitamin_pop_frames = 1
+
+[vitamins.pills]
+yy = [57, 0]
+yb = [74, 0]
+yr = [91, 0]
+bb = [57, 9]
+by = [74, 9]
+br = [91, 9]
+rr = [57, 18]
+ry = [74, 18]
+rb = [91, 18]
+
+[vitamins.yellow]
+north = [[0, 0], [7, 0]]
+east = [[14, 0], [21, 0]]
+south = [[7, 0], [0, 0]]
+west = [[21, 0], [14, 0]]
+garbage = [28, 0]
+virus_idle = [42, 0]
+virus_pop = [35, 0]
+vitamin_pop = [35, 0]
+
+[vitamins.red]
+north = [[0, 14], [7, 14]]
+east = [[14, 14], [21, 14]]
+south = [[7, 14], [0, 14]]
+west = [[21, 14], [14, 14]]
+garbage = [28, 14]
+virus_idle = [42, 14]
+virus_pop = [35, 14]
+vitamin_pop = [35, 14]
+
+[vitamins.blue]
+north = [[0, 7], [7, 7]]
+east = [[14, 7], [21, 7]]
+south = [[7, 7], [0, 7]]
+west = [[21, 7], [14, 7]]
+garbage = [28, 7]
+virus_idle = [42, 7]
+virus_pop = [35, 7]
+vitamin_pop = [35, 7]
+
+[dr]
+throw = { file = "dr/throw.png", frames = 3 }
+game_over = { file = "dr/game-over.png", frames = 1 }
+victory = { file = "dr/victory.png", frames = 2 }
+idle = { file = "dr/idle.png", frames = 1 }
+
+[files]
+background = "background.png"
+bottles = "bottles.png"
+font = "font.png"
+match_end = "match-end.png"
+
+[bottles]
+low = [81, 0]
+medium = [0, 0]
+high = [162, 0]
+
+[match_end]
+game_over = [[65, 0], [65, 129]]
+next_level = [[0, 0], [0, 129]]
+
+[layout]
+bottle = [0, 0]
+bottle_size = [80, 176]
+blocks = [8, 40]
+block_overlap = 1
+dr_hand = [102, 30]
+dr_throw = [97, 37]
+dr_throw_end_offset = [-1, -1]
+dr_game_over = [97, 37]
+dr_victory = [102, 37]
+hold = [125, 30]
+peek = [94, 55]
+peek_offset = 10
+peek_max = 2
+peek_scale = 0.75
+garbage = [40, 32]
+score = [92, 113]
+virus_level = [123, 134]
+virus_count = [123, 155]
+speed_level = [88, 134]
+
+[scenes]
+low = { type = "checkerboard", size = [8, 8], colors = ["#000000", "#003f00"] }
+medium = { type = "checkerboard", size = [8, 8], colors = ["#000000", "#2d0585"] }
+high = { type = "tile", file = "tile.png" }
+
+[menu]
+highlight_color = "#d82800"
+
+[music]
+fever = { file = "fever-intro.ogg", repeat = "fever-repeat.ogg" }
+chill = { file = "chill.ogg" }
+"##;
+
+    #[test]
+    fn parses_descriptor() {
+        let descriptor = ThemeDescriptor::parse(NES_DESCRIPTOR).unwrap();
+        assert_eq!(descriptor.name, "nes");
+        assert_eq!(descriptor.ghost_alpha, 0x40);
+        assert_eq!(descriptor.vitamins.red.garbage, [28, 14]);
+        assert!(matches!(
+            descriptor.scenes.medium,
+            SceneDescriptor::Checkerboard { colors: [_, HexColor(color)], .. }
+                if color == Color::RGB(0x2d, 0x05, 0x85)
+        ));
+        assert!(matches!(descriptor.scenes.high, SceneDescriptor::Tile { .. }));
+
+        let starfield = NES_DESCRIPTOR.replace(
+            r#"high = { type = "tile", file = "tile.png" }"#,
+            r##"high = { type = "starfield", color = "#fcfcfc" }"##,
+        );
+        let descriptor = ThemeDescriptor::parse(&starfield).unwrap();
+        assert!(matches!(
+            descriptor.scenes.high,
+            SceneDescriptor::Starfield { color: HexColor(color) }
+                if color == Color::RGB(0xfc, 0xfc, 0xfc)
+        ));
+        assert!(descriptor.music.chill.unwrap().repeat.is_none());
+        assert!(descriptor.sounds.move_pill.is_none());
+        assert_eq!(descriptor.layout.speed_level, Some([88, 134]));
+    }
+
+    #[test]
+    fn rejects_invalid_descriptor() {
+        let zero_frames = NES_DESCRIPTOR.replace("virus_pop_frames = 1", "virus_pop_frames = 0");
+        assert_eq!(
+            ThemeDescriptor::parse(&zero_frames).unwrap_err(),
+            "vitamins.virus_pop_frames must be greater than zero"
+        );
+
+        let bad_color = NES_DESCRIPTOR.replace("#d82800", "red");
+        assert!(ThemeDescriptor::parse(&bad_color)
+            .unwrap_err()
+            .contains("invalid color red"));
+
+        let missing = NES_DESCRIPTOR.replace("block_size = 7", "");
+        assert!(ThemeDescriptor::parse(&missing)
+            .unwrap_err()
+            .contains("block_size"));
+    }
+}
@@ -7,6 +7,7 @@ use sdl2::rect::{Point, Rect};
 use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
 use sdl2::ttf::Sdl2TtfContext;
 use sdl2::video::WindowContext;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use sdl2::pixels::PixelFormatEnum::RGBA8888;
 
@@ -148,7 +149,8 @@ pub fn alpha_sprites(snips: [Point; 10], width: u32, height: u32) -> Vec<FontSpr
 }
 
 pub struct FontRender<'a> {
-    texture: Texture<'a>,
+    /// alpha is only modulated while drawing faded text, the font is drawn from a shared reference
+    texture: RefCell<Texture<'a>>,
     sprites: HashMap<char, Rect>,
     spacing: u32,
 }
@@ -164,7 +166,7 @@ impl<'a> FontRender<'a> {
         texture.set_blend_mode(BlendMode::Blend);
         let sprites = sprites.iter().map(|&x| (x.value, x.snip)).collect();
         Ok(Self {
-            texture,
+            texture: RefCell::new(texture),
             sprites,
             spacing,
         })
@@ -183,7 +185,7 @@ impl<'a> FontRender<'a> {
         let chars = ('A'..='Z')
             .chain('a'..='z')
             .chain('0'..='9')
-            .chain([' ', ',', '.'])
+            .chain([' ', ',', '.', '+'])
             .map(|c| {
                 (
                     c,
@@ -216,7 +218,7 @@ impl<'a> FontRender<'a> {
             .map_err(|e| e.to_string())?;
 
         Ok(Self {
-            texture,
+            texture: RefCell::new(texture),
             sprites,
             spacing: 0,
         })
@@ -227,12 +229,42 @@ impl<'a> FontRender<'a> {
         canvas: &mut WindowCanvas,
         dest: Point,
         value: &str,
+    ) -> Result<(), String> {
+        self.copy_string(canvas, &self.texture.borrow(), dest, value)
+    }
+
+    /// Renders the string centered on a point, faded by an alpha where 0 renders nothing
+    pub fn render_string_faded(
+        &self,
+        canvas: &mut WindowCanvas,
+        center: Point,
+        value: &str,
+        alpha: u8,
+    ) -> Result<(), String> {
+        if alpha == 0 {
+            return Ok(());
+        }
+        let (width, height) = self.string_size(value);
+        let dest = center.offset(-(width as i32) / 2, -(height as i32) / 2);
+        let mut texture = self.texture.borrow_mut();
+        texture.set_alpha_mod(alpha);
+        let result = self.copy_string(canvas, &texture, dest, value);
+        texture.set_alpha_mod(0xff);
+        result
+    }
+
+    fn copy_string(
+        &self,
+        canvas: &mut WindowCanvas,
+        texture: &Texture,
+        dest: Point,
+        value: &str,
     ) -> Result<(), String> {
         let mut dest = dest;
         for ch in value.chars() {
             let snip = self.sprite(ch);
             let rect = Rect::new(dest.x(), dest.y(), snip.width(), snip.height());
-            canvas.copy(&self.texture, snip, rect)?;
+            canvas.copy(texture, snip, rect)?;
             dest += Point::new((snip.width() + self.spacing) as i32, 0);
         }
         Ok(())
@@ -249,6 +281,40 @@ impl<'a> FontRender<'a> {
         self.render_string(canvas, rect.top_left(), value)
     }
 
+    /// Renders the string centered & scaled up by a whole number to fit the destination,
+    /// then zoomed by a factor e.g. for a pop animation
+    pub fn render_string_fill(
+        &self,
+        canvas: &mut WindowCanvas,
+        dest: Rect,
+        value: &str,
+        zoom: f64,
+    ) -> Result<(), String> {
+        let (width, height) = self.string_size(value);
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let scale = (dest.width() / width).min(dest.height() / height).max(1) as f64 * zoom;
+        let rect = Rect::from_center(
+            dest.center(),
+            (width as f64 * scale).round() as u32,
+            (height as f64 * scale).round() as u32,
+        );
+        let mut x = rect.x() as f64;
+        for ch in value.chars() {
+            let snip = self.sprite(ch);
+            let char_rect = Rect::new(
+                x.round() as i32,
+                rect.y(),
+                (snip.width() as f64 * scale).round() as u32,
+                (snip.height() as f64 * scale).round() as u32,
+            );
+            canvas.copy(&self.texture.borrow(), snip, char_rect)?;
+            x += (snip.width() + self.spacing) as f64 * scale;
+        }
+        Ok(())
+    }
+
     pub fn render_number(
         &self,
         canvas: &mut WindowCanvas,
@@ -284,7 +350,7 @@ impl<'a> FontRender<'a> {
             let snip = self.sprite(ch);
             dest -= Point::new(snip.width() as i32, 0);
             let rect = Rect::new(dest.x(), dest.y(), snip.width(), snip.height());
-            canvas.copy(&self.texture, snip, rect)?;
+            canvas.copy(&self.texture.borrow(), snip, rect)?;
             dest -= Point::new(self.spacing as i32, 0);
         }
         Ok(())
@@ -351,6 +417,7 @@ pub struct FontThemeOptions {
     score: ThemedNumeric,
     virus_level: ThemedNumeric,
     virus_count: ThemedNumeric,
+    speed_level: Option<ThemedNumeric>,
 }
 
 impl FontThemeOptions {
@@ -365,6 +432,7 @@ impl FontThemeOptions {
             score,
             virus_level,
             virus_count,
+            speed_level: None,
         }
     }
 
@@ -382,6 +450,12 @@ impl FontThemeOptions {
         )
     }
 
+    /// The speed level is only shown by themes with somewhere to put it
+    pub fn with_speed_level(mut self, speed_level: ThemedNumeric) -> Self {
+        self.speed_level = Some(speed_level);
+        self
+    }
+
     pub fn build<'a>(
         &self,
         texture_creator: &'a TextureCreator<WindowContext>,
@@ -395,6 +469,7 @@ impl FontThemeOptions {
             score: self.score,
             virus_level: self.virus_level,
             virus_count: self.virus_count,
+            speed_level: self.speed_level,
         })
     }
 }
@@ -404,6 +479,7 @@ pub struct FontTheme<'a> {
     score: ThemedNumeric,
     virus_level: ThemedNumeric,
     virus_count: ThemedNumeric,
+    speed_level: Option<ThemedNumeric>,
 }
 
 impl<'a> FontTheme<'a> {
@@ -418,9 +494,15 @@ impl<'a> FontTheme<'a> {
             score,
             virus_level,
             virus_count,
+            speed_level: None,
         }
     }
 
+    pub fn with_speed_level(mut self, speed_level: ThemedNumeric) -> Self {
+        self.speed_level = Some(speed_level);
+        self
+    }
+
     pub fn render_all(
         &self,
         canvas: &mut WindowCanvas,
@@ -440,6 +522,68 @@ impl<'a> FontTheme<'a> {
             canvas,
             self.virus_count.snips,
             metrics.virus_count(),
+        )?;
+        if let Some(speed_level) = self.speed_level {
+            self.fonts[speed_level.font_index].render_number(
+                canvas,
+                speed_level.snips,
+                metrics.speed_level(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remaining viruses of a single color, in the font of the virus count
+    pub fn render_virus_color_count(
+        &self,
+        canvas: &mut WindowCanvas,
+        point: Point,
+        count: u32,
+    ) -> Result<(), String> {
+        self.fonts[self.virus_count.font_index].render_number(
+            canvas,
+            MetricSnips::zero_fill(point, self.virus_count.snips.max_value),
+            count,
         )
     }
+
+    pub fn render_countdown(
+        &self,
+        canvas: &mut WindowCanvas,
+        dest: Rect,
+        seconds: u32,
+    ) -> Result<(), String> {
+        // integer scaling keeps pixel art fonts crisp
+        let dest = Rect::from_center(dest.center(), dest.width() / 2, dest.height() / 2);
+        let font = &self.fonts[self.score.font_index];
+        font.render_string_fill(canvas, dest, &seconds.to_string(), 1.0)
+    }
+
+    pub fn render_chain(
+        &self,
+        canvas: &mut WindowCanvas,
+        dest: Rect,
+        chain: u32,
+        zoom: f64,
+    ) -> Result<(), String> {
+        let font = &self.fonts[self.score.font_index];
+        font.render_string_fill(canvas, dest, &chain.to_string(), zoom)
+    }
+
+    /// The score of a combo e.g. "+400" centered on a point, the plus is left off by fonts of
+    /// only digits
+    pub fn render_score_popup(
+        &self,
+        canvas: &mut WindowCanvas,
+        center: Point,
+        score: u32,
+        alpha: u8,
+    ) -> Result<(), String> {
+        let font = &self.fonts[self.score.font_index];
+        let mut value = font.format_number(score, u32::MAX, None);
+        if font.sprites.contains_key(&'+') {
+            value.insert(0, '+');
+        }
+        font.render_string_faded(canvas, center, &value, alpha)
+    }
 }
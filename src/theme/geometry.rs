@@ -1,4 +1,4 @@
-use crate::game::bottle::{BOTTLE_HEIGHT, BOTTLE_WIDTH};
+use crate::game::bottle::{BottleSize, BOTTLE_HEIGHT, BOTTLE_WIDTH};
 use crate::game::geometry::BottlePoint;
 
 use sdl2::rect::{Point, Rect};
@@ -64,6 +64,28 @@ impl BottleGeometry {
     pub fn game_snip(&self) -> Rect {
         Rect::new(self.offset.x(), self.offset.y(), self.width, self.height)
     }
+
+    /// Geometry of a bottle of some other size, with the blocks shrunk to fit centered
+    /// in the game snip of this classic bottle so the theme's bottle art is kept as it is
+    pub fn fit(&self, size: BottleSize) -> Self {
+        if size == BottleSize::CLASSIC {
+            return *self;
+        }
+        let block_size = (self.width / size.width()).min(self.height / size.height());
+        let raw_block_size = (block_size * self.raw_block_size / self.block_size).max(1);
+        let width = block_size * size.width();
+        let height = block_size * size.height();
+        Self {
+            raw_block_size,
+            block_size,
+            height,
+            width,
+            offset: Point::new(
+                self.offset.x() + (self.width - width) as i32 / 2,
+                self.offset.y() + (self.height - height) as i32 / 2,
+            ),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +100,13 @@ mod tests {
         assert_eq!(geometry.raw_block((0, 15)), Rect::new(96, 192, 7, 7));
         assert_eq!(geometry.raw_block((4, 12)), Rect::new(128, 168, 7, 7));
     }
+
+    #[test]
+    fn fits_wide_bottle() {
+        let geometry = BottleGeometry::new(7, 1, (96, 72));
+        let wide = geometry.fit(BottleSize::new(12, 16));
+        assert_eq!(wide.block_size(), 5);
+        assert_eq!(wide.game_snip(), Rect::new(98, 96, 60, 80));
+        assert_eq!(wide.raw_block((0, 0)), Rect::new(98, 96, 4, 4));
+    }
 }
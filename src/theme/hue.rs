@@ -0,0 +1,94 @@
+//! Hue rotation of sprites, used to tint a copy of one color for a theme without art for another
+
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Rect;
+use sdl2::render::{Texture, WindowCanvas};
+
+/// Turns the blue vitamins & viruses green
+pub const BLUE_TO_GREEN: f64 = -100.0;
+
+/// Rotates the hue of a color by some degrees, keeping its saturation & brightness
+pub fn rotate_hue((r, g, b): (u8, u8, u8), degrees: f64) -> (u8, u8, u8) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    if chroma == 0.0 {
+        // greys have no hue to rotate
+        let v = (max * 255.0).round() as u8;
+        return (v, v, v);
+    }
+
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let hue = (hue + degrees).rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (hue.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |c: f64| ((c + min) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// Rotates the hue of every pixel within a rect of a target texture, in place
+pub fn rotate_texture_hue(
+    canvas: &mut WindowCanvas,
+    texture: &mut Texture,
+    rect: Rect,
+    degrees: f64,
+) -> Result<(), String> {
+    // target textures are created as RGBA8888 so the pixels are written back as read
+    let mut pixels = vec![];
+    canvas
+        .with_texture_canvas(texture, |c| {
+            pixels = c.read_pixels(rect, PixelFormatEnum::RGBA8888).unwrap()
+        })
+        .map_err(|e| e.to_string())?;
+
+    for chunk in pixels.chunks_mut(4) {
+        let rgba = u32::from_ne_bytes(chunk.try_into().unwrap());
+        let (r, g, b) = rotate_hue(
+            ((rgba >> 24) as u8, (rgba >> 16) as u8, (rgba >> 8) as u8),
+            degrees,
+        );
+        let rotated = (r as u32) << 24 | (g as u32) << 16 | (b as u32) << 8 | rgba & 0xff;
+        chunk.copy_from_slice(&rotated.to_ne_bytes());
+    }
+
+    texture
+        .update(rect, &pixels, rect.width() as usize * 4)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_primaries() {
+        assert_eq!(rotate_hue((255, 0, 0), 120.0), (0, 255, 0));
+        assert_eq!(rotate_hue((255, 0, 0), -120.0), (0, 0, 255));
+        assert_eq!(rotate_hue((0, 0, 255), 360.0), (0, 0, 255));
+    }
+
+    #[test]
+    fn keeps_greys() {
+        assert_eq!(rotate_hue((128, 128, 128), 90.0), (128, 128, 128));
+    }
+
+    #[test]
+    fn blue_to_green() {
+        let (r, g, b) = rotate_hue((56, 120, 248), BLUE_TO_GREEN);
+        assert!(g > r && g > b, "{:?}", (r, g, b));
+    }
+}
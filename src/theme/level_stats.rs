@@ -0,0 +1,152 @@
+use crate::font::{FontTexture, FontType};
+use crate::game::metrics::GameStats;
+use crate::game::NextLevelPreview;
+use num_format::{Locale, ToFormattedString};
+use sdl2::pixels::Color;
+use sdl2::pixels::PixelFormatEnum::RGBA8888;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
+use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::WindowContext;
+use std::time::Duration;
+
+const LABEL_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+
+/// Breakdown of a completed level & what's next, shown over the player's bottle until the next
+/// level starts. Space is left under the rows for the theme to draw the next bottle
+pub struct LevelStatsScreen<'a> {
+    texture: Texture<'a>,
+    dest: Rect,
+    preview_dest: Rect,
+}
+
+impl<'a> LevelStatsScreen<'a> {
+    pub fn new(
+        canvas: &mut WindowCanvas,
+        ttf: &Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        dest: Rect,
+        virus_level: u32,
+        stats: GameStats,
+        next: &NextLevelPreview,
+    ) -> Result<Self, String> {
+        let speed: &str = next.speed.into();
+        let rows = [
+            ("pills", stats.pills().to_formatted_string(&Locale::en)),
+            ("viruses", stats.viruses_cleared().to_formatted_string(&Locale::en)),
+            ("max combo", stats.max_combo().to_formatted_string(&Locale::en)),
+            ("time", format_time(stats.time())),
+            ("speed bonus", stats.speed_bonus().to_formatted_string(&Locale::en)),
+            ("score", stats.score().to_formatted_string(&Locale::en)),
+            ("next level", next.virus_level.to_string()),
+            ("next viruses", next.viruses.len().to_string()),
+            ("next speed", speed.to_string()),
+        ];
+
+        let padding = dest.width() / 16;
+        let title_font = FontType::Retro.load(ttf, dest.width() / 7)?;
+        let font = FontType::Bold.load(ttf, dest.width() / 12)?;
+        let title = FontTexture::from_string(
+            &title_font,
+            texture_creator,
+            &format!("level {} clear", virus_level),
+            Color::WHITE,
+        )?;
+        let rows = rows
+            .iter()
+            .map(|(label, value)| {
+                Ok((
+                    FontTexture::from_string(&font, texture_creator, label, LABEL_COLOR)?,
+                    FontTexture::from_string(&font, texture_creator, value, Color::WHITE)?,
+                ))
+            })
+            .collect::<Result<Vec<(FontTexture, FontTexture)>, String>>()?;
+
+        let mut texture = texture_creator
+            .create_texture_target(RGBA8888, dest.width(), dest.height())
+            .map_err(|e| e.to_string())?;
+        texture.set_blend_mode(BlendMode::Blend);
+
+        let row_height = rows.iter().map(|(l, _)| l.height).max().unwrap_or(0);
+        let size = next.bottle_size;
+        let preview_block_size = ((dest.width() / 3) / size.width())
+            .min((dest.height() / 4) / size.height())
+            .max(1);
+        let preview_width = preview_block_size * size.width();
+        let preview_height = preview_block_size * size.height();
+        let height = title.height + 2 * padding + rows.len() as u32 * row_height + preview_height;
+        let top = (dest.height() as i32 - height as i32).max(0) / 2;
+        let preview_dest = Rect::new(
+            dest.x() + (dest.width() - preview_width) as i32 / 2,
+            dest.y() + top + (height - preview_height) as i32,
+            preview_width,
+            preview_height,
+        );
+        canvas
+            .with_texture_canvas(&mut texture, |c| {
+                c.set_draw_color(Color::RGBA(0, 0, 0, 0xdd));
+                c.clear();
+
+                let mut y = top;
+                let title_x = (dest.width() as i32 - title.width as i32) / 2;
+                c.copy(
+                    &title.texture,
+                    None,
+                    Rect::new(title_x, y, title.width, title.height),
+                )
+                .unwrap();
+                y += (title.height + padding) as i32;
+
+                // labels on the left, values right aligned
+                for (label, value) in rows.iter() {
+                    c.copy(
+                        &label.texture,
+                        None,
+                        Rect::new(padding as i32, y, label.width, label.height),
+                    )
+                    .unwrap();
+                    let value_x = (dest.width() - padding) as i32 - value.width as i32;
+                    c.copy(
+                        &value.texture,
+                        None,
+                        Rect::new(value_x, y, value.width, value.height),
+                    )
+                    .unwrap();
+                    y += row_height as i32;
+                }
+            })
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            texture,
+            dest,
+            preview_dest,
+        })
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        canvas.copy(&self.texture, None, self.dest)
+    }
+
+    /// Where the next bottle is previewed, under the rows
+    pub fn preview_dest(&self) -> Rect {
+        self.preview_dest
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_time() {
+        assert_eq!(format_time(Duration::from_millis(59_999)), "0:59");
+        assert_eq!(format_time(Duration::from_secs(61)), "1:01");
+        assert_eq!(format_time(Duration::from_secs(600)), "10:00");
+    }
+}
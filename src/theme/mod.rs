@@ -1,34 +1,59 @@
 use crate::animate::dr::DrAnimationType;
+use crate::animate::crawl::VirusCrawlType;
 use crate::animate::virus::VirusAnimationType;
+use crate::cheat::Cheats;
 use crate::animate::PlayerAnimations;
+use crate::animate::next_level_interstitial::State as NextLevelInterstitial;
 
+use crate::game::metrics::GameMetrics;
 use crate::game::pill::VirusColor;
 use crate::game::{Game, GameSpeed};
+use crate::menu::MenuStyle;
 use crate::particles::particle::ParticleAnimationType;
+use crate::theme::bottle_view::BottleBlocks;
 use crate::theme::font::FontTheme;
 use crate::theme::geometry::BottleGeometry;
+use crate::theme::helper::TextureQuery;
 use crate::theme::scene::SceneRender;
 use crate::theme::sound::AudioTheme;
 use crate::theme::sprite_sheet::{DrType, VitaminSpriteSheet};
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
+use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::render::{Texture, WindowCanvas};
+use std::f64::consts::FRAC_1_SQRT_2;
+use strum::IntoEnumIterator;
 
 pub mod all;
 pub mod animation;
+pub mod arcade;
 pub mod block_mask;
+pub mod bottle_view;
+pub mod external;
 pub mod font;
+pub mod gameboy;
 pub mod geometry;
 pub mod helper;
+pub mod hue;
+pub mod level_stats;
 pub mod n64;
 pub mod nes;
 pub mod particle;
+pub mod pattern;
 pub mod pause;
+pub mod power_up;
 mod retro;
 pub mod scene;
 pub mod snes;
 pub mod sound;
 pub mod sprite_sheet;
+pub mod transition;
+
+/// Outline of the pips of a vs. series that are still to be won
+const SERIES_PIP_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
+/// Glass & rim of the mini bottle previewing the next level
+const PREVIEW_BOTTLE_COLOR: Color = Color::RGBA(0x20, 0x20, 0x30, 0xcc);
+const PREVIEW_RIM_COLOR: Color = Color::RGB(0xaa, 0xaa, 0xaa);
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
 pub enum ThemeName {
@@ -36,19 +61,27 @@ pub enum ThemeName {
     Nes,
     Snes,
     N64,
+    GameBoy,
+    Arcade,
     Particle,
+    /// Loaded from disk, by index in the order they were loaded
+    External(usize),
 }
 
 #[derive(Copy, Clone, Debug)]
 pub struct AnimationMeta {
     pub virus_type: VirusAnimationType,
+    pub virus_crawl_type: VirusCrawlType,
     pub red_virus_frames: usize,
     pub blue_virus_frames: usize,
     pub yellow_virus_frames: usize,
+    pub green_virus_frames: usize,
     pub vitamin_pop_frames: usize,
     pub virus_pop_frames: usize,
     pub throw_start: Point,
     pub throw_end: Point,
+    /// where held pills fly to & from
+    pub hold_point: Point,
     pub dr_throw_type: DrAnimationType,
     pub dr_throw_frames: usize,
     pub dr_victory_type: DrAnimationType,
@@ -67,6 +100,7 @@ impl AnimationMeta {
             VirusColor::Yellow => self.yellow_virus_frames,
             VirusColor::Blue => self.blue_virus_frames,
             VirusColor::Red => self.red_virus_frames,
+            VirusColor::Green => self.green_virus_frames,
         };
         match self.virus_type {
             VirusAnimationType::Linear { fps } => ParticleAnimationType::Linear { frames, fps },
@@ -90,6 +124,81 @@ impl AnimationMeta {
     }
 }
 
+/// Themed values selected by game speed e.g. bottle art
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SpeedVariants<T> {
+    low: T,
+    medium: T,
+    high: T,
+}
+
+impl<T: Copy> SpeedVariants<T> {
+    pub fn new(low: T, medium: T, high: T) -> Self {
+        Self { low, medium, high }
+    }
+
+    /// Same value at every speed, the default for themes without speed variants
+    pub fn uniform(value: T) -> Self {
+        Self::new(value, value, value)
+    }
+
+    pub fn get(&self, speed: GameSpeed) -> T {
+        match speed {
+            GameSpeed::Low => self.low,
+            GameSpeed::Medium => self.medium,
+            GameSpeed::High => self.high,
+        }
+    }
+
+    pub fn map<U: Copy, F: Fn(T) -> U>(&self, f: F) -> SpeedVariants<U> {
+        SpeedVariants::new(f(self.low), f(self.medium), f(self.high))
+    }
+}
+
+/// Remaining viruses of each color, counted beside a virus dancing with those in the bottle.
+/// Green is only counted while there are green viruses
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VirusCounters {
+    /// top left of the first counter, relative to the background
+    pub point: Point,
+    /// counters in each row, in the order yellow, blue, red & green
+    pub columns: usize,
+    /// from the top left of one counter to the next in its row & to the next row
+    pub spacing: (i32, i32),
+}
+
+impl VirusCounters {
+    /// Top left of the virus of the nth counter, the count is drawn to its right
+    fn point(&self, index: usize) -> Point {
+        let (column, row) = (index % self.columns, index / self.columns);
+        self.point.offset(column as i32 * self.spacing.0, row as i32 * self.spacing.1)
+    }
+}
+
+/// Magnifying glass that a virus of each color lounges in, see [crate::animate::lounge]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VirusLounge {
+    /// center of the glass, relative to the background
+    pub center: Point,
+    pub radius: i16,
+    pub lens_color: Color,
+    pub rim_color: Color,
+}
+
+/// How the background art is laid out for the players of a match
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum BackgroundLayout {
+    /// Every player draws their own copy of the background
+    #[default]
+    PerPlayer,
+    /// One background for all players, e.g. arcade cabinet art with two bottles side by side.
+    /// Players are drawn over their panel on it, given by the top left in player order
+    Shared {
+        panel_size: (u32, u32),
+        panels: Vec<Point>,
+    },
+}
+
 pub struct Theme<'a> {
     name: ThemeName,
     scene_low: SceneRender<'a>,
@@ -100,12 +209,11 @@ pub struct Theme<'a> {
     audio: AudioTheme,
     font: FontTheme<'a>,
     bottles_texture: Texture<'a>,
-    bottle_low_snip: Rect,
-    bottle_medium_snip: Rect,
-    bottle_high_snip: Rect,
+    bottle_snips: SpeedVariants<Rect>,
     background_texture: Texture<'a>,
     bottle_bg_snip: Rect,
     background_size: (u32, u32),
+    background_layout: BackgroundLayout,
     dr_order_first: bool,
     dr_hand_point: Point,
     dr_throw_point: Point,
@@ -120,6 +228,10 @@ pub struct Theme<'a> {
     peek_max: u32,
     peek_offset: i32,
     peek_scale: Option<f64>,
+    garbage_point: Point,
+    virus_counters: Option<VirusCounters>,
+    virus_lounge: Option<VirusLounge>,
+    menu_style: MenuStyle,
 }
 
 impl<'a> Theme<'a> {
@@ -127,6 +239,10 @@ impl<'a> Theme<'a> {
         self.name
     }
 
+    pub fn menu_style(&self) -> MenuStyle {
+        self.menu_style
+    }
+
     pub fn sprites(&self) -> &VitaminSpriteSheet<'a> {
         &self.sprites
     }
@@ -155,6 +271,46 @@ impl<'a> Theme<'a> {
         self.bottle_bg_snip
     }
 
+    /// Lays the players out over panels of a single background, the background size becomes the
+    /// panel size & all points of the theme are relative to the panel
+    pub fn with_shared_background(mut self, panel_size: (u32, u32), panels: Vec<Point>) -> Self {
+        self.background_size = panel_size;
+        self.background_layout = BackgroundLayout::Shared { panel_size, panels };
+        self
+    }
+
+    /// Size of the shared background when it has a panel for every player of the match,
+    /// otherwise the players are laid out individually
+    pub fn shared_background_size(&self, players: u32) -> Option<(u32, u32)> {
+        match &self.background_layout {
+            BackgroundLayout::Shared { panels, .. } if panels.len() == players as usize => {
+                Some(self.background_texture.size())
+            }
+            _ => None,
+        }
+    }
+
+    /// Snip of the player's panel on the shared background, players without a panel reuse them
+    pub fn background_panel(&self, player: u32) -> Option<Rect> {
+        match &self.background_layout {
+            BackgroundLayout::PerPlayer => None,
+            BackgroundLayout::Shared { panel_size, panels } => {
+                let point = panels[player as usize % panels.len()];
+                Some(Rect::new(point.x(), point.y(), panel_size.0, panel_size.1))
+            }
+        }
+    }
+
+    /// Draws the shared background behind the players, the player backgrounds only hold sprites
+    pub fn draw_shared_background(
+        &self,
+        canvas: &mut WindowCanvas,
+        src: Rect,
+        dst: Rect,
+    ) -> Result<(), String> {
+        canvas.copy(&self.background_texture, src, dst)
+    }
+
     pub fn audio(&self) -> &AudioTheme {
         &self.audio
     }
@@ -164,15 +320,19 @@ impl<'a> Theme<'a> {
         canvas: &mut WindowCanvas,
         game: &Game,
         animations: &PlayerAnimations,
+        cheats: Cheats,
     ) -> Result<(), String> {
         canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
         canvas.clear();
         let (width, height) = self.background_size;
-        canvas.copy(
-            &self.background_texture,
-            None,
-            Rect::new(0, 0, width, height),
-        )?;
+        let dr_scale = cheats.dr_scale();
+        if self.background_layout == BackgroundLayout::PerPlayer {
+            canvas.copy(
+                &self.background_texture,
+                None,
+                Rect::new(0, 0, width, height),
+            )?;
+        }
 
         let metrics = game.metrics();
         if let Some(game_over) = animations.game_over().state() {
@@ -181,6 +341,7 @@ impl<'a> Theme<'a> {
                 DrType::GameOver,
                 self.dr_game_over_point,
                 game_over.dr_frame(),
+                dr_scale,
             )?;
         } else if let Some(victory) = animations.victory().state() {
             self.sprites.draw_dr(
@@ -188,6 +349,7 @@ impl<'a> Theme<'a> {
                 DrType::Victory,
                 self.dr_victory_point,
                 victory.dr_frame(),
+                dr_scale,
             )?;
         } else if let Some(next_level_interstitial) = animations.next_level_interstitial().state() {
             self.sprites.draw_dr(
@@ -195,6 +357,7 @@ impl<'a> Theme<'a> {
                 DrType::Victory,
                 self.dr_victory_point,
                 next_level_interstitial.dr_frame(),
+                dr_scale,
             )?;
         } else {
             let peek = metrics.queue();
@@ -206,6 +369,7 @@ impl<'a> Theme<'a> {
                         DrType::Throw,
                         self.dr_throw_point,
                         spawn.dr_throw_frame(),
+                        dr_scale,
                     )?;
                     self.sprites.draw_pill(
                         canvas,
@@ -227,6 +391,7 @@ impl<'a> Theme<'a> {
                         DrType::Throw,
                         self.dr_throw_point,
                         spawn.dr_throw_frame(),
+                        dr_scale,
                     )?;
                 }
 
@@ -240,59 +405,291 @@ impl<'a> Theme<'a> {
                     DrType::Idle,
                     self.dr_throw_point,
                     animations.idle().frame(),
+                    dr_scale,
                 )?;
-                self.sprites
-                    .draw_pill(canvas, peek[0], self.dr_hand_point, None, None)?;
+                if let Some(&next) = peek.first() {
+                    self.sprites
+                        .draw_pill(canvas, next, self.dr_hand_point, None, None)?;
+                }
             } else {
-                self.sprites
-                    .draw_pill(canvas, peek[0], self.dr_hand_point, None, None)?;
+                if let Some(&next) = peek.first() {
+                    self.sprites
+                        .draw_pill(canvas, next, self.dr_hand_point, None, None)?;
+                }
                 self.sprites.draw_dr(
                     canvas,
                     DrType::Idle,
                     self.dr_throw_point,
                     animations.idle().frame(),
+                    dr_scale,
                 )?;
             }
-            if let Some(hold) = metrics.hold() {
+            let hold = animations.hold();
+            if let Some(state) = hold.state() {
+                // shrinking into the hold box as the pill held before grows back out of it
+                let peek_scale = self.peek_scale.unwrap_or(1.0);
+                let t = state.progress();
+                self.sprites.draw_pill(
+                    canvas,
+                    state.shape(),
+                    hold.held_position(state),
+                    None,
+                    1.0 + (peek_scale - 1.0) * t,
+                )?;
+                if let Some(released) = state.released() {
+                    self.sprites.draw_pill(
+                        canvas,
+                        released,
+                        hold.released_position(state),
+                        None,
+                        peek_scale + (1.0 - peek_scale) * t,
+                    )?;
+                }
+            } else if let Some(hold) = metrics.hold() {
                 self.sprites
                     .draw_pill(canvas, hold, self.hold_point, None, self.peek_scale)?;
             }
-            for i in 0..self.peek_max.min(peek.len() as u32 - 1) {
+            // the pill in the dr's hand is followed by as many as the theme has room for
+            let column = peek.iter().skip(1).take(self.peek_max as usize);
+            for (i, &shape) in column.enumerate() {
                 let point = self
                     .peek_point
                     .offset(0, peek_offset + i as i32 * self.peek_offset);
-                self.sprites.draw_pill(
-                    canvas,
-                    peek[i as usize + 1],
-                    point,
-                    None,
-                    self.peek_scale,
-                )?;
+                self.sprites
+                    .draw_pill(canvas, shape, point, None, self.peek_scale)?;
             }
         }
 
+        if let Some(virus_counters) = self.virus_counters {
+            self.draw_virus_counters(canvas, virus_counters, metrics, animations)?;
+        }
+        if let Some(virus_lounge) = self.virus_lounge {
+            self.draw_virus_lounge(canvas, virus_lounge, metrics, animations)?;
+        }
+
         self.font.render_all(canvas, metrics)
     }
 
-    pub fn draw_bottle(
+    fn draw_virus_counters(
         &self,
         canvas: &mut WindowCanvas,
-        game: &Game,
+        virus_counters: VirusCounters,
+        metrics: GameMetrics,
         animations: &PlayerAnimations,
+    ) -> Result<(), String> {
+        let block_size = self.geometry.block_size();
+        let colors = VirusColor::iter()
+            .filter(|&c| c != VirusColor::Green || metrics.virus_count_of(c) > 0);
+        for (i, color) in colors.enumerate() {
+            let point = virus_counters.point(i);
+            let dest = Rect::new(point.x(), point.y(), block_size, block_size);
+            let frame = animations.virus().frame(color);
+            self.sprites.draw_virus(canvas, color, dest, frame)?;
+            self.font.render_virus_color_count(
+                canvas,
+                point.offset(block_size as i32 + 1, 0),
+                metrics.virus_count_of(color),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_virus_lounge(
+        &self,
+        canvas: &mut WindowCanvas,
+        virus_lounge: VirusLounge,
+        metrics: GameMetrics,
+        animations: &PlayerAnimations,
+    ) -> Result<(), String> {
+        let (x, y) = (virus_lounge.center.x() as i16, virus_lounge.center.y() as i16);
+        let radius = virus_lounge.radius;
+        // the handle sticks out of the bottom right of the rim
+        let handle = (radius as f64 * FRAC_1_SQRT_2).round() as i16;
+        canvas.thick_line(
+            x + handle,
+            y + handle,
+            x + 2 * handle,
+            y + 2 * handle,
+            3,
+            virus_lounge.rim_color,
+        )?;
+        canvas.filled_circle(x, y, radius, virus_lounge.lens_color)?;
+        canvas.circle(x, y, radius, virus_lounge.rim_color)?;
+
+        let block_size = self.geometry.block_size();
+        for virus in animations.lounge().viruses(metrics.virus_counts()) {
+            let (dx, dy) = virus.offset;
+            let shake = virus.shake * block_size as f64;
+            let center = (
+                x as f64 + dx * radius as f64 + shake,
+                y as f64 + dy * radius as f64,
+            );
+            let dest = Rect::from_center(
+                Point::new(center.0.round() as i32, center.1.round() as i32),
+                block_size,
+                block_size,
+            );
+            match virus.pop_frame {
+                Some(frame) => self.sprites.draw_virus_pop(canvas, virus.color, dest, frame)?,
+                None => {
+                    let frame = animations.virus().frame(virus.color);
+                    self.sprites.draw_virus(canvas, virus.color, dest, frame)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws a mini bottle of the next level fit into dest, with the viruses seeded so far
+    pub fn draw_next_level_preview(
+        &self,
+        canvas: &mut WindowCanvas,
+        dest: Rect,
+        interstitial: &NextLevelInterstitial,
+        animations: &PlayerAnimations,
+    ) -> Result<(), String> {
+        let size = interstitial.preview().bottle_size;
+        let block_size = (dest.width() / size.width())
+            .min(dest.height() / size.height())
+            .max(1);
+        let mut bottle = Rect::new(0, 0, block_size * size.width(), block_size * size.height());
+        bottle.center_on(dest.center());
+        canvas.set_draw_color(PREVIEW_BOTTLE_COLOR);
+        canvas.fill_rect(bottle)?;
+        canvas.set_draw_color(PREVIEW_RIM_COLOR);
+        canvas.draw_rect(bottle)?;
+
+        for virus in interstitial.seeded_viruses() {
+            let block = Rect::new(
+                bottle.x() + virus.position.x() * block_size as i32,
+                bottle.y() + virus.position.y() * block_size as i32,
+                block_size,
+                block_size,
+            );
+            let frame = animations.virus().frame(virus.color);
+            self.sprites.draw_virus(canvas, virus.color, block, frame)?;
+        }
+        Ok(())
+    }
+
+    /// Draws the resume countdown over the bottle, call after draw_bottle
+    pub fn draw_countdown(&self, canvas: &mut WindowCanvas, seconds: u32) -> Result<(), String> {
+        self.font
+            .render_countdown(canvas, self.geometry.game_snip(), seconds)
+    }
+
+    /// Draws the chain counter near the top of the bottle once a chain is underway
+    pub fn draw_chain(
+        &self,
+        canvas: &mut WindowCanvas,
+        metrics: GameMetrics,
+        animations: &PlayerAnimations,
+    ) -> Result<(), String> {
+        let chain = metrics.current_chain();
+        if chain < 2 {
+            return Ok(());
+        }
+        let game_snip = self.geometry.game_snip();
+        let dest = Rect::new(
+            game_snip.x(),
+            game_snip.y() + game_snip.height() as i32 / 8,
+            game_snip.width(),
+            game_snip.height() / 8,
+        );
+        self.font
+            .render_chain(canvas, dest, chain, animations.chain().zoom())
+    }
+
+    /// Draws the score of recent combos rising from where they cleared, call after draw_bottle
+    pub fn draw_score_popups(
+        &self,
+        canvas: &mut WindowCanvas,
+        animations: &PlayerAnimations,
+    ) -> Result<(), String> {
+        let score_popup = animations.score_popup();
+        for popup in score_popup.popups() {
+            self.font.render_score_popup(
+                canvas,
+                score_popup.position(popup),
+                popup.score(),
+                popup.alpha(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Draws a garbage block above the bottle for each pending garbage, call after draw_bottle
+    pub fn draw_pending_garbage(
+        &self,
+        canvas: &mut WindowCanvas,
+        metrics: GameMetrics,
+    ) -> Result<(), String> {
+        let pending = metrics.pending_garbage().collect::<Vec<VirusColor>>();
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let icon_size = self.geometry.raw_block((0, 0)).width();
+        let block_size = self.geometry.block_size() as i32;
+        let x = self.garbage_point.x() - pending.len() as i32 * block_size / 2;
+        for (i, color) in pending.into_iter().enumerate() {
+            let dest = Rect::new(
+                x + i as i32 * block_size,
+                self.garbage_point.y(),
+                icon_size,
+                icon_size,
+            );
+            self.sprites.draw_garbage(canvas, color, dest)?;
+        }
+        Ok(())
+    }
+
+    /// Draws a pip on the background above the top left of the bottle for each win needed to take
+    /// a vs. series, the pips that are won are filled in with garbage. Nothing is drawn for a
+    /// single game.
+    pub fn draw_series_wins(
+        &self,
+        canvas: &mut WindowCanvas,
+        wins: u32,
+        needed: u32,
+    ) -> Result<(), String> {
+        if needed < 2 {
+            return Ok(());
+        }
+        let block_size = self.geometry.block_size();
+        let snip = self.bottle_snip();
+        // along the top of the background when the bottle has no room above it
+        let y = (snip.top() - block_size as i32).max(0);
+        canvas.set_draw_color(SERIES_PIP_COLOR);
+        for i in 0..needed {
+            let x = snip.left() + (i * block_size) as i32;
+            let dest = Rect::new(x, y, block_size, block_size);
+            if i < wins {
+                self.sprites.draw_garbage(canvas, VirusColor::Yellow, dest)?;
+            } else {
+                canvas.draw_rect(dest)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Blocks of the bottle, the vitamins of pills are tinted by the rainbow pills cheat
+    pub fn draw_bottle<B: BottleBlocks>(
+        &self,
+        canvas: &mut WindowCanvas,
+        blocks: &B,
+        animations: &PlayerAnimations,
+        vitamin_tint: Option<Color>,
     ) -> Result<(), String> {
         canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
         canvas.clear();
 
-        let bottle_snip = match game.speed() {
-            GameSpeed::Low => self.bottle_low_snip,
-            GameSpeed::Medium => self.bottle_medium_snip,
-            GameSpeed::High => self.bottle_high_snip,
-        };
+        let bottle_snip = self.bottle_snips.get(blocks.speed());
         let bottle_dest = Rect::new(0, 0, bottle_snip.width(), bottle_snip.height());
         canvas.copy(&self.bottles_texture, bottle_snip, bottle_dest)?;
 
+        let geometry = self.geometry.fit(blocks.size());
         self.sprites
-            .draw_bottle(canvas, game, &self.geometry, animations)?;
+            .draw_bottle(canvas, blocks, &geometry, animations, vitamin_tint)?;
         if let Some(game_over_frame) = animations
             .game_over()
             .state()
@@ -318,3 +715,20 @@ impl<'a> Theme<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn virus_counters_wrap_into_rows() {
+        let counters = VirusCounters {
+            point: Point::new(84, 2),
+            columns: 2,
+            spacing: (38, 11),
+        };
+        assert_eq!(counters.point(0), Point::new(84, 2));
+        assert_eq!(counters.point(1), Point::new(122, 2));
+        assert_eq!(counters.point(2), Point::new(84, 13));
+    }
+}
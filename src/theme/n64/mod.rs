@@ -2,17 +2,20 @@ use crate::animate::dr::DrAnimationType;
 use crate::animate::virus::VirusAnimationType;
 use crate::config::Config;
 use crate::game::random::MAX_VIRUSES;
-use crate::game::rules::MAX_VIRUS_LEVEL;
-use crate::game::MAX_SCORE;
+use crate::game::rules::{MusicTrack, MAX_VIRUS_LEVEL};
+use crate::game::{MAX_SCORE, MAX_SPEED_LEVEL};
+use crate::menu::{MenuChime, MenuStyle};
 use crate::theme::animation::AnimationSpriteSheetData;
 use crate::theme::font::{FontRenderOptions, FontThemeOptions, MetricSnips, ThemedNumeric};
+use crate::theme::gameboy::sound as gameboy_sound;
 use crate::theme::geometry::BottleGeometry;
 use crate::theme::retro::{retro_theme, RetroThemeOptions};
 use crate::theme::scene::SceneType;
 use crate::theme::sound::AudioTheme;
 use crate::theme::sprite_sheet::{pills, BlockAnimationsData, BlockPoints, VitaminSpriteSheetData};
-use crate::theme::{Theme, ThemeName};
+use crate::theme::{SpeedVariants, Theme, ThemeName};
 
+use sdl2::pixels::Color;
 use sdl2::rect::Point;
 use sdl2::render::{TextureCreator, WindowCanvas};
 use sdl2::video::WindowContext;
@@ -82,7 +85,7 @@ fn animations(j: i32) -> BlockAnimationsData {
 pub fn n64_theme<'a>(
     canvas: &mut WindowCanvas,
     texture_creator: &'a TextureCreator<WindowContext>,
-    config: Config,
+    config: &Config,
 ) -> Result<Theme<'a>, String> {
     let scene = SceneType::Tile {
         texture: sprites::BACKGROUND_TILE,
@@ -144,7 +147,9 @@ pub fn n64_theme<'a>(
             sound::NEXT_LEVEL_JINGLE,
             None,
         )?
-        .with_game_music(sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+        .with_game_music(MusicTrack::Fever, sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+        // no chill recording of its own, borrow the Game Boy one
+        .with_game_music(MusicTrack::Chill, gameboy_sound::CHILL, None)?
         .with_game_over_music(sound::GAME_OVER, None)?
         .with_next_level_music(sound::FEVER_NEXT_LEVEL, None)?
         .with_victory_music(sound::VICTORY_INTRO, sound::VICTORY_REPEAT)?,
@@ -156,11 +161,14 @@ pub fn n64_theme<'a>(
             ThemedNumeric::new(0, MetricSnips::zero_fill((111, 105), MAX_SCORE)),
             ThemedNumeric::new(1, MetricSnips::zero_fill((131, 143), MAX_VIRUS_LEVEL)),
             ThemedNumeric::new(1, MetricSnips::zero_fill((131, 183), MAX_VIRUSES)),
-        ),
+        )
+        // small, to the left of the virus level
+        .with_speed_level(ThemedNumeric::new(
+            0,
+            MetricSnips::zero_fill((111, 150), MAX_SPEED_LEVEL),
+        )),
         bottles_file: sprites::BOTTLES,
-        bottle_low: Point::new(0, 0),
-        bottle_medium: Point::new(0, 0),
-        bottle_high: Point::new(0, 0),
+        bottles: SpeedVariants::uniform(Point::new(0, 0)),
         bottle_width: 96,
         bottle_height: 209,
         background_file: sprites::BACKGROUND,
@@ -179,6 +187,17 @@ pub fn n64_theme<'a>(
         peek_offset: 10,
         peek_max: 2,
         peek_scale: Some(0.82),
+        garbage_point: Point::new(48, 31),
+        // dr's window is at the very top
+        virus_counters: None,
+        virus_lounge: None,
+        menu_style: MenuStyle {
+            highlight_color: Color::RGB(0x00, 0x98, 0x48),
+            selected_color: Color::WHITE,
+            chime: MenuChime::Modern,
+            ..MenuStyle::default()
+        },
+        color_blind_patterns: config.accessibility.color_blind_patterns,
     };
 
     retro_theme(canvas, texture_creator, options)
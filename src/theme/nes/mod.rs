@@ -2,16 +2,18 @@ use crate::animate::dr::DrAnimationType;
 use crate::animate::virus::VirusAnimationType;
 use crate::config::Config;
 use crate::game::random::MAX_VIRUSES;
-use crate::game::rules::MAX_VIRUS_LEVEL;
-use crate::game::MAX_SCORE;
+use crate::game::rules::{MusicTrack, MAX_VIRUS_LEVEL};
+use crate::game::{MAX_SCORE, MAX_SPEED_LEVEL};
+use crate::menu::{MenuChime, MenuStyle};
 use crate::theme::animation::AnimationSpriteSheetData;
-use crate::theme::font::{FontRenderOptions, FontThemeOptions, MetricSnips};
+use crate::theme::font::{FontRenderOptions, FontThemeOptions, MetricSnips, ThemedNumeric};
+use crate::theme::gameboy::sound as gameboy_sound;
 use crate::theme::geometry::BottleGeometry;
 use crate::theme::retro::{retro_theme, RetroThemeOptions};
 use crate::theme::scene::SceneType;
 use crate::theme::sound::AudioTheme;
 use crate::theme::sprite_sheet::{pills, BlockAnimationsData, BlockPoints, VitaminSpriteSheetData};
-use crate::theme::{Theme, ThemeName};
+use crate::theme::{SpeedVariants, Theme, ThemeName, VirusCounters, VirusLounge};
 use sdl2::pixels::Color;
 use sdl2::rect::Point;
 use sdl2::render::{TextureCreator, WindowCanvas};
@@ -28,7 +30,7 @@ mod sprites {
     pub const FONT: &[u8] = include_bytes!("font.png");
     pub const MATCH_END: &[u8] = include_bytes!("match-end.png");
 }
-mod sound {
+pub(super) mod sound {
     pub const FEVER_INTRO: &[u8] = include_bytes!("fever-intro.ogg");
     pub const FEVER_REPEAT: &[u8] = include_bytes!("fever-repeat.ogg");
     pub const FEVER_NEXT_LEVEL_INTRO: &[u8] = include_bytes!("fever-next-level-intro.ogg");
@@ -92,9 +94,18 @@ fn pill(i: i32, j: i32) -> Point {
 pub fn nes_theme<'a>(
     canvas: &mut WindowCanvas,
     texture_creator: &'a TextureCreator<WindowContext>,
-    config: Config,
+    config: &Config,
 ) -> Result<Theme<'a>, String> {
-    let options = RetroThemeOptions {
+    let options = nes_theme_options(texture_creator, config)?;
+    retro_theme(canvas, texture_creator, options)
+}
+
+/// The NES layout, shared with the arcade release which ran on the same hardware
+pub(super) fn nes_theme_options(
+    texture_creator: &TextureCreator<WindowContext>,
+    config: &Config,
+) -> Result<RetroThemeOptions, String> {
+    Ok(RetroThemeOptions {
         name: ThemeName::Nes,
         scene_low: SceneType::Checkerboard {
             width: 8,
@@ -162,7 +173,9 @@ pub fn nes_theme<'a>(
             sound::NEXT_LEVEL_JINGLE,
             None,
         )?
-        .with_game_music(sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+        .with_game_music(MusicTrack::Fever, sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+        // no chill recording of its own, borrow the Game Boy one
+        .with_game_music(MusicTrack::Chill, gameboy_sound::CHILL, None)?
         .with_game_over_music(sound::GAME_OVER_INTRO, sound::GAME_OVER_REPEAT)?
         .with_next_level_music(
             sound::FEVER_NEXT_LEVEL_INTRO,
@@ -174,11 +187,14 @@ pub fn nes_theme<'a>(
             MetricSnips::zero_fill((92, 113), MAX_SCORE),
             MetricSnips::zero_fill((123, 134), MAX_VIRUS_LEVEL),
             MetricSnips::zero_fill((123, 155), MAX_VIRUSES),
-        ),
+        )
+        // below the level label, left of the virus level
+        .with_speed_level(ThemedNumeric::new(
+            0,
+            MetricSnips::zero_fill((88, 134), MAX_SPEED_LEVEL),
+        )),
         bottles_file: sprites::BOTTLES,
-        bottle_low: Point::new(81, 0),
-        bottle_medium: Point::new(0, 0),
-        bottle_high: Point::new(162, 0),
+        bottles: SpeedVariants::new(Point::new(81, 0), Point::new(0, 0), Point::new(162, 0)),
         bottle_width: 80,
         bottle_height: 176,
         background_file: sprites::BACKGROUND,
@@ -198,7 +214,25 @@ pub fn nes_theme<'a>(
         peek_offset: 10,
         peek_max: 2,
         peek_scale: Some(0.75),
-    };
-
-    retro_theme(canvas, texture_creator, options)
+        garbage_point: Point::new(40, 32),
+        // above dr's window, right of the lounge
+        virus_counters: Some(VirusCounters {
+            point: Point::new(110, 2),
+            columns: 2,
+            spacing: (24, 11),
+        }),
+        virus_lounge: Some(VirusLounge {
+            center: Point::new(94, 11),
+            radius: 10,
+            lens_color: Color::RGBA(0xbd, 0xcc, 0xfe, 0xc0),
+            rim_color: Color::RGB(0xc4, 0xb2, 0x00),
+        }),
+        menu_style: MenuStyle {
+            highlight_color: Color::RGB(0xd8, 0x28, 0x00),
+            selected_color: Color::WHITE,
+            chime: MenuChime::Retro,
+            ..MenuStyle::default()
+        },
+        color_blind_patterns: config.accessibility.color_blind_patterns,
+    })
 }
@@ -7,6 +7,7 @@ pub enum GameMetricType {
     Score,
     Level,
     VirusCount,
+    SpeedLevel,
 }
 
 impl GameMetricType {
@@ -15,6 +16,7 @@ impl GameMetricType {
             GameMetricType::Score => "Score",
             GameMetricType::Level => "Level",
             GameMetricType::VirusCount => "Virus",
+            GameMetricType::SpeedLevel => "Speed",
         }
     }
 }
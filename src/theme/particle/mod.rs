@@ -1,14 +1,17 @@
+use crate::animate::crawl::VirusCrawlType;
 use crate::animate::dr::DrAnimationType;
 use crate::animate::virus::VirusAnimationType;
 use crate::config::Config;
 use crate::font::FontType;
 use crate::game::bottle::BOTTLE_HEIGHT;
 use crate::game::pill::{VirusColor, LEFT_VITAMIN_SPAWN_POINT};
-use crate::game::random::MAX_VIRUSES;
-use crate::game::rules::MAX_VIRUS_LEVEL;
-use crate::game::MAX_SCORE;
-use crate::theme::animation::AnimationSpriteSheetData;
+use crate::game::random::{MAX_VIRUSES, PEEK_SIZE};
+use crate::game::rules::{MusicTrack, MAX_VIRUS_LEVEL};
+use crate::game::{MAX_SCORE, MAX_SPEED_LEVEL};
+use crate::menu::MenuStyle;
+use crate::theme::animation::{AnimationSpriteSheetData, SpriteFile};
 use crate::theme::font::{FontRender, FontTheme, ThemedNumeric};
+use crate::theme::gameboy::sound as gameboy_sound;
 use crate::theme::geometry::BottleGeometry;
 use crate::theme::helper::{TextureFactory, TextureQuery};
 use crate::theme::particle::game_metrics::GameMetricsTable;
@@ -17,7 +20,7 @@ use crate::theme::sound::AudioTheme;
 use crate::theme::sprite_sheet::{
     pills, BlockAnimationsData, BlockPoints, DrType, VitaminSpriteSheet, VitaminSpriteSheetData,
 };
-use crate::theme::{AnimationMeta, Theme, ThemeName};
+use crate::theme::{AnimationMeta, BackgroundLayout, SpeedVariants, Theme, ThemeName};
 use game_metrics::GameMetricType;
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
@@ -31,7 +34,9 @@ mod game_metrics;
 pub mod sprites {
     // vitamins
     pub const VITAMINS: &[u8] = include_bytes!("vitamins.png");
-    pub const SRC_BLOCK_SIZE: u32 = 100; // TODO I have this upto 400 does it look any nicer?
+    pub const VITAMINS_2X: &[u8] = include_bytes!("vitamins@2x.png");
+    /// block size of the 1x vitamins, 100 at 2x
+    pub const SRC_BLOCK_SIZE: u32 = 50;
 
     // viruses
     pub const VIRUS_RED_IDLE: &[u8] = include_bytes!("viruses/r.png");
@@ -73,11 +78,22 @@ const BOTTLE_TOP_BUFFER_PCT: f64 = 0.15;
 const MIN_VERTICAL_BUFFER_PCT: f64 = 0.03; // TODO this should be derived
 const BOTTLE_BORDER_PCT_OF_BLOCK: f64 = 0.5;
 const BOTTLE_BOARDER_SHADOW: u8 = 0x99;
+/// bottle border tint at low, medium & high speed
+const BOTTLE_ACCENTS: [Color; 3] = [
+    Color::RGB(0x66, 0xcc, 0xff),
+    Color::WHITE,
+    Color::RGB(0xff, 0x66, 0x44),
+];
 const VERTICAL_GUTTER_PCT_OF_BLOCK: f64 = 0.2;
 
 const DR_SCALE_OF_BLOCK: f64 = 6.5;
 const PEEK_SCALE: f64 = 0.8;
 
+/// The vitamins at 2x are picked when the blocks are large e.g. on a 4k monitor
+fn vitamins() -> SpriteFile {
+    SpriteFile::new(sprites::VITAMINS).with_variant(2, sprites::VITAMINS_2X)
+}
+
 fn block(i: i32, j: i32) -> Point {
     Point::new(
         i * sprites::SRC_BLOCK_SIZE as i32,
@@ -107,7 +123,7 @@ fn animations(virus_idle: &'static [u8], garbage_i: i32) -> BlockAnimationsData
         AnimationSpriteSheetData::exclusive_square_linear(virus_idle),
         AnimationSpriteSheetData::static_first_square_frame(virus_idle),
         AnimationSpriteSheetData::non_exclusive_linear(
-            sprites::VITAMINS,
+            vitamins(),
             block(garbage_i, 3),
             1,
             sprites::SRC_BLOCK_SIZE,
@@ -120,7 +136,7 @@ pub fn particle_theme<'a>(
     canvas: &mut WindowCanvas,
     texture_creator: &'a TextureCreator<WindowContext>,
     ttf: &Sdl2TtfContext,
-    config: Config,
+    config: &Config,
 ) -> Result<Theme<'a>, String> {
     let (_, window_height) = canvas.window().size();
 
@@ -186,12 +202,13 @@ pub fn particle_theme<'a>(
             (GameMetricType::Score, MAX_SCORE),
             (GameMetricType::Level, MAX_VIRUS_LEVEL),
             (GameMetricType::VirusCount, MAX_VIRUSES),
+            (GameMetricType::SpeedLevel, MAX_SPEED_LEVEL),
         ],
     );
     metrics_right.offset_x(bottle_bg_snip.right() + vertical_gutter as i32);
 
     let sprite_data = VitaminSpriteSheetData::new(
-        sprites::VITAMINS,
+        vitamins(),
         pills(
             sprites::SRC_BLOCK_SIZE * 2,
             sprites::SRC_BLOCK_SIZE,
@@ -221,7 +238,13 @@ pub fn particle_theme<'a>(
         AnimationSpriteSheetData::exclusive_table(sprites::DR_IDLE, 12, 11, 123),
         Some(DR_SCALE_OF_BLOCK * block_size as f64 / sprites::SRC_DR_WIDTH as f64),
     );
-    let sprites = VitaminSpriteSheet::new(canvas, texture_creator, sprite_data, block_size)?;
+    let sprites = VitaminSpriteSheet::new(
+        canvas,
+        texture_creator,
+        sprite_data,
+        block_size,
+        config.accessibility.color_blind_patterns,
+    )?;
 
     let dr_y = bottle_top_buffer as i32;
     let dr_x = bottle_bg_snip.right() + vertical_gutter as i32;
@@ -260,27 +283,49 @@ pub fn particle_theme<'a>(
     }
 
     let all_metrics = metrics_right.rows();
-    let mut bottle_texture =
-        texture_creator.create_texture_target_blended(bottle_snip.width(), bottle_snip.height())?;
+    // one bottle per speed side by side, each with tinted borders
+    let mut bottle_texture = texture_creator.create_texture_target_blended(
+        bottle_snip.width() * BOTTLE_ACCENTS.len() as u32,
+        bottle_snip.height(),
+    )?;
     canvas
         .with_texture_canvas(&mut bottle_texture, |c| {
             c.set_draw_color(Color::RGBA(0, 0, 0, 0));
             c.clear();
-            for (r, color) in borders.iter().copied() {
-                c.set_draw_color(Color::RGBA(color, color, color, color));
-                c.draw_rect(r).unwrap();
+            for (i, accent) in BOTTLE_ACCENTS.iter().enumerate() {
+                let offset_x = i as i32 * bottle_snip.width() as i32;
+                for (r, color) in borders.iter().copied() {
+                    let tint = |channel: u8| (channel as u32 * color as u32 / 0xff) as u8;
+                    c.set_draw_color(Color::RGBA(
+                        tint(accent.r),
+                        tint(accent.g),
+                        tint(accent.b),
+                        color,
+                    ));
+                    c.draw_rect(Rect::new(r.x() + offset_x, r.y(), r.width(), r.height()))
+                        .unwrap();
+                }
+                // re-clear the board to get rid of the top of the border
+                c.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                c.fill_rect(Rect::new(
+                    offset_x + border_weight as i32,
+                    0,
+                    geometry.width(),
+                    bottle_top_buffer + geometry.height(),
+                ))
+                .unwrap();
             }
-            // re-clear the board to get rid of the top of the border
-            c.set_draw_color(Color::RGBA(0, 0, 0, 0));
-            c.fill_rect(Rect::new(
-                border_weight as i32,
-                0,
-                geometry.width(),
-                bottle_top_buffer + geometry.height(),
-            ))
-            .unwrap();
         })
         .map_err(|e| e.to_string())?;
+    let accent_snip = |i: i32| {
+        Rect::new(
+            i * bottle_snip.width() as i32,
+            0,
+            bottle_snip.width(),
+            bottle_snip.height(),
+        )
+    };
+    let bottle_snips = SpeedVariants::new(accent_snip(0), accent_snip(1), accent_snip(2));
 
     let mut bg_texture = texture_creator.create_texture_target_blended(
         bottle_bg_snip.right() as u32 + vertical_gutter + dr_width.max(metrics_right.width()),
@@ -299,14 +344,18 @@ pub fn particle_theme<'a>(
         })
         .map_err(|e| e.to_string())?;
 
+    let hold_point = Point::new(0, bottle_top_buffer as i32);
     let animation_meta = AnimationMeta {
         virus_type: VirusAnimationType::Linear { fps: 30 },
+        virus_crawl_type: VirusCrawlType::Slide,
         red_virus_frames: sprites.virus_frames(VirusColor::Red),
         blue_virus_frames: sprites.virus_frames(VirusColor::Blue),
         yellow_virus_frames: sprites.virus_frames(VirusColor::Yellow),
+        green_virus_frames: sprites.virus_frames(VirusColor::Green),
         vitamin_pop_frames: sprites.vitamin_pop_frames(),
         virus_pop_frames: sprites.virus_pop_frames(),
         throw_start: dr_hand_point,
+        hold_point,
         throw_end: geometry.point(LEFT_VITAMIN_SPAWN_POINT).offset(bottle_bg_snip.left(), 0),
         dr_throw_type: DrAnimationType::Linear { fps: sprites::DR_FPS },
         dr_throw_frames: sprites.dr_sprites(DrType::Throw).frame_count(),
@@ -365,12 +414,18 @@ pub fn particle_theme<'a>(
         .find(|r| r.metric() == GameMetricType::VirusCount)
         .unwrap()
         .value();
+    let speed_level_snips = all_metrics
+        .iter()
+        .find(|r| r.metric() == GameMetricType::SpeedLevel)
+        .unwrap()
+        .value();
     let font_theme = FontTheme::new(
         vec![font],
         ThemedNumeric::new(0, score_snips),
         ThemedNumeric::new(0, virus_level_snips),
         ThemedNumeric::new(0, virus_count_snips),
-    );
+    )
+    .with_speed_level(ThemedNumeric::new(0, speed_level_snips));
 
     let audio = AudioTheme::new(
         config.audio,
@@ -387,7 +442,9 @@ pub fn particle_theme<'a>(
         sound::NEXT_LEVEL_JINGLE,
         sound::HARD_DROP,
     )?
-    .with_game_music(sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+    .with_game_music(MusicTrack::Fever, sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+    // no chill recording of its own, borrow the Game Boy one
+    .with_game_music(MusicTrack::Chill, gameboy_sound::CHILL, None)?
     .with_game_over_music(sound::GAME_OVER_INTRO, sound::GAME_OVER_REPEAT)?
     .with_next_level_music(
         sound::FEVER_NEXT_LEVEL_INTRO,
@@ -395,25 +452,23 @@ pub fn particle_theme<'a>(
     )?
     .with_victory_music(sound::VICTORY, None)?;
 
-    let scene_type = SceneType::Particles {
-        base_color: Color::WHITE,
-    };
+    // the faster the game the faster the background
+    let base_color = Color::WHITE;
     Ok(Theme {
         name: ThemeName::Particle,
-        scene_low: scene_type.build(canvas, texture_creator)?,
-        scene_medium: scene_type.build(canvas, texture_creator)?,
-        scene_high: scene_type.build(canvas, texture_creator)?,
+        scene_low: SceneType::Snow { base_color }.build(canvas, texture_creator)?,
+        scene_medium: SceneType::Bubbles { base_color }.build(canvas, texture_creator)?,
+        scene_high: SceneType::Starfield { base_color }.build(canvas, texture_creator)?,
         sprites,
         geometry,
         audio,
         font: font_theme,
         bottles_texture: bottle_texture,
-        bottle_low_snip: bottle_snip,
-        bottle_medium_snip: bottle_snip,
-        bottle_high_snip: bottle_snip,
+        bottle_snips,
         background_texture: bg_texture,
         bottle_bg_snip,
         background_size,
+        background_layout: BackgroundLayout::PerPlayer,
         dr_order_first: true,
         dr_hand_point,
         dr_throw_point,
@@ -423,13 +478,21 @@ pub fn particle_theme<'a>(
         game_over_snips: vec![game_over_snip],
         next_level_snips: vec![next_level_snip],
         match_end_texture,
-        hold_point: Point::new(
-            0,
-            bottle_top_buffer as i32,
-        ),
+        hold_point,
         peek_point: dr_hand_point + Point::new(0, (1.5 * block_size as f64).round() as i32),
         peek_offset: block_size as i32,
-        peek_max: 2,
+        // room for the whole queue below the dr's hand
+        peek_max: PEEK_SIZE as u32 - 1,
         peek_scale: Some(PEEK_SCALE),
+        garbage_point: Point::new(
+            bottle_snip.width() as i32 / 2,
+            bottle_top_buffer as i32 - 3 * block_size as i32 / 2,
+        ),
+        virus_counters: None,
+        virus_lounge: None,
+        menu_style: MenuStyle {
+            font: FontType::Bold,
+            ..MenuStyle::default()
+        },
     })
 }
@@ -0,0 +1,106 @@
+use crate::game::pill::VirusColor;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, WindowCanvas};
+
+const GLYPH_SIZE: u32 = 5;
+
+/// 5x5 glyphs, one row per byte with the left most cell in the highest of the low 5 bits
+const YELLOW_GLYPH: [u8; 5] = [0b00000, 0b01110, 0b01010, 0b01110, 0b00000];
+const RED_GLYPH: [u8; 5] = [0b00000, 0b00100, 0b01110, 0b00100, 0b00000];
+const BLUE_GLYPH: [u8; 5] = [0b00000, 0b01010, 0b00100, 0b01010, 0b00000];
+const GREEN_GLYPH: [u8; 5] = [0b00000, 0b00100, 0b01010, 0b00100, 0b00000];
+
+/// Draws the glyph of a color over a block or virus so colors can be told apart by shape:
+/// a ring on yellow, a plus on red, a cross on blue & a diamond on green
+pub fn draw_color_pattern(
+    canvas: &mut WindowCanvas,
+    color: VirusColor,
+    dst: Rect,
+    block_size: u32,
+) -> Result<(), String> {
+    // dark on the light yellow, light on the darker red, blue & green
+    canvas.set_draw_color(match color {
+        VirusColor::Yellow => Color::RGBA(0, 0, 0, 0xa0),
+        VirusColor::Red | VirusColor::Blue | VirusColor::Green => Color::RGBA(0xff, 0xff, 0xff, 0xc0),
+    });
+    canvas.set_blend_mode(BlendMode::Blend);
+    let result = canvas.fill_rects(&glyph_cells(color_glyph(color), dst, block_size));
+    canvas.set_blend_mode(BlendMode::None);
+    result
+}
+
+/// Draws the glyphs of both halves of a horizontal pill
+pub fn draw_pill_pattern(
+    canvas: &mut WindowCanvas,
+    (left_color, right_color): (VirusColor, VirusColor),
+    dst: Rect,
+    block_size: u32,
+) -> Result<(), String> {
+    let half_width = dst.width() / 2;
+    let left = Rect::new(dst.x(), dst.y(), half_width, dst.height());
+    let right = Rect::new(dst.x() + half_width as i32, dst.y(), half_width, dst.height());
+    draw_color_pattern(canvas, left_color, left, block_size)?;
+    draw_color_pattern(canvas, right_color, right, block_size)
+}
+
+fn color_glyph(color: VirusColor) -> [u8; 5] {
+    match color {
+        VirusColor::Yellow => YELLOW_GLYPH,
+        VirusColor::Red => RED_GLYPH,
+        VirusColor::Blue => BLUE_GLYPH,
+        VirusColor::Green => GREEN_GLYPH,
+    }
+}
+
+/// Filled cells of a glyph, centered on dst & scaled to the block size
+pub(crate) fn glyph_cells(glyph: [u8; 5], dst: Rect, block_size: u32) -> Vec<Rect> {
+    let cell = (block_size / GLYPH_SIZE).max(1);
+    let x = dst.x() + (dst.width() as i32 - (cell * GLYPH_SIZE) as i32) / 2;
+    let y = dst.y() + (dst.height() as i32 - (cell * GLYPH_SIZE) as i32) / 2;
+    (0..GLYPH_SIZE)
+        .flat_map(|j| (0..GLYPH_SIZE).map(move |i| (i, j)))
+        .filter(|&(i, j)| glyph[j as usize] & (1 << (GLYPH_SIZE - 1 - i)) != 0)
+        .map(|(i, j)| {
+            Rect::new(
+                x + (i * cell) as i32,
+                y + (j * cell) as i32,
+                cell,
+                cell,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_centered_on_block() {
+        let cells = glyph_cells(RED_GLYPH, Rect::new(10, 20, 12, 12), 12);
+        assert_eq!(
+            cells,
+            vec![
+                Rect::new(15, 23, 2, 2),
+                Rect::new(13, 25, 2, 2),
+                Rect::new(15, 25, 2, 2),
+                Rect::new(17, 25, 2, 2),
+                Rect::new(15, 27, 2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn distinct_glyph_per_color() {
+        let dst = Rect::new(0, 0, 7, 7);
+        for (i, a) in VirusColor::FOUR_COLORS.into_iter().enumerate() {
+            for b in VirusColor::FOUR_COLORS.into_iter().skip(i + 1) {
+                assert_ne!(
+                    glyph_cells(color_glyph(a), dst, 7),
+                    glyph_cells(color_glyph(b), dst, 7)
+                );
+            }
+        }
+    }
+}
@@ -1,13 +1,33 @@
-use crate::font::{FontTexture, FontType};
+use crate::config::InputConfig;
+use crate::manual::ManualScreen;
+use crate::menu::{Menu, MenuItem, MenuStyle};
+use crate::menu_input::MenuInputKey;
 use sdl2::pixels::Color;
+use sdl2::rect::Point;
 use sdl2::pixels::PixelFormatEnum::RGBA8888;
-use sdl2::rect::Rect;
 use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
 use sdl2::ttf::Sdl2TtfContext;
 use sdl2::video::WindowContext;
 
+const RESUME: &str = "resume";
+const RESTART: &str = "restart match";
+const CHANGE_THEME: &str = "change theme";
+const MANUAL: &str = "manual";
+const QUIT: &str = "quit to menu";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseAction {
+    Resume,
+    Restart,
+    NextTheme,
+    ReturnToMenu,
+}
+
 pub struct PausedScreen<'a> {
     texture: Texture<'a>,
+    menu: Menu<'a>,
+    manual: ManualScreen<'a>,
+    show_manual: bool,
 }
 
 impl<'a> PausedScreen<'a> {
@@ -15,15 +35,26 @@ impl<'a> PausedScreen<'a> {
         canvas: &mut WindowCanvas,
         ttf: &Sdl2TtfContext,
         texture_creator: &'a TextureCreator<WindowContext>,
+        input: &InputConfig,
         (window_width, window_height): (u32, u32),
+        allow_theme_change: bool,
+        style: MenuStyle,
     ) -> Result<Self, String> {
-        let font = FontType::Retro.load(ttf, window_width / 16)?;
-        let font_texture = FontTexture::from_string(&font, texture_creator, "pause", Color::WHITE)?;
-        let font_rect = Rect::from_center(
-            (window_width as i32 / 2, window_height as i32 / 2),
-            font_texture.width,
-            font_texture.height,
-        );
+        let mut menu_items = vec![MenuItem::select(RESUME), MenuItem::select(RESTART)];
+        if allow_theme_change {
+            menu_items.push(MenuItem::select(CHANGE_THEME));
+        }
+        menu_items.push(MenuItem::select(MANUAL));
+        menu_items.push(MenuItem::select(QUIT));
+        let menu = Menu::new(
+            menu_items,
+            canvas,
+            ttf,
+            texture_creator,
+            "pause".to_string(),
+            None,
+            style,
+        )?;
 
         let mut texture = texture_creator
             .create_texture_target(RGBA8888, window_width, window_height)
@@ -33,13 +64,64 @@ impl<'a> PausedScreen<'a> {
             .with_texture_canvas(&mut texture, |c| {
                 c.set_draw_color(Color::RGBA(0, 0, 0, 0xdd));
                 c.clear();
-                c.copy(&font_texture.texture, None, font_rect).unwrap();
             })
             .map_err(|e| e.to_string())?;
-        Ok(Self { texture })
+        let manual = ManualScreen::new(
+            input,
+            ttf,
+            texture_creator,
+            (window_width, window_height),
+        )?;
+        Ok(Self {
+            texture,
+            menu,
+            manual,
+            show_manual: false,
+        })
+    }
+
+    /// Moves the cursor back to resume, called each time the game is paused
+    pub fn reset(&mut self) {
+        self.menu.reset();
+        self.show_manual = false;
+    }
+
+    /// Highlights the pause menu item under the mouse
+    pub fn hover(&mut self, point: Point) {
+        if !self.show_manual {
+            self.menu.hover(point);
+        }
+    }
+
+    pub fn read_key(&mut self, key: MenuInputKey) -> Option<PauseAction> {
+        if self.show_manual {
+            // back to the pause menu rather than resuming
+            self.show_manual = !self.manual.read_key(key);
+            return None;
+        }
+        if key == MenuInputKey::Back {
+            return Some(PauseAction::Resume);
+        }
+        match self.menu.read_key(key)? {
+            (RESUME, _) => Some(PauseAction::Resume),
+            (RESTART, _) => Some(PauseAction::Restart),
+            (CHANGE_THEME, _) => Some(PauseAction::NextTheme),
+            (MANUAL, _) => {
+                self.manual.reset();
+                self.show_manual = true;
+                None
+            }
+            (QUIT, _) => Some(PauseAction::ReturnToMenu),
+            _ => None,
+        }
     }
 
-    pub fn draw(&self, canvas: &mut WindowCanvas) -> Result<(), String> {
-        canvas.copy(&self.texture, None, None)
+    pub fn draw(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        canvas.copy(&self.texture, None, None)?;
+        if self.show_manual {
+            self.manual.draw(canvas)
+        } else {
+            self.menu.draw(canvas)
+        }
     }
 }
@@ -0,0 +1,86 @@
+//! Marks drawn over power-up pills so they stand out from plain pills in every theme
+
+use crate::game::pill::PillEffect;
+use crate::theme::pattern::glyph_cells;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, WindowCanvas};
+
+/// 5x5 glyphs in the same layout as the color patterns
+const BOMB_GLYPH: [u8; 5] = [0b00001, 0b01110, 0b11111, 0b11111, 0b01110];
+const LIGHTNING_GLYPH: [u8; 5] = [0b00110, 0b01100, 0b11111, 0b00110, 0b01100];
+const HEAVY_GLYPH: [u8; 5] = [0b00100, 0b00100, 0b11111, 0b01110, 0b00100];
+
+const MARK_COLOR: Color = Color::RGBA(0xff, 0xff, 0xff, 0xe0);
+const SHADOW_COLOR: Color = Color::RGBA(0, 0, 0, 0xa0);
+
+fn effect_glyph(effect: PillEffect) -> Option<[u8; 5]> {
+    match effect {
+        PillEffect::None => None,
+        PillEffect::Bomb => Some(BOMB_GLYPH),
+        PillEffect::Lightning => Some(LIGHTNING_GLYPH),
+        PillEffect::Heavy => Some(HEAVY_GLYPH),
+    }
+}
+
+/// Draws the mark of a power-up over one of its vitamins, nothing for a plain pill
+pub fn draw_power_up(
+    canvas: &mut WindowCanvas,
+    effect: PillEffect,
+    dst: Rect,
+    block_size: u32,
+) -> Result<(), String> {
+    let glyph = match effect_glyph(effect) {
+        Some(glyph) => glyph,
+        None => return Ok(()),
+    };
+    let cells = glyph_cells(glyph, dst, block_size);
+    // a drop shadow keeps the light mark readable on every color
+    let shadow = cells
+        .iter()
+        .map(|cell| Rect::new(cell.x() + 1, cell.y() + 1, cell.width(), cell.height()))
+        .collect::<Vec<Rect>>();
+    canvas.set_blend_mode(BlendMode::Blend);
+    canvas.set_draw_color(SHADOW_COLOR);
+    let result = canvas.fill_rects(&shadow).and_then(|_| {
+        canvas.set_draw_color(MARK_COLOR);
+        canvas.fill_rects(&cells)
+    });
+    canvas.set_blend_mode(BlendMode::None);
+    result
+}
+
+/// Draws the marks over both halves of a horizontal power-up pill
+pub fn draw_power_up_pill(
+    canvas: &mut WindowCanvas,
+    effect: PillEffect,
+    dst: Rect,
+    block_size: u32,
+) -> Result<(), String> {
+    let half_width = dst.width() / 2;
+    let left = Rect::new(dst.x(), dst.y(), half_width, dst.height());
+    let right = Rect::new(dst.x() + half_width as i32, dst.y(), half_width, dst.height());
+    draw_power_up(canvas, effect, left, block_size)?;
+    draw_power_up(canvas, effect, right, block_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn mark_per_power_up() {
+        assert_eq!(effect_glyph(PillEffect::None), None);
+        let glyphs = PillEffect::POWER_UPS.map(|e| effect_glyph(e).unwrap());
+        for (i, a) in glyphs.iter().enumerate() {
+            for b in glyphs.iter().skip(i + 1) {
+                assert_ne!(a, b);
+            }
+        }
+        assert_eq!(
+            PillEffect::iter().filter(|e| e.is_power_up()).count(),
+            PillEffect::POWER_UPS.len()
+        );
+    }
+}
@@ -1,13 +1,18 @@
+use crate::animate::crawl::VirusCrawlType;
 use crate::animate::dr::DrAnimationType;
 use crate::animate::virus::VirusAnimationType;
 use crate::game::pill::{VirusColor, LEFT_VITAMIN_SPAWN_POINT};
+use crate::menu::MenuStyle;
+use crate::particles::prescribed::PrescribedParticles;
 use crate::theme::font::FontThemeOptions;
 use crate::theme::geometry::BottleGeometry;
 use crate::theme::helper::{TextureFactory, TextureQuery};
 use crate::theme::scene::SceneType;
 use crate::theme::sound::AudioTheme;
 use crate::theme::sprite_sheet::{DrType, VitaminSpriteSheet, VitaminSpriteSheetData};
-use crate::theme::{AnimationMeta, Theme, ThemeName};
+use crate::theme::{
+    AnimationMeta, BackgroundLayout, SpeedVariants, Theme, ThemeName, VirusCounters, VirusLounge,
+};
 
 use sdl2::rect::{Point, Rect};
 use sdl2::render::{TextureCreator, WindowCanvas};
@@ -28,9 +33,8 @@ pub struct RetroThemeOptions {
     pub audio: AudioTheme,
     pub font: FontThemeOptions,
     pub bottles_file: &'static [u8],
-    pub bottle_low: Point,
-    pub bottle_medium: Point,
-    pub bottle_high: Point,
+    /// top left of the bottle art in bottles_file for each speed
+    pub bottles: SpeedVariants<Point>,
     pub bottle_width: u32,
     pub bottle_height: u32,
     pub background_file: &'static [u8],
@@ -49,6 +53,15 @@ pub struct RetroThemeOptions {
     pub peek_max: u32,
     pub peek_offset: i32,
     pub peek_scale: Option<f64>,
+    /// top center of the pending garbage warning icons, relative to the bottle
+    pub garbage_point: Point,
+    /// None for themes without room to count the viruses of each color
+    pub virus_counters: Option<VirusCounters>,
+    /// None for themes without a magnifying glass
+    pub virus_lounge: Option<VirusLounge>,
+    pub menu_style: MenuStyle,
+    /// overlay a glyph on the blocks & viruses of each color, see AccessibilityConfig
+    pub color_blind_patterns: bool,
 }
 
 pub fn retro_theme<'a>(
@@ -56,7 +69,13 @@ pub fn retro_theme<'a>(
     texture_creator: &'a TextureCreator<WindowContext>,
     options: RetroThemeOptions,
 ) -> Result<Theme<'a>, String> {
-    let sprites = VitaminSpriteSheet::new(canvas, texture_creator, options.sprites, None)?;
+    let sprites = VitaminSpriteSheet::new(
+        canvas,
+        texture_creator,
+        options.sprites,
+        None,
+        options.color_blind_patterns,
+    )?;
     let bottles_texture = texture_creator.load_texture_bytes_blended(options.bottles_file)?;
 
     let background_texture = texture_creator.load_texture_bytes_blended(options.background_file)?;
@@ -92,12 +111,15 @@ pub fn retro_theme<'a>(
 
     let animation_meta = AnimationMeta {
         virus_type: options.virus_animation_type,
+        virus_crawl_type: VirusCrawlType::Hop,
         red_virus_frames: sprites.virus_frames(VirusColor::Red),
         blue_virus_frames: sprites.virus_frames(VirusColor::Blue),
         yellow_virus_frames: sprites.virus_frames(VirusColor::Yellow),
+        green_virus_frames: sprites.virus_frames(VirusColor::Green),
         vitamin_pop_frames: sprites.vitamin_pop_frames(),
         virus_pop_frames: sprites.virus_pop_frames(),
         throw_start: options.dr_hand_point,
+        hold_point: options.hold_point,
         throw_end: options.geometry.point(LEFT_VITAMIN_SPAWN_POINT) + options.bottle_point + options.dr_throw_end_offset,
         dr_throw_type: options.dr_throw_animation_type,
         dr_throw_frames: sprites.dr_sprites(DrType::Throw).frame_count(),
@@ -111,34 +133,31 @@ pub fn retro_theme<'a>(
         next_level_interstitial_frames: next_level_snips.len(),
     };
 
+    let victory = PrescribedParticles::Confetti {
+        theme: options.name,
+    };
     Ok(Theme {
         name: options.name,
-        scene_low: options.scene_low.build(canvas, texture_creator)?,
-        scene_medium: options.scene_medium.build(canvas, texture_creator)?,
-        scene_high: options.scene_high.build(canvas, texture_creator)?,
+        scene_low: options
+            .scene_low
+            .build(canvas, texture_creator)?
+            .with_victory(victory),
+        scene_medium: options
+            .scene_medium
+            .build(canvas, texture_creator)?
+            .with_victory(victory),
+        scene_high: options
+            .scene_high
+            .build(canvas, texture_creator)?
+            .with_victory(victory),
         sprites,
         geometry: options.geometry,
         audio: options.audio,
         font,
         bottles_texture,
-        bottle_low_snip: Rect::new(
-            options.bottle_low.x,
-            options.bottle_low.y,
-            options.bottle_width,
-            options.bottle_height,
-        ),
-        bottle_medium_snip: Rect::new(
-            options.bottle_medium.x,
-            options.bottle_medium.y,
-            options.bottle_width,
-            options.bottle_height,
-        ),
-        bottle_high_snip: Rect::new(
-            options.bottle_high.x,
-            options.bottle_high.y,
-            options.bottle_width,
-            options.bottle_height,
-        ),
+        bottle_snips: options
+            .bottles
+            .map(|p| Rect::new(p.x, p.y, options.bottle_width, options.bottle_height)),
         bottle_bg_snip: Rect::new(
             options.bottle_point.x(),
             options.bottle_point.y(),
@@ -147,6 +166,7 @@ pub fn retro_theme<'a>(
         ),
         background_texture,
         background_size,
+        background_layout: BackgroundLayout::PerPlayer,
         dr_order_first: options.dr_order_first,
         dr_hand_point: options.dr_hand_point,
         dr_throw_point: options.dr_throw_point,
@@ -161,5 +181,9 @@ pub fn retro_theme<'a>(
         peek_offset: options.peek_offset,
         peek_scale: options.peek_scale,
         peek_max: options.peek_max,
+        garbage_point: options.garbage_point,
+        virus_counters: options.virus_counters,
+        virus_lounge: options.virus_lounge,
+        menu_style: options.menu_style,
     })
 }
@@ -1,8 +1,13 @@
 use crate::game::event::GameEvent;
+use crate::game::geometry::BottlePoint;
 use crate::game::pill::VITAMIN_SPAWN_POINTS;
+use crate::game::rules::GarbageStyle;
 use crate::particles::prescribed::{
+    prescribed_bubbles, prescribed_orbit, prescribed_snow, prescribed_starfield,
     PlayerParticleTarget, PlayerTargetedParticles, PrescribedParticles,
 };
+use crate::particles::scale::Scale as ParticleScale;
+use crate::particles::source::ParticleSource;
 use crate::scale::Scale;
 use sdl2::image::LoadTexture;
 use sdl2::pixels::Color;
@@ -14,9 +19,22 @@ use sdl2::pixels::PixelFormatEnum::RGBA8888;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SceneType {
+    /// Particles orbiting the middle of the window
     Particles {
         base_color: Color,
     },
+    /// Drifting down slowly
+    Snow {
+        base_color: Color,
+    },
+    /// Rising from the bottom of the window
+    Bubbles {
+        base_color: Color,
+    },
+    /// Streaking across the window
+    Starfield {
+        base_color: Color,
+    },
     Checkerboard {
         width: u32,
         height: u32,
@@ -41,6 +59,8 @@ pub struct SceneRender<'a> {
     scene_type: SceneType,
     texture: Texture<'a>,
     rect_0: Rect,
+    /// emitted over the background of the winner, otherwise particle scenes spray their bottle
+    victory: Option<PrescribedParticles>,
 }
 
 impl<'a> SceneRender<'a> {
@@ -81,7 +101,10 @@ impl<'a> SceneRender<'a> {
             }
             SceneType::Tile { texture } => texture_creator.load_texture_bytes(texture)?,
             // TODO this is dirty
-            SceneType::Particles { .. } => texture_creator
+            SceneType::Particles { .. }
+            | SceneType::Snow { .. }
+            | SceneType::Bubbles { .. }
+            | SceneType::Starfield { .. } => texture_creator
                 .create_texture_target(None, 1, 1)
                 .map_err(|e| e.to_string())?,
         };
@@ -91,15 +114,54 @@ impl<'a> SceneRender<'a> {
             scene_type,
             texture,
             rect_0: Rect::new(0, 0, query.width, query.height),
+            victory: None,
         })
     }
 
+    pub fn with_victory(mut self, particles: PrescribedParticles) -> Self {
+        self.victory = Some(particles);
+        self
+    }
+
     pub fn is_particles(&self) -> bool {
-        matches!(self.scene_type, SceneType::Particles { .. })
+        self.base_color().is_some()
+    }
+
+    /// Color of the particles of a particle scene
+    fn base_color(&self) -> Option<Color> {
+        match self.scene_type {
+            SceneType::Particles { base_color }
+            | SceneType::Snow { base_color }
+            | SceneType::Bubbles { base_color }
+            | SceneType::Starfield { base_color } => Some(base_color),
+            _ => None,
+        }
+    }
+
+    /// The background of a particle scene, drawn behind the bottles
+    pub fn particle_source(
+        &self,
+        window: Rect,
+        scale: &ParticleScale,
+    ) -> Option<Box<dyn ParticleSource>> {
+        match self.scene_type {
+            SceneType::Particles { .. } => Some(prescribed_orbit(window, scale)),
+            SceneType::Snow { base_color } => Some(prescribed_snow(window, scale, base_color)),
+            SceneType::Bubbles { base_color } => {
+                Some(prescribed_bubbles(window, scale, base_color))
+            }
+            SceneType::Starfield { base_color } => {
+                Some(prescribed_starfield(window, scale, base_color))
+            }
+            _ => None,
+        }
     }
 
     pub fn emit_particles(&self, event: GameEvent) -> Option<PlayerTargetedParticles> {
-        if let SceneType::Particles { base_color } = self.scene_type {
+        if let (GameEvent::Victory { player }, Some(victory)) = (&event, self.victory) {
+            return Some(victory.into_targeted(*player, PlayerParticleTarget::Background));
+        }
+        if let Some(base_color) = self.base_color() {
             match event {
                 GameEvent::Spawned { player } => {
                     let target = PlayerParticleTarget::Blocks(VITAMIN_SPAWN_POINTS.to_vec());
@@ -113,6 +175,15 @@ impl<'a> SceneRender<'a> {
                     let particles = PrescribedParticles::BurstUp { color: base_color };
                     Some(particles.into_targeted(player, target))
                 }
+                GameEvent::HardDropPreview {
+                    player,
+                    vitamins,
+                    ghost,
+                } => {
+                    let target = PlayerParticleTarget::DropPath { vitamins, ghost };
+                    let particles = PrescribedParticles::Beam { color: base_color };
+                    Some(particles.into_targeted(player, target))
+                }
                 GameEvent::SendGarbage { player, .. } => Some(
                     PrescribedParticles::PerimeterBurst { color: base_color }
                         .into_targeted(player, PlayerParticleTarget::Bottle),
@@ -121,12 +192,30 @@ impl<'a> SceneRender<'a> {
                     player,
                     vitamins,
                     hard_or_soft_dropped,
+                    ..
                 } if hard_or_soft_dropped => {
                     let target = PlayerParticleTarget::Vitamins(vitamins);
                     let particles = PrescribedParticles::BurstDown { color: base_color };
                     Some(particles.into_targeted(player, target))
                 }
-                GameEvent::ReceivedGarbage { player, garbage } => {
+                GameEvent::PowerUp {
+                    player, vitamins, ..
+                } => {
+                    let target = PlayerParticleTarget::Vitamins(vitamins);
+                    let particles = PrescribedParticles::PerimeterBurst { color: base_color };
+                    Some(particles.into_targeted(player, target))
+                }
+                GameEvent::ReceivedGarbage {
+                    player,
+                    garbage,
+                    style: GarbageStyle::Flush,
+                } => {
+                    // a row of garbage lands as one, so bursts around the whole row
+                    let target = PlayerParticleTarget::GarbageRow(garbage);
+                    let particles = PrescribedParticles::PerimeterBurst { color: base_color };
+                    Some(particles.into_targeted(player, target))
+                }
+                GameEvent::ReceivedGarbage { player, garbage, .. } => {
                     let target = PlayerParticleTarget::Garbage(garbage);
                     let particles = PrescribedParticles::BurstDown { color: base_color };
                     Some(particles.into_targeted(player, target))
@@ -136,6 +225,7 @@ impl<'a> SceneRender<'a> {
                     let particles = PrescribedParticles::FadeInLatticeBurstAndFall {
                         fade_in: Duration::from_millis(250),
                         color: base_color,
+                        bounce: true,
                     };
                     Some(particles.into_targeted(player, target))
                 }
@@ -143,6 +233,10 @@ impl<'a> SceneRender<'a> {
                     PrescribedParticles::PerimeterSpray { color: base_color }
                         .into_targeted(player, PlayerParticleTarget::Bottle),
                 ),
+                GameEvent::ToppedOut { player, .. } => Some(
+                    PrescribedParticles::PerimeterBurst { color: base_color }
+                        .into_targeted(player, PlayerParticleTarget::Bottle),
+                ),
                 GameEvent::LevelComplete { player } => Some(
                     PrescribedParticles::PerimeterBurst { color: base_color }
                         .into_targeted(player, PlayerParticleTarget::Bottle),
@@ -154,16 +248,33 @@ impl<'a> SceneRender<'a> {
         }
     }
 
+    /// Souls of the blocks a combo cleared, flying to the bottle of the player receiving its garbage
+    pub fn emit_soul(
+        &self,
+        player: u32,
+        blocks: Vec<BottlePoint>,
+        to: u32,
+    ) -> Option<PlayerTargetedParticles> {
+        if let Some(base_color) = self.base_color() {
+            let target = PlayerParticleTarget::Soul { blocks, to };
+            let particles = PrescribedParticles::Soul { color: base_color };
+            Some(particles.into_targeted(player, target))
+        } else {
+            None
+        }
+    }
+
     pub fn draw(&self, canvas: &mut WindowCanvas, scale: &Scale) -> Result<(), String> {
         if self.is_particles() {
             return Ok(());
         }
 
-        let (window_width, window_height) = scale.window_size();
+        let viewport = scale.viewport();
         let mut rect = scale.scale_rect(self.rect_0);
 
-        let (offset_x, repeat_x) = Self::offset_and_repeat(window_width, rect.width());
-        let (offset_y, repeat_y) = Self::offset_and_repeat(window_height, rect.height());
+        let (offset_x, repeat_x) = Self::offset_and_repeat(viewport.width(), rect.width());
+        let (offset_y, repeat_y) = Self::offset_and_repeat(viewport.height(), rect.height());
+        let (offset_x, offset_y) = (offset_x + viewport.x(), offset_y + viewport.y());
 
         rect.offset(offset_x, offset_y);
         for x in 0..repeat_x {
@@ -2,17 +2,20 @@ use crate::animate::dr::DrAnimationType;
 use crate::animate::virus::VirusAnimationType;
 use crate::config::Config;
 use crate::game::random::MAX_VIRUSES;
-use crate::game::rules::MAX_VIRUS_LEVEL;
-use crate::game::MAX_SCORE;
+use crate::game::rules::{MusicTrack, MAX_VIRUS_LEVEL};
+use crate::game::{MAX_SCORE, MAX_SPEED_LEVEL};
+use crate::menu::{MenuChime, MenuStyle};
 use crate::theme::animation::AnimationSpriteSheetData;
-use crate::theme::font::{FontRenderOptions, FontThemeOptions, MetricSnips};
+use crate::theme::font::{FontRenderOptions, FontThemeOptions, MetricSnips, ThemedNumeric};
+use crate::theme::gameboy::sound as gameboy_sound;
 use crate::theme::geometry::BottleGeometry;
 use crate::theme::retro::{retro_theme, RetroThemeOptions};
 use crate::theme::scene::SceneType;
 use crate::theme::sound::AudioTheme;
 use crate::theme::sprite_sheet::{pills, BlockAnimationsData, BlockPoints, VitaminSpriteSheetData};
-use crate::theme::{Theme, ThemeName};
+use crate::theme::{SpeedVariants, Theme, ThemeName, VirusCounters};
 
+use sdl2::pixels::Color;
 use sdl2::rect::Point;
 use sdl2::render::{TextureCreator, WindowCanvas};
 use sdl2::video::WindowContext;
@@ -86,7 +89,7 @@ fn match_end(i: i32, j: i32) -> Point {
 pub fn snes_theme<'a>(
     canvas: &mut WindowCanvas,
     texture_creator: &'a TextureCreator<WindowContext>,
-    config: Config,
+    config: &Config,
 ) -> Result<Theme<'a>, String> {
     let scene = SceneType::Tile {
         texture: sprites::BACKGROUND_TILE,
@@ -148,7 +151,9 @@ pub fn snes_theme<'a>(
             sound::NEXT_LEVEL_JINGLE,
             None,
         )?
-        .with_game_music(sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+        .with_game_music(MusicTrack::Fever, sound::FEVER_INTRO, sound::FEVER_REPEAT)?
+        // no chill recording of its own, borrow the Game Boy one
+        .with_game_music(MusicTrack::Chill, gameboy_sound::CHILL, None)?
         .with_game_over_music(sound::GAME_OVER_INTRO, sound::GAME_OVER_REPEAT)?
         .with_next_level_music(sound::FEVER_NEXT_LEVEL, None)?
         .with_victory_music(sound::VICTORY_INTRO, sound::VICTORY_REPEAT)?,
@@ -157,11 +162,14 @@ pub fn snes_theme<'a>(
             MetricSnips::zero_fill((91, 110), MAX_SCORE),
             MetricSnips::zero_fill((123, 131), MAX_VIRUS_LEVEL),
             MetricSnips::zero_fill((123, 152), MAX_VIRUSES),
-        ),
+        )
+        // below the level label, left of the virus level
+        .with_speed_level(ThemedNumeric::new(
+            0,
+            MetricSnips::zero_fill((88, 131), MAX_SPEED_LEVEL),
+        )),
         bottles_file: sprites::BOTTLES,
-        bottle_low: Point::new(0, 0),
-        bottle_medium: Point::new(0, 0),
-        bottle_high: Point::new(0, 0),
+        bottles: SpeedVariants::uniform(Point::new(0, 0)),
         bottle_width: 79,
         bottle_height: 175,
         background_file: sprites::BACKGROUND,
@@ -189,6 +197,21 @@ pub fn snes_theme<'a>(
         peek_offset: 10,
         peek_max: 2,
         peek_scale: Some(0.82),
+        garbage_point: Point::new(39, 31),
+        // above dr's window
+        virus_counters: Some(VirusCounters {
+            point: Point::new(80, 2),
+            columns: 3,
+            spacing: (25, 10),
+        }),
+        virus_lounge: None,
+        menu_style: MenuStyle {
+            highlight_color: Color::RGB(0xf8, 0xd8, 0x40),
+            selected_color: Color::BLACK,
+            chime: MenuChime::Retro,
+            ..MenuStyle::default()
+        },
+        color_blind_patterns: config.accessibility.color_blind_patterns,
     };
 
     retro_theme(canvas, texture_creator, options)
@@ -1,12 +1,16 @@
 use sdl2::get_error;
-use sdl2::mixer::{Chunk, Music};
+use sdl2::mixer::{Channel, Chunk, Group, Music};
 use sdl2::rwops::RWops;
-use sdl2::sys::mixer;
+use sdl2::sys::mixer::{self, MIX_CHANNELS};
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::rc::Rc;
+use std::time::Duration;
 
-use crate::config::AudioConfig;
+use crate::config::{AudioConfig, UserMusicConfig};
 use crate::game::event::GameEvent;
+use crate::game::rules::{MusicTrack, MAX_MATCH_PLAYERS};
 
 static mut NEXT_MUSIC: Option<Rc<StructuredMusic>> = None;
 
@@ -41,6 +45,14 @@ impl StructuredMusic {
         })
     }
 
+    pub fn from_file(path: &Path, loops: i32) -> Result<Self, String> {
+        Ok(Self {
+            intro: None,
+            repeating: Music::from_file(path)?,
+            loops,
+        })
+    }
+
     pub fn into_rc(self) -> Rc<Self> {
         Rc::new(self)
     }
@@ -88,27 +100,152 @@ impl LoadSound for AudioConfig {
         if raw.is_null() {
             Err(get_error())
         } else {
-            let mut chunk = Chunk { raw, owned: true };
-            chunk.set_volume(self.effects_volume());
-            Ok(chunk)
+            // effects volume is applied to the mixer channels, see AudioConfig::apply
+            Ok(Chunk { raw, owned: true })
         }
     }
 }
 
+/// Length of a loaded chunk at the format the mixer was opened with
+fn chunk_duration(chunk: &Chunk) -> Duration {
+    let Ok((frequency, format, channels)) = sdl2::mixer::query_spec() else {
+        return Duration::ZERO;
+    };
+    // the low byte of an SDL audio format is the bits per sample
+    let bytes_per_second = frequency as u64 * channels as u64 * (format & 0xff) as u64 / 8;
+    // SAFETY: raw is a valid chunk until the Chunk is dropped
+    let length = unsafe { (*chunk.raw).alen } as u64;
+    Duration::from_nanos(length * 1_000_000_000 / bytes_per_second.max(1))
+}
+
+/// Music is lowered while the combo jingles play & restored once they have finished
+#[derive(Debug, Default)]
+pub struct MusicDucking {
+    remaining: Option<Duration>,
+}
+
+impl MusicDucking {
+    pub fn is_ducked(&self) -> bool {
+        self.remaining.is_some()
+    }
+
+    pub fn duck(&mut self, duration: Duration, config: &AudioConfig) {
+        if !self.is_ducked() {
+            config.apply_ducked(true);
+        }
+        self.remaining = self.remaining.max(Some(duration));
+    }
+
+    pub fn update(&mut self, delta: Duration, config: &AudioConfig) {
+        if let Some(remaining) = self.remaining {
+            let remaining = remaining.saturating_sub(delta);
+            if remaining.is_zero() {
+                self.remaining = None;
+                config.apply_ducked(false);
+            } else {
+                self.remaining = Some(remaining);
+            }
+        }
+    }
+}
+
+/// Mixer channels reserved for the effects of each player
+const PLAYER_CHANNELS: i32 = MIX_CHANNELS as i32;
+/// Volume of the speaker on the far side from a player in vs. matches
+const FAR_PAN: u8 = 64;
+
+/// Tags a group of mixer channels for each player, panned toward their bottle in vs. matches
+/// so you can hear whose bottle popped. Call before every match.
+pub fn pan_player_channels(players: u32) -> Result<(), String> {
+    let channels = sdl2::mixer::allocate_channels(-1);
+    for player in 0..MAX_MATCH_PLAYERS as i32 {
+        let from = player * PLAYER_CHANNELS;
+        let to = (from + PLAYER_CHANNELS).min(channels);
+        if from >= to {
+            break;
+        }
+        player_group(player as u32).add_channels_range(from, to - 1);
+        for channel in (from..to).map(Channel) {
+            match (players, player) {
+                (1, _) => channel.unset_panning()?,
+                (_, 0) => channel.set_panning(255, FAR_PAN)?,
+                _ => channel.set_panning(FAR_PAN, 255)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Group 0 is every channel
+fn player_group(player: u32) -> Group {
+    Group(player as i32 + 1)
+}
+
 pub trait Playable {
     fn play(&self) -> Result<(), String>;
+
+    /// Plays on the channels of a player, any channel for match sounds
+    fn play_for(&self, player: Option<u32>) -> Result<(), String>;
 }
 
 impl Playable for Chunk {
     fn play(&self) -> Result<(), String> {
         // TODO ignore cannot play sound
-        sdl2::mixer::Channel::all().play(self, 0)?;
+        Channel::all().play(self, 0)?;
         Ok(())
     }
+
+    fn play_for(&self, player: Option<u32>) -> Result<(), String> {
+        let Some(group) = player.map(player_group) else {
+            return self.play();
+        };
+        // the oldest effect of the player is cut off when all of their channels are busy
+        match group.find_available().or_else(|| group.find_oldest()) {
+            Some(channel) => {
+                channel.play(self, 0)?;
+                Ok(())
+            }
+            None => self.play(),
+        }
+    }
+}
+
+/// Music loaded from the user music directory, shared by every theme
+#[derive(Default)]
+pub struct UserMusic {
+    game: Option<Rc<StructuredMusic>>,
+    next_level: Option<Rc<StructuredMusic>>,
+    game_over: Option<Rc<StructuredMusic>>,
+    victory: Option<Rc<StructuredMusic>>,
+}
+
+impl UserMusic {
+    /// Files that are missing or cannot be decoded are skipped, falling back to the theme music
+    pub fn load(config: &UserMusicConfig) -> Self {
+        let load = |file_name: &Option<String>, loops: i32| {
+            let file_name = file_name.as_ref()?;
+            match config
+                .path(file_name)
+                .and_then(|path| StructuredMusic::from_file(&path, loops))
+            {
+                Ok(music) => Some(music.into_rc()),
+                Err(error) => {
+                    println!("cannot load music {}: {}", file_name, error);
+                    None
+                }
+            }
+        };
+        Self {
+            game: load(&config.game, -1),
+            next_level: load(&config.next_level, 1),
+            game_over: load(&config.game_over, 1),
+            victory: load(&config.victory, -1),
+        }
+    }
 }
 
 pub struct AudioTheme {
-    game_music: Option<Rc<StructuredMusic>>,
+    game_music: HashMap<MusicTrack, Rc<StructuredMusic>>,
     game_over_music: Option<Rc<StructuredMusic>>,
     next_level_music: Option<Rc<StructuredMusic>>,
     victory_music: Option<Rc<StructuredMusic>>,
@@ -146,7 +283,7 @@ impl AudioTheme {
         next_level_jingle.set_volume(next_level_jingle.get_volume() / 2);
 
         Ok(Self {
-            game_music: None,
+            game_music: HashMap::new(),
             game_over_music: None,
             next_level_music: None,
             victory_music: None,
@@ -165,12 +302,18 @@ impl AudioTheme {
         })
     }
 
-    pub fn with_game_music(
+    pub fn with_game_music<R: Into<Option<&'static [u8]>>>(
         mut self,
-        intro: &'static [u8],
-        repeating: &'static [u8],
+        track: MusicTrack,
+        music: &'static [u8],
+        repeating: R,
     ) -> Result<Self, String> {
-        self.game_music = Some(StructuredMusic::new(intro, repeating)?.into_rc());
+        let music = if let Some(repeating) = repeating.into() {
+            StructuredMusic::new(music, repeating)?.into_rc()
+        } else {
+            StructuredMusic::repeat(music)?.into_rc()
+        };
+        self.game_music.insert(track, music);
         Ok(self)
     }
 
@@ -213,13 +356,38 @@ impl AudioTheme {
         Ok(self)
     }
 
-    pub fn play_game_music(&self) -> Result<(), String> {
-        StructuredMusic::maybe_play(self.game_music.as_ref())
+    /// Replaces the embedded music, the user game music is played for every track
+    pub fn set_user_music(&mut self, music: &UserMusic) {
+        if let Some(game) = music.game.as_ref() {
+            self.game_music.clear();
+            self.game_music.insert(MusicTrack::Fever, game.clone());
+        }
+        if let Some(next_level) = music.next_level.as_ref() {
+            self.next_level_music = Some(next_level.clone());
+        }
+        if let Some(game_over) = music.game_over.as_ref() {
+            self.game_over_music = Some(game_over.clone());
+        }
+        if let Some(victory) = music.victory.as_ref() {
+            self.victory_music = Some(victory.clone());
+        }
+    }
+
+    /// Themes without the track play fever, None halts the music
+    fn game_music(&self, track: Option<MusicTrack>) -> Option<&Rc<StructuredMusic>> {
+        let track = track?;
+        self.game_music
+            .get(&track)
+            .or_else(|| self.game_music.get(&MusicTrack::Fever))
     }
 
-    pub fn fade_in_game_music(&self) -> Result<(), String> {
+    pub fn play_game_music(&self, track: Option<MusicTrack>) -> Result<(), String> {
+        StructuredMusic::maybe_play(self.game_music(track))
+    }
+
+    pub fn fade_in_game_music(&self, track: Option<MusicTrack>) -> Result<(), String> {
         // TODO fade in
-        StructuredMusic::maybe_play(self.game_music.as_ref())
+        StructuredMusic::maybe_play(self.game_music(track))
     }
 
     pub fn play_game_over_music(&self) -> Result<(), String> {
@@ -242,33 +410,55 @@ impl AudioTheme {
         self.next_level_jingle.play()
     }
 
-    pub fn receive_event(&self, event: GameEvent) -> Result<(), String> {
+    /// How long to duck the music for when a game event plays a combo jingle
+    pub fn ducking_duration(&self, event: &GameEvent) -> Option<Duration> {
         match event {
-            GameEvent::Move => self.move_pill.play(),
-            GameEvent::Rotate => self.rotate.play(),
-            GameEvent::Lock { .. } | GameEvent::DropGarbage => self.drop.play(),
-            GameEvent::HardDrop { .. } => {
-                self.hard_drop.as_ref().map(|c| c.play()).unwrap_or(Ok(()))
+            GameEvent::Destroy {
+                blocks,
+                is_combo: true,
+                ..
+            } => {
+                let chunk = if blocks.iter().any(|b| b.is_virus) {
+                    &self.destroy_virus_combo
+                } else {
+                    &self.destroy_vitamin_combo
+                };
+                Some(chunk_duration(chunk))
             }
+            _ => None,
+        }
+    }
+
+    pub fn receive_event(&self, event: GameEvent) -> Result<(), String> {
+        let player = event.player();
+        match event {
+            GameEvent::Move { .. } => self.move_pill.play_for(player),
+            GameEvent::Rotate { .. } => self.rotate.play_for(player),
+            GameEvent::Lock { .. } | GameEvent::DropGarbage { .. } => self.drop.play_for(player),
+            GameEvent::HardDrop { .. } => self
+                .hard_drop
+                .as_ref()
+                .map(|c| c.play_for(player))
+                .unwrap_or(Ok(())),
             GameEvent::Destroy {
                 blocks, is_combo, ..
             } => {
                 if blocks.iter().any(|b| b.is_virus) {
                     if is_combo {
-                        self.destroy_virus_combo.play()
+                        self.destroy_virus_combo.play_for(player)
                     } else {
-                        self.destroy_virus.play()
+                        self.destroy_virus.play_for(player)
                     }
                 } else {
                     if is_combo {
-                        self.destroy_vitamin_combo.play()
+                        self.destroy_vitamin_combo.play_for(player)
                     } else {
-                        self.destroy_vitamin.play()
+                        self.destroy_vitamin.play_for(player)
                     }
                 }
             }
-            GameEvent::ReceivedGarbage { .. } => self.receive_garbage.play(),
-            GameEvent::SpeedLevelUp => self.speed_level_up.play(),
+            GameEvent::ReceivedGarbage { .. } => self.receive_garbage.play_for(player),
+            GameEvent::SpeedLevelUp { .. } => self.speed_level_up.play_for(player),
             GameEvent::Paused => {
                 Music::pause();
                 self.paused.play()
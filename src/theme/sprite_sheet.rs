@@ -1,17 +1,21 @@
 use crate::animate::PlayerAnimations;
 use crate::game::block::Block;
-use crate::game::bottle::BOTTLE_HEIGHT;
 use crate::game::geometry::{BottlePoint, Rotation};
 use crate::game::pill::{PillShape, VirusColor, VitaminOrdinal};
-use crate::game::Game;
-use crate::theme::animation::{AnimationSpriteSheet, AnimationSpriteSheetData};
+use crate::theme::bottle_view::BottleBlocks;
+use crate::theme::animation::{
+    snip_at_resolution, AnimationSpriteSheet, AnimationSpriteSheetData, SpriteFile,
+};
 use crate::theme::block_mask::BlockMask;
 use crate::theme::geometry::BottleGeometry;
 use crate::theme::helper::TextureFactory;
+use crate::theme::hue::{rotate_texture_hue, BLUE_TO_GREEN};
+use crate::theme::pattern::{draw_color_pattern, draw_pill_pattern};
+use crate::theme::power_up::{draw_power_up, draw_power_up_pill};
 use sdl2::image::LoadTexture;
 
 use sdl2::rect::{Point, Rect};
-use sdl2::render::{Texture, TextureCreator, WindowCanvas};
+use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
 use sdl2::video::WindowContext;
 use std::collections::HashMap;
 use sdl2::pixels::Color;
@@ -41,8 +45,8 @@ impl BlockAnimationsData {
         }
     }
 
-    pub fn non_exclusive_linear(
-        file: &'static [u8],
+    pub fn non_exclusive_linear<F: Into<SpriteFile>>(
+        file: F,
         virus_idle_start: Point,
         virus_idle_frames: u32,
         virus_pop_start: Point,
@@ -51,16 +55,17 @@ impl BlockAnimationsData {
         vitamin_pop_frames: u32,
         block_size: u32,
     ) -> Self {
+        let file = file.into();
         Self::new(
             AnimationSpriteSheetData::non_exclusive_linear(
-                file,
+                file.clone(),
                 virus_idle_start,
                 virus_idle_frames,
                 block_size,
                 block_size,
             ),
             AnimationSpriteSheetData::non_exclusive_linear(
-                file,
+                file.clone(),
                 virus_pop_start,
                 virus_pop_frames,
                 block_size,
@@ -81,21 +86,22 @@ impl BlockAnimationsData {
         canvas: &mut WindowCanvas,
         texture_creator: &'a TextureCreator<WindowContext>,
         block_size: u32,
+        scale: f64,
     ) -> Result<BlockAnimations<'a>, String> {
         Ok(BlockAnimations {
-            virus_idle: self.virus_idle.sprite_sheet(texture_creator)?.scale(
+            virus_idle: self.virus_idle.sprite_sheet(texture_creator, scale)?.0.scale(
                 canvas,
                 texture_creator,
                 block_size,
                 block_size,
             )?,
-            virus_pop: self.virus_pop.sprite_sheet(texture_creator)?.scale(
+            virus_pop: self.virus_pop.sprite_sheet(texture_creator, scale)?.0.scale(
                 canvas,
                 texture_creator,
                 block_size,
                 block_size,
             )?,
-            vitamin_pop: self.vitamin_pop.sprite_sheet(texture_creator)?.scale(
+            vitamin_pop: self.vitamin_pop.sprite_sheet(texture_creator, scale)?.0.scale(
                 canvas,
                 texture_creator,
                 block_size,
@@ -234,7 +240,7 @@ pub struct PillSnips {
 
 impl PillSnips {
     fn snip(&self, shape: PillShape) -> Rect {
-        *self.shapes.get(&shape).unwrap()
+        *self.shapes.get(&shape.without_effect()).unwrap()
     }
 }
 
@@ -310,6 +316,7 @@ pub struct FlatVitaminSpriteSheet<'a> {
     red_viruses: AnimationSpriteSheet<'a>,
     blue_viruses: AnimationSpriteSheet<'a>,
     yellow_viruses: AnimationSpriteSheet<'a>,
+    green_viruses: AnimationSpriteSheet<'a>,
     dr_animations: DrAnimations<'a>
 }
 
@@ -318,13 +325,14 @@ impl<'a> FlatVitaminSpriteSheet<'a> {
         &self.texture
     }
     pub fn snip(&self, shape: PillShape) -> Rect {
-        self.snips[&shape]
+        self.snips[&shape.without_effect()]
     }
     pub fn virus(&self, color: VirusColor) -> &AnimationSpriteSheet<'a> {
         match color {
             VirusColor::Yellow => &self.yellow_viruses,
             VirusColor::Blue => &self.blue_viruses,
-            VirusColor::Red => &self.red_viruses
+            VirusColor::Red => &self.red_viruses,
+            VirusColor::Green => &self.green_viruses,
         }
     }
     pub fn dr(&self, dr_type: DrType) -> &AnimationSpriteSheet {
@@ -333,7 +341,7 @@ impl<'a> FlatVitaminSpriteSheet<'a> {
 }
 
 pub struct VitaminSpriteSheetData {
-    file: &'static [u8],
+    file: SpriteFile,
     pills: HashMap<PillShape, Rect>,
     pill_size: (u32, u32),
     yellow_blocks: BlockPoints,
@@ -342,6 +350,9 @@ pub struct VitaminSpriteSheetData {
     red_animations: BlockAnimationsData,
     blue_blocks: BlockPoints,
     blue_animations: BlockAnimationsData,
+    /// None for a theme without green art, the blue blocks are tinted green instead
+    green_blocks: Option<BlockPoints>,
+    green_animations: Option<BlockAnimationsData>,
     source_block_size: u32,
     ghost_alpha: u8,
     dr_throw: AnimationSpriteSheetData,
@@ -352,8 +363,8 @@ pub struct VitaminSpriteSheetData {
 }
 
 impl VitaminSpriteSheetData {
-    pub fn new(
-        file: &'static [u8],
+    pub fn new<F: Into<SpriteFile>>(
+        file: F,
         pills: HashMap<PillShape, Rect>,
         pill_size: (u32, u32),
         yellow_blocks: BlockPoints,
@@ -373,7 +384,7 @@ impl VitaminSpriteSheetData {
         yellow_animations.assert_same_frames(&red_animations);
         blue_animations.assert_same_frames(&red_animations);
         Self {
-            file,
+            file: file.into(),
             pills,
             pill_size,
             yellow_blocks,
@@ -382,6 +393,8 @@ impl VitaminSpriteSheetData {
             red_animations,
             blue_blocks,
             blue_animations,
+            green_blocks: None,
+            green_animations: None,
             source_block_size,
             ghost_alpha,
             dr_throw,
@@ -392,23 +405,42 @@ impl VitaminSpriteSheetData {
         }
     }
 
+    /// Green blocks & animations for a theme that has them, only needed in four color mode
+    pub fn with_green(
+        mut self,
+        green_blocks: BlockPoints,
+        green_animations: BlockAnimationsData,
+    ) -> Self {
+        green_animations.assert_same_frames(&self.red_animations);
+        self.green_blocks = Some(green_blocks);
+        self.green_animations = Some(green_animations);
+        self
+    }
+
+    /// Green is copied from blue then tinted when the theme has no green art
+    fn tints_green(&self) -> bool {
+        self.green_blocks.is_none()
+    }
+
     fn points(&self, color: VirusColor) -> &BlockPoints {
         match color {
             VirusColor::Yellow => &self.yellow_blocks,
             VirusColor::Blue => &self.blue_blocks,
             VirusColor::Red => &self.red_blocks,
+            VirusColor::Green => self.green_blocks.as_ref().unwrap_or(&self.blue_blocks),
         }
     }
 
-    fn source_snips(&self, color: VirusColor) -> BlockSnips {
+    fn source_snips(&self, color: VirusColor, resolution: u32) -> BlockSnips {
         let src = self.points(color);
+        let block = |p: Point| snip_at_resolution(self.source_block(p), resolution);
         BlockSnips {
             color,
-            north: src.north.map(|p| self.source_block(p)),
-            east: src.east.map(|p| self.source_block(p)),
-            south: src.south.map(|p| self.source_block(p)),
-            west: src.west.map(|p| self.source_block(p)),
-            garbage: self.source_block(src.garbage),
+            north: src.north.map(block),
+            east: src.east.map(block),
+            south: src.south.map(block),
+            west: src.west.map(block),
+            garbage: block(src.garbage),
             width: 0, // doesnt matter, these are unused for source snips
             height: 0,
         }
@@ -432,9 +464,13 @@ impl VitaminSpriteSheetData {
         }
     }
 
-    fn pill_source_snips(&self) -> PillSnips {
+    fn pill_source_snips(&self, resolution: u32) -> PillSnips {
         PillSnips {
-            shapes: self.pills.clone(),
+            shapes: self
+                .pills
+                .iter()
+                .map(|(shape, snip)| (*shape, snip_at_resolution(*snip, resolution)))
+                .collect(),
             // width and height are not used
             width: 0,
             height: 0,
@@ -445,10 +481,10 @@ impl VitaminSpriteSheetData {
         let mut context = BlockContext::new(0, block_size);
         let (pill_width, pill_height) = self.pill_size;
         PillSnips {
-            shapes: self
-                .pills
+            // pills with green have no source art but still need a target
+            shapes: PillShape::FOUR_COLORS
                 .iter()
-                .map(|(s, _r)| (*s, context.next_sprite(pill_width, pill_height)))
+                .map(|s| (*s, context.next_sprite(pill_width, pill_height)))
                 .collect(),
             width: context.width(),
             height: context.height,
@@ -461,15 +497,16 @@ impl VitaminSpriteSheetData {
         texture_creator: &'a TextureCreator<WindowContext>,
         dr_type: DrType,
     ) -> Result<AnimationSpriteSheet<'a>, String> {
-        let dr = match dr_type {
-            DrType::Throw => self.dr_throw.sprite_sheet(texture_creator),
-            DrType::GameOver => self.dr_game_over.sprite_sheet(texture_creator),
-            DrType::Victory => self.dr_victory.sprite_sheet(texture_creator),
-            DrType::Idle => self.dr_idle.sprite_sheet(texture_creator),
-        }?;
+        let data = match dr_type {
+            DrType::Throw => &self.dr_throw,
+            DrType::GameOver => &self.dr_game_over,
+            DrType::Victory => &self.dr_victory,
+            DrType::Idle => &self.dr_idle,
+        };
+        let (dr, resolution) = data.sprite_sheet(texture_creator, self.dr_scale.unwrap_or(1.0))?;
 
         if let Some(dr_scale) = self.dr_scale {
-            dr.scale_f64(canvas, texture_creator, dr_scale)
+            dr.scale_f64(canvas, texture_creator, dr_scale / resolution as f64)
         } else {
             Ok(dr)
         }
@@ -486,9 +523,10 @@ impl VitaminSpriteSheetData {
             VirusColor::Yellow => &self.yellow_animations,
             VirusColor::Blue => &self.blue_animations,
             VirusColor::Red => &self.red_animations,
+            VirusColor::Green => self.green_animations.as_ref().unwrap_or(&self.blue_animations),
         };
-
-        data.build(canvas, texture_creator, block_size)
+        let scale = block_size as f64 / self.source_block_size as f64;
+        data.build(canvas, texture_creator, block_size, scale)
     }
 }
 
@@ -504,13 +542,13 @@ pub enum DrType {
 fn scale_blocks<'a>(
     canvas: &mut WindowCanvas,
     data: &VitaminSpriteSheetData,
-    src_texture: &Texture<'a>,
+    (src_texture, resolution): (&Texture<'a>, u32),
     target_texture: &mut Texture<'a>,
     target_snips: &BlockSnips,
 ) -> Result<(), String> {
     canvas
         .with_texture_canvas(target_texture, |c| {
-            let src_snips = data.source_snips(target_snips.color);
+            let src_snips = data.source_snips(target_snips.color, resolution);
             for (src, target) in src_snips.flatten().into_iter().zip(target_snips.flatten()) {
                 c.copy(&src_texture, src, target).unwrap();
             }
@@ -521,13 +559,13 @@ fn scale_blocks<'a>(
 fn scale_pills<'a>(
     canvas: &mut WindowCanvas,
     data: &VitaminSpriteSheetData,
-    src_texture: &Texture<'a>,
+    (src_texture, resolution): (&Texture<'a>, u32),
     target_texture: &mut Texture<'a>,
     target_snips: &PillSnips,
 ) -> Result<(), String> {
     canvas
         .with_texture_canvas(target_texture, |c| {
-            let src_snips = data.pill_source_snips();
+            let src_snips = data.pill_source_snips(resolution);
             for shape in PillShape::ALL {
                 c.copy(
                     &src_texture,
@@ -547,9 +585,11 @@ pub struct VitaminSpriteSheet<'a> {
     yellow_blocks: BlockSnips,
     red_blocks: BlockSnips,
     blue_blocks: BlockSnips,
+    green_blocks: BlockSnips,
     yellow_animations: BlockAnimations<'a>,
     red_animations: BlockAnimations<'a>,
     blue_animations: BlockAnimations<'a>,
+    green_animations: BlockAnimations<'a>,
     pills: PillSnips,
     pill_texture: Texture<'a>,
     block_size: u32,
@@ -558,6 +598,7 @@ pub struct VitaminSpriteSheet<'a> {
     yellow_virus_mask: BlockMask,
     red_virus_mask: BlockMask,
     blue_virus_mask: BlockMask,
+    green_virus_mask: BlockMask,
 }
 
 impl<'a> VitaminSpriteSheet<'a> {
@@ -566,9 +607,14 @@ impl<'a> VitaminSpriteSheet<'a> {
         texture_creator: &'a TextureCreator<WindowContext>,
         data: VitaminSpriteSheetData,
         block_size: B,
+        color_blind_patterns: bool,
     ) -> Result<Self, String> {
         let block_size = block_size.into().unwrap_or(data.source_block_size);
-        let sprite_src = texture_creator.load_texture_bytes(data.file)?;
+        let (resolution, file) = data
+            .file
+            .variant(block_size as f64 / data.source_block_size as f64);
+        let sprite_src = texture_creator.load_texture_bytes(file)?;
+        let src = (&sprite_src, resolution);
         let yellow_blocks = data.target_snips(VirusColor::Yellow, 0, block_size);
         let red_blocks =
             data.target_snips(VirusColor::Red, yellow_blocks.height as i32, block_size);
@@ -577,16 +623,20 @@ impl<'a> VitaminSpriteSheet<'a> {
             (yellow_blocks.height + red_blocks.height) as i32,
             block_size,
         );
+        let green_y = yellow_blocks.height + red_blocks.height + blue_blocks.height;
+        let green_blocks = data.target_snips(VirusColor::Green, green_y as i32, block_size);
         let width = yellow_blocks
             .width
             .max(red_blocks.width)
-            .max(blue_blocks.width);
-        let height = yellow_blocks.height + red_blocks.height + blue_blocks.height;
+            .max(blue_blocks.width)
+            .max(green_blocks.width);
+        let height = green_y + green_blocks.height;
         let mut texture = texture_creator.create_texture_target_blended(width, height)?;
 
-        scale_blocks(canvas, &data, &sprite_src, &mut texture, &yellow_blocks)?;
-        scale_blocks(canvas, &data, &sprite_src, &mut texture, &red_blocks)?;
-        scale_blocks(canvas, &data, &sprite_src, &mut texture, &blue_blocks)?;
+        scale_blocks(canvas, &data, src, &mut texture, &yellow_blocks)?;
+        scale_blocks(canvas, &data, src, &mut texture, &red_blocks)?;
+        scale_blocks(canvas, &data, src, &mut texture, &blue_blocks)?;
+        scale_blocks(canvas, &data, src, &mut texture, &green_blocks)?;
 
         let mut yellow_animations =
             data.block_animations(canvas, texture_creator, VirusColor::Yellow, block_size)?;
@@ -594,11 +644,48 @@ impl<'a> VitaminSpriteSheet<'a> {
             data.block_animations(canvas, texture_creator, VirusColor::Red, block_size)?;
         let mut blue_animations =
             data.block_animations(canvas, texture_creator, VirusColor::Blue, block_size)?;
+        let mut green_animations =
+            data.block_animations(canvas, texture_creator, VirusColor::Green, block_size)?;
+        if data.tints_green() {
+            let green_rect = Rect::new(0, green_y as i32, green_blocks.width, green_blocks.height);
+            rotate_texture_hue(canvas, &mut texture, green_rect, BLUE_TO_GREEN)?;
+            for sheet in [
+                &mut green_animations.virus_idle,
+                &mut green_animations.virus_pop,
+                &mut green_animations.vitamin_pop,
+            ] {
+                sheet.rotate_hue(canvas, BLUE_TO_GREEN)?;
+            }
+        }
 
         let garbage_mask = BlockMask::from_texture(canvas, &mut texture, red_blocks.garbage)?;
         let yellow_virus_mask = yellow_animations.virus_idle.block_mask(canvas, 0)?;
         let red_virus_mask = red_animations.virus_idle.block_mask(canvas, 0)?;
         let blue_virus_mask = blue_animations.virus_idle.block_mask(canvas, 0)?;
+        let green_virus_mask = green_animations.virus_idle.block_mask(canvas, 0)?;
+
+        // patterns are drawn after the masks so they do not change the outlines
+        if color_blind_patterns {
+            canvas
+                .with_texture_canvas(&mut texture, |c| {
+                    for blocks in [&yellow_blocks, &red_blocks, &blue_blocks, &green_blocks] {
+                        for snip in blocks.flatten() {
+                            draw_color_pattern(c, blocks.color, snip, block_size).unwrap();
+                        }
+                    }
+                })
+                .map_err(|e| e.to_string())?;
+            for (color, animations) in [
+                (VirusColor::Yellow, &mut yellow_animations),
+                (VirusColor::Red, &mut red_animations),
+                (VirusColor::Blue, &mut blue_animations),
+                (VirusColor::Green, &mut green_animations),
+            ] {
+                animations.virus_idle.overlay_frames(canvas, |c, frame| {
+                    draw_color_pattern(c, color, frame, block_size)
+                })?;
+            }
+        }
 
         let mut alpha_textures = HashMap::new();
         for i in 0..0xff / ALPHA_STRIDE {
@@ -619,7 +706,37 @@ impl<'a> VitaminSpriteSheet<'a> {
         let pills = data.pill_target_snips(block_size);
         let mut pill_texture =
             texture_creator.create_texture_target_blended(pills.width, pills.height)?;
-        scale_pills(canvas, &data, &sprite_src, &mut pill_texture, &pills)?;
+        scale_pills(canvas, &data, src, &mut pill_texture, &pills)?;
+        // no theme has pill art with green, so those are put together from their vitamins
+        canvas
+            .with_texture_canvas(&mut pill_texture, |c| {
+                let snips = [&yellow_blocks, &blue_blocks, &red_blocks, &green_blocks];
+                for shape in PillShape::FOUR_COLORS.into_iter().filter(|s| s.has_green()) {
+                    let dst = pills.snip(shape);
+                    let half_width = dst.width() / 2;
+                    let halves = [
+                        (shape.left_color(), VitaminOrdinal::Left, 0),
+                        (shape.right_color(), VitaminOrdinal::Right, half_width),
+                    ];
+                    for (color, ordinal, x) in halves {
+                        let snip = snips[color as usize].vitamin(Rotation::North, ordinal);
+                        let half = Rect::new(dst.x() + x as i32, dst.y(), half_width, dst.height());
+                        c.copy(&texture, snip, half).unwrap();
+                    }
+                }
+            })
+            .map_err(|e| e.to_string())?;
+        // pills with green were put together from vitamins that already have their patterns
+        if color_blind_patterns {
+            canvas
+                .with_texture_canvas(&mut pill_texture, |c| {
+                    for shape in PillShape::ALL {
+                        let colors = (shape.left_color(), shape.right_color());
+                        draw_pill_pattern(c, colors, pills.snip(shape), block_size).unwrap();
+                    }
+                })
+                .map_err(|e| e.to_string())?;
+        }
 
         let dr_throw = data.dr(canvas, texture_creator, DrType::Throw)?;
         let dr_game_over = data.dr(canvas, texture_creator, DrType::GameOver)?;
@@ -633,9 +750,11 @@ impl<'a> VitaminSpriteSheet<'a> {
             yellow_blocks,
             red_blocks,
             blue_blocks,
+            green_blocks,
             yellow_animations,
             red_animations,
             blue_animations,
+            green_animations,
             pills,
             pill_texture,
             block_size,
@@ -649,6 +768,7 @@ impl<'a> VitaminSpriteSheet<'a> {
             yellow_virus_mask,
             red_virus_mask,
             blue_virus_mask,
+            green_virus_mask,
         })
     }
 
@@ -657,6 +777,7 @@ impl<'a> VitaminSpriteSheet<'a> {
             VirusColor::Yellow => self.yellow_animations.virus_idle.frame_count(),
             VirusColor::Blue => self.blue_animations.virus_idle.frame_count(),
             VirusColor::Red => self.red_animations.virus_idle.frame_count(),
+            VirusColor::Green => self.green_animations.virus_idle.frame_count(),
         }
     }
 
@@ -677,6 +798,7 @@ impl<'a> VitaminSpriteSheet<'a> {
             VirusColor::Yellow => &self.yellow_virus_mask,
             VirusColor::Blue => &self.blue_virus_mask,
             VirusColor::Red => &self.red_virus_mask,
+            VirusColor::Green => &self.green_virus_mask,
         }
     }
 
@@ -690,17 +812,33 @@ impl<'a> VitaminSpriteSheet<'a> {
         dr_type: DrType,
         point: Point,
         frame: usize,
+        scale: f64,
     ) -> Result<(), String> {
-        self.dr_sprites(dr_type).draw_frame(canvas, point, frame)
+        let sprites = self.dr_sprites(dr_type);
+        if scale == 1.0 {
+            return sprites.draw_frame(canvas, point, frame);
+        }
+        // scale about the feet so the dr stays planted in the same spot
+        let (width, height) = sprites.frame_size();
+        let scaled_width = (width as f64 * scale).round() as u32;
+        let scaled_height = (height as f64 * scale).round() as u32;
+        let dest = Rect::new(
+            point.x() - (scaled_width as i32 - width as i32) / 2,
+            point.y() - (scaled_height as i32 - height as i32),
+            scaled_width,
+            scaled_height,
+        );
+        sprites.draw_frame_scaled(canvas, dest, frame)
     }
 
     /// TODO maybe move this into the theme, it deals with animations and what not which is a theme concern
-    pub fn draw_bottle(
+    pub fn draw_bottle<B: BottleBlocks>(
         &self,
         canvas: &mut WindowCanvas,
-        game: &Game,
+        blocks: &B,
         geometry: &BottleGeometry,
         animations: &PlayerAnimations,
+        vitamin_tint: Option<Color>,
     ) -> Result<(), String> {
         if let Some(spawning_viruses) = animations.next_level().state().map(|s| s.display_viruses())
         {
@@ -732,21 +870,24 @@ impl<'a> VitaminSpriteSheet<'a> {
                         frame.offset_y,
                         frame.alpha_mod,
                     )?;
+                    self.tint_vitamin(canvas, dest, frame.offset_y, vitamin_tint)?;
                 }
             }
         }
 
         let lock_animation = animations.lock().state().cloned().unwrap_or_default();
         let lock_offset_y = lock_animation.offset_y();
+        let crawl = animations.crawl().state();
 
-        for j in (0..BOTTLE_HEIGHT).rev() {
-            for (i, block) in game.row(j).iter().copied().enumerate() {
+        for j in (0..blocks.size().height()).rev() {
+            for (i, block) in blocks.row(j).iter().copied().enumerate() {
                 let point = BottlePoint::new(i as i32, j as i32);
                 let dest = geometry.raw_block(point);
                 match block {
                     Block::Empty => {}
                     Block::Vitamin(color, rotation, ordinal) if draw_vitamin => {
-                        self.draw_vitamin(canvas, color, rotation, ordinal, dest, 0.0, None)?
+                        self.draw_vitamin(canvas, color, rotation, ordinal, dest, 0.0, None)?;
+                        self.tint_vitamin(canvas, dest, 0.0, vitamin_tint)?
                     }
                     Block::Stack(color, rotation, ordinal) => {
                         let offset_y = if lock_animation.animates(point) {
@@ -754,16 +895,23 @@ impl<'a> VitaminSpriteSheet<'a> {
                         } else {
                             0.0
                         };
-                        self.draw_vitamin(canvas, color, rotation, ordinal, dest, offset_y, None)?
+                        self.draw_vitamin(canvas, color, rotation, ordinal, dest, offset_y, None)?;
+                        self.tint_vitamin(canvas, dest, offset_y, vitamin_tint)?
                     }
                     Block::Garbage(color) => {
                         canvas.copy(&self.texture, self.snips(color).garbage, dest)?
                     }
-                    Block::Virus(color) => self.animations(color).virus_idle.draw_frame_scaled(
-                        canvas,
-                        dest,
-                        animations.virus().frame(color),
-                    )?,
+                    Block::Virus(color) => {
+                        let dest = match crawl.and_then(|c| c.offset(point)) {
+                            Some((dx, dy)) => self.offset_by_block_ratio(dest, dx, dy),
+                            None => dest,
+                        };
+                        self.animations(color).virus_idle.draw_frame_scaled(
+                            canvas,
+                            dest,
+                            animations.virus().frame(color),
+                        )?
+                    }
                     Block::Ghost(color, rotation, ordinal) if draw_vitamin => self.draw_vitamin(
                         canvas,
                         color,
@@ -778,6 +926,15 @@ impl<'a> VitaminSpriteSheet<'a> {
             }
         }
 
+        if draw_vitamin {
+            for (vitamins, effect) in blocks.power_ups() {
+                for vitamin in vitamins {
+                    let dest = geometry.raw_block(vitamin.position());
+                    draw_power_up(canvas, effect, dest, self.block_size)?;
+                }
+            }
+        }
+
         if let Some(destroyed) = animations.destroy().state() {
             for block in destroyed.blocks() {
                 let animations = self.animations(block.color);
@@ -801,6 +958,27 @@ impl<'a> VitaminSpriteSheet<'a> {
         Ok(())
     }
 
+    /// Draws a virus outside of the bottle e.g. beside its count, dancing with those in the bottle
+    pub fn draw_virus(
+        &self,
+        canvas: &mut WindowCanvas,
+        color: VirusColor,
+        dest: Rect,
+        frame: usize,
+    ) -> Result<(), String> {
+        self.animations(color).virus_idle.draw_frame_scaled(canvas, dest, frame)
+    }
+
+    pub fn draw_virus_pop(
+        &self,
+        canvas: &mut WindowCanvas,
+        color: VirusColor,
+        dest: Rect,
+        frame: usize,
+    ) -> Result<(), String> {
+        self.animations(color).virus_pop.draw_frame_scaled(canvas, dest, frame)
+    }
+
     pub fn draw_pill<A: Into<Option<f64>>, S: Into<Option<f64>>>(
         &self,
         canvas: &mut WindowCanvas,
@@ -815,12 +993,23 @@ impl<'a> VitaminSpriteSheet<'a> {
             dest.scale_f64_mut(scale);
         }
         if let Some(angle) = angle.into() {
+            // thrown pills spin so they go unmarked until they land in the bottle
             canvas.copy_ex(&self.pill_texture, snip, dest, angle, None, false, false)
         } else {
-            canvas.copy(&self.pill_texture, snip, dest)
+            canvas.copy(&self.pill_texture, snip, dest)?;
+            draw_power_up_pill(canvas, shape.effect(), dest, dest.height())
         }
     }
 
+    pub fn draw_garbage(
+        &self,
+        canvas: &mut WindowCanvas,
+        color: VirusColor,
+        dest: Rect,
+    ) -> Result<(), String> {
+        canvas.copy(&self.texture, self.snips(color).garbage, dest)
+    }
+
     pub fn draw_vitamin<A: Into<Option<u8>>>(
         &self,
         canvas: &mut WindowCanvas,
@@ -846,6 +1035,25 @@ impl<'a> VitaminSpriteSheet<'a> {
         }
     }
 
+    /// Multiplies the vitamin drawn at a block by a color, the transparent corners of the block
+    /// stay transparent so nothing else in the bottle is tinted
+    fn tint_vitamin(
+        &self,
+        canvas: &mut WindowCanvas,
+        dest: Rect,
+        offset_y: f64,
+        tint: Option<Color>,
+    ) -> Result<(), String> {
+        if let Some(tint) = tint {
+            let blend_mode = canvas.blend_mode();
+            canvas.set_blend_mode(BlendMode::Mod);
+            canvas.set_draw_color(tint);
+            canvas.fill_rect(self.offset_by_block_ratio(dest, 0.0, offset_y))?;
+            canvas.set_blend_mode(blend_mode);
+        }
+        Ok(())
+    }
+
     fn offset_by_block_ratio(&self, rect: Rect, offset_x: f64, offset_y: f64) -> Rect {
         let block_size = self.block_size as f64;
         Rect::new(
@@ -861,6 +1069,7 @@ impl<'a> VitaminSpriteSheet<'a> {
             VirusColor::Yellow => &self.yellow_blocks,
             VirusColor::Blue => &self.blue_blocks,
             VirusColor::Red => &self.red_blocks,
+            VirusColor::Green => &self.green_blocks,
         }
     }
 
@@ -869,6 +1078,7 @@ impl<'a> VitaminSpriteSheet<'a> {
             VirusColor::Yellow => &self.yellow_animations,
             VirusColor::Blue => &self.blue_animations,
             VirusColor::Red => &self.red_animations,
+            VirusColor::Green => &self.green_animations,
         }
     }
 
@@ -907,6 +1117,9 @@ impl<'a> VitaminSpriteSheet<'a> {
             yellow_viruses: self.yellow_animations
                 .virus_idle
                 .clone(canvas, texture_creator)?,
+            green_viruses: self.green_animations
+                .virus_idle
+                .clone(canvas, texture_creator)?,
             dr_animations: self.dr_animations.clone(canvas, texture_creator)?,
         })
     }
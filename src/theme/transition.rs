@@ -0,0 +1,164 @@
+use sdl2::pixels::PixelFormatEnum::RGBA8888;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
+use sdl2::video::WindowContext;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const TRANSITION_DURATION: Duration = Duration::from_millis(1000);
+const MOSAIC_ROWS: u32 = 12;
+const MAX_PIXELATE_BLOCK: f64 = 48.0;
+
+/// Effect used to move from one theme to the next when switching themes every level
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeTransition {
+    /// The previous theme fades out over the next
+    #[default]
+    Fade,
+    /// The previous theme slides off to the left
+    Slide,
+    /// The previous theme falls away in tiles
+    Mosaic,
+    /// The previous theme breaks down into ever larger pixels as it fades out
+    Pixelate,
+}
+
+/// A screenshot of the previous theme drawn over the next until the transition completes
+pub struct Transition<'a> {
+    effect: ThemeTransition,
+    previous: Texture<'a>,
+    /// render target the previous theme is downscaled into to pixelate it
+    scratch: Texture<'a>,
+    window_size: (u32, u32),
+    duration: Option<Duration>,
+}
+
+impl<'a> Transition<'a> {
+    pub fn new(
+        texture_creator: &'a TextureCreator<WindowContext>,
+        window_size: (u32, u32),
+        effect: ThemeTransition,
+    ) -> Result<Self, String> {
+        let (window_width, window_height) = window_size;
+        let mut previous = texture_creator
+            .create_texture_target(RGBA8888, window_width, window_height)
+            .map_err(|e| e.to_string())?;
+        previous.set_blend_mode(BlendMode::Blend);
+        let mut scratch = texture_creator
+            .create_texture_target(RGBA8888, window_width, window_height)
+            .map_err(|e| e.to_string())?;
+        scratch.set_blend_mode(BlendMode::Blend);
+        Ok(Self {
+            effect,
+            previous,
+            scratch,
+            window_size,
+            duration: None,
+        })
+    }
+
+    /// Captures the screen as the previous theme, it is drawn over the next from now on
+    pub fn start(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
+        self.duration = Some(Duration::ZERO);
+
+        let query = self.previous.query();
+        let pixels = canvas.read_pixels(None, query.format)?;
+        self.previous
+            .update(
+                None,
+                pixels.as_slice(),
+                query.format.byte_size_per_pixel() * query.width as usize,
+            )
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.duration.is_some()
+    }
+
+    pub fn draw(&mut self, canvas: &mut WindowCanvas, delta: Duration) -> Result<(), String> {
+        let duration = match self.duration {
+            None => return Ok(()),
+            Some(duration) => duration + delta,
+        };
+        if duration > TRANSITION_DURATION {
+            self.duration = None;
+            return Ok(());
+        }
+        self.duration = Some(duration);
+
+        let progress = duration.as_secs_f64() / TRANSITION_DURATION.as_secs_f64();
+        let alpha = (255.0 * (1.0 - progress)) as u8;
+        let (window_width, window_height) = self.window_size;
+        match self.effect {
+            ThemeTransition::Fade => {
+                self.previous.set_alpha_mod(alpha);
+                canvas.copy(&self.previous, None, None)?;
+            }
+            ThemeTransition::Slide => {
+                // ease in & out so the slide starts and lands gently
+                let eased = progress * progress * (3.0 - 2.0 * progress);
+                let x = -(eased * window_width as f64) as i32;
+                self.previous.set_alpha_mod(u8::MAX);
+                canvas.copy(
+                    &self.previous,
+                    None,
+                    Rect::new(x, 0, window_width, window_height),
+                )?;
+            }
+            ThemeTransition::Mosaic => {
+                self.previous.set_alpha_mod(u8::MAX);
+                for tile in mosaic_tiles(self.window_size, progress) {
+                    canvas.copy(&self.previous, tile, tile)?;
+                }
+            }
+            ThemeTransition::Pixelate => {
+                let block = 1.0 + progress * (MAX_PIXELATE_BLOCK - 1.0);
+                let width = (window_width as f64 / block).ceil().max(1.0) as u32;
+                let height = (window_height as f64 / block).ceil().max(1.0) as u32;
+                let small = Rect::new(0, 0, width, height);
+                self.previous.set_alpha_mod(u8::MAX);
+                let previous = &self.previous;
+                canvas
+                    .with_texture_canvas(&mut self.scratch, |c| {
+                        c.copy(previous, None, small).unwrap();
+                    })
+                    .map_err(|e| e.to_string())?;
+                // textures scale nearest neighbour, so scaling back up shows the blocks
+                self.scratch.set_alpha_mod(alpha);
+                canvas.copy(&self.scratch, small, None)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tiles of the window that still show the previous theme, each falls away at its own time
+fn mosaic_tiles((window_width, window_height): (u32, u32), progress: f64) -> Vec<Rect> {
+    let size = (window_height / MOSAIC_ROWS).max(1);
+    let columns = window_width.div_ceil(size);
+    let rows = window_height.div_ceil(size);
+    (0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (column, row)))
+        .filter(|&(column, row)| {
+            // a cheap hash scatters the tiles, they fall away in a fixed but random looking order
+            let hash = (column * 7919 + row * 104729) % 97;
+            hash as f64 / 97.0 >= progress
+        })
+        .map(|(column, row)| Rect::new((column * size) as i32, (row * size) as i32, size, size))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mosaic_tiles_fall_away() {
+        let window_size = (160, 120);
+        assert_eq!(mosaic_tiles(window_size, 0.0).len(), 16 * 12);
+        let half = mosaic_tiles(window_size, 0.5).len();
+        assert!(half > 0 && half < 16 * 12);
+        assert!(mosaic_tiles(window_size, 1.0).is_empty());
+    }
+}
@@ -1,24 +1,34 @@
-use crate::scale::Scale;
+use crate::scale::{Layout, Scale};
 use crate::theme::all::AllThemes;
+use crate::theme::transition::{ThemeTransition, Transition};
 use crate::theme::Theme;
 use sdl2::rect::{Point, Rect};
 use sdl2::render::{BlendMode, Texture, TextureCreator, WindowCanvas};
 
 use crate::animate::event::AnimationEvent;
 use crate::animate::PlayerAnimations;
-use crate::game::event::ColoredBlock;
+use crate::game::bottle::BottleSize;
+use crate::game::event::{ColoredBlock, VirusCrawl};
 use crate::game::geometry::BottlePoint;
-use crate::game::pill::{PillShape, Vitamins};
-use crate::game::rules::{GameConfig, MatchThemes};
-use crate::game::GameSpeed;
+use crate::game::pill::{PillShape, VirusColor, Vitamins};
+use crate::game::rules::{GameConfig, MusicTrack, ThemeOrder, MAX_MATCH_PLAYERS};
+use crate::game::{GameSpeed, NextLevelPreview};
 use crate::player::MatchState;
 
 use sdl2::video::WindowContext;
 use std::time::Duration;
 use sdl2::pixels::PixelFormatEnum::RGBA8888;
-use crate::config::VideoConfig;
-
-const THEME_FADE_DURATION: Duration = Duration::from_millis(1000);
+use crate::config::{AccessibilityConfig, VideoConfig};
+use sdl2::pixels::Color;
+
+const MATCH_END_ZOOM_DURATION: Duration = Duration::from_millis(1500);
+/// height of the zoomed view relative to the focused bottle
+const MATCH_END_ZOOM_PADDING: f64 = 1.3;
+/// Slight blue accent for player 1 & red for player 2, multiplied over their background
+const PLAYER_TINTS: [Color; MAX_MATCH_PLAYERS] = [
+    Color::RGB(0xd0, 0xdc, 0xff),
+    Color::RGB(0xff, 0xd0, 0xd0),
+];
 
 pub struct PlayerTextures<'a> {
     pub background: Texture<'a>,
@@ -45,6 +55,11 @@ impl<'a> PlayerTextures<'a> {
 
         Ok(Self { background, bottle })
     }
+
+    pub fn set_player_tint(&mut self, player: u32) {
+        let Color { r, g, b, .. } = PLAYER_TINTS[player as usize];
+        self.background.set_color_mod(r, g, b);
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -62,21 +77,35 @@ struct ThemedPlayer {
 }
 
 impl ThemedPlayer {
-    pub fn new(player: u32, theme: &Theme, scale: Scale) -> Self {
-        let (theme_width, theme_height) = theme.background_size();
-        let mut bg_snip = scale.scale_rect(Rect::new(0, 0, theme_width, theme_height));
-        bg_snip.center_on(scale.player_window(player).center());
-        let bottle_snip =
+    pub fn new(player: u32, theme: &Theme, scale: Scale, shared_bg: Option<Rect>) -> Self {
+        let mut result = Self {
+            bg_snip: Rect::new(0, 0, 1, 1),
+            bottle_snip: Rect::new(0, 0, 1, 1),
+            game_snip: Rect::new(0, 0, 1, 1),
+            animations: PlayerAnimations::new(player, theme),
+        };
+        result.rescale(player, theme, scale, shared_bg);
+        result
+    }
+
+    /// Moves the player to a new scale, e.g. when the window is resized, keeping the animations
+    fn rescale(&mut self, player: u32, theme: &Theme, scale: Scale, shared_bg: Option<Rect>) {
+        let bg_snip = match (shared_bg, theme.background_panel(player)) {
+            (Some(shared_bg), Some(panel)) => {
+                scale.scale_and_offset_rect(panel, shared_bg.x(), shared_bg.y())
+            }
+            _ => {
+                let (theme_width, theme_height) = theme.background_size();
+                let mut bg_snip = scale.scale_rect(Rect::new(0, 0, theme_width, theme_height));
+                bg_snip.center_on(scale.player_window(player).center());
+                bg_snip
+            }
+        };
+        self.bottle_snip =
             scale.scale_and_offset_rect(theme.bottle_snip(), bg_snip.x(), bg_snip.y());
-        let game_snip =
+        self.game_snip =
             scale.scale_and_offset_rect(theme.geometry().game_snip(), bg_snip.x(), bg_snip.y());
-        let animations = PlayerAnimations::new(player, theme);
-        Self {
-            bg_snip,
-            bottle_snip,
-            game_snip,
-            animations,
-        }
+        self.bg_snip = bg_snip;
     }
 
     pub fn update_animations(&mut self, delta: Duration) -> Vec<AnimationEvent> {
@@ -89,33 +118,89 @@ pub struct ScaledTheme<'a> {
     bg_source_snip: Rect,
     bottle_source_snip: Rect,
     player_themes: Vec<ThemedPlayer>,
+    /// source & destination snips of a shared background, drawn before the players
+    shared_bg_snips: Vec<(Rect, Rect)>,
     scale: Scale,
 }
 
 impl<'a> ScaledTheme<'a> {
     fn new(theme: &'a Theme, players: u32, window_size: (u32, u32), video_config: VideoConfig) -> Self {
-        let scale = Scale::new(
-            players,
-            theme.background_size(),
-            window_size,
-            theme.geometry().block_size(),
-            video_config,
-            theme.name()
-        );
+        let scale = Self::scale(theme, players, window_size, video_config);
         let (theme_width, theme_height) = theme.background_size();
         let bg_source_snip = Rect::new(0, 0, theme_width, theme_height);
         let bottle_rect = theme.bottle_snip();
         let bottle_source_snip = Rect::new(0, 0, bottle_rect.width(), bottle_rect.height());
+        let shared_bg = Self::shared_bg_snip(theme, players, scale);
         let player_themes = (0..players)
-            .map(|pid| ThemedPlayer::new(pid, theme, scale))
+            .map(|pid| ThemedPlayer::new(pid, theme, scale, shared_bg.map(|(_, dst)| dst)))
             .collect::<Vec<ThemedPlayer>>();
-        Self {
+        let mut result = Self {
             theme,
             bg_source_snip,
             bottle_source_snip,
             player_themes,
+            shared_bg_snips: vec![],
             scale,
+        };
+        result.shared_bg_snips = result.shared_bg_snips(shared_bg);
+        result
+    }
+
+    /// Source & destination of the shared background when it has a panel for every player
+    fn shared_bg_snip(theme: &Theme, players: u32, scale: Scale) -> Option<(Rect, Rect)> {
+        let (width, height) = theme.shared_background_size(players)?;
+        let src = Rect::new(0, 0, width, height);
+        let mut dst = scale.scale_rect(src);
+        dst.center_on(scale.player_window(0).center());
+        Some((src, dst))
+    }
+
+    /// The whole shared background or when there are fewer players than panels, e.g. in single
+    /// player, each player is drawn over a copy of their own panel
+    fn shared_bg_snips(&self, shared_bg: Option<(Rect, Rect)>) -> Vec<(Rect, Rect)> {
+        if let Some(shared_bg) = shared_bg {
+            return vec![shared_bg];
         }
+        self.player_themes
+            .iter()
+            .enumerate()
+            .filter_map(|(player, themed)| {
+                self.theme
+                    .background_panel(player as u32)
+                    .map(|panel| (panel, themed.bg_snip))
+            })
+            .collect()
+    }
+
+    fn scale(
+        theme: &Theme,
+        players: u32,
+        window_size: (u32, u32),
+        video_config: VideoConfig,
+    ) -> Scale {
+        // a shared background is scaled as one player spanning the window
+        let (players, background_size) = match theme.shared_background_size(players) {
+            Some(size) => (1, size),
+            None => (players, theme.background_size()),
+        };
+        Scale::new(
+            players,
+            background_size,
+            window_size,
+            theme.geometry().block_size(),
+            video_config,
+            theme.name()
+        )
+    }
+
+    fn rescale(&mut self, window_size: (u32, u32), video_config: VideoConfig) {
+        let players = self.player_themes.len() as u32;
+        self.scale = Self::scale(self.theme, players, window_size, video_config);
+        let shared_bg = Self::shared_bg_snip(self.theme, players, self.scale);
+        for (player, themed) in self.player_themes.iter_mut().enumerate() {
+            themed.rescale(player as u32, self.theme, self.scale, shared_bg.map(|(_, dst)| dst));
+        }
+        self.shared_bg_snips = self.shared_bg_snips(shared_bg);
     }
 
     pub fn update_animations(&mut self, delta: Duration) -> Vec<AnimationEvent> {
@@ -154,11 +239,51 @@ impl<'a> ScaledTheme<'a> {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+struct CameraZoom {
+    player: u32,
+    duration: Duration,
+}
+
+impl CameraZoom {
+    fn new(player: u32) -> Self {
+        Self {
+            player,
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// eased progress of the zoom from 0.0 to 1.0
+    fn progress(&self) -> f64 {
+        let t = (self.duration.as_secs_f64() / MATCH_END_ZOOM_DURATION.as_secs_f64()).min(1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+/// Reduced motion keeps to a plain fade between themes
+fn transition_effect(
+    video_config: VideoConfig,
+    accessibility: AccessibilityConfig,
+) -> ThemeTransition {
+    if accessibility.reduced_motion {
+        ThemeTransition::Fade
+    } else {
+        video_config.theme_transition
+    }
+}
+
 pub struct ThemeContext<'a> {
     current: usize,
     themes: Vec<ScaledTheme<'a>>,
-    fade_buffer: Texture<'a>,
-    fade_duration: Option<Duration>,
+    theme_order: ThemeOrder,
+    theme_sequence: Vec<usize>,
+    transition: Transition<'a>,
+    window_size: (u32, u32),
+    match_end_zoom: bool,
+    accessibility: AccessibilityConfig,
+    zoom: Option<CameraZoom>,
+    /// blocks are fitted into the classic bottle of every theme
+    bottle_size: BottleSize,
 }
 
 impl<'a> ThemeContext<'a> {
@@ -167,34 +292,66 @@ impl<'a> ThemeContext<'a> {
         texture_creator: &'a TextureCreator<WindowContext>,
         game_config: GameConfig,
         window_size: (u32, u32),
-        video_config: VideoConfig
+        video_config: VideoConfig,
+        theme_sequence: Vec<usize>,
+        accessibility: AccessibilityConfig,
     ) -> Result<Self, String> {
-        let (window_width, window_height) = window_size;
-
-        let mut fade_buffer = texture_creator
-            .create_texture_target(RGBA8888, window_width, window_height)
-            .map_err(|e| e.to_string())?;
-        fade_buffer.set_blend_mode(BlendMode::Blend);
-
-        let current = match game_config.themes() {
-            MatchThemes::All | MatchThemes::Nes => 0,
-            MatchThemes::Snes => 1,
-            MatchThemes::N64 => 2,
-            MatchThemes::Particle => 3,
-        };
+        let mut themes = all_themes
+            .all()
+            .iter()
+            .map(|theme| ScaledTheme::new(theme, game_config.players(), window_size, video_config))
+            .collect::<Vec<_>>();
+        for theme in themes.iter_mut() {
+            for player in theme.player_themes.iter_mut() {
+                player
+                    .animations
+                    .impact_mut()
+                    .set_intensity(accessibility.screen_shake());
+            }
+        }
+        let current = game_config.themes().index().unwrap_or_else(|| {
+            game_config
+                .theme_order()
+                .first(themes.len(), &theme_sequence)
+        });
 
         Ok(Self {
             current,
-            themes: all_themes
-                .all()
-                .iter()
-                .map(|theme| ScaledTheme::new(theme, game_config.players(), window_size, video_config))
-                .collect(),
-            fade_buffer,
-            fade_duration: None,
+            themes,
+            theme_order: game_config.theme_order(),
+            theme_sequence,
+            transition: Transition::new(
+                texture_creator,
+                window_size,
+                transition_effect(video_config, accessibility),
+            )?,
+            window_size,
+            match_end_zoom: video_config.match_end_zoom && !accessibility.reduced_motion,
+            accessibility,
+            zoom: None,
+            bottle_size: game_config.bottle_size(),
         })
     }
 
+    /// Rescales every theme to a resized window, animations carry on from where they were
+    pub fn resize(
+        &mut self,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        window_size: (u32, u32),
+        video_config: VideoConfig,
+    ) -> Result<(), String> {
+        self.transition = Transition::new(
+            texture_creator,
+            window_size,
+            transition_effect(video_config, self.accessibility),
+        )?;
+        self.window_size = window_size;
+        for theme in self.themes.iter_mut() {
+            theme.rescale(window_size, video_config);
+        }
+        Ok(())
+    }
+
     pub fn max_background_size(&self) -> (u32, u32) {
         let sizes = self
             .themes
@@ -221,6 +378,57 @@ impl<'a> ThemeContext<'a> {
         theme.player_themes.get(player as usize).unwrap().game_snip
     }
 
+    pub fn player_background_snip(&self, player: u32) -> Rect {
+        let theme = &self.themes[self.current];
+        theme.player_themes.get(player as usize).unwrap().bg_snip
+    }
+
+    /// Where the level stats are shown, over the bottle unless there is space beside the background
+    pub fn player_stats_snip(&self, player: u32) -> Rect {
+        let theme = self.current();
+        let themed = &theme.player_themes[player as usize];
+        let players = theme.player_themes.len() as u32;
+        if theme.scale.layout() != Layout::Ultrawide
+            || theme.theme.shared_background_size(players).is_some()
+        {
+            return themed.game_snip;
+        }
+        let window = theme.scale.player_window(player);
+        let left_space = themed.bg_snip.left() - window.left();
+        let right_space = window.right() - themed.bg_snip.right();
+        let (space, space_x) = if left_space > right_space {
+            (left_space, window.left())
+        } else {
+            (right_space, themed.bg_snip.right())
+        };
+        let width = themed.game_snip.width() as i32;
+        if space < width {
+            return themed.game_snip;
+        }
+        Rect::new(
+            space_x + (space - width) / 2,
+            themed.game_snip.y(),
+            themed.game_snip.width(),
+            themed.game_snip.height(),
+        )
+    }
+
+    /// Draws the next bottle of a player between levels, seeded virus by virus
+    pub fn draw_next_level_preview(
+        &self,
+        canvas: &mut WindowCanvas,
+        player: u32,
+        dest: Rect,
+    ) -> Result<(), String> {
+        let animations = self.player_animations(player);
+        match animations.next_level_interstitial().state() {
+            Some(state) => self
+                .theme()
+                .draw_next_level_preview(canvas, dest, state, animations),
+            None => Ok(()),
+        }
+    }
+
     pub fn player_animations(&self, player: u32) -> &PlayerAnimations {
         &self.current().player_themes[player as usize].animations
     }
@@ -251,12 +459,63 @@ impl<'a> ThemeContext<'a> {
         }
     }
 
+    /// Viruses in the lounge wince when viruses of their color are cleared
+    pub fn animate_lounge_wince(&mut self, player: u32, colors: &[VirusColor]) {
+        for theme in self.themes.iter_mut() {
+            let lounge = theme.animations_mut(player).lounge_mut();
+            for &color in colors {
+                lounge.wince(color);
+            }
+        }
+    }
+
+    pub fn animate_lounge_eliminate(&mut self, player: u32, color: VirusColor) {
+        for theme in self.themes.iter_mut() {
+            theme.animations_mut(player).lounge_mut().eliminate(color);
+        }
+    }
+
+    pub fn animate_virus_crawl(&mut self, player: u32, crawls: Vec<VirusCrawl>) {
+        for theme in self.themes.iter_mut() {
+            theme
+                .animations_mut(player)
+                .crawl_mut()
+                .crawl(crawls.clone());
+        }
+    }
+
     pub fn animate_impact(&mut self, player: u32) {
         for theme in self.themes.iter_mut() {
             theme.animations_mut(player).impact_mut().impact();
         }
     }
 
+    pub fn animate_chain(&mut self, player: u32) {
+        for theme in self.themes.iter_mut() {
+            theme.animations_mut(player).chain_mut().chain();
+        }
+    }
+
+    /// Pops the score of a combo up from the middle of the blocks it cleared
+    pub fn animate_score_popup(&mut self, player: u32, score: u32, cleared: &[BottlePoint]) {
+        if cleared.is_empty() {
+            return;
+        }
+        let count = cleared.len() as i32;
+        for theme in self.themes.iter_mut() {
+            let geometry = theme.theme.geometry().fit(self.bottle_size);
+            let half_block = geometry.block_size() as i32 / 2;
+            let sum = cleared
+                .iter()
+                .fold(Point::new(0, 0), |sum, &p| sum + geometry.point(p));
+            let from = Point::new(sum.x() / count, sum.y() / count).offset(half_block, half_block);
+            theme
+                .animations_mut(player)
+                .score_popup_mut()
+                .popup(score, from);
+        }
+    }
+
     pub fn animate_lock(&mut self, player: u32, vitamins: Vitamins) {
         for theme in self.themes.iter_mut() {
             theme.animations_mut(player).lock_mut().lock(vitamins);
@@ -281,24 +540,58 @@ impl<'a> ThemeContext<'a> {
         }
     }
 
+    pub fn animate_hold(
+        &mut self,
+        player: u32,
+        shape: PillShape,
+        vitamins: Vitamins,
+        released: Option<PillShape>,
+    ) {
+        // held from the top left of the pill, in the background of each theme
+        let x = vitamins.iter().map(|v| v.position().x()).min().unwrap();
+        let y = vitamins.iter().map(|v| v.position().y()).min().unwrap();
+        for theme in self.themes.iter_mut() {
+            let from = theme.theme.geometry().fit(self.bottle_size).point((x, y))
+                + theme.theme.bottle_snip().top_left();
+            theme
+                .animations_mut(player)
+                .hold_mut()
+                .hold(shape, from, released);
+        }
+    }
+
+    /// Drops in flight animations as the bottle is restored to an earlier state
+    pub fn animate_rewind(&mut self, player: u32) {
+        for theme in self.themes.iter_mut() {
+            theme.animations_mut(player).reset();
+        }
+    }
+
     pub fn animate_game_over(&mut self, player: u32) {
         for theme in self.themes.iter_mut() {
             theme.animations_mut(player).game_over_mut().game_over();
         }
+        // the winner takes precedence over the losers
+        if self.match_end_zoom && self.zoom.is_none() {
+            self.zoom = Some(CameraZoom::new(player));
+        }
     }
 
     pub fn animate_victory(&mut self, player: u32) {
         for theme in self.themes.iter_mut() {
             theme.animations_mut(player).victory_mut().victory();
         }
+        if self.match_end_zoom {
+            self.zoom = Some(CameraZoom::new(player));
+        }
     }
 
-    pub fn animate_next_level_interstitial(&mut self, player: u32) {
+    pub fn animate_next_level_interstitial(&mut self, player: u32, preview: &NextLevelPreview) {
         for theme in self.themes.iter_mut() {
             theme
                 .animations_mut(player)
                 .next_level_interstitial_mut()
-                .display();
+                .display(preview.clone());
         }
     }
 
@@ -375,15 +668,18 @@ impl<'a> ThemeContext<'a> {
         canvas: &mut WindowCanvas,
         match_state: MatchState,
         is_single_player: bool,
+        music: Option<MusicTrack>,
     ) -> Result<(), String> {
         for theme in self.themes.iter_mut() {
             for player in theme.player_themes.iter_mut() {
                 player.animations.reset();
             }
         }
-        self.current = (self.current + 1) % self.themes.len();
+        self.current = self
+            .theme_order
+            .next(self.current, self.themes.len(), &self.theme_sequence);
 
-        self.start_fade(canvas)?;
+        self.transition.start(canvas)?;
 
         // handle music
         let audio = self.theme().audio();
@@ -391,9 +687,9 @@ impl<'a> ThemeContext<'a> {
             MatchState::Normal if self.is_animating_next_level_interstitial() => {
                 audio.play_next_level_music()?
             }
-            MatchState::Normal => audio.fade_in_game_music()?,
-            MatchState::Paused => {
-                audio.play_game_music()?;
+            MatchState::Normal | MatchState::Overtime { .. } => audio.fade_in_game_music(music)?,
+            MatchState::Paused | MatchState::Resuming { .. } => {
+                audio.play_game_music(music)?;
                 audio.pause_music();
             }
             MatchState::GameOver { .. } => {
@@ -408,22 +704,12 @@ impl<'a> ThemeContext<'a> {
         Ok(())
     }
 
-    fn start_fade(&mut self, canvas: &mut WindowCanvas) -> Result<(), String> {
-        self.fade_duration = Some(Duration::ZERO);
-
-        let query = self.fade_buffer.query();
-        let pixels = canvas.read_pixels(None, query.format)?;
-        self.fade_buffer
-            .update(
-                None,
-                pixels.as_slice(),
-                query.format.byte_size_per_pixel() * query.width as usize,
-            )
-            .map_err(|e| e.to_string())
+    pub fn is_fading(&self) -> bool {
+        self.transition.is_running()
     }
 
-    pub fn is_fading(&self) -> bool {
-        self.fade_duration.is_some()
+    pub fn letterbox_bars(&self) -> Vec<Rect> {
+        self.current().scale.letterbox_bars()
     }
 
     pub fn draw_scene(&self, canvas: &mut WindowCanvas, speed: GameSpeed) -> Result<(), String> {
@@ -437,12 +723,22 @@ impl<'a> ThemeContext<'a> {
         texture_refs: &mut [(&mut Texture, TextureMode)],
         delta: Duration,
     ) -> Result<(), String> {
+        if let Some(zoom) = self.zoom.as_mut() {
+            zoom.duration = (zoom.duration + delta).min(MATCH_END_ZOOM_DURATION);
+        }
+        let camera = self.camera_view();
+
         let current = self.current();
+        for (src, dst) in current.shared_bg_snips.iter() {
+            let dst = self.camera_transform(camera, *dst);
+            current.theme.draw_shared_background(canvas, *src, dst)?;
+        }
         for (texture, texture_mode) in texture_refs.iter_mut() {
             match texture_mode {
                 TextureMode::Background(pid) => {
                     let player = &current.player_themes[*pid as usize];
-                    canvas.copy(texture, current.bg_source_snip, player.bg_snip)?;
+                    let dst = self.camera_transform(camera, player.bg_snip);
+                    canvas.copy(texture, current.bg_source_snip, dst)?;
                 }
                 TextureMode::Bottle(pid) => {
                     let player = &current.player_themes[*pid as usize];
@@ -452,35 +748,63 @@ impl<'a> ThemeContext<'a> {
                         offset_x,
                         offset_y,
                     );
+                    let dst = self.camera_transform(camera, dst);
                     canvas.copy(texture, current.bottle_source_snip, dst)?;
                 }
             }
         }
 
-        // check if we should be fading out the previous theme
-        match self.fade_duration {
-            None => {}
-            Some(duration) => {
-                let duration = duration + delta;
-                if duration > THEME_FADE_DURATION {
-                    self.fade_duration = None;
-                } else {
-                    let alpha = 255.0 * duration.as_millis() as f64
-                        / THEME_FADE_DURATION.as_millis() as f64;
-                    self.fade_buffer.set_alpha_mod(255 - alpha as u8);
-                    canvas.copy(&self.fade_buffer, None, None)?;
-                    self.fade_duration = Some(duration);
-                }
-            }
-        }
+        // transition out of the previous theme
+        self.transition.draw(canvas, delta)
+    }
 
-        Ok(())
+    /// Visible region of the window while zooming toward a player at the end of a match
+    fn camera_view(&self) -> Option<Rect> {
+        let zoom = self.zoom?;
+        let (window_width, window_height) = self.window_size;
+        let (window_width, window_height) = (window_width as f64, window_height as f64);
+        let target = self.current().player_themes[zoom.player as usize].bottle_snip;
+
+        // keep the aspect ratio of the window, never zoom out past it
+        let aspect_ratio = window_width / window_height;
+        let mut height = (target.height() as f64 * MATCH_END_ZOOM_PADDING).min(window_height);
+        let mut width = height * aspect_ratio;
+        if width > window_width {
+            width = window_width;
+            height = width / aspect_ratio;
+        }
+        let center = target.center();
+        let x = (center.x() as f64 - width / 2.0).clamp(0.0, window_width - width);
+        let y = (center.y() as f64 - height / 2.0).clamp(0.0, window_height - height);
+
+        let t = zoom.progress();
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        Some(Rect::new(
+            lerp(0.0, x).round() as i32,
+            lerp(0.0, y).round() as i32,
+            lerp(window_width, width).round() as u32,
+            lerp(window_height, height).round() as u32,
+        ))
+    }
+
+    fn camera_transform(&self, camera: Option<Rect>, rect: Rect) -> Rect {
+        let camera = match camera {
+            None => return rect,
+            Some(camera) => camera,
+        };
+        let scale = self.window_size.0 as f64 / camera.width() as f64;
+        Rect::new(
+            ((rect.x() - camera.x()) as f64 * scale).round() as i32,
+            ((rect.y() - camera.y()) as f64 * scale).round() as i32,
+            (rect.width() as f64 * scale).round() as u32,
+            (rect.height() as f64 * scale).round() as u32,
+        )
     }
 
     pub fn player_block_snips(&self, player: u32, points: Vec<BottlePoint>) -> Vec<Rect> {
         let theme = &self.themes[self.current];
         let player = &theme.player_themes[player as usize];
-        let geometry = theme.theme.geometry();
+        let geometry = theme.theme.geometry().fit(self.bottle_size);
         points
             .into_iter()
             .map(|p| geometry.raw_block(p))
@@ -500,7 +824,7 @@ impl<'a> ThemeContext<'a> {
     ) -> Vec<Point> {
         let theme = &self.themes[self.current];
         let player = &theme.player_themes[player as usize];
-        let geometry = theme.theme.geometry();
+        let geometry = theme.theme.geometry().fit(self.bottle_size);
         let sprites = theme.theme.sprites();
 
         blocks
@@ -526,7 +850,7 @@ impl<'a> ThemeContext<'a> {
     pub fn player_vitamin_snips(&self, player: u32, vitamins: Vitamins) -> [Rect; 2] {
         let theme = &self.themes[self.current];
         let player = &theme.player_themes[player as usize];
-        let geometry = theme.theme.geometry();
+        let geometry = theme.theme.geometry().fit(self.bottle_size);
         vitamins.map(|v| geometry.raw_block(v.position())).map(|r| {
             theme
                 .scale
@@ -534,7 +858,7 @@ impl<'a> ThemeContext<'a> {
         })
     }
 
-    pub fn render_scene_particles(&self) -> bool {
-        self.current().theme.scene(GameSpeed::Low).is_particles()
+    pub fn render_scene_particles(&self, speed: GameSpeed) -> bool {
+        self.current().theme.scene(speed).is_particles()
     }
 }
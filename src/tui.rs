@@ -0,0 +1,319 @@
+//! A single player game in the terminal, bypassing SDL entirely.
+//! The bottle is drawn by the same renderer trait as the browser build, two pixels per character
+//! with unicode half blocks in 24-bit color, using plain ANSI escapes & a raw mode terminal.
+
+use crate::game::event::GameEvent;
+use crate::game::rules::GameConfig;
+use crate::game_input::GameInputKey;
+use crate::player::Match;
+use dr_rustario_core::render::{draw_flat_scene, Rect, Renderer, Rgb};
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
+
+const FRAME: Duration = Duration::from_nanos(16_666_667);
+/// Terminals never report key releases, so soft drop is held for as long as the key is repeating
+const SOFT_DROP_RELEASE: Duration = Duration::from_millis(150);
+const HALF_BLOCK: char = '▀';
+const ESCAPE: u8 = 0x1b;
+const CTRL_C: u8 = 0x03;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum TerminalKey {
+    Game(GameInputKey),
+    Quit,
+}
+
+/// Arrow keys move & drop, Z & X rotate, C holds, P pauses and Q or escape quits
+fn parse_keys(bytes: &[u8]) -> Vec<TerminalKey> {
+    let player = 0;
+    let mut keys = vec![];
+    let mut index = 0;
+    while index < bytes.len() {
+        let key = match bytes[index] {
+            ESCAPE if bytes.get(index + 1) == Some(&b'[') && index + 2 < bytes.len() => {
+                index += 2;
+                match bytes[index] {
+                    b'A' => Some(GameInputKey::HardDrop { player }),
+                    b'B' => Some(GameInputKey::SoftDrop { player }),
+                    b'C' => Some(GameInputKey::MoveRight { player }),
+                    b'D' => Some(GameInputKey::MoveLeft { player }),
+                    _ => None,
+                }
+                .map(TerminalKey::Game)
+            }
+            ESCAPE | CTRL_C | b'q' | b'Q' => Some(TerminalKey::Quit),
+            b'z' | b'Z' => Some(TerminalKey::Game(GameInputKey::RotateAnticlockwise { player })),
+            b'x' | b'X' => Some(TerminalKey::Game(GameInputKey::RotateClockwise { player })),
+            b'c' | b'C' => Some(TerminalKey::Game(GameInputKey::Hold { player })),
+            b'p' | b'P' => Some(TerminalKey::Game(GameInputKey::Pause)),
+            _ => None,
+        };
+        keys.extend(key);
+        index += 1;
+    }
+    keys
+}
+
+/// Pixels are buffered & written as a whole frame on present, with a status line underneath
+struct TerminalRenderer {
+    width: u32,
+    height: u32,
+    pixels: Vec<Rgb>,
+    status: String,
+}
+
+impl TerminalRenderer {
+    fn new(columns: u32, rows: u32) -> Self {
+        let width = columns.max(1);
+        let height = rows.max(1) * 2;
+        Self {
+            width,
+            height,
+            pixels: vec![(0, 0, 0); (width * height) as usize],
+            status: String::new(),
+        }
+    }
+
+    fn pixel(&self, x: u32, y: u32) -> Rgb {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Colors are only written when they change from the previous character
+    fn frame(&self) -> String {
+        let mut frame = String::from("\x1b[H");
+        for y in (0..self.height).step_by(2) {
+            let mut previous = None;
+            for x in 0..self.width {
+                let colors = (self.pixel(x, y), self.pixel(x, y + 1));
+                if previous != Some(colors) {
+                    let ((r1, g1, b1), (r2, g2, b2)) = colors;
+                    write!(frame, "\x1b[38;2;{r1};{g1};{b1}m\x1b[48;2;{r2};{g2};{b2}m").unwrap();
+                    previous = Some(colors);
+                }
+                frame.push(HALF_BLOCK);
+            }
+            frame.push_str("\x1b[0m\r\n");
+        }
+        frame.push_str("\x1b[2K");
+        frame.push_str(&self.status);
+        frame
+    }
+}
+
+impl Renderer for TerminalRenderer {
+    fn output_size(&self) -> Result<(u32, u32), String> {
+        Ok((self.width, self.height))
+    }
+
+    fn clear(&mut self, color: Rgb) {
+        self.pixels.fill(color);
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Rgb) -> Result<(), String> {
+        let left = rect.x.max(0) as u32;
+        let top = rect.y.max(0) as u32;
+        let right = ((rect.x + rect.width as i32).max(0) as u32).min(self.width);
+        let bottom = ((rect.y + rect.height as i32).max(0) as u32).min(self.height);
+        for y in top..bottom {
+            for x in left..right {
+                self.pixels[(y * self.width + x) as usize] = color;
+            }
+        }
+        Ok(())
+    }
+
+    fn present(&mut self) {
+        let mut stdout = std::io::stdout().lock();
+        // nothing sensible to do if the terminal has gone away
+        let _ = stdout.write_all(self.frame().as_bytes());
+        let _ = stdout.flush();
+    }
+}
+
+/// Keys are read on a thread as stdin blocks, the game loop polls the channel every frame
+fn spawn_input() -> Receiver<Vec<u8>> {
+    let (sender, receiver) = channel();
+    std::thread::spawn(move || {
+        let mut buffer = [0; 64];
+        let mut stdin = std::io::stdin();
+        while let Ok(count) = stdin.read(&mut buffer) {
+            if count == 0 || sender.send(buffer[..count].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Restores the terminal when dropped, even when the game returns an error
+#[cfg(unix)]
+struct RawMode(libc::termios);
+
+#[cfg(unix)]
+impl RawMode {
+    fn enable() -> Result<Self, String> {
+        // SAFETY: termios is plain data filled in by tcgetattr
+        let original = unsafe {
+            let mut original = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err("stdin is not a terminal".to_string());
+            }
+            let mut raw = original;
+            libc::cfmakeraw(&mut raw);
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw);
+            original
+        };
+        // alternate screen & hidden cursor
+        print!("\x1b[?1049h\x1b[?25l");
+        Ok(Self(original))
+    }
+
+    /// Columns & rows, falling back to the classic 80x24
+    fn terminal_size(&self) -> (u32, u32) {
+        // SAFETY: winsize is plain data filled in by the ioctl
+        unsafe {
+            let mut size: libc::winsize = std::mem::zeroed();
+            if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0 && size.ws_col > 0
+            {
+                (size.ws_col as u32, size.ws_row as u32)
+            } else {
+                (80, 24)
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = std::io::stdout().flush();
+        // SAFETY: restoring the attributes read in enable
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct RawMode;
+
+#[cfg(not(unix))]
+impl RawMode {
+    fn enable() -> Result<Self, String> {
+        Err("the terminal frontend is only supported on unix".to_string())
+    }
+
+    fn terminal_size(&self) -> (u32, u32) {
+        (80, 24)
+    }
+}
+
+/// Plays until game over or quit, then prints the result to the normal screen
+pub fn run(modifiers: &[String]) -> Result<(), String> {
+    let mut fixture = Match::new(GameConfig::default(), modifiers);
+    let raw_mode = RawMode::enable()?;
+    let (columns, rows) = raw_mode.terminal_size();
+    // the last row is the status line
+    let mut renderer = TerminalRenderer::new(columns, rows.saturating_sub(1));
+    let input = spawn_input();
+
+    let mut paused = false;
+    let mut soft_drop_pressed: Option<Instant> = None;
+    let mut last_frame = Instant::now();
+    'game: loop {
+        let now = Instant::now();
+        let delta = now - last_frame;
+        last_frame = now;
+
+        for key in input.try_iter().flat_map(|bytes| parse_keys(&bytes)) {
+            match key {
+                TerminalKey::Quit => break 'game,
+                TerminalKey::Game(GameInputKey::Pause) => paused = !paused,
+                TerminalKey::Game(_) if paused => {}
+                TerminalKey::Game(key) => {
+                    if matches!(key, GameInputKey::SoftDrop { .. }) {
+                        soft_drop_pressed = Some(now);
+                    }
+                    fixture.apply_key(key);
+                }
+            }
+        }
+
+        let game = fixture.player_mut(0).game_mut();
+        if soft_drop_pressed.is_some_and(|pressed| now - pressed > SOFT_DROP_RELEASE) {
+            soft_drop_pressed = None;
+            game.set_soft_drop(false);
+        }
+
+        if !paused {
+            game.update(delta);
+            let mut events = vec![];
+            game.consume_events(&mut events);
+            for event in events {
+                match event {
+                    GameEvent::LevelComplete { .. } => game.next_level()?,
+                    GameEvent::GameOver { .. } => break 'game,
+                    _ => {}
+                }
+            }
+        }
+
+        renderer.status = format!(
+            " level {}  score {}  viruses {}{}",
+            game.virus_level(),
+            game.metrics().score(),
+            game.metrics().virus_count(),
+            if paused { "  (paused)" } else { "" }
+        );
+        draw_flat_scene(&mut renderer, &[&*game])?;
+        std::thread::sleep(FRAME.saturating_sub(now.elapsed()));
+    }
+
+    drop(raw_mode);
+    let game = fixture.player(0).game();
+    println!(
+        "level {}, score {}",
+        game.virus_level(),
+        game.metrics().score()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_keys() {
+        let player = 0;
+        assert_eq!(
+            parse_keys(b"\x1b[D\x1b[Cxzq"),
+            vec![
+                TerminalKey::Game(GameInputKey::MoveLeft { player }),
+                TerminalKey::Game(GameInputKey::MoveRight { player }),
+                TerminalKey::Game(GameInputKey::RotateClockwise { player }),
+                TerminalKey::Game(GameInputKey::RotateAnticlockwise { player }),
+                TerminalKey::Quit,
+            ]
+        );
+        assert_eq!(parse_keys(b"\x1b"), vec![TerminalKey::Quit]);
+        assert_eq!(parse_keys(b"\x1b[Z?"), vec![]);
+    }
+
+    #[test]
+    fn draws_half_blocks() {
+        let mut renderer = TerminalRenderer::new(2, 1);
+        renderer.clear((0, 0, 0));
+        renderer.fill_rect(Rect::new(1, 1, 5, 5), (255, 0, 0)).unwrap();
+        assert_eq!(
+            renderer.frame(),
+            concat!(
+                "\x1b[H\x1b[38;2;0;0;0m\x1b[48;2;0;0;0m▀",
+                "\x1b[38;2;0;0;0m\x1b[48;2;255;0;0m▀\x1b[0m\r\n\x1b[2K"
+            )
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Events shared by every screen: alt+enter toggles fullscreen and window resizes are flagged,
+//! so that the screen can be laid out again at the new size. Controllers are opened to rumble as
+//! they are connected.
+
+use crate::rumble::Rumble;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::render::WindowCanvas;
+use sdl2::video::FullscreenType;
+use sdl2::{EventPump, TimerSubsystem};
+
+pub struct WindowEvents {
+    event_pump: EventPump,
+    timer: TimerSubsystem,
+    /// SDL ticks when the events were last polled, to time the keys pressed during a frame
+    polled_at: u32,
+    /// window size the current screen was laid out at
+    layout_size: (u32, u32),
+    resized: bool,
+    rumble: Rumble,
+}
+
+impl WindowEvents {
+    pub fn new(
+        event_pump: EventPump,
+        timer: TimerSubsystem,
+        layout_size: (u32, u32),
+        rumble: Rumble,
+    ) -> Self {
+        Self {
+            event_pump,
+            timer,
+            polled_at: 0,
+            layout_size,
+            resized: false,
+            rumble,
+        }
+    }
+
+    pub fn rumble_mut(&mut self) -> &mut Rumble {
+        &mut self.rumble
+    }
+
+    pub fn polled_at(&self) -> u32 {
+        self.polled_at
+    }
+
+    pub fn layout_size(&self) -> (u32, u32) {
+        self.layout_size
+    }
+
+    pub fn is_resized(&self) -> bool {
+        self.resized
+    }
+
+    /// Until the screen is laid out again, the canvas stretches it to fit the resized window
+    pub fn poll(&mut self, canvas: &mut WindowCanvas) -> Result<Vec<Event>, String> {
+        let mut result = vec![];
+        for event in self.event_pump.poll_iter() {
+            match event {
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return),
+                    keymod,
+                    repeat,
+                    ..
+                } if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) => {
+                    if !repeat {
+                        toggle_fullscreen(canvas)?;
+                    }
+                }
+                Event::Window {
+                    win_event: WindowEvent::SizeChanged(..),
+                    ..
+                } => {
+                    if !self.resized && canvas.window().size() != self.layout_size {
+                        self.resized = true;
+                        let (width, height) = self.layout_size;
+                        canvas
+                            .set_logical_size(width, height)
+                            .map_err(|e| e.to_string())?;
+                    }
+                }
+                Event::ControllerDeviceAdded { .. } | Event::ControllerDeviceRemoved { .. } => {
+                    self.rumble.receive_event(&event)
+                }
+                event => result.push(event),
+            }
+        }
+        self.polled_at = self.timer.ticks();
+        Ok(result)
+    }
+
+    /// Lays out the next screen at the current window size
+    pub fn reset_layout(&mut self, canvas: &mut WindowCanvas) -> Result<(u32, u32), String> {
+        self.resized = false;
+        canvas.set_logical_size(0, 0).map_err(|e| e.to_string())?;
+        self.layout_size = canvas.window().size();
+        Ok(self.layout_size)
+    }
+}
+
+fn toggle_fullscreen(canvas: &mut WindowCanvas) -> Result<(), String> {
+    let window = canvas.window_mut();
+    let fullscreen = match window.fullscreen_state() {
+        FullscreenType::Off => FullscreenType::Desktop,
+        _ => FullscreenType::Off,
+    };
+    window.set_fullscreen(fullscreen)
+}
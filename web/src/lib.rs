@@ -0,0 +1,137 @@
+//! Dr. Rustario in the browser: a single player game drawn on an html canvas.
+//!
+//! The bindings to the canvas api are declared here rather than pulling in all of `web-sys`,
+//! see the README for building with `wasm-bindgen`.
+
+use dr_rustario_core::game::event::GameEvent;
+use dr_rustario_core::game::random::{random, RandomMode};
+use dr_rustario_core::game::rules::MAX_VIRUS_LEVEL;
+use dr_rustario_core::game::{Game, GameSpeed};
+use dr_rustario_core::render::{draw_flat_scene, Rect, Renderer, Rgb};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    pub type CanvasRenderingContext2d;
+    pub type HtmlCanvasElement;
+
+    #[wasm_bindgen(method, getter)]
+    fn canvas(this: &CanvasRenderingContext2d) -> HtmlCanvasElement;
+
+    #[wasm_bindgen(method, setter = fillStyle)]
+    fn set_fill_style(this: &CanvasRenderingContext2d, value: &str);
+
+    #[wasm_bindgen(method, js_name = fillRect)]
+    fn fill_rect(this: &CanvasRenderingContext2d, x: f64, y: f64, width: f64, height: f64);
+
+    #[wasm_bindgen(method, getter)]
+    fn width(this: &HtmlCanvasElement) -> u32;
+
+    #[wasm_bindgen(method, getter)]
+    fn height(this: &HtmlCanvasElement) -> u32;
+}
+
+/// The drawing primitives on an html canvas, it is presented by the browser after each frame
+struct CanvasRenderer<'a>(&'a CanvasRenderingContext2d);
+
+impl CanvasRenderer<'_> {
+    fn set_color(&self, (r, g, b): Rgb) {
+        self.0.set_fill_style(&format!("rgb({}, {}, {})", r, g, b));
+    }
+}
+
+impl Renderer for CanvasRenderer<'_> {
+    fn output_size(&self) -> Result<(u32, u32), String> {
+        let canvas = self.0.canvas();
+        Ok((canvas.width(), canvas.height()))
+    }
+
+    fn clear(&mut self, color: Rgb) {
+        let (width, height) = self.output_size().unwrap();
+        self.set_color(color);
+        self.0.fill_rect(0.0, 0.0, width as f64, height as f64);
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Rgb) -> Result<(), String> {
+        self.set_color(color);
+        self.0.fill_rect(
+            rect.x as f64,
+            rect.y as f64,
+            rect.width as f64,
+            rect.height as f64,
+        );
+        Ok(())
+    }
+
+    fn present(&mut self) {}
+}
+
+#[wasm_bindgen]
+pub struct WebGame {
+    game: Game,
+    game_over: bool,
+}
+
+#[wasm_bindgen]
+impl WebGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32, virus_level: u32) -> Result<WebGame, String> {
+        let random = random(1, RandomMode::Bag, Some(seed)).pop().unwrap();
+        let virus_level = virus_level.min(MAX_VIRUS_LEVEL);
+        Ok(Self {
+            game: Game::new(0, virus_level, GameSpeed::Medium, random)?,
+            game_over: false,
+        })
+    }
+
+    /// Takes the `code` of a keyboard event, e.g. `ArrowLeft`
+    pub fn key_down(&mut self, code: &str) {
+        match code {
+            "ArrowLeft" => self.game.left(),
+            "ArrowRight" => self.game.right(),
+            "ArrowDown" => self.game.set_soft_drop(true),
+            "ArrowUp" => self.game.hard_drop(),
+            "KeyZ" => self.game.rotate(false),
+            "KeyX" => self.game.rotate(true),
+            "KeyC" => self.game.hold(),
+            _ => {}
+        }
+    }
+
+    pub fn key_up(&mut self, code: &str) {
+        if code == "ArrowDown" {
+            self.game.set_soft_drop(false);
+        }
+    }
+
+    /// Call once per animation frame with the time since the last one
+    pub fn update(&mut self, delta_ms: f64) -> Result<(), String> {
+        if self.game_over {
+            return Ok(());
+        }
+        self.game.update(Duration::from_secs_f64(delta_ms.max(0.0) / 1000.0));
+        let mut events = vec![];
+        self.game.consume_events(&mut events);
+        for event in events {
+            match event {
+                GameEvent::LevelComplete { .. } => self.game.next_level()?,
+                GameEvent::GameOver { .. } => self.game_over = true,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    pub fn score(&self) -> u32 {
+        self.game.metrics().score()
+    }
+
+    pub fn draw(&self, context: &CanvasRenderingContext2d) -> Result<(), String> {
+        draw_flat_scene(&mut CanvasRenderer(context), &[&self.game])
+    }
+}